@@ -0,0 +1,146 @@
+use anyhow::Result;
+use clap::Subcommand;
+use datasov_client::marketplace::DataType as ListingDataType;
+use datasov_client::{admin, parse, squads};
+use solana_sdk::pubkey::Pubkey;
+
+use super::Context;
+
+#[derive(Subcommand)]
+pub enum AdminCommand {
+    /// Create the KYC oracle registry with the signer as authority
+    InitOracleRegistry {
+        /// Minimum oracle stake in lamports
+        minimum_stake: u64,
+        /// Lamports slashed per upheld dispute
+        slash_amount: u64,
+    },
+    /// Create the compliance matrix (registry authority only)
+    InitComplianceMatrix,
+    /// Create the consumer blacklist (registry authority only)
+    InitBlacklist,
+    /// Create the marketplace with the signer as authority
+    InitMarketplace {
+        fee_basis_points: u16,
+    },
+    /// Halt or resume purchases of one data type (marketplace authority only)
+    HaltDataType {
+        #[clap(value_parser = parse::listing_data_type)]
+        data_type: ListingDataType,
+        /// Resume purchases instead of halting them
+        #[clap(long)]
+        resume: bool,
+    },
+    /// Replace the set of enabled marketplace subsystems (marketplace authority only)
+    SetFeatureFlags {
+        /// Bitset of enabled subsystems; bit 0 is compute-to-data
+        flags: u64,
+    },
+    /// Create the identity state snapshot, built by `crank` (registry authority only)
+    InitStateSnapshot {
+        /// Key the snapshot crank signs with
+        crank: Pubkey,
+    },
+    /// Hand the marketplace to another authority, e.g. the timelock or a Squads vault (marketplace
+    /// authority only)
+    SetMarketplaceAuthority {
+        new_authority: Pubkey,
+        /// Treat `new_authority` as a Squads multisig and hand the role to its vault at this index
+        #[clap(long)]
+        squads_vault: Option<u8>,
+    },
+    /// Hand the oracle registry, compliance matrix and blacklist to another authority, e.g. the
+    /// timelock or a Squads vault (registry authority only)
+    SetRegistryAuthority {
+        new_authority: Pubkey,
+        /// Treat `new_authority` as a Squads multisig and hand the role to its vault at this index
+        #[clap(long)]
+        squads_vault: Option<u8>,
+    },
+    /// Let a storage oracle check listings' stored data (marketplace authority only)
+    AddStorageOracle {
+        oracle: Pubkey,
+    },
+    /// Revoke a storage oracle (marketplace authority only)
+    RemoveStorageOracle {
+        oracle: Pubkey,
+    },
+    /// Let a compliance officer freeze identities pending investigation (registry authority only)
+    AddComplianceOfficer {
+        officer: Pubkey,
+    },
+    /// Dismiss a compliance officer (registry authority only)
+    RemoveComplianceOfficer {
+        officer: Pubkey,
+    },
+}
+
+pub async fn run(ctx: &Context, command: AdminCommand) -> Result<()> {
+    let authority = ctx.signer_pubkey()?;
+    let (action, instruction) = match command {
+        AdminCommand::InitOracleRegistry { minimum_stake, slash_amount } => (
+            "admin init-oracle-registry",
+            admin::initialize_oracle_registry(&authority, minimum_stake, slash_amount),
+        ),
+        AdminCommand::InitComplianceMatrix => (
+            "admin init-compliance-matrix",
+            admin::initialize_compliance_matrix(&authority),
+        ),
+        AdminCommand::InitBlacklist => ("admin init-blacklist", admin::initialize_blacklist(&authority)),
+        AdminCommand::InitMarketplace { fee_basis_points } => (
+            "admin init-marketplace",
+            admin::initialize_marketplace(&authority, fee_basis_points),
+        ),
+        AdminCommand::HaltDataType { data_type, resume } => (
+            "admin halt-data-type",
+            admin::halt_data_type(&authority, data_type, !resume),
+        ),
+        AdminCommand::SetFeatureFlags { flags } => (
+            "admin set-feature-flags",
+            admin::set_feature_flags(&authority, flags),
+        ),
+        AdminCommand::InitStateSnapshot { crank } => (
+            "admin init-state-snapshot",
+            admin::initialize_state_snapshot(&authority, &crank),
+        ),
+        AdminCommand::SetMarketplaceAuthority {
+            new_authority,
+            squads_vault,
+        } => (
+            "admin set-marketplace-authority",
+            admin::set_marketplace_authority(&authority, &role_holder(new_authority, squads_vault)),
+        ),
+        AdminCommand::SetRegistryAuthority {
+            new_authority,
+            squads_vault,
+        } => (
+            "admin set-registry-authority",
+            admin::set_registry_authority(&authority, &role_holder(new_authority, squads_vault)),
+        ),
+        AdminCommand::AddStorageOracle { oracle } => (
+            "admin add-storage-oracle",
+            admin::add_storage_oracle(&authority, &oracle),
+        ),
+        AdminCommand::RemoveStorageOracle { oracle } => (
+            "admin remove-storage-oracle",
+            admin::remove_storage_oracle(&authority, &oracle),
+        ),
+        AdminCommand::AddComplianceOfficer { officer } => (
+            "admin add-compliance-officer",
+            admin::add_compliance_officer(&authority, &officer),
+        ),
+        AdminCommand::RemoveComplianceOfficer { officer } => (
+            "admin remove-compliance-officer",
+            admin::remove_compliance_officer(&authority, &officer),
+        ),
+    };
+    ctx.send(action, vec![instruction]).await
+}
+
+/// `new_authority` itself, or its Squads vault at `squads_vault`
+fn role_holder(new_authority: Pubkey, squads_vault: Option<u8>) -> Pubkey {
+    match squads_vault {
+        Some(index) => squads::vault(&new_authority, index),
+        None => new_authority,
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use datasov_client::faucet::{self, Drip};
+use datasov_client::identity::VerificationLevel;
+use datasov_client::parse;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+
+use super::Context;
+use crate::output;
+
+#[derive(Subcommand)]
+pub enum FaucetCommand {
+    /// Create a demo payment token with the signer as its mint authority
+    CreateMint,
+    /// Fund the signer with SOL and demo tokens and register it a verified identity, in one transaction
+    Drip {
+        identity_id: String,
+        /// Keypair file of the faucet: the demo mint's authority, registered as a KYC oracle
+        #[clap(long)]
+        faucet_keypair: String,
+        /// Demo token mint
+        #[clap(long, value_parser)]
+        mint: Pubkey,
+        /// Demo tokens, in base units
+        #[clap(long, default_value_t = faucet::DEFAULT_DRIP_TOKENS)]
+        tokens: u64,
+        #[clap(long, default_value_t = faucet::DEFAULT_DRIP_LAMPORTS)]
+        lamports: u64,
+        #[clap(long, value_parser = parse::verification_level, default_value = "basic")]
+        level: VerificationLevel,
+    },
+}
+
+pub async fn run(ctx: &Context, command: FaucetCommand) -> Result<()> {
+    let signer = ctx.signer()?;
+    match command {
+        FaucetCommand::CreateMint => {
+            let mint = ctx.rpc.create_demo_mint(signer).await?;
+            output::address(ctx.json, "faucet create-mint", &mint);
+        }
+        FaucetCommand::Drip {
+            identity_id,
+            faucet_keypair,
+            mint,
+            tokens,
+            lamports,
+            level,
+        } => {
+            let authority = read_keypair_file(&faucet_keypair)
+                .map_err(|err| anyhow!("failed to read {}: {}", faucet_keypair, err))?;
+            let drip = Drip {
+                tokens,
+                lamports,
+                verification_level: level,
+                ..Drip::new(signer.pubkey(), identity_id)
+            };
+            let signature = ctx.rpc.drip(&authority, signer, &mint, &drip).await?;
+            output::signature(ctx.json, "faucet drip", &signature);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use datasov_client::identity::{self, DataType, PermissionType, StoragePointer, VerificationLevel};
+use datasov_client::instructions::{self, GrantAccessRequest};
+use datasov_client::pda;
+use solana_sdk::pubkey::Pubkey;
+
+use super::Context;
+
+pub async fn register(ctx: &Context, identity_id: String, storage_pointer: StoragePointer) -> Result<()> {
+    let owner = ctx.signer_pubkey()?;
+    ctx.send(
+        "register-identity",
+        vec![instructions::register_identity(&owner, &identity_id, storage_pointer)],
+    )
+    .await
+}
+
+pub async fn verify(
+    ctx: &Context,
+    identity_id: String,
+    verification_level: VerificationLevel,
+    kyc_pointer: StoragePointer,
+) -> Result<()> {
+    let oracle_authority = ctx.signer_pubkey()?;
+    let instruction = instructions::identity(
+        identity::accounts::VerifyIdentity {
+            identity: pda::identity::identity(&identity_id),
+            oracle: pda::identity::oracle(&oracle_authority),
+            oracle_registry: pda::identity::oracle_registry(),
+            oracle_authority,
+        },
+        identity::instruction::VerifyIdentity {
+            verification_level,
+            kyc_pointer,
+        },
+    );
+    ctx.send("verify", vec![instruction]).await
+}
+
+pub async fn grant(
+    ctx: &Context,
+    identity_id: String,
+    consumer: Pubkey,
+    permission_type: PermissionType,
+    data_types: Vec<DataType>,
+    expires_at: Option<i64>,
+    permission_pointer: StoragePointer,
+) -> Result<()> {
+    let owner = ctx.signer_pubkey()?;
+    let identity_address = pda::identity::identity(&identity_id);
+    let consumer_staked = ctx.rpc.has_consumer_stake(&consumer).await?;
+    let audit_entry_count = ctx.rpc.audit_entry_count(&identity_address).await?;
+
+    let instruction = instructions::grant_access(GrantAccessRequest {
+        owner,
+        identity_id,
+        consumer,
+        permission_type,
+        data_types,
+        expires_at,
+        permission_pointer,
+        consumer_staked,
+        guardian: None,
+        audit_entry_count,
+    });
+    ctx.send("grant", vec![instruction]).await
+}
+
+pub async fn revoke(
+    ctx: &Context,
+    identity_id: String,
+    consumer: Pubkey,
+    revocation_pointer: StoragePointer,
+) -> Result<()> {
+    let owner = ctx.signer_pubkey()?;
+    let audit_entry_count = ctx
+        .rpc
+        .audit_entry_count(&pda::identity::identity(&identity_id))
+        .await?;
+    ctx.send(
+        "revoke",
+        vec![instructions::revoke_access(
+            &owner,
+            &identity_id,
+            &consumer,
+            revocation_pointer,
+            audit_entry_count,
+        )],
+    )
+    .await
+}
+
+pub async fn request_access(
+    ctx: &Context,
+    identity_id: String,
+    permission_type: PermissionType,
+    data_types: Vec<DataType>,
+    duration: i64,
+    price: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let consumer = ctx.signer_pubkey()?;
+    ctx.send(
+        "request-access",
+        vec![instructions::request_access(
+            &consumer,
+            &identity_id,
+            permission_type,
+            data_types,
+            duration,
+            price,
+            expires_at,
+        )],
+    )
+    .await
+}
+
+pub async fn accept_request(
+    ctx: &Context,
+    identity_id: String,
+    consumer: Pubkey,
+    permission_pointer: StoragePointer,
+) -> Result<()> {
+    let owner = ctx.signer_pubkey()?;
+    let consumer_staked = ctx.rpc.has_consumer_stake(&consumer).await?;
+    let audit_entry_count = ctx
+        .rpc
+        .audit_entry_count(&pda::identity::identity(&identity_id))
+        .await?;
+    ctx.send(
+        "accept-request",
+        vec![instructions::accept_access_request(
+            &owner,
+            &identity_id,
+            &consumer,
+            permission_pointer,
+            consumer_staked,
+            audit_entry_count,
+        )],
+    )
+    .await
+}
+
+pub async fn cancel_request(ctx: &Context, identity_id: String) -> Result<()> {
+    let consumer = ctx.signer_pubkey()?;
+    ctx.send(
+        "cancel-request",
+        vec![instructions::cancel_access_request(&consumer, &identity_id)],
+    )
+    .await
+}
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use clap::Subcommand;
+use datasov_client::identity::{AccessPermission, IdentityAccount, KYCOracle};
+use datasov_client::marketplace::DataListing;
+use datasov_client::query::ListingQuery;
+use datasov_client::staking::Vesting;
+use solana_sdk::pubkey::Pubkey;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Context;
+use crate::output;
+
+#[derive(Subcommand)]
+pub enum ListTarget {
+    /// Registered identities
+    Identities {
+        /// Only identities owned by this wallet
+        #[clap(long, value_parser)]
+        owner: Option<Pubkey>,
+    },
+    /// Registered KYC oracles
+    Oracles,
+    /// Permissions granted on one identity
+    Permissions { identity_id: String },
+    /// Marketplace listings
+    Listings {
+        /// Only listings that can still be purchased
+        #[clap(long)]
+        active: bool,
+    },
+    /// Protocol token vestings, with what has vested so far
+    Vestings {
+        /// Only vestings paying this wallet
+        #[clap(long, value_parser)]
+        beneficiary: Option<Pubkey>,
+    },
+    /// Listings featured by curation votes in the last ended epoch, most votes first
+    Featured,
+}
+
+pub async fn run(ctx: &Context, target: ListTarget) -> Result<()> {
+    let records = match target {
+        ListTarget::Identities { owner } => ctx
+            .rpc
+            .fetch_all::<IdentityAccount>(Vec::new())
+            .await?
+            .iter()
+            .filter(|(_, identity)| owner.is_none() || owner == Some(identity.owner))
+            .map(|(address, identity)| output::identity(address, identity))
+            .collect(),
+        ListTarget::Oracles => ctx
+            .rpc
+            .fetch_all::<KYCOracle>(Vec::new())
+            .await?
+            .iter()
+            .map(|(address, oracle)| output::oracle(address, oracle))
+            .collect(),
+        ListTarget::Permissions { identity_id } => ctx
+            .rpc
+            .fetch_all::<AccessPermission>(Vec::new())
+            .await?
+            .iter()
+            .filter(|(_, permission)| permission.identity_id == identity_id)
+            .map(|(address, permission)| output::permission(address, permission))
+            .collect(),
+        ListTarget::Listings { active } => ctx
+            .rpc
+            .fetch_all::<DataListing>(ListingQuery::new().filters())
+            .await?
+            .iter()
+            .filter(|(_, listing)| !active || listing.is_active())
+            .map(|(address, listing)| output::listing(address, listing))
+            .collect(),
+        ListTarget::Vestings { beneficiary } => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+            ctx.rpc
+                .fetch_all::<Vesting>(Vec::new())
+                .await?
+                .iter()
+                .filter(|(_, vesting)| beneficiary.is_none() || beneficiary == Some(vesting.beneficiary))
+                .map(|(address, vesting)| output::vesting(address, vesting, now))
+                .collect()
+        }
+        ListTarget::Featured => {
+            let mut records = Vec::new();
+            for featured in ctx.rpc.featured_set().await?.map_or_else(Vec::new, |set| set.listings) {
+                if let Some(listing) = ctx.rpc.fetch_optional::<DataListing>(&featured.listing).await? {
+                    records.push(output::featured_listing(&featured.listing, &listing, featured.votes));
+                }
+            }
+            records
+        }
+    };
+    output::records(ctx.json, records);
+    Ok(())
+}
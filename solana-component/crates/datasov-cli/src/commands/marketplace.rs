@@ -0,0 +1,63 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{ensure, Result};
+use datasov_client::identity::Purpose;
+use datasov_client::instructions::{self, PurchaseRequest};
+use datasov_client::marketplace;
+use datasov_client::pda;
+use solana_sdk::pubkey::Pubkey;
+
+use super::Context;
+
+pub async fn purchase(
+    ctx: &Context,
+    listing_id: u64,
+    buyer_identity_id: String,
+    purpose: Purpose,
+    mint: Pubkey,
+    max_staleness: Option<i64>,
+) -> Result<()> {
+    let buyer = ctx.signer_pubkey()?;
+    let listing_address = pda::marketplace::listing(listing_id);
+    let listing = ctx.rpc.fetch::<marketplace::DataListing>(&listing_address).await?;
+    let marketplace_account = ctx.rpc.fetch_marketplace().await?;
+    let buyer_identity = ctx.rpc.fetch_identity(&buyer_identity_id).await?;
+    ensure!(
+        !listing.is_private(),
+        "listing {} is private and needs a price reveal from the seller",
+        listing_id
+    );
+    ensure!(
+        buyer_identity.owner == buyer,
+        "identity {} is not owned by the signer",
+        buyer_identity_id
+    );
+
+    let seller_identity = pda::identity::identity(listing.identity_id());
+    let buyer_staked = ctx.rpc.has_consumer_stake(&buyer).await?;
+    let buyer_weighted = ctx.rpc.has_stake_weight(&buyer).await?;
+    let rebate = ctx.rpc.rebate_ledgers(&buyer, &listing.payout_wallet).await?;
+    let audit_entry_count = ctx.rpc.audit_entry_count(&seller_identity).await?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+    let instruction = instructions::purchase_data(PurchaseRequest {
+        buyer,
+        buyer_identity_id,
+        listing_id,
+        payout_wallet: listing.payout_wallet,
+        seller_identity_id: listing.identity_id().to_string(),
+        data_union: listing.union(),
+        data_type: listing.data_type(),
+        purpose,
+        mint,
+        // Proceeds are escrowed only while the marketplace has a refund window
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked,
+        buyer_weighted,
+        rebate,
+        max_staleness,
+        day: marketplace::PriceCandle::day_of(now),
+        audit_entry_count,
+    });
+    ctx.send("purchase", vec![instruction]).await
+}
@@ -0,0 +1,235 @@
+mod admin;
+mod faucet;
+mod identity;
+mod list;
+mod marketplace;
+mod oracle;
+mod timelock;
+
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use datasov_client::identity::{DataType, PermissionType, Purpose, StoragePointer, VerificationLevel};
+use datasov_client::{parse, storage};
+use datasov_client::rpc::DataSovRpc;
+use datasov_client::TransactionBuilder;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+use crate::output;
+
+pub use admin::AdminCommand;
+pub use faucet::FaucetCommand;
+pub use list::ListTarget;
+pub use oracle::OracleCommand;
+pub use timelock::TimelockCommand;
+
+/// Everything a command needs: the RPC connection, the signer (for commands that send) and output mode
+pub struct Context {
+    pub rpc: DataSovRpc,
+    pub signer: Option<Box<dyn Signer>>,
+    pub json: bool,
+}
+
+impl Context {
+    pub fn signer(&self) -> Result<&dyn Signer> {
+        self.signer.as_deref().ok_or_else(|| anyhow!("this command requires a signer"))
+    }
+
+    pub fn signer_pubkey(&self) -> Result<Pubkey> {
+        Ok(self.signer()?.pubkey())
+    }
+
+    /// Send `instructions` in one transaction signed and paid for by the CLI signer
+    pub async fn send(&self, action: &str, instructions: Vec<Instruction>) -> Result<()> {
+        let signer = self.signer()?;
+        let builder = instructions
+            .into_iter()
+            .fold(TransactionBuilder::new(), TransactionBuilder::instruction);
+        let signature = self.rpc.send(&builder, &signer.pubkey(), &[signer]).await?;
+        output::signature(self.json, action, &signature);
+        Ok(())
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Register a new identity owned by the signer
+    RegisterIdentity {
+        identity_id: String,
+        /// Identity document: an `ar://`, `ipfs://`, `shdw://` or `fil://` URI, or a bare Arweave
+        /// transaction ID
+        #[clap(value_parser = storage::parse)]
+        pointer: StoragePointer,
+    },
+
+    /// Verify a pending identity, signing as a registered KYC oracle
+    Verify {
+        identity_id: String,
+        #[clap(long, value_parser = parse::verification_level)]
+        level: VerificationLevel,
+        /// KYC attestation, as a storage URI
+        #[clap(long, value_parser = storage::parse)]
+        pointer: StoragePointer,
+    },
+
+    /// Grant a consumer access to the signer's identity
+    Grant {
+        identity_id: String,
+        #[clap(value_parser)]
+        consumer: Pubkey,
+        #[clap(long, value_parser = parse::permission_type, default_value = "read-only")]
+        permission: PermissionType,
+        /// Comma-separated data types, e.g. location-history,app-usage
+        #[clap(long, value_parser = parse::data_type, value_delimiter = ',', required = true)]
+        data_types: Vec<DataType>,
+        /// Unix timestamp after which the permission lapses
+        #[clap(long)]
+        expires_at: Option<i64>,
+        /// Signed permission document, as a storage URI
+        #[clap(long, value_parser = storage::parse)]
+        pointer: StoragePointer,
+    },
+
+    /// Revoke a consumer's access to the signer's identity
+    Revoke {
+        identity_id: String,
+        #[clap(value_parser)]
+        consumer: Pubkey,
+        /// Revocation record, as a storage URI
+        #[clap(long, value_parser = storage::parse)]
+        pointer: StoragePointer,
+    },
+
+    /// Offer to pay an identity's owner for access, escrowing the price from the signer
+    RequestAccess {
+        identity_id: String,
+        #[clap(long, value_parser = parse::permission_type, default_value = "read-only")]
+        permission: PermissionType,
+        /// Comma-separated data types, e.g. location-history,app-usage
+        #[clap(long, value_parser = parse::data_type, value_delimiter = ',', required = true)]
+        data_types: Vec<DataType>,
+        /// Seconds the permission runs once accepted
+        #[clap(long)]
+        duration: i64,
+        /// Lamports paid to the owner on acceptance
+        #[clap(long)]
+        price: u64,
+        /// Unix timestamp after which the owner can no longer accept
+        #[clap(long)]
+        expires_at: i64,
+    },
+
+    /// Accept a consumer's paid access request to the signer's identity
+    AcceptRequest {
+        identity_id: String,
+        #[clap(value_parser)]
+        consumer: Pubkey,
+        /// Signed permission document, as a storage URI
+        #[clap(long, value_parser = storage::parse)]
+        pointer: StoragePointer,
+    },
+
+    /// Withdraw the signer's unaccepted access request, refunding its price
+    CancelRequest {
+        identity_id: String,
+    },
+
+    /// List protocol accounts
+    List {
+        #[clap(subcommand)]
+        target: ListTarget,
+    },
+
+    /// Purchase a data listing with the signer's token account
+    Purchase {
+        listing_id: u64,
+        /// Identity the access is granted to; must be owned by the signer
+        #[clap(long)]
+        buyer_identity: String,
+        #[clap(long, value_parser = parse::purpose)]
+        purpose: Purpose,
+        /// Settlement token mint
+        #[clap(long, value_parser)]
+        mint: Pubkey,
+        /// Refuse the listing if its data was last attested fresh more than this many seconds ago
+        #[clap(long)]
+        max_staleness: Option<i64>,
+    },
+
+    /// KYC oracle operations
+    Oracle {
+        #[clap(subcommand)]
+        command: OracleCommand,
+    },
+
+    /// Registry and marketplace authority operations
+    Admin {
+        #[clap(subcommand)]
+        command: AdminCommand,
+    },
+
+    /// Devnet demo token and onboarding
+    Faucet {
+        #[clap(subcommand)]
+        command: FaucetCommand,
+    },
+
+    /// Delayed upgrades and admin actions
+    Timelock {
+        #[clap(subcommand)]
+        command: TimelockCommand,
+    },
+}
+
+impl Command {
+    pub fn sends_transaction(&self) -> bool {
+        !matches!(self, Command::List { .. })
+    }
+
+    pub async fn run(self, ctx: &Context) -> Result<()> {
+        match self {
+            Command::RegisterIdentity { identity_id, pointer } => {
+                identity::register(ctx, identity_id, pointer).await
+            }
+            Command::Verify { identity_id, level, pointer } => {
+                identity::verify(ctx, identity_id, level, pointer).await
+            }
+            Command::Grant {
+                identity_id,
+                consumer,
+                permission,
+                data_types,
+                expires_at,
+                pointer,
+            } => identity::grant(ctx, identity_id, consumer, permission, data_types, expires_at, pointer).await,
+            Command::Revoke { identity_id, consumer, pointer } => {
+                identity::revoke(ctx, identity_id, consumer, pointer).await
+            }
+            Command::RequestAccess {
+                identity_id,
+                permission,
+                data_types,
+                duration,
+                price,
+                expires_at,
+            } => identity::request_access(ctx, identity_id, permission, data_types, duration, price, expires_at).await,
+            Command::AcceptRequest { identity_id, consumer, pointer } => {
+                identity::accept_request(ctx, identity_id, consumer, pointer).await
+            }
+            Command::CancelRequest { identity_id } => identity::cancel_request(ctx, identity_id).await,
+            Command::List { target } => list::run(ctx, target).await,
+            Command::Purchase {
+                listing_id,
+                buyer_identity,
+                purpose,
+                mint,
+                max_staleness,
+            } => marketplace::purchase(ctx, listing_id, buyer_identity, purpose, mint, max_staleness).await,
+            Command::Oracle { command } => oracle::run(ctx, command).await,
+            Command::Admin { command } => admin::run(ctx, command).await,
+            Command::Faucet { command } => faucet::run(ctx, command).await,
+            Command::Timelock { command } => timelock::run(ctx, command).await,
+        }
+    }
+}
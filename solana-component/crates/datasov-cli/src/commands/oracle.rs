@@ -0,0 +1,59 @@
+use anyhow::Result;
+use clap::Subcommand;
+use datasov_client::identity;
+use datasov_client::instructions;
+use datasov_client::pda;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use super::Context;
+
+#[derive(Subcommand)]
+pub enum OracleCommand {
+    /// Register the signer as a KYC oracle
+    Register {
+        provider_name: String,
+        /// Stake in lamports; must meet the registry minimum
+        stake_amount: u64,
+    },
+    /// Verify a consumer as a registered business
+    VerifyBusiness {
+        #[clap(value_parser)]
+        consumer: Pubkey,
+        /// Arweave transaction holding the business registry extract
+        #[clap(long)]
+        arweave_tx_id: String,
+    },
+}
+
+pub async fn run(ctx: &Context, command: OracleCommand) -> Result<()> {
+    let oracle_authority = ctx.signer_pubkey()?;
+    match command {
+        OracleCommand::Register { provider_name, stake_amount } => {
+            let instruction = instructions::identity(
+                identity::accounts::RegisterOracle {
+                    oracle: pda::identity::oracle(&oracle_authority),
+                    oracle_registry: pda::identity::oracle_registry(),
+                    oracle_authority,
+                    system_program: system_program::ID,
+                },
+                identity::instruction::RegisterOracle { provider_name, stake_amount },
+            );
+            ctx.send("oracle register", vec![instruction]).await
+        }
+        OracleCommand::VerifyBusiness { consumer, arweave_tx_id } => {
+            let instruction = instructions::identity(
+                identity::accounts::VerifyBusiness {
+                    consumer_profile: pda::identity::consumer_profile(&consumer),
+                    oracle: pda::identity::oracle(&oracle_authority),
+                    oracle_authority,
+                    oracle_registry: pda::identity::oracle_registry(),
+                },
+                identity::instruction::VerifyBusiness {
+                    arweave_business_registry_tx_id: arweave_tx_id,
+                },
+            );
+            ctx.send("oracle verify-business", vec![instruction]).await
+        }
+    }
+}
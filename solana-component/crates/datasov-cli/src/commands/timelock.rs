@@ -0,0 +1,96 @@
+use anyhow::Result;
+use clap::Subcommand;
+use datasov_client::{admin, instructions, pda, timelock};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use super::Context;
+
+/// Default delay between queueing and executing an operation, in seconds (two days)
+const DEFAULT_DELAY: i64 = 2 * 24 * 60 * 60;
+
+#[derive(Subcommand)]
+pub enum TimelockCommand {
+    /// Create the timelock; hand it the upgrade, marketplace and registry authorities afterwards
+    Init {
+        /// Key that queues and cancels operations
+        admin: Pubkey,
+        /// Seconds before a queued operation can execute
+        #[clap(long, default_value_t = DEFAULT_DELAY)]
+        delay: i64,
+    },
+    /// Queue a program upgrade from a buffer whose authority is the timelock (admin only)
+    QueueUpgrade {
+        program: Pubkey,
+        buffer: Pubkey,
+        /// Receives the buffer's lamports; defaults to the signer
+        #[clap(long)]
+        spill: Option<Pubkey>,
+    },
+    /// Queue a marketplace fee change (admin only)
+    QueueMarketplaceFee { fee_basis_points: u16 },
+    /// Cancel a queued operation (admin only)
+    Cancel { operation_id: u64 },
+    /// Execute a queued operation whose delay has passed
+    Execute { operation_id: u64 },
+}
+
+pub async fn run(ctx: &Context, command: TimelockCommand) -> Result<()> {
+    let signer = ctx.signer_pubkey()?;
+    let timelock_address = pda::timelock::timelock();
+    let (action, instruction) = match command {
+        TimelockCommand::Init { admin, delay } => (
+            "timelock init",
+            instructions::timelock(
+                timelock::accounts::InitializeTimelock {
+                    timelock: timelock_address,
+                    payer: signer,
+                    system_program: system_program::ID,
+                },
+                timelock::instruction::InitializeTimelock { admin, delay },
+            ),
+        ),
+        TimelockCommand::QueueUpgrade { program, buffer, spill } => {
+            let operation_id = next_operation_id(ctx).await?;
+            let upgrade = instructions::upgrade_program(&program, &buffer, &spill.unwrap_or(signer));
+            (
+                "timelock queue-upgrade",
+                instructions::queue_operation(&signer, operation_id, &upgrade),
+            )
+        }
+        TimelockCommand::QueueMarketplaceFee { fee_basis_points } => {
+            let operation_id = next_operation_id(ctx).await?;
+            let set_fee = admin::set_marketplace_fee(&timelock_address, fee_basis_points);
+            (
+                "timelock queue-marketplace-fee",
+                instructions::queue_operation(&signer, operation_id, &set_fee),
+            )
+        }
+        TimelockCommand::Cancel { operation_id } => (
+            "timelock cancel",
+            instructions::timelock(
+                timelock::accounts::CancelOperation {
+                    timelock: timelock_address,
+                    operation: pda::timelock::operation(operation_id),
+                    admin: signer,
+                },
+                timelock::instruction::CancelOperation {},
+            ),
+        ),
+        TimelockCommand::Execute { operation_id } => {
+            let operation: timelock::Operation = ctx.rpc.fetch(&pda::timelock::operation(operation_id)).await?;
+            let queued = instructions::operation_instruction(&operation);
+            (
+                "timelock execute",
+                instructions::execute_operation(operation_id, &queued),
+            )
+        }
+    };
+    ctx.send(action, vec![instruction]).await
+}
+
+/// ID the next queued operation gets
+async fn next_operation_id(ctx: &Context) -> Result<u64> {
+    let timelock: timelock::Timelock = ctx.rpc.fetch(&pda::timelock::timelock()).await?;
+    Ok(timelock.operation_count)
+}
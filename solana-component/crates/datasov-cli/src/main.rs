@@ -0,0 +1,95 @@
+//! `datasov`: drive the DataSov identity and marketplace programs from the command line.
+//!
+//! RPC URL and signer default to the Solana CLI config. Signers are anything the Solana CLI
+//! accepts: a keypair file, `usb://ledger[?key=N]`, `prompt://` or `stdin`, plus `exec://<program>` for an
+//! external signer (see [`datasov_client::signer::RemoteSigner::command`]), so oracle and admin
+//! operations can run without a hot key on the host.
+
+mod commands;
+mod output;
+
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
+use datasov_client::rpc::DataSovRpc;
+use datasov_client::signer::RemoteSigner;
+use solana_clap_v3_utils::keypair::signer_from_path;
+use solana_cli_config::{Config, CONFIG_FILE};
+use solana_remote_wallet::remote_wallet::RemoteWalletManager;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signer::Signer;
+
+use commands::{Command, Context};
+
+#[derive(Parser)]
+#[clap(name = "datasov", version, about = "Drive the DataSov identity and marketplace programs")]
+struct Cli {
+    /// JSON-RPC URL; repeat to fail over between endpoints [default: from the Solana CLI config]
+    #[clap(long, short = 'u', global = true, multiple_occurrences = true)]
+    url: Vec<String>,
+
+    /// Signer: keypair file, usb://ledger, prompt://, stdin or exec://<program> [default: from the Solana CLI config]
+    #[clap(long, short = 'k', global = true)]
+    keypair: Option<String>,
+
+    /// Solana CLI config file
+    #[clap(long, short = 'C', global = true)]
+    config: Option<String>,
+
+    /// Print results as JSON
+    #[clap(long, global = true)]
+    json: bool,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let matches = Cli::command().get_matches();
+    if let Err(err) = run(&matches).await {
+        eprintln!("error: {:#}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn run(matches: &ArgMatches) -> Result<()> {
+    let cli = Cli::from_arg_matches(matches)?;
+
+    let config = match cli.config.as_deref().or(CONFIG_FILE.as_deref()) {
+        Some(path) => Config::load(path).unwrap_or_default(),
+        None => Config::default(),
+    };
+    let urls = match cli.url.is_empty() {
+        true => vec![config.json_rpc_url],
+        false => cli.url,
+    };
+    let rpc = DataSovRpc::with_endpoints(urls, CommitmentConfig::confirmed());
+
+    // Only load the signer when the command sends a transaction, so read-only
+    // commands never prompt a hardware wallet
+    let signer: Option<Box<dyn Signer>> = if cli.command.sends_transaction() {
+        let path = cli.keypair.unwrap_or(config.keypair_path);
+        let signer: Box<dyn Signer> = match path.strip_prefix("exec://") {
+            Some(program) => Box::new(
+                RemoteSigner::command(program).map_err(|err| anyhow!("failed to load signer {}: {}", path, err))?,
+            ),
+            None => {
+                let mut wallet_manager: Option<Rc<RemoteWalletManager>> = None;
+                signer_from_path(matches, &path, "keypair", &mut wallet_manager)
+                    .map_err(|err| anyhow!("failed to load signer {}: {}", path, err))?
+            }
+        };
+        Some(signer)
+    } else {
+        None
+    };
+
+    let context = Context {
+        rpc,
+        signer,
+        json: cli.json,
+    };
+    cli.command.run(&context).await
+}
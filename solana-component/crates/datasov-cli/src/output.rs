@@ -0,0 +1,135 @@
+//! Human-readable and JSON rendering of command results.
+
+use datasov_client::identity::{AccessPermission, IdentityAccount, KYCOracle};
+use datasov_client::marketplace::DataListing;
+use datasov_client::staking::Vesting;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+pub fn signature(as_json: bool, action: &str, signature: &Signature) {
+    if as_json {
+        println!("{}", json!({ "action": action, "signature": signature.to_string() }));
+    } else {
+        println!("{}: {}", action, signature);
+    }
+}
+
+/// Report an account `action` created
+pub fn address(as_json: bool, action: &str, address: &Pubkey) {
+    if as_json {
+        println!("{}", json!({ "action": action, "address": address.to_string() }));
+    } else {
+        println!("{}: {}", action, address);
+    }
+}
+
+/// Print records as a JSON array, or as `key: value` blocks separated by blank lines
+pub fn records(as_json: bool, records: Vec<Value>) {
+    if as_json {
+        println!("{}", Value::Array(records));
+        return;
+    }
+    if records.is_empty() {
+        println!("No accounts found");
+    }
+    for (index, record) in records.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        if let Value::Object(fields) = record {
+            for (key, value) in fields {
+                match value {
+                    Value::String(text) => println!("{}: {}", key, text),
+                    Value::Null => println!("{}: -", key),
+                    other => println!("{}: {}", key, other),
+                }
+            }
+        }
+    }
+}
+
+pub fn identity(address: &Pubkey, identity: &IdentityAccount) -> Value {
+    json!({
+        "address": address.to_string(),
+        "identity_id": identity.identity_id,
+        "owner": identity.owner.to_string(),
+        "status": format!("{:?}", identity.status),
+        "verification_level": format!("{:?}", identity.verification_level),
+        "verified_at": identity.verified_at,
+        "storage_pointer": identity.storage_pointer.to_string(),
+        "guardian": identity.guardian.map(|guardian| guardian.to_string()),
+        "sns_domain": identity.sns_domain,
+        "created_at": identity.created_at,
+    })
+}
+
+pub fn oracle(address: &Pubkey, oracle: &KYCOracle) -> Value {
+    json!({
+        "address": address.to_string(),
+        "authority": oracle.oracle_pubkey.to_string(),
+        "provider_name": oracle.provider_name,
+        "stake_amount": oracle.stake_amount,
+        "verification_count": oracle.verification_count,
+        "reputation_score": oracle.reputation_score,
+        "is_active": oracle.is_active,
+    })
+}
+
+pub fn permission(address: &Pubkey, permission: &AccessPermission) -> Value {
+    json!({
+        "address": address.to_string(),
+        "identity_id": permission.identity_id,
+        "consumer": permission.consumer.to_string(),
+        "permission_type": format!("{:?}", permission.permission_type),
+        "data_types": permission.data_types.iter().map(|data_type| format!("{:?}", data_type)).collect::<Vec<_>>(),
+        "is_active": permission.is_active,
+        "granted_at": permission.granted_at,
+        "expires_at": permission.expires_at,
+        "access_count": permission.access_count,
+    })
+}
+
+pub fn listing(address: &Pubkey, listing: &DataListing) -> Value {
+    json!({
+        "address": address.to_string(),
+        "listing_id": listing.id,
+        "owner": listing.owner.to_string(),
+        "identity_id": listing.identity_id(),
+        "data_type": format!("{:?}", listing.data_type()),
+        "price": (!listing.is_private()).then_some(listing.price),
+        "is_private": listing.is_private(),
+        "is_active": listing.is_active(),
+        "storage_paused": listing.storage_paused(),
+        "license_template": listing.license_template(),
+        "rights_transferable": listing.rights_transferable(),
+        "description": listing.description(),
+        "preview": listing.preview().map(|preview| preview.pointer.to_string()),
+        "last_refreshed_at": listing.last_refreshed_at,
+        "buyer": listing.buyer().map(|buyer| buyer.to_string()),
+    })
+}
+
+/// A listing in the featured set with the staking weight that voted for it
+pub fn featured_listing(address: &Pubkey, listing: &DataListing, votes: u64) -> Value {
+    let mut record = self::listing(address, listing);
+    record["votes"] = json!(votes);
+    record
+}
+
+pub fn vesting(address: &Pubkey, vesting: &Vesting, now: i64) -> Value {
+    json!({
+        "address": address.to_string(),
+        "beneficiary": vesting.beneficiary.to_string(),
+        "funder": vesting.funder.to_string(),
+        "vesting_id": vesting.vesting_id,
+        "kind": format!("{:?}", vesting.kind),
+        "total": vesting.total,
+        "vested": vesting.vested_at(now),
+        "claimed": vesting.claimed,
+        "start_at": vesting.start_at,
+        "cliff_at": vesting.cliff_at,
+        "end_at": vesting.end_at,
+        "revoked_at": vesting.revoked_at,
+    })
+}
@@ -0,0 +1,630 @@
+//! Instructions of the registry, marketplace and staking authorities.
+//!
+//! Each builder takes the `authority` that signs, so the same instruction serves a hot key, the
+//! timelock (see [`crate::instructions::queue_operation`]) or a Squads vault (see [`crate::squads`]).
+//! Instructions that create an account have the authority pay its rent, so a vault needs SOL.
+
+use datasov_identity::{AttestationKind, Groth16VerifyingKey, VerificationLevel};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::instructions::{identity, marketplace, staking};
+use crate::pda;
+
+/// Create the oracle registry, making `authority` the registry authority
+pub fn initialize_oracle_registry(authority: &Pubkey, minimum_stake: u64, slash_amount: u64) -> Instruction {
+    identity(
+        datasov_identity::accounts::InitializeOracleRegistry {
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::InitializeOracleRegistry {
+            minimum_stake,
+            slash_amount,
+        },
+    )
+}
+
+pub fn set_oracle_registry_parameters(authority: &Pubkey, minimum_stake: u64, slash_amount: u64) -> Instruction {
+    identity(
+        datasov_identity::accounts::UpdateOracleRegistry {
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+        },
+        datasov_identity::instruction::SetOracleRegistryParameters {
+            minimum_stake,
+            slash_amount,
+        },
+    )
+}
+
+/// Hand the oracle registry, compliance matrix and blacklist to `new_authority`
+pub fn set_registry_authority(authority: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::SetRegistryAuthority {
+            oracle_registry: pda::identity::oracle_registry(),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            blacklist: pda::identity::blacklist(),
+            authority: *authority,
+        },
+        datasov_identity::instruction::SetRegistryAuthority {
+            new_authority: *new_authority,
+        },
+    )
+}
+
+pub fn initialize_compliance_matrix(authority: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::InitializeComplianceMatrix {
+            compliance_matrix: pda::identity::compliance_matrix(),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::InitializeComplianceMatrix {},
+    )
+}
+
+pub fn set_compliance_rule(
+    authority: &Pubkey,
+    data_type: datasov_identity::DataType,
+    min_buyer_level: VerificationLevel,
+    min_seller_level: VerificationLevel,
+    min_consumer_stake: u64,
+) -> Instruction {
+    identity(
+        datasov_identity::accounts::SetComplianceRule {
+            compliance_matrix: pda::identity::compliance_matrix(),
+            authority: *authority,
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::SetComplianceRule {
+            data_type,
+            min_buyer_level,
+            min_seller_level,
+            min_consumer_stake,
+        },
+    )
+}
+
+pub fn initialize_identity_crank_fund(authority: &Pubkey, reward_per_account: u64) -> Instruction {
+    identity(
+        datasov_identity::accounts::InitializeCrankFund {
+            crank_fund: pda::identity::crank_fund(),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::InitializeCrankFund { reward_per_account },
+    )
+}
+
+pub fn initialize_blacklist(authority: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::InitializeBlacklist {
+            blacklist: pda::identity::blacklist(),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::InitializeBlacklist {},
+    )
+}
+
+/// Blacklist `consumer`, notifying the owners of `permissions` (grants to the consumer)
+pub fn add_to_blacklist(
+    authority: &Pubkey,
+    consumer: &Pubkey,
+    reason_hash: [u8; 32],
+    permissions: &[Pubkey],
+) -> Instruction {
+    let mut instruction = identity(
+        datasov_identity::accounts::UpdateBlacklist {
+            blacklist: pda::identity::blacklist(),
+            authority: *authority,
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::AddToBlacklist {
+            consumer: *consumer,
+            reason_hash,
+        },
+    );
+    let permissions = permissions
+        .iter()
+        .map(|permission| AccountMeta::new_readonly(*permission, false));
+    instruction.accounts.extend(permissions);
+    instruction
+}
+
+pub fn remove_from_blacklist(authority: &Pubkey, consumer: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::UpdateBlacklist {
+            blacklist: pda::identity::blacklist(),
+            authority: *authority,
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::RemoveFromBlacklist { consumer: *consumer },
+    )
+}
+
+pub fn slash_consumer_stake(
+    authority: &Pubkey,
+    consumer: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+    evidence_tx_id: &str,
+) -> Instruction {
+    identity(
+        datasov_identity::accounts::SlashConsumerStake {
+            consumer_stake: pda::identity::consumer_stake(consumer),
+            oracle_registry: pda::identity::oracle_registry(),
+            recipient: *recipient,
+            authority: *authority,
+        },
+        datasov_identity::instruction::SlashConsumerStake {
+            amount,
+            evidence_tx_id: evidence_tx_id.to_string(),
+        },
+    )
+}
+
+pub fn initialize_juror_pool(authority: &Pubkey, vrf_authority: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::InitializeJurorPool {
+            juror_pool: pda::identity::juror_pool(),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::InitializeJurorPool {
+            vrf_authority: *vrf_authority,
+        },
+    )
+}
+
+pub fn register_web_proof_verifier(
+    authority: &Pubkey,
+    verifier_id: u32,
+    verification_level: VerificationLevel,
+    verifying_key: Groth16VerifyingKey,
+) -> Instruction {
+    identity(
+        datasov_identity::accounts::RegisterWebProofVerifier {
+            verifier: pda::identity::web_proof_verifier(verifier_id),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::RegisterWebProofVerifier {
+            verifier_id,
+            verification_level,
+            verifying_key,
+        },
+    )
+}
+
+pub fn set_web_proof_verifier_active(authority: &Pubkey, verifier_id: u32, is_active: bool) -> Instruction {
+    identity(
+        datasov_identity::accounts::SetWebProofVerifierActive {
+            verifier: pda::identity::web_proof_verifier(verifier_id),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+        },
+        datasov_identity::instruction::SetWebProofVerifierActive { is_active },
+    )
+}
+
+pub fn register_attestation_source(
+    authority: &Pubkey,
+    kind: AttestationKind,
+    issuer: &Pubkey,
+    schema: &Pubkey,
+    verification_level: VerificationLevel,
+) -> Instruction {
+    identity(
+        datasov_identity::accounts::RegisterAttestationSource {
+            attestation_source: pda::identity::attestation_source(issuer, schema),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::RegisterAttestationSource {
+            kind,
+            issuer: *issuer,
+            schema: *schema,
+            verification_level,
+        },
+    )
+}
+
+pub fn set_attestation_source_active(
+    authority: &Pubkey,
+    issuer: &Pubkey,
+    schema: &Pubkey,
+    is_active: bool,
+) -> Instruction {
+    identity(
+        datasov_identity::accounts::SetAttestationSourceActive {
+            attestation_source: pda::identity::attestation_source(issuer, schema),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+        },
+        datasov_identity::instruction::SetAttestationSourceActive { is_active },
+    )
+}
+
+pub fn initialize_state_snapshot(authority: &Pubkey, crank: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::InitializeStateSnapshot {
+            state_snapshot: pda::identity::state_snapshot(),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::InitializeStateSnapshot { crank: *crank },
+    )
+}
+
+/// Let `officer` freeze identities pending investigation
+pub fn add_compliance_officer(authority: &Pubkey, officer: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::AddComplianceOfficer {
+            compliance_officer: pda::identity::compliance_officer(officer),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::AddComplianceOfficer { officer: *officer },
+    )
+}
+
+pub fn remove_compliance_officer(authority: &Pubkey, officer: &Pubkey) -> Instruction {
+    identity(
+        datasov_identity::accounts::RemoveComplianceOfficer {
+            compliance_officer: pda::identity::compliance_officer(officer),
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+        },
+        datasov_identity::instruction::RemoveComplianceOfficer {},
+    )
+}
+
+/// Create the marketplace, making `authority` the marketplace authority
+pub fn initialize_marketplace(authority: &Pubkey, fee_basis_points: u16) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::InitializeMarketplace {
+            marketplace: pda::marketplace::marketplace(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::InitializeMarketplace {
+            marketplace_fee_basis_points: fee_basis_points,
+        },
+    )
+}
+
+pub fn set_marketplace_fee(authority: &Pubkey, fee_basis_points: u16) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::UpdateMarketplace {
+            marketplace: pda::marketplace::marketplace(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetMarketplaceFee { fee_basis_points },
+    )
+}
+
+pub fn set_marketplace_authority(authority: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::UpdateMarketplace {
+            marketplace: pda::marketplace::marketplace(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetMarketplaceAuthority {
+            new_authority: *new_authority,
+        },
+    )
+}
+
+/// Withdraw `amount` of `mint` fees to the authority's associated token account, which must exist
+pub fn withdraw_fees(authority: &Pubkey, mint: &Pubkey, amount: u64) -> Instruction {
+    let marketplace_address = pda::marketplace::marketplace();
+    marketplace(
+        datasov_solana::accounts::WithdrawFees {
+            marketplace: marketplace_address,
+            authority: *authority,
+            marketplace_token_account: get_associated_token_address(&marketplace_address, mint),
+            authority_token_account: get_associated_token_address(authority, mint),
+            token_program: spl_token::ID,
+        },
+        datasov_solana::instruction::WithdrawFees { amount },
+    )
+}
+
+pub fn halt_data_type(authority: &Pubkey, data_type: datasov_solana::DataType, halted: bool) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::HaltDataType {
+            marketplace: pda::marketplace::marketplace(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::HaltDataType { data_type, halted },
+    )
+}
+
+/// Replace the enabled subsystems, a bitset of `FeatureFlags` constants
+pub fn set_feature_flags(authority: &Pubkey, flags: u64) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::SetFeatureFlags {
+            marketplace: pda::marketplace::marketplace(),
+            feature_flags: pda::marketplace::feature_flags(),
+            authority: *authority,
+            payer: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::SetFeatureFlags { flags },
+    )
+}
+
+pub fn initialize_marketplace_crank_fund(authority: &Pubkey, reward_per_account: u64) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::InitializeCrankFund {
+            crank_fund: pda::marketplace::crank_fund(),
+            marketplace: pda::marketplace::marketplace(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::InitializeCrankFund { reward_per_account },
+    )
+}
+
+pub fn set_settlement_mints(authority: &Pubkey, stablecoin_only: bool, approved_mints: Vec<Pubkey>) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::SetSettlementMints {
+            marketplace: pda::marketplace::marketplace(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetSettlementMints {
+            stablecoin_only,
+            approved_mints,
+        },
+    )
+}
+
+pub fn set_refund_window(authority: &Pubkey, refund_window: i64) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::SetRefundWindow {
+            marketplace: pda::marketplace::marketplace(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetRefundWindow { refund_window },
+    )
+}
+
+/// Start fee rebates paid in `mint` from the rebate vault, which is funded by plain transfers
+pub fn initialize_rebates(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    epoch_duration: i64,
+    emission_per_epoch: u64,
+) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::InitializeRebates {
+            marketplace: pda::marketplace::marketplace(),
+            rebate_program: pda::marketplace::rebate_program(),
+            mint: *mint,
+            vault: pda::marketplace::rebate_vault(),
+            authority: *authority,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::InitializeRebates {
+            epoch_duration,
+            emission_per_epoch,
+        },
+    )
+}
+
+pub fn set_rebate_emission(authority: &Pubkey, emission_per_epoch: u64) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::UpdateRebateProgram {
+            marketplace: pda::marketplace::marketplace(),
+            rebate_program: pda::marketplace::rebate_program(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetRebateEmission { emission_per_epoch },
+    )
+}
+
+/// Configure buybacks of `protocol_mint` paid for with the marketplace's `fee_mint` fees
+pub fn initialize_buyback(
+    authority: &Pubkey,
+    fee_mint: &Pubkey,
+    protocol_mint: &Pubkey,
+    parameters: datasov_solana::BuybackParameters,
+) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::InitializeBuyback {
+            marketplace: pda::marketplace::marketplace(),
+            buyback_config: pda::marketplace::buyback(),
+            fee_mint: *fee_mint,
+            protocol_mint: *protocol_mint,
+            input_vault: pda::marketplace::buyback_input(),
+            output_vault: pda::marketplace::buyback_output(),
+            authority: *authority,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::InitializeBuyback { parameters },
+    )
+}
+
+pub fn set_buyback_parameters(authority: &Pubkey, parameters: datasov_solana::BuybackParameters) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::UpdateBuyback {
+            marketplace: pda::marketplace::marketplace(),
+            buyback_config: pda::marketplace::buyback(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetBuybackParameters { parameters },
+    )
+}
+
+/// Start featured-listing curation with vote bonds in the protocol token `mint`
+pub fn initialize_curation(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    epoch_duration: i64,
+    vote_bond: u64,
+    dispute_window: i64,
+) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::InitializeCuration {
+            marketplace: pda::marketplace::marketplace(),
+            curation_config: pda::marketplace::curation(),
+            mint: *mint,
+            vault: pda::marketplace::curation_vault(),
+            authority: *authority,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::InitializeCuration {
+            epoch_duration,
+            vote_bond,
+            dispute_window,
+        },
+    )
+}
+
+pub fn set_curation_parameters(authority: &Pubkey, vote_bond: u64, dispute_window: i64) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::UpdateCuration {
+            marketplace: pda::marketplace::marketplace(),
+            curation_config: pda::marketplace::curation(),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetCurationParameters {
+            vote_bond,
+            dispute_window,
+        },
+    )
+}
+
+/// Flag `listing`, curated in `epoch`, as disputed: it leaves the featured set and its curators
+/// forfeit their bonds
+pub fn flag_curated_listing(authority: &Pubkey, listing: &Pubkey, epoch: u64) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::FlagCuratedListing {
+            marketplace: pda::marketplace::marketplace(),
+            tally: pda::marketplace::listing_tally(epoch, listing),
+            featured_set: pda::marketplace::featured_set(epoch),
+            authority: *authority,
+        },
+        datasov_solana::instruction::FlagCuratedListing {},
+    )
+}
+
+/// Let `oracle` report on listings' stored data; failed checks pause a listing's sales
+pub fn add_storage_oracle(authority: &Pubkey, oracle: &Pubkey) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::AddStorageOracle {
+            marketplace: pda::marketplace::marketplace(),
+            storage_oracle: pda::marketplace::storage_oracle(oracle),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::AddStorageOracle { oracle: *oracle },
+    )
+}
+
+pub fn remove_storage_oracle(authority: &Pubkey, oracle: &Pubkey) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::RemoveStorageOracle {
+            marketplace: pda::marketplace::marketplace(),
+            storage_oracle: pda::marketplace::storage_oracle(oracle),
+            authority: *authority,
+        },
+        datasov_solana::instruction::RemoveStorageOracle {},
+    )
+}
+
+/// Publish license terms listings can reference; the authority also pays the template's rent
+pub fn create_license_template(
+    authority: &Pubkey,
+    template_id: u32,
+    terms: datasov_solana::LicenseTerms,
+    terms_hash: [u8; 32],
+) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::CreateLicenseTemplate {
+            marketplace: pda::marketplace::marketplace(),
+            license_template: pda::marketplace::license_template(template_id),
+            authority: *authority,
+            payer: *authority,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::CreateLicenseTemplate {
+            template_id,
+            terms,
+            terms_hash,
+        },
+    )
+}
+
+pub fn set_license_template_active(authority: &Pubkey, template_id: u32, is_active: bool) -> Instruction {
+    marketplace(
+        datasov_solana::accounts::UpdateLicenseTemplate {
+            marketplace: pda::marketplace::marketplace(),
+            license_template: pda::marketplace::license_template(template_id),
+            authority: *authority,
+        },
+        datasov_solana::instruction::SetLicenseTemplateActive { is_active },
+    )
+}
+
+/// Revoke `vesting`, returning its unvested tokens of `mint` to the funder's associated token
+/// account
+pub fn revoke_vesting(authority: &Pubkey, mint: &Pubkey, vesting: &datasov_staking::Vesting) -> Instruction {
+    let address = pda::staking::vesting(&vesting.beneficiary, vesting.vesting_id);
+    staking(
+        datasov_staking::accounts::RevokeVesting {
+            config: pda::staking::config(),
+            vesting: address,
+            vault: pda::staking::vesting_vault(&address),
+            funder_token_account: get_associated_token_address(&vesting.funder, mint),
+            authority: *authority,
+            token_program: spl_token::ID,
+        },
+        datasov_staking::instruction::RevokeVesting {},
+    )
+}
+
+/// Post an airdrop of `mint` whose allocations hash to `merkle_root`; fund its vault with plain
+/// transfers
+pub fn create_airdrop(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    airdrop_id: u64,
+    merkle_root: [u8; 32],
+    leaf_count: u32,
+) -> Instruction {
+    let airdrop = pda::staking::airdrop(airdrop_id);
+    staking(
+        datasov_staking::accounts::CreateAirdrop {
+            config: pda::staking::config(),
+            airdrop,
+            vault: pda::staking::airdrop_vault(&airdrop),
+            mint: *mint,
+            authority: *authority,
+            payer: *authority,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        datasov_staking::instruction::CreateAirdrop {
+            airdrop_id,
+            merkle_root,
+            leaf_count,
+        },
+    )
+}
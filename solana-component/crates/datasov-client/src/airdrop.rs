@@ -0,0 +1,98 @@
+//! Merkle trees of airdrop allocations, for posting an airdrop's root and proving claims against it.
+//!
+//! Leaves and inner nodes hash as the staking program's `Airdrop::leaf` and `Airdrop::node` do,
+//! and the tree is padded with zero leaves to a power of two, so a proof carries `Airdrop::depth`
+//! siblings from the leaf up.
+
+use datasov_staking::Airdrop;
+use solana_sdk::pubkey::Pubkey;
+
+/// Tokens one wallet may claim from an airdrop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allocation {
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+/// Every level of an airdrop's tree, for generating proofs
+pub struct AirdropTree {
+    allocations: Vec<Allocation>,
+    /// Filled nodes per level, leaves first; nodes past the end are empty subtrees
+    levels: Vec<Vec<[u8; 32]>>,
+    /// Root of an empty subtree per level
+    zeros: Vec<[u8; 32]>,
+}
+
+impl AirdropTree {
+    /// Tree over `allocations`, each leaf at its position in the list
+    pub fn new(allocations: Vec<Allocation>) -> Self {
+        let depth = (allocations.len() as u32).next_power_of_two().trailing_zeros() as usize;
+        let mut zeros = vec![[0u8; 32]];
+        let mut levels = vec![allocations
+            .iter()
+            .enumerate()
+            .map(|(index, allocation)| Airdrop::leaf(index as u32, &allocation.wallet, allocation.amount))
+            .collect::<Vec<_>>()];
+        for level in 0..depth {
+            let zero = zeros[level];
+            let next = levels[level]
+                .chunks(2)
+                .map(|pair| Airdrop::node(&pair[0], pair.get(1).unwrap_or(&zero)))
+                .collect();
+            levels.push(next);
+            zeros.push(Airdrop::node(&zero, &zero));
+        }
+        Self {
+            allocations,
+            levels,
+            zeros,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.allocations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allocations.is_empty()
+    }
+
+    /// Leaf count to post with the root
+    pub fn leaf_count(&self) -> u32 {
+        self.allocations.len() as u32
+    }
+
+    /// Root to post for the airdrop
+    pub fn root(&self) -> [u8; 32] {
+        let depth = self.levels.len() - 1;
+        self.levels[depth].first().copied().unwrap_or(self.zeros[depth])
+    }
+
+    /// Index of the first allocation to `wallet`
+    pub fn index_of(&self, wallet: &Pubkey) -> Option<u32> {
+        self.allocations
+            .iter()
+            .position(|allocation| allocation.wallet == *wallet)
+            .map(|index| index as u32)
+    }
+
+    pub fn allocation(&self, index: u32) -> Option<&Allocation> {
+        self.allocations.get(index as usize)
+    }
+
+    /// Siblings from allocation `index`'s leaf up to the root
+    pub fn proof(&self, index: u32) -> Option<Vec<[u8; 32]>> {
+        self.allocation(index)?;
+        let index = index as usize;
+        Some(
+            (0..self.levels.len() - 1)
+                .map(|level| {
+                    self.levels[level]
+                        .get((index >> level) ^ 1)
+                        .copied()
+                        .unwrap_or(self.zeros[level])
+                })
+                .collect(),
+        )
+    }
+}
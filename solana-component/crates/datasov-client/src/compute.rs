@@ -0,0 +1,182 @@
+//! Compute budget and priority fees.
+//!
+//! Every DataSov instruction has a compute unit ceiling here, with headroom over its most
+//! expensive path (audit page creation, CPIs into the identity and token programs), so a
+//! transaction can request just the units it needs. Validators schedule by fee per requested
+//! unit, so a tight limit buys more priority for the same fee. Instructions of other programs
+//! fall back to the runtime's per-instruction default; simulating a transaction (see
+//! `DataSovRpc::simulated_units`) gives an exact figure for anything unusual.
+
+use anchor_lang::Discriminator;
+use datasov_audit::instruction as audit;
+use datasov_governance::instruction as governance;
+use datasov_identity::instruction as identity;
+use datasov_solana::instruction as marketplace;
+use datasov_staking::instruction as staking;
+use datasov_timelock::instruction as timelock;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+
+/// Units the runtime allows an instruction that has no ceiling here
+pub const DEFAULT_UNITS: u32 = 200_000;
+
+/// Most units a transaction may request
+pub const MAX_UNITS: u32 = 1_400_000;
+
+/// Units for the two compute budget instructions themselves
+const BUDGET_UNITS: u32 = 300;
+
+/// Ceilings of the identity program's instructions that cost more than a plain account write
+const IDENTITY_UNITS: &[([u8; 8], u32)] = &[
+    (identity::RegisterIdentity::DISCRIMINATOR, 30_000),
+    (identity::VerifyIdentity::DISCRIMINATOR, 60_000),
+    (identity::GrantAccess::DISCRIMINATOR, 90_000),
+    (identity::RevokeAccess::DISCRIMINATOR, 70_000),
+    (identity::ValidateAccess::DISCRIMINATOR, 80_000),
+    (identity::ValidateAccessBatch::DISCRIMINATOR, 300_000),
+    (identity::GrantTrialAccess::DISCRIMINATOR, 90_000),
+    (identity::TrusteeGrantAccess::DISCRIMINATOR, 100_000),
+    (identity::RedeemGrantVoucher::DISCRIMINATOR, 120_000),
+    (identity::AcceptAccessRequest::DISCRIMINATOR, 100_000),
+    (identity::VerifyWebProof::DISCRIMINATOR, 90_000),
+    (identity::VerifyWithAttestation::DISCRIMINATOR, 80_000),
+    (identity::AttestConsumption::DISCRIMINATOR, 70_000),
+    (identity::ResolveDispute::DISCRIMINATOR, 150_000),
+    (identity::SweepExpired::DISCRIMINATOR, 200_000),
+    (identity::ExtendStateSnapshot::DISCRIMINATOR, 200_000),
+    (identity::PublishStateSnapshot::DISCRIMINATOR, 40_000),
+];
+
+/// Ceilings of the marketplace program's instructions that cost more than a plain account write
+const MARKETPLACE_UNITS: &[([u8; 8], u32)] = &[
+    (marketplace::CreateDataListing::DISCRIMINATOR, 40_000),
+    (marketplace::CreatePrivateListing::DISCRIMINATOR, 50_000),
+    (marketplace::CreateTrustListing::DISCRIMINATOR, 50_000),
+    (marketplace::PurchaseData::DISCRIMINATOR, 180_000),
+    (marketplace::PurchaseFor::DISCRIMINATOR, 180_000),
+    (marketplace::PurchaseAsDelegate::DISCRIMINATOR, 190_000),
+    // Recombines the proven ciphertexts with curve syscalls before two confidential transfers
+    (marketplace::PurchaseDataConfidential::DISCRIMINATOR, 260_000),
+    (marketplace::ReleaseProceeds::DISCRIMINATOR, 60_000),
+    (marketplace::ClaimRevocationRefund::DISCRIMINATOR, 60_000),
+    (marketplace::WithdrawFees::DISCRIMINATOR, 30_000),
+    (marketplace::ConfigureConfidentialFees::DISCRIMINATOR, 40_000),
+    (marketplace::ApplyConfidentialFees::DISCRIMINATOR, 30_000),
+    (marketplace::WithdrawConfidentialFees::DISCRIMINATOR, 50_000),
+    (marketplace::CreateComputeJob::DISCRIMINATOR, 80_000),
+    (marketplace::ReleaseComputePayment::DISCRIMINATOR, 60_000),
+    (marketplace::RefundComputeJob::DISCRIMINATOR, 50_000),
+    (marketplace::ClaimFlReward::DISCRIMINATOR, 50_000),
+    (marketplace::ClaimUnionProceeds::DISCRIMINATOR, 50_000),
+    (marketplace::InitializeRebates::DISCRIMINATOR, 40_000),
+    (marketplace::ClaimRebate::DISCRIMINATOR, 50_000),
+    (marketplace::InitializeBuyback::DISCRIMINATOR, 50_000),
+    // Includes the DEX route, which for a multi-hop aggregator swap dominates
+    (marketplace::ExecuteBuyback::DISCRIMINATOR, 600_000),
+    // Hashes every chunk
+    (marketplace::SealDataManifest::DISCRIMINATOR, 40_000),
+    (marketplace::InitializeCuration::DISCRIMINATOR, 40_000),
+    // Re-sorts the featured set and may create it, the tally and the vote
+    (marketplace::VoteFeatured::DISCRIMINATOR, 80_000),
+    (marketplace::SettleCurationVote::DISCRIMINATOR, 40_000),
+    (marketplace::SweepExpired::DISCRIMINATOR, 200_000),
+    // Creates the mint with its metadata, and the buyer's token account
+    (marketplace::MintLicense::DISCRIMINATOR, 150_000),
+];
+
+/// Ceilings of the audit program's instructions
+const AUDIT_UNITS: &[([u8; 8], u32)] = &[
+    (audit::AppendEntry::DISCRIMINATOR, 25_000),
+    (audit::CheckpointAuditLog::DISCRIMINATOR, 200_000),
+];
+
+/// Ceilings of the governance program's instructions that cost more than a plain account write
+const GOVERNANCE_UNITS: &[([u8; 8], u32)] = &[
+    (governance::DepositStake::DISCRIMINATOR, 30_000),
+    (governance::CreateProposal::DISCRIMINATOR, 30_000),
+    (governance::CastVote::DISCRIMINATOR, 30_000),
+    (governance::ExecuteSetMarketplaceFee::DISCRIMINATOR, 50_000),
+    (governance::ExecuteSetComplianceRule::DISCRIMINATOR, 50_000),
+    (governance::ExecuteSetOracleRegistryParameters::DISCRIMINATOR, 50_000),
+    (governance::ExecuteSetRebateEmission::DISCRIMINATOR, 50_000),
+    (governance::ExecuteRevokeVesting::DISCRIMINATOR, 60_000),
+    (governance::ExecuteCreateAirdrop::DISCRIMINATOR, 70_000),
+    (governance::ExecuteCreateLicenseTemplate::DISCRIMINATOR, 70_000),
+    (governance::ExecuteSetFeatureFlags::DISCRIMINATOR, 60_000),
+    (governance::ExecuteLiftIdentityFreeze::DISCRIMINATOR, 50_000),
+];
+
+/// Ceilings of the timelock program's instructions that cost more than a plain account write.
+/// An executed operation also spends whatever its queued instruction does; simulate those.
+const TIMELOCK_UNITS: &[([u8; 8], u32)] = &[
+    (timelock::QueueOperation::DISCRIMINATOR, 30_000),
+    (timelock::ExecuteOperation::DISCRIMINATOR, 200_000),
+];
+
+/// Ceilings of the staking program's instructions that cost more than a plain account write
+const STAKING_UNITS: &[([u8; 8], u32)] = &[
+    (staking::InitializeStaking::DISCRIMINATOR, 40_000),
+    (staking::Stake::DISCRIMINATOR, 50_000),
+    (staking::WithdrawStake::DISCRIMINATOR, 30_000),
+    (staking::SlashBacking::DISCRIMINATOR, 30_000),
+    (staking::ApplyDisputeSlash::DISCRIMINATOR, 40_000),
+    (staking::CreateVesting::DISCRIMINATOR, 50_000),
+    (staking::ClaimVested::DISCRIMINATOR, 30_000),
+    (staking::RevokeVesting::DISCRIMINATOR, 30_000),
+    (staking::CreateAirdrop::DISCRIMINATOR, 40_000),
+    (staking::ClaimAirdrop::DISCRIMINATOR, 50_000),
+];
+
+/// Ceiling for DataSov instructions not listed above, which only write their own accounts
+const SIMPLE_UNITS: u32 = 20_000;
+
+/// Compute unit ceiling for one instruction
+pub fn instruction_units(instruction: &Instruction) -> u32 {
+    let table = match instruction.program_id {
+        id if id == datasov_identity::ID => IDENTITY_UNITS,
+        id if id == datasov_solana::ID => MARKETPLACE_UNITS,
+        id if id == datasov_audit::ID => AUDIT_UNITS,
+        id if id == datasov_governance::ID => GOVERNANCE_UNITS,
+        id if id == datasov_timelock::ID => TIMELOCK_UNITS,
+        id if id == datasov_staking::ID => STAKING_UNITS,
+        _ => return DEFAULT_UNITS,
+    };
+    instruction
+        .data
+        .get(..8)
+        .and_then(|discriminator| table.iter().find(|(known, _)| known == discriminator))
+        .map_or(SIMPLE_UNITS, |(_, units)| *units)
+}
+
+/// Compute unit ceiling for a transaction of `instructions`, budget instructions included
+pub fn transaction_units(instructions: &[Instruction]) -> u32 {
+    instructions
+        .iter()
+        .map(instruction_units)
+        .fold(BUDGET_UNITS, u32::saturating_add)
+        .min(MAX_UNITS)
+}
+
+/// Instructions requesting `units` at `micro_lamports` per unit; a zero price is left out
+pub fn budget_instructions(units: u32, micro_lamports: u64) -> Vec<Instruction> {
+    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(units)];
+    if micro_lamports > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(micro_lamports));
+    }
+    instructions
+}
+
+/// The `percentile` (0-100) of recent per-unit fees, in micro-lamports
+pub fn fee_percentile(fees: &[u64], percentile: u8) -> u64 {
+    let mut fees = fees.to_vec();
+    fees.sort_unstable();
+    match fees.len() {
+        0 => 0,
+        len => fees[(len - 1) * percentile.min(100) as usize / 100],
+    }
+}
+
+/// Priority fee in lamports for `units` at `micro_lamports` per unit
+pub fn priority_fee_lamports(units: u32, micro_lamports: u64) -> u64 {
+    (units as u128 * micro_lamports as u128).div_ceil(1_000_000) as u64
+}
@@ -0,0 +1,170 @@
+//! Encryption shared by sellers and buyers.
+//!
+//! Documents are sealed with a per-document [`ContentKey`] (AES-256-GCM, `nonce || ciphertext`).
+//! Content keys, and other small secrets, are handed to a counterparty by sealing them to the
+//! X25519 key published on its identity (`set_identity_encryption_key`) or consumer profile
+//! (`set_consumer_encryption_key`).
+//!
+//! Envelope format: `version (1) || ephemeral public key (32) || nonce (12) || ciphertext`, under
+//! the AES-256-GCM key `HKDF-SHA256(X25519(ephemeral, recipient), salt = ephemeral || recipient)`.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use solana_sdk::signer::Signer;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::{ClientError, Result};
+
+const NONCE_LEN: usize = 12;
+const ENVELOPE_VERSION: u8 = 1;
+const ENVELOPE_INFO: &[u8] = b"datasov-envelope-v1";
+/// Message signed by a wallet to derive its encryption key; changing it changes every derived key
+const DERIVATION_MESSAGE: &[u8] = b"DataSov encryption key v1";
+
+/// Per-document AES-256-GCM key. Whoever holds it can read the document, so it only travels
+/// wrapped to a recipient with [`wrap_content_key`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct ContentKey([u8; 32]);
+
+impl ContentKey {
+    pub fn generate() -> Self {
+        Self(Aes256Gcm::generate_key(OsRng).into())
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for ContentKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ContentKey(..)")
+    }
+}
+
+/// Encrypt under a fresh random nonce
+pub fn seal(key: &ContentKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut sealed = Vec::with_capacity(NONCE_LEN + plaintext.len() + 16);
+    seal_into(&mut sealed, key.as_bytes(), plaintext)?;
+    Ok(sealed)
+}
+
+/// Decrypt a payload produced by [`seal`]; fails if it was truncated or modified
+pub fn open(key: &ContentKey, sealed: &[u8]) -> Result<Vec<u8>> {
+    open_with(key.as_bytes(), sealed)
+}
+
+/// An X25519 keypair whose public half is published on chain
+pub struct EncryptionKeypair {
+    secret: StaticSecret,
+}
+
+impl EncryptionKeypair {
+    pub fn generate() -> Self {
+        Self {
+            secret: StaticSecret::random_from_rng(OsRng),
+        }
+    }
+
+    pub fn from_secret_bytes(bytes: [u8; 32]) -> Self {
+        Self {
+            secret: StaticSecret::from(bytes),
+        }
+    }
+
+    /// Derive from a wallet, so the key can be recovered from the wallet alone. Ed25519
+    /// signatures are deterministic, so the same signer always yields the same keypair.
+    pub fn derive(signer: &dyn Signer) -> Result<Self> {
+        let signature = signer.try_sign_message(DERIVATION_MESSAGE)?;
+        Ok(Self::from_secret_bytes(Sha256::digest(signature).into()))
+    }
+
+    /// Value for `set_identity_encryption_key` / `set_consumer_encryption_key`
+    pub fn public_key(&self) -> [u8; 32] {
+        PublicKey::from(&self.secret).to_bytes()
+    }
+
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.secret.to_bytes()
+    }
+}
+
+/// Encrypt `plaintext` so only the holder of `recipient`'s secret key can read it
+pub fn seal_to(recipient: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let recipient = PublicKey::from(*recipient);
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(&recipient);
+    if !shared.was_contributory() {
+        return Err(ClientError::Encryption);
+    }
+    let key = envelope_key(shared.as_bytes(), ephemeral_public.as_bytes(), recipient.as_bytes());
+
+    let mut envelope = Vec::with_capacity(1 + 32 + NONCE_LEN + plaintext.len() + 16);
+    envelope.push(ENVELOPE_VERSION);
+    envelope.extend_from_slice(ephemeral_public.as_bytes());
+    seal_into(&mut envelope, &key, plaintext)?;
+    Ok(envelope)
+}
+
+/// Decrypt an envelope produced by [`seal_to`] for `keypair`
+pub fn open_envelope(keypair: &EncryptionKeypair, envelope: &[u8]) -> Result<Vec<u8>> {
+    let (&version, rest) = envelope.split_first().ok_or(ClientError::Decryption)?;
+    if version != ENVELOPE_VERSION || rest.len() < 32 {
+        return Err(ClientError::Decryption);
+    }
+    let (ephemeral_public, sealed) = rest.split_at(32);
+    let ephemeral_public: [u8; 32] = ephemeral_public.try_into().map_err(|_| ClientError::Decryption)?;
+    let shared = keypair.secret.diffie_hellman(&PublicKey::from(ephemeral_public));
+    let key = envelope_key(shared.as_bytes(), &ephemeral_public, &keypair.public_key());
+    open_with(&key, sealed)
+}
+
+/// Hand a document's content key to `recipient`
+pub fn wrap_content_key(recipient: &[u8; 32], key: &ContentKey) -> Result<Vec<u8>> {
+    seal_to(recipient, key.as_bytes())
+}
+
+pub fn unwrap_content_key(keypair: &EncryptionKeypair, envelope: &[u8]) -> Result<ContentKey> {
+    let bytes = open_envelope(keypair, envelope)?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| ClientError::Decryption)?;
+    Ok(ContentKey(bytes))
+}
+
+fn envelope_key(shared: &[u8; 32], ephemeral_public: &[u8; 32], recipient: &[u8; 32]) -> [u8; 32] {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient);
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(ENVELOPE_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn seal_into(out: &mut Vec<u8>, key: &[u8; 32], plaintext: &[u8]) -> Result<()> {
+    let nonce = Aes256Gcm::generate_nonce(OsRng);
+    let ciphertext = Aes256Gcm::new(&Key::<Aes256Gcm>::from(*key))
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| ClientError::Encryption)?;
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(())
+}
+
+fn open_with(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return Err(ClientError::Decryption);
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().map_err(|_| ClientError::Decryption)?;
+    Aes256Gcm::new(&Key::<Aes256Gcm>::from(*key))
+        .decrypt(&Nonce::from(nonce), ciphertext)
+        .map_err(|_| ClientError::Decryption)
+}
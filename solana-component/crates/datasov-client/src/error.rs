@@ -0,0 +1,57 @@
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    #[error("account {address} could not be decoded: {source}")]
+    Decode {
+        address: Pubkey,
+        #[source]
+        source: anchor_lang::error::Error,
+    },
+
+    #[error("transaction signing failed: {0}")]
+    Signing(#[from] solana_sdk::signer::SignerError),
+
+    #[error("transaction message could not be compiled: {0}")]
+    Compile(#[from] solana_sdk::message::CompileError),
+
+    #[error("account {0} is not an address lookup table")]
+    LookupTable(Pubkey),
+
+    #[error("encryption failed")]
+    Encryption,
+
+    #[error("decryption failed: wrong key or tampered ciphertext")]
+    Decryption,
+
+    /// Boxed: the RPC error is several hundred bytes and would bloat every `Result`
+    #[cfg(feature = "rpc")]
+    #[error("rpc request failed: {0}")]
+    Rpc(Box<solana_client::client_error::ClientError>),
+
+    #[cfg(feature = "rpc")]
+    #[error("transaction simulation failed: {0}")]
+    Simulation(String),
+
+    #[error("transaction {signature} failed: {error}")]
+    TransactionFailed {
+        signature: solana_sdk::signature::Signature,
+        error: solana_sdk::transaction::TransactionError,
+    },
+
+    #[error("transaction blockhash expired before confirmation, {attempts} times")]
+    BlockhashExpired { attempts: u32 },
+}
+
+#[cfg(feature = "rpc")]
+impl From<solana_client::client_error::ClientError> for ClientError {
+    fn from(err: solana_client::client_error::ClientError) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
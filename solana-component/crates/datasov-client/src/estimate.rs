@@ -0,0 +1,127 @@
+//! Up-front cost of an operation, for wallets to show before asking the user to sign.
+//!
+//! Rent is what the signer locks into accounts the operation creates, including the audit log
+//! and page a new audit entry may open. Token amounts follow the marketplace's own split: a coupon
+//! discount first, then the fee, with the remainder going to the seller or into escrow. Network
+//! fees are not included.
+
+use datasov_audit::AuditPage;
+use datasov_constants::sizes;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::rent::Rent;
+
+/// An operation to estimate, with the on-chain state its cost depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    RegisterIdentity,
+    /// `permission_exists` when the consumer already holds a permission, which is then updated in
+    /// place; `audit_entry_count` is the identity's current audit log length
+    GrantAccess {
+        permission_exists: bool,
+        audit_entry_count: u64,
+    },
+    /// `private` listings also create their buyer allowlist
+    CreateListing {
+        private: bool,
+    },
+    /// `escrow` when the marketplace has a refund window and the listing is not a union listing;
+    /// `audit_entry_count` is the seller identity's current audit log length
+    Purchase {
+        price: u64,
+        fee_basis_points: u16,
+        discount_basis_points: Option<u16>,
+        escrow: bool,
+        audit_entry_count: u64,
+    },
+}
+
+/// Lamports and settlement-token amounts an operation moves
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Estimate {
+    /// Rent-exempt lamports for the accounts the operation creates, paid by the signer
+    pub rent_lamports: u64,
+    /// Tokens the buyer pays, after any coupon discount
+    pub amount: u64,
+    /// Tokens the marketplace takes as its fee
+    pub marketplace_fee: u64,
+    /// Tokens paid straight to the seller
+    pub seller_amount: u64,
+    /// Tokens held in escrow until the refund window closes
+    pub escrowed_amount: u64,
+}
+
+/// Estimate `operation` under `rent`, the cluster's rent sysvar or `Rent::default()`
+pub fn estimate(operation: &Operation, rent: &Rent) -> Estimate {
+    match *operation {
+        Operation::RegisterIdentity => Estimate {
+            rent_lamports: rent.minimum_balance(sizes::identity::IDENTITY),
+            ..Estimate::default()
+        },
+        Operation::GrantAccess {
+            permission_exists,
+            audit_entry_count,
+        } => {
+            let permission = match permission_exists {
+                true => 0,
+                false => rent.minimum_balance(sizes::identity::PERMISSION),
+            };
+            Estimate {
+                rent_lamports: permission + audit_rent(rent, audit_entry_count),
+                ..Estimate::default()
+            }
+        }
+        Operation::CreateListing { private } => {
+            let allowlist = match private {
+                true => rent.minimum_balance(sizes::marketplace::LISTING_ALLOWLIST),
+                false => 0,
+            };
+            Estimate {
+                rent_lamports: rent.minimum_balance(sizes::marketplace::LISTING) + allowlist,
+                ..Estimate::default()
+            }
+        }
+        Operation::Purchase {
+            price,
+            fee_basis_points,
+            discount_basis_points,
+            escrow,
+            audit_entry_count,
+        } => {
+            let amount = price - basis_points(price, discount_basis_points.unwrap_or(0));
+            let marketplace_fee = basis_points(amount, fee_basis_points);
+            let owner_amount = amount - marketplace_fee;
+            let escrow_rent = match escrow {
+                true => rent.minimum_balance(spl_token::state::Account::LEN),
+                false => 0,
+            };
+            Estimate {
+                rent_lamports: rent.minimum_balance(sizes::marketplace::RECEIPT)
+                    + escrow_rent
+                    + audit_rent(rent, audit_entry_count),
+                amount,
+                marketplace_fee,
+                seller_amount: if escrow { 0 } else { owner_amount },
+                escrowed_amount: if escrow { owner_amount } else { 0 },
+            }
+        }
+    }
+}
+
+/// Rent for appending to an audit log of `entry_count` entries: the log itself on the first entry,
+/// and a new page whenever the last one is full
+pub fn audit_rent(rent: &Rent, entry_count: u64) -> u64 {
+    let log = match entry_count {
+        0 => rent.minimum_balance(sizes::audit::AUDIT_LOG),
+        _ => 0,
+    };
+    let page = match entry_count % AuditPage::MAX_ENTRIES as u64 {
+        0 => rent.minimum_balance(sizes::audit::AUDIT_PAGE),
+        _ => 0,
+    };
+    log + page
+}
+
+/// `amount * basis_points / 10000`, rounded down as the marketplace does
+fn basis_points(amount: u64, basis_points: u16) -> u64 {
+    (amount as u128 * basis_points.min(10000) as u128 / 10000) as u64
+}
@@ -0,0 +1,109 @@
+//! Devnet onboarding: a demo payment token and a one-transaction drip of SOL, demo tokens and a
+//! verified identity to a new user.
+//!
+//! The faucet key is the demo mint's authority and a registered, active KYC oracle, so it can
+//! verify the identity it registers for the user in the same transaction. The user signs too, as
+//! the identity's owner. Demo identities have no documents behind them and record
+//! [`storage::placeholder`] pointers. Listings are bought with the demo token like any other SPL
+//! mint; on a marketplace restricted to approved mints, its authority has to approve the demo mint
+//! first.
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{instructions, pda, storage};
+
+/// Decimals of the demo payment token
+pub const DEMO_DECIMALS: u8 = 6;
+
+/// Demo tokens dripped by default, in base units (1,000 tokens)
+pub const DEFAULT_DRIP_TOKENS: u64 = 1_000 * 10u64.pow(DEMO_DECIMALS as u32);
+
+/// Lamports dripped by default, enough for fees and the rent of a few accounts
+pub const DEFAULT_DRIP_LAMPORTS: u64 = 100_000_000;
+
+/// Bytes of the demo mint account
+pub const MINT_SPACE: usize = spl_token::state::Mint::LEN;
+
+/// What one drip hands a new user
+pub struct Drip {
+    pub user: Pubkey,
+    /// Identity registered for the user and verified by the faucet
+    pub identity_id: String,
+    pub verification_level: datasov_identity::VerificationLevel,
+    pub tokens: u64,
+    pub lamports: u64,
+}
+
+impl Drip {
+    /// The default drip to `user`, with a basic-level identity
+    pub fn new(user: Pubkey, identity_id: impl Into<String>) -> Self {
+        Self {
+            user,
+            identity_id: identity_id.into(),
+            verification_level: datasov_identity::VerificationLevel::Basic,
+            tokens: DEFAULT_DRIP_TOKENS,
+            lamports: DEFAULT_DRIP_LAMPORTS,
+        }
+    }
+}
+
+/// Create the demo mint at `mint` (which signs) with `faucet` as its authority and payer.
+/// `rent_lamports` is the rent exemption for [`MINT_SPACE`] bytes.
+pub fn create_demo_mint(faucet: &Pubkey, mint: &Pubkey, rent_lamports: u64) -> Vec<Instruction> {
+    vec![
+        system_instruction::create_account(faucet, mint, rent_lamports, MINT_SPACE as u64, &spl_token::ID),
+        spl_token::instruction::initialize_mint2(&spl_token::ID, mint, faucet, None, DEMO_DECIMALS)
+            .expect("token program ID is spl_token"),
+    ]
+}
+
+/// Fund `drip.user` with SOL and demo tokens, then register and verify their identity.
+/// Signed by `faucet`, which pays the fee, and by the user.
+pub fn drip(faucet: &Pubkey, mint: &Pubkey, drip: &Drip) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    if drip.lamports > 0 {
+        instructions.push(system_instruction::transfer(faucet, &drip.user, drip.lamports));
+    }
+    if drip.tokens > 0 {
+        instructions.push(create_associated_token_account_idempotent(
+            faucet,
+            &drip.user,
+            mint,
+            &spl_token::ID,
+        ));
+        instructions.push(
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                mint,
+                &get_associated_token_address(&drip.user, mint),
+                faucet,
+                &[],
+                drip.tokens,
+            )
+            .expect("token program ID is spl_token"),
+        );
+    }
+    instructions.push(instructions::register_identity(
+        &drip.user,
+        &drip.identity_id,
+        storage::placeholder(&drip.identity_id),
+    ));
+    instructions.push(instructions::identity(
+        datasov_identity::accounts::VerifyIdentity {
+            identity: pda::identity::identity(&drip.identity_id),
+            oracle: pda::identity::oracle(faucet),
+            oracle_registry: pda::identity::oracle_registry(),
+            oracle_authority: *faucet,
+        },
+        datasov_identity::instruction::VerifyIdentity {
+            verification_level: drip.verification_level.clone(),
+            kyc_pointer: storage::placeholder(&format!("{}-kyc", drip.identity_id)),
+        },
+    ));
+    instructions
+}
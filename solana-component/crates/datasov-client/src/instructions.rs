@@ -0,0 +1,894 @@
+//! Instruction builders.
+//!
+//! Every program instruction can be built with [`identity`], [`marketplace`], [`audit`],
+//! [`governance`], [`timelock`] or [`staking`] from the Anchor-generated account and argument structs, e.g.
+//! `identity(accounts::RegisterIdentity { .. }, instruction::RegisterIdentity { .. })`, so account
+//! lists and argument types are checked at compile time. The functions below fill in the PDAs for
+//! the most common identity flows.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use datasov_identity::{DataType, PermissionType, Purpose, StoragePointer};
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+use spl_associated_token_account::{get_associated_token_address, get_associated_token_address_with_program_id};
+
+use crate::pda;
+
+/// Build an instruction for `program_id` from Anchor client account and argument structs
+pub fn build<A: ToAccountMetas, D: InstructionData>(program_id: Pubkey, accounts: A, args: D) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// Build a `datasov_identity` instruction
+pub fn identity<A: ToAccountMetas, D: InstructionData>(accounts: A, args: D) -> Instruction {
+    build(datasov_identity::ID, accounts, args)
+}
+
+/// Build a `datasov_solana` marketplace instruction
+pub fn marketplace<A: ToAccountMetas, D: InstructionData>(accounts: A, args: D) -> Instruction {
+    build(datasov_solana::ID, accounts, args)
+}
+
+/// Build a `datasov_audit` instruction
+pub fn audit<A: ToAccountMetas, D: InstructionData>(accounts: A, args: D) -> Instruction {
+    build(datasov_audit::ID, accounts, args)
+}
+
+/// Build a `datasov_governance` instruction
+pub fn governance<A: ToAccountMetas, D: InstructionData>(accounts: A, args: D) -> Instruction {
+    build(datasov_governance::ID, accounts, args)
+}
+
+/// Build a `datasov_timelock` instruction
+pub fn timelock<A: ToAccountMetas, D: InstructionData>(accounts: A, args: D) -> Instruction {
+    build(datasov_timelock::ID, accounts, args)
+}
+
+/// Build a `datasov_staking` instruction
+pub fn staking<A: ToAccountMetas, D: InstructionData>(accounts: A, args: D) -> Instruction {
+    build(datasov_staking::ID, accounts, args)
+}
+
+/// Audit accounts for an identity-program instruction logging against `subject`.
+/// `entry_count` is the subject's current audit log length (zero before its first entry).
+pub fn identity_audit_accounts(subject: &Pubkey, entry_count: u64) -> datasov_identity::accounts::AuditAccounts {
+    let audit_log = pda::audit::audit_log(subject);
+    datasov_identity::accounts::AuditAccounts {
+        audit_log,
+        audit_page: pda::audit::next_audit_page(&audit_log, entry_count),
+        audit_writer: pda::identity::audit_writer(),
+        audit_program: datasov_audit::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// Audit accounts for a marketplace instruction logging against `subject`
+pub fn marketplace_audit_accounts(subject: &Pubkey, entry_count: u64) -> datasov_solana::accounts::AuditAccounts {
+    let audit_log = pda::audit::audit_log(subject);
+    datasov_solana::accounts::AuditAccounts {
+        audit_log,
+        audit_page: pda::audit::next_audit_page(&audit_log, entry_count),
+        audit_writer: pda::marketplace::audit_writer(),
+        audit_program: datasov_audit::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// Register a new identity owned by `owner`, its document at `storage_pointer`
+pub fn register_identity(owner: &Pubkey, identity_id: &str, storage_pointer: StoragePointer) -> Instruction {
+    identity(
+        datasov_identity::accounts::RegisterIdentity {
+            identity: pda::identity::identity(identity_id),
+            owner: *owner,
+            oracle_registry: pda::identity::oracle_registry(),
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::RegisterIdentity {
+            identity_id: identity_id.to_string(),
+            storage_pointer,
+        },
+    )
+}
+
+/// Terms for [`grant_access`]
+pub struct GrantAccessRequest {
+    pub owner: Pubkey,
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub permission_type: PermissionType,
+    pub data_types: Vec<DataType>,
+    pub expires_at: Option<i64>,
+    /// Signed grant terms
+    pub permission_pointer: StoragePointer,
+    /// Whether the consumer has a stake account to present
+    pub consumer_staked: bool,
+    /// Guardian co-signer, for ward identities
+    pub guardian: Option<Pubkey>,
+    /// Current length of the identity's audit log
+    pub audit_entry_count: u64,
+}
+
+/// Grant a consumer access as the identity owner
+pub fn grant_access(request: GrantAccessRequest) -> Instruction {
+    let identity_account = pda::identity::identity(&request.identity_id);
+    identity(
+        datasov_identity::accounts::GrantAccess {
+            permission: pda::identity::permission(&identity_account, &request.consumer),
+            identity: identity_account,
+            blacklist: pda::identity::blacklist(),
+            consumer_profile: pda::identity::consumer_profile(&request.consumer),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            consumer_stake: request
+                .consumer_staked
+                .then(|| pda::identity::consumer_stake(&request.consumer)),
+            consumer: request.consumer,
+            data_trust: None,
+            trust_membership: None,
+            owner: request.owner,
+            guardian: request.guardian,
+            audit: identity_audit_accounts(&identity_account, request.audit_entry_count),
+            oracle_registry: pda::identity::oracle_registry(),
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::GrantAccess {
+            permission_type: request.permission_type,
+            data_types: request.data_types,
+            expires_at: request.expires_at,
+            permission_pointer: request.permission_pointer,
+        },
+    )
+}
+
+/// Revoke a consumer's access as the identity owner
+pub fn revoke_access(
+    owner: &Pubkey,
+    identity_id: &str,
+    consumer: &Pubkey,
+    revocation_pointer: StoragePointer,
+    audit_entry_count: u64,
+) -> Instruction {
+    let identity_account = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::RevokeAccess {
+            permission: pda::identity::permission(&identity_account, consumer),
+            identity: identity_account,
+            consumer_profile: pda::identity::consumer_profile(consumer),
+            owner: *owner,
+            audit: identity_audit_accounts(&identity_account, audit_entry_count),
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::RevokeAccess { revocation_pointer },
+    )
+}
+
+/// Offer `price` lamports, escrowed from the consumer, for a grant of `data_types` on identity
+/// `identity_id` lasting `duration` seconds; the owner may accept until `expires_at`
+pub fn request_access(
+    consumer: &Pubkey,
+    identity_id: &str,
+    permission_type: PermissionType,
+    data_types: Vec<DataType>,
+    duration: i64,
+    price: u64,
+    expires_at: i64,
+) -> Instruction {
+    let identity_account = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::RequestAccess {
+            access_request: pda::identity::access_request(&identity_account, consumer),
+            identity: identity_account,
+            consumer: *consumer,
+            oracle_registry: pda::identity::oracle_registry(),
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::RequestAccess {
+            permission_type,
+            data_types,
+            duration,
+            price,
+            expires_at,
+        },
+    )
+}
+
+/// Accept `consumer`'s access request as the identity owner, granting it and collecting the price
+pub fn accept_access_request(
+    owner: &Pubkey,
+    identity_id: &str,
+    consumer: &Pubkey,
+    permission_pointer: StoragePointer,
+    consumer_staked: bool,
+    audit_entry_count: u64,
+) -> Instruction {
+    let identity_account = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::AcceptAccessRequest {
+            permission: pda::identity::permission(&identity_account, consumer),
+            access_request: pda::identity::access_request(&identity_account, consumer),
+            identity: identity_account,
+            blacklist: pda::identity::blacklist(),
+            consumer_profile: pda::identity::consumer_profile(consumer),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            consumer_stake: consumer_staked.then(|| pda::identity::consumer_stake(consumer)),
+            consumer: *consumer,
+            owner: *owner,
+            guardian: None,
+            audit: identity_audit_accounts(&identity_account, audit_entry_count),
+            oracle_registry: pda::identity::oracle_registry(),
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::AcceptAccessRequest { permission_pointer },
+    )
+}
+
+/// Withdraw an unaccepted access request on identity `identity_id`, refunding its price
+pub fn cancel_access_request(consumer: &Pubkey, identity_id: &str) -> Instruction {
+    let identity_account = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::CancelAccessRequest {
+            access_request: pda::identity::access_request(&identity_account, consumer),
+            consumer: *consumer,
+        },
+        datasov_identity::instruction::CancelAccessRequest {},
+    )
+}
+
+/// Close `consumer`'s long-expired grant on identity `identity_id` for its rent, refunding
+/// `rent_payer` and rewarding `caller`
+pub fn reclaim_permission_rent(
+    caller: &Pubkey,
+    identity_id: &str,
+    consumer: &Pubkey,
+    rent_payer: &Pubkey,
+) -> Instruction {
+    let identity_account = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::ReclaimPermissionRent {
+            permission: pda::identity::permission(&identity_account, consumer),
+            identity: identity_account,
+            rent_payer: *rent_payer,
+            caller: *caller,
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::ReclaimPermissionRent {},
+    )
+}
+
+/// Publish (or clear) an identity's X25519 encryption key, as its owner
+pub fn set_identity_encryption_key(owner: &Pubkey, identity_id: &str, encryption_key: Option<[u8; 32]>) -> Instruction {
+    identity(
+        datasov_identity::accounts::UpdateIdentity {
+            identity: pda::identity::identity(identity_id),
+            owner: *owner,
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::SetIdentityEncryptionKey { encryption_key },
+    )
+}
+
+/// Publish (or clear) a consumer's X25519 encryption key on its profile
+pub fn set_consumer_encryption_key(consumer: &Pubkey, encryption_key: Option<[u8; 32]>) -> Instruction {
+    identity(
+        datasov_identity::accounts::UpdateConsumerProfile {
+            consumer_profile: pda::identity::consumer_profile(consumer),
+            consumer: *consumer,
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::SetConsumerEncryptionKey { encryption_key },
+    )
+}
+
+/// Validate (and meter) a consumer's access to one data type
+pub fn validate_access(
+    consumer: &Pubkey,
+    identity_id: &str,
+    data_type: DataType,
+    epsilon_cost: u32,
+    consumer_staked: bool,
+    audit_entry_count: u64,
+) -> Instruction {
+    let identity_account = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::ValidateAccess {
+            permission: pda::identity::permission(&identity_account, consumer),
+            identity: identity_account,
+            blacklist: pda::identity::blacklist(),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            consumer_stake: consumer_staked.then(|| pda::identity::consumer_stake(consumer)),
+            consumer: *consumer,
+            audit: identity_audit_accounts(&identity_account, audit_entry_count),
+        },
+        datasov_identity::instruction::ValidateAccess {
+            data_type,
+            epsilon_cost,
+        },
+    )
+}
+
+/// One grant for [`validate_access_batch`] to check
+pub struct AccessCheck {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub data_type: DataType,
+}
+
+/// Check `checks` without metering them. The return data is a little-endian `u64` whose bit `i`
+/// is set when `checks[i]` passes. `staked_consumers` are the consumers with a stake account to
+/// present.
+pub fn validate_access_batch(checks: &[AccessCheck], staked_consumers: &[Pubkey]) -> Instruction {
+    let mut instruction = identity(
+        datasov_identity::accounts::ValidateAccessBatch {
+            blacklist: pda::identity::blacklist(),
+            compliance_matrix: pda::identity::compliance_matrix(),
+        },
+        datasov_identity::instruction::ValidateAccessBatch {
+            data_types: checks.iter().map(|check| check.data_type.clone()).collect(),
+        },
+    );
+    for check in checks {
+        let identity_account = pda::identity::identity(&check.identity_id);
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(pda::identity::permission(&identity_account, &check.consumer), false),
+            AccountMeta::new_readonly(identity_account, false),
+        ]);
+    }
+    let stakes = staked_consumers
+        .iter()
+        .map(|consumer| AccountMeta::new_readonly(pda::identity::consumer_stake(consumer), false));
+    instruction.accounts.extend(stakes);
+    instruction
+}
+
+/// Terms for [`purchase_data`]
+pub struct PurchaseRequest {
+    pub buyer: Pubkey,
+    /// Identity the access is granted to; must be owned by `buyer`
+    pub buyer_identity_id: String,
+    pub listing_id: u64,
+    /// `DataListing::payout_wallet`, whose associated token account receives the proceeds
+    pub payout_wallet: Pubkey,
+    /// `DataListing::identity_id`
+    pub seller_identity_id: String,
+    /// `DataListing::union`
+    pub data_union: Option<Pubkey>,
+    /// `DataListing::data_type`, whose price candle records the sale
+    pub data_type: datasov_solana::DataType,
+    pub purpose: Purpose,
+    /// Settlement token mint; payment moves between the parties' associated token accounts
+    pub mint: Pubkey,
+    /// Whether the marketplace escrows proceeds (its refund window is non-zero)
+    pub escrowed: bool,
+    /// Whether the buyer has a stake account to present
+    pub buyer_staked: bool,
+    /// Whether the buyer has staked protocol tokens, whose weight may earn a fee discount
+    pub buyer_weighted: bool,
+    /// Rebate ledgers opened for the current fee rebate epoch, to credit with the fee
+    pub rebate: Option<RebateLedgers>,
+    /// Oldest attested refresh, in seconds before now, the buyer accepts
+    pub max_staleness: Option<i64>,
+    /// UTC day the purchase settles on, `PriceCandle::day_of` the cluster clock
+    pub day: i64,
+    /// Current length of the seller identity's audit log
+    pub audit_entry_count: u64,
+}
+
+/// Which sides of a purchase hold a rebate ledger for `epoch`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebateLedgers {
+    pub epoch: u64,
+    pub buyer: bool,
+    /// Ledger of the listing's payout wallet
+    pub seller: bool,
+}
+
+/// Purchase a public listing with the buyer's own token account
+pub fn purchase_data(request: PurchaseRequest) -> Instruction {
+    purchase(request, None)
+}
+
+/// Purchase a private listing with the buyer's own token account, opening its price commitment
+/// with `price_reveal`; the buyer must be on the listing's allowlist
+pub fn purchase_private_data(request: PurchaseRequest, price_reveal: datasov_solana::PriceReveal) -> Instruction {
+    purchase(request, Some(price_reveal))
+}
+
+fn purchase(request: PurchaseRequest, price_reveal: Option<datasov_solana::PriceReveal>) -> Instruction {
+    let listing = pda::marketplace::listing(request.listing_id);
+    let marketplace_address = pda::marketplace::marketplace();
+    let seller_identity = pda::identity::identity(&request.seller_identity_id);
+    marketplace(
+        datasov_solana::accounts::PurchaseData {
+            listing,
+            marketplace: marketplace_address,
+            seller_identity,
+            buyer_identity: pda::identity::identity(&request.buyer_identity_id),
+            buyer_permission: pda::identity::permission(&seller_identity, &request.buyer),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            blacklist: pda::identity::blacklist(),
+            buyer_profile: pda::identity::consumer_profile(&request.buyer),
+            buyer_stake: request
+                .buyer_staked
+                .then(|| pda::identity::consumer_stake(&request.buyer)),
+            allowlist: price_reveal
+                .is_some()
+                .then(|| pda::marketplace::allowlist(&listing)),
+            coupon: None,
+            purchase_delegate: None,
+            buyer: request.buyer,
+            buyer_token_account: get_associated_token_address(&request.buyer, &request.mint),
+            owner_token_account: get_associated_token_address(&request.payout_wallet, &request.mint),
+            marketplace_token_account: get_associated_token_address(&marketplace_address, &request.mint),
+            data_union: request.data_union,
+            settlement_mint: Some(request.mint),
+            escrow_token_account: request.escrowed.then(|| pda::marketplace::escrow(&listing)),
+            receipt: pda::marketplace::receipt(&listing),
+            buyer_weight: request
+                .buyer_weighted
+                .then(|| pda::staking::stake_weight(&request.buyer)),
+            staking_config: request.buyer_weighted.then(pda::staking::config),
+            rebate_epoch: request
+                .rebate
+                .map(|rebate| pda::marketplace::rebate_epoch(rebate.epoch)),
+            buyer_rebate: request
+                .rebate
+                .filter(|rebate| rebate.buyer)
+                .map(|rebate| pda::marketplace::rebate_ledger(rebate.epoch, &request.buyer)),
+            seller_rebate: request
+                .rebate
+                .filter(|rebate| rebate.seller && request.payout_wallet != request.buyer)
+                .map(|rebate| pda::marketplace::rebate_ledger(rebate.epoch, &request.payout_wallet)),
+            price_candle: pda::marketplace::price_candle(&request.mint, &request.data_type, request.day),
+            earnings_ledger: pda::marketplace::earnings_ledger(&seller_identity),
+            identity_program: datasov_identity::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            audit: marketplace_audit_accounts(&seller_identity, request.audit_entry_count),
+        },
+        datasov_solana::instruction::PurchaseData {
+            listing_id: request.listing_id,
+            purpose: request.purpose,
+            price_reveal,
+            coupon_code: None,
+            max_staleness: request.max_staleness,
+        },
+    )
+}
+
+/// Close settled listing `listing_id` and its purchase receipt for their rent, refunding
+/// `rent_payer` and `receipt_rent_payer` and rewarding `caller`. `permission` is the grant the
+/// receipt is bound to.
+pub fn reclaim_listing_rent(
+    caller: &Pubkey,
+    listing_id: u64,
+    permission: &Pubkey,
+    rent_payer: &Pubkey,
+    receipt_rent_payer: &Pubkey,
+) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    marketplace(
+        datasov_solana::accounts::ReclaimListingRent {
+            listing,
+            receipt: pda::marketplace::receipt(&listing),
+            permission: *permission,
+            rent_payer: *rent_payer,
+            receipt_rent_payer: *receipt_rent_payer,
+            caller: *caller,
+            marketplace: pda::marketplace::marketplace(),
+        },
+        datasov_solana::instruction::ReclaimListingRent {
+            _listing_id: listing_id,
+        },
+    )
+}
+
+/// Queue `instruction` on the timelock as operation `operation_id` (the timelock's current
+/// operation count). Any signer of `instruction` other than the timelock PDA will be missing when
+/// it executes.
+pub fn queue_operation(admin: &Pubkey, operation_id: u64, instruction: &Instruction) -> Instruction {
+    timelock(
+        datasov_timelock::accounts::QueueOperation {
+            timelock: pda::timelock::timelock(),
+            operation: pda::timelock::operation(operation_id),
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        datasov_timelock::instruction::QueueOperation {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| datasov_timelock::OperationAccount {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        },
+    )
+}
+
+/// Execute queued operation `operation_id`, whose queued instruction is `instruction`
+pub fn execute_operation(operation_id: u64, instruction: &Instruction) -> Instruction {
+    let timelock_address = pda::timelock::timelock();
+    let mut execute = timelock(
+        datasov_timelock::accounts::ExecuteOperation {
+            timelock: timelock_address,
+            operation: pda::timelock::operation(operation_id),
+        },
+        datasov_timelock::instruction::ExecuteOperation {},
+    );
+    // The timelock PDA signs inside the program, not in the transaction
+    let queued = instruction.accounts.iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != timelock_address,
+        ..meta.clone()
+    });
+    let program = AccountMeta::new_readonly(instruction.program_id, false);
+    execute.accounts.extend(queued.chain([program]));
+    execute
+}
+
+/// The instruction queued as `operation`, for [`execute_operation`]
+pub fn operation_instruction(operation: &datasov_timelock::Operation) -> Instruction {
+    Instruction {
+        program_id: operation.program_id,
+        accounts: operation
+            .accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: operation.data.clone(),
+    }
+}
+
+/// Upgrade `program` from `buffer` with the timelock as upgrade authority, for [`queue_operation`].
+/// The buffer's authority must be the timelock too, so it cannot change while the upgrade waits.
+pub fn upgrade_program(program: &Pubkey, buffer: &Pubkey, spill: &Pubkey) -> Instruction {
+    bpf_loader_upgradeable::upgrade(program, buffer, &pda::timelock::timelock(), spill)
+}
+
+/// Stake `amount` protocol tokens of `mint` from the staker's associated token account behind
+/// `subject` in `role`
+pub fn stake(
+    staker: &Pubkey,
+    mint: &Pubkey,
+    role: datasov_staking::StakeRole,
+    subject: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let backing = pda::staking::backing(role, subject);
+    staking(
+        datasov_staking::accounts::Stake {
+            config: pda::staking::config(),
+            backing,
+            position: pda::staking::position(&backing, staker),
+            weight: pda::staking::stake_weight(staker),
+            vault: pda::staking::vault(),
+            staker_token_account: get_associated_token_address(staker, mint),
+            staker: *staker,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        datasov_staking::instruction::Stake {
+            role,
+            subject: *subject,
+            amount,
+        },
+    )
+}
+
+/// Lock protocol tokens of `mint` from the funder's associated token account on `schedule` for
+/// `beneficiary`
+pub fn create_vesting(
+    funder: &Pubkey,
+    beneficiary: &Pubkey,
+    mint: &Pubkey,
+    schedule: datasov_staking::instruction::CreateVesting,
+) -> Instruction {
+    let vesting = pda::staking::vesting(beneficiary, schedule.vesting_id);
+    staking(
+        datasov_staking::accounts::CreateVesting {
+            config: pda::staking::config(),
+            vesting,
+            vault: pda::staking::vesting_vault(&vesting),
+            mint: *mint,
+            funder_token_account: get_associated_token_address(funder, mint),
+            beneficiary: *beneficiary,
+            funder: *funder,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        schedule,
+    )
+}
+
+/// Claim what has vested to the beneficiary's associated token account for `mint`
+pub fn claim_vested(beneficiary: &Pubkey, mint: &Pubkey, vesting_id: u64) -> Instruction {
+    let vesting = pda::staking::vesting(beneficiary, vesting_id);
+    staking(
+        datasov_staking::accounts::ClaimVested {
+            config: pda::staking::config(),
+            vesting,
+            vault: pda::staking::vesting_vault(&vesting),
+            beneficiary_token_account: get_associated_token_address(beneficiary, mint),
+            beneficiary: *beneficiary,
+            token_program: spl_token::ID,
+        },
+        datasov_staking::instruction::ClaimVested {},
+    )
+}
+
+/// Claim allocation `index` of an airdrop to the claimant's associated token account for `mint`,
+/// with the proof from [`crate::airdrop::AirdropTree::proof`]
+pub fn claim_airdrop(
+    claimant: &Pubkey,
+    mint: &Pubkey,
+    airdrop_id: u64,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let airdrop = pda::staking::airdrop(airdrop_id);
+    staking(
+        datasov_staking::accounts::ClaimAirdrop {
+            config: pda::staking::config(),
+            airdrop,
+            bitmap: pda::staking::airdrop_bitmap(&airdrop, index),
+            vault: pda::staking::airdrop_vault(&airdrop),
+            claimant_token_account: get_associated_token_address(claimant, mint),
+            claimant: *claimant,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        datasov_staking::instruction::ClaimAirdrop { index, amount, proof },
+    )
+}
+
+/// Run the buyback through `route`, a swap on the configured DEX from the buyback input vault to
+/// its output vault with [`pda::marketplace::buyback`] as the swapping authority
+pub fn execute_buyback(fee_mint: &Pubkey, protocol_mint: &Pubkey, route: &Instruction, quoted_out: u64) -> Instruction {
+    let buyback_address = pda::marketplace::buyback();
+    let marketplace_address = pda::marketplace::marketplace();
+    let mut execute = marketplace(
+        datasov_solana::accounts::ExecuteBuyback {
+            buyback_config: buyback_address,
+            marketplace: marketplace_address,
+            marketplace_token_account: get_associated_token_address(&marketplace_address, fee_mint),
+            fee_mint: *fee_mint,
+            protocol_mint: *protocol_mint,
+            input_vault: pda::marketplace::buyback_input(),
+            output_vault: pda::marketplace::buyback_output(),
+            dex_program: route.program_id,
+            token_program: spl_token::ID,
+        },
+        datasov_solana::instruction::ExecuteBuyback {
+            route_data: route.data.clone(),
+            quoted_out,
+        },
+    );
+    // The buyback PDA signs inside the program, not in the transaction
+    execute.accounts.extend(route.accounts.iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != buyback_address,
+        ..meta.clone()
+    }));
+    execute
+}
+
+/// Vote listing `listing_id` into the featured set of curation `epoch`, bonding from the curator's
+/// associated token account for the protocol token `mint`
+pub fn vote_featured(curator: &Pubkey, mint: &Pubkey, listing_id: u64, epoch: u64) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    let tally = pda::marketplace::listing_tally(epoch, &listing);
+    marketplace(
+        datasov_solana::accounts::VoteFeatured {
+            curation_config: pda::marketplace::curation(),
+            listing,
+            curator_weight: pda::staking::stake_weight(curator),
+            featured_set: pda::marketplace::featured_set(epoch),
+            tally,
+            vote: pda::marketplace::curation_vote(&tally, curator),
+            vault: pda::marketplace::curation_vault(),
+            curator_token_account: get_associated_token_address(curator, mint),
+            curator: *curator,
+            marketplace: pda::marketplace::marketplace(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::VoteFeatured { epoch },
+    )
+}
+
+/// Settle `curator`'s vote on `listing` in curation `epoch` once its dispute window has closed;
+/// both the curator's and the marketplace's associated token accounts for `mint` must exist
+pub fn settle_curation_vote(curator: &Pubkey, mint: &Pubkey, listing: &Pubkey, epoch: u64) -> Instruction {
+    let marketplace_address = pda::marketplace::marketplace();
+    let tally = pda::marketplace::listing_tally(epoch, listing);
+    marketplace(
+        datasov_solana::accounts::SettleCurationVote {
+            curation_config: pda::marketplace::curation(),
+            tally,
+            vote: pda::marketplace::curation_vote(&tally, curator),
+            marketplace: marketplace_address,
+            vault: pda::marketplace::curation_vault(),
+            curator_token_account: get_associated_token_address(curator, mint),
+            marketplace_token_account: get_associated_token_address(&marketplace_address, mint),
+            curator: *curator,
+            token_program: spl_token::ID,
+        },
+        datasov_solana::instruction::SettleCurationVote {},
+    )
+}
+
+/// Manifest chunks that fit one transaction alongside its accounts
+pub const MANIFEST_CHUNKS_PER_INSTRUCTION: usize = 12;
+
+/// Record `chunks` as the manifest of listing `listing_id` and seal it: a create, as many appends
+/// as needed, then the seal, each to be sent in its own transaction, in order
+pub fn data_manifest(owner: &Pubkey, listing_id: u64, chunks: &[datasov_solana::ManifestChunk]) -> Vec<Instruction> {
+    let listing = pda::marketplace::listing(listing_id);
+    let manifest = pda::marketplace::data_manifest(&listing);
+    let update = || datasov_solana::accounts::UpdateDataManifest {
+        listing,
+        manifest,
+        owner: *owner,
+        marketplace: pda::marketplace::marketplace(),
+    };
+    let mut batches = chunks.chunks(MANIFEST_CHUNKS_PER_INSTRUCTION);
+    let mut instructions = vec![marketplace(
+        datasov_solana::accounts::CreateDataManifest {
+            listing,
+            manifest,
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::CreateDataManifest {
+            chunks: batches.next().unwrap_or_default().to_vec(),
+        },
+    )];
+    instructions.extend(batches.map(|batch| {
+        marketplace(
+            update(),
+            datasov_solana::instruction::AppendManifestChunks { chunks: batch.to_vec() },
+        )
+    }));
+    instructions.push(marketplace(update(), datasov_solana::instruction::SealDataManifest {}));
+    instructions
+}
+
+/// Report, as storage oracle `oracle`, whether listing `listing_id`'s data still resolves and
+/// matches its committed hash
+pub fn record_storage_check(oracle: &Pubkey, listing_id: u64, healthy: bool) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    marketplace(
+        datasov_solana::accounts::RecordStorageCheck {
+            listing,
+            storage_oracle: pda::marketplace::storage_oracle(oracle),
+            storage_health: pda::marketplace::storage_health(&listing),
+            oracle: *oracle,
+            marketplace: pda::marketplace::marketplace(),
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::RecordStorageCheck { healthy },
+    )
+}
+
+/// Mint `buyer` the license NFT of their licensed purchase of listing `listing_id`, sold under
+/// license template `template_id`
+pub fn mint_license(buyer: &Pubkey, listing_id: u64, template_id: u32) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    let receipt = pda::marketplace::receipt(&listing);
+    let license_mint = pda::marketplace::license_mint(&receipt);
+    marketplace(
+        datasov_solana::accounts::MintLicense {
+            marketplace: pda::marketplace::marketplace(),
+            listing,
+            receipt,
+            license_template: pda::marketplace::license_template(template_id),
+            license_mint,
+            buyer_token_account: get_associated_token_address_with_program_id(
+                buyer,
+                &license_mint,
+                &spl_token_2022::ID,
+            ),
+            buyer: *buyer,
+            token_program: spl_token_2022::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        },
+        datasov_solana::instruction::MintLicense {
+            _listing_id: listing_id,
+        },
+    )
+}
+
+/// Hand `buyer`'s access right to listing `listing_id` to the owner of identity
+/// `recipient_identity_id`. `permission` is the one the receipt is bound to; pass the
+/// purchase's `license_template`, if it was licensed.
+pub fn transfer_access_right(
+    buyer: &Pubkey,
+    listing_id: u64,
+    seller_identity_id: &str,
+    permission: &Pubkey,
+    recipient_identity_id: &str,
+    recipient: &Pubkey,
+    license_template: Option<u32>,
+) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    let seller_identity = pda::identity::identity(seller_identity_id);
+    marketplace(
+        datasov_solana::accounts::TransferAccessRight {
+            listing,
+            receipt: pda::marketplace::receipt(&listing),
+            permission: *permission,
+            license_template: license_template.map(pda::marketplace::license_template),
+            seller_identity,
+            recipient_identity: pda::identity::identity(recipient_identity_id),
+            recipient_permission: pda::identity::permission(&seller_identity, recipient),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            blacklist: pda::identity::blacklist(),
+            buyer: *buyer,
+            marketplace: pda::marketplace::marketplace(),
+            identity_program: datasov_identity::ID,
+        },
+        datasov_solana::instruction::TransferAccessRight {
+            _listing_id: listing_id,
+        },
+    )
+}
+
+/// Freeze identity `identity_id` for `duration` seconds as compliance officer `officer`, who pays
+/// the freeze's rent
+pub fn freeze_identity(officer: &Pubkey, identity_id: &str, duration: i64, reason_hash: [u8; 32]) -> Instruction {
+    let identity_address = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::FreezeIdentity {
+            identity: identity_address,
+            freeze: pda::identity::identity_freeze(&identity_address),
+            compliance_officer: pda::identity::compliance_officer(officer),
+            officer: *officer,
+            oracle_registry: pda::identity::oracle_registry(),
+            system_program: system_program::ID,
+        },
+        datasov_identity::instruction::FreezeIdentity { duration, reason_hash },
+    )
+}
+
+/// Appeal the freeze on identity `identity_id` to governance, as its owner
+pub fn appeal_freeze(owner: &Pubkey, identity_id: &str, appeal_hash: [u8; 32]) -> Instruction {
+    let identity_address = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::AppealFreeze {
+            identity: identity_address,
+            freeze: pda::identity::identity_freeze(&identity_address),
+            owner: *owner,
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        datasov_identity::instruction::AppealFreeze { appeal_hash },
+    )
+}
+
+/// Lift the freeze `officer` placed on identity `identity_id`, as the registry authority, the
+/// officer, or anyone once it has expired
+pub fn lift_freeze(authority: &Pubkey, identity_id: &str, officer: &Pubkey) -> Instruction {
+    let identity_address = pda::identity::identity(identity_id);
+    identity(
+        datasov_identity::accounts::LiftFreeze {
+            identity: identity_address,
+            freeze: pda::identity::identity_freeze(&identity_address),
+            officer: *officer,
+            oracle_registry: pda::identity::oracle_registry(),
+            authority: *authority,
+        },
+        datasov_identity::instruction::LiftFreeze {},
+    )
+}
@@ -0,0 +1,59 @@
+//! Rust client SDK for the DataSov programs.
+//!
+//! - [`pda`] derives every program-derived address used by the identity, marketplace, audit,
+//!   governance, timelock and staking programs.
+//! - [`instructions`] builds typed instructions from the Anchor-generated `accounts` and `instruction`
+//!   structs of each program, with helpers for the common identity flows.
+//! - [`transaction`] assembles and signs legacy and versioned transactions.
+//! - [`lookup_table`] creates and extends the protocol's address lookup table of shared accounts.
+//! - [`admin`] builds the registry, marketplace and staking authorities' instructions for any
+//!   signer, and [`squads`] proposes them from a Squads multisig vault.
+//! - [`airdrop`] builds the Merkle tree of an airdrop's allocations and the proofs to claim them.
+//! - [`compute`] sizes compute budgets per instruction and picks priority fees.
+//! - [`encryption`] seals documents and hands their keys to counterparties over X25519.
+//! - [`signer`] signs with a Ledger (feature `ledger`) or an external signing service instead of a hot key.
+//! - [`faucet`] mints a devnet demo token and onboards new users with SOL, tokens and a verified identity.
+//! - [`estimate`] works out the rent and token amounts of an operation before it is signed.
+//! - [`snapshot`] proves identity state against the published state snapshot root.
+//! - [`parse`] reads program enums from kebab-case names, and [`storage`] reads and writes storage
+//!   pointers as `ar://`, `ipfs://`, `shdw://` and `fil://` URIs.
+//! - [`rpc`] (feature `rpc`, on by default) fetches and decodes program accounts over JSON-RPC, and
+//!   [`query`] builds the `getProgramAccounts` filters and pages for listing them.
+//! - [`subscribe`] (feature `rpc`) streams decoded account changes over the websocket, reconnecting
+//!   as needed.
+//!
+//! The program crates are re-exported, so their account structs, enums and events are the
+//! typed account layer for integrators.
+
+pub mod admin;
+pub mod airdrop;
+pub mod compute;
+pub mod encryption;
+pub mod error;
+pub mod estimate;
+pub mod faucet;
+pub mod instructions;
+pub mod lookup_table;
+pub mod parse;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod query;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod signer;
+pub mod snapshot;
+pub mod squads;
+pub mod storage;
+#[cfg(feature = "rpc")]
+pub mod subscribe;
+pub mod transaction;
+
+pub use datasov_audit as audit;
+pub use datasov_governance as governance;
+pub use datasov_identity as identity;
+pub use datasov_solana as marketplace;
+pub use datasov_staking as staking;
+pub use datasov_timelock as timelock;
+
+pub use error::{ClientError, Result};
+pub use transaction::TransactionBuilder;
@@ -0,0 +1,76 @@
+//! Address lookup tables for versioned transactions.
+//!
+//! A purchase references over twenty accounts, and a legacy transaction spends 32 bytes on each,
+//! so escrow, coupons or royalty splits push it past the 1232-byte packet limit. Most of those
+//! accounts are protocol globals (programs, registries, the marketplace and its token accounts)
+//! that every transaction shares. The protocol authority keeps them in a lookup table: [`create`]
+//! it, then [`extend`] it with [`protocol_addresses`]. Clients fetch the table
+//! (`DataSovRpc::fetch_lookup_table`) and add it with
+//! [`TransactionBuilder::lookup_table`](crate::TransactionBuilder::lookup_table), after which each
+//! shared account costs one byte.
+
+use solana_sdk::address_lookup_table::instruction as lookup_table;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::clock::Slot;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{compute_budget, system_program, sysvar};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::pda;
+
+/// Addresses per extend instruction, so an extend transaction stays under the packet limit
+pub const ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Accounts shared by the identity and marketplace instructions, plus each settlement mint and
+/// the marketplace's token account for it
+pub fn protocol_addresses(mints: &[Pubkey]) -> Vec<Pubkey> {
+    let marketplace = pda::marketplace::marketplace();
+    let mut addresses = vec![
+        datasov_identity::ID,
+        datasov_solana::ID,
+        datasov_audit::ID,
+        system_program::ID,
+        spl_token::ID,
+        spl_associated_token_account::ID,
+        compute_budget::ID,
+        sysvar::instructions::ID,
+        pda::identity::oracle_registry(),
+        pda::identity::compliance_matrix(),
+        pda::identity::blacklist(),
+        pda::identity::audit_writer(),
+        marketplace,
+        pda::marketplace::audit_writer(),
+    ];
+    for mint in mints {
+        addresses.push(*mint);
+        addresses.push(get_associated_token_address(&marketplace, mint));
+    }
+    addresses
+}
+
+/// Create a lookup table owned by `authority`; returns the instruction and the table address.
+/// `recent_slot` must be a recent finalized slot.
+pub fn create(authority: &Pubkey, payer: &Pubkey, recent_slot: Slot) -> (Instruction, Pubkey) {
+    lookup_table::create_lookup_table(*authority, *payer, recent_slot)
+}
+
+/// Append `addresses` to `table`, [`ADDRESSES_PER_EXTEND`] per instruction; send each in its own
+/// transaction. Appended addresses are usable from the next slot.
+pub fn extend(table: &Pubkey, authority: &Pubkey, payer: &Pubkey, addresses: &[Pubkey]) -> Vec<Instruction> {
+    addresses
+        .chunks(ADDRESSES_PER_EXTEND)
+        .map(|chunk| lookup_table::extend_lookup_table(*table, *authority, Some(*payer), chunk.to_vec()))
+        .collect()
+}
+
+/// `addresses` not yet in `table`, in order and without duplicates
+pub fn missing(table: &AddressLookupTableAccount, addresses: &[Pubkey]) -> Vec<Pubkey> {
+    let mut missing: Vec<Pubkey> = Vec::new();
+    for address in addresses {
+        if !table.addresses.contains(address) && !missing.contains(address) {
+            missing.push(*address);
+        }
+    }
+    missing
+}
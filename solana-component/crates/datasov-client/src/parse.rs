@@ -0,0 +1,122 @@
+//! Parsers for program enums from kebab-case names (e.g. `location-history`), shared by the CLI and
+//! HTTP front ends.
+
+use datasov_identity::{DataType, PermissionType, Purpose, VerificationLevel};
+use datasov_solana::DataType as ListingDataType;
+
+fn unknown(kind: &str, value: &str, expected: &[&str]) -> String {
+    format!("unknown {} `{}`, expected one of: {}", kind, value, expected.join(", "))
+}
+
+pub fn data_type(value: &str) -> Result<DataType, String> {
+    Ok(match value {
+        "location-history" => DataType::LocationHistory,
+        "app-usage" => DataType::AppUsage,
+        "purchase-history" => DataType::PurchaseHistory,
+        "health-data" => DataType::HealthData,
+        "social-media-activity" => DataType::SocialMediaActivity,
+        "search-history" => DataType::SearchHistory,
+        "financial-data" => DataType::FinancialData,
+        "communication-data" => DataType::CommunicationData,
+        "custom" => DataType::Custom,
+        _ => {
+            return Err(unknown(
+                "data type",
+                value,
+                &[
+                    "location-history",
+                    "app-usage",
+                    "purchase-history",
+                    "health-data",
+                    "social-media-activity",
+                    "search-history",
+                    "financial-data",
+                    "communication-data",
+                    "custom",
+                ],
+            ))
+        }
+    })
+}
+
+/// Marketplace data type; custom types are written `custom:<name>`
+pub fn listing_data_type(value: &str) -> Result<ListingDataType, String> {
+    if let Some(name) = value.strip_prefix("custom:") {
+        return Ok(ListingDataType::Custom(name.to_string()));
+    }
+    Ok(match data_type(value)? {
+        DataType::LocationHistory => ListingDataType::LocationHistory,
+        DataType::AppUsage => ListingDataType::AppUsage,
+        DataType::PurchaseHistory => ListingDataType::PurchaseHistory,
+        DataType::HealthData => ListingDataType::HealthData,
+        DataType::SocialMediaActivity => ListingDataType::SocialMediaActivity,
+        DataType::SearchHistory => ListingDataType::SearchHistory,
+        DataType::FinancialData => ListingDataType::FinancialData,
+        DataType::CommunicationData => ListingDataType::CommunicationData,
+        DataType::Custom => return Err("custom listing data types are written `custom:<name>`".to_string()),
+    })
+}
+
+pub fn permission_type(value: &str) -> Result<PermissionType, String> {
+    Ok(match value {
+        "read-only" => PermissionType::ReadOnly,
+        "read-write" => PermissionType::ReadWrite,
+        "share" => PermissionType::Share,
+        "analyze" => PermissionType::Analyze,
+        "export" => PermissionType::Export,
+        _ => {
+            return Err(unknown(
+                "permission type",
+                value,
+                &["read-only", "read-write", "share", "analyze", "export"],
+            ))
+        }
+    })
+}
+
+pub fn verification_level(value: &str) -> Result<VerificationLevel, String> {
+    Ok(match value {
+        "basic" => VerificationLevel::Basic,
+        "enhanced" => VerificationLevel::Enhanced,
+        "high" => VerificationLevel::High,
+        "credential" => VerificationLevel::Credential,
+        _ => {
+            return Err(unknown(
+                "verification level",
+                value,
+                &["basic", "enhanced", "high", "credential"],
+            ))
+        }
+    })
+}
+
+pub fn purpose(value: &str) -> Result<Purpose, String> {
+    Ok(match value {
+        "research" => Purpose::Research,
+        "analytics" => Purpose::Analytics,
+        "marketing" => Purpose::Marketing,
+        "advertising" => Purpose::Advertising,
+        "product-development" => Purpose::ProductDevelopment,
+        "personalization" => Purpose::Personalization,
+        "compliance" => Purpose::Compliance,
+        "model-training" => Purpose::ModelTraining,
+        "other" => Purpose::Other,
+        _ => {
+            return Err(unknown(
+                "purpose",
+                value,
+                &[
+                    "research",
+                    "analytics",
+                    "marketing",
+                    "advertising",
+                    "product-development",
+                    "personalization",
+                    "compliance",
+                    "model-training",
+                    "other",
+                ],
+            ))
+        }
+    })
+}
@@ -0,0 +1,540 @@
+//! Program-derived addresses, mirroring the `seeds` constraints in each program.
+
+use datasov_constants::seeds;
+use solana_sdk::pubkey::Pubkey;
+
+fn find(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+/// PDAs owned by `datasov_identity`
+pub mod identity {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        datasov_identity::ID
+    }
+
+    pub fn oracle_registry() -> Pubkey {
+        find(&[seeds::identity::ORACLE_REGISTRY], &program_id())
+    }
+
+    pub fn oracle(authority: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::ORACLE, authority.as_ref()], &program_id())
+    }
+
+    pub fn identity(identity_id: &str) -> Pubkey {
+        find(&[seeds::identity::IDENTITY, &seeds::identity::identity_id(identity_id)], &program_id())
+    }
+
+    pub fn permission(identity: &Pubkey, consumer: &Pubkey) -> Pubkey {
+        find(
+            &[seeds::identity::PERMISSION, identity.as_ref(), consumer.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn trial_record(consumer: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::TRIAL, consumer.as_ref()], &program_id())
+    }
+
+    pub fn compliance_matrix() -> Pubkey {
+        find(&[seeds::identity::COMPLIANCE_MATRIX], &program_id())
+    }
+
+    pub fn crank_fund() -> Pubkey {
+        find(&[seeds::identity::CRANK_FUND], &program_id())
+    }
+
+    pub fn blacklist() -> Pubkey {
+        find(&[seeds::identity::BLACKLIST], &program_id())
+    }
+
+    pub fn consumer_profile(consumer: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::CONSUMER, consumer.as_ref()], &program_id())
+    }
+
+    pub fn consumer_stake(consumer: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::CONSUMER_STAKE, consumer.as_ref()], &program_id())
+    }
+
+    pub fn juror_pool() -> Pubkey {
+        find(&[seeds::identity::JUROR_POOL], &program_id())
+    }
+
+    pub fn juror(authority: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::JUROR, authority.as_ref()], &program_id())
+    }
+
+    pub fn dispute(dispute_id: u64) -> Pubkey {
+        find(
+            &[seeds::identity::DISPUTE, dispute_id.to_le_bytes().as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn tee_attestation(provider: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::TEE_ATTESTATION, provider.as_ref()], &program_id())
+    }
+
+    pub fn data_trust(creator: &Pubkey, trust_id: u64) -> Pubkey {
+        find(
+            &[
+                seeds::identity::TRUST,
+                creator.as_ref(),
+                trust_id.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn trust_membership(data_trust: &Pubkey, identity: &Pubkey) -> Pubkey {
+        find(
+            &[seeds::identity::TRUST_MEMBER, data_trust.as_ref(), identity.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn export_request(identity: &Pubkey, consumer: &Pubkey) -> Pubkey {
+        find(
+            &[seeds::identity::EXPORT, identity.as_ref(), consumer.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn consumption_attestation(permission: &Pubkey, sequence: u32) -> Pubkey {
+        find(
+            &[
+                seeds::identity::CONSUMPTION,
+                permission.as_ref(),
+                sequence.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn web_proof_verifier(verifier_id: u32) -> Pubkey {
+        find(
+            &[seeds::identity::WEB_PROOF_VERIFIER, verifier_id.to_le_bytes().as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn web_proof_nullifier(verifier: &Pubkey, nullifier: &[u8; 32]) -> Pubkey {
+        find(
+            &[
+                seeds::identity::WEB_PROOF_NULLIFIER,
+                verifier.as_ref(),
+                nullifier.as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn attestation_source(issuer: &Pubkey, schema: &Pubkey) -> Pubkey {
+        find(
+            &[seeds::identity::ATTESTATION_SOURCE, issuer.as_ref(), schema.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn domain_link(domain_record: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::DOMAIN_LINK, domain_record.as_ref()], &program_id())
+    }
+
+    pub fn voucher_nonce(identity: &Pubkey, nonce: u64) -> Pubkey {
+        find(
+            &[
+                seeds::identity::VOUCHER_NONCE,
+                identity.as_ref(),
+                nonce.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn access_request(identity: &Pubkey, consumer: &Pubkey) -> Pubkey {
+        find(
+            &[seeds::identity::ACCESS_REQUEST, identity.as_ref(), consumer.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn state_snapshot() -> Pubkey {
+        find(&[seeds::identity::STATE_SNAPSHOT], &program_id())
+    }
+
+    pub fn compliance_officer(officer: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::COMPLIANCE_OFFICER, officer.as_ref()], &program_id())
+    }
+
+    pub fn identity_freeze(identity: &Pubkey) -> Pubkey {
+        find(&[seeds::identity::IDENTITY_FREEZE, identity.as_ref()], &program_id())
+    }
+
+    pub fn audit_writer() -> Pubkey {
+        find(&[seeds::audit::AUDIT_WRITER], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_solana`
+pub mod marketplace {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        datasov_solana::ID
+    }
+
+    pub fn marketplace() -> Pubkey {
+        find(&[seeds::marketplace::MARKETPLACE], &program_id())
+    }
+
+    pub fn crank_fund() -> Pubkey {
+        find(&[seeds::marketplace::CRANK_FUND], &program_id())
+    }
+
+    pub fn listing(listing_id: u64) -> Pubkey {
+        find(
+            &[seeds::marketplace::LISTING, listing_id.to_le_bytes().as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn allowlist(listing: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::ALLOWLIST, listing.as_ref()], &program_id())
+    }
+
+    pub fn data_manifest(listing: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::DATA_MANIFEST, listing.as_ref()], &program_id())
+    }
+
+    pub fn receipt(listing: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::RECEIPT, listing.as_ref()], &program_id())
+    }
+
+    pub fn escrow(listing: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::ESCROW, listing.as_ref()], &program_id())
+    }
+
+    pub fn coupon(seller: &Pubkey, code_hash: &[u8; 32]) -> Pubkey {
+        find(
+            &[seeds::marketplace::COUPON, seller.as_ref(), code_hash.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn purchase_delegate(consumer: &Pubkey, delegate: &Pubkey) -> Pubkey {
+        find(
+            &[
+                seeds::marketplace::PURCHASE_DELEGATE,
+                consumer.as_ref(),
+                delegate.as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn compute_job(listing: &Pubkey, buyer: &Pubkey, job_id: u64) -> Pubkey {
+        find(
+            &[
+                seeds::marketplace::COMPUTE_JOB,
+                listing.as_ref(),
+                buyer.as_ref(),
+                job_id.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn job_escrow(compute_job: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::JOB_ESCROW, compute_job.as_ref()], &program_id())
+    }
+
+    pub fn fl_job(consumer: &Pubkey, job_id: u64) -> Pubkey {
+        find(
+            &[
+                seeds::marketplace::FL_JOB,
+                consumer.as_ref(),
+                job_id.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn fl_escrow(fl_job: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::FL_ESCROW, fl_job.as_ref()], &program_id())
+    }
+
+    pub fn fl_participant(fl_job: &Pubkey, identity: &Pubkey) -> Pubkey {
+        find(
+            &[seeds::marketplace::FL_PARTICIPANT, fl_job.as_ref(), identity.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn data_union(operator: &Pubkey, union_id: u64) -> Pubkey {
+        find(
+            &[
+                seeds::marketplace::UNION,
+                operator.as_ref(),
+                union_id.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn union_vault(data_union: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::UNION_VAULT, data_union.as_ref()], &program_id())
+    }
+
+    pub fn union_member(data_union: &Pubkey, identity: &Pubkey) -> Pubkey {
+        find(
+            &[seeds::marketplace::UNION_MEMBER, data_union.as_ref(), identity.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn rebate_program() -> Pubkey {
+        find(&[seeds::marketplace::REBATE_PROGRAM], &program_id())
+    }
+
+    pub fn rebate_vault() -> Pubkey {
+        find(&[seeds::marketplace::REBATE_VAULT], &program_id())
+    }
+
+    pub fn rebate_epoch(epoch: u64) -> Pubkey {
+        find(
+            &[seeds::marketplace::REBATE_EPOCH, epoch.to_le_bytes().as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn rebate_ledger(epoch: u64, participant: &Pubkey) -> Pubkey {
+        find(
+            &[
+                seeds::marketplace::REBATE_LEDGER,
+                epoch.to_le_bytes().as_ref(),
+                participant.as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn buyback() -> Pubkey {
+        find(&[seeds::marketplace::BUYBACK], &program_id())
+    }
+
+    pub fn buyback_input() -> Pubkey {
+        find(&[seeds::marketplace::BUYBACK_INPUT], &program_id())
+    }
+
+    pub fn buyback_output() -> Pubkey {
+        find(&[seeds::marketplace::BUYBACK_OUTPUT], &program_id())
+    }
+
+    pub fn curation() -> Pubkey {
+        find(&[seeds::marketplace::CURATION], &program_id())
+    }
+
+    pub fn curation_vault() -> Pubkey {
+        find(&[seeds::marketplace::CURATION_VAULT], &program_id())
+    }
+
+    pub fn featured_set(epoch: u64) -> Pubkey {
+        find(&[seeds::marketplace::FEATURED_SET, epoch.to_le_bytes().as_ref()], &program_id())
+    }
+
+    pub fn listing_tally(epoch: u64, listing: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::LISTING_TALLY, epoch.to_le_bytes().as_ref(), listing.as_ref()], &program_id())
+    }
+
+    pub fn curation_vote(tally: &Pubkey, curator: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::CURATION_VOTE, tally.as_ref(), curator.as_ref()], &program_id())
+    }
+
+    pub fn storage_oracle(oracle: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::STORAGE_ORACLE, oracle.as_ref()], &program_id())
+    }
+
+    pub fn storage_health(listing: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::STORAGE_HEALTH, listing.as_ref()], &program_id())
+    }
+
+    pub fn license_template(template_id: u32) -> Pubkey {
+        find(&[seeds::marketplace::LICENSE_TEMPLATE, &template_id.to_le_bytes()], &program_id())
+    }
+
+    /// Token-2022 mint of the license NFT of the purchase behind `receipt`
+    pub fn license_mint(receipt: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::LICENSE_MINT, receipt.as_ref()], &program_id())
+    }
+
+    /// Price candle of `data_type` sales settled in `mint` on UTC day `day`
+    pub fn price_candle(mint: &Pubkey, data_type: &datasov_solana::DataType, day: i64) -> Pubkey {
+        find(
+            &[
+                seeds::marketplace::PRICE_CANDLE,
+                mint.as_ref(),
+                &[data_type.index() as u8],
+                day.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    /// Lifetime earnings of the identity account `identity`
+    pub fn earnings_ledger(identity: &Pubkey) -> Pubkey {
+        find(&[seeds::marketplace::EARNINGS_LEDGER, identity.as_ref()], &program_id())
+    }
+
+    pub fn feature_flags() -> Pubkey {
+        find(&[seeds::marketplace::FEATURE_FLAGS], &program_id())
+    }
+
+    pub fn audit_writer() -> Pubkey {
+        find(&[seeds::audit::AUDIT_WRITER], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_audit`
+pub mod audit {
+    use super::*;
+    use datasov_audit::AuditPage;
+
+    pub fn program_id() -> Pubkey {
+        datasov_audit::ID
+    }
+
+    pub fn audit_log(subject: &Pubkey) -> Pubkey {
+        find(&[seeds::audit::AUDIT_LOG, subject.as_ref()], &program_id())
+    }
+
+    pub fn audit_page(audit_log: &Pubkey, page_index: u64) -> Pubkey {
+        find(
+            &[
+                seeds::audit::AUDIT_PAGE,
+                audit_log.as_ref(),
+                page_index.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    /// Page that the log's next entry lands on, given its current entry count
+    pub fn next_audit_page(audit_log: &Pubkey, entry_count: u64) -> Pubkey {
+        audit_page(audit_log, entry_count / AuditPage::MAX_ENTRIES as u64)
+    }
+
+    pub fn audit_checkpoint(audit_log: &Pubkey, epoch: u64) -> Pubkey {
+        find(
+            &[
+                seeds::audit::AUDIT_CHECKPOINT,
+                audit_log.as_ref(),
+                epoch.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+}
+
+/// PDAs owned by `datasov_governance`
+pub mod governance {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        datasov_governance::ID
+    }
+
+    pub fn governance() -> Pubkey {
+        find(&[seeds::governance::GOVERNANCE], &program_id())
+    }
+
+    pub fn stake(staker: &Pubkey) -> Pubkey {
+        find(&[seeds::governance::STAKE, staker.as_ref()], &program_id())
+    }
+
+    pub fn proposal(proposal_id: u64) -> Pubkey {
+        find(&[seeds::governance::PROPOSAL, proposal_id.to_le_bytes().as_ref()], &program_id())
+    }
+
+    pub fn vote(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+        find(&[seeds::governance::VOTE, proposal.as_ref(), voter.as_ref()], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_timelock`
+pub mod timelock {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        datasov_timelock::ID
+    }
+
+    /// Also the upgrade and admin authority of the programs under the timelock
+    pub fn timelock() -> Pubkey {
+        find(&[seeds::timelock::TIMELOCK], &program_id())
+    }
+
+    pub fn operation(operation_id: u64) -> Pubkey {
+        find(&[seeds::timelock::OPERATION, operation_id.to_le_bytes().as_ref()], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_staking`
+pub mod staking {
+    use super::*;
+    use datasov_staking::StakeRole;
+
+    pub fn program_id() -> Pubkey {
+        datasov_staking::ID
+    }
+
+    pub fn config() -> Pubkey {
+        find(&[seeds::staking::CONFIG], &program_id())
+    }
+
+    /// Token account holding every staked token
+    pub fn vault() -> Pubkey {
+        find(&[seeds::staking::VAULT], &program_id())
+    }
+
+    pub fn backing(role: StakeRole, subject: &Pubkey) -> Pubkey {
+        find(&[seeds::staking::BACKING, &[role.seed()], subject.as_ref()], &program_id())
+    }
+
+    pub fn position(backing: &Pubkey, staker: &Pubkey) -> Pubkey {
+        find(&[seeds::staking::POSITION, backing.as_ref(), staker.as_ref()], &program_id())
+    }
+
+    pub fn stake_weight(staker: &Pubkey) -> Pubkey {
+        find(&[seeds::staking::STAKE_WEIGHT, staker.as_ref()], &program_id())
+    }
+
+    pub fn dispute_slash(dispute: &Pubkey, backing: &Pubkey) -> Pubkey {
+        find(&[seeds::staking::DISPUTE_SLASH, dispute.as_ref(), backing.as_ref()], &program_id())
+    }
+
+    pub fn vesting(beneficiary: &Pubkey, vesting_id: u64) -> Pubkey {
+        find(&[seeds::staking::VESTING, beneficiary.as_ref(), &vesting_id.to_le_bytes()], &program_id())
+    }
+
+    /// Token account holding a vesting's unclaimed tokens
+    pub fn vesting_vault(vesting: &Pubkey) -> Pubkey {
+        find(&[seeds::staking::VESTING_VAULT, vesting.as_ref()], &program_id())
+    }
+
+    pub fn airdrop(airdrop_id: u64) -> Pubkey {
+        find(&[seeds::staking::AIRDROP, &airdrop_id.to_le_bytes()], &program_id())
+    }
+
+    /// Token account an airdrop pays out of
+    pub fn airdrop_vault(airdrop: &Pubkey) -> Pubkey {
+        find(&[seeds::staking::AIRDROP_VAULT, airdrop.as_ref()], &program_id())
+    }
+
+    /// Claimed flags of the chunk of allocations holding `index`
+    pub fn airdrop_bitmap(airdrop: &Pubkey, index: u32) -> Pubkey {
+        let chunk = datasov_staking::AirdropBitmap::chunk_of(index);
+        find(&[seeds::staking::AIRDROP_BITMAP, airdrop.as_ref(), &chunk.to_le_bytes()], &program_id())
+    }
+}
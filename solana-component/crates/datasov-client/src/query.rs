@@ -0,0 +1,141 @@
+//! `getProgramAccounts` filters for listings, permissions and identities.
+//!
+//! Each filterable field sits at a fixed offset ahead of the variable-length fields (the
+//! `*_OFFSET` constants on the account types), so a `memcmp` on it matches every account
+//! regardless of string lengths. Pass [`ListingQuery::filters`] and friends to
+//! [`DataSovRpc::fetch_all`](crate::rpc::DataSovRpc::fetch_all), or to
+//! [`DataSovRpc::fetch_page`](crate::rpc::DataSovRpc::fetch_page) to walk the results a page at a time.
+
+use anchor_lang::{AnchorSerialize, Space};
+use datasov_identity::{AccessPermission, IdentityAccount, IdentityStatus, PermissionType, VerificationLevel};
+use datasov_solana::{DataListing, DataType};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+
+fn memcmp<T: AnchorSerialize + ?Sized>(offset: usize, value: &T) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, &value.try_to_vec().unwrap()))
+}
+
+/// Filters on [`DataListing`] accounts
+#[derive(Clone, Debug, Default)]
+pub struct ListingQuery {
+    filters: Vec<RpcFilterType>,
+}
+
+impl ListingQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn owner(mut self, owner: &Pubkey) -> Self {
+        self.filters.push(memcmp(DataListing::OWNER_OFFSET, owner));
+        self
+    }
+
+    pub fn active(mut self, is_active: bool) -> Self {
+        self.filters.push(memcmp(DataListing::IS_ACTIVE_OFFSET, &is_active));
+        self
+    }
+
+    /// Matches custom data types on their full name
+    pub fn data_type(mut self, data_type: &DataType) -> Self {
+        // The variant index, then a custom name's length and bytes, as the listing stores them
+        let mut bytes = vec![data_type.index() as u8];
+        if let DataType::Custom(name) = data_type {
+            bytes.push(name.len() as u8);
+            bytes.extend_from_slice(name.as_bytes());
+        }
+        self.filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            DataListing::DATA_TYPE_OFFSET,
+            &bytes,
+        )));
+        self
+    }
+
+    /// Listings still in an earlier layout are left out; only the current size decodes zero-copy
+    pub fn filters(&self) -> Vec<RpcFilterType> {
+        let mut filters = vec![RpcFilterType::DataSize((8 + DataListing::INIT_SPACE) as u64)];
+        filters.extend(self.filters.iter().cloned());
+        filters
+    }
+}
+
+/// Filters on [`AccessPermission`] accounts
+#[derive(Clone, Debug, Default)]
+pub struct PermissionQuery {
+    filters: Vec<RpcFilterType>,
+}
+
+impl PermissionQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn consumer(mut self, consumer: &Pubkey) -> Self {
+        self.filters.push(memcmp(AccessPermission::CONSUMER_OFFSET, consumer));
+        self
+    }
+
+    pub fn active(mut self, is_active: bool) -> Self {
+        self.filters
+            .push(memcmp(AccessPermission::IS_ACTIVE_OFFSET, &is_active));
+        self
+    }
+
+    pub fn permission_type(mut self, permission_type: &PermissionType) -> Self {
+        self.filters
+            .push(memcmp(AccessPermission::PERMISSION_TYPE_OFFSET, permission_type));
+        self
+    }
+
+    /// Permissions on the identity `identity_id`
+    pub fn identity_id(mut self, identity_id: &str) -> Self {
+        self.filters
+            .push(memcmp(AccessPermission::IDENTITY_ID_OFFSET, identity_id));
+        self
+    }
+
+    pub fn filters(&self) -> Vec<RpcFilterType> {
+        self.filters.clone()
+    }
+}
+
+/// Filters on [`IdentityAccount`] accounts
+#[derive(Clone, Debug, Default)]
+pub struct IdentityQuery {
+    filters: Vec<RpcFilterType>,
+}
+
+impl IdentityQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn owner(mut self, owner: &Pubkey) -> Self {
+        self.filters.push(memcmp(IdentityAccount::OWNER_OFFSET, owner));
+        self
+    }
+
+    pub fn status(mut self, status: &IdentityStatus) -> Self {
+        self.filters.push(memcmp(IdentityAccount::STATUS_OFFSET, status));
+        self
+    }
+
+    pub fn verification_level(mut self, verification_level: &VerificationLevel) -> Self {
+        self.filters
+            .push(memcmp(IdentityAccount::VERIFICATION_LEVEL_OFFSET, verification_level));
+        self
+    }
+
+    pub fn filters(&self) -> Vec<RpcFilterType> {
+        self.filters.clone()
+    }
+}
+
+/// One page of a paginated fetch, in address order
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+    pub accounts: Vec<(Pubkey, T)>,
+    /// Cursor for the following page, `None` on the last one
+    pub next: Option<Pubkey>,
+}
@@ -0,0 +1,650 @@
+//! Async account fetchers and transaction submission over JSON-RPC.
+//!
+//! Every request goes to the active endpoint and, on a transient failure (connection errors,
+//! rate limiting, an unhealthy or lagging node), is retried with exponential backoff, moving to the
+//! next endpoint each time. Transactions are confirmed by polling their status and are re-signed
+//! with a fresh blockhash if it expires before they land.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anchor_lang::{AccountDeserialize, Discriminator, Owner};
+use datasov_audit::AuditLog;
+use datasov_identity::{AccessPermission, ConsumerProfile, ConsumerStake, IdentityAccount, StateSnapshot};
+use datasov_solana::{
+    Coupon, CurationConfig, DataListing, FeaturedSet, Marketplace, PurchaseReceipt, RebateEpoch, RebateLedger,
+    RebateProgram,
+};
+use datasov_staking::StakeWeight;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::client_error::{ClientError as RpcError, ClientErrorKind};
+pub use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET,
+    JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::{RpcError as RpcRequestError, MAX_MULTIPLE_ACCOUNTS};
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_transaction_status::TransactionConfirmationStatus;
+
+use crate::estimate::{self, Estimate, Operation};
+use crate::faucet::{self, Drip};
+use crate::lookup_table;
+use crate::query::Page;
+use crate::snapshot::IdentityTree;
+use crate::instructions::RebateLedgers;
+use crate::{compute, pda, ClientError, Result, TransactionBuilder};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often and how patiently a request or transaction is retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts per request, and signed transactions per send, before giving up
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for each one after
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+/// Progress of a transaction through [`DataSovRpc::send_with_status`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendStatus {
+    /// Signed with a fresh blockhash and sent; `attempt` counts from 1
+    Sent { signature: Signature, attempt: u32 },
+    /// In a block, but not yet at the commitment waited for
+    Landed {
+        signature: Signature,
+        confirmation_status: Option<TransactionConfirmationStatus>,
+    },
+    /// The blockhash expired before the transaction landed; it is re-signed and sent again
+    Expired { signature: Signature },
+    /// Reached the commitment waited for
+    Confirmed { signature: Signature },
+}
+
+pub struct DataSovRpc {
+    endpoints: Vec<RpcClient>,
+    /// Index of the endpoint requests go to first; moves on each transient failure
+    active: AtomicUsize,
+    retry: RetryPolicy,
+}
+
+impl DataSovRpc {
+    pub fn new(rpc_url: String) -> Self {
+        Self::from_client(RpcClient::new(rpc_url))
+    }
+
+    pub fn from_client(client: RpcClient) -> Self {
+        Self::from_clients(vec![client])
+    }
+
+    /// Fail over between `urls`, in order, all at `commitment`
+    pub fn with_endpoints(urls: Vec<String>, commitment: CommitmentConfig) -> Self {
+        Self::from_clients(
+            urls.into_iter()
+                .map(|url| RpcClient::new_with_commitment(url, commitment))
+                .collect(),
+        )
+    }
+
+    /// Fail over between `clients`, in order; panics if there are none
+    pub fn from_clients(clients: Vec<RpcClient>) -> Self {
+        assert!(!clients.is_empty(), "at least one RPC endpoint is required");
+        Self {
+            endpoints: clients,
+            active: AtomicUsize::new(0),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// The active endpoint, for requests this type does not wrap; these are not retried
+    pub fn client(&self) -> &RpcClient {
+        &self.endpoints[self.active.load(Ordering::Relaxed)]
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.client().commitment()
+    }
+
+    /// Run `request` against the active endpoint, retrying transient failures with backoff and
+    /// moving to the next endpoint before each retry
+    pub async fn call<'a, T, F, Fut>(&'a self, request: F) -> Result<T>
+    where
+        F: Fn(&'a RpcClient) -> Fut,
+        Fut: Future<Output = std::result::Result<T, RpcError>>,
+    {
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            let index = self.active.load(Ordering::Relaxed);
+            match request(&self.endpoints[index]).await {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < self.retry.max_attempts && is_transient(&err) => {
+                    // Another request may have failed over already
+                    let next = (index + 1) % self.endpoints.len();
+                    let _ = self
+                        .active
+                        .compare_exchange(index, next, Ordering::Relaxed, Ordering::Relaxed);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.retry.max_backoff);
+                    attempt += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Fetch and decode an Anchor account, or `None` if it does not exist
+    pub async fn fetch_optional<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let account = self
+            .call(|client| client.get_account_with_commitment(address, client.commitment()))
+            .await?
+            .value;
+        account
+            .map(|account| {
+                T::try_deserialize(&mut account.data.as_slice()).map_err(|source| ClientError::Decode {
+                    address: *address,
+                    source,
+                })
+            })
+            .transpose()
+    }
+
+    /// Fetch and decode an Anchor account that must exist
+    pub async fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        self.fetch_optional(address)
+            .await?
+            .ok_or(ClientError::AccountNotFound(*address))
+    }
+
+    /// Fetch every account of type `T`, matched on its discriminator plus any extra `filters`.
+    /// Accounts that fail to decode (e.g. from an older layout) are skipped.
+    pub async fn fetch_all<T: AccountDeserialize + Discriminator + Owner>(
+        &self,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, T)>> {
+        let mut filters = filters;
+        filters.insert(
+            0,
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR)),
+        );
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let program_id = T::owner();
+        let accounts = self
+            .call(|client| client.get_program_accounts_with_config(&program_id, config.clone()))
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                T::try_deserialize(&mut account.data.as_slice())
+                    .ok()
+                    .map(|decoded| (address, decoded))
+            })
+            .collect())
+    }
+
+    /// Fetch up to `limit` accounts of type `T` matching `filters` (see [`crate::query`]), in
+    /// address order, starting after the cursor `after`. Lists matching addresses without their
+    /// data, then fetches only the page's accounts. Accounts that fail to decode are skipped.
+    pub async fn fetch_page<T: AccountDeserialize + Discriminator + Owner>(
+        &self,
+        filters: Vec<RpcFilterType>,
+        after: Option<&Pubkey>,
+        limit: usize,
+    ) -> Result<Page<T>> {
+        let mut filters = filters;
+        filters.insert(
+            0,
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR)),
+        );
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig { offset: 0, length: 0 }),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let program_id = T::owner();
+        let mut addresses: Vec<Pubkey> = self
+            .call(|client| client.get_program_accounts_with_config(&program_id, config.clone()))
+            .await?
+            .into_iter()
+            .map(|(address, _)| address)
+            .filter(|address| after.is_none_or(|after| address > after))
+            .collect();
+        addresses.sort();
+
+        let page = &addresses[..limit.min(addresses.len())];
+        let mut accounts = Vec::with_capacity(page.len());
+        for chunk in page.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let fetched = self.call(|client| client.get_multiple_accounts(chunk)).await?;
+            accounts.extend(chunk.iter().zip(fetched).filter_map(|(address, account)| {
+                T::try_deserialize(&mut account?.data.as_slice())
+                    .ok()
+                    .map(|decoded| (*address, decoded))
+            }));
+        }
+        Ok(Page {
+            accounts,
+            next: match addresses.len() > page.len() {
+                true => page.last().copied(),
+                false => None,
+            },
+        })
+    }
+
+    /// Fetch an address lookup table, to pass to [`TransactionBuilder::lookup_table`]
+    pub async fn fetch_lookup_table(&self, address: &Pubkey) -> Result<AddressLookupTableAccount> {
+        let account = self
+            .call(|client| client.get_account_with_commitment(address, client.commitment()))
+            .await?
+            .value
+            .ok_or(ClientError::AccountNotFound(*address))?;
+        let table = AddressLookupTable::deserialize(&account.data).map_err(|_| ClientError::LookupTable(*address))?;
+        Ok(AddressLookupTableAccount {
+            key: *address,
+            addresses: table.addresses.to_vec(),
+        })
+    }
+
+    pub async fn fetch_identity(&self, identity_id: &str) -> Result<IdentityAccount> {
+        self.fetch(&pda::identity::identity(identity_id)).await
+    }
+
+    pub async fn fetch_permission(&self, identity: &Pubkey, consumer: &Pubkey) -> Result<Option<AccessPermission>> {
+        self.fetch_optional(&pda::identity::permission(identity, consumer))
+            .await
+    }
+
+    pub async fn fetch_consumer_profile(&self, consumer: &Pubkey) -> Result<Option<ConsumerProfile>> {
+        self.fetch_optional(&pda::identity::consumer_profile(consumer)).await
+    }
+
+    /// X25519 key published on an identity, to wrap content keys for its owner
+    pub async fn identity_encryption_key(&self, identity_id: &str) -> Result<Option<[u8; 32]>> {
+        Ok(self.fetch_identity(identity_id).await?.encryption_key)
+    }
+
+    /// X25519 key published on a consumer profile, to wrap content keys for the consumer
+    pub async fn consumer_encryption_key(&self, consumer: &Pubkey) -> Result<Option<[u8; 32]>> {
+        Ok(self
+            .fetch_consumer_profile(consumer)
+            .await?
+            .and_then(|profile| profile.encryption_key))
+    }
+
+    /// Whether the consumer has a stake account, which grant and purchase instructions must then include
+    pub async fn has_consumer_stake(&self, consumer: &Pubkey) -> Result<bool> {
+        Ok(self
+            .fetch_optional::<ConsumerStake>(&pda::identity::consumer_stake(consumer))
+            .await?
+            .is_some())
+    }
+
+    /// Whether the wallet has staked protocol tokens, whose weight purchase instructions may then
+    /// present for a fee discount
+    pub async fn has_stake_weight(&self, staker: &Pubkey) -> Result<bool> {
+        Ok(self
+            .fetch_optional::<StakeWeight>(&pda::staking::stake_weight(staker))
+            .await?
+            .is_some())
+    }
+
+    /// Rebate ledgers the buyer and the payout wallet hold for the running fee rebate epoch, or
+    /// `None` when rebates are off, the epoch is not open, or neither side opted in
+    pub async fn rebate_ledgers(&self, buyer: &Pubkey, payout_wallet: &Pubkey) -> Result<Option<RebateLedgers>> {
+        let Some(rebate_program) = self
+            .fetch_optional::<RebateProgram>(&pda::marketplace::rebate_program())
+            .await?
+        else {
+            return Ok(None);
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        let epoch = rebate_program.current_epoch(now);
+        let open = self
+            .fetch_optional::<RebateEpoch>(&pda::marketplace::rebate_epoch(epoch))
+            .await?
+            .is_some_and(|rebate_epoch| rebate_epoch.is_current(now));
+        if !open {
+            return Ok(None);
+        }
+
+        let buyer = self
+            .fetch_optional::<RebateLedger>(&pda::marketplace::rebate_ledger(epoch, buyer))
+            .await?
+            .is_some();
+        let seller = self
+            .fetch_optional::<RebateLedger>(&pda::marketplace::rebate_ledger(epoch, payout_wallet))
+            .await?
+            .is_some();
+        Ok((buyer || seller).then_some(RebateLedgers { epoch, buyer, seller }))
+    }
+
+    /// Featured set of the last ended curation epoch, the one front ends feature, or `None` when
+    /// curation is off, no epoch has ended, or nobody voted in it
+    pub async fn featured_set(&self) -> Result<Option<FeaturedSet>> {
+        let Some(curation_config) = self
+            .fetch_optional::<CurationConfig>(&pda::marketplace::curation())
+            .await?
+        else {
+            return Ok(None);
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        let Some(epoch) = curation_config.current_epoch(now).checked_sub(1) else {
+            return Ok(None);
+        };
+        self.fetch_optional(&pda::marketplace::featured_set(epoch)).await
+    }
+
+    pub async fn fetch_state_snapshot(&self) -> Result<StateSnapshot> {
+        self.fetch(&pda::identity::state_snapshot()).await
+    }
+
+    /// Snapshot tree over every identity as it stands now, to generate proofs against a root
+    /// published from the same state
+    pub async fn identity_tree(&self) -> Result<IdentityTree> {
+        Ok(IdentityTree::new(self.fetch_all::<IdentityAccount>(Vec::new()).await?))
+    }
+
+    pub async fn fetch_marketplace(&self) -> Result<Marketplace> {
+        self.fetch(&pda::marketplace::marketplace()).await
+    }
+
+    pub async fn fetch_listing(&self, listing_id: u64) -> Result<Option<DataListing>> {
+        self.fetch_optional(&pda::marketplace::listing(listing_id)).await
+    }
+
+    pub async fn fetch_receipt(&self, listing: &Pubkey) -> Result<Option<PurchaseReceipt>> {
+        self.fetch_optional(&pda::marketplace::receipt(listing)).await
+    }
+
+    /// Current length of a subject's audit log, needed to address the page the next entry lands on
+    pub async fn audit_entry_count(&self, subject: &Pubkey) -> Result<u64> {
+        Ok(self
+            .fetch_optional::<AuditLog>(&pda::audit::audit_log(subject))
+            .await?
+            .map_or(0, |log| log.entry_count))
+    }
+
+    /// The cluster's rent parameters
+    pub async fn rent(&self) -> Result<Rent> {
+        let address = solana_sdk::sysvar::rent::ID;
+        let account = self.call(|client| client.get_account(&address)).await?;
+        solana_sdk::account::from_account(&account).ok_or(ClientError::AccountNotFound(address))
+    }
+
+    /// Estimate `operation` under the cluster's current rent
+    pub async fn estimate(&self, operation: &Operation) -> Result<Estimate> {
+        Ok(estimate::estimate(operation, &self.rent().await?))
+    }
+
+    /// Grant of access to `identity_id` for `consumer`, as it would execute now
+    pub async fn grant_operation(&self, identity_id: &str, consumer: &Pubkey) -> Result<Operation> {
+        let identity = pda::identity::identity(identity_id);
+        Ok(Operation::GrantAccess {
+            permission_exists: self.fetch_permission(&identity, consumer).await?.is_some(),
+            audit_entry_count: self.audit_entry_count(&identity).await?,
+        })
+    }
+
+    /// Purchase of listing `listing_id`, as it would execute now. `revealed_price` is the price
+    /// of a private listing, ignored for public ones; `coupon` is the coupon account to redeem.
+    pub async fn purchase_operation(
+        &self,
+        listing_id: u64,
+        revealed_price: Option<u64>,
+        coupon: Option<&Pubkey>,
+    ) -> Result<Operation> {
+        let listing: DataListing = self.fetch(&pda::marketplace::listing(listing_id)).await?;
+        let marketplace = self.fetch_marketplace().await?;
+        let discount_basis_points = match coupon {
+            Some(coupon) => Some(self.fetch::<Coupon>(coupon).await?.discount_basis_points),
+            None => None,
+        };
+        let seller_identity = pda::identity::identity(listing.identity_id());
+        Ok(Operation::Purchase {
+            price: match listing.is_private() {
+                true => revealed_price.unwrap_or(0),
+                false => listing.price,
+            },
+            fee_basis_points: marketplace.fee_basis_points,
+            discount_basis_points,
+            escrow: marketplace.refund_window > 0 && listing.union().is_none(),
+            audit_entry_count: self.audit_entry_count(&seller_identity).await?,
+        })
+    }
+
+    /// The `percentile` (0-100) of per-unit priority fees recently paid by transactions writing
+    /// the accounts `builder` writes, in micro-lamports
+    pub async fn recent_priority_fee(&self, builder: &TransactionBuilder, percentile: u8) -> Result<u64> {
+        let mut writable: Vec<Pubkey> = builder
+            .instructions()
+            .iter()
+            .flat_map(|instruction| &instruction.accounts)
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        writable.sort_unstable();
+        writable.dedup();
+        // The node accepts at most 128 accounts
+        writable.truncate(128);
+        let fees: Vec<u64> = self
+            .call(|client| client.get_recent_prioritization_fees(&writable))
+            .await?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        Ok(compute::fee_percentile(&fees, percentile))
+    }
+
+    /// `builder` with its compute unit ceiling and the `percentile` recent priority fee
+    pub async fn with_priority_fee(&self, builder: TransactionBuilder, percentile: u8) -> Result<TransactionBuilder> {
+        let micro_lamports = self.recent_priority_fee(&builder, percentile).await?;
+        Ok(builder.priority_fee(micro_lamports))
+    }
+
+    /// Compute units `builder` consumes when simulated now, to size the limit of instructions
+    /// with no known ceiling
+    pub async fn simulated_units(&self, builder: &TransactionBuilder, payer: &Pubkey) -> Result<u32> {
+        let transaction = builder
+            .clone()
+            .compute_budget(compute::MAX_UNITS, 0)
+            .build_versioned_unsigned(payer, Hash::default())?;
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let simulation = self
+            .call(|client| client.simulate_transaction_with_config(&transaction, config.clone()))
+            .await?
+            .value;
+        if let Some(err) = simulation.err {
+            return Err(ClientError::Simulation(err.to_string()));
+        }
+        Ok(simulation.units_consumed.unwrap_or(compute::MAX_UNITS as u64) as u32)
+    }
+
+    /// Create a demo payment token at a new address, with `authority` as its mint authority
+    pub async fn create_demo_mint(&self, authority: &dyn Signer) -> Result<Pubkey> {
+        let mint = Keypair::new();
+        let rent_lamports = self.rent().await?.minimum_balance(faucet::MINT_SPACE);
+        let builder = faucet::create_demo_mint(&authority.pubkey(), &mint.pubkey(), rent_lamports)
+            .into_iter()
+            .fold(TransactionBuilder::new(), TransactionBuilder::instruction);
+        self.send(&builder, &authority.pubkey(), &[authority, &mint]).await?;
+        Ok(mint.pubkey())
+    }
+
+    /// Hand out `drip` from the faucet `authority` of `mint`, in one transaction the user co-signs
+    pub async fn drip(&self, authority: &dyn Signer, user: &dyn Signer, mint: &Pubkey, drip: &Drip) -> Result<Signature> {
+        let builder = faucet::drip(&authority.pubkey(), mint, drip)
+            .into_iter()
+            .fold(TransactionBuilder::new(), TransactionBuilder::instruction);
+        self.send(&builder, &authority.pubkey(), &[authority, user]).await
+    }
+
+    /// Create a lookup table with `authority` and fill it with `addresses`; returns its address.
+    /// The table is usable a slot after the last extend lands.
+    pub async fn create_lookup_table(&self, authority: &dyn Signer, addresses: &[Pubkey]) -> Result<Pubkey> {
+        let recent_slot = self
+            .call(|client| client.get_slot_with_commitment(CommitmentConfig::finalized()))
+            .await?;
+        let (instruction, table) = lookup_table::create(&authority.pubkey(), &authority.pubkey(), recent_slot);
+        self.send(
+            &TransactionBuilder::new().instruction(instruction),
+            &authority.pubkey(),
+            &[authority],
+        )
+        .await?;
+        self.extend_lookup_table(authority, &table, addresses).await?;
+        Ok(table)
+    }
+
+    /// Append the `addresses` not yet in `table`, one transaction per
+    /// [`lookup_table::ADDRESSES_PER_EXTEND`]; returns how many were added
+    pub async fn extend_lookup_table(
+        &self,
+        authority: &dyn Signer,
+        table: &Pubkey,
+        addresses: &[Pubkey],
+    ) -> Result<usize> {
+        let current = self.fetch_lookup_table(table).await?;
+        let missing = lookup_table::missing(&current, addresses);
+        for instruction in lookup_table::extend(table, &authority.pubkey(), &authority.pubkey(), &missing) {
+            self.send(
+                &TransactionBuilder::new().instruction(instruction),
+                &authority.pubkey(),
+                &[authority],
+            )
+            .await?;
+        }
+        Ok(missing.len())
+    }
+
+    /// Sign with a fresh blockhash, send, and wait for confirmation
+    pub async fn send(
+        &self,
+        builder: &TransactionBuilder,
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+    ) -> Result<Signature> {
+        self.send_with_status(builder, payer, signers, |_| {}).await
+    }
+
+    /// [`send`](Self::send), reporting each step to `on_status`. Builders with lookup tables are sent
+    /// as v0 transactions, others as legacy ones. A transaction whose blockhash expires unconfirmed
+    /// is re-signed and sent again, up to the retry policy's attempts.
+    pub async fn send_with_status(
+        &self,
+        builder: &TransactionBuilder,
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+        mut on_status: impl FnMut(&SendStatus),
+    ) -> Result<Signature> {
+        let commitment = self.commitment();
+        for attempt in 1..=self.retry.max_attempts {
+            let (recent_blockhash, last_valid_block_height) = self
+                .call(|client| client.get_latest_blockhash_with_commitment(commitment))
+                .await?;
+            let transaction = builder.build_versioned(payer, signers, recent_blockhash)?;
+            let signature = self.call(|client| client.send_transaction(&transaction)).await?;
+            on_status(&SendStatus::Sent { signature, attempt });
+
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                // Read the height first: a transaction not found after it passed can no longer land
+                let block_height = self
+                    .call(|client| client.get_block_height_with_commitment(commitment))
+                    .await?;
+                let status = self
+                    .call(|client| client.get_signature_statuses(std::slice::from_ref(&signature)))
+                    .await?
+                    .value
+                    .pop()
+                    .flatten();
+                match status {
+                    Some(status) => {
+                        if let Some(error) = status.err.clone() {
+                            return Err(ClientError::TransactionFailed { signature, error });
+                        }
+                        if status.satisfies_commitment(commitment) {
+                            on_status(&SendStatus::Confirmed { signature });
+                            return Ok(signature);
+                        }
+                        on_status(&SendStatus::Landed {
+                            signature,
+                            confirmation_status: status.confirmation_status,
+                        });
+                    }
+                    None if block_height > last_valid_block_height => {
+                        on_status(&SendStatus::Expired { signature });
+                        break;
+                    }
+                    None => {}
+                }
+            }
+        }
+        Err(ClientError::BlockhashExpired {
+            attempts: self.retry.max_attempts,
+        })
+    }
+}
+
+/// Whether a failed request may succeed if retried, here or on another endpoint
+fn is_transient(err: &RpcError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(err) => err
+            .status()
+            .is_none_or(|status| status.as_u16() == 429 || status.is_server_error()),
+        ClientErrorKind::RpcError(RpcRequestError::RpcRequestError(_)) => true,
+        ClientErrorKind::RpcError(RpcRequestError::RpcResponseError { code, .. }) => matches!(
+            *code,
+            JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                | JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                | JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET
+                | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,121 @@
+//! Signers that keep keys off the machine sending transactions.
+//!
+//! Oracle verification and marketplace fee operations only need something implementing
+//! [`Signer`], so a hot keypair can be swapped for:
+//! - [`RemoteSigner`], which hands each message to an external signer (HSM, KMS, signing
+//!   service) and checks the returned signature against the expected pubkey.
+//! - [`ledger`] (feature `ledger`), a Ledger device addressed as `usb://ledger[/<pubkey>][?key=<account>[/<change>]]`.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::{Signer, SignerError};
+
+/// Signs one message outside this process
+pub type SignFn = dyn Fn(&[u8]) -> Result<Signature, SignerError> + Send + Sync;
+
+/// Signer backed by an external signing service. Every signature is verified before it is
+/// returned, so a misconfigured or compromised backend cannot produce an invalid transaction.
+pub struct RemoteSigner {
+    pubkey: Pubkey,
+    sign: Box<SignFn>,
+}
+
+impl RemoteSigner {
+    pub fn new(pubkey: Pubkey, sign: impl Fn(&[u8]) -> Result<Signature, SignerError> + Send + Sync + 'static) -> Self {
+        Self {
+            pubkey,
+            sign: Box::new(sign),
+        }
+    }
+
+    /// Signer driven by an external program: `<program> pubkey` prints its base58 pubkey and
+    /// `<program> sign` reads a message on stdin and prints its base58 signature
+    pub fn command(program: &str) -> Result<Self, SignerError> {
+        let pubkey = run(program, "pubkey", &[])?;
+        let pubkey = Pubkey::from_str(&pubkey)
+            .map_err(|err| SignerError::Protocol(format!("{} returned an invalid pubkey: {}", program, err)))?;
+        let program = program.to_string();
+        Ok(Self::new(pubkey, move |message| {
+            let signature = run(&program, "sign", message)?;
+            Signature::from_str(&signature)
+                .map_err(|err| SignerError::Protocol(format!("{} returned an invalid signature: {}", program, err)))
+        }))
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let signature = (self.sign)(message)?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Protocol(format!(
+                "remote signer returned a signature not made by {}",
+                self.pubkey
+            )));
+        }
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// Run `program action` with `input` on stdin and return its trimmed stdout
+fn run(program: &str, action: &str, input: &[u8]) -> Result<String, SignerError> {
+    let mut child = Command::new(program)
+        .arg(action)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| SignerError::Connection(format!("failed to start {}: {}", program, err)))?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input)
+        .map_err(|err| SignerError::Connection(format!("failed to write to {}: {}", program, err)))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|err| SignerError::Connection(format!("{} failed: {}", program, err)))?;
+    if !output.status.success() {
+        return Err(SignerError::Custom(format!("{} {} exited with {}", program, action, output.status)));
+    }
+    String::from_utf8(output.stdout)
+        .map(|stdout| stdout.trim().to_string())
+        .map_err(|_| SignerError::Protocol(format!("{} printed non-UTF-8 output", program)))
+}
+
+/// Connect to a Ledger at `uri`, e.g. `usb://ledger?key=0`. With `confirm_key` the device asks
+/// the operator to approve the derived pubkey before it is used.
+#[cfg(feature = "ledger")]
+pub fn ledger(uri: &str, confirm_key: bool) -> Result<solana_remote_wallet::remote_keypair::RemoteKeypair, SignerError> {
+    use solana_remote_wallet::locator::Locator;
+    use solana_remote_wallet::remote_keypair::generate_remote_keypair;
+    use solana_remote_wallet::remote_wallet::maybe_wallet_manager;
+    use solana_sdk::derivation_path::DerivationPath;
+
+    let (locator, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let derivation_path = match query.strip_prefix("key=") {
+        Some(key) => DerivationPath::from_key_str(key).map_err(|err| SignerError::InvalidInput(err.to_string()))?,
+        None if query.is_empty() => DerivationPath::default(),
+        None => return Err(SignerError::InvalidInput(format!("unsupported query in {}", uri))),
+    };
+    let locator = Locator::new_from_path(locator).map_err(|err| SignerError::InvalidInput(err.to_string()))?;
+    let wallet_manager = maybe_wallet_manager()?.ok_or(SignerError::NoDeviceFound)?;
+    Ok(generate_remote_keypair(
+        locator,
+        derivation_path,
+        &wallet_manager,
+        confirm_key,
+        "signer",
+    )?)
+}
@@ -0,0 +1,140 @@
+//! Merkle proofs of identity state against the root the identity program publishes in its
+//! `StateSnapshot` account.
+//!
+//! The tree is rebuilt off chain from the same identities, in the same order (by account address),
+//! that the snapshot crank folds in, so its root matches the published one for the same state. A
+//! light client or another chain holding that root checks an [`IdentityProof`] without reading any
+//! identity account.
+
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
+use datasov_identity::{IdentityAccount, IdentityStatus, StateSnapshot, VerificationLevel};
+use solana_sdk::pubkey::Pubkey;
+
+/// The identity fields a snapshot commits to
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IdentityLeaf {
+    pub identity_id: String,
+    pub status: IdentityStatus,
+    pub verification_level: VerificationLevel,
+    pub owner: Pubkey,
+}
+
+impl IdentityLeaf {
+    pub fn hash(&self) -> [u8; 32] {
+        StateSnapshot::leaf(&self.identity_id, &self.status, &self.verification_level, &self.owner)
+    }
+}
+
+impl From<&IdentityAccount> for IdentityLeaf {
+    fn from(identity: &IdentityAccount) -> Self {
+        Self {
+            identity_id: identity.identity_id.clone(),
+            status: identity.status.clone(),
+            verification_level: identity.verification_level.clone(),
+            owner: identity.owner,
+        }
+    }
+}
+
+/// Proof that an identity was in a given state as of a snapshot
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IdentityProof {
+    pub leaf: IdentityLeaf,
+    /// Position of the leaf in the tree
+    pub index: u64,
+    /// Sibling hashes from the leaf up to the root, `StateSnapshot::DEPTH` of them
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl IdentityProof {
+    /// Root the proof leads to
+    pub fn root(&self) -> [u8; 32] {
+        let mut node = self.leaf.hash();
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = match (self.index >> level) & 1 {
+                0 => StateSnapshot::node(&node, sibling),
+                _ => StateSnapshot::node(sibling, &node),
+            };
+        }
+        node
+    }
+
+    /// Whether the proof is complete and leads to `root`
+    pub fn verify(&self, root: &[u8; 32]) -> bool {
+        self.siblings.len() == StateSnapshot::DEPTH && self.root() == *root
+    }
+}
+
+/// Every level of the snapshot tree, for generating proofs
+pub struct IdentityTree {
+    addresses: Vec<Pubkey>,
+    leaves: Vec<IdentityLeaf>,
+    /// Filled nodes per level, leaves first; nodes past the end are empty subtrees
+    levels: Vec<Vec<[u8; 32]>>,
+    /// Root of an empty subtree per level
+    zeros: Vec<[u8; 32]>,
+}
+
+impl IdentityTree {
+    /// Tree over `identities`, keyed by account address
+    pub fn new(identities: impl IntoIterator<Item = (Pubkey, IdentityAccount)>) -> Self {
+        let mut identities: Vec<(Pubkey, IdentityLeaf)> = identities
+            .into_iter()
+            .map(|(address, identity)| (address, IdentityLeaf::from(&identity)))
+            .collect();
+        identities.sort_by_key(|(address, _)| *address);
+        let (addresses, leaves): (Vec<Pubkey>, Vec<IdentityLeaf>) = identities.into_iter().unzip();
+
+        let mut zeros = vec![[0u8; 32]];
+        let mut levels = vec![leaves.iter().map(IdentityLeaf::hash).collect::<Vec<_>>()];
+        for level in 0..StateSnapshot::DEPTH {
+            let zero = zeros[level];
+            let next = levels[level]
+                .chunks(2)
+                .map(|pair| StateSnapshot::node(&pair[0], pair.get(1).unwrap_or(&zero)))
+                .collect();
+            levels.push(next);
+            zeros.push(StateSnapshot::node(&zero, &zero));
+        }
+        Self {
+            addresses,
+            leaves,
+            levels,
+            zeros,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Root to compare with the published `StateSnapshot::root`
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[StateSnapshot::DEPTH]
+            .first()
+            .copied()
+            .unwrap_or(self.zeros[StateSnapshot::DEPTH])
+    }
+
+    /// Proof for the identity account at `address`, if it is in the tree
+    pub fn proof(&self, address: &Pubkey) -> Option<IdentityProof> {
+        let index = self.addresses.binary_search(address).ok()?;
+        let siblings = (0..StateSnapshot::DEPTH)
+            .map(|level| {
+                self.levels[level]
+                    .get((index >> level) ^ 1)
+                    .copied()
+                    .unwrap_or(self.zeros[level])
+            })
+            .collect();
+        Some(IdentityProof {
+            leaf: self.leaves[index].clone(),
+            index: index as u64,
+            siblings,
+        })
+    }
+}
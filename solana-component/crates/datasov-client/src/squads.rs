@@ -0,0 +1,62 @@
+//! Squads v4 multisig vaults as registry and marketplace authorities.
+//!
+//! A vault is a system-owned PDA of the Squads program, so the DataSov programs accept it as a
+//! signer like any key. To hand it a role, the current authority sends
+//! [`admin::set_registry_authority`](crate::admin::set_registry_authority) or
+//! [`admin::set_marketplace_authority`](crate::admin::set_marketplace_authority) with the
+//! [`vault`] address. From then on, admin actions are multisig proposals: build the instructions
+//! with [`crate::admin`] and the vault as authority, then pass [`transaction_message`] of them to
+//! Squads' `vault_transaction_create`.
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+/// Squads v4 program
+pub const PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_MULTISIG: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+
+/// Multisig account created with `create_key`
+pub fn multisig(create_key: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SEED_PREFIX, SEED_MULTISIG, create_key.as_ref()], &PROGRAM_ID).0
+}
+
+/// Vault `index` of `multisig`; index 0 is the default vault
+pub fn vault(multisig: &Pubkey, index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[SEED_PREFIX, multisig.as_ref(), SEED_VAULT, &[index]], &PROGRAM_ID).0
+}
+
+/// `instructions`, to run with `vault` as signer and fee payer, encoded as the Squads
+/// `TransactionMessage` that `vault_transaction_create` takes
+pub fn transaction_message(vault: &Pubkey, instructions: &[Instruction]) -> Vec<u8> {
+    let message = Message::new(instructions, Some(vault));
+    let header = message.header;
+    let num_signers = header.num_required_signatures;
+    let num_writable_non_signers =
+        message.account_keys.len() as u8 - num_signers - header.num_readonly_unsigned_accounts;
+
+    let mut data = vec![
+        num_signers,
+        num_signers - header.num_readonly_signed_accounts,
+        num_writable_non_signers,
+    ];
+    data.push(message.account_keys.len() as u8);
+    for key in &message.account_keys {
+        data.extend_from_slice(key.as_ref());
+    }
+    data.push(message.instructions.len() as u8);
+    for instruction in &message.instructions {
+        data.push(instruction.program_id_index);
+        data.push(instruction.accounts.len() as u8);
+        data.extend_from_slice(&instruction.accounts);
+        data.extend_from_slice(&(instruction.data.len() as u16).to_le_bytes());
+        data.extend_from_slice(&instruction.data);
+    }
+    // No address lookup tables
+    data.push(0);
+    data
+}
@@ -0,0 +1,69 @@
+//! Storage pointers as `<scheme>://<reference>` URIs, the form the CLI and HTTP front ends read and
+//! print them in: `ar://<transaction ID>`, `ipfs://<CID>`, `shdw://<storage account>/<file name>`
+//! and `fil://<deal ID>`. A bare reference is an Arweave transaction ID, the only backend before
+//! pointers named theirs.
+//!
+//! Datasets too large for one Arweave transaction are listed chunk by chunk in a
+//! [`DataManifest`](datasov_solana::DataManifest); [`manifest_chunk`] and [`chunk_tx_id`] convert
+//! its entries to and from transaction IDs.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use datasov_identity::{StorageBackend, StoragePointer};
+use datasov_solana::ManifestChunk;
+use sha2::{Digest, Sha256};
+
+/// Read a pointer from its URI, checking the reference is well formed for its backend
+pub fn parse(value: &str) -> Result<StoragePointer, String> {
+    let (backend, reference) = match value.split_once("://") {
+        Some((scheme, reference)) => {
+            let backend = StorageBackend::ALL
+                .into_iter()
+                .find(|backend| backend.scheme() == scheme)
+                .ok_or_else(|| {
+                    let schemes: Vec<_> = StorageBackend::ALL.iter().map(StorageBackend::scheme).collect();
+                    format!(
+                        "unknown storage scheme `{}`, expected one of: {}",
+                        scheme,
+                        schemes.join(", ")
+                    )
+                })?;
+            (backend, reference)
+        }
+        None => (StorageBackend::Arweave, value),
+    };
+    let pointer = StoragePointer {
+        backend,
+        reference: reference.to_string(),
+    };
+    if !pointer.is_valid() {
+        return Err(format!("`{}` is not a well-formed {:?} reference", reference, backend));
+    }
+    Ok(pointer)
+}
+
+/// Well-formed Arweave pointer for a document that was never uploaded, as demo and test identities
+/// carry: the unpadded base64url of `label`'s SHA-256, shaped like a transaction ID
+pub fn placeholder(label: &str) -> StoragePointer {
+    StoragePointer::arweave(URL_SAFE_NO_PAD.encode(Sha256::digest(label.as_bytes())))
+}
+
+/// Manifest entry for a chunk uploaded as Arweave transaction `tx_id`, `stored` being its bytes as
+/// uploaded
+pub fn manifest_chunk(tx_id: &str, stored: &[u8]) -> Result<ManifestChunk, String> {
+    let tx_id = URL_SAFE_NO_PAD
+        .decode(tx_id)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| format!("`{}` is not an Arweave transaction ID", tx_id))?;
+    Ok(ManifestChunk {
+        tx_id,
+        size: stored.len() as u64,
+        hash: Sha256::digest(stored).into(),
+    })
+}
+
+/// Arweave transaction ID of a manifest chunk, in its base64url form
+pub fn chunk_tx_id(chunk: &ManifestChunk) -> String {
+    URL_SAFE_NO_PAD.encode(chunk.tx_id)
+}
@@ -0,0 +1,243 @@
+//! Typed account subscriptions over the websocket.
+//!
+//! Each [`Subscription`] is a stream of decoded [`AccountUpdate`]s fed by a background task,
+//! which reconnects with backoff and resubscribes whenever the websocket drops. Changes made
+//! while disconnected are not replayed, so refetch over [`DataSovRpc`] if a gap matters.
+//! Dropping the subscription stops its task.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use anchor_lang::{AccountDeserialize, Discriminator, Owner};
+use datasov_identity::{AccessPermission, IdentityAccount};
+use datasov_solana::DataListing;
+use futures_util::stream::{select_all, BoxStream, Stream, StreamExt};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::nonblocking::pubsub_client::{PubsubClient, PubsubClientError};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use crate::query::{IdentityQuery, ListingQuery, PermissionQuery};
+use crate::rpc::{DataSovRpc, RetryPolicy};
+use crate::{pda, Result};
+
+/// A change to a subscribed account
+#[derive(Debug, Clone)]
+pub struct AccountUpdate<T> {
+    pub address: Pubkey,
+    pub slot: Slot,
+    /// `None` once the account is closed
+    pub account: Option<T>,
+}
+
+/// Stream of updates to one or more accounts; ends only if there is nothing to watch
+pub struct Subscription<T> {
+    updates: UnboundedReceiver<AccountUpdate<T>>,
+    task: JoinHandle<()>,
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = AccountUpdate<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.updates.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// What a subscription watches
+#[derive(Clone)]
+enum Source {
+    Account(Pubkey),
+    /// Accounts of a program matching every filter
+    Program(Pubkey, Vec<RpcFilterType>),
+}
+
+impl Source {
+    /// Every account of type `T` matching `filters`
+    fn accounts_of<T: Discriminator + Owner>(filters: Vec<RpcFilterType>) -> Self {
+        let mut filters = filters;
+        filters.insert(
+            0,
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR)),
+        );
+        Source::Program(T::owner(), filters)
+    }
+}
+
+pub struct DataSovSubscriber {
+    ws_url: String,
+    commitment: CommitmentConfig,
+    retry: RetryPolicy,
+}
+
+impl DataSovSubscriber {
+    /// Subscribe over the websocket endpoint `ws_url`, at confirmed commitment
+    pub fn new(ws_url: String) -> Self {
+        Self {
+            ws_url,
+            commitment: CommitmentConfig::confirmed(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Reconnects wait `initial_backoff`, doubling up to `max_backoff`; `max_attempts` is unused,
+    /// as a subscription retries for as long as it is held
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn subscribe_identity(&self, identity_id: &str) -> Subscription<IdentityAccount> {
+        self.subscribe(vec![Source::Account(pda::identity::identity(identity_id))])
+    }
+
+    /// Permissions on every identity `owner` holds when subscribing; identities registered later
+    /// are not covered. The stream ends at once if `owner` holds none.
+    pub async fn subscribe_permissions(
+        &self,
+        rpc: &DataSovRpc,
+        owner: &Pubkey,
+    ) -> Result<Subscription<AccessPermission>> {
+        let identities = rpc
+            .fetch_all::<IdentityAccount>(IdentityQuery::new().owner(owner).filters())
+            .await?;
+        Ok(self.subscribe(
+            identities
+                .iter()
+                .map(|(_, identity)| {
+                    Source::accounts_of::<AccessPermission>(
+                        PermissionQuery::new().identity_id(&identity.identity_id).filters(),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    /// Listings matching `filter`, including ones created after subscribing
+    pub fn subscribe_listings(&self, filter: &ListingQuery) -> Subscription<DataListing> {
+        self.subscribe(vec![Source::accounts_of::<DataListing>(filter.filters())])
+    }
+
+    fn subscribe<T: AccountDeserialize + Send + 'static>(&self, sources: Vec<Source>) -> Subscription<T> {
+        let (sender, updates) = mpsc::unbounded_channel();
+        let task = tokio::spawn(forward(
+            self.ws_url.clone(),
+            self.commitment,
+            self.retry.clone(),
+            sources,
+            sender,
+        ));
+        Subscription { updates, task }
+    }
+}
+
+/// Connect, stream updates into `sender` and reconnect whenever the websocket drops, until the
+/// subscription is dropped
+async fn forward<T: AccountDeserialize>(
+    ws_url: String,
+    commitment: CommitmentConfig,
+    retry: RetryPolicy,
+    sources: Vec<Source>,
+    sender: UnboundedSender<AccountUpdate<T>>,
+) {
+    if sources.is_empty() {
+        return;
+    }
+    let mut backoff = retry.initial_backoff;
+    while !sender.is_closed() {
+        if let Ok(pubsub) = PubsubClient::new(&ws_url).await {
+            // A subscription that was up starts its backoff over
+            if stream_updates(&pubsub, commitment, &sources, &sender).await.is_ok() {
+                backoff = retry.initial_backoff;
+            }
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(retry.max_backoff);
+    }
+}
+
+/// Subscribe to every source and forward decoded updates until the websocket drops. Updates that
+/// fail to decode (e.g. from an older layout) are skipped.
+async fn stream_updates<T: AccountDeserialize>(
+    pubsub: &PubsubClient,
+    commitment: CommitmentConfig,
+    sources: &[Source],
+    sender: &UnboundedSender<AccountUpdate<T>>,
+) -> std::result::Result<(), PubsubClientError> {
+    let account_config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(commitment),
+        ..RpcAccountInfoConfig::default()
+    };
+    let mut streams: Vec<BoxStream<'_, (Pubkey, Slot, UiAccount)>> = Vec::new();
+    let mut unsubscribes = Vec::new();
+    for source in sources {
+        match source {
+            Source::Account(address) => {
+                let address = *address;
+                let (accounts, unsubscribe) = pubsub.account_subscribe(&address, Some(account_config.clone())).await?;
+                unsubscribes.push(unsubscribe);
+                streams.push(
+                    accounts
+                        .map(move |response| (address, response.context.slot, response.value))
+                        .boxed(),
+                );
+            }
+            Source::Program(program_id, filters) => {
+                let config = RpcProgramAccountsConfig {
+                    filters: Some(filters.clone()),
+                    account_config: account_config.clone(),
+                    ..RpcProgramAccountsConfig::default()
+                };
+                let (accounts, unsubscribe) = pubsub.program_subscribe(program_id, Some(config)).await?;
+                unsubscribes.push(unsubscribe);
+                streams.push(
+                    accounts
+                        .filter_map(|response| async move {
+                            let address = response.value.pubkey.parse().ok()?;
+                            Some((address, response.context.slot, response.value.account))
+                        })
+                        .boxed(),
+                );
+            }
+        }
+    }
+
+    let mut updates = select_all(streams);
+    while let Some((address, slot, account)) = updates.next().await {
+        let account = match account.decode::<Account>() {
+            // Closed accounts are reported with zero lamports
+            Some(account) if account.lamports > 0 => match T::try_deserialize(&mut account.data.as_slice()) {
+                Ok(decoded) => Some(decoded),
+                Err(_) => continue,
+            },
+            _ => None,
+        };
+        if sender.send(AccountUpdate { address, slot, account }).is_err() {
+            break;
+        }
+    }
+
+    drop(updates);
+    for unsubscribe in unsubscribes {
+        unsubscribe().await;
+    }
+    Ok(())
+}
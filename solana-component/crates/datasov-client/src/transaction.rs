@@ -0,0 +1,116 @@
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+
+use crate::{compute, Result};
+
+/// Collects instructions into a single transaction
+#[derive(Debug, Default, Clone)]
+pub struct TransactionBuilder {
+    instructions: Vec<Instruction>,
+    /// Compute unit limit and price, in micro-lamports per unit, prepended when building
+    compute_budget: Option<(u32, u64)>,
+    /// Lookup tables the versioned transaction resolves accounts through
+    lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl TransactionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an instruction
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Request `units` compute units at `micro_lamports` per unit
+    pub fn compute_budget(mut self, units: u32, micro_lamports: u64) -> Self {
+        self.compute_budget = Some((units, micro_lamports));
+        self
+    }
+
+    /// Request the compute unit ceiling of the instructions added so far at `micro_lamports` per unit
+    pub fn priority_fee(self, micro_lamports: u64) -> Self {
+        let units = compute::transaction_units(&self.instructions);
+        self.compute_budget(units, micro_lamports)
+    }
+
+    /// Resolve accounts through `table` (see [`crate::lookup_table`]); only the versioned builds use it
+    pub fn lookup_table(mut self, table: AddressLookupTableAccount) -> Self {
+        self.lookup_tables.push(table);
+        self
+    }
+
+    /// Instructions added, without the compute budget ones
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Requested compute unit limit and price, if any
+    pub fn requested_compute_budget(&self) -> Option<(u32, u64)> {
+        self.compute_budget
+    }
+
+    /// Lookup tables added
+    pub fn lookup_tables(&self) -> &[AddressLookupTableAccount] {
+        &self.lookup_tables
+    }
+
+    /// Instructions to send, compute budget first
+    fn all_instructions(&self) -> Vec<Instruction> {
+        let mut instructions = match self.compute_budget {
+            Some((units, micro_lamports)) => compute::budget_instructions(units, micro_lamports),
+            None => Vec::new(),
+        };
+        instructions.extend(self.instructions.iter().cloned());
+        instructions
+    }
+
+    /// Unsigned transaction paid by `payer`, for wallets to sign
+    pub fn build_unsigned(&self, payer: &Pubkey) -> Transaction {
+        Transaction::new_with_payer(&self.all_instructions(), Some(payer))
+    }
+
+    /// Signed transaction; `signers` must include the payer
+    pub fn build_signed(&self, payer: &Pubkey, signers: &[&dyn Signer], recent_blockhash: Hash) -> Result<Transaction> {
+        let mut transaction = self.build_unsigned(payer);
+        transaction.try_sign(signers, recent_blockhash)?;
+        Ok(transaction)
+    }
+
+    /// Message paid by `payer`: v0 through the lookup tables if any were added, legacy otherwise
+    pub fn build_message(&self, payer: &Pubkey, recent_blockhash: Hash) -> Result<VersionedMessage> {
+        if self.lookup_tables.is_empty() {
+            let mut message = self.build_unsigned(payer).message;
+            message.recent_blockhash = recent_blockhash;
+            return Ok(VersionedMessage::Legacy(message));
+        }
+        let message = v0::Message::try_compile(payer, &self.all_instructions(), &self.lookup_tables, recent_blockhash)?;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    /// Unsigned versioned transaction, with placeholder signatures, for simulation or wallets to sign
+    pub fn build_versioned_unsigned(&self, payer: &Pubkey, recent_blockhash: Hash) -> Result<VersionedTransaction> {
+        let message = self.build_message(payer, recent_blockhash)?;
+        let signatures = vec![Signature::default(); message.header().num_required_signatures as usize];
+        Ok(VersionedTransaction { signatures, message })
+    }
+
+    /// Signed versioned transaction; `signers` must include the payer
+    pub fn build_versioned(
+        &self,
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction> {
+        let message = self.build_message(payer, recent_blockhash)?;
+        Ok(VersionedTransaction::try_new(message, signers)?)
+    }
+}
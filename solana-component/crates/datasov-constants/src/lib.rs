@@ -0,0 +1,177 @@
+//! Error codes, PDA seeds and account sizes of the DataSov programs, for off-chain code.
+//!
+//! Everything here is re-exported from or computed by the program crates, so SDKs, indexers and
+//! tests stay in step with the deployed programs without copying seeds or sizes by hand.
+//!
+//! - [`seeds`] has the seed prefix of every PDA, per owning program.
+//! - [`errors`] has each program's `ErrorCode`; `u32::from(code)` is the custom error code a failed
+//!   transaction reports, and its `Display` is the message.
+//! - [`sizes`] has the allocated size of every account type, discriminator included, as passed
+//!   to `getProgramAccounts` data-size filters.
+
+/// PDA seed prefixes, per owning program
+pub mod seeds {
+    pub mod identity {
+        pub use datasov_identity::seeds::*;
+    }
+
+    pub mod marketplace {
+        pub use datasov_solana::seeds::*;
+    }
+
+    pub mod audit {
+        pub use datasov_audit::seeds::*;
+    }
+
+    pub mod governance {
+        pub use datasov_governance::seeds::*;
+    }
+
+    pub mod timelock {
+        pub use datasov_timelock::seeds::*;
+    }
+
+    pub mod staking {
+        pub use datasov_staking::seeds::*;
+    }
+}
+
+/// Program error codes
+pub mod errors {
+    /// Custom error code of each program's first `ErrorCode` variant
+    pub use anchor_lang::error::ERROR_CODE_OFFSET;
+
+    pub use datasov_audit::ErrorCode as AuditError;
+    pub use datasov_governance::ErrorCode as GovernanceError;
+    pub use datasov_identity::ErrorCode as IdentityError;
+    pub use datasov_solana::ErrorCode as MarketplaceError;
+    pub use datasov_staking::ErrorCode as StakingError;
+    pub use datasov_timelock::ErrorCode as TimelockError;
+}
+
+/// Allocated account sizes in bytes, including the 8-byte discriminator
+pub mod sizes {
+    use anchor_lang::Space;
+
+    const DISCRIMINATOR: usize = 8;
+
+    pub mod identity {
+        use super::*;
+        use datasov_identity::{
+            AccessPermission, AccessRequest, AttestationSource, Blacklist, ComplianceMatrix, ComplianceOfficer,
+            ConsumerProfile, ConsumerStake, ConsumptionAttestation, CrankFund, DataTrust, Dispute, DomainLink,
+            ExportRequest, IdentityAccount, IdentityFreeze, Juror, JurorPool, KYCOracle, KYCOracleRegistry,
+            StateSnapshot, TeeAttestation, TrialRecord, TrustMembership, VoucherNonce, WebProofNullifier,
+            WebProofVerifier,
+        };
+
+        pub const ORACLE_REGISTRY: usize = DISCRIMINATOR + KYCOracleRegistry::INIT_SPACE;
+        pub const ORACLE: usize = DISCRIMINATOR + KYCOracle::INIT_SPACE;
+        pub const IDENTITY: usize = DISCRIMINATOR + IdentityAccount::INIT_SPACE;
+        pub const PERMISSION: usize = DISCRIMINATOR + AccessPermission::INIT_SPACE;
+        pub const COMPLIANCE_MATRIX: usize = DISCRIMINATOR + ComplianceMatrix::INIT_SPACE;
+        pub const CRANK_FUND: usize = DISCRIMINATOR + CrankFund::INIT_SPACE;
+        pub const BLACKLIST: usize = DISCRIMINATOR + Blacklist::INIT_SPACE;
+        pub const CONSUMER_PROFILE: usize = DISCRIMINATOR + ConsumerProfile::INIT_SPACE;
+        pub const CONSUMER_STAKE: usize = DISCRIMINATOR + ConsumerStake::INIT_SPACE;
+        pub const TRIAL_RECORD: usize = DISCRIMINATOR + TrialRecord::INIT_SPACE;
+        pub const JUROR_POOL: usize = DISCRIMINATOR + JurorPool::INIT_SPACE;
+        pub const JUROR: usize = DISCRIMINATOR + Juror::INIT_SPACE;
+        pub const DISPUTE: usize = DISCRIMINATOR + Dispute::INIT_SPACE;
+        pub const TEE_ATTESTATION: usize = DISCRIMINATOR + TeeAttestation::INIT_SPACE;
+        pub const DATA_TRUST: usize = DISCRIMINATOR + DataTrust::INIT_SPACE;
+        pub const TRUST_MEMBERSHIP: usize = DISCRIMINATOR + TrustMembership::INIT_SPACE;
+        pub const EXPORT_REQUEST: usize = DISCRIMINATOR + ExportRequest::INIT_SPACE;
+        pub const CONSUMPTION_ATTESTATION: usize = DISCRIMINATOR + ConsumptionAttestation::INIT_SPACE;
+        pub const WEB_PROOF_VERIFIER: usize = DISCRIMINATOR + WebProofVerifier::INIT_SPACE;
+        pub const WEB_PROOF_NULLIFIER: usize = DISCRIMINATOR + WebProofNullifier::INIT_SPACE;
+        pub const ATTESTATION_SOURCE: usize = DISCRIMINATOR + AttestationSource::INIT_SPACE;
+        pub const DOMAIN_LINK: usize = DISCRIMINATOR + DomainLink::INIT_SPACE;
+        pub const VOUCHER_NONCE: usize = DISCRIMINATOR + VoucherNonce::INIT_SPACE;
+        pub const ACCESS_REQUEST: usize = DISCRIMINATOR + AccessRequest::INIT_SPACE;
+        pub const STATE_SNAPSHOT: usize = DISCRIMINATOR + StateSnapshot::INIT_SPACE;
+        pub const COMPLIANCE_OFFICER: usize = DISCRIMINATOR + ComplianceOfficer::INIT_SPACE;
+        pub const IDENTITY_FREEZE: usize = DISCRIMINATOR + IdentityFreeze::INIT_SPACE;
+    }
+
+    pub mod marketplace {
+        use super::*;
+        use datasov_solana::{
+            BuybackConfig, ComputeJob, Coupon, CrankFund, CurationConfig, CurationVote, DataListing, DataManifest,
+            DataUnion, EarningsLedger, FeatureFlags, FeaturedSet, FlJob, FlParticipant, LicenseTemplate,
+            ListingAllowlist, ListingTally, Marketplace, PriceCandle, PurchaseDelegate, PurchaseReceipt, RebateEpoch,
+            RebateLedger, RebateProgram, StorageHealth, StorageOracle, UnionMember,
+        };
+
+        pub const MARKETPLACE: usize = DISCRIMINATOR + Marketplace::INIT_SPACE;
+        pub const LISTING: usize = DISCRIMINATOR + DataListing::INIT_SPACE;
+        pub const LISTING_ALLOWLIST: usize = DISCRIMINATOR + ListingAllowlist::INIT_SPACE;
+        pub const DATA_MANIFEST: usize = DISCRIMINATOR + DataManifest::INIT_SPACE;
+        pub const RECEIPT: usize = DISCRIMINATOR + PurchaseReceipt::INIT_SPACE;
+        pub const CRANK_FUND: usize = DISCRIMINATOR + CrankFund::INIT_SPACE;
+        pub const COUPON: usize = DISCRIMINATOR + Coupon::INIT_SPACE;
+        pub const PURCHASE_DELEGATE: usize = DISCRIMINATOR + PurchaseDelegate::INIT_SPACE;
+        pub const COMPUTE_JOB: usize = DISCRIMINATOR + ComputeJob::INIT_SPACE;
+        pub const FL_JOB: usize = DISCRIMINATOR + FlJob::INIT_SPACE;
+        pub const FL_PARTICIPANT: usize = DISCRIMINATOR + FlParticipant::INIT_SPACE;
+        pub const DATA_UNION: usize = DISCRIMINATOR + DataUnion::INIT_SPACE;
+        pub const UNION_MEMBER: usize = DISCRIMINATOR + UnionMember::INIT_SPACE;
+        pub const REBATE_PROGRAM: usize = DISCRIMINATOR + RebateProgram::INIT_SPACE;
+        pub const REBATE_EPOCH: usize = DISCRIMINATOR + RebateEpoch::INIT_SPACE;
+        pub const REBATE_LEDGER: usize = DISCRIMINATOR + RebateLedger::INIT_SPACE;
+        pub const BUYBACK: usize = DISCRIMINATOR + BuybackConfig::INIT_SPACE;
+        pub const CURATION: usize = DISCRIMINATOR + CurationConfig::INIT_SPACE;
+        pub const FEATURED_SET: usize = DISCRIMINATOR + FeaturedSet::INIT_SPACE;
+        pub const LISTING_TALLY: usize = DISCRIMINATOR + ListingTally::INIT_SPACE;
+        pub const CURATION_VOTE: usize = DISCRIMINATOR + CurationVote::INIT_SPACE;
+        pub const STORAGE_ORACLE: usize = DISCRIMINATOR + StorageOracle::INIT_SPACE;
+        pub const STORAGE_HEALTH: usize = DISCRIMINATOR + StorageHealth::INIT_SPACE;
+        pub const LICENSE_TEMPLATE: usize = DISCRIMINATOR + LicenseTemplate::INIT_SPACE;
+        pub const PRICE_CANDLE: usize = DISCRIMINATOR + PriceCandle::INIT_SPACE;
+        pub const EARNINGS_LEDGER: usize = DISCRIMINATOR + EarningsLedger::INIT_SPACE;
+        pub const FEATURE_FLAGS: usize = DISCRIMINATOR + FeatureFlags::INIT_SPACE;
+    }
+
+    pub mod audit {
+        use super::*;
+        use datasov_audit::{AuditCheckpoint, AuditLog, AuditPage};
+
+        pub const AUDIT_LOG: usize = DISCRIMINATOR + AuditLog::INIT_SPACE;
+        pub const AUDIT_PAGE: usize = DISCRIMINATOR + AuditPage::INIT_SPACE;
+        pub const AUDIT_CHECKPOINT: usize = DISCRIMINATOR + AuditCheckpoint::INIT_SPACE;
+    }
+
+    pub mod governance {
+        use super::*;
+        use datasov_governance::{Governance, Proposal, StakeAccount, VoteRecord};
+
+        pub const GOVERNANCE: usize = DISCRIMINATOR + Governance::INIT_SPACE;
+        pub const STAKE: usize = DISCRIMINATOR + StakeAccount::INIT_SPACE;
+        pub const PROPOSAL: usize = DISCRIMINATOR + Proposal::INIT_SPACE;
+        pub const VOTE: usize = DISCRIMINATOR + VoteRecord::INIT_SPACE;
+    }
+
+    pub mod timelock {
+        use super::*;
+        use datasov_timelock::{Operation, Timelock};
+
+        pub const TIMELOCK: usize = DISCRIMINATOR + Timelock::INIT_SPACE;
+        pub const OPERATION: usize = DISCRIMINATOR + Operation::INIT_SPACE;
+    }
+
+    pub mod staking {
+        use super::*;
+        use datasov_staking::{
+            Airdrop, AirdropBitmap, Backing, DisputeSlash, StakePosition, StakeWeight, StakingConfig, Vesting,
+        };
+
+        pub const CONFIG: usize = DISCRIMINATOR + StakingConfig::INIT_SPACE;
+        pub const BACKING: usize = DISCRIMINATOR + Backing::INIT_SPACE;
+        pub const POSITION: usize = DISCRIMINATOR + StakePosition::INIT_SPACE;
+        pub const STAKE_WEIGHT: usize = DISCRIMINATOR + StakeWeight::INIT_SPACE;
+        pub const DISPUTE_SLASH: usize = DISCRIMINATOR + DisputeSlash::INIT_SPACE;
+        pub const VESTING: usize = DISCRIMINATOR + Vesting::INIT_SPACE;
+        pub const AIRDROP: usize = DISCRIMINATOR + Airdrop::INIT_SPACE;
+        pub const AIRDROP_BITMAP: usize = DISCRIMINATOR + AirdropBitmap::INIT_SPACE;
+    }
+}
@@ -0,0 +1,163 @@
+//! `datasov-crank`: publish the identity state snapshot.
+//!
+//! Every `--interval` seconds, starts a new snapshot, folds in every identity account in address
+//! order, `--batch-size` to a transaction, and publishes the resulting Merkle root. The root is
+//! checked against one computed off chain from the same accounts; identities that change while a
+//! snapshot is being built can make the two differ, which is logged and corrected by the next run.
+//! With `--interval 0` the crank publishes once and exits.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use datasov_client::identity::{accounts, instruction, IdentityAccount};
+use datasov_client::rpc::{DataSovRpc, RpcClient};
+use datasov_client::snapshot::IdentityTree;
+use datasov_client::{instructions, pda, TransactionBuilder};
+use log::{error, info, warn};
+use solana_cli_config::{Config, CONFIG_FILE};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signer::Signer;
+
+#[derive(Parser)]
+#[clap(
+    name = "datasov-crank",
+    version,
+    about = "Publish the DataSov identity state snapshot"
+)]
+struct Args {
+    /// JSON-RPC URL [default: from the Solana CLI config]
+    #[clap(long, short = 'u')]
+    url: Option<String>,
+
+    /// Crank keypair file, also paying fees [default: from the Solana CLI config]
+    #[clap(long, short = 'k')]
+    keypair: Option<String>,
+
+    /// Solana CLI config file
+    #[clap(long, short = 'C')]
+    config: Option<String>,
+
+    /// Seconds between snapshots; 0 publishes once and exits
+    #[clap(long, default_value_t = 3600)]
+    interval: u64,
+
+    /// Identities folded in per transaction
+    #[clap(long, default_value_t = 16)]
+    batch_size: usize,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+    if args.batch_size == 0 {
+        bail!("--batch-size must be at least 1");
+    }
+
+    let config = match args.config.as_deref().or(CONFIG_FILE.as_deref()) {
+        Some(path) => Config::load(path).unwrap_or_default(),
+        None => Config::default(),
+    };
+    let url = args.url.clone().unwrap_or(config.json_rpc_url);
+    let keypair_path = args.keypair.clone().unwrap_or(config.keypair_path);
+    let crank = read_keypair_file(&keypair_path).map_err(|err| anyhow!("failed to read {}: {}", keypair_path, err))?;
+    let rpc = DataSovRpc::from_client(RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()));
+
+    let snapshot = rpc.fetch_state_snapshot().await?;
+    if snapshot.crank != crank.pubkey() {
+        bail!("{} is not the snapshot crank ({})", crank.pubkey(), snapshot.crank);
+    }
+
+    loop {
+        match publish(&rpc, &crank, args.batch_size).await {
+            Ok(()) => {}
+            Err(err) if args.interval == 0 => return Err(err),
+            Err(err) => error!("Snapshot failed: {:#}", err),
+        }
+        if args.interval == 0 {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(args.interval)).await;
+    }
+}
+
+/// Build and publish one snapshot of every identity
+async fn publish(rpc: &DataSovRpc, crank: &Keypair, batch_size: usize) -> Result<()> {
+    let identities = rpc.fetch_all::<IdentityAccount>(Vec::new()).await?;
+    let mut addresses: Vec<Pubkey> = identities.iter().map(|(address, _)| *address).collect();
+    addresses.sort();
+    let expected = IdentityTree::new(identities);
+
+    send(
+        rpc,
+        crank,
+        snapshot_instruction(crank, instruction::BeginStateSnapshot {}, &[]),
+    )
+    .await?;
+    for batch in addresses.chunks(batch_size) {
+        send(
+            rpc,
+            crank,
+            snapshot_instruction(crank, instruction::ExtendStateSnapshot {}, batch),
+        )
+        .await?;
+    }
+    send(
+        rpc,
+        crank,
+        snapshot_instruction(crank, instruction::PublishStateSnapshot {}, &[]),
+    )
+    .await?;
+
+    let snapshot = rpc.fetch_state_snapshot().await?;
+    info!(
+        "Published snapshot epoch {} over {} identities: {}",
+        snapshot.epoch,
+        snapshot.leaf_count,
+        hex(&snapshot.root)
+    );
+    if snapshot.root != expected.root() {
+        warn!(
+            "Published root differs from the {} identities read at the start ({}); an identity changed mid-build",
+            expected.len(),
+            hex(&expected.root())
+        );
+    }
+    Ok(())
+}
+
+/// A snapshot-building instruction, with `identities` as its remaining accounts
+fn snapshot_instruction<D: anchor_lang::InstructionData>(
+    crank: &Keypair,
+    args: D,
+    identities: &[Pubkey],
+) -> Instruction {
+    let mut instruction = instructions::identity(
+        accounts::BuildStateSnapshot {
+            state_snapshot: pda::identity::state_snapshot(),
+            crank: crank.pubkey(),
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        args,
+    );
+    instruction.accounts.extend(
+        identities
+            .iter()
+            .map(|address| AccountMeta::new_readonly(*address, false)),
+    );
+    instruction
+}
+
+async fn send(rpc: &DataSovRpc, crank: &Keypair, instruction: Instruction) -> Result<()> {
+    let builder = TransactionBuilder::new().instruction(instruction);
+    rpc.send(&builder, &crank.pubkey(), &[crank]).await?;
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
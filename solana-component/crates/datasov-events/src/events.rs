@@ -0,0 +1,176 @@
+//! Typed event enums for each program.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+
+/// Declares a per-program event enum with discriminator dispatch
+macro_rules! program_events {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident($event:path),)*
+        }
+    ) => {
+        $(#[$meta])*
+        pub enum $name {
+            $($variant($event),)*
+        }
+
+        impl $name {
+            /// Decode an event body by discriminator; `None` if the discriminator is not a known event.
+            /// Trailing bytes are ignored so events that gained appended fields in a newer program
+            /// version still decode.
+            pub fn decode(discriminator: &[u8; 8], data: &[u8]) -> Option<std::io::Result<Self>> {
+                $(
+                    if *discriminator == <$event as Discriminator>::DISCRIMINATOR {
+                        return Some(<$event>::deserialize(&mut &data[..]).map(Self::$variant));
+                    }
+                )*
+                None
+            }
+
+            /// Event name without the `Event` suffix, e.g. `AccessGranted`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => stringify!($variant),)*
+                }
+            }
+
+            /// Name for a discriminator without decoding the body
+            pub fn name_of(discriminator: &[u8; 8]) -> Option<&'static str> {
+                $(
+                    if *discriminator == <$event as Discriminator>::DISCRIMINATOR {
+                        return Some(stringify!($variant));
+                    }
+                )*
+                None
+            }
+        }
+    };
+}
+
+program_events! {
+    /// Events emitted by `datasov_identity`
+    pub enum IdentityEvent {
+        OracleRegistered(datasov_identity::OracleRegisteredEvent),
+        IdentityRegistered(datasov_identity::IdentityRegisteredEvent),
+        IdentityVerified(datasov_identity::IdentityVerifiedEvent),
+        IdentityUpdated(datasov_identity::IdentityUpdatedEvent),
+        IdentityRevoked(datasov_identity::IdentityRevokedEvent),
+        AccessGranted(datasov_identity::AccessGrantedEvent),
+        AccessRevoked(datasov_identity::AccessRevokedEvent),
+        ComplianceRuleUpdated(datasov_identity::ComplianceRuleUpdatedEvent),
+        ExpiredSwept(datasov_identity::ExpiredSweptEvent),
+        ConsumerBlacklisted(datasov_identity::ConsumerBlacklistedEvent),
+        ConsumerUnblacklisted(datasov_identity::ConsumerUnblacklistedEvent),
+        BlacklistedGrant(datasov_identity::BlacklistedGrantEvent),
+        ConsumerRegistered(datasov_identity::ConsumerRegisteredEvent),
+        ConsumerStaked(datasov_identity::ConsumerStakedEvent),
+        ConsumerSlashed(datasov_identity::ConsumerSlashedEvent),
+        BusinessVerified(datasov_identity::BusinessVerifiedEvent),
+        TrialAccessGranted(datasov_identity::TrialAccessGrantedEvent),
+        DisputeOpened(datasov_identity::DisputeOpenedEvent),
+        DisputePanelSelected(datasov_identity::DisputePanelSelectedEvent),
+        DisputeResolved(datasov_identity::DisputeResolvedEvent),
+        PermissionPurposesUpdated(datasov_identity::PermissionPurposesUpdatedEvent),
+        TeeAttestationVerified(datasov_identity::TeeAttestationVerifiedEvent),
+        DataTrustCreated(datasov_identity::DataTrustCreatedEvent),
+        GuardianshipUpdated(datasov_identity::GuardianshipUpdatedEvent),
+        ExportRequested(datasov_identity::ExportRequestedEvent),
+        ExportFulfilled(datasov_identity::ExportFulfilledEvent),
+        ExportOverdue(datasov_identity::ExportOverdueEvent),
+        ConsumerTermsPublished(datasov_identity::ConsumerTermsPublishedEvent),
+        ConsentUpdated(datasov_identity::ConsentUpdatedEvent),
+        PrivacyBudgetUpdated(datasov_identity::PrivacyBudgetUpdatedEvent),
+        ConsumptionAttested(datasov_identity::ConsumptionAttestedEvent),
+        WebProofVerified(datasov_identity::WebProofVerifiedEvent),
+        ExternalAttestationVerified(datasov_identity::ExternalAttestationVerifiedEvent),
+        DomainLinked(datasov_identity::DomainLinkedEvent),
+        DomainUnlinked(datasov_identity::DomainUnlinkedEvent),
+        EncryptionKeyUpdated(datasov_identity::EncryptionKeyUpdatedEvent),
+        StateSnapshotPublished(datasov_identity::StateSnapshotPublishedEvent),
+        OracleRegistryUpdated(datasov_identity::OracleRegistryUpdatedEvent),
+        RegistryAuthorityChanged(datasov_identity::RegistryAuthorityChangedEvent),
+        IdentityFrozen(datasov_identity::IdentityFrozenEvent),
+        FreezeAppealed(datasov_identity::FreezeAppealedEvent),
+        IdentityFreezeLifted(datasov_identity::IdentityFreezeLiftedEvent),
+        AccessRequested(datasov_identity::AccessRequestedEvent),
+        AccessRequestAccepted(datasov_identity::AccessRequestAcceptedEvent),
+        RentReclaimed(datasov_identity::RentReclaimedEvent),
+        SelfVerificationRejected(datasov_identity::SelfVerificationRejectedEvent),
+    }
+}
+
+program_events! {
+    /// Events emitted by `datasov_solana`
+    pub enum MarketplaceEvent {
+        DataTypeHaltUpdated(datasov_solana::DataTypeHaltUpdatedEvent),
+        ExpiredSwept(datasov_solana::ExpiredSweptEvent),
+        RentReclaimed(datasov_solana::RentReclaimedEvent),
+        SettlementMintsUpdated(datasov_solana::SettlementMintsUpdatedEvent),
+        RevocationRefunded(datasov_solana::RevocationRefundedEvent),
+        ComputeJobCreated(datasov_solana::ComputeJobCreatedEvent),
+        ComputeResultPosted(datasov_solana::ComputeResultPostedEvent),
+        FlJobCreated(datasov_solana::FlJobCreatedEvent),
+        FlContributionRecorded(datasov_solana::FlContributionRecordedEvent),
+        DataUnionMembership(datasov_solana::DataUnionMembershipEvent),
+        PurchaseWatermark(datasov_solana::PurchaseWatermarkEvent),
+        MarketplaceFeeUpdated(datasov_solana::MarketplaceFeeUpdatedEvent),
+        MarketplaceAuthorityChanged(datasov_solana::MarketplaceAuthorityChangedEvent),
+        RebateEmissionUpdated(datasov_solana::RebateEmissionUpdatedEvent),
+        RebateClaimed(datasov_solana::RebateClaimedEvent),
+        BuybackExecuted(datasov_solana::BuybackExecutedEvent),
+        ListingCurated(datasov_solana::ListingCuratedEvent),
+        CuratedListingDisputed(datasov_solana::CuratedListingDisputedEvent),
+        CurationVoteSettled(datasov_solana::CurationVoteSettledEvent),
+        DataManifestSealed(datasov_solana::DataManifestSealedEvent),
+        ListingFreshnessAttested(datasov_solana::ListingFreshnessAttestedEvent),
+        ListingDataUpdated(datasov_solana::ListingDataUpdatedEvent),
+        StorageChecked(datasov_solana::StorageCheckedEvent),
+        LicenseTemplateCreated(datasov_solana::LicenseTemplateCreatedEvent),
+        LicenseMinted(datasov_solana::LicenseMintedEvent),
+        AccessRightTransferred(datasov_solana::AccessRightTransferredEvent),
+        FeatureFlagsUpdated(datasov_solana::FeatureFlagsUpdatedEvent),
+    }
+}
+
+program_events! {
+    /// Events emitted by `datasov_audit`
+    pub enum AuditEvent {
+        AuditCheckpointed(datasov_audit::AuditCheckpointedEvent),
+    }
+}
+
+program_events! {
+    /// Events emitted by `datasov_governance`
+    pub enum GovernanceEvent {
+        ProposalCreated(datasov_governance::ProposalCreatedEvent),
+        VoteCast(datasov_governance::VoteCastEvent),
+        ProposalExecuted(datasov_governance::ProposalExecutedEvent),
+    }
+}
+
+program_events! {
+    /// Events emitted by `datasov_timelock`
+    pub enum TimelockEvent {
+        OperationQueued(datasov_timelock::OperationQueuedEvent),
+        OperationCancelled(datasov_timelock::OperationCancelledEvent),
+        OperationExecuted(datasov_timelock::OperationExecutedEvent),
+        TimelockDelayChanged(datasov_timelock::TimelockDelayChangedEvent),
+        TimelockAdminChanged(datasov_timelock::TimelockAdminChangedEvent),
+    }
+}
+
+program_events! {
+    /// Events emitted by `datasov_staking`
+    pub enum StakingEvent {
+        Staked(datasov_staking::StakedEvent),
+        StakeWithdrawn(datasov_staking::StakeWithdrawnEvent),
+        BackingSlashed(datasov_staking::BackingSlashedEvent),
+        FeeTiersUpdated(datasov_staking::FeeTiersUpdatedEvent),
+        VestingCreated(datasov_staking::VestingCreatedEvent),
+        VestedClaimed(datasov_staking::VestedClaimedEvent),
+        VestingRevoked(datasov_staking::VestingRevokedEvent),
+        AirdropCreated(datasov_staking::AirdropCreatedEvent),
+        AirdropClaimed(datasov_staking::AirdropClaimedEvent),
+    }
+}
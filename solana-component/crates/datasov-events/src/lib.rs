@@ -0,0 +1,190 @@
+//! Typed decoding of DataSov program events.
+//!
+//! Events reach clients in two shapes:
+//! - `emit!` writes `Program data: <base64>` log lines; [`parse_logs`] extracts them, attributing
+//!   each to the program executing at that point of the invoke stack.
+//! - `emit_cpi!` self-invokes with `EVENT_IX_TAG_LE ++ discriminator ++ body` as instruction data;
+//!   [`parse_cpi_event`] extracts those from inner instructions.
+//!
+//! Both produce [`RawEvent`]s, which [`RawEvent::decode`] turns into a [`DataSovEvent`].
+//!
+//! Versioning: events only ever gain fields appended at the end, and decoding ignores trailing
+//! bytes, so an older build of this crate still decodes events from a newer program. Event types it
+//! has never heard of decode to [`DataSovEvent::Unknown`] instead of failing.
+//!
+//! Ordering: identity and marketplace events end in `event_sequence` and `event_slot`, numbered per
+//! program, so a skipped number means a missed event. Governance, staking, timelock and audit
+//! events carry neither; order those by slot and by position within the block.
+
+mod events;
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use thiserror::Error;
+
+pub use events::{AuditEvent, GovernanceEvent, IdentityEvent, MarketplaceEvent, StakingEvent, TimelockEvent};
+
+/// Programs whose events this crate decodes
+pub const PROGRAMS: [Pubkey; 6] = [
+    datasov_identity::ID,
+    datasov_solana::ID,
+    datasov_audit::ID,
+    datasov_governance::ID,
+    datasov_timelock::ID,
+    datasov_staking::ID,
+];
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("{name} event body could not be decoded: {source}")]
+    Malformed {
+        name: &'static str,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// An event as emitted on chain, before decoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawEvent {
+    pub program: Pubkey,
+    pub discriminator: [u8; 8],
+    /// Borsh-encoded body, without the discriminator
+    pub data: Vec<u8>,
+}
+
+pub enum DataSovEvent {
+    Identity(IdentityEvent),
+    Marketplace(MarketplaceEvent),
+    Audit(AuditEvent),
+    Governance(GovernanceEvent),
+    Timelock(TimelockEvent),
+    Staking(StakingEvent),
+    /// A discriminator this build does not know, e.g. an event added in a newer program version
+    Unknown(RawEvent),
+}
+
+impl DataSovEvent {
+    /// Event name without the `Event` suffix, or `None` for unknown events
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::Identity(event) => Some(event.name()),
+            Self::Marketplace(event) => Some(event.name()),
+            Self::Audit(event) => Some(event.name()),
+            Self::Governance(event) => Some(event.name()),
+            Self::Timelock(event) => Some(event.name()),
+            Self::Staking(event) => Some(event.name()),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl RawEvent {
+    /// Split `discriminator ++ body` bytes; `None` if shorter than a discriminator
+    pub fn from_bytes(program: Pubkey, bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 8 {
+            return None;
+        }
+        let mut discriminator = [0u8; 8];
+        discriminator.copy_from_slice(&bytes[..8]);
+        Some(Self {
+            program,
+            discriminator,
+            data: bytes[8..].to_vec(),
+        })
+    }
+
+    /// Event name without decoding the body, or `None` for unknown events
+    pub fn name(&self) -> Option<&'static str> {
+        if self.program == datasov_identity::ID {
+            IdentityEvent::name_of(&self.discriminator)
+        } else if self.program == datasov_solana::ID {
+            MarketplaceEvent::name_of(&self.discriminator)
+        } else if self.program == datasov_audit::ID {
+            AuditEvent::name_of(&self.discriminator)
+        } else if self.program == datasov_governance::ID {
+            GovernanceEvent::name_of(&self.discriminator)
+        } else if self.program == datasov_timelock::ID {
+            TimelockEvent::name_of(&self.discriminator)
+        } else if self.program == datasov_staking::ID {
+            StakingEvent::name_of(&self.discriminator)
+        } else {
+            None
+        }
+    }
+
+    pub fn decode(&self) -> Result<DataSovEvent, DecodeError> {
+        let decoded = if self.program == datasov_identity::ID {
+            IdentityEvent::decode(&self.discriminator, &self.data).map(|event| event.map(DataSovEvent::Identity))
+        } else if self.program == datasov_solana::ID {
+            MarketplaceEvent::decode(&self.discriminator, &self.data).map(|event| event.map(DataSovEvent::Marketplace))
+        } else if self.program == datasov_audit::ID {
+            AuditEvent::decode(&self.discriminator, &self.data).map(|event| event.map(DataSovEvent::Audit))
+        } else if self.program == datasov_governance::ID {
+            GovernanceEvent::decode(&self.discriminator, &self.data).map(|event| event.map(DataSovEvent::Governance))
+        } else if self.program == datasov_timelock::ID {
+            TimelockEvent::decode(&self.discriminator, &self.data).map(|event| event.map(DataSovEvent::Timelock))
+        } else if self.program == datasov_staking::ID {
+            StakingEvent::decode(&self.discriminator, &self.data).map(|event| event.map(DataSovEvent::Staking))
+        } else {
+            None
+        };
+        match decoded {
+            Some(Ok(event)) => Ok(event),
+            Some(Err(source)) => Err(DecodeError::Malformed {
+                name: self.name().unwrap_or("unknown"),
+                source,
+            }),
+            None => Ok(DataSovEvent::Unknown(self.clone())),
+        }
+    }
+}
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Extract events emitted via `emit!` by any DataSov program from a transaction's log messages
+pub fn parse_logs(logs: &[String]) -> Vec<RawEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        if let Some(encoded) = line.strip_prefix(PROGRAM_DATA) {
+            let Some(program) = stack.last() else { continue };
+            if !PROGRAMS.contains(program) {
+                continue;
+            }
+            let Ok(bytes) = STANDARD.decode(encoded) else { continue };
+            events.extend(RawEvent::from_bytes(*program, &bytes));
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            let (Some(id), Some(action)) = (words.next(), words.next()) else { continue };
+            match action {
+                "invoke" => {
+                    if let Ok(program) = id.parse() {
+                        stack.push(program);
+                    }
+                }
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+/// Extract an `emit_cpi!` event from an inner instruction `program` invoked on itself
+pub fn parse_cpi_event(program: &Pubkey, instruction_data: &[u8]) -> Option<RawEvent> {
+    if !PROGRAMS.contains(program) {
+        return None;
+    }
+    let event = instruction_data.strip_prefix(EVENT_IX_TAG_LE.as_slice())?;
+    RawEvent::from_bytes(*program, event)
+}
+
+/// Decode every event in a transaction's logs
+pub fn decode_logs(logs: &[String]) -> Vec<Result<DataSovEvent, DecodeError>> {
+    parse_logs(logs).iter().map(RawEvent::decode).collect()
+}
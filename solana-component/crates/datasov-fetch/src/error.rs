@@ -0,0 +1,37 @@
+use datasov_storage::StorageError;
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("unexpected response from {endpoint}")]
+    UnexpectedResponse { endpoint: String },
+    #[error("transaction {id} not found")]
+    NotFound { id: String },
+    #[error("uploaded by {actual}, expected {expected}")]
+    OwnerMismatch { expected: Pubkey, actual: String },
+    #[error("tag {name} is {actual:?}, expected {expected:?}")]
+    TagMismatch {
+        name: String,
+        expected: String,
+        actual: Option<String>,
+    },
+    #[error("received {actual} bytes, expected {expected}")]
+    SizeMismatch { expected: usize, actual: usize },
+    #[error("content of {id} does not match the on-chain hash")]
+    HashMismatch { id: String },
+    #[error("{pointer} is not on Arweave")]
+    UnsupportedBackend { pointer: String },
+    #[error("manifest of listing {listing} is not sealed")]
+    ManifestNotSealed { listing: Pubkey },
+    #[error("document {id} describes a different identity or document type than requested")]
+    BodyMismatch { id: String },
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("document is not valid JSON: {0}")]
+    Payload(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, FetchError>;
@@ -0,0 +1,296 @@
+//! Read side of [`datasov_storage`]: fetch a document by Arweave transaction id and refuse it
+//! unless it matches what the chain says it should be.
+//!
+//! Checks run before decryption, in order: the signer ([`Expected::owner`]), the tags
+//! ([`Expected::tag`]), the size reported by the gateway (catching truncated responses) and the
+//! SHA-256 of the stored bytes against the on-chain commitment ([`Expected::content_hash`]).
+//! AES-GCM then authenticates the plaintext, so a swapped or truncated document is an error rather
+//! than silently wrong data.
+//!
+//! Datasets listed chunk by chunk in a sealed [`DataManifest`] are fetched with
+//! [`Fetcher::fetch_manifest`], several chunks at a time, each checked against the size and hash
+//! the manifest records for it.
+
+mod error;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use datasov_client::identity::{StorageBackend, StoragePointer};
+use datasov_client::marketplace::{DataManifest, ListingPreview};
+use datasov_client::storage::chunk_tx_id;
+use datasov_storage::upload::ARWEAVE_GATEWAY_URL;
+use datasov_storage::{content_hash, crypto, ContentKey, DocumentType, IdentityDocument, Tag, APP_NAME};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+pub use error::{FetchError, Result};
+
+/// Chunks of a manifest fetched at once by default
+pub const DEFAULT_CHUNK_CONCURRENCY: usize = 8;
+
+/// What a fetched document must match; unset fields are not checked
+#[derive(Debug, Clone, Default)]
+pub struct Expected {
+    content_hash: Option<[u8; 32]>,
+    owner: Option<Pubkey>,
+    tags: Vec<Tag>,
+}
+
+impl Expected {
+    /// Expect a DataSov document of `document_type`
+    pub fn document(document_type: DocumentType) -> Self {
+        Self::default()
+            .tag("App-Name", APP_NAME)
+            .tag("Document-Type", document_type.tag_value())
+    }
+
+    /// SHA-256 of the stored bytes, as committed on chain
+    pub fn content_hash(mut self, hash: [u8; 32]) -> Self {
+        self.content_hash = Some(hash);
+        self
+    }
+
+    /// Key that must have signed the upload
+    pub fn owner(mut self, owner: Pubkey) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Tag that must be present with exactly this value
+    pub fn tag(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push(Tag::new(name, value));
+        self
+    }
+}
+
+pub struct Fetcher {
+    http: reqwest::Client,
+    gateway_url: String,
+}
+
+impl Default for Fetcher {
+    fn default() -> Self {
+        Self::new(ARWEAVE_GATEWAY_URL)
+    }
+}
+
+impl Fetcher {
+    pub fn new(gateway_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            gateway_url: gateway_url.into(),
+        }
+    }
+
+    /// Fetch the stored (still encrypted) bytes of `id`, after checking them against `expected`
+    pub async fn fetch_verified(&self, id: &str, expected: &Expected) -> Result<Vec<u8>> {
+        let metadata = self.metadata(id).await?;
+        let owner_key = metadata
+            .pointer("/owner/key")
+            .and_then(Value::as_str)
+            .ok_or_else(|| self.unexpected("graphql"))?;
+        let tags: Vec<Tag> = metadata
+            .get("tags")
+            .and_then(Value::as_array)
+            .ok_or_else(|| self.unexpected("graphql"))?
+            .iter()
+            .filter_map(|tag| Some(Tag::new(tag.get("name")?.as_str()?, tag.get("value")?.as_str()?)))
+            .collect();
+        let size: usize = metadata
+            .pointer("/data/size")
+            .and_then(Value::as_str)
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(|| self.unexpected("graphql"))?;
+
+        if let Some(owner) = &expected.owner {
+            let actual = URL_SAFE_NO_PAD.decode(owner_key).unwrap_or_default();
+            if actual != owner.to_bytes() {
+                return Err(FetchError::OwnerMismatch {
+                    expected: *owner,
+                    actual: owner_key.to_string(),
+                });
+            }
+        }
+        for tag in &expected.tags {
+            let actual = tags.iter().find(|candidate| candidate.name == tag.name);
+            if actual.map(|actual| &actual.value) != Some(&tag.value) {
+                return Err(FetchError::TagMismatch {
+                    name: tag.name.clone(),
+                    expected: tag.value.clone(),
+                    actual: actual.map(|actual| actual.value.clone()),
+                });
+            }
+        }
+
+        let stored = self
+            .http
+            .get(format!("{}/raw/{}", self.gateway_url, id))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec();
+        if stored.len() != size {
+            return Err(FetchError::SizeMismatch {
+                expected: size,
+                actual: stored.len(),
+            });
+        }
+        if let Some(hash) = expected.content_hash {
+            if content_hash(&stored) != hash {
+                return Err(FetchError::HashMismatch { id: id.to_string() });
+            }
+        }
+        Ok(stored)
+    }
+
+    /// Fetch, verify and decrypt `id` with the document's content key
+    pub async fn fetch_decrypted(&self, id: &str, expected: &Expected, key: &ContentKey) -> Result<Vec<u8>> {
+        let stored = self.fetch_verified(id, expected).await?;
+        Ok(crypto::open(key, &stored)?)
+    }
+
+    /// Fetch, verify, decrypt and parse a JSON document
+    pub async fn fetch_json<T: DeserializeOwned>(&self, id: &str, expected: &Expected, key: &ContentKey) -> Result<T> {
+        let plaintext = self.fetch_decrypted(id, expected, key).await?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Fetch an identity, KYC or permission document, additionally checking that its body agrees
+    /// with its tags about the identity it describes
+    pub async fn fetch_identity_document(
+        &self,
+        id: &str,
+        document_type: DocumentType,
+        identity_id: &str,
+        expected: Expected,
+        key: &ContentKey,
+    ) -> Result<IdentityDocument> {
+        let expected = Expected {
+            tags: Expected::document(document_type)
+                .tag("Identity-Id", identity_id)
+                .tags
+                .into_iter()
+                .chain(expected.tags)
+                .collect(),
+            ..expected
+        };
+        let document: IdentityDocument = self.fetch_json(id, &expected, key).await?;
+        if document.document_type != document_type || document.identity_id != identity_id {
+            return Err(FetchError::BodyMismatch { id: id.to_string() });
+        }
+        Ok(document)
+    }
+
+    /// Fetch a listing's public preview sample, checking it against the hash the listing commits to.
+    /// Previews are stored unencrypted, so the bytes are the sample itself.
+    pub async fn fetch_preview(&self, preview: &ListingPreview) -> Result<Vec<u8>> {
+        if preview.pointer.backend != StorageBackend::Arweave {
+            return Err(FetchError::UnsupportedBackend {
+                pointer: preview.pointer.to_string(),
+            });
+        }
+        self.fetch_verified(&preview.pointer.reference, &Expected::default().content_hash(preview.hash))
+            .await
+    }
+
+    /// Whether the data behind `pointer` still resolves and, unless `data_hash` is zeroed, matches
+    /// it: what a storage oracle reports with `record_storage_check`. Errors are left to the caller
+    /// rather than reported as failed checks, since they may be the gateway's rather than the data's.
+    pub async fn check_storage(&self, pointer: &StoragePointer, data_hash: [u8; 32]) -> Result<bool> {
+        if pointer.backend != StorageBackend::Arweave {
+            return Err(FetchError::UnsupportedBackend {
+                pointer: pointer.to_string(),
+            });
+        }
+        let expected = if data_hash == [0; 32] {
+            Expected::default()
+        } else {
+            Expected::default().content_hash(data_hash)
+        };
+        match self.fetch_verified(&pointer.reference, &expected).await {
+            Ok(_) => Ok(true),
+            Err(FetchError::NotFound { .. } | FetchError::SizeMismatch { .. } | FetchError::HashMismatch { .. }) => {
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetch every chunk of a sealed manifest, up to `concurrency` at a time, and return their
+    /// stored bytes in manifest order. Each chunk must match `expected` as well as the size and
+    /// hash the manifest records for it.
+    pub async fn fetch_manifest(
+        &self,
+        manifest: &DataManifest,
+        expected: &Expected,
+        concurrency: usize,
+    ) -> Result<Vec<Vec<u8>>> {
+        if !manifest.is_sealed {
+            return Err(FetchError::ManifestNotSealed {
+                listing: manifest.listing,
+            });
+        }
+        stream::iter(&manifest.chunks)
+            .map(|chunk| async move {
+                let id = chunk_tx_id(chunk);
+                let stored = self
+                    .fetch_verified(&id, &expected.clone().content_hash(chunk.hash))
+                    .await?;
+                if stored.len() as u64 != chunk.size {
+                    return Err(FetchError::SizeMismatch {
+                        expected: chunk.size as usize,
+                        actual: stored.len(),
+                    });
+                }
+                Ok(stored)
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await
+    }
+
+    /// Fetch a sealed manifest's chunks, decrypt each with the dataset's content key and join them
+    pub async fn fetch_manifest_decrypted(
+        &self,
+        manifest: &DataManifest,
+        expected: &Expected,
+        key: &ContentKey,
+        concurrency: usize,
+    ) -> Result<Vec<u8>> {
+        let mut plaintext = Vec::new();
+        for stored in self.fetch_manifest(manifest, expected, concurrency).await? {
+            plaintext.extend(crypto::open(key, &stored)?);
+        }
+        Ok(plaintext)
+    }
+
+    async fn metadata(&self, id: &str) -> Result<Value> {
+        let response: Value = self
+            .http
+            .post(format!("{}/graphql", self.gateway_url))
+            .json(&json!({
+                "query": "query($id: ID!) { transaction(id: $id) { owner { key } tags { name value } data { size } } }",
+                "variables": { "id": id },
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match response.pointer("/data/transaction") {
+            Some(Value::Null) => Err(FetchError::NotFound { id: id.to_string() }),
+            Some(transaction) => Ok(transaction.clone()),
+            None => Err(self.unexpected("graphql")),
+        }
+    }
+
+    fn unexpected(&self, path: &str) -> FetchError {
+        FetchError::UnexpectedResponse {
+            endpoint: format!("{}/{}", self.gateway_url, path),
+        }
+    }
+}
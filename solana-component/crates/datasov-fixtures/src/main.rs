@@ -0,0 +1,95 @@
+//! `datasov-fixtures`: seed a cluster with realistic DataSov state for demos and front-end load
+//! tests.
+//!
+//! Reads a TOML scenario (see `scenarios/`) and creates oracles, identities at mixed verification
+//! levels, access grants (some expiring, some revoked) and listings that are active, sold or
+//! cancelled. With `--start-validator` it first launches `solana-test-validator` with the built
+//! programs and keeps it running until Ctrl-C. The target cluster needs a faucet; every wallet is
+//! funded by airdrop. A JSON manifest of everything created, including wallet keys, is written to
+//! `--output` (or stdout).
+
+mod scenario;
+mod seed;
+mod validator;
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use datasov_client::rpc::{DataSovRpc, RpcClient};
+use log::info;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use scenario::Scenario;
+use seed::Seeder;
+use validator::LocalValidator;
+
+#[derive(Parser)]
+#[clap(name = "datasov-fixtures", version, about = "Seed a cluster with DataSov demo state")]
+struct Args {
+    /// Scenario file; omit to seed the default demo
+    scenario: Option<PathBuf>,
+
+    /// JSON-RPC URL of the target cluster
+    #[clap(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Start a fresh solana-test-validator with the programs loaded, and keep it running
+    #[clap(long)]
+    start_validator: bool,
+
+    /// Directory with the built program `.so` files, for --start-validator
+    #[clap(long, default_value = "target/deploy")]
+    programs_dir: PathBuf,
+
+    /// Ledger directory for --start-validator
+    #[clap(long, default_value = "test-ledger")]
+    ledger: PathBuf,
+
+    /// Write the manifest here instead of stdout
+    #[clap(long, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// Transactions in flight at once
+    #[clap(long, default_value_t = 8)]
+    concurrency: usize,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let scenario = match &args.scenario {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+            Scenario::from_toml(&text).with_context(|| format!("invalid scenario {}", path.display()))?
+        }
+        None => Scenario::default(),
+    };
+
+    let rpc = DataSovRpc::from_client(RpcClient::new_with_commitment(
+        args.url.clone(),
+        CommitmentConfig::confirmed(),
+    ));
+    let validator = match args.start_validator {
+        true => Some(LocalValidator::start(&args.programs_dir, &args.ledger, rpc.client()).await?),
+        false => None,
+    };
+
+    let manifest = Seeder::new(&rpc, &scenario, args.concurrency).run().await?;
+    let json = serde_json::to_string_pretty(&manifest)?;
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, json).with_context(|| format!("failed to write {}", path.display()))?;
+            info!("Wrote manifest to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+
+    if validator.is_some() {
+        info!("Seeded; validator running at {} until Ctrl-C", args.url);
+        tokio::signal::ctrl_c().await?;
+    }
+    Ok(())
+}
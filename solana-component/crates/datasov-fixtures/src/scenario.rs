@@ -0,0 +1,235 @@
+//! TOML scenario files. Every section and field is optional; an empty file seeds a small demo.
+
+use anyhow::{bail, Result};
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::DataType as ListingDataType;
+use datasov_client::parse;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    /// Identity ids are `<prefix>-<n>`
+    pub identity_prefix: String,
+    pub marketplace: MarketplaceConfig,
+    pub oracles: OracleConfig,
+    pub identities: IdentityConfig,
+    pub permissions: PermissionConfig,
+    pub listings: ListingConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketplaceConfig {
+    pub fee_basis_points: u16,
+    pub mint_decimals: u8,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OracleConfig {
+    pub count: usize,
+    /// Registry minimum stake in lamports; every oracle stakes exactly this
+    pub minimum_stake: u64,
+    pub slash_amount: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdentityConfig {
+    pub count: usize,
+    /// How many are verified by an oracle; the rest stay pending
+    pub verified: usize,
+    /// Verification levels, assigned round-robin, e.g. `["basic", "enhanced"]`
+    #[serde(deserialize_with = "verification_levels")]
+    pub levels: Vec<VerificationLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PermissionConfig {
+    /// Grants between verified identities
+    pub count: usize,
+    #[serde(deserialize_with = "data_types")]
+    pub data_types: Vec<DataType>,
+    /// How many grants expire `ttl` seconds after seeding
+    pub expiring: usize,
+    pub ttl: i64,
+    /// How many grants are revoked once listings are sold
+    pub revoked: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListingConfig {
+    pub active: usize,
+    pub sold: usize,
+    pub cancelled: usize,
+    /// Prices are spread between these, in settlement-mint base units
+    pub min_price: u64,
+    pub max_price: u64,
+    /// Listing data types, assigned round-robin; custom types are written `custom:<name>`
+    #[serde(deserialize_with = "listing_data_types")]
+    pub data_types: Vec<ListingDataType>,
+    #[serde(deserialize_with = "purpose")]
+    pub purpose: Purpose,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            identity_prefix: "demo".to_string(),
+            marketplace: MarketplaceConfig::default(),
+            oracles: OracleConfig::default(),
+            identities: IdentityConfig::default(),
+            permissions: PermissionConfig::default(),
+            listings: ListingConfig::default(),
+        }
+    }
+}
+
+impl Default for MarketplaceConfig {
+    fn default() -> Self {
+        Self {
+            fee_basis_points: 250,
+            mint_decimals: 6,
+        }
+    }
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            count: 3,
+            minimum_stake: 1_000_000_000,
+            slash_amount: 100_000_000,
+        }
+    }
+}
+
+impl Default for IdentityConfig {
+    fn default() -> Self {
+        Self {
+            count: 20,
+            verified: 15,
+            levels: vec![
+                VerificationLevel::Basic,
+                VerificationLevel::Enhanced,
+                VerificationLevel::High,
+            ],
+        }
+    }
+}
+
+impl Default for PermissionConfig {
+    fn default() -> Self {
+        Self {
+            count: 10,
+            data_types: vec![DataType::LocationHistory, DataType::AppUsage],
+            expiring: 3,
+            ttl: 86_400,
+            revoked: 2,
+        }
+    }
+}
+
+impl Default for ListingConfig {
+    fn default() -> Self {
+        Self {
+            active: 10,
+            sold: 5,
+            cancelled: 2,
+            min_price: 1_000_000,
+            max_price: 50_000_000,
+            data_types: vec![
+                ListingDataType::LocationHistory,
+                ListingDataType::AppUsage,
+                ListingDataType::PurchaseHistory,
+            ],
+            purpose: Purpose::Research,
+        }
+    }
+}
+
+impl Scenario {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let scenario: Self = toml::from_str(text)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Reject scenarios the programs would refuse part-way through seeding
+    fn validate(&self) -> Result<()> {
+        let verified = self.identities.verified;
+        if verified > self.identities.count {
+            bail!("identities.verified exceeds identities.count");
+        }
+        if verified > 0 && self.oracles.count == 0 {
+            bail!("verifying identities needs at least one oracle");
+        }
+        if verified > 0 && self.identities.levels.is_empty() {
+            bail!("identities.levels must not be empty");
+        }
+        if self.permissions.count > 0 || self.listings.sold > 0 {
+            if verified < 2 {
+                bail!("grants and sales need at least two verified identities");
+            }
+            if self.permissions.data_types.is_empty() && self.permissions.count > 0 {
+                bail!("permissions.data_types must not be empty");
+            }
+        }
+        // Each verified identity grants to each other at most once
+        if self.permissions.count > verified * verified.saturating_sub(1) {
+            bail!("permissions.count exceeds the number of distinct verified identity pairs");
+        }
+        if self.permissions.expiring + self.permissions.revoked > self.permissions.count {
+            bail!("permissions.expiring + permissions.revoked exceeds permissions.count");
+        }
+        if self.permissions.ttl <= 0 {
+            bail!("permissions.ttl must be positive");
+        }
+        if self.listing_count() > 0 {
+            if verified == 0 {
+                bail!("listings need at least one verified identity");
+            }
+            if self.listings.data_types.is_empty() {
+                bail!("listings.data_types must not be empty");
+            }
+            if self.listings.min_price > self.listings.max_price {
+                bail!("listings.min_price exceeds listings.max_price");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn listing_count(&self) -> usize {
+        self.listings.active + self.listings.sold + self.listings.cancelled
+    }
+}
+
+fn parse_list<'de, D: Deserializer<'de>, T>(
+    deserializer: D,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<Vec<T>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|value| parse(value).map_err(D::Error::custom))
+        .collect()
+}
+
+fn verification_levels<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<VerificationLevel>, D::Error> {
+    parse_list(deserializer, parse::verification_level)
+}
+
+fn data_types<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<DataType>, D::Error> {
+    parse_list(deserializer, parse::data_type)
+}
+
+fn listing_data_types<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<ListingDataType>, D::Error> {
+    parse_list(deserializer, parse::listing_data_type)
+}
+
+fn purpose<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Purpose, D::Error> {
+    parse::purpose(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
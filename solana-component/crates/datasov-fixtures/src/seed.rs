@@ -0,0 +1,691 @@
+//! Populates a fresh cluster from a [`Scenario`].
+//!
+//! Work on different identities runs concurrently; work on one identity (grants, purchases and
+//! revocations, which append to its audit log) runs in order, since each instruction addresses the
+//! audit page its entry lands on.
+
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use datasov_client::identity::{self, DataType, PermissionType, VerificationLevel};
+use datasov_client::instructions::{self, GrantAccessRequest, PurchaseRequest};
+use datasov_client::marketplace::{self, DataType as ListingDataType};
+use datasov_client::pda;
+use datasov_client::rpc::DataSovRpc;
+use datasov_client::{storage, TransactionBuilder};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use log::info;
+use serde::Serialize;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::{system_instruction, system_program};
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::scenario::Scenario;
+
+/// Lamports each identity wallet receives for rent
+const IDENTITY_LAMPORTS: u64 = LAMPORTS_PER_SOL / 10;
+/// Lamports each oracle receives on top of its stake
+const ORACLE_LAMPORTS: u64 = LAMPORTS_PER_SOL;
+/// System transfers packed into one funding transaction
+const TRANSFERS_PER_TRANSACTION: usize = 20;
+
+/// Everything created, with wallet secret keys (in Solana CLI keypair format) so front ends can
+/// sign in as any participant
+#[derive(Serialize)]
+pub struct Manifest {
+    pub authority: KeypairRecord,
+    pub mint: String,
+    pub oracles: Vec<KeypairRecord>,
+    pub identities: Vec<IdentityRecord>,
+    pub permissions: Vec<PermissionRecord>,
+    pub listings: Vec<ListingRecord>,
+}
+
+#[derive(Serialize)]
+pub struct KeypairRecord {
+    pub pubkey: String,
+    pub secret_key: Vec<u8>,
+}
+
+impl From<&Keypair> for KeypairRecord {
+    fn from(keypair: &Keypair) -> Self {
+        Self {
+            pubkey: keypair.pubkey().to_string(),
+            secret_key: keypair.to_bytes().to_vec(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct IdentityRecord {
+    pub identity_id: String,
+    pub owner: KeypairRecord,
+    /// `None` while pending
+    pub verification_level: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PermissionRecord {
+    pub identity_id: String,
+    pub consumer_identity_id: String,
+    pub data_types: Vec<String>,
+    pub expires_at: Option<i64>,
+    pub revoked: bool,
+}
+
+#[derive(Serialize)]
+pub struct ListingRecord {
+    pub listing_id: u64,
+    pub seller_identity_id: String,
+    pub price: u64,
+    pub data_type: String,
+    pub stage: ListingStage,
+    pub buyer_identity_id: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ListingStage {
+    Active,
+    Sold,
+    Cancelled,
+}
+
+/// A planned grant from one verified identity (by index) to another
+struct Grant {
+    owner: usize,
+    consumer: usize,
+    data_types: Vec<DataType>,
+    expires_at: Option<i64>,
+    revoke: bool,
+}
+
+struct Listing {
+    listing_id: u64,
+    seller: usize,
+    buyer: Option<usize>,
+    price: u64,
+    data_type: ListingDataType,
+    stage: ListingStage,
+}
+
+struct Participant {
+    wallet: Keypair,
+    identity_id: String,
+}
+
+pub struct Seeder<'a> {
+    rpc: &'a DataSovRpc,
+    scenario: &'a Scenario,
+    /// Pays every fee and is the registry, marketplace and mint authority
+    payer: Keypair,
+    concurrency: usize,
+}
+
+impl<'a> Seeder<'a> {
+    pub fn new(rpc: &'a DataSovRpc, scenario: &'a Scenario, concurrency: usize) -> Self {
+        Self {
+            rpc,
+            scenario,
+            payer: Keypair::new(),
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    pub async fn run(self) -> Result<Manifest> {
+        self.airdrop_payer().await?;
+        self.initialize().await?;
+        let mint = self.create_mint().await?;
+        let oracles = self.register_oracles().await?;
+        let participants = self.register_identities().await?;
+        let levels = self.verify_identities(&participants, &oracles).await?;
+
+        let listings = self.plan_listings();
+        let grants = self.plan_grants(&listings)?;
+        self.grant(&participants, &grants).await?;
+        self.create_listings(&participants, &listings).await?;
+        self.purchase(&participants, &listings, &mint).await?;
+        self.revoke(&participants, &grants).await?;
+
+        let identities = participants
+            .iter()
+            .enumerate()
+            .map(|(index, participant)| IdentityRecord {
+                identity_id: participant.identity_id.clone(),
+                owner: KeypairRecord::from(&participant.wallet),
+                verification_level: levels.get(index).map(|level| format!("{:?}", level)),
+            })
+            .collect();
+        let permissions = grants
+            .iter()
+            .map(|grant| PermissionRecord {
+                identity_id: participants[grant.owner].identity_id.clone(),
+                consumer_identity_id: participants[grant.consumer].identity_id.clone(),
+                data_types: grant
+                    .data_types
+                    .iter()
+                    .map(|data_type| format!("{:?}", data_type))
+                    .collect(),
+                expires_at: grant.expires_at,
+                revoked: grant.revoke,
+            })
+            .collect();
+        let listings = listings
+            .iter()
+            .map(|listing| ListingRecord {
+                listing_id: listing.listing_id,
+                seller_identity_id: participants[listing.seller].identity_id.clone(),
+                price: listing.price,
+                data_type: format!("{:?}", listing.data_type),
+                stage: listing.stage,
+                buyer_identity_id: listing.buyer.map(|buyer| participants[buyer].identity_id.clone()),
+            })
+            .collect();
+        Ok(Manifest {
+            authority: KeypairRecord::from(&self.payer),
+            mint: mint.to_string(),
+            oracles: oracles.iter().map(KeypairRecord::from).collect(),
+            identities,
+            permissions,
+            listings,
+        })
+    }
+
+    /// Send one transaction paid by the seeder payer and also signed by `signers`
+    async fn send(&self, instructions: Vec<Instruction>, signers: &[&Keypair]) -> Result<()> {
+        let builder = instructions
+            .into_iter()
+            .fold(TransactionBuilder::new(), TransactionBuilder::instruction);
+        let mut all_signers: Vec<&dyn Signer> = vec![&self.payer];
+        all_signers.extend(signers.iter().map(|signer| *signer as &dyn Signer));
+        self.rpc.send(&builder, &self.payer.pubkey(), &all_signers).await?;
+        Ok(())
+    }
+
+    /// Run `task` for every item, `concurrency` at a time
+    async fn for_each<T, F, Fut>(&self, items: Vec<T>, task: F) -> Result<()>
+    where
+        F: Fn(T) -> Fut,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        stream::iter(items)
+            .map(task)
+            .buffer_unordered(self.concurrency)
+            .try_collect::<Vec<()>>()
+            .await?;
+        Ok(())
+    }
+
+    async fn airdrop_payer(&self) -> Result<()> {
+        let scenario = self.scenario;
+        let lamports = 10 * LAMPORTS_PER_SOL
+            + scenario.oracles.count as u64 * (2 * scenario.oracles.minimum_stake + ORACLE_LAMPORTS)
+            + scenario.identities.count as u64 * IDENTITY_LAMPORTS;
+        let client = self.rpc.client();
+        let signature = client
+            .request_airdrop(&self.payer.pubkey(), lamports)
+            .await
+            .context("airdrop failed; the target cluster needs a faucet")?;
+        while !client.confirm_transaction(&signature).await? {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+        Ok(())
+    }
+
+    async fn fund(&self, recipients: &[Pubkey], lamports: u64) -> Result<()> {
+        let chunks: Vec<&[Pubkey]> = recipients.chunks(TRANSFERS_PER_TRANSACTION).collect();
+        self.for_each(chunks, |chunk| async move {
+            let transfers = chunk
+                .iter()
+                .map(|recipient| system_instruction::transfer(&self.payer.pubkey(), recipient, lamports))
+                .collect();
+            self.send(transfers, &[]).await
+        })
+        .await
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        info!("Initializing registries and marketplace");
+        let authority = self.payer.pubkey();
+        let oracles = &self.scenario.oracles;
+        self.send(
+            vec![
+                instructions::identity(
+                    identity::accounts::InitializeOracleRegistry {
+                        oracle_registry: pda::identity::oracle_registry(),
+                        authority,
+                        system_program: system_program::ID,
+                    },
+                    identity::instruction::InitializeOracleRegistry {
+                        minimum_stake: oracles.minimum_stake,
+                        slash_amount: oracles.slash_amount,
+                    },
+                ),
+                instructions::identity(
+                    identity::accounts::InitializeComplianceMatrix {
+                        compliance_matrix: pda::identity::compliance_matrix(),
+                        oracle_registry: pda::identity::oracle_registry(),
+                        authority,
+                        system_program: system_program::ID,
+                    },
+                    identity::instruction::InitializeComplianceMatrix {},
+                ),
+                instructions::identity(
+                    identity::accounts::InitializeBlacklist {
+                        blacklist: pda::identity::blacklist(),
+                        oracle_registry: pda::identity::oracle_registry(),
+                        authority,
+                        system_program: system_program::ID,
+                    },
+                    identity::instruction::InitializeBlacklist {},
+                ),
+                instructions::marketplace(
+                    marketplace::accounts::InitializeMarketplace {
+                        marketplace: pda::marketplace::marketplace(),
+                        authority,
+                        system_program: system_program::ID,
+                    },
+                    marketplace::instruction::InitializeMarketplace {
+                        marketplace_fee_basis_points: self.scenario.marketplace.fee_basis_points,
+                    },
+                ),
+            ],
+            &[],
+        )
+        .await
+    }
+
+    /// Settlement mint, with the marketplace's fee account
+    async fn create_mint(&self) -> Result<Pubkey> {
+        let mint = Keypair::new();
+        let payer = self.payer.pubkey();
+        let rent = self
+            .rpc
+            .client()
+            .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+            .await?;
+        self.send(
+            vec![
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent,
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    self.scenario.marketplace.mint_decimals,
+                )?,
+                create_associated_token_account_idempotent(
+                    &payer,
+                    &pda::marketplace::marketplace(),
+                    &mint.pubkey(),
+                    &spl_token::ID,
+                ),
+            ],
+            &[&mint],
+        )
+        .await?;
+        Ok(mint.pubkey())
+    }
+
+    async fn register_oracles(&self) -> Result<Vec<Keypair>> {
+        let config = &self.scenario.oracles;
+        info!("Registering {} oracles", config.count);
+        let oracles: Vec<Keypair> = (0..config.count).map(|_| Keypair::new()).collect();
+        let pubkeys: Vec<Pubkey> = oracles.iter().map(Keypair::pubkey).collect();
+        self.fund(&pubkeys, config.minimum_stake + ORACLE_LAMPORTS).await?;
+        self.for_each(oracles.iter().enumerate().collect(), |(index, oracle)| async move {
+            let register = instructions::identity(
+                identity::accounts::RegisterOracle {
+                    oracle: pda::identity::oracle(&oracle.pubkey()),
+                    oracle_registry: pda::identity::oracle_registry(),
+                    oracle_authority: oracle.pubkey(),
+                    system_program: system_program::ID,
+                },
+                identity::instruction::RegisterOracle {
+                    provider_name: format!("Fixture Oracle {}", index + 1),
+                    stake_amount: config.minimum_stake,
+                },
+            );
+            self.send(vec![register], &[oracle]).await
+        })
+        .await?;
+        Ok(oracles)
+    }
+
+    /// Register every identity with a consumer profile, so any of them can buy once verified
+    async fn register_identities(&self) -> Result<Vec<Participant>> {
+        let count = self.scenario.identities.count;
+        info!("Registering {} identities", count);
+        let participants: Vec<Participant> = (0..count)
+            .map(|index| Participant {
+                wallet: Keypair::new(),
+                identity_id: format!("{}-{}", self.scenario.identity_prefix, index + 1),
+            })
+            .collect();
+        let pubkeys: Vec<Pubkey> = participants
+            .iter()
+            .map(|participant| participant.wallet.pubkey())
+            .collect();
+        self.fund(&pubkeys, IDENTITY_LAMPORTS).await?;
+        self.for_each(participants.iter().collect(), |participant| async move {
+            let owner = participant.wallet.pubkey();
+            let identity_id = &participant.identity_id;
+            let register = instructions::register_identity(
+                &owner,
+                identity_id,
+                storage::placeholder(&format!("{}-document", identity_id)),
+            );
+            let profile = instructions::identity(
+                identity::accounts::RegisterConsumer {
+                    consumer_profile: pda::identity::consumer_profile(&owner),
+                    consumer: owner,
+                    oracle_registry: pda::identity::oracle_registry(),
+                    system_program: system_program::ID,
+                },
+                identity::instruction::RegisterConsumer {
+                    organization_name_hash: [0u8; 32],
+                    contact_tx_id: format!("{}-contact", identity_id),
+                    kyb_attestation_tx_id: String::new(),
+                },
+            );
+            self.send(vec![register, profile], &[&participant.wallet]).await
+        })
+        .await?;
+        Ok(participants)
+    }
+
+    /// Verify the first `identities.verified` identities, spreading them over the oracles;
+    /// returns their levels by index
+    async fn verify_identities(
+        &self,
+        participants: &[Participant],
+        oracles: &[Keypair],
+    ) -> Result<Vec<VerificationLevel>> {
+        let config = &self.scenario.identities;
+        info!("Verifying {} identities", config.verified);
+        let levels: Vec<VerificationLevel> = (0..config.verified)
+            .map(|index| config.levels[index % config.levels.len()].clone())
+            .collect();
+        // One oracle's verifications update its account, so each oracle works through its share in order
+        let mut by_oracle: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for index in 0..config.verified {
+            by_oracle.entry(index % oracles.len()).or_default().push(index);
+        }
+        self.for_each(by_oracle.into_iter().collect(), |(oracle_index, indices)| {
+            let oracle = &oracles[oracle_index];
+            let levels = &levels;
+            async move {
+                for index in indices {
+                    let participant = &participants[index];
+                    let verify = instructions::identity(
+                        identity::accounts::VerifyIdentity {
+                            identity: pda::identity::identity(&participant.identity_id),
+                            oracle: pda::identity::oracle(&oracle.pubkey()),
+                            oracle_registry: pda::identity::oracle_registry(),
+                            oracle_authority: oracle.pubkey(),
+                        },
+                        identity::instruction::VerifyIdentity {
+                            verification_level: levels[index].clone(),
+                            kyc_pointer: storage::placeholder(&format!("{}-kyc", participant.identity_id)),
+                        },
+                    );
+                    self.send(vec![verify], &[oracle]).await?;
+                }
+                Ok(())
+            }
+        })
+        .await?;
+        Ok(levels)
+    }
+
+    /// Listings in stage order (sold, cancelled, active), sellers and data types round-robin over
+    /// the verified identities; each sold listing is bought by the next verified identity
+    fn plan_listings(&self) -> Vec<Listing> {
+        let config = &self.scenario.listings;
+        let verified = self.scenario.identities.verified;
+        let price_range = config.max_price - config.min_price + 1;
+        (0..self.scenario.listing_count())
+            .map(|index| {
+                let stage = if index < config.sold {
+                    ListingStage::Sold
+                } else if index < config.sold + config.cancelled {
+                    ListingStage::Cancelled
+                } else {
+                    ListingStage::Active
+                };
+                let seller = index % verified;
+                Listing {
+                    listing_id: index as u64 + 1,
+                    seller,
+                    buyer: (stage == ListingStage::Sold).then(|| (seller + 1) % verified),
+                    // Spread prices over the range without a random source, so runs are repeatable
+                    price: config.min_price + (index as u64).wrapping_mul(7919) % price_range,
+                    data_type: config.data_types[index % config.data_types.len()].clone(),
+                    stage,
+                }
+            })
+            .collect()
+    }
+
+    /// `permissions.count` grants over distinct verified pairs, plus whatever the sold listings'
+    /// buyers need. Sales happen before revocations, so a revoked grant can still back a sale.
+    fn plan_grants(&self, listings: &[Listing]) -> Result<Vec<Grant>> {
+        let config = &self.scenario.permissions;
+        let verified = self.scenario.identities.verified;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let mut grants: Vec<Grant> = (0..config.count)
+            .map(|index| Grant {
+                owner: index % verified,
+                // Offsets 1..verified-1 never pair an identity with itself or repeat a pair
+                consumer: (index % verified + 1 + index / verified) % verified,
+                data_types: config.data_types.clone(),
+                expires_at: (index < config.expiring).then_some(now + config.ttl),
+                revoke: index >= config.expiring && index < config.expiring + config.revoked,
+            })
+            .collect();
+        for listing in listings {
+            let Some(buyer) = listing.buyer else { continue };
+            let data_type = listing.data_type.to_identity_data_type();
+            match grants
+                .iter_mut()
+                .find(|grant| grant.owner == listing.seller && grant.consumer == buyer)
+            {
+                Some(grant) if grant.data_types.contains(&data_type) => {}
+                Some(grant) => grant.data_types.push(data_type),
+                None => grants.push(Grant {
+                    owner: listing.seller,
+                    consumer: buyer,
+                    data_types: vec![data_type],
+                    expires_at: None,
+                    revoke: false,
+                }),
+            }
+        }
+        Ok(grants)
+    }
+
+    /// Grants grouped by granting identity, since each one appends to that identity's audit log
+    fn by_owner(grants: &[Grant], include: impl Fn(&Grant) -> bool) -> Vec<Vec<&Grant>> {
+        let mut groups: BTreeMap<usize, Vec<&Grant>> = BTreeMap::new();
+        for grant in grants.iter().filter(|grant| include(grant)) {
+            groups.entry(grant.owner).or_default().push(grant);
+        }
+        groups.into_values().collect()
+    }
+
+    async fn grant(&self, participants: &[Participant], grants: &[Grant]) -> Result<()> {
+        info!("Granting {} permissions", grants.len());
+        self.for_each(Self::by_owner(grants, |_| true), |group| async move {
+            for grant in group {
+                let owner = &participants[grant.owner];
+                let consumer = &participants[grant.consumer];
+                let request = GrantAccessRequest {
+                    owner: owner.wallet.pubkey(),
+                    identity_id: owner.identity_id.clone(),
+                    consumer: consumer.wallet.pubkey(),
+                    permission_type: PermissionType::ReadOnly,
+                    data_types: grant.data_types.clone(),
+                    expires_at: grant.expires_at,
+                    permission_pointer: storage::placeholder(&format!(
+                        "{}-grant-{}",
+                        owner.identity_id, consumer.identity_id
+                    )),
+                    consumer_staked: false,
+                    guardian: None,
+                    audit_entry_count: self
+                        .rpc
+                        .audit_entry_count(&pda::identity::identity(&owner.identity_id))
+                        .await?,
+                };
+                self.send(vec![instructions::grant_access(request)], &[&owner.wallet])
+                    .await?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn create_listings(&self, participants: &[Participant], listings: &[Listing]) -> Result<()> {
+        info!("Creating {} listings", listings.len());
+        self.for_each(listings.iter().collect(), |listing| async move {
+            let seller = &participants[listing.seller];
+            let mut instructions = vec![instructions::marketplace(
+                marketplace::accounts::CreateDataListing {
+                    listing: pda::marketplace::listing(listing.listing_id),
+                    marketplace: pda::marketplace::marketplace(),
+                    seller_identity: pda::identity::identity(&seller.identity_id),
+                    compliance_matrix: pda::identity::compliance_matrix(),
+                    data_trust: None,
+                    trust_membership: None,
+                    owner: seller.wallet.pubkey(),
+                    guardian: None,
+                    identity_program: identity::ID,
+                    system_program: system_program::ID,
+                    payout_wallet: None,
+                },
+                marketplace::instruction::CreateDataListing {
+                    listing_id: listing.listing_id,
+                    price: listing.price,
+                    data_type: listing.data_type.clone(),
+                    description: format!("{:?} from {}", listing.data_type, seller.identity_id),
+                    identity_id: seller.identity_id.clone(),
+                },
+            )];
+            if listing.stage == ListingStage::Cancelled {
+                instructions.push(instructions::marketplace(
+                    marketplace::accounts::CancelListing {
+                        listing: pda::marketplace::listing(listing.listing_id),
+                        owner: seller.wallet.pubkey(),
+                    },
+                    marketplace::instruction::CancelListing {},
+                ));
+            }
+            self.send(instructions, &[&seller.wallet]).await
+        })
+        .await
+    }
+
+    /// Fund each buyer with the listing price and buy; purchases append to the seller's audit log,
+    /// so they run in order per seller
+    async fn purchase(&self, participants: &[Participant], listings: &[Listing], mint: &Pubkey) -> Result<()> {
+        let mut by_seller: BTreeMap<usize, Vec<&Listing>> = BTreeMap::new();
+        for listing in listings.iter().filter(|listing| listing.stage == ListingStage::Sold) {
+            by_seller.entry(listing.seller).or_default().push(listing);
+        }
+        info!("Selling {} listings", self.scenario.listings.sold);
+        let payer = self.payer.pubkey();
+        self.for_each(by_seller.into_values().collect(), |group| async move {
+            for listing in group {
+                let seller = &participants[listing.seller];
+                let buyer = &participants[listing.buyer.expect("sold listings have a buyer")];
+                let buyer_wallet = buyer.wallet.pubkey();
+                self.send(
+                    vec![
+                        create_associated_token_account_idempotent(&payer, &buyer_wallet, mint, &spl_token::ID),
+                        create_associated_token_account_idempotent(
+                            &payer,
+                            &seller.wallet.pubkey(),
+                            mint,
+                            &spl_token::ID,
+                        ),
+                        spl_token::instruction::mint_to(
+                            &spl_token::ID,
+                            mint,
+                            &get_associated_token_address(&buyer_wallet, mint),
+                            &payer,
+                            &[],
+                            listing.price,
+                        )?,
+                    ],
+                    &[],
+                )
+                .await?;
+
+                let request = PurchaseRequest {
+                    buyer: buyer_wallet,
+                    buyer_identity_id: buyer.identity_id.clone(),
+                    listing_id: listing.listing_id,
+                    payout_wallet: seller.wallet.pubkey(),
+                    seller_identity_id: seller.identity_id.clone(),
+                    data_union: None,
+                    data_type: listing.data_type.clone(),
+                    purpose: self.scenario.listings.purpose.clone(),
+                    mint: *mint,
+                    escrowed: false,
+                    buyer_staked: false,
+                    buyer_weighted: false,
+                    rebate: None,
+                    max_staleness: None,
+                    day: marketplace::PriceCandle::day_of(
+                        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64
+                    ),
+                    audit_entry_count: self
+                        .rpc
+                        .audit_entry_count(&pda::identity::identity(&seller.identity_id))
+                        .await?,
+                };
+                self.send(vec![instructions::purchase_data(request)], &[&buyer.wallet])
+                    .await?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn revoke(&self, participants: &[Participant], grants: &[Grant]) -> Result<()> {
+        info!("Revoking {} permissions", self.scenario.permissions.revoked);
+        self.for_each(Self::by_owner(grants, |grant| grant.revoke), |group| async move {
+            for grant in group {
+                let owner = &participants[grant.owner];
+                let consumer = &participants[grant.consumer];
+                let audit_entry_count = self
+                    .rpc
+                    .audit_entry_count(&pda::identity::identity(&owner.identity_id))
+                    .await?;
+                let revoke = instructions::revoke_access(
+                    &owner.wallet.pubkey(),
+                    &owner.identity_id,
+                    &consumer.wallet.pubkey(),
+                    storage::placeholder(&format!("{}-revoke-{}", owner.identity_id, consumer.identity_id)),
+                    audit_entry_count,
+                );
+                self.send(vec![revoke], &[&owner.wallet]).await?;
+            }
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,66 @@
+//! A `solana-test-validator` with the DataSov programs loaded at their declared ids.
+
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use datasov_client::rpc::RpcClient;
+use log::info;
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Program crate name (and `.so` file stem) with the id it is deployed at
+fn programs() -> [(&'static str, String); 3] {
+    [
+        ("datasov_identity", datasov_client::identity::ID.to_string()),
+        ("datasov_solana", datasov_client::marketplace::ID.to_string()),
+        ("datasov_audit", datasov_client::audit::ID.to_string()),
+    ]
+}
+
+/// Validator process, killed on drop
+pub struct LocalValidator {
+    child: Child,
+}
+
+impl LocalValidator {
+    /// Start a fresh validator on the default ports with the programs built in `programs_dir`
+    /// (`anchor build` writes them to `target/deploy`), and wait until it serves RPC
+    pub async fn start(programs_dir: &Path, ledger: &Path, rpc: &RpcClient) -> Result<Self> {
+        let mut command = Command::new("solana-test-validator");
+        command.arg("--reset").arg("--quiet").arg("--ledger").arg(ledger);
+        for (name, program_id) in programs() {
+            let path = programs_dir.join(format!("{}.so", name));
+            if !path.exists() {
+                bail!("{} not found; run `anchor build` first", path.display());
+            }
+            command.arg("--bpf-program").arg(program_id).arg(path);
+        }
+        let child = command
+            .stdout(Stdio::null())
+            .spawn()
+            .context("failed to start solana-test-validator; is the Solana CLI installed?")?;
+        let mut validator = Self { child };
+
+        info!("Waiting for the local validator at {}", rpc.url());
+        let started = tokio::time::Instant::now();
+        while rpc.get_health().await.is_err() {
+            if let Some(status) = validator.child.try_wait()? {
+                bail!("solana-test-validator exited with {}", status);
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                bail!("local validator did not become healthy within {:?}", STARTUP_TIMEOUT);
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        Ok(validator)
+    }
+}
+
+impl Drop for LocalValidator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,443 @@
+//! Indexed state: row types and the queries shared by the REST and GraphQL front ends.
+
+use async_graphql::{Enum, SimpleObject};
+use datasov_client::parse;
+use serde::{Deserialize, Serialize};
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, Row};
+
+use crate::error::{self, ApiError};
+
+pub const DEFAULT_LIMIT: i64 = 50;
+pub const MAX_LIMIT: i64 = 200;
+
+/// Clamp caller-supplied pagination to `(limit, offset)`
+pub fn page(limit: Option<i64>, offset: Option<i64>) -> (i64, i64) {
+    (
+        limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        offset.unwrap_or(0).max(0),
+    )
+}
+
+#[derive(Serialize, SimpleObject)]
+#[graphql(complex)]
+pub struct Identity {
+    pub address: String,
+    pub identity_id: String,
+    pub owner: String,
+    pub status: String,
+    pub verification_level: String,
+    pub verified_at: Option<i64>,
+    /// Storage URI of the identity's current document
+    pub storage_pointer: String,
+    pub guardian: Option<String>,
+    pub sns_domain: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub slot: i64,
+}
+
+impl From<Row> for Identity {
+    fn from(row: Row) -> Self {
+        Self {
+            address: row.get("address"),
+            identity_id: row.get("identity_id"),
+            owner: row.get("owner"),
+            status: row.get("status"),
+            verification_level: row.get("verification_level"),
+            verified_at: row.get("verified_at"),
+            storage_pointer: row.get("storage_pointer"),
+            guardian: row.get("guardian"),
+            sns_domain: row.get("sns_domain"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            slot: row.get("slot"),
+        }
+    }
+}
+
+#[derive(Serialize, SimpleObject)]
+#[graphql(complex)]
+pub struct Permission {
+    pub address: String,
+    pub identity_id: String,
+    pub consumer: String,
+    pub permission_type: String,
+    pub data_types: Vec<String>,
+    pub granted_at: i64,
+    pub expires_at: Option<i64>,
+    pub is_active: bool,
+    pub is_trial: bool,
+    pub access_count: i64,
+    pub revoked_at: Option<i64>,
+    pub slot: i64,
+}
+
+impl From<Row> for Permission {
+    fn from(row: Row) -> Self {
+        Self {
+            address: row.get("address"),
+            identity_id: row.get("identity_id"),
+            consumer: row.get("consumer"),
+            permission_type: row.get("permission_type"),
+            data_types: row.get("data_types"),
+            granted_at: row.get("granted_at"),
+            expires_at: row.get("expires_at"),
+            is_active: row.get("is_active"),
+            is_trial: row.get("is_trial"),
+            access_count: row.get("access_count"),
+            revoked_at: row.get("revoked_at"),
+            slot: row.get("slot"),
+        }
+    }
+}
+
+#[derive(Serialize, SimpleObject)]
+#[graphql(complex)]
+pub struct Listing {
+    pub address: String,
+    pub listing_id: i64,
+    pub owner: String,
+    pub identity_id: String,
+    pub data_type: String,
+    /// `None` for private listings
+    pub price: Option<i64>,
+    pub is_private: bool,
+    pub is_active: bool,
+    pub description: String,
+    pub buyer: Option<String>,
+    pub created_at: i64,
+    pub sold_at: Option<i64>,
+    pub cancelled_at: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub slot: i64,
+}
+
+impl From<Row> for Listing {
+    fn from(row: Row) -> Self {
+        Self {
+            address: row.get("address"),
+            listing_id: row.get("listing_id"),
+            owner: row.get("owner"),
+            identity_id: row.get("identity_id"),
+            data_type: row.get("data_type"),
+            price: row.get("price"),
+            is_private: row.get("is_private"),
+            is_active: row.get("is_active"),
+            description: row.get("description"),
+            buyer: row.get("buyer"),
+            created_at: row.get("created_at"),
+            sold_at: row.get("sold_at"),
+            cancelled_at: row.get("cancelled_at"),
+            expires_at: row.get("expires_at"),
+            slot: row.get("slot"),
+        }
+    }
+}
+
+#[derive(Serialize, SimpleObject)]
+pub struct Receipt {
+    pub address: String,
+    pub listing: String,
+    pub listing_id: i64,
+    pub buyer: String,
+    pub payer: String,
+    pub seller: String,
+    /// `None` for confidential purchases
+    pub amount: Option<i64>,
+    pub is_confidential: bool,
+    pub purpose: String,
+    pub purchased_at: i64,
+    pub slot: i64,
+}
+
+impl From<Row> for Receipt {
+    fn from(row: Row) -> Self {
+        Self {
+            address: row.get("address"),
+            listing: row.get("listing"),
+            listing_id: row.get("listing_id"),
+            buyer: row.get("buyer"),
+            payer: row.get("payer"),
+            seller: row.get("seller"),
+            amount: row.get("amount"),
+            is_confidential: row.get("is_confidential"),
+            purpose: row.get("purpose"),
+            purchased_at: row.get("purchased_at"),
+            slot: row.get("slot"),
+        }
+    }
+}
+
+#[derive(Serialize, SimpleObject)]
+pub struct Oracle {
+    pub address: String,
+    pub authority: String,
+    pub provider_name: String,
+    pub stake_amount: i64,
+    pub verification_count: i64,
+    pub successful_verifications: i64,
+    pub reputation_score: i32,
+    pub is_active: bool,
+    pub registered_at: i64,
+    pub slot: i64,
+}
+
+impl From<Row> for Oracle {
+    fn from(row: Row) -> Self {
+        Self {
+            address: row.get("address"),
+            authority: row.get("authority"),
+            provider_name: row.get("provider_name"),
+            stake_amount: row.get("stake_amount"),
+            verification_count: row.get("verification_count"),
+            successful_verifications: row.get("successful_verifications"),
+            reputation_score: row.get("reputation_score"),
+            is_active: row.get("is_active"),
+            registered_at: row.get("registered_at"),
+            slot: row.get("slot"),
+        }
+    }
+}
+
+/// Aggregates over the oracle set
+#[derive(Serialize, SimpleObject)]
+pub struct OracleStats {
+    pub oracle_count: i64,
+    pub active_count: i64,
+    pub total_stake: i64,
+    pub verification_count: i64,
+    pub successful_verifications: i64,
+    /// Mean reputation of active oracles, `None` when there are none
+    pub average_reputation: Option<f64>,
+}
+
+/// One dated change to an identity, its permissions or its listings
+#[derive(Serialize, SimpleObject)]
+pub struct TimelineEntry {
+    pub at: i64,
+    /// e.g. `IdentityVerified`, `AccessGranted`, `ListingSold`
+    pub kind: String,
+    /// Consumer, buyer or listing id the change concerns, if any
+    pub subject: Option<String>,
+}
+
+#[derive(Enum, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingSort {
+    #[default]
+    Newest,
+    PriceAsc,
+    PriceDesc,
+}
+
+/// Listing search criteria; every filter is optional
+#[derive(Default)]
+pub struct ListingSearch {
+    /// Kebab-case data type, or `custom:<name>`
+    pub data_type: Option<String>,
+    pub owner: Option<String>,
+    pub identity_id: Option<String>,
+    pub active: Option<bool>,
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    pub sort: ListingSort,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub async fn identity(db: &Client, identity_id: &str) -> Result<Option<Identity>, ApiError> {
+    Ok(db
+        .query_opt("SELECT * FROM identities WHERE identity_id = $1", &[&identity_id])
+        .await?
+        .map(Identity::from))
+}
+
+pub async fn identities_by_owner(db: &Client, owner: &str) -> Result<Vec<Identity>, ApiError> {
+    let owner = error::pubkey("owner", owner)?.to_string();
+    let rows = db
+        .query(
+            "SELECT * FROM identities WHERE owner = $1 ORDER BY created_at",
+            &[&owner],
+        )
+        .await?;
+    Ok(rows.into_iter().map(Identity::from).collect())
+}
+
+pub async fn listing(db: &Client, listing_id: i64) -> Result<Option<Listing>, ApiError> {
+    Ok(db
+        .query_opt("SELECT * FROM listings WHERE listing_id = $1", &[&listing_id])
+        .await?
+        .map(Listing::from))
+}
+
+pub async fn search_listings(db: &Client, search: ListingSearch) -> Result<Vec<Listing>, ApiError> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut params: Vec<Box<dyn ToSql + Sync + Send>> = Vec::new();
+    let mut filter = |condition: &str, value: Box<dyn ToSql + Sync + Send>| {
+        params.push(value);
+        conditions.push(condition.replace('?', &format!("${}", params.len())));
+    };
+
+    if let Some(data_type) = &search.data_type {
+        // Stored by variant name, as the indexer writes it
+        let data_type = error::parse(parse::listing_data_type, data_type)?;
+        filter("data_type = ?", Box::new(format!("{:?}", data_type)));
+    }
+    if let Some(owner) = &search.owner {
+        filter("owner = ?", Box::new(error::pubkey("owner", owner)?.to_string()));
+    }
+    if let Some(identity_id) = search.identity_id {
+        filter("identity_id = ?", Box::new(identity_id));
+    }
+    if let Some(active) = search.active {
+        filter("is_active = ?", Box::new(active));
+    }
+    if let Some(min_price) = search.min_price {
+        filter("price >= ?", Box::new(min_price));
+    }
+    if let Some(max_price) = search.max_price {
+        filter("price <= ?", Box::new(max_price));
+    }
+
+    let order = match search.sort {
+        ListingSort::Newest => "created_at DESC, listing_id DESC",
+        ListingSort::PriceAsc => "price ASC NULLS LAST, listing_id",
+        ListingSort::PriceDesc => "price DESC NULLS LAST, listing_id",
+    };
+    let (limit, offset) = page(search.limit, search.offset);
+
+    let mut sql = "SELECT * FROM listings".to_string();
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql.push_str(&format!(" ORDER BY {} LIMIT {} OFFSET {}", order, limit, offset));
+
+    let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|param| param.as_ref() as _).collect();
+    let rows = db.query(&sql, &params).await?;
+    Ok(rows.into_iter().map(Listing::from).collect())
+}
+
+/// Receipts matching any combination of buyer, seller and listing, newest first
+pub async fn receipts(
+    db: &Client,
+    buyer: Option<&str>,
+    seller: Option<&str>,
+    listing_id: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<Receipt>, ApiError> {
+    let buyer = buyer.map(|buyer| error::pubkey("buyer", buyer)).transpose()?;
+    let seller = seller.map(|seller| error::pubkey("seller", seller)).transpose()?;
+    let (limit, offset) = page(limit, offset);
+    let rows = db
+        .query(
+            "SELECT * FROM receipts \
+             WHERE ($1::TEXT IS NULL OR buyer = $1) AND ($2::TEXT IS NULL OR seller = $2) \
+             AND ($3::BIGINT IS NULL OR listing_id = $3) \
+             ORDER BY purchased_at DESC LIMIT $4 OFFSET $5",
+            &[
+                &buyer.map(|buyer| buyer.to_string()),
+                &seller.map(|seller| seller.to_string()),
+                &listing_id,
+                &limit,
+                &offset,
+            ],
+        )
+        .await?;
+    Ok(rows.into_iter().map(Receipt::from).collect())
+}
+
+/// Permission edges from an identity, to a consumer, or both
+pub async fn permissions(
+    db: &Client,
+    identity_id: Option<&str>,
+    consumer: Option<&str>,
+    active_only: bool,
+) -> Result<Vec<Permission>, ApiError> {
+    let consumer = consumer
+        .map(|consumer| error::pubkey("consumer", consumer))
+        .transpose()?
+        .map(|consumer| consumer.to_string());
+    let rows = db
+        .query(
+            "SELECT * FROM permissions \
+             WHERE ($1::TEXT IS NULL OR identity_id = $1) AND ($2::TEXT IS NULL OR consumer = $2) \
+             AND (NOT $3 OR is_active) \
+             ORDER BY granted_at DESC",
+            &[&identity_id, &consumer, &active_only],
+        )
+        .await?;
+    Ok(rows.into_iter().map(Permission::from).collect())
+}
+
+/// Dated changes to an identity, newest first.
+///
+/// Derived from indexed account state, so a re-granted permission shows only its latest grant.
+pub async fn timeline(db: &Client, identity_id: &str, limit: Option<i64>) -> Result<Vec<TimelineEntry>, ApiError> {
+    let (limit, _) = page(limit, None);
+    let rows = db
+        .query(
+            "SELECT at, kind, subject FROM ( \
+                 SELECT created_at AS at, 'IdentityRegistered' AS kind, NULL::TEXT AS subject \
+                 FROM identities WHERE identity_id = $1 \
+                 UNION ALL SELECT verified_at, 'IdentityVerified', NULL \
+                 FROM identities WHERE identity_id = $1 AND verified_at IS NOT NULL \
+                 UNION ALL SELECT granted_at, 'AccessGranted', consumer \
+                 FROM permissions WHERE identity_id = $1 \
+                 UNION ALL SELECT revoked_at, 'AccessRevoked', consumer \
+                 FROM permissions WHERE identity_id = $1 AND revoked_at IS NOT NULL \
+                 UNION ALL SELECT created_at, 'ListingCreated', listing_id::TEXT \
+                 FROM listings WHERE identity_id = $1 \
+                 UNION ALL SELECT sold_at, 'ListingSold', buyer \
+                 FROM listings WHERE identity_id = $1 AND sold_at IS NOT NULL \
+                 UNION ALL SELECT cancelled_at, 'ListingCancelled', listing_id::TEXT \
+                 FROM listings WHERE identity_id = $1 AND cancelled_at IS NOT NULL \
+             ) timeline ORDER BY at DESC LIMIT $2",
+            &[&identity_id, &limit],
+        )
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| TimelineEntry {
+            at: row.get("at"),
+            kind: row.get("kind"),
+            subject: row.get("subject"),
+        })
+        .collect())
+}
+
+/// Oracles by reputation, best first
+pub async fn oracles(db: &Client, active_only: bool) -> Result<Vec<Oracle>, ApiError> {
+    let rows = db
+        .query(
+            "SELECT * FROM oracles WHERE (NOT $1 OR is_active) ORDER BY reputation_score DESC, authority",
+            &[&active_only],
+        )
+        .await?;
+    Ok(rows.into_iter().map(Oracle::from).collect())
+}
+
+pub async fn oracle_stats(db: &Client) -> Result<OracleStats, ApiError> {
+    let row = db
+        .query_one(
+            "SELECT COUNT(*) AS oracle_count, \
+             COUNT(*) FILTER (WHERE is_active) AS active_count, \
+             COALESCE(SUM(stake_amount), 0)::BIGINT AS total_stake, \
+             COALESCE(SUM(verification_count), 0)::BIGINT AS verification_count, \
+             COALESCE(SUM(successful_verifications), 0)::BIGINT AS successful_verifications, \
+             (AVG(reputation_score) FILTER (WHERE is_active))::FLOAT8 AS average_reputation \
+             FROM oracles",
+            &[],
+        )
+        .await?;
+    Ok(OracleStats {
+        oracle_count: row.get("oracle_count"),
+        active_count: row.get("active_count"),
+        total_stake: row.get("total_stake"),
+        verification_count: row.get("verification_count"),
+        successful_verifications: row.get("successful_verifications"),
+        average_reputation: row.get("average_reputation"),
+    })
+}
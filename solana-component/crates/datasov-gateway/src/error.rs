@@ -0,0 +1,51 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use datasov_client::ClientError;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::metrics::METRICS;
+
+/// Request failure, rendered as `{"error": "..."}` with a matching status code
+pub enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    Internal(anyhow::Error),
+}
+
+pub type ApiResult<T> = Result<Json<T>, ApiError>;
+
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(err: E) -> Self {
+        ApiError::Internal(err.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::Internal(err) => {
+                if let Some(ClientError::Rpc(_)) = err.downcast_ref() {
+                    METRICS.rpc_errors.inc();
+                }
+                log::error!("{:#}", err);
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal error".to_string())
+            }
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+pub fn pubkey(field: &str, value: &str) -> Result<Pubkey, ApiError> {
+    value
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("`{}` is not a valid pubkey", field)))
+}
+
+/// Run one of the shared kebab-case enum parsers, reporting failures as bad requests
+pub fn parse<T>(parser: fn(&str) -> Result<T, String>, value: &str) -> Result<T, ApiError> {
+    parser(value).map_err(ApiError::BadRequest)
+}
@@ -0,0 +1,213 @@
+//! GraphQL over indexed state at `/graphql` (GraphiQL on `GET`), for front ends that want
+//! listings, identity timelines, permission graphs and oracle stats in one round trip.
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, InputObject, Object, Schema, SimpleObject,
+};
+use axum::extract::State;
+use axum::response::Html;
+use axum::Json;
+
+use crate::db::{
+    self, Identity, Listing, ListingSearch, ListingSort, Oracle, OracleStats, Permission, Receipt, TimelineEntry,
+};
+use crate::error::ApiError;
+use crate::AppState;
+
+pub type DataSovSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 1000;
+
+pub fn schema(state: AppState) -> DataSovSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(state)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// `POST /graphql`
+pub async fn execute(
+    State(schema): State<DataSovSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+/// `GET /graphql`
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+impl From<ApiError> for async_graphql::Error {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::NotFound(message) | ApiError::BadRequest(message) => async_graphql::Error::new(message),
+            ApiError::Internal(err) => {
+                log::error!("{:#}", err);
+                async_graphql::Error::new("internal error")
+            }
+        }
+    }
+}
+
+fn db<'a>(ctx: &Context<'a>) -> &'a tokio_postgres::Client {
+    &ctx.data_unchecked::<AppState>().db
+}
+
+#[derive(InputObject, Default)]
+pub struct ListingFilter {
+    /// Kebab-case data type, or `custom:<name>`
+    data_type: Option<String>,
+    owner: Option<String>,
+    identity_id: Option<String>,
+    active: Option<bool>,
+    min_price: Option<i64>,
+    max_price: Option<i64>,
+}
+
+/// A data consumer and the permissions and purchases that point at it
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Consumer {
+    address: String,
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    async fn identity(&self, ctx: &Context<'_>, identity_id: String) -> async_graphql::Result<Option<Identity>> {
+        Ok(db::identity(db(ctx), &identity_id).await?)
+    }
+
+    async fn identities_by_owner(&self, ctx: &Context<'_>, owner: String) -> async_graphql::Result<Vec<Identity>> {
+        Ok(db::identities_by_owner(db(ctx), &owner).await?)
+    }
+
+    async fn listing(&self, ctx: &Context<'_>, listing_id: i64) -> async_graphql::Result<Option<Listing>> {
+        Ok(db::listing(db(ctx), listing_id).await?)
+    }
+
+    async fn listings(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ListingFilter>,
+        #[graphql(default)] sort: ListingSort,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> async_graphql::Result<Vec<Listing>> {
+        let filter = filter.unwrap_or_default();
+        let search = ListingSearch {
+            data_type: filter.data_type,
+            owner: filter.owner,
+            identity_id: filter.identity_id,
+            active: filter.active,
+            min_price: filter.min_price,
+            max_price: filter.max_price,
+            sort,
+            limit,
+            offset,
+        };
+        Ok(db::search_listings(db(ctx), search).await?)
+    }
+
+    async fn consumer(&self, address: String) -> Consumer {
+        Consumer { address }
+    }
+
+    async fn oracles(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] active_only: bool,
+    ) -> async_graphql::Result<Vec<Oracle>> {
+        Ok(db::oracles(db(ctx), active_only).await?)
+    }
+
+    async fn oracle_stats(&self, ctx: &Context<'_>) -> async_graphql::Result<OracleStats> {
+        Ok(db::oracle_stats(db(ctx)).await?)
+    }
+}
+
+#[ComplexObject]
+impl Identity {
+    /// Dated changes to the identity, its permissions and its listings, newest first
+    async fn timeline(&self, ctx: &Context<'_>, limit: Option<i64>) -> async_graphql::Result<Vec<TimelineEntry>> {
+        Ok(db::timeline(db(ctx), &self.identity_id, limit).await?)
+    }
+
+    /// Consumers this identity has granted access to
+    async fn permissions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] active_only: bool,
+    ) -> async_graphql::Result<Vec<Permission>> {
+        Ok(db::permissions(db(ctx), Some(&self.identity_id), None, active_only).await?)
+    }
+
+    async fn listings(
+        &self,
+        ctx: &Context<'_>,
+        active: Option<bool>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> async_graphql::Result<Vec<Listing>> {
+        let search = ListingSearch {
+            identity_id: Some(self.identity_id.clone()),
+            active,
+            limit,
+            offset,
+            ..ListingSearch::default()
+        };
+        Ok(db::search_listings(db(ctx), search).await?)
+    }
+}
+
+#[ComplexObject]
+impl Permission {
+    /// The granting identity
+    async fn identity(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Identity>> {
+        Ok(db::identity(db(ctx), &self.identity_id).await?)
+    }
+
+    async fn grantee(&self) -> Consumer {
+        Consumer {
+            address: self.consumer.clone(),
+        }
+    }
+}
+
+#[ComplexObject]
+impl Listing {
+    /// The seller's identity
+    async fn identity(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Identity>> {
+        Ok(db::identity(db(ctx), &self.identity_id).await?)
+    }
+
+    async fn receipts(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Receipt>> {
+        Ok(db::receipts(db(ctx), None, None, Some(self.listing_id), None, None).await?)
+    }
+}
+
+#[ComplexObject]
+impl Consumer {
+    /// Identities that have granted this consumer access
+    async fn permissions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] active_only: bool,
+    ) -> async_graphql::Result<Vec<Permission>> {
+        Ok(db::permissions(db(ctx), None, Some(&self.address), active_only).await?)
+    }
+
+    async fn purchases(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> async_graphql::Result<Vec<Receipt>> {
+        Ok(db::receipts(db(ctx), Some(&self.address), None, None, limit, offset).await?)
+    }
+}
@@ -0,0 +1,87 @@
+//! `datasov-gateway`: HTTP API for wallets and dapps.
+//!
+//! REST reads come from the indexer's Postgres database, except permission checks, which are evaluated
+//! against the permission account on chain. Writes never touch a key: they return an unsigned
+//! transaction, with a recent blockhash and the caller as fee payer, for the wallet to sign and send.
+//! The same indexed state is queryable over GraphQL at `/graphql`, and Prometheus metrics are
+//! served at `/metrics`.
+//!
+//! Configuration (environment):
+//! - `DATABASE_URL` (required), the indexer database
+//! - `DATASOV_RPC_URL` (default `http://127.0.0.1:8899`)
+//! - `GATEWAY_ADDR` (default `0.0.0.0:8080`)
+
+mod db;
+mod error;
+mod graphql;
+mod metrics;
+mod read;
+mod write;
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::middleware;
+use axum::routing::{get, post};
+use axum::Router;
+use datasov_client::rpc::{DataSovRpc, RpcClient};
+use log::{error, info};
+use solana_sdk::commitment_config::CommitmentConfig;
+use tokio_postgres::NoTls;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub db: Arc<tokio_postgres::Client>,
+    pub rpc: Arc<DataSovRpc>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set")?;
+    let rpc_url = std::env::var("DATASOV_RPC_URL").unwrap_or_else(|_| "http://127.0.0.1:8899".to_string());
+    let addr = std::env::var("GATEWAY_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string());
+
+    let (client, connection) = tokio_postgres::connect(&database_url, NoTls)
+        .await
+        .context("failed to connect to Postgres")?;
+    tokio::spawn(async move {
+        if let Err(err) = connection.await {
+            error!("Postgres connection closed: {}", err);
+        }
+    });
+
+    let state = AppState {
+        db: Arc::new(client),
+        rpc: Arc::new(DataSovRpc::from_client(RpcClient::new_with_commitment(
+            rpc_url,
+            CommitmentConfig::confirmed(),
+        ))),
+    };
+
+    let graphql = Router::new()
+        .route("/graphql", get(graphql::graphiql).post(graphql::execute))
+        .with_state(graphql::schema(state.clone()));
+    let app = Router::new()
+        .route("/identities/:identity_id", get(read::identity))
+        .route("/permissions/check", get(read::check_permission))
+        .route("/listings", get(read::listings))
+        .route("/listings/:listing_id", get(read::listing))
+        .route("/receipts", get(read::receipts))
+        .route("/transactions/register-identity", post(write::register_identity))
+        .route("/transactions/grant", post(write::grant))
+        .route("/transactions/revoke", post(write::revoke))
+        .route("/transactions/purchase", post(write::purchase))
+        .with_state(state)
+        .merge(graphql)
+        .route_layer(middleware::from_fn(metrics::track))
+        .route("/metrics", get(metrics::render));
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to bind {}", addr))?;
+    info!("Listening on {}", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
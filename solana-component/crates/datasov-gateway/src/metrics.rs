@@ -0,0 +1,70 @@
+//! Prometheus metrics, served in the text exposition format at `/metrics`.
+
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::HeaderName;
+use axum::middleware::Next;
+use axum::response::Response;
+use prometheus::{register_histogram_vec, register_int_counter, register_int_counter_vec, Encoder, TextEncoder};
+use prometheus::{HistogramVec, IntCounter, IntCounterVec};
+
+pub struct Metrics {
+    /// Requests answered, by route pattern and status code
+    pub requests: IntCounterVec,
+    /// Request latency in seconds, by route pattern
+    pub latency: HistogramVec,
+    /// Requests that failed on an RPC error
+    pub rpc_errors: IntCounter,
+}
+
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(|| Metrics {
+    requests: register_int_counter_vec!(
+        "datasov_gateway_requests_total",
+        "API requests answered",
+        &["route", "status"]
+    )
+    .unwrap(),
+    latency: register_histogram_vec!(
+        "datasov_gateway_request_duration_seconds",
+        "API request latency",
+        &["route"]
+    )
+    .unwrap(),
+    rpc_errors: register_int_counter!(
+        "datasov_gateway_rpc_errors_total",
+        "API requests failed on an RPC error"
+    )
+    .unwrap(),
+});
+
+/// Middleware recording the count and latency of every routed request
+pub async fn track(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |path| path.as_str().to_string());
+    let started = Instant::now();
+    let response = next.run(request).await;
+    METRICS
+        .latency
+        .with_label_values(&[&route])
+        .observe(started.elapsed().as_secs_f64());
+    METRICS
+        .requests
+        .with_label_values(&[&route, response.status().as_str()])
+        .inc();
+    response
+}
+
+/// `GET /metrics`
+pub async fn render() -> ([(HeaderName, String); 1], Vec<u8>) {
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut body) {
+        log::warn!("Failed to encode metrics: {}", err);
+    }
+    ([(CONTENT_TYPE, encoder.format_type().to_string())], body)
+}
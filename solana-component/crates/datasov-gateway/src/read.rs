@@ -0,0 +1,170 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use datasov_client::{parse, pda};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, Identity, Listing, ListingSearch, ListingSort, Receipt};
+use crate::error::{self, ApiError, ApiResult};
+use crate::AppState;
+
+/// `GET /identities/:identity_id`
+pub async fn identity(State(state): State<AppState>, Path(identity_id): Path<String>) -> ApiResult<Identity> {
+    db::identity(&state.db, &identity_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("identity {} not found", identity_id)))
+}
+
+#[derive(Deserialize)]
+pub struct PermissionQuery {
+    identity_id: String,
+    consumer: String,
+    /// Kebab-case data type the consumer wants to read
+    data_type: Option<String>,
+    /// Kebab-case purpose the consumer declares
+    purpose: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PermissionCheck {
+    allowed: bool,
+    /// Why access is denied, when it is
+    reason: Option<&'static str>,
+    permission_type: Option<String>,
+    expires_at: Option<i64>,
+    remaining_accesses: Option<u32>,
+}
+
+impl PermissionCheck {
+    fn denied(reason: &'static str) -> Self {
+        Self {
+            allowed: false,
+            reason: Some(reason),
+            permission_type: None,
+            expires_at: None,
+            remaining_accesses: None,
+        }
+    }
+}
+
+/// `GET /permissions/check?identity_id=..&consumer=..[&data_type=..][&purpose=..]`
+///
+/// Read from chain rather than the index, since a stale answer here would leak or block access.
+pub async fn check_permission(
+    State(state): State<AppState>,
+    Query(query): Query<PermissionQuery>,
+) -> ApiResult<PermissionCheck> {
+    let consumer = error::pubkey("consumer", &query.consumer)?;
+    let data_type = query
+        .data_type
+        .as_deref()
+        .map(|value| error::parse(parse::data_type, value))
+        .transpose()?;
+    let purpose = query
+        .purpose
+        .as_deref()
+        .map(|value| error::parse(parse::purpose, value))
+        .transpose()?;
+
+    let identity = pda::identity::identity(&query.identity_id);
+    let Some(permission) = state.rpc.fetch_permission(&identity, &consumer).await? else {
+        return Ok(Json(PermissionCheck::denied("no permission granted")));
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+    let denial = if !permission.is_active {
+        Some("permission revoked")
+    } else if permission.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        Some("permission expired")
+    } else if permission
+        .max_accesses
+        .is_some_and(|max| permission.access_count >= max)
+    {
+        Some("access limit reached")
+    } else if data_type.is_some_and(|data_type| !permission.data_types.contains(&data_type)) {
+        Some("data type not granted")
+    } else if purpose.is_some_and(|purpose| !permission.allows_purpose(&purpose)) {
+        Some("purpose not allowed")
+    } else {
+        None
+    };
+
+    Ok(Json(PermissionCheck {
+        allowed: denial.is_none(),
+        reason: denial,
+        permission_type: Some(format!("{:?}", permission.permission_type)),
+        expires_at: permission.expires_at,
+        remaining_accesses: permission
+            .max_accesses
+            .map(|max| max.saturating_sub(permission.access_count)),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ListingQuery {
+    /// Kebab-case data type, or `custom:<name>`
+    data_type: Option<String>,
+    owner: Option<String>,
+    identity_id: Option<String>,
+    active: Option<bool>,
+    min_price: Option<i64>,
+    max_price: Option<i64>,
+    /// `newest` (default), `price_asc` or `price_desc`
+    sort: Option<ListingSort>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// `GET /listings`, filtered, sorted and paginated
+pub async fn listings(State(state): State<AppState>, Query(query): Query<ListingQuery>) -> ApiResult<Vec<Listing>> {
+    let search = ListingSearch {
+        data_type: query.data_type,
+        owner: query.owner,
+        identity_id: query.identity_id,
+        active: query.active,
+        min_price: query.min_price,
+        max_price: query.max_price,
+        sort: query.sort.unwrap_or_default(),
+        limit: query.limit,
+        offset: query.offset,
+    };
+    Ok(Json(db::search_listings(&state.db, search).await?))
+}
+
+/// `GET /listings/:listing_id`
+pub async fn listing(State(state): State<AppState>, Path(listing_id): Path<i64>) -> ApiResult<Listing> {
+    db::listing(&state.db, listing_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("listing {} not found", listing_id)))
+}
+
+#[derive(Deserialize)]
+pub struct ReceiptQuery {
+    buyer: Option<String>,
+    seller: Option<String>,
+    listing_id: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// `GET /receipts?buyer=..|seller=..|listing_id=..`, newest first
+pub async fn receipts(State(state): State<AppState>, Query(query): Query<ReceiptQuery>) -> ApiResult<Vec<Receipt>> {
+    if query.buyer.is_none() && query.seller.is_none() && query.listing_id.is_none() {
+        return Err(ApiError::BadRequest(
+            "one of `buyer`, `seller` or `listing_id` is required".to_string(),
+        ));
+    }
+    let receipts = db::receipts(
+        &state.db,
+        query.buyer.as_deref(),
+        query.seller.as_deref(),
+        query.listing_id,
+        query.limit,
+        query.offset,
+    )
+    .await?;
+    Ok(Json(receipts))
+}
@@ -0,0 +1,221 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::State;
+use axum::Json;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use datasov_client::identity::DataType;
+use datasov_client::instructions::{self, GrantAccessRequest, PurchaseRequest};
+use datasov_client::marketplace::PriceCandle;
+use datasov_client::{parse, pda, storage, TransactionBuilder};
+use serde::{Deserialize, Serialize};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{self, ApiError, ApiResult};
+use crate::AppState;
+
+/// A transaction for the wallet to sign and submit
+#[derive(Serialize)]
+pub struct UnsignedTransaction {
+    /// Base64 of the bincode-serialized `Transaction`, signatures zeroed
+    transaction: String,
+    recent_blockhash: String,
+    /// Accounts that must sign, fee payer first
+    signers: Vec<String>,
+}
+
+/// Wrap `instruction` in a transaction paid for by `payer`, stamped with a recent blockhash
+async fn unsigned(state: &AppState, payer: &Pubkey, instruction: Instruction) -> ApiResult<UnsignedTransaction> {
+    let mut transaction = TransactionBuilder::new().instruction(instruction).build_unsigned(payer);
+    transaction.message.recent_blockhash = state.rpc.call(|client| client.get_latest_blockhash()).await?;
+    let required = transaction.message.header.num_required_signatures as usize;
+    Ok(Json(UnsignedTransaction {
+        transaction: STANDARD.encode(bincode::serialize(&transaction)?),
+        recent_blockhash: transaction.message.recent_blockhash.to_string(),
+        signers: transaction.message.account_keys[..required]
+            .iter()
+            .map(Pubkey::to_string)
+            .collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct RegisterIdentityRequest {
+    owner: String,
+    identity_id: String,
+    /// Storage URI of the identity document
+    storage_pointer: String,
+}
+
+/// `POST /transactions/register-identity`
+pub async fn register_identity(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterIdentityRequest>,
+) -> ApiResult<UnsignedTransaction> {
+    let owner = error::pubkey("owner", &request.owner)?;
+    let storage_pointer = error::parse(storage::parse, &request.storage_pointer)?;
+    let instruction = instructions::register_identity(&owner, &request.identity_id, storage_pointer);
+    unsigned(&state, &owner, instruction).await
+}
+
+#[derive(Deserialize)]
+pub struct GrantRequest {
+    owner: String,
+    identity_id: String,
+    consumer: String,
+    /// Kebab-case, default `read-only`
+    permission_type: Option<String>,
+    /// Kebab-case data types
+    data_types: Vec<String>,
+    expires_at: Option<i64>,
+    /// Storage URI of the signed permission document
+    permission_pointer: String,
+}
+
+/// `POST /transactions/grant`
+pub async fn grant(State(state): State<AppState>, Json(request): Json<GrantRequest>) -> ApiResult<UnsignedTransaction> {
+    let owner = error::pubkey("owner", &request.owner)?;
+    let consumer = error::pubkey("consumer", &request.consumer)?;
+    let permission_pointer = error::parse(storage::parse, &request.permission_pointer)?;
+    let permission_type = error::parse(
+        parse::permission_type,
+        request.permission_type.as_deref().unwrap_or("read-only"),
+    )?;
+    if request.data_types.is_empty() {
+        return Err(ApiError::BadRequest("`data_types` must not be empty".to_string()));
+    }
+    let data_types = request
+        .data_types
+        .iter()
+        .map(|data_type| error::parse(parse::data_type, data_type))
+        .collect::<Result<Vec<DataType>, _>>()?;
+
+    let identity_address = pda::identity::identity(&request.identity_id);
+    let consumer_staked = state.rpc.has_consumer_stake(&consumer).await?;
+    let audit_entry_count = state.rpc.audit_entry_count(&identity_address).await?;
+    let instruction = instructions::grant_access(GrantAccessRequest {
+        owner,
+        identity_id: request.identity_id,
+        consumer,
+        permission_type,
+        data_types,
+        expires_at: request.expires_at,
+        permission_pointer,
+        consumer_staked,
+        guardian: None,
+        audit_entry_count,
+    });
+    unsigned(&state, &owner, instruction).await
+}
+
+#[derive(Deserialize)]
+pub struct RevokeRequest {
+    owner: String,
+    identity_id: String,
+    consumer: String,
+    /// Storage URI of the revocation record
+    revocation_pointer: String,
+}
+
+/// `POST /transactions/revoke`
+pub async fn revoke(
+    State(state): State<AppState>,
+    Json(request): Json<RevokeRequest>,
+) -> ApiResult<UnsignedTransaction> {
+    let owner = error::pubkey("owner", &request.owner)?;
+    let consumer = error::pubkey("consumer", &request.consumer)?;
+    let revocation_pointer = error::parse(storage::parse, &request.revocation_pointer)?;
+    let audit_entry_count = state
+        .rpc
+        .audit_entry_count(&pda::identity::identity(&request.identity_id))
+        .await?;
+    let instruction = instructions::revoke_access(
+        &owner,
+        &request.identity_id,
+        &consumer,
+        revocation_pointer,
+        audit_entry_count,
+    );
+    unsigned(&state, &owner, instruction).await
+}
+
+#[derive(Deserialize)]
+pub struct PurchaseBody {
+    buyer: String,
+    /// Identity the access is granted to; must be owned by `buyer`
+    buyer_identity_id: String,
+    listing_id: u64,
+    /// Kebab-case purpose
+    purpose: String,
+    /// Settlement token mint
+    mint: String,
+    /// Refuse listings last attested fresh more than this many seconds ago
+    max_staleness: Option<i64>,
+}
+
+/// `POST /transactions/purchase`
+pub async fn purchase(
+    State(state): State<AppState>,
+    Json(request): Json<PurchaseBody>,
+) -> ApiResult<UnsignedTransaction> {
+    let buyer = error::pubkey("buyer", &request.buyer)?;
+    let mint = error::pubkey("mint", &request.mint)?;
+    let purpose = error::parse(parse::purpose, &request.purpose)?;
+
+    let listing = state
+        .rpc
+        .fetch_listing(request.listing_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("listing {} not found", request.listing_id)))?;
+    if listing.is_private() {
+        return Err(ApiError::BadRequest(format!(
+            "listing {} is private and needs a price reveal from the seller",
+            request.listing_id
+        )));
+    }
+    let buyer_identity = state
+        .rpc
+        .fetch_optional::<datasov_client::identity::IdentityAccount>(&pda::identity::identity(
+            &request.buyer_identity_id,
+        ))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("identity {} not found", request.buyer_identity_id)))?;
+    if buyer_identity.owner != buyer {
+        return Err(ApiError::BadRequest(format!(
+            "identity {} is not owned by the buyer",
+            request.buyer_identity_id
+        )));
+    }
+
+    let marketplace = state.rpc.fetch_marketplace().await?;
+    let buyer_staked = state.rpc.has_consumer_stake(&buyer).await?;
+    let buyer_weighted = state.rpc.has_stake_weight(&buyer).await?;
+    let rebate = state.rpc.rebate_ledgers(&buyer, &listing.payout_wallet).await?;
+    let audit_entry_count = state
+        .rpc
+        .audit_entry_count(&pda::identity::identity(listing.identity_id()))
+        .await?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    let instruction = instructions::purchase_data(PurchaseRequest {
+        buyer,
+        buyer_identity_id: request.buyer_identity_id,
+        listing_id: request.listing_id,
+        payout_wallet: listing.payout_wallet,
+        seller_identity_id: listing.identity_id().to_string(),
+        data_union: listing.union(),
+        data_type: listing.data_type(),
+        purpose,
+        mint,
+        escrowed: marketplace.refund_window > 0,
+        buyer_staked,
+        buyer_weighted,
+        rebate,
+        max_staleness: request.max_staleness,
+        day: PriceCandle::day_of(now),
+        audit_entry_count,
+    });
+    unsigned(&state, &buyer, instruction).await
+}
@@ -0,0 +1,225 @@
+//! CPI interface to the DataSov identity program, for Anchor programs gating their own
+//! instructions on DataSov access permissions.
+//!
+//! The identity program's accounts travel as the calling instruction's remaining accounts, in the
+//! order the account-meta builders produce, so the caller's own `Accounts` struct stays unchanged:
+//!
+//! ```ignore
+//! // Off-chain, when building the caller's instruction
+//! instruction.accounts.extend(datasov_identity_cpi::validate_access_metas(&consumer, true, "alice", false, entry_count));
+//!
+//! // On-chain, in the caller's handler
+//! let validation = datasov_identity_cpi::validate_access_cpi(ctx.remaining_accounts, &[], DataType::AppUsage, 0)?;
+//! require!(validation.allows_purpose(&Purpose::Research), MyError::PurposeNotGranted);
+//! ```
+//!
+//! A consumer that is a PDA of the calling program signs with `signer_seeds`; its meta is then
+//! built with `consumer_signs: false`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::solana_program::program::get_return_data;
+use datasov_audit::AuditPage;
+use datasov_identity::cpi::accounts::{AuditAccounts, GrantAccess, ValidateAccess};
+use datasov_identity::seeds;
+
+pub use datasov_identity::{AccessValidation, DataType, PermissionType, Purpose, StorageBackend, StoragePointer, ID};
+
+/// Accounts `validate_access_cpi` expects: `ValidateAccess` followed by the identity program
+pub const VALIDATE_ACCESS_ACCOUNTS: usize = 12;
+
+/// Accounts `grant_access_cpi` expects: `GrantAccess` followed by the identity program
+pub const GRANT_ACCESS_ACCOUNTS: usize = 19;
+
+/// Validate and meter `consumer`'s access to `data_type`, returning the grant as it stands after
+/// this access. `accounts` are those built by [`validate_access_metas`], in that order.
+pub fn validate_access_cpi<'info>(
+    accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+    data_type: DataType,
+    epsilon_cost: u32,
+) -> Result<AccessValidation> {
+    require!(
+        accounts.len() >= VALIDATE_ACCESS_ACCOUNTS,
+        ErrorCode::AccountNotEnoughKeys
+    );
+    let program = identity_program(&accounts[11])?;
+    let cpi_accounts = ValidateAccess {
+        permission: accounts[0].clone(),
+        identity: accounts[1].clone(),
+        blacklist: accounts[2].clone(),
+        compliance_matrix: accounts[3].clone(),
+        consumer_stake: optional(&accounts[4]),
+        consumer: accounts[5].clone(),
+        audit: audit_accounts(&accounts[6..11]),
+    };
+    datasov_identity::cpi::validate_access(
+        CpiContext::new_with_signer(program, cpi_accounts, signer_seeds),
+        data_type,
+        epsilon_cost,
+    )?;
+
+    let (program_id, data) = get_return_data().ok_or_else(|| error!(CpiError::MalformedReturnData))?;
+    parse_access_validation(&program_id, &data)
+}
+
+/// Grant `consumer` access to an identity on behalf of its owner, which signs directly or, as a
+/// PDA of the calling program, through `signer_seeds`. `accounts` are those built by
+/// [`grant_access_metas`], in that order.
+pub fn grant_access_cpi<'info>(
+    accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+    permission_type: PermissionType,
+    data_types: Vec<DataType>,
+    expires_at: Option<i64>,
+    permission_pointer: StoragePointer,
+) -> Result<()> {
+    require!(accounts.len() >= GRANT_ACCESS_ACCOUNTS, ErrorCode::AccountNotEnoughKeys);
+    let program = identity_program(&accounts[18])?;
+    let cpi_accounts = GrantAccess {
+        permission: accounts[0].clone(),
+        identity: accounts[1].clone(),
+        blacklist: accounts[2].clone(),
+        consumer_profile: accounts[3].clone(),
+        compliance_matrix: accounts[4].clone(),
+        consumer_stake: optional(&accounts[5]),
+        consumer: accounts[6].clone(),
+        data_trust: optional(&accounts[7]),
+        trust_membership: optional(&accounts[8]),
+        owner: accounts[9].clone(),
+        guardian: optional(&accounts[10]),
+        audit: audit_accounts(&accounts[11..16]),
+        oracle_registry: accounts[16].clone(),
+        system_program: accounts[17].clone(),
+    };
+    datasov_identity::cpi::grant_access(
+        CpiContext::new_with_signer(program, cpi_accounts, signer_seeds),
+        permission_type,
+        data_types,
+        expires_at,
+        permission_pointer,
+    )
+}
+
+/// Decode `validate_access` return data, as read on-chain after the CPI or off-chain from a
+/// simulation, checking it came from the identity program
+pub fn parse_access_validation(program_id: &Pubkey, data: &[u8]) -> Result<AccessValidation> {
+    require_keys_eq!(*program_id, ID, CpiError::ForeignReturnData);
+    AccessValidation::try_from_slice(data).map_err(|_| error!(CpiError::MalformedReturnData))
+}
+
+/// Metas for [`validate_access_cpi`]. `consumer_signs` is false when the consumer is a PDA of the
+/// calling program; `audit_entry_count` is the identity's current audit log length.
+pub fn validate_access_metas(
+    consumer: &Pubkey,
+    consumer_signs: bool,
+    identity_id: &str,
+    consumer_staked: bool,
+    audit_entry_count: u64,
+) -> Vec<AccountMeta> {
+    let identity = find(&[seeds::IDENTITY, &seeds::identity_id(identity_id)]);
+    let mut metas = datasov_identity::accounts::ValidateAccess {
+        permission: find(&[seeds::PERMISSION, identity.as_ref(), consumer.as_ref()]),
+        identity,
+        blacklist: find(&[seeds::BLACKLIST]),
+        compliance_matrix: find(&[seeds::COMPLIANCE_MATRIX]),
+        consumer_stake: consumer_staked.then(|| find(&[seeds::CONSUMER_STAKE, consumer.as_ref()])),
+        consumer: *consumer,
+        audit: audit_metas(&identity, audit_entry_count),
+    }
+    .to_account_metas(None);
+    metas[5].is_signer = consumer_signs;
+    metas.push(AccountMeta::new_readonly(ID, false));
+    metas
+}
+
+/// Metas for [`grant_access_cpi`]. `owner_signs` is false when the identity owner is a PDA of the
+/// calling program; `audit_entry_count` is the identity's current audit log length. Trust grants
+/// and guardian co-signing are not covered.
+pub fn grant_access_metas(
+    owner: &Pubkey,
+    owner_signs: bool,
+    identity_id: &str,
+    consumer: &Pubkey,
+    consumer_staked: bool,
+    audit_entry_count: u64,
+) -> Vec<AccountMeta> {
+    let identity = find(&[seeds::IDENTITY, &seeds::identity_id(identity_id)]);
+    let mut metas = datasov_identity::accounts::GrantAccess {
+        permission: find(&[seeds::PERMISSION, identity.as_ref(), consumer.as_ref()]),
+        identity,
+        blacklist: find(&[seeds::BLACKLIST]),
+        consumer_profile: find(&[seeds::CONSUMER, consumer.as_ref()]),
+        compliance_matrix: find(&[seeds::COMPLIANCE_MATRIX]),
+        consumer_stake: consumer_staked.then(|| find(&[seeds::CONSUMER_STAKE, consumer.as_ref()])),
+        consumer: *consumer,
+        data_trust: None,
+        trust_membership: None,
+        owner: *owner,
+        guardian: None,
+        audit: audit_metas(&identity, audit_entry_count),
+        oracle_registry: find(&[seeds::ORACLE_REGISTRY]),
+        system_program: anchor_lang::system_program::ID,
+    }
+    .to_account_metas(None);
+    metas[9].is_signer = owner_signs;
+    metas.push(AccountMeta::new_readonly(ID, false));
+    metas
+}
+
+/// Errors from this interface, numbered clear of the calling program's own
+#[error_code(offset = 9000)]
+pub enum CpiError {
+    #[msg("Return data is not from the DataSov identity program")]
+    ForeignReturnData,
+    #[msg("Missing or malformed access validation return data")]
+    MalformedReturnData,
+}
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &ID).0
+}
+
+fn audit_metas(identity: &Pubkey, entry_count: u64) -> datasov_identity::accounts::AuditAccounts {
+    let audit_log = Pubkey::find_program_address(
+        &[datasov_audit::seeds::AUDIT_LOG, identity.as_ref()],
+        &datasov_audit::ID,
+    )
+    .0;
+    let page_index = entry_count / AuditPage::MAX_ENTRIES as u64;
+    datasov_identity::accounts::AuditAccounts {
+        audit_log,
+        audit_page: Pubkey::find_program_address(
+            &[
+                datasov_audit::seeds::AUDIT_PAGE,
+                audit_log.as_ref(),
+                page_index.to_le_bytes().as_ref(),
+            ],
+            &datasov_audit::ID,
+        )
+        .0,
+        audit_writer: find(&[datasov_audit::seeds::AUDIT_WRITER]),
+        audit_program: datasov_audit::ID,
+        system_program: anchor_lang::system_program::ID,
+    }
+}
+
+fn identity_program<'info>(account: &AccountInfo<'info>) -> Result<AccountInfo<'info>> {
+    require_keys_eq!(account.key(), ID, ErrorCode::InvalidProgramId);
+    Ok(account.clone())
+}
+
+/// An optional account left out is passed as the identity program's ID
+fn optional<'info>(account: &AccountInfo<'info>) -> Option<AccountInfo<'info>> {
+    (account.key() != ID).then(|| account.clone())
+}
+
+fn audit_accounts<'info>(accounts: &[AccountInfo<'info>]) -> AuditAccounts<'info> {
+    AuditAccounts {
+        audit_log: accounts[0].clone(),
+        audit_page: accounts[1].clone(),
+        audit_writer: accounts[2].clone(),
+        audit_program: accounts[3].clone(),
+        system_program: accounts[4].clone(),
+    }
+}
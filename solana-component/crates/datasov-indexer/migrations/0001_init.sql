@@ -0,0 +1,106 @@
+-- DataSov indexer schema. Pubkeys are base58 text, u64 amounts are BIGINT, program enums are
+-- stored by variant name and storage pointers as `<scheme>://<reference>` URIs. Every row records
+-- the slot it was last written at so out-of-order notifications never overwrite newer state.
+
+CREATE TABLE IF NOT EXISTS identities (
+    address            TEXT PRIMARY KEY,
+    identity_id        TEXT NOT NULL UNIQUE,
+    owner              TEXT NOT NULL,
+    status             TEXT NOT NULL,
+    verification_level TEXT NOT NULL,
+    verified_at        BIGINT,
+    storage_pointer    TEXT NOT NULL,
+    guardian           TEXT,
+    sns_domain         TEXT,
+    created_at         BIGINT NOT NULL,
+    updated_at         BIGINT NOT NULL,
+    slot               BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS identities_owner_idx ON identities (owner);
+
+CREATE TABLE IF NOT EXISTS oracles (
+    address                  TEXT PRIMARY KEY,
+    authority                TEXT NOT NULL UNIQUE,
+    provider_name            TEXT NOT NULL,
+    stake_amount             BIGINT NOT NULL,
+    verification_count       BIGINT NOT NULL,
+    successful_verifications BIGINT NOT NULL,
+    reputation_score         INTEGER NOT NULL,
+    is_active                BOOLEAN NOT NULL,
+    registered_at            BIGINT NOT NULL,
+    slot                     BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS permissions (
+    address         TEXT PRIMARY KEY,
+    identity_id     TEXT NOT NULL,
+    consumer        TEXT NOT NULL,
+    permission_type TEXT NOT NULL,
+    data_types      TEXT[] NOT NULL,
+    granted_at      BIGINT NOT NULL,
+    expires_at      BIGINT,
+    is_active       BOOLEAN NOT NULL,
+    is_trial        BOOLEAN NOT NULL,
+    access_count    BIGINT NOT NULL,
+    revoked_at      BIGINT,
+    slot            BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS permissions_identity_idx ON permissions (identity_id);
+CREATE INDEX IF NOT EXISTS permissions_consumer_idx ON permissions (consumer);
+
+CREATE TABLE IF NOT EXISTS listings (
+    address      TEXT PRIMARY KEY,
+    listing_id   BIGINT NOT NULL UNIQUE,
+    owner        TEXT NOT NULL,
+    identity_id  TEXT NOT NULL,
+    data_type    TEXT NOT NULL,
+    -- NULL for private listings, whose price is only a commitment on chain
+    price        BIGINT,
+    is_private   BOOLEAN NOT NULL,
+    is_active    BOOLEAN NOT NULL,
+    description  TEXT NOT NULL,
+    buyer        TEXT,
+    created_at   BIGINT NOT NULL,
+    sold_at      BIGINT,
+    cancelled_at BIGINT,
+    expires_at   BIGINT,
+    slot         BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS listings_owner_idx ON listings (owner);
+CREATE INDEX IF NOT EXISTS listings_data_type_idx ON listings (data_type);
+
+CREATE TABLE IF NOT EXISTS receipts (
+    address         TEXT PRIMARY KEY,
+    listing         TEXT NOT NULL,
+    listing_id      BIGINT NOT NULL,
+    buyer           TEXT NOT NULL,
+    payer           TEXT NOT NULL,
+    seller          TEXT NOT NULL,
+    -- NULL for confidential purchases
+    amount          BIGINT,
+    is_confidential BOOLEAN NOT NULL,
+    purpose         TEXT NOT NULL,
+    purchased_at    BIGINT NOT NULL,
+    slot            BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS receipts_buyer_idx ON receipts (buyer);
+CREATE INDEX IF NOT EXISTS receipts_seller_idx ON receipts (seller);
+
+CREATE TABLE IF NOT EXISTS events (
+    signature   TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        BIGINT NOT NULL,
+    program     TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    -- Borsh-encoded event body, without the 8-byte discriminator
+    data        BYTEA NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_name_idx ON events (name, slot);
+
+-- Newest transaction processed per program, so a restarted indexer backfills only the gap
+CREATE TABLE IF NOT EXISTS cursors (
+    program        TEXT PRIMARY KEY,
+    last_signature TEXT NOT NULL,
+    last_slot      BIGINT NOT NULL
+);
@@ -0,0 +1,47 @@
+//! Decoding of the program accounts the indexer normalizes.
+
+use anchor_lang::{AccountDeserialize, Discriminator, Space};
+use datasov_client::identity::{self, AccessPermission, IdentityAccount, KYCOracle};
+use datasov_client::marketplace::{self, DataListing, PurchaseReceipt};
+use solana_sdk::pubkey::Pubkey;
+
+pub enum IndexedAccount {
+    Identity(IdentityAccount),
+    Oracle(KYCOracle),
+    Permission(AccessPermission),
+    Listing(Box<DataListing>),
+    Receipt(PurchaseReceipt),
+}
+
+fn try_decode<T: AccountDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+    if !data.starts_with(&T::DISCRIMINATOR) {
+        return None;
+    }
+    T::try_deserialize(&mut &data[..]).ok()
+}
+
+/// Listings are zero-copy and decode only at the current size; older layouts wait for migration
+fn try_decode_listing(data: &[u8]) -> Option<DataListing> {
+    if data.len() != 8 + DataListing::INIT_SPACE {
+        return None;
+    }
+    try_decode(data)
+}
+
+impl IndexedAccount {
+    /// Decode an account owned by `owner`; `None` for account types the indexer does not track
+    pub fn decode(owner: &Pubkey, data: &[u8]) -> Option<Self> {
+        if *owner == identity::ID {
+            try_decode(data)
+                .map(Self::Identity)
+                .or_else(|| try_decode(data).map(Self::Oracle))
+                .or_else(|| try_decode(data).map(Self::Permission))
+        } else if *owner == marketplace::ID {
+            try_decode_listing(data)
+                .map(|listing| Self::Listing(Box::new(listing)))
+                .or_else(|| try_decode(data).map(Self::Receipt))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Catch-up from RPC: a full account snapshot, then every transaction since the stored cursor.
+
+use anchor_lang::{AccountDeserialize, Discriminator, Owner};
+use anyhow::Result;
+use datasov_client::query::ListingQuery;
+use datasov_client::rpc::DataSovRpc;
+use log::info;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_filter::RpcFilterType;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::accounts::IndexedAccount;
+use crate::metrics::METRICS;
+use crate::store::Store;
+
+async fn snapshot<T: AccountDeserialize + Discriminator + Owner>(
+    rpc: &DataSovRpc,
+    store: &Store,
+    slot: u64,
+    filters: Vec<RpcFilterType>,
+    wrap: fn(T) -> IndexedAccount,
+) -> Result<usize> {
+    let accounts = rpc.fetch_all::<T>(filters).await?;
+    let count = accounts.len();
+    for (address, account) in accounts {
+        store.upsert_account(&address, slot, &wrap(account)).await?;
+    }
+    METRICS.account_updates.inc_by(count as u64);
+    Ok(count)
+}
+
+/// Upsert every tracked account as of the current slot
+pub async fn accounts(rpc: &DataSovRpc, store: &Store) -> Result<()> {
+    let slot = rpc.client().get_slot().await?;
+    let identities = snapshot(rpc, store, slot, Vec::new(), IndexedAccount::Identity).await?;
+    let oracles = snapshot(rpc, store, slot, Vec::new(), IndexedAccount::Oracle).await?;
+    let permissions = snapshot(rpc, store, slot, Vec::new(), IndexedAccount::Permission).await?;
+    let listings = snapshot(rpc, store, slot, ListingQuery::new().filters(), |listing| {
+        IndexedAccount::Listing(Box::new(listing))
+    })
+    .await?;
+    let receipts = snapshot(rpc, store, slot, Vec::new(), IndexedAccount::Receipt).await?;
+    info!(
+        "Account snapshot at slot {}: {} identities, {} oracles, {} permissions, {} listings, {} receipts",
+        slot, identities, oracles, permissions, listings, receipts
+    );
+    Ok(())
+}
+
+/// Index events from every transaction involving `program` since its cursor, oldest first
+pub async fn events(rpc: &DataSovRpc, store: &Store, program: &Pubkey) -> Result<()> {
+    let client = rpc.client();
+    let until = store
+        .cursor(program)
+        .await?
+        .map(|signature| signature.parse::<Signature>())
+        .transpose()?;
+
+    let mut pending = Vec::new();
+    let mut before = None;
+    loop {
+        let page = client
+            .get_signatures_for_address_with_config(
+                program,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: None,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+        let Some(last) = page.last() else { break };
+        before = Some(last.signature.parse()?);
+        pending.extend(page);
+    }
+
+    info!("Backfilling {} transactions for {}", pending.len(), program);
+    for status in pending.into_iter().rev() {
+        if status.err.is_none() {
+            let transaction = client
+                .get_transaction_with_config(
+                    &status.signature.parse()?,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Json),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )
+                .await?;
+            let log_messages: Option<Vec<String>> = transaction
+                .transaction
+                .meta
+                .and_then(|meta| meta.log_messages.into());
+            let events = datasov_events::parse_logs(&log_messages.unwrap_or_default());
+            store.insert_events(&status.signature, status.slot, &events).await?;
+            METRICS.events.with_label_values(&[&program.to_string()]).inc_by(events.len() as u64);
+        }
+        store.set_cursor(program, &status.signature, status.slot).await?;
+        METRICS.indexed(status.slot);
+    }
+    Ok(())
+}
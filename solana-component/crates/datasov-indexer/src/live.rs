@@ -0,0 +1,97 @@
+//! Live updates over the websocket: program account changes and transaction logs.
+
+use anyhow::{anyhow, Result};
+use futures_util::stream::{select_all, BoxStream, StreamExt};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_client::rpc_response::{Response, RpcKeyedAccount, RpcLogsResponse};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::accounts::IndexedAccount;
+use crate::metrics::METRICS;
+use crate::store::Store;
+
+enum Update {
+    Account(Response<RpcKeyedAccount>),
+    Logs(Pubkey, Response<RpcLogsResponse>),
+}
+
+/// Stream updates into the store until the websocket drops
+pub async fn run(ws_url: &str, store: &Store, programs: &[Pubkey]) -> Result<()> {
+    let pubsub = PubsubClient::new(ws_url).await?;
+    let mut streams: Vec<BoxStream<'_, Update>> = Vec::new();
+    let mut unsubscribes = Vec::new();
+
+    for program in programs {
+        let program = *program;
+        let (accounts, unsubscribe) = pubsub
+            .program_subscribe(
+                &program,
+                Some(RpcProgramAccountsConfig {
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                }),
+            )
+            .await?;
+        unsubscribes.push(unsubscribe);
+        streams.push(accounts.map(Update::Account).boxed());
+
+        let (transactions, unsubscribe) = pubsub
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![program.to_string()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+        unsubscribes.push(unsubscribe);
+        streams.push(transactions.map(move |response| Update::Logs(program, response)).boxed());
+    }
+
+    let mut updates = select_all(streams);
+    while let Some(update) = updates.next().await {
+        match update {
+            Update::Account(response) => {
+                let slot = response.context.slot;
+                let address: Pubkey = response.value.pubkey.parse()?;
+                match response.value.account.decode::<Account>() {
+                    Some(account) if account.lamports > 0 => {
+                        if let Some(indexed) = IndexedAccount::decode(&account.owner, &account.data) {
+                            store.upsert_account(&address, slot, &indexed).await?;
+                        }
+                    }
+                    // Closed accounts are reported with zero lamports
+                    _ => store.remove_account(&address).await?,
+                }
+                METRICS.account_updates.inc();
+                METRICS.indexed(slot);
+            }
+            Update::Logs(program, response) => {
+                let slot = response.context.slot;
+                let transaction = response.value;
+                if transaction.err.is_none() {
+                    let events = datasov_events::parse_logs(&transaction.logs);
+                    store.insert_events(&transaction.signature, slot, &events).await?;
+                    METRICS.events.with_label_values(&[&program.to_string()]).inc_by(events.len() as u64);
+                }
+                store.set_cursor(&program, &transaction.signature, slot).await?;
+                METRICS.indexed(slot);
+            }
+        }
+    }
+
+    drop(updates);
+    for unsubscribe in unsubscribes {
+        unsubscribe().await;
+    }
+    Err(anyhow!("websocket subscription closed"))
+}
@@ -0,0 +1,79 @@
+//! `datasov-indexer`: mirrors DataSov program state and events into Postgres.
+//!
+//! On start it applies `migrations/`, snapshots every tracked account, and replays transactions
+//! since the last stored cursor; it then follows program account changes and transaction logs over
+//! the websocket. When the websocket drops it backfills the gap and resubscribes.
+//!
+//! Configuration (environment):
+//! - `DATABASE_URL` (required), e.g. `postgres://datasov@localhost/datasov`
+//! - `DATASOV_RPC_URL` (default `http://127.0.0.1:8899`)
+//! - `DATASOV_WS_URL` (default `ws://127.0.0.1:8900`)
+//! - `METRICS_ADDR` (default `0.0.0.0:9100`), where Prometheus metrics are served at `/metrics`
+
+mod accounts;
+mod backfill;
+mod live;
+mod metrics;
+mod store;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use datasov_client::rpc::{DataSovRpc, RpcClient};
+use datasov_events::PROGRAMS;
+use log::{error, info, warn};
+use solana_sdk::commitment_config::CommitmentConfig;
+use tokio_postgres::NoTls;
+
+use metrics::METRICS;
+use store::Store;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set")?;
+    let rpc_url = std::env::var("DATASOV_RPC_URL").unwrap_or_else(|_| "http://127.0.0.1:8899".to_string());
+    let ws_url = std::env::var("DATASOV_WS_URL").unwrap_or_else(|_| "ws://127.0.0.1:8900".to_string());
+    let metrics_addr = std::env::var("METRICS_ADDR").unwrap_or_else(|_| "0.0.0.0:9100".to_string());
+
+    let (client, connection) = tokio_postgres::connect(&database_url, NoTls)
+        .await
+        .context("failed to connect to Postgres")?;
+    tokio::spawn(async move {
+        if let Err(err) = connection.await {
+            error!("Postgres connection closed: {}", err);
+        }
+    });
+    let store = Store::new(client);
+    store.migrate().await?;
+
+    let rpc = Arc::new(DataSovRpc::from_client(RpcClient::new_with_commitment(
+        rpc_url,
+        CommitmentConfig::confirmed(),
+    )));
+    tokio::spawn(async move {
+        if let Err(err) = metrics::serve(metrics_addr).await {
+            error!("Metrics server stopped: {:#}", err);
+        }
+    });
+    let poll_rpc = rpc.clone();
+    tokio::spawn(async move { metrics::poll(&poll_rpc).await });
+
+    loop {
+        backfill::accounts(&rpc, &store).await?;
+        for program in &PROGRAMS {
+            backfill::events(&rpc, &store, program).await?;
+        }
+
+        info!("Subscribing to {}", ws_url);
+        if let Err(err) = live::run(&ws_url, &store, &PROGRAMS).await {
+            METRICS.rpc_errors.with_label_values(&["websocket"]).inc();
+            warn!("Live indexing stopped: {:#}", err);
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
@@ -0,0 +1,99 @@
+//! Prometheus metrics, served in the text exposition format at `/metrics`.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::HeaderName;
+use axum::routing::get;
+use axum::Router;
+use datasov_client::rpc::DataSovRpc;
+use log::{info, warn};
+use prometheus::{register_int_counter, register_int_counter_vec, register_int_gauge, Encoder, TextEncoder};
+use prometheus::{IntCounter, IntCounterVec, IntGauge};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+pub struct Metrics {
+    /// Events stored, by emitting program
+    pub events: IntCounterVec,
+    /// Account upserts and removals applied
+    pub account_updates: IntCounter,
+    /// Highest slot the store has caught up to
+    pub indexed_slot: IntGauge,
+    /// Slots between the cluster and `indexed_slot`
+    pub lag: IntGauge,
+    /// Failed RPC and websocket requests, by source
+    pub rpc_errors: IntCounterVec,
+    pub marketplace_volume: IntGauge,
+    pub marketplace_listings: IntGauge,
+}
+
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(|| Metrics {
+    events: register_int_counter_vec!("datasov_indexer_events_total", "Events indexed", &["program"]).unwrap(),
+    account_updates: register_int_counter!("datasov_indexer_account_updates_total", "Account updates indexed").unwrap(),
+    indexed_slot: register_int_gauge!("datasov_indexer_indexed_slot", "Highest slot indexed").unwrap(),
+    lag: register_int_gauge!("datasov_indexer_lag_slots", "Slots the index trails the cluster by").unwrap(),
+    rpc_errors: register_int_counter_vec!("datasov_indexer_rpc_errors_total", "Failed RPC requests", &["source"])
+        .unwrap(),
+    marketplace_volume: register_int_gauge!(
+        "datasov_marketplace_volume",
+        "Total settled purchase volume, in settlement token base units"
+    )
+    .unwrap(),
+    marketplace_listings: register_int_gauge!("datasov_marketplace_listings", "Listings ever created").unwrap(),
+});
+
+impl Metrics {
+    /// Record that everything up to `slot` is indexed
+    pub fn indexed(&self, slot: u64) {
+        if slot as i64 > self.indexed_slot.get() {
+            self.indexed_slot.set(slot as i64);
+        }
+    }
+}
+
+/// Serve `/metrics` on `addr` until the process exits
+pub async fn serve(addr: String) -> Result<()> {
+    let app = Router::new().route("/metrics", get(render));
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to bind {}", addr))?;
+    info!("Serving metrics on {}", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn render() -> ([(HeaderName, String); 1], Vec<u8>) {
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut body) {
+        warn!("Failed to encode metrics: {}", err);
+    }
+    ([(CONTENT_TYPE, encoder.format_type().to_string())], body)
+}
+
+/// Refresh the lag and marketplace gauges from the cluster every poll interval
+pub async fn poll(rpc: &DataSovRpc) {
+    loop {
+        match rpc.call(|client| client.get_slot()).await {
+            Ok(slot) => METRICS.lag.set((slot as i64 - METRICS.indexed_slot.get()).max(0)),
+            Err(err) => {
+                METRICS.rpc_errors.with_label_values(&["slot"]).inc();
+                warn!("Failed to fetch the current slot: {}", err);
+            }
+        }
+        match rpc.fetch_marketplace().await {
+            Ok(marketplace) => {
+                METRICS.marketplace_volume.set(marketplace.total_volume as i64);
+                METRICS.marketplace_listings.set(marketplace.total_listings as i64);
+            }
+            Err(err) => {
+                METRICS.rpc_errors.with_label_values(&["marketplace"]).inc();
+                warn!("Failed to fetch the marketplace: {}", err);
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,238 @@
+//! Postgres writes. Upserts only apply when the incoming slot is at least the stored one.
+
+use anyhow::Result;
+use datasov_events::RawEvent;
+use solana_sdk::pubkey::Pubkey;
+use tokio_postgres::Client;
+
+use crate::accounts::IndexedAccount;
+
+const SCHEMA: &str = include_str!("../migrations/0001_init.sql");
+
+pub struct Store {
+    client: Client,
+}
+
+fn key(pubkey: &Pubkey) -> String {
+    pubkey.to_string()
+}
+
+fn optional_key(pubkey: Option<Pubkey>) -> Option<String> {
+    pubkey.as_ref().map(key)
+}
+
+impl Store {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Create tables and indexes that do not exist yet
+    pub async fn migrate(&self) -> Result<()> {
+        self.client.batch_execute(SCHEMA).await?;
+        Ok(())
+    }
+
+    pub async fn upsert_account(&self, address: &Pubkey, slot: u64, account: &IndexedAccount) -> Result<()> {
+        let address = key(address);
+        let slot = slot as i64;
+        match account {
+            IndexedAccount::Identity(identity) => {
+                self.client
+                    .execute(
+                        "INSERT INTO identities (address, identity_id, owner, status, verification_level, verified_at, \
+                         storage_pointer, guardian, sns_domain, created_at, updated_at, slot) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) \
+                         ON CONFLICT (address) DO UPDATE SET status = EXCLUDED.status, \
+                         verification_level = EXCLUDED.verification_level, verified_at = EXCLUDED.verified_at, \
+                         storage_pointer = EXCLUDED.storage_pointer, guardian = EXCLUDED.guardian, \
+                         sns_domain = EXCLUDED.sns_domain, updated_at = EXCLUDED.updated_at, slot = EXCLUDED.slot \
+                         WHERE identities.slot <= EXCLUDED.slot",
+                        &[
+                            &address,
+                            &identity.identity_id,
+                            &key(&identity.owner),
+                            &format!("{:?}", identity.status),
+                            &format!("{:?}", identity.verification_level),
+                            &identity.verified_at,
+                            &identity.storage_pointer.to_string(),
+                            &optional_key(identity.guardian),
+                            &(!identity.sns_domain.is_empty()).then(|| identity.sns_domain.clone()),
+                            &identity.created_at,
+                            &identity.updated_at,
+                            &slot,
+                        ],
+                    )
+                    .await?;
+            }
+            IndexedAccount::Oracle(oracle) => {
+                self.client
+                    .execute(
+                        "INSERT INTO oracles (address, authority, provider_name, stake_amount, verification_count, \
+                         successful_verifications, reputation_score, is_active, registered_at, slot) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+                         ON CONFLICT (address) DO UPDATE SET stake_amount = EXCLUDED.stake_amount, \
+                         verification_count = EXCLUDED.verification_count, \
+                         successful_verifications = EXCLUDED.successful_verifications, \
+                         reputation_score = EXCLUDED.reputation_score, is_active = EXCLUDED.is_active, \
+                         slot = EXCLUDED.slot \
+                         WHERE oracles.slot <= EXCLUDED.slot",
+                        &[
+                            &address,
+                            &key(&oracle.oracle_pubkey),
+                            &oracle.provider_name,
+                            &(oracle.stake_amount as i64),
+                            &(oracle.verification_count as i64),
+                            &(oracle.successful_verifications as i64),
+                            &(oracle.reputation_score as i32),
+                            &oracle.is_active,
+                            &oracle.registered_at,
+                            &slot,
+                        ],
+                    )
+                    .await?;
+            }
+            IndexedAccount::Permission(permission) => {
+                let data_types: Vec<String> = permission
+                    .data_types
+                    .iter()
+                    .map(|data_type| format!("{:?}", data_type))
+                    .collect();
+                self.client
+                    .execute(
+                        "INSERT INTO permissions (address, identity_id, consumer, permission_type, data_types, \
+                         granted_at, expires_at, is_active, is_trial, access_count, revoked_at, slot) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) \
+                         ON CONFLICT (address) DO UPDATE SET permission_type = EXCLUDED.permission_type, \
+                         data_types = EXCLUDED.data_types, granted_at = EXCLUDED.granted_at, \
+                         expires_at = EXCLUDED.expires_at, is_active = EXCLUDED.is_active, \
+                         is_trial = EXCLUDED.is_trial, access_count = EXCLUDED.access_count, \
+                         revoked_at = EXCLUDED.revoked_at, slot = EXCLUDED.slot \
+                         WHERE permissions.slot <= EXCLUDED.slot",
+                        &[
+                            &address,
+                            &permission.identity_id,
+                            &key(&permission.consumer),
+                            &format!("{:?}", permission.permission_type),
+                            &data_types,
+                            &permission.granted_at,
+                            &permission.expires_at,
+                            &permission.is_active,
+                            &permission.is_trial,
+                            &(permission.access_count as i64),
+                            &permission.revoked_at,
+                            &slot,
+                        ],
+                    )
+                    .await?;
+            }
+            IndexedAccount::Listing(listing) => {
+                self.client
+                    .execute(
+                        "INSERT INTO listings (address, listing_id, owner, identity_id, data_type, price, is_private, \
+                         is_active, description, buyer, created_at, sold_at, cancelled_at, expires_at, slot) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) \
+                         ON CONFLICT (address) DO UPDATE SET price = EXCLUDED.price, is_active = EXCLUDED.is_active, \
+                         description = EXCLUDED.description, buyer = EXCLUDED.buyer, sold_at = EXCLUDED.sold_at, \
+                         cancelled_at = EXCLUDED.cancelled_at, expires_at = EXCLUDED.expires_at, slot = EXCLUDED.slot \
+                         WHERE listings.slot <= EXCLUDED.slot",
+                        &[
+                            &address,
+                            &(listing.id as i64),
+                            &key(&listing.owner),
+                            &listing.identity_id(),
+                            &format!("{:?}", listing.data_type()),
+                            &(!listing.is_private()).then_some(listing.price as i64),
+                            &listing.is_private(),
+                            &listing.is_active(),
+                            &listing.description(),
+                            &optional_key(listing.buyer()),
+                            &listing.created_at,
+                            &listing.sold_at(),
+                            &listing.cancelled_at(),
+                            &listing.expires_at(),
+                            &slot,
+                        ],
+                    )
+                    .await?;
+            }
+            IndexedAccount::Receipt(receipt) => {
+                self.client
+                    .execute(
+                        "INSERT INTO receipts (address, listing, listing_id, buyer, payer, seller, amount, \
+                         is_confidential, purpose, purchased_at, slot) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
+                         ON CONFLICT (address) DO UPDATE SET slot = EXCLUDED.slot \
+                         WHERE receipts.slot <= EXCLUDED.slot",
+                        &[
+                            &address,
+                            &key(&receipt.listing),
+                            &(receipt.listing_id as i64),
+                            &key(&receipt.buyer),
+                            &key(&receipt.payer),
+                            &key(&receipt.seller),
+                            &(!receipt.is_confidential).then_some(receipt.amount as i64),
+                            &receipt.is_confidential,
+                            &format!("{:?}", receipt.purpose),
+                            &receipt.purchased_at,
+                            &slot,
+                        ],
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop a closed account from whichever table holds it
+    pub async fn remove_account(&self, address: &Pubkey) -> Result<()> {
+        let address = key(address);
+        for table in ["identities", "oracles", "permissions", "listings", "receipts"] {
+            self.client
+                .execute(&format!("DELETE FROM {} WHERE address = $1", table), &[&address])
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Record a transaction's events; events of unknown types are skipped but keep their index
+    pub async fn insert_events(&self, signature: &str, slot: u64, events: &[RawEvent]) -> Result<()> {
+        for (event_index, event) in events.iter().enumerate() {
+            let Some(name) = event.name() else { continue };
+            self.client
+                .execute(
+                    "INSERT INTO events (signature, event_index, slot, program, name, data) \
+                     VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING",
+                    &[
+                        &signature,
+                        &(event_index as i32),
+                        &(slot as i64),
+                        &key(&event.program),
+                        &name,
+                        &event.data,
+                    ],
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn cursor(&self, program: &Pubkey) -> Result<Option<String>> {
+        let row = self
+            .client
+            .query_opt("SELECT last_signature FROM cursors WHERE program = $1", &[&key(program)])
+            .await?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    pub async fn set_cursor(&self, program: &Pubkey, signature: &str, slot: u64) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO cursors (program, last_signature, last_slot) VALUES ($1, $2, $3) \
+                 ON CONFLICT (program) DO UPDATE SET last_signature = EXCLUDED.last_signature, \
+                 last_slot = EXCLUDED.last_slot WHERE cursors.last_slot <= EXCLUDED.last_slot",
+                &[&key(program), &signature, &(slot as i64)],
+            )
+            .await?;
+        Ok(())
+    }
+}
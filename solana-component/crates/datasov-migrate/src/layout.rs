@@ -0,0 +1,179 @@
+//! Account types with earlier layouts, and how to find and migrate accounts still in them.
+
+use anchor_lang::{Discriminator, Owner, Space};
+use datasov_client::instructions;
+use datasov_client::{identity, marketplace};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+/// An account type that has changed layout, with its own `migrate_*` instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Kind {
+    Identity,
+    Permission,
+    ConsumerProfile,
+    OracleRegistry,
+    Marketplace,
+    Listing,
+    Receipt,
+    ComputeJob,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 8] = [
+        Kind::Identity,
+        Kind::Permission,
+        Kind::ConsumerProfile,
+        Kind::OracleRegistry,
+        Kind::Marketplace,
+        Kind::Listing,
+        Kind::Receipt,
+        Kind::ComputeJob,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Identity => "identity",
+            Kind::Permission => "permission",
+            Kind::ConsumerProfile => "consumer-profile",
+            Kind::OracleRegistry => "oracle-registry",
+            Kind::Marketplace => "marketplace",
+            Kind::Listing => "listing",
+            Kind::Receipt => "receipt",
+            Kind::ComputeJob => "compute-job",
+        }
+    }
+
+    fn program_id(self) -> Pubkey {
+        match self {
+            Kind::Identity | Kind::Permission | Kind::ConsumerProfile | Kind::OracleRegistry => {
+                identity::IdentityAccount::owner()
+            }
+            _ => marketplace::Marketplace::owner(),
+        }
+    }
+
+    fn discriminator(self) -> [u8; 8] {
+        match self {
+            Kind::Identity => identity::IdentityAccount::DISCRIMINATOR,
+            Kind::Permission => identity::AccessPermission::DISCRIMINATOR,
+            Kind::ConsumerProfile => identity::ConsumerProfile::DISCRIMINATOR,
+            Kind::OracleRegistry => identity::KYCOracleRegistry::DISCRIMINATOR,
+            Kind::Marketplace => marketplace::Marketplace::DISCRIMINATOR,
+            Kind::Listing => marketplace::DataListing::DISCRIMINATOR,
+            Kind::Receipt => marketplace::PurchaseReceipt::DISCRIMINATOR,
+            Kind::ComputeJob => marketplace::ComputeJob::DISCRIMINATOR,
+        }
+    }
+
+    /// Allocated sizes of the earlier layouts, oldest first; the index is the layout version
+    pub fn legacy_spaces(self) -> &'static [usize] {
+        match self {
+            Kind::Identity => identity::IdentityAccount::LEGACY_SPACES,
+            Kind::Permission => identity::AccessPermission::LEGACY_SPACES,
+            Kind::ConsumerProfile => identity::ConsumerProfile::LEGACY_SPACES,
+            Kind::OracleRegistry => identity::KYCOracleRegistry::LEGACY_SPACES,
+            Kind::Marketplace => marketplace::Marketplace::LEGACY_SPACES,
+            Kind::Listing => marketplace::DataListing::LEGACY_SPACES,
+            Kind::Receipt => marketplace::PurchaseReceipt::LEGACY_SPACES,
+            Kind::ComputeJob => marketplace::ComputeJob::LEGACY_SPACES,
+        }
+    }
+
+    /// Allocated size of the current layout
+    pub fn current_space(self) -> usize {
+        8 + match self {
+            Kind::Identity => identity::IdentityAccount::INIT_SPACE,
+            Kind::Permission => identity::AccessPermission::INIT_SPACE,
+            Kind::ConsumerProfile => identity::ConsumerProfile::INIT_SPACE,
+            Kind::OracleRegistry => identity::KYCOracleRegistry::INIT_SPACE,
+            Kind::Marketplace => marketplace::Marketplace::INIT_SPACE,
+            Kind::Listing => marketplace::DataListing::INIT_SPACE,
+            Kind::Receipt => marketplace::PurchaseReceipt::INIT_SPACE,
+            Kind::ComputeJob => marketplace::ComputeJob::INIT_SPACE,
+        }
+    }
+
+    /// Instruction rewriting `account` in the current layout, with `payer` covering the added rent
+    pub fn migrate_instruction(self, account: Pubkey, payer: Pubkey) -> Instruction {
+        let identity_accounts = identity::accounts::MigrateAccount {
+            account,
+            payer,
+            system_program: system_program::ID,
+        };
+        let marketplace_accounts = marketplace::accounts::MigrateAccount {
+            account,
+            payer,
+            system_program: system_program::ID,
+        };
+        match self {
+            Kind::Identity => {
+                instructions::identity(identity_accounts, identity::instruction::MigrateIdentityAccount {})
+            }
+            Kind::Permission => {
+                instructions::identity(identity_accounts, identity::instruction::MigrateAccessPermission {})
+            }
+            Kind::ConsumerProfile => {
+                instructions::identity(identity_accounts, identity::instruction::MigrateConsumerProfile {})
+            }
+            Kind::OracleRegistry => {
+                instructions::identity(identity_accounts, identity::instruction::MigrateOracleRegistry {})
+            }
+            Kind::Marketplace => {
+                instructions::marketplace(marketplace_accounts, marketplace::instruction::MigrateMarketplace {})
+            }
+            Kind::Listing => {
+                instructions::marketplace(marketplace_accounts, marketplace::instruction::MigrateListing {})
+            }
+            Kind::Receipt => instructions::marketplace(
+                marketplace_accounts,
+                marketplace::instruction::MigratePurchaseReceipt {},
+            ),
+            Kind::ComputeJob => {
+                instructions::marketplace(marketplace_accounts, marketplace::instruction::MigrateComputeJob {})
+            }
+        }
+    }
+}
+
+/// An account found in an earlier layout
+#[derive(Debug, Clone, Copy)]
+pub struct LegacyAccount {
+    pub address: Pubkey,
+    pub kind: Kind,
+    pub version: usize,
+}
+
+/// Find every `kind` account still in an earlier layout. One query per legacy size, matched on
+/// discriminator and data length, fetching no account data.
+pub async fn scan(client: &RpcClient, kind: Kind) -> anyhow::Result<Vec<LegacyAccount>> {
+    let mut found = Vec::new();
+    for (version, space) in kind.legacy_spaces().iter().enumerate() {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(*space as u64),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &kind.discriminator())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig { offset: 0, length: 0 }),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = client
+            .get_program_accounts_with_config(&kind.program_id(), config)
+            .await?;
+        found.extend(
+            accounts
+                .into_iter()
+                .map(|(address, _)| LegacyAccount { address, kind, version }),
+        );
+    }
+    Ok(found)
+}
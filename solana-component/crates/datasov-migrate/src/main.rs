@@ -0,0 +1,228 @@
+//! `datasov-migrate`: bring accounts created under earlier layouts up to the current one.
+//!
+//! Scans both programs for accounts whose allocated size matches an earlier layout of their type,
+//! reports how many there are per type and layout version, and sends the matching `migrate_*`
+//! instruction for each, `--batch-size` to a transaction. The payer covers the rent for the added
+//! space. `--dry-run` simulates every batch without sending anything. With `--state`, the outcome
+//! of every account is recorded as the run goes, so a rerun after an interruption skips what is
+//! done and, unless `--retry-failed`, what failed.
+
+mod layout;
+mod state;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use datasov_client::rpc::{DataSovRpc, RpcClient};
+use datasov_client::TransactionBuilder;
+use log::{info, warn};
+use solana_cli_config::{Config, CONFIG_FILE};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signer::Signer;
+
+use layout::{Kind, LegacyAccount};
+use state::State;
+
+#[derive(Parser)]
+#[clap(
+    name = "datasov-migrate",
+    version,
+    about = "Migrate DataSov accounts to the current layouts"
+)]
+struct Args {
+    /// JSON-RPC URL [default: from the Solana CLI config]
+    #[clap(long, short = 'u')]
+    url: Option<String>,
+
+    /// Keypair file paying fees and added rent [default: from the Solana CLI config]
+    #[clap(long, short = 'k')]
+    keypair: Option<String>,
+
+    /// Solana CLI config file
+    #[clap(long, short = 'C')]
+    config: Option<String>,
+
+    /// Only migrate these account types [default: all]
+    #[clap(long, value_enum, multiple_values = true)]
+    only: Vec<Kind>,
+
+    /// Migrations per transaction
+    #[clap(long, default_value_t = 8)]
+    batch_size: usize,
+
+    /// Simulate every batch instead of sending it
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Progress file to record outcomes in and resume from
+    #[clap(long)]
+    state: Option<PathBuf>,
+
+    /// Retry accounts the progress file records as failed
+    #[clap(long)]
+    retry_failed: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+    if args.batch_size == 0 {
+        bail!("--batch-size must be at least 1");
+    }
+
+    let config = match args.config.as_deref().or(CONFIG_FILE.as_deref()) {
+        Some(path) => Config::load(path).unwrap_or_default(),
+        None => Config::default(),
+    };
+    let url = args.url.clone().unwrap_or(config.json_rpc_url);
+    let keypair_path = args.keypair.clone().unwrap_or(config.keypair_path);
+    let payer = read_keypair_file(&keypair_path).map_err(|err| anyhow!("failed to read {}: {}", keypair_path, err))?;
+    let rpc = DataSovRpc::from_client(RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()));
+    let mut state = State::load(args.state.as_deref())?;
+
+    let kinds = match args.only.is_empty() {
+        true => Kind::ALL.to_vec(),
+        false => args.only.clone(),
+    };
+    let mut found = Vec::new();
+    for kind in kinds {
+        found.extend(layout::scan(rpc.client(), kind).await?);
+    }
+    report(&found);
+
+    let pending: Vec<LegacyAccount> = found
+        .into_iter()
+        .filter(|account| !state.is_migrated(&account.address))
+        .filter(|account| args.retry_failed || !state.has_failed(&account.address))
+        .collect();
+    if pending.is_empty() {
+        info!("Nothing to migrate");
+        return Ok(());
+    }
+
+    let migrator = Migrator {
+        rpc: &rpc,
+        payer: &payer,
+        dry_run: args.dry_run,
+    };
+    for batch in pending.chunks(args.batch_size) {
+        migrator.run(batch, &mut state).await;
+        // A dry run reads the progress file but never writes it
+        if !args.dry_run {
+            state.save()?;
+        }
+    }
+
+    let failed = pending
+        .iter()
+        .filter(|account| state.has_failed(&account.address))
+        .count();
+    match (args.dry_run, failed) {
+        (true, 0) => info!("Dry run: all {} migrations simulate cleanly", pending.len()),
+        (true, _) => bail!("dry run: {} of {} migrations fail", failed, pending.len()),
+        (false, 0) => info!("Migrated {} accounts", pending.len()),
+        (false, _) => bail!("{} of {} migrations failed", failed, pending.len()),
+    }
+    Ok(())
+}
+
+/// Log how many legacy accounts there are of each type and layout version
+fn report(found: &[LegacyAccount]) {
+    let mut counts = BTreeMap::new();
+    for account in found {
+        *counts.entry((account.kind, account.version)).or_insert(0usize) += 1;
+    }
+    for ((kind, version), count) in &counts {
+        info!(
+            "{}: {} at layout version {} ({} -> {} bytes)",
+            kind.name(),
+            count,
+            version,
+            kind.legacy_spaces()[*version],
+            kind.current_space(),
+        );
+    }
+    info!("{} accounts in earlier layouts", found.len());
+}
+
+struct Migrator<'a> {
+    rpc: &'a DataSovRpc,
+    payer: &'a Keypair,
+    dry_run: bool,
+}
+
+impl Migrator<'_> {
+    /// Migrate `batch` in one transaction; if that fails, retry each account alone so one bad
+    /// account does not hold back the rest
+    async fn run(&self, batch: &[LegacyAccount], state: &mut State) {
+        let err = match self.attempt(batch, state).await {
+            Ok(()) => return,
+            Err(err) => err,
+        };
+        if let [account] = batch {
+            self.record_failure(account, err, state);
+            return;
+        }
+        warn!("Batch of {} failed ({:#}); retrying one at a time", batch.len(), err);
+        for account in batch {
+            if let Err(err) = self.attempt(std::slice::from_ref(account), state).await {
+                self.record_failure(account, err, state);
+            }
+        }
+    }
+
+    async fn attempt(&self, batch: &[LegacyAccount], state: &mut State) -> Result<()> {
+        let signature = self.submit(batch).await?;
+        for account in batch {
+            state.record_migrated(&account.address, &signature);
+        }
+        info!("{} {} accounts: {}", self.verb(), batch.len(), signature);
+        Ok(())
+    }
+
+    fn record_failure(&self, account: &LegacyAccount, err: anyhow::Error, state: &mut State) {
+        warn!("{} {} failed: {:#}", account.kind.name(), account.address, err);
+        state.record_failed(&account.address, &format!("{:#}", err));
+    }
+
+    /// Send or simulate one transaction; the signature, or `simulated` on a dry run
+    async fn submit(&self, batch: &[LegacyAccount]) -> Result<String> {
+        let payer = self.payer.pubkey();
+        let builder = batch
+            .iter()
+            .map(|account| account.kind.migrate_instruction(account.address, payer))
+            .fold(TransactionBuilder::new(), TransactionBuilder::instruction);
+        if !self.dry_run {
+            let signature = self.rpc.send(&builder, &payer, &[self.payer]).await?;
+            return Ok(signature.to_string());
+        }
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let simulation = self
+            .rpc
+            .client()
+            .simulate_transaction_with_config(&builder.build_unsigned(&payer), config)
+            .await?
+            .value;
+        match simulation.err {
+            None => Ok("simulated".to_string()),
+            Some(err) => Err(anyhow!("{} (logs: {:?})", err, simulation.logs.unwrap_or_default())),
+        }
+    }
+
+    fn verb(&self) -> &'static str {
+        match self.dry_run {
+            true => "Simulated",
+            false => "Migrated",
+        }
+    }
+}
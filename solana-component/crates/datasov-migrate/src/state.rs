@@ -0,0 +1,66 @@
+//! Progress file, so an interrupted or partly failed run can pick up where it stopped.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+/// Outcome of every account a run has attempted, keyed by address
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    /// Signature of the transaction that migrated the account
+    #[serde(default)]
+    pub migrated: BTreeMap<String, String>,
+    /// Error from the last attempt
+    #[serde(default)]
+    pub failed: BTreeMap<String, String>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl State {
+    /// Load the progress file at `path`, or start empty if there is none yet; `None` keeps progress in memory only
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let mut state = match path {
+            Some(path) if path.exists() => {
+                let text =
+                    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+                serde_json::from_str(&text).with_context(|| format!("invalid state file {}", path.display()))?
+            }
+            _ => State::default(),
+        };
+        state.path = path.map(Path::to_path_buf);
+        Ok(state)
+    }
+
+    pub fn is_migrated(&self, address: &Pubkey) -> bool {
+        self.migrated.contains_key(&address.to_string())
+    }
+
+    pub fn has_failed(&self, address: &Pubkey) -> bool {
+        self.failed.contains_key(&address.to_string())
+    }
+
+    pub fn record_migrated(&mut self, address: &Pubkey, signature: &str) {
+        self.failed.remove(&address.to_string());
+        self.migrated.insert(address.to_string(), signature.to_string());
+    }
+
+    pub fn record_failed(&mut self, address: &Pubkey, error: &str) {
+        self.failed.insert(address.to_string(), error.to_string());
+    }
+
+    /// Write the progress file, if there is one. Written whole to a temporary file and renamed so
+    /// an interruption never leaves it truncated.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let temporary = path.with_extension("tmp");
+        std::fs::write(&temporary, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("failed to write {}", temporary.display()))?;
+        std::fs::rename(&temporary, path).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
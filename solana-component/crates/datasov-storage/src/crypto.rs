@@ -0,0 +1,17 @@
+//! Payload encryption, using the SDK's [`datasov_client::encryption`] so sealed documents are
+//! interchangeable with every other DataSov client.
+
+use datasov_client::encryption;
+pub use datasov_client::encryption::ContentKey;
+
+use crate::{Result, StorageError};
+
+/// Encrypt under a fresh random nonce
+pub fn seal(key: &ContentKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    encryption::seal(key, plaintext).map_err(|_| StorageError::Encryption)
+}
+
+/// Decrypt a payload produced by [`seal`]; fails if it was truncated or modified
+pub fn open(key: &ContentKey, sealed: &[u8]) -> Result<Vec<u8>> {
+    encryption::open(key, sealed).map_err(|_| StorageError::Decryption)
+}
@@ -0,0 +1,146 @@
+//! ANS-104 data items signed with an ed25519 (Solana) key, signature type 2.
+//!
+//! Layout: `signature_type (u16 LE) || signature (64) || owner (32) || target flag (0) ||
+//! anchor flag (1) || anchor (32) || tag count (u64 LE) || tag bytes length (u64 LE) ||
+//! Avro-encoded tags || data`. The signature covers the SHA-384 deep hash of the fields.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha384};
+use solana_sdk::signer::Signer;
+
+use crate::Result;
+
+const SIGNATURE_TYPE_ED25519: u16 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub value: String,
+}
+
+impl Tag {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+pub struct DataItem {
+    signature: [u8; 64],
+    owner: [u8; 32],
+    anchor: [u8; 32],
+    tags: Vec<Tag>,
+    data: Vec<u8>,
+}
+
+impl DataItem {
+    /// Sign `data` and `tags` as `signer`, under a random anchor so identical uploads get distinct ids
+    pub fn sign(signer: &dyn Signer, tags: Vec<Tag>, data: Vec<u8>) -> Result<Self> {
+        let owner = signer.try_pubkey()?.to_bytes();
+        let anchor: [u8; 32] = rand::random();
+        let message = signing_message(&owner, &anchor, &encode_tags(&tags), &data);
+        let signature = signer.try_sign_message(&message)?;
+        Ok(Self {
+            signature: signature.into(),
+            owner,
+            anchor,
+            tags,
+            data,
+        })
+    }
+
+    /// Arweave transaction id: base64url of the SHA-256 of the signature
+    pub fn id(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.signature))
+    }
+
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let tag_bytes = encode_tags(&self.tags);
+        let mut bytes = Vec::with_capacity(2 + 64 + 32 + 1 + 33 + 16 + tag_bytes.len() + self.data.len());
+        bytes.extend_from_slice(&SIGNATURE_TYPE_ED25519.to_le_bytes());
+        bytes.extend_from_slice(&self.signature);
+        bytes.extend_from_slice(&self.owner);
+        bytes.push(0);
+        bytes.push(1);
+        bytes.extend_from_slice(&self.anchor);
+        bytes.extend_from_slice(&(self.tags.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(tag_bytes.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&tag_bytes);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+}
+
+fn signing_message(owner: &[u8], anchor: &[u8], tag_bytes: &[u8], data: &[u8]) -> Vec<u8> {
+    let signature_type = SIGNATURE_TYPE_ED25519.to_string();
+    deep_hash_list(&[
+        b"dataitem",
+        b"1",
+        signature_type.as_bytes(),
+        owner,
+        b"",
+        anchor,
+        tag_bytes,
+        data,
+    ])
+    .to_vec()
+}
+
+fn deep_hash_blob(blob: &[u8]) -> [u8; 48] {
+    let tag = Sha384::digest(format!("blob{}", blob.len()));
+    Sha384::new()
+        .chain_update(tag)
+        .chain_update(Sha384::digest(blob))
+        .finalize()
+        .into()
+}
+
+fn deep_hash_list(items: &[&[u8]]) -> [u8; 48] {
+    let mut acc: [u8; 48] = Sha384::digest(format!("list{}", items.len())).into();
+    for item in items {
+        acc = Sha384::new()
+            .chain_update(acc)
+            .chain_update(deep_hash_blob(item))
+            .finalize()
+            .into();
+    }
+    acc
+}
+
+/// Avro `array<record { name: bytes, value: bytes }>`, or nothing when there are no tags
+fn encode_tags(tags: &[Tag]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if tags.is_empty() {
+        return bytes;
+    }
+    write_long(&mut bytes, tags.len() as i64);
+    for tag in tags {
+        write_long(&mut bytes, tag.name.len() as i64);
+        bytes.extend_from_slice(tag.name.as_bytes());
+        write_long(&mut bytes, tag.value.len() as i64);
+        bytes.extend_from_slice(tag.value.as_bytes());
+    }
+    write_long(&mut bytes, 0);
+    bytes
+}
+
+/// Avro long: zigzag, then little-endian base-128 varint
+fn write_long(bytes: &mut Vec<u8>, value: i64) {
+    let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+    while zigzag >= 0x80 {
+        bytes.push((zigzag as u8 & 0x7f) | 0x80);
+        zigzag >>= 7;
+    }
+    bytes.push(zigzag as u8);
+}
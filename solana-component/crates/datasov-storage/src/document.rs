@@ -0,0 +1,78 @@
+//! Typed DataSov documents, matching the JSON written by the integration layer's `ArweaveService`.
+//! Enum-like fields stay strings so documents from newer writers still parse.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DocumentType {
+    Identity,
+    KycVerification,
+    AccessPermission,
+}
+
+impl DocumentType {
+    /// Value of the `Document-Type` tag
+    pub fn tag_value(self) -> &'static str {
+        match self {
+            DocumentType::Identity => "IDENTITY",
+            DocumentType::KycVerification => "KYC_VERIFICATION",
+            DocumentType::AccessPermission => "ACCESS_PERMISSION",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityDocument {
+    pub document_type: DocumentType,
+    pub version: String,
+    pub timestamp: i64,
+    pub identity_id: String,
+    pub owner: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_provider: Option<String>,
+    pub identity_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_personal_info: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kyc_verification: Option<KycVerification>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_permission: Option<AccessPermissionTerms>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KycVerification {
+    pub verification_level: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_method: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_proof: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_signature: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessPermissionTerms {
+    pub consumer: String,
+    pub permission_type: String,
+    pub data_types: Vec<String>,
+    pub granted_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
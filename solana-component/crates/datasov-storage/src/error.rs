@@ -0,0 +1,22 @@
+use solana_sdk::signer::SignerError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("bundler rejected the upload ({status}): {body}")]
+    Rejected { status: u16, body: String },
+    #[error("unexpected response from {endpoint}")]
+    UnexpectedResponse { endpoint: String },
+    #[error("signing failed: {0}")]
+    Signing(#[from] SignerError),
+    #[error("encryption failed")]
+    Encryption,
+    #[error("decryption failed: wrong key or tampered ciphertext")]
+    Decryption,
+    #[error("transaction {id} was not confirmed in time")]
+    ConfirmationTimeout { id: String },
+}
+
+pub type Result<T> = std::result::Result<T, StorageError>;
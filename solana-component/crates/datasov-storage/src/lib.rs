@@ -0,0 +1,35 @@
+//! Off-chain half of the DataSov protocol: encrypt a document, upload it to Arweave through a
+//! bundler (Turbo or Bundlr) as an ANS-104 data item signed with the caller's Solana key, and wait
+//! until it is mined. The returned transaction id is what `register_identity`,
+//! `create_data_listing` and the permission instructions take as their `arweave_*_tx_id`.
+//!
+//! - [`crypto`] seals payloads with AES-256-GCM under a per-document [`ContentKey`]; hand the key
+//!   to buyers with `datasov_client::encryption::wrap_content_key`.
+//! - [`data_item`] builds and signs ANS-104 data items.
+//! - [`upload`] submits data items and polls a gateway for confirmation.
+//! - [`document`] defines the JSON documents DataSov stores.
+//!
+//! Hash commitments recorded on chain (`description_hash`, `package_hash`, ...) are the SHA-256 of
+//! the stored, encrypted bytes; see [`content_hash`].
+
+pub mod crypto;
+pub mod data_item;
+pub mod document;
+pub mod error;
+pub mod upload;
+
+use sha2::{Digest, Sha256};
+
+pub use crypto::ContentKey;
+pub use data_item::{DataItem, Tag};
+pub use document::{DocumentType, IdentityDocument};
+pub use error::{Result, StorageError};
+pub use upload::{Upload, Uploader};
+
+/// `App-Name` tag on every DataSov document, matching the integration layer
+pub const APP_NAME: &str = "DataSov";
+
+/// SHA-256 of the bytes stored on Arweave, as committed on chain
+pub fn content_hash(stored: &[u8]) -> [u8; 32] {
+    Sha256::digest(stored).into()
+}
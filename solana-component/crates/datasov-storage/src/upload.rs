@@ -0,0 +1,166 @@
+//! Bundler submission and confirmation polling.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use solana_sdk::signer::Signer;
+
+use crate::crypto::{self, ContentKey};
+use crate::data_item::{DataItem, Tag};
+use crate::{content_hash, Result, StorageError, APP_NAME};
+
+/// ArDrive Turbo upload endpoint for Solana-signed data items
+pub const TURBO_UPLOAD_URL: &str = "https://upload.ardrive.io/v1/tx/solana";
+/// Bundlr (Irys) node upload endpoint for Solana-signed data items
+pub const BUNDLR_UPLOAD_URL: &str = "https://node1.bundlr.network/tx/solana";
+pub const ARWEAVE_GATEWAY_URL: &str = "https://arweave.net";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// An accepted upload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upload {
+    /// Arweave transaction id, for the program's `arweave_*_tx_id` arguments
+    pub id: String,
+    /// [`content_hash`] of the stored bytes, for the program's hash commitments
+    pub content_hash: [u8; 32],
+}
+
+pub struct Uploader {
+    http: reqwest::Client,
+    upload_url: String,
+    gateway_url: String,
+    confirmation_timeout: Duration,
+}
+
+impl Uploader {
+    /// Upload through the bundler at `upload_url`, e.g. [`TURBO_UPLOAD_URL`] or [`BUNDLR_UPLOAD_URL`]
+    pub fn new(upload_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            upload_url: upload_url.into(),
+            gateway_url: ARWEAVE_GATEWAY_URL.to_string(),
+            confirmation_timeout: DEFAULT_CONFIRMATION_TIMEOUT,
+        }
+    }
+
+    pub fn turbo() -> Self {
+        Self::new(TURBO_UPLOAD_URL)
+    }
+
+    pub fn bundlr() -> Self {
+        Self::new(BUNDLR_UPLOAD_URL)
+    }
+
+    /// Gateway polled for confirmation
+    pub fn with_gateway(mut self, gateway_url: impl Into<String>) -> Self {
+        self.gateway_url = gateway_url.into();
+        self
+    }
+
+    pub fn with_confirmation_timeout(mut self, timeout: Duration) -> Self {
+        self.confirmation_timeout = timeout;
+        self
+    }
+
+    /// Encrypt `payload` under `key`, upload it signed by `signer`, and wait until it is mined.
+    /// `tags` are added to the standard DataSov tags, e.g. `Document-Type` and `Identity-Id`.
+    pub async fn upload(
+        &self,
+        signer: &dyn Signer,
+        payload: &[u8],
+        key: &ContentKey,
+        tags: Vec<Tag>,
+    ) -> Result<Upload> {
+        let upload = self.submit(signer, payload, key, tags).await?;
+        self.wait_for_confirmation(&upload.id).await?;
+        Ok(upload)
+    }
+
+    /// Encrypt and upload without waiting for confirmation; the bundler guarantees eventual
+    /// inclusion once it has accepted the item
+    pub async fn submit(
+        &self,
+        signer: &dyn Signer,
+        payload: &[u8],
+        key: &ContentKey,
+        tags: Vec<Tag>,
+    ) -> Result<Upload> {
+        let sealed = crypto::seal(key, payload)?;
+        let mut all_tags = standard_tags();
+        all_tags.extend(tags);
+        let item = DataItem::sign(signer, all_tags, sealed)?;
+        self.post(&item).await?;
+        Ok(Upload {
+            id: item.id(),
+            content_hash: content_hash(item.data()),
+        })
+    }
+
+    async fn post(&self, item: &DataItem) -> Result<()> {
+        let response = self
+            .http
+            .post(&self.upload_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(item.to_bytes())
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(StorageError::Rejected {
+                status: status.as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Poll the gateway until `id` is in a block, or the confirmation timeout passes
+    pub async fn wait_for_confirmation(&self, id: &str) -> Result<()> {
+        let deadline = Instant::now() + self.confirmation_timeout;
+        loop {
+            if self.block_height(id).await?.is_some() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(StorageError::ConfirmationTimeout { id: id.to_string() });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Height of the block containing `id`, or `None` while it is pending
+    pub async fn block_height(&self, id: &str) -> Result<Option<u64>> {
+        let endpoint = format!("{}/graphql", self.gateway_url);
+        let response: Value = self
+            .http
+            .post(&endpoint)
+            .json(&json!({
+                "query": "query($id: ID!) { transaction(id: $id) { block { height } } }",
+                "variables": { "id": id },
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let transaction = response
+            .pointer("/data/transaction")
+            .ok_or(StorageError::UnexpectedResponse { endpoint })?;
+        Ok(transaction.pointer("/block/height").and_then(Value::as_u64))
+    }
+}
+
+fn standard_tags() -> Vec<Tag> {
+    let unix_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    vec![
+        Tag::new("App-Name", APP_NAME),
+        Tag::new("App-Version", env!("CARGO_PKG_VERSION")),
+        Tag::new("Content-Type", "application/octet-stream"),
+        Tag::new("Encryption", "AES-256-GCM"),
+        Tag::new("Unix-Time", unix_time.to_string()),
+    ]
+}
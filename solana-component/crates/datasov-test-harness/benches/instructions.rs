@@ -0,0 +1,349 @@
+//! Compute units, heap and transaction size of the protocol's instructions, and the sizes of the
+//! accounts they create, with the change since the last recorded run.
+//!
+//! Each instruction runs once against a bootstrapped [`Protocol`], in an order where each builds
+//! on the state the one before left. Compute units are only metered for the built programs, so
+//! run with `SBF_OUT_DIR=target/deploy`; heap is only metered under the native processors, so
+//! run once without it too:
+//!
+//! ```text
+//! SBF_OUT_DIR=target/deploy cargo bench -p datasov-test-harness
+//! cargo bench -p datasov-test-harness
+//! ```
+//!
+//! Every run appends its figures, keyed by the current commit, to `DATASOV_BENCH_HISTORY`
+//! (default `target/bench-history.tsv`); keep that file between CI runs to track trends.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+use datasov_client::identity::{AccessPermission, DataType, PermissionType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, AccessCheck, GrantAccessRequest, PurchaseRequest};
+use datasov_client::marketplace::{self, DataType as ListingDataType};
+use datasov_client::{compute, identity, pda, storage};
+use datasov_constants::sizes;
+use datasov_test_harness::heap::{self, HeapMeter};
+use datasov_test_harness::{Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+
+#[global_allocator]
+static HEAP: HeapMeter = HeapMeter;
+
+/// One instruction's figures
+struct Sample {
+    name: &'static str,
+    units: u64,
+    /// Units `datasov_client::compute` requests for the instruction
+    budget: u64,
+    /// Zero when the programs ran from `SBF_OUT_DIR`
+    heap: usize,
+    transaction_bytes: usize,
+}
+
+struct Bench {
+    protocol: Protocol,
+    samples: Vec<Sample>,
+}
+
+impl Bench {
+    /// Measure `instruction` alone in a transaction, then execute it for the instructions after
+    async fn run(&mut self, name: &'static str, instruction: Instruction, signers: &[&Keypair]) {
+        let budget = compute::instruction_units(&instruction) as u64;
+        let instructions = [instruction];
+        let harness = &mut self.protocol.harness;
+        let transaction_bytes = harness.transaction_size(&instructions, signers).await;
+        heap::take_peak();
+        let units = harness.units_consumed(&instructions, signers).await;
+        let heap = heap::take_peak();
+        harness.execute(&instructions, signers).await;
+        self.samples.push(Sample {
+            name,
+            units,
+            budget,
+            heap,
+            transaction_bytes,
+        });
+    }
+
+    async fn participant(&mut self, prefix: &str) -> Participant {
+        let identity_id = self.protocol.unique_identity_id(prefix);
+        self.protocol
+            .verified_participant(&identity_id, VerificationLevel::Enhanced)
+            .await
+    }
+}
+
+async fn run_instructions(bench: &mut Bench) {
+    let wallet = bench.protocol.harness.wallet().await;
+    let identity_id = bench.protocol.unique_identity_id("bench");
+    let instruction =
+        instructions::register_identity(&wallet.pubkey(), &identity_id, storage::placeholder("bench-document"));
+    bench.run("register_identity", instruction, &[&wallet]).await;
+    let owner = Participant { wallet, identity_id };
+
+    let oracle = bench.protocol.oracles[0].insecure_clone();
+    let instruction = instructions::identity(
+        identity::accounts::VerifyIdentity {
+            identity: owner.identity(),
+            oracle: pda::identity::oracle(&oracle.pubkey()),
+            oracle_registry: pda::identity::oracle_registry(),
+            oracle_authority: oracle.pubkey(),
+        },
+        identity::instruction::VerifyIdentity {
+            verification_level: VerificationLevel::Enhanced,
+            kyc_pointer: storage::placeholder("bench-kyc"),
+        },
+    );
+    bench.run("verify_identity", instruction, &[&oracle]).await;
+
+    let instruction = instructions::identity(
+        identity::accounts::RegisterConsumer {
+            consumer_profile: pda::identity::consumer_profile(&owner.pubkey()),
+            consumer: owner.pubkey(),
+            oracle_registry: pda::identity::oracle_registry(),
+            system_program: system_program::ID,
+        },
+        identity::instruction::RegisterConsumer {
+            organization_name_hash: [0u8; 32],
+            contact_tx_id: "bench-contact".to_string(),
+            kyb_attestation_tx_id: String::new(),
+        },
+    );
+    bench.run("register_consumer", instruction, &[&owner.wallet]).await;
+
+    let instruction = instructions::identity(
+        identity::accounts::UpdateIdentity {
+            identity: owner.identity(),
+            owner: owner.pubkey(),
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        identity::instruction::UpdateIdentity {
+            new_storage_pointer: storage::placeholder("bench-update"),
+        },
+    );
+    bench.run("update_identity", instruction, &[&owner.wallet]).await;
+
+    let consumer = bench.participant("consumer").await;
+    let instruction = instructions::grant_access(GrantAccessRequest {
+        owner: owner.pubkey(),
+        identity_id: owner.identity_id.clone(),
+        consumer: consumer.pubkey(),
+        permission_type: PermissionType::ReadOnly,
+        data_types: vec![DataType::LocationHistory],
+        expires_at: None,
+        permission_pointer: storage::placeholder("bench-grant"),
+        consumer_staked: false,
+        guardian: None,
+        audit_entry_count: bench.protocol.harness.audit_entry_count(&owner.identity()).await,
+    });
+    bench.run("grant_access", instruction, &[&owner.wallet]).await;
+
+    let instruction = instructions::validate_access(
+        &consumer.pubkey(),
+        &owner.identity_id,
+        DataType::LocationHistory,
+        0,
+        false,
+        bench.protocol.harness.audit_entry_count(&owner.identity()).await,
+    );
+    bench.run("validate_access", instruction, &[&consumer.wallet]).await;
+
+    let checks: Vec<AccessCheck> = (0..AccessPermission::MAX_BATCH_CHECKS)
+        .map(|_| AccessCheck {
+            identity_id: owner.identity_id.clone(),
+            consumer: consumer.pubkey(),
+            data_type: DataType::LocationHistory,
+        })
+        .collect();
+    bench
+        .run(
+            "validate_access_batch",
+            instructions::validate_access_batch(&checks, &[]),
+            &[],
+        )
+        .await;
+
+    let price = 1_000_000;
+    let listing_id = bench.protocol.unique_listing_id();
+    let instruction = instructions::marketplace(
+        marketplace::accounts::CreateDataListing {
+            listing: pda::marketplace::listing(listing_id),
+            marketplace: pda::marketplace::marketplace(),
+            seller_identity: owner.identity(),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            data_trust: None,
+            trust_membership: None,
+            owner: owner.pubkey(),
+            guardian: None,
+            identity_program: identity::ID,
+            system_program: system_program::ID,
+            payout_wallet: None,
+        },
+        marketplace::instruction::CreateDataListing {
+            listing_id,
+            price,
+            data_type: ListingDataType::LocationHistory,
+            description: "Bench listing".to_string(),
+            identity_id: owner.identity_id.clone(),
+        },
+    );
+    bench.run("create_data_listing", instruction, &[&owner.wallet]).await;
+
+    bench.protocol.fund_tokens(&consumer, price).await;
+    let mint = bench.protocol.mint;
+    bench.protocol.harness.token_account(&owner.pubkey(), &mint).await;
+    let marketplace_account: marketplace::Marketplace =
+        bench.protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let instruction = instructions::purchase_data(PurchaseRequest {
+        buyer: consumer.pubkey(),
+        buyer_identity_id: consumer.identity_id.clone(),
+        listing_id,
+        payout_wallet: owner.pubkey(),
+        seller_identity_id: owner.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: None,
+        max_staleness: None,
+        day: marketplace::PriceCandle::day_of(bench.protocol.harness.now().await),
+        audit_entry_count: bench.protocol.harness.audit_entry_count(&owner.identity()).await,
+    });
+    bench.run("purchase_data", instruction, &[&consumer.wallet]).await;
+
+    let instruction = instructions::revoke_access(
+        &owner.pubkey(),
+        &owner.identity_id,
+        &consumer.pubkey(),
+        storage::placeholder("bench-revoke"),
+        bench.protocol.harness.audit_entry_count(&owner.identity()).await,
+    );
+    bench.run("revoke_access", instruction, &[&owner.wallet]).await;
+}
+
+/// Allocated sizes of the accounts the benchmarked instructions create
+const ACCOUNT_SIZES: &[(&str, usize)] = &[
+    ("identity", sizes::identity::IDENTITY),
+    ("consumer_profile", sizes::identity::CONSUMER_PROFILE),
+    ("permission", sizes::identity::PERMISSION),
+    ("listing", sizes::marketplace::LISTING),
+    ("receipt", sizes::marketplace::RECEIPT),
+    ("audit_log", sizes::audit::AUDIT_LOG),
+    ("audit_page", sizes::audit::AUDIT_PAGE),
+];
+
+fn commit() -> String {
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn history_path() -> PathBuf {
+    std::env::var_os("DATASOV_BENCH_HISTORY")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/bench-history.tsv"))
+}
+
+/// Latest recorded value of each (metric, name) from a commit other than `commit`
+fn previous(history: &str, commit: &str) -> HashMap<(String, String), u64> {
+    let mut previous = HashMap::new();
+    for line in history.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if let [recorded, metric, name, value] = fields[..] {
+            if recorded != commit {
+                if let Ok(value) = value.parse() {
+                    previous.insert((metric.to_string(), name.to_string()), value);
+                }
+            }
+        }
+    }
+    previous
+}
+
+/// `value`, with its change from the previous run if there was one
+fn with_delta(previous: &HashMap<(String, String), u64>, metric: &str, name: &str, value: u64) -> String {
+    match previous.get(&(metric.to_string(), name.to_string())) {
+        Some(&before) if before != value => format!("{} ({:+})", value, value as i64 - before as i64),
+        _ => value.to_string(),
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let mut bench = Bench {
+        protocol: Protocol::start().await,
+        samples: Vec::new(),
+    };
+    run_instructions(&mut bench).await;
+
+    let commit = commit();
+    let path = history_path();
+    let previous = previous(&fs::read_to_string(&path).unwrap_or_default(), &commit);
+    let mut rows = Vec::new();
+
+    println!(
+        "{:<24} {:>16} {:>9} {:>14} {:>12}",
+        "instruction", "units", "budget", "heap", "tx bytes"
+    );
+    for sample in &bench.samples {
+        println!(
+            "{:<24} {:>16} {:>9} {:>14} {:>12}",
+            sample.name,
+            with_delta(&previous, "units", sample.name, sample.units),
+            sample.budget,
+            match sample.heap {
+                0 => "-".to_string(),
+                heap => with_delta(&previous, "heap", sample.name, heap as u64),
+            },
+            with_delta(&previous, "tx_bytes", sample.name, sample.transaction_bytes as u64),
+        );
+        if sample.units > sample.budget {
+            println!("  over budget by {} units", sample.units - sample.budget);
+        }
+        if sample.transaction_bytes > PACKET_DATA_SIZE {
+            println!("  over the {}-byte packet limit", PACKET_DATA_SIZE);
+        }
+        rows.push(("units", sample.name, sample.units));
+        if sample.heap > 0 {
+            rows.push(("heap", sample.name, sample.heap as u64));
+        }
+        rows.push(("tx_bytes", sample.name, sample.transaction_bytes as u64));
+    }
+
+    println!("\n{:<24} {:>16}", "account", "bytes");
+    for (name, size) in ACCOUNT_SIZES {
+        println!(
+            "{:<24} {:>16}",
+            name,
+            with_delta(&previous, "account_bytes", name, *size as u64)
+        );
+        rows.push(("account_bytes", name, *size as u64));
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let mut history = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .expect("open bench history");
+    for (metric, name, value) in rows {
+        writeln!(history, "{}\t{}\t{}\t{}", commit, metric, name, value).expect("write bench history");
+    }
+    println!("\nrecorded under {} in {}", commit, path.display());
+}
@@ -0,0 +1,407 @@
+use datasov_client::identity::{self, DataType, JurorPool, PermissionType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, GrantAccessRequest, PurchaseRequest};
+use datasov_client::marketplace::{self, DataType as ListingDataType};
+use datasov_client::{pda, storage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+
+use crate::Harness;
+
+/// Parameters for [`Protocol::bootstrap`]
+#[derive(Debug, Clone)]
+pub struct ProtocolConfig {
+    /// Minimum KYC oracle (and juror) stake in lamports
+    pub minimum_stake: u64,
+    /// Lamports slashed per upheld dispute
+    pub slash_amount: u64,
+    pub fee_basis_points: u16,
+    /// KYC oracles to register; each also joins the juror pool
+    pub oracles: usize,
+    pub mint_decimals: u8,
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        Self {
+            minimum_stake: 1_000_000_000,
+            slash_amount: 100_000_000,
+            fee_basis_points: 250,
+            // Enough jurors to empanel a dispute
+            oracles: identity::Dispute::PANEL_SIZE,
+            mint_decimals: 6,
+        }
+    }
+}
+
+/// A wallet with an identity registered under `identity_id`
+pub struct Participant {
+    pub wallet: Keypair,
+    pub identity_id: String,
+}
+
+impl Participant {
+    pub fn pubkey(&self) -> Pubkey {
+        self.wallet.pubkey()
+    }
+
+    /// Address of the participant's identity account
+    pub fn identity(&self) -> Pubkey {
+        pda::identity::identity(&self.identity_id)
+    }
+}
+
+/// A harness with the registries, oracles, juror pool, marketplace and a settlement mint in place.
+/// Participants, grants, listings and purchases are added with the methods below or the
+/// [`crate::scenarios`] builders.
+pub struct Protocol {
+    pub harness: Harness,
+    pub config: ProtocolConfig,
+    /// Authority of the oracle registry, compliance matrix, blacklist, juror pool and marketplace
+    pub authority: Keypair,
+    pub oracles: Vec<Keypair>,
+    pub vrf_authority: Keypair,
+    /// Settlement mint; the harness payer is its mint authority
+    pub mint: Pubkey,
+    next_identity: u64,
+    next_listing_id: u64,
+}
+
+impl Protocol {
+    pub async fn start() -> Self {
+        Self::bootstrap(Harness::start().await, ProtocolConfig::default()).await
+    }
+
+    /// Initialize both programs' global state on `harness`
+    pub async fn bootstrap(mut harness: Harness, config: ProtocolConfig) -> Self {
+        let authority = harness.wallet().await;
+        let vrf_authority = Keypair::new();
+        let authority_key = authority.pubkey();
+
+        let setup = [
+            instructions::identity(
+                identity::accounts::InitializeOracleRegistry {
+                    oracle_registry: pda::identity::oracle_registry(),
+                    authority: authority_key,
+                    system_program: system_program::ID,
+                },
+                identity::instruction::InitializeOracleRegistry {
+                    minimum_stake: config.minimum_stake,
+                    slash_amount: config.slash_amount,
+                },
+            ),
+            instructions::identity(
+                identity::accounts::InitializeComplianceMatrix {
+                    compliance_matrix: pda::identity::compliance_matrix(),
+                    oracle_registry: pda::identity::oracle_registry(),
+                    authority: authority_key,
+                    system_program: system_program::ID,
+                },
+                identity::instruction::InitializeComplianceMatrix {},
+            ),
+            instructions::identity(
+                identity::accounts::InitializeBlacklist {
+                    blacklist: pda::identity::blacklist(),
+                    oracle_registry: pda::identity::oracle_registry(),
+                    authority: authority_key,
+                    system_program: system_program::ID,
+                },
+                identity::instruction::InitializeBlacklist {},
+            ),
+            instructions::identity(
+                identity::accounts::InitializeJurorPool {
+                    juror_pool: pda::identity::juror_pool(),
+                    oracle_registry: pda::identity::oracle_registry(),
+                    authority: authority_key,
+                    system_program: system_program::ID,
+                },
+                identity::instruction::InitializeJurorPool {
+                    vrf_authority: vrf_authority.pubkey(),
+                },
+            ),
+            instructions::marketplace(
+                marketplace::accounts::InitializeMarketplace {
+                    marketplace: pda::marketplace::marketplace(),
+                    authority: authority_key,
+                    system_program: system_program::ID,
+                },
+                marketplace::instruction::InitializeMarketplace {
+                    marketplace_fee_basis_points: config.fee_basis_points,
+                },
+            ),
+        ];
+        harness.execute(&setup, &[&authority]).await;
+
+        let mut oracles = Vec::with_capacity(config.oracles);
+        for index in 0..config.oracles {
+            let oracle = harness.wallet().await;
+            let register = instructions::identity(
+                identity::accounts::RegisterOracle {
+                    oracle: pda::identity::oracle(&oracle.pubkey()),
+                    oracle_registry: pda::identity::oracle_registry(),
+                    oracle_authority: oracle.pubkey(),
+                    system_program: system_program::ID,
+                },
+                identity::instruction::RegisterOracle {
+                    provider_name: format!("Test Oracle {}", index + 1),
+                    stake_amount: config.minimum_stake,
+                },
+            );
+            let join_jury = instructions::identity(
+                identity::accounts::JoinJury {
+                    juror: pda::identity::juror(&oracle.pubkey()),
+                    juror_pool: pda::identity::juror_pool(),
+                    oracle: pda::identity::oracle(&oracle.pubkey()),
+                    oracle_registry: pda::identity::oracle_registry(),
+                    authority: oracle.pubkey(),
+                    system_program: system_program::ID,
+                },
+                identity::instruction::JoinJury {
+                    stake_amount: config.minimum_stake,
+                },
+            );
+            harness.execute(&[register, join_jury], &[&oracle]).await;
+            oracles.push(oracle);
+        }
+
+        let mint = harness.create_mint(config.mint_decimals).await;
+        harness.token_account(&pda::marketplace::marketplace(), &mint).await;
+
+        Self {
+            harness,
+            config,
+            authority,
+            oracles,
+            vrf_authority,
+            mint,
+            next_identity: 0,
+            next_listing_id: 0,
+        }
+    }
+
+    /// Fresh identity id with the given prefix, unique within this protocol
+    pub fn unique_identity_id(&mut self, prefix: &str) -> String {
+        self.next_identity += 1;
+        format!("{}-{}", prefix, self.next_identity)
+    }
+
+    /// Fresh listing id, unique within this protocol
+    pub fn unique_listing_id(&mut self) -> u64 {
+        self.next_listing_id += 1;
+        self.next_listing_id
+    }
+
+    /// Fund a new wallet and register a pending identity for it
+    pub async fn register_identity(&mut self, identity_id: &str) -> Participant {
+        let wallet = self.harness.wallet().await;
+        let instruction = instructions::register_identity(
+            &wallet.pubkey(),
+            identity_id,
+            storage::placeholder(&format!("{}-document", identity_id)),
+        );
+        self.harness.execute(&[instruction], &[&wallet]).await;
+        Participant {
+            wallet,
+            identity_id: identity_id.to_string(),
+        }
+    }
+
+    /// Verify a pending identity, signed by the first oracle
+    pub async fn verify(&mut self, participant: &Participant, level: VerificationLevel) {
+        let oracle = &self.oracles[0];
+        let instruction = instructions::identity(
+            identity::accounts::VerifyIdentity {
+                identity: participant.identity(),
+                oracle: pda::identity::oracle(&oracle.pubkey()),
+                oracle_registry: pda::identity::oracle_registry(),
+                oracle_authority: oracle.pubkey(),
+            },
+            identity::instruction::VerifyIdentity {
+                verification_level: level,
+                kyc_pointer: storage::placeholder(&format!("{}-kyc", participant.identity_id)),
+            },
+        );
+        self.harness.execute(&[instruction], &[oracle]).await;
+    }
+
+    /// Register and verify an identity, and give it a consumer profile so it can also buy
+    pub async fn verified_participant(&mut self, identity_id: &str, level: VerificationLevel) -> Participant {
+        let participant = self.register_identity(identity_id).await;
+        self.verify(&participant, level).await;
+        self.register_consumer(&participant).await;
+        participant
+    }
+
+    /// Create the consumer profile grants and purchases require
+    pub async fn register_consumer(&mut self, participant: &Participant) {
+        let consumer = participant.pubkey();
+        let instruction = instructions::identity(
+            identity::accounts::RegisterConsumer {
+                consumer_profile: pda::identity::consumer_profile(&consumer),
+                consumer,
+                oracle_registry: pda::identity::oracle_registry(),
+                system_program: system_program::ID,
+            },
+            identity::instruction::RegisterConsumer {
+                organization_name_hash: [0u8; 32],
+                contact_tx_id: format!("{}-contact", participant.identity_id),
+                kyb_attestation_tx_id: String::new(),
+            },
+        );
+        self.harness.execute(&[instruction], &[&participant.wallet]).await;
+    }
+
+    /// Grant `consumer` access to `owner`'s identity; returns the permission address
+    pub async fn grant(
+        &mut self,
+        owner: &Participant,
+        consumer: &Participant,
+        data_types: Vec<DataType>,
+        expires_at: Option<i64>,
+    ) -> Pubkey {
+        let identity_account = owner.identity();
+        let request = GrantAccessRequest {
+            owner: owner.pubkey(),
+            identity_id: owner.identity_id.clone(),
+            consumer: consumer.pubkey(),
+            permission_type: PermissionType::ReadOnly,
+            data_types,
+            expires_at,
+            permission_pointer: storage::placeholder(&format!("{}-grant-{}", owner.identity_id, consumer.identity_id)),
+            consumer_staked: false,
+            guardian: None,
+            audit_entry_count: self.harness.audit_entry_count(&identity_account).await,
+        };
+        self.harness
+            .execute(&[instructions::grant_access(request)], &[&owner.wallet])
+            .await;
+        pda::identity::permission(&identity_account, &consumer.pubkey())
+    }
+
+    /// Revoke `consumer`'s access to `owner`'s identity
+    pub async fn revoke(&mut self, owner: &Participant, consumer: &Participant) {
+        let audit_entry_count = self.harness.audit_entry_count(&owner.identity()).await;
+        let instruction = instructions::revoke_access(
+            &owner.pubkey(),
+            &owner.identity_id,
+            &consumer.pubkey(),
+            storage::placeholder(&format!("{}-revoke-{}", owner.identity_id, consumer.identity_id)),
+            audit_entry_count,
+        );
+        self.harness.execute(&[instruction], &[&owner.wallet]).await;
+    }
+
+    /// List data from `seller`'s identity; returns the listing id
+    pub async fn create_listing(&mut self, seller: &Participant, price: u64, data_type: ListingDataType) -> u64 {
+        let listing_id = self.unique_listing_id();
+        self.create_listing_with_id(seller, listing_id, price, data_type).await;
+        listing_id
+    }
+
+    /// List data from `seller`'s identity under a chosen listing id
+    pub async fn create_listing_with_id(
+        &mut self,
+        seller: &Participant,
+        listing_id: u64,
+        price: u64,
+        data_type: ListingDataType,
+    ) {
+        let instruction = instructions::marketplace(
+            marketplace::accounts::CreateDataListing {
+                listing: pda::marketplace::listing(listing_id),
+                marketplace: pda::marketplace::marketplace(),
+                seller_identity: seller.identity(),
+                compliance_matrix: pda::identity::compliance_matrix(),
+                data_trust: None,
+                trust_membership: None,
+                owner: seller.pubkey(),
+                guardian: None,
+                identity_program: identity::ID,
+                system_program: system_program::ID,
+                payout_wallet: None,
+            },
+            marketplace::instruction::CreateDataListing {
+                listing_id,
+                price,
+                data_type,
+                description: format!("Test listing {}", listing_id),
+                identity_id: seller.identity_id.clone(),
+            },
+        );
+        self.harness.execute(&[instruction], &[&seller.wallet]).await;
+    }
+
+    /// Mint settlement tokens to a participant
+    pub async fn fund_tokens(&mut self, participant: &Participant, amount: u64) -> Pubkey {
+        let mint = self.mint;
+        self.harness.mint_to(&mint, &participant.pubkey(), amount).await
+    }
+
+    /// Buy `seller`'s listing with the settlement mint; the buyer must hold an active permission
+    /// from the seller covering the listing's data type. Returns the receipt address.
+    pub async fn purchase(
+        &mut self,
+        buyer: &Participant,
+        seller: &Participant,
+        listing_id: u64,
+        purpose: Purpose,
+    ) -> Pubkey {
+        let mint = self.mint;
+        self.harness.token_account(&seller.pubkey(), &mint).await;
+        let marketplace_account: marketplace::Marketplace = self.harness.fetch(&pda::marketplace::marketplace()).await;
+        let listing: marketplace::DataListing = self.harness.fetch(&pda::marketplace::listing(listing_id)).await;
+        let request = PurchaseRequest {
+            buyer: buyer.pubkey(),
+            buyer_identity_id: buyer.identity_id.clone(),
+            listing_id,
+            payout_wallet: seller.pubkey(),
+            seller_identity_id: seller.identity_id.clone(),
+            data_union: None,
+            data_type: listing.data_type(),
+            purpose,
+            mint,
+            escrowed: marketplace_account.refund_window > 0,
+            buyer_staked: false,
+            buyer_weighted: false,
+            rebate: None,
+            max_staleness: None,
+            day: marketplace::PriceCandle::day_of(self.harness.now().await),
+            audit_entry_count: self.harness.audit_entry_count(&seller.identity()).await,
+        };
+        self.harness
+            .execute(&[instructions::purchase_data(request)], &[&buyer.wallet])
+            .await;
+        pda::marketplace::receipt(&pda::marketplace::listing(listing_id))
+    }
+
+    /// Open a dispute against the consumer of `permission` on `claimant`'s identity; returns the dispute address
+    pub async fn open_dispute(
+        &mut self,
+        claimant: &Participant,
+        permission: &Pubkey,
+        requested_slash: u64,
+        jury_fee: u64,
+    ) -> Pubkey {
+        let juror_pool: JurorPool = self.harness.fetch(&pda::identity::juror_pool()).await;
+        let dispute = pda::identity::dispute(juror_pool.dispute_count);
+        let instruction = instructions::identity(
+            identity::accounts::OpenDispute {
+                dispute,
+                juror_pool: pda::identity::juror_pool(),
+                identity: claimant.identity(),
+                permission: *permission,
+                claimant: claimant.pubkey(),
+                oracle_registry: pda::identity::oracle_registry(),
+                system_program: system_program::ID,
+            },
+            identity::instruction::OpenDispute {
+                evidence_tx_id: format!("{}-evidence-{}", claimant.identity_id, juror_pool.dispute_count),
+                requested_slash,
+                jury_fee,
+            },
+        );
+        self.harness.execute(&[instruction], &[&claimant.wallet]).await;
+        dispute
+    }
+}
@@ -0,0 +1,333 @@
+use anchor_lang::AccountDeserialize;
+use datasov_client::audit::AuditLog;
+use datasov_client::pda;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::clock::Clock;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::heap;
+
+/// Lamports given to each wallet created with [`Harness::wallet`]
+pub const WALLET_LAMPORTS: u64 = 100_000_000_000;
+
+// Anchor's entrypoints tie the account slice and the account infos to one lifetime, which the
+// ProgramTest processor signature does not; leaking the slice is harmless in tests
+fn identity_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    heap::metered(|| datasov_client::identity::entry(program_id, accounts, data))
+}
+
+fn marketplace_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    heap::metered(|| datasov_client::marketplace::entry(program_id, accounts, data))
+}
+
+fn audit_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    heap::metered(|| datasov_client::audit::entry(program_id, accounts, data))
+}
+
+fn staking_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    heap::metered(|| datasov_client::staking::entry(program_id, accounts, data))
+}
+
+/// A bank running the identity, marketplace, audit and staking programs, with helpers for wallets,
+/// tokens, the clock and decoding program accounts
+pub struct Harness {
+    pub context: ProgramTestContext,
+}
+
+impl Harness {
+    /// ProgramTest with all four programs registered as native processors. With `SBF_OUT_DIR`
+    /// (or `BPF_OUT_DIR`) pointing at `target/deploy`, the built `.so` files run instead.
+    pub fn program_test() -> ProgramTest {
+        let mut program_test = ProgramTest::new(
+            "datasov_identity",
+            datasov_client::identity::ID,
+            processor!(identity_entry),
+        );
+        program_test.add_program(
+            "datasov_solana",
+            datasov_client::marketplace::ID,
+            processor!(marketplace_entry),
+        );
+        program_test.add_program("datasov_audit", datasov_client::audit::ID, processor!(audit_entry));
+        program_test.add_program(
+            "datasov_staking",
+            datasov_client::staking::ID,
+            processor!(staking_entry),
+        );
+        program_test
+    }
+
+    pub async fn start() -> Self {
+        Self::with_program_test(Self::program_test()).await
+    }
+
+    /// Start from a customised [`Harness::program_test`], e.g. with extra programs or accounts
+    pub async fn with_program_test(program_test: ProgramTest) -> Self {
+        let context = program_test.start_with_context().await;
+        heap::meter_cpis();
+        Self { context }
+    }
+
+    /// Fee payer for every transaction sent through the harness
+    pub fn payer(&self) -> &Keypair {
+        &self.context.payer
+    }
+
+    /// Send `instructions` in one transaction paid by the harness payer and signed by `signers`
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await?;
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Transaction of `instructions` paid by the harness payer and signed by `signers`
+    async fn transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Transaction, BanksClientError> {
+        let recent_blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend(
+            signers
+                .iter()
+                .copied()
+                .filter(|signer| signer.pubkey() != self.context.payer.pubkey()),
+        );
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            recent_blockhash,
+        ))
+    }
+
+    /// Compute units `instructions` consume when simulated, panicking if they fail. Figures are
+    /// only meaningful for the built programs (see [`Harness::program_test`]); native processors
+    /// are not metered.
+    pub async fn units_consumed(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> u64 {
+        let transaction = self
+            .transaction(instructions, signers)
+            .await
+            .expect("get_latest_blockhash");
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await
+            .expect("simulate_transaction");
+        if let Some(Err(err)) = simulation.result {
+            panic!("simulation failed: {}", err);
+        }
+        simulation
+            .simulation_details
+            .expect("simulation details")
+            .units_consumed
+    }
+
+    /// Serialized size in bytes of the transaction [`Harness::process`] would send, to compare
+    /// with the 1232-byte packet limit
+    pub async fn transaction_size(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> usize {
+        let transaction = self
+            .transaction(instructions, signers)
+            .await
+            .expect("get_latest_blockhash");
+        // One-byte signature count: transactions carry far fewer than 128 signatures
+        1 + transaction.signatures.len() * 64 + transaction.message.serialize().len()
+    }
+
+    /// Wait for a blockhash the latest transaction did not use, so that retrying a failed
+    /// transaction is not answered with the failure recorded under the same signature
+    pub async fn refresh_blockhash(&mut self) {
+        let used = self
+            .context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .expect("get_latest_blockhash");
+        while self
+            .context
+            .get_new_latest_blockhash()
+            .await
+            .expect("get_new_latest_blockhash")
+            == used
+        {}
+    }
+
+    /// Like [`Harness::process`], panicking with the transaction error when it fails
+    pub async fn execute(&mut self, instructions: &[Instruction], signers: &[&Keypair]) {
+        if let Err(err) = self.process(instructions, signers).await {
+            panic!("transaction failed: {}", err);
+        }
+    }
+
+    /// Transfer lamports from the harness payer
+    pub async fn fund(&mut self, to: &Pubkey, lamports: u64) {
+        let instruction = system_instruction::transfer(&self.context.payer.pubkey(), to, lamports);
+        self.execute(&[instruction], &[]).await;
+    }
+
+    /// New keypair funded with [`WALLET_LAMPORTS`]
+    pub async fn wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        self.fund(&wallet.pubkey(), WALLET_LAMPORTS).await;
+        wallet
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .expect("get_balance")
+    }
+
+    /// Decode an Anchor account, or `None` if it does not exist
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> Option<T> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .expect("get_account")?;
+        Some(
+            T::try_deserialize(&mut account.data.as_slice())
+                .unwrap_or_else(|err| panic!("account {} could not be decoded: {}", address, err)),
+        )
+    }
+
+    /// Decode an Anchor account that must exist
+    pub async fn fetch<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        self.account(address)
+            .await
+            .unwrap_or_else(|| panic!("account {} not found", address))
+    }
+
+    /// Current length of `subject`'s audit log, as instruction builders need it
+    pub async fn audit_entry_count(&mut self, subject: &Pubkey) -> u64 {
+        self.account::<AuditLog>(&pda::audit::audit_log(subject))
+            .await
+            .map_or(0, |log| log.entry_count)
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .expect("get_sysvar")
+    }
+
+    /// Current unix timestamp as programs see it
+    pub async fn now(&mut self) -> i64 {
+        self.clock().await.unix_timestamp
+    }
+
+    /// Move the clock forward, e.g. past a permission's expiry or a refund window
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock = self.clock().await;
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// New SPL mint with the harness payer as mint authority
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        let rent = self.context.banks_client.get_rent().await.expect("get_rent");
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &payer, None, decimals)
+                .expect("initialize_mint2"),
+        ];
+        self.execute(&instructions, &[&mint]).await;
+        mint.pubkey()
+    }
+
+    /// Associated token account of `owner` for `mint`, created if missing
+    pub async fn token_account(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let instruction =
+            create_associated_token_account_idempotent(&self.context.payer.pubkey(), owner, mint, &spl_token::ID);
+        self.execute(&[instruction], &[]).await;
+        get_associated_token_address(owner, mint)
+    }
+
+    /// Mint tokens into `owner`'s associated token account, creating it if missing
+    pub async fn mint_to(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let token_account = self.token_account(owner, mint).await;
+        let payer = self.context.payer.pubkey();
+        let instruction = spl_token::instruction::mint_to(&spl_token::ID, mint, &token_account, &payer, &[], amount)
+            .expect("mint_to");
+        self.execute(&[instruction], &[]).await;
+        token_account
+    }
+
+    /// Token balance of an SPL token account, zero if it does not exist
+    pub async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        match self
+            .context
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .expect("get_account")
+        {
+            Some(account) => {
+                spl_token::state::Account::unpack(&account.data)
+                    .expect("token account")
+                    .amount
+            }
+            None => 0,
+        }
+    }
+}
+
+/// Custom program error code of a failed transaction, e.g. to compare with
+/// `u32::from(datasov_identity::ErrorCode::PermissionExpired)`
+pub fn program_error_code(err: &BanksClientError) -> Option<u32> {
+    match err {
+        BanksClientError::TransactionError(TransactionError::InstructionError(_, InstructionError::Custom(code)))
+        | BanksClientError::SimulationError {
+            err: TransactionError::InstructionError(_, InstructionError::Custom(code)),
+            ..
+        } => Some(*code),
+        _ => None,
+    }
+}
+
+/// Assert that a transaction failed with the given Anchor error
+pub fn assert_program_error(result: Result<(), BanksClientError>, expected: impl Into<u32>) {
+    let expected = expected.into();
+    match result {
+        Ok(()) => panic!("transaction succeeded, expected program error {}", expected),
+        Err(err) => assert_eq!(
+            program_error_code(&err),
+            Some(expected),
+            "expected program error {}, got {}",
+            expected,
+            err
+        ),
+    }
+}
@@ -0,0 +1,212 @@
+//! Heap metering for the native processors.
+//!
+//! The SBF bump allocator never frees, so the heap an invocation needs is every byte it
+//! allocates. With [`HeapMeter`] installed as the global allocator, the harness counts those bytes
+//! per program invocation (CPIs included, each in its own frame, as on chain) and [`take_peak`]
+//! reports the largest count since it was last called. What the runtime allocates to carry out a
+//! CPI is left out, as are the built programs.
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static HEAP: datasov_test_harness::heap::HeapMeter = datasov_test_harness::heap::HeapMeter;
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Once;
+
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_stubs::{self, SyscallStubs};
+use solana_sdk::pubkey::Pubkey;
+
+/// Deepest stack of frames metered: the top-level instruction, then for each of the runtime's
+/// four CPI levels the call's own frame and the callee's
+const MAX_DEPTH: usize = 9;
+
+thread_local! {
+    /// Invocations in progress on this thread
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// Bytes allocated by each invocation in progress, outermost first
+    static ALLOCATED: Cell<[usize; MAX_DEPTH]> = const { Cell::new([0; MAX_DEPTH]) };
+}
+
+/// Largest heap any invocation has needed since the last [`take_peak`]
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether [`HeapMeter`] is the global allocator, i.e. has allocated anything
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Global allocator that counts what metered invocations allocate, then defers to [`System`]
+pub struct HeapMeter;
+
+unsafe impl GlobalAlloc for HeapMeter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    // The bump allocator reallocates by allocating anew
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn record(bytes: usize) {
+    // Allocations during thread teardown go unmetered
+    let _ = DEPTH.try_with(|depth| match depth.get() {
+        0 => {}
+        depth => {
+            let _ = ALLOCATED.try_with(|allocated| {
+                let mut frames = allocated.get();
+                frames[depth.min(MAX_DEPTH) - 1] += bytes;
+                allocated.set(frames);
+            });
+        }
+    });
+}
+
+/// Run `f` in a frame of its own; returns its result and the bytes it allocated, less those
+/// allocated in frames it opened
+fn frame<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let depth = DEPTH.with(|depth| {
+        depth.set(depth.get() + 1);
+        depth.get().min(MAX_DEPTH)
+    });
+    ALLOCATED.with(|allocated| {
+        let mut frames = allocated.get();
+        frames[depth - 1] = 0;
+        allocated.set(frames);
+    });
+    let result = f();
+    let allocated = ALLOCATED.with(|allocated| allocated.get()[depth - 1]);
+    DEPTH.with(|depth| depth.set(depth.get() - 1));
+    (result, allocated)
+}
+
+/// Run one program invocation, counting its allocations in a frame of its own
+pub(crate) fn metered<R>(invocation: impl FnOnce() -> R) -> R {
+    let (result, allocated) = frame(invocation);
+    PEAK.fetch_max(allocated, Ordering::Relaxed);
+    result
+}
+
+/// Wrap the syscall stubs ProgramTest installs so a CPI runs in an unmetered frame of its own,
+/// keeping the runtime's work out of the caller's count. Only done when [`HeapMeter`] is the
+/// global allocator: swapping the stubs races with processors running on other threads, which
+/// benchmarks do not have.
+pub(crate) fn meter_cpis() {
+    static WRAP: Once = Once::new();
+    if INSTALLED.load(Ordering::Relaxed) {
+        WRAP.call_once(|| {
+            let inner = program_stubs::set_syscall_stubs(Box::new(Unset));
+            program_stubs::set_syscall_stubs(Box::new(CpiStubs { inner }));
+        });
+    }
+}
+
+/// Placeholder while the stubs are swapped
+struct Unset;
+
+impl SyscallStubs for Unset {}
+
+/// Forwards every syscall to `inner`, running CPIs in an unmetered frame
+struct CpiStubs {
+    inner: Box<dyn SyscallStubs>,
+}
+
+impl SyscallStubs for CpiStubs {
+    fn sol_log(&self, message: &str) {
+        self.inner.sol_log(message)
+    }
+
+    fn sol_log_compute_units(&self) {
+        self.inner.sol_log_compute_units()
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.inner.sol_remaining_compute_units()
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        frame(|| self.inner.sol_invoke_signed(instruction, account_infos, signers_seeds)).0
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_rent_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.inner.sol_get_last_restart_slot(var_addr)
+    }
+
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.inner.sol_memcpy(dst, src, n)
+    }
+
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.inner.sol_memmove(dst, src, n)
+    }
+
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.inner.sol_memcmp(s1, s2, n, result)
+    }
+
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.inner.sol_memset(s, c, n)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.inner.sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.inner.sol_set_return_data(data)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        self.inner.sol_log_data(fields)
+    }
+
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.inner.sol_get_processed_sibling_instruction(index)
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        self.inner.sol_get_stack_height()
+    }
+}
+
+/// Largest heap one invocation needed since the last call, or zero if nothing was metered
+pub fn take_peak() -> usize {
+    PEAK.swap(0, Ordering::Relaxed)
+}
@@ -0,0 +1,23 @@
+//! End-to-end test harness for the DataSov programs.
+//!
+//! - [`Harness`] runs the identity, marketplace and audit programs in a `solana-program-test`
+//!   bank, with helpers for wallets, SPL tokens, the clock, compute units, transaction sizes and
+//!   decoding accounts.
+//! - [`Protocol`] bootstraps the oracle registry, compliance matrix, blacklist, juror pool,
+//!   marketplace, KYC oracles and a settlement mint, and seeds identities, grants, listings,
+//!   purchases and disputes.
+//! - [`scenarios`] builds common starting states: a verified seller, an expiring permission and a
+//!   disputed purchase.
+//! - [`heap`] meters the heap each program invocation needs under the native processors.
+//!
+//! A test starts from `Protocol::start().await`, builds a scenario, sends the instruction under
+//! test with `protocol.harness.process(..)` and checks the result with [`assert_program_error`] or
+//! by fetching accounts.
+
+mod fixtures;
+mod harness;
+pub mod heap;
+pub mod scenarios;
+
+pub use fixtures::{Participant, Protocol, ProtocolConfig};
+pub use harness::{assert_program_error, program_error_code, Harness, WALLET_LAMPORTS};
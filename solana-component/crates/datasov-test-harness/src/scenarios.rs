@@ -0,0 +1,198 @@
+//! Builders for the states most end-to-end tests start from. Each builder has defaults that pass
+//! every program check, so a test only sets what it is about.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::DataType as ListingDataType;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{Participant, Protocol};
+
+/// A verified identity with a consumer profile, ready to list or buy
+pub struct VerifiedSeller {
+    identity_id: Option<String>,
+    level: VerificationLevel,
+}
+
+impl Default for VerifiedSeller {
+    fn default() -> Self {
+        Self {
+            identity_id: None,
+            level: VerificationLevel::Enhanced,
+        }
+    }
+}
+
+impl VerifiedSeller {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn identity_id(mut self, identity_id: &str) -> Self {
+        self.identity_id = Some(identity_id.to_string());
+        self
+    }
+
+    pub fn level(mut self, level: VerificationLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub async fn build(self, protocol: &mut Protocol) -> Participant {
+        let identity_id = self
+            .identity_id
+            .unwrap_or_else(|| protocol.unique_identity_id("seller"));
+        protocol.verified_participant(&identity_id, self.level).await
+    }
+}
+
+/// An owner's grant to a consumer that lapses `ttl` seconds from now
+pub struct ExpiringPermission {
+    ttl: i64,
+    data_types: Vec<DataType>,
+}
+
+/// State built by [`ExpiringPermission`]
+pub struct ExpiringPermissionScenario {
+    pub owner: Participant,
+    pub consumer: Participant,
+    pub permission: Pubkey,
+    pub expires_at: i64,
+}
+
+impl Default for ExpiringPermission {
+    fn default() -> Self {
+        Self {
+            ttl: 3600,
+            data_types: vec![DataType::LocationHistory],
+        }
+    }
+}
+
+impl ExpiringPermission {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seconds until the permission expires
+    pub fn ttl(mut self, ttl: i64) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn data_types(mut self, data_types: Vec<DataType>) -> Self {
+        self.data_types = data_types;
+        self
+    }
+
+    pub async fn build(self, protocol: &mut Protocol) -> ExpiringPermissionScenario {
+        let owner = VerifiedSeller::new().build(protocol).await;
+        let consumer_id = protocol.unique_identity_id("consumer");
+        let consumer = protocol
+            .verified_participant(&consumer_id, VerificationLevel::Enhanced)
+            .await;
+        let expires_at = protocol.harness.now().await + self.ttl;
+        let permission = protocol
+            .grant(&owner, &consumer, self.data_types, Some(expires_at))
+            .await;
+        ExpiringPermissionScenario {
+            owner,
+            consumer,
+            permission,
+            expires_at,
+        }
+    }
+}
+
+/// A completed purchase whose seller has opened a dispute against the buyer
+pub struct DisputedPurchase {
+    price: u64,
+    data_type: ListingDataType,
+    purpose: Purpose,
+    requested_slash: u64,
+    jury_fee: u64,
+}
+
+/// State built by [`DisputedPurchase`]
+pub struct DisputedPurchaseScenario {
+    pub seller: Participant,
+    pub buyer: Participant,
+    pub listing_id: u64,
+    /// The buyer's permission on the seller's identity
+    pub permission: Pubkey,
+    pub receipt: Pubkey,
+    pub dispute: Pubkey,
+}
+
+impl Default for DisputedPurchase {
+    fn default() -> Self {
+        Self {
+            price: 1_000_000,
+            data_type: ListingDataType::LocationHistory,
+            purpose: Purpose::Research,
+            requested_slash: 0,
+            jury_fee: 10_000_000,
+        }
+    }
+}
+
+impl DisputedPurchase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listing price in settlement-mint base units
+    pub fn price(mut self, price: u64) -> Self {
+        self.price = price;
+        self
+    }
+
+    /// Listing data type; the buyer's permission is granted for the matching identity data type
+    pub fn data_type(mut self, data_type: ListingDataType) -> Self {
+        self.data_type = data_type;
+        self
+    }
+
+    pub fn purpose(mut self, purpose: Purpose) -> Self {
+        self.purpose = purpose;
+        self
+    }
+
+    /// Consumer stake the seller asks the jury to slash
+    pub fn requested_slash(mut self, requested_slash: u64) -> Self {
+        self.requested_slash = requested_slash;
+        self
+    }
+
+    /// Lamports the seller pays into the dispute for the jury
+    pub fn jury_fee(mut self, jury_fee: u64) -> Self {
+        self.jury_fee = jury_fee;
+        self
+    }
+
+    pub async fn build(self, protocol: &mut Protocol) -> DisputedPurchaseScenario {
+        let seller = VerifiedSeller::new().build(protocol).await;
+        let buyer_id = protocol.unique_identity_id("buyer");
+        let buyer = protocol
+            .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+            .await;
+
+        let permission = protocol
+            .grant(&seller, &buyer, vec![self.data_type.to_identity_data_type()], None)
+            .await;
+        let listing_id = protocol.create_listing(&seller, self.price, self.data_type).await;
+        protocol.fund_tokens(&buyer, self.price).await;
+        let receipt = protocol.purchase(&buyer, &seller, listing_id, self.purpose).await;
+        let dispute = protocol
+            .open_dispute(&seller, &permission, self.requested_slash, self.jury_fee)
+            .await;
+
+        DisputedPurchaseScenario {
+            seller,
+            buyer,
+            listing_id,
+            permission,
+            receipt,
+            dispute,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Paid access requests: a consumer escrows lamports against the grant they want, and the identity
+//! owner accepts in one instruction that creates the permission and collects the payment.
+
+use datasov_client::identity::{AccessPermission, DataType, ErrorCode, PermissionType, VerificationLevel};
+use datasov_client::{instructions, pda, storage};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signer::Signer;
+
+const PRICE: u64 = 100_000_000;
+const DURATION: i64 = 86_400;
+
+async fn request(protocol: &mut Protocol, owner: &Participant, consumer: &Participant, price: u64) -> Instruction {
+    let expires_at = protocol.harness.now().await + 60;
+    instructions::request_access(
+        &consumer.pubkey(),
+        &owner.identity_id,
+        PermissionType::ReadOnly,
+        vec![DataType::LocationHistory],
+        DURATION,
+        price,
+        expires_at,
+    )
+}
+
+async fn accept(protocol: &mut Protocol, owner: &Participant, consumer: &Participant) -> Instruction {
+    let audit_entry_count = protocol.harness.audit_entry_count(&owner.identity()).await;
+    instructions::accept_access_request(
+        &owner.pubkey(),
+        &owner.identity_id,
+        &consumer.pubkey(),
+        storage::placeholder("paid-grant"),
+        false,
+        audit_entry_count,
+    )
+}
+
+async fn participants(protocol: &mut Protocol) -> (Participant, Participant) {
+    let owner_id = protocol.unique_identity_id("owner");
+    let owner = protocol
+        .verified_participant(&owner_id, VerificationLevel::Enhanced)
+        .await;
+    let consumer_id = protocol.unique_identity_id("consumer");
+    let consumer = protocol
+        .verified_participant(&consumer_id, VerificationLevel::Enhanced)
+        .await;
+    (owner, consumer)
+}
+
+#[tokio::test]
+async fn accepting_a_request_grants_access_and_pays_the_owner() {
+    let mut protocol = Protocol::start().await;
+    let (owner, consumer) = participants(&mut protocol).await;
+    let consumer_lamports = protocol.harness.lamports(&consumer.pubkey()).await;
+    let owner_lamports = protocol.harness.lamports(&owner.pubkey()).await;
+
+    let offer = request(&mut protocol, &owner, &consumer, PRICE).await;
+    protocol.harness.execute(&[offer], &[&consumer.wallet]).await;
+    let access_request = pda::identity::access_request(&owner.identity(), &consumer.pubkey());
+    assert!(protocol.harness.lamports(&access_request).await > PRICE);
+
+    // Only the owner can accept
+    let outsider = protocol.harness.wallet().await;
+    let mut hijack = accept(&mut protocol, &owner, &consumer).await;
+    hijack.accounts[8].pubkey = outsider.pubkey();
+    let result = protocol.harness.process(&[hijack], &[&outsider]).await;
+    assert!(result.is_err());
+
+    let acceptance = accept(&mut protocol, &owner, &consumer).await;
+    protocol.harness.execute(&[acceptance], &[&owner.wallet]).await;
+
+    let permission: AccessPermission = protocol
+        .harness
+        .fetch(&pda::identity::permission(&owner.identity(), &consumer.pubkey()))
+        .await;
+    assert!(permission.is_active);
+    assert_eq!(permission.data_types, vec![DataType::LocationHistory]);
+    assert_eq!(permission.expires_at, Some(permission.granted_at + DURATION));
+
+    // The request closes, its rent back with the consumer, who is down exactly the price
+    assert_eq!(protocol.harness.lamports(&access_request).await, 0);
+    assert_eq!(
+        protocol.harness.lamports(&consumer.pubkey()).await,
+        consumer_lamports - PRICE
+    );
+    assert!(protocol.harness.lamports(&owner.pubkey()).await > owner_lamports);
+}
+
+#[tokio::test]
+async fn requests_lapse_and_can_be_withdrawn() {
+    let mut protocol = Protocol::start().await;
+    let (owner, consumer) = participants(&mut protocol).await;
+    let consumer_lamports = protocol.harness.lamports(&consumer.pubkey()).await;
+
+    let free = request(&mut protocol, &owner, &consumer, 0).await;
+    let result = protocol.harness.process(&[free], &[&consumer.wallet]).await;
+    assert_program_error(result, ErrorCode::InvalidAccessRequest);
+
+    let offer = request(&mut protocol, &owner, &consumer, PRICE).await;
+    protocol.harness.execute(&[offer], &[&consumer.wallet]).await;
+    let cancel = instructions::cancel_access_request(&consumer.pubkey(), &owner.identity_id);
+    protocol.harness.execute(&[cancel], &[&consumer.wallet]).await;
+    assert_eq!(protocol.harness.lamports(&consumer.pubkey()).await, consumer_lamports);
+
+    // Past its deadline the owner can no longer accept, but the consumer can still withdraw
+    protocol.harness.refresh_blockhash().await;
+    let offer = request(&mut protocol, &owner, &consumer, PRICE).await;
+    protocol.harness.execute(&[offer], &[&consumer.wallet]).await;
+    protocol.harness.advance_clock(61).await;
+    let acceptance = accept(&mut protocol, &owner, &consumer).await;
+    let result = protocol.harness.process(&[acceptance], &[&owner.wallet]).await;
+    assert_program_error(result, ErrorCode::AccessRequestExpired);
+
+    protocol.harness.refresh_blockhash().await;
+    let cancel = instructions::cancel_access_request(&consumer.pubkey(), &owner.identity_id);
+    protocol.harness.execute(&[cancel], &[&consumer.wallet]).await;
+    assert_eq!(protocol.harness.lamports(&consumer.pubkey()).await, consumer_lamports);
+}
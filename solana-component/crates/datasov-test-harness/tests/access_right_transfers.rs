@@ -0,0 +1,112 @@
+//! Access right transfers: on listings that allow it, a buyer hands the access right bought under
+//! a receipt to another verified identity the data owner has also granted access.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions;
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, ErrorCode, PurchaseReceipt};
+use datasov_client::pda;
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+
+/// A sold listing, its seller and buyer
+async fn sold_listing(protocol: &mut Protocol, rights_transferable: bool) -> (Participant, Participant, u64) {
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let set_transferable = instructions::marketplace(
+        marketplace::accounts::SetListingRightsTransferable {
+            listing: pda::marketplace::listing(listing_id),
+            owner: seller.pubkey(),
+        },
+        marketplace::instruction::SetListingRightsTransferable { rights_transferable },
+    );
+    protocol.harness.execute(&[set_transferable], &[&seller.wallet]).await;
+
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+    (seller, buyer, listing_id)
+}
+
+fn transfer(seller: &Participant, holder: &Participant, recipient: &Participant, listing_id: u64) -> Instruction {
+    instructions::transfer_access_right(
+        &holder.pubkey(),
+        listing_id,
+        &seller.identity_id,
+        &pda::identity::permission(&seller.identity(), &holder.pubkey()),
+        &recipient.identity_id,
+        &recipient.pubkey(),
+        None,
+    )
+}
+
+#[tokio::test]
+async fn buyers_transfer_rights_to_granted_identities() {
+    let mut protocol = Protocol::start().await;
+    let (seller, buyer, listing_id) = sold_listing(&mut protocol, true).await;
+    let recipient_id = protocol.unique_identity_id("recipient");
+    let recipient = protocol
+        .verified_participant(&recipient_id, VerificationLevel::Enhanced)
+        .await;
+
+    // The data owner must have granted the recipient access too
+    let result = protocol
+        .harness
+        .process(&[transfer(&seller, &buyer, &recipient, listing_id)], &[&buyer.wallet])
+        .await;
+    assert!(result.is_err());
+
+    protocol
+        .grant(&seller, &recipient, vec![DataType::LocationHistory], None)
+        .await;
+    protocol
+        .harness
+        .execute(&[transfer(&seller, &buyer, &recipient, listing_id)], &[&buyer.wallet])
+        .await;
+
+    let listing = pda::marketplace::listing(listing_id);
+    let receipt: PurchaseReceipt = protocol.harness.fetch(&pda::marketplace::receipt(&listing)).await;
+    assert_eq!(receipt.buyer, recipient.pubkey());
+    assert_eq!(
+        receipt.permission,
+        pda::identity::permission(&seller.identity(), &recipient.pubkey())
+    );
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.buyer(), Some(recipient.pubkey()));
+
+    // The previous holder no longer holds anything to transfer
+    let result = protocol
+        .harness
+        .process(&[transfer(&seller, &buyer, &recipient, listing_id)], &[&buyer.wallet])
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn rights_stay_put_unless_the_listing_allows_transfer() {
+    let mut protocol = Protocol::start().await;
+    let (seller, buyer, listing_id) = sold_listing(&mut protocol, false).await;
+    let recipient_id = protocol.unique_identity_id("recipient");
+    let recipient = protocol
+        .verified_participant(&recipient_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &recipient, vec![DataType::LocationHistory], None)
+        .await;
+
+    let result = protocol
+        .harness
+        .process(&[transfer(&seller, &buyer, &recipient, listing_id)], &[&buyer.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::RightsNotTransferable);
+}
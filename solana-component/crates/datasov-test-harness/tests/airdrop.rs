@@ -0,0 +1,140 @@
+//! Airdrops: the staking authority (governance) posts the Merkle root of (wallet, amount)
+//! allocations, and each wallet claims its allocation once with a proof from the tree.
+
+use datasov_client::admin;
+use datasov_client::airdrop::{AirdropTree, Allocation};
+use datasov_client::instructions;
+use datasov_client::pda;
+use datasov_client::staking::{self, Airdrop, ErrorCode};
+use datasov_test_harness::{assert_program_error, Protocol};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const AIRDROP_ID: u64 = 1;
+
+/// Initialize staking with the protocol authority; returns the protocol token mint
+async fn initialize_staking(protocol: &mut Protocol) -> Pubkey {
+    let mint = protocol.harness.create_mint(6).await;
+    let instruction = instructions::staking(
+        staking::accounts::InitializeStaking {
+            config: pda::staking::config(),
+            mint,
+            vault: pda::staking::vault(),
+            authority: protocol.authority.pubkey(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::InitializeStaking {
+            unstake_cooldown: 0,
+            consumer_slash_bps: 0,
+            juror_slash_bps: 0,
+            vote_authority: pda::governance::governance(),
+        },
+    );
+    protocol.harness.execute(&[instruction], &[&protocol.authority]).await;
+    mint
+}
+
+/// An airdrop to three new wallets, its vault funded with their allocations
+async fn airdrop(protocol: &mut Protocol, mint: &Pubkey) -> (Vec<Keypair>, AirdropTree) {
+    let mut wallets = Vec::new();
+    for _ in 0..3 {
+        let wallet = protocol.harness.wallet().await;
+        protocol.harness.token_account(&wallet.pubkey(), mint).await;
+        wallets.push(wallet);
+    }
+    let tree = AirdropTree::new(
+        wallets
+            .iter()
+            .zip([1_000, 2_000, 3_000])
+            .map(|(wallet, amount)| Allocation {
+                wallet: wallet.pubkey(),
+                amount,
+            })
+            .collect(),
+    );
+
+    let authority = protocol.authority.pubkey();
+    let create = admin::create_airdrop(&authority, mint, AIRDROP_ID, tree.root(), tree.leaf_count());
+    protocol.harness.execute(&[create], &[&protocol.authority]).await;
+
+    let payer = protocol.harness.payer().pubkey();
+    let vault = pda::staking::airdrop_vault(&pda::staking::airdrop(AIRDROP_ID));
+    let fund = spl_token::instruction::mint_to(&spl_token::ID, mint, &vault, &payer, &[], 6_000).expect("mint_to");
+    protocol.harness.execute(&[fund], &[]).await;
+    (wallets, tree)
+}
+
+#[tokio::test]
+async fn each_allocation_is_claimed_once_with_its_proof() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_staking(&mut protocol).await;
+    let (wallets, tree) = airdrop(&mut protocol, &mint).await;
+
+    for (index, wallet) in wallets.iter().enumerate() {
+        let index = index as u32;
+        let amount = tree.allocation(index).unwrap().amount;
+        let claim = instructions::claim_airdrop(
+            &wallet.pubkey(),
+            &mint,
+            AIRDROP_ID,
+            index,
+            amount,
+            tree.proof(index).unwrap(),
+        );
+        protocol.harness.execute(&[claim], &[wallet]).await;
+        let token_account = get_associated_token_address(&wallet.pubkey(), &mint);
+        assert_eq!(protocol.harness.token_balance(&token_account).await, amount);
+    }
+
+    let airdrop: Airdrop = protocol.harness.fetch(&pda::staking::airdrop(AIRDROP_ID)).await;
+    assert_eq!(airdrop.claimed_count, 3);
+    assert_eq!(airdrop.claimed_amount, 6_000);
+
+    // A fresh blockhash, so the repeat is not answered with the first claim's success
+    protocol.harness.refresh_blockhash().await;
+    let claim = instructions::claim_airdrop(
+        &wallets[0].pubkey(),
+        &mint,
+        AIRDROP_ID,
+        0,
+        1_000,
+        tree.proof(0).unwrap(),
+    );
+    let result = protocol.harness.process(&[claim], &[&wallets[0]]).await;
+    assert_program_error(result, ErrorCode::AirdropAlreadyClaimed);
+}
+
+#[tokio::test]
+async fn a_claim_must_match_its_allocation() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_staking(&mut protocol).await;
+    let (wallets, tree) = airdrop(&mut protocol, &mint).await;
+
+    // More than allocated
+    let claim = instructions::claim_airdrop(
+        &wallets[1].pubkey(),
+        &mint,
+        AIRDROP_ID,
+        1,
+        3_000,
+        tree.proof(1).unwrap(),
+    );
+    let result = protocol.harness.process(&[claim], &[&wallets[1]]).await;
+    assert_program_error(result, ErrorCode::InvalidMerkleProof);
+
+    // Another wallet's allocation
+    let claim = instructions::claim_airdrop(
+        &wallets[1].pubkey(),
+        &mint,
+        AIRDROP_ID,
+        2,
+        3_000,
+        tree.proof(2).unwrap(),
+    );
+    let result = protocol.harness.process(&[claim], &[&wallets[1]]).await;
+    assert_program_error(result, ErrorCode::InvalidMerkleProof);
+}
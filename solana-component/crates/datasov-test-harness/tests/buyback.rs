@@ -0,0 +1,170 @@
+//! Buyback-and-burn: a share of the marketplace's fee balance is swapped for the protocol token
+//! through the configured DEX and burned, no sooner than the interval allows and never below the
+//! quote's slippage bound or the price floor. A mock DEX stands in for Jupiter or Orca.
+
+use datasov_client::admin;
+use datasov_client::instructions;
+use datasov_client::marketplace::{BuybackConfig, BuybackParameters, ErrorCode};
+use datasov_client::pda;
+use datasov_test_harness::{assert_program_error, Harness, Protocol, ProtocolConfig};
+use solana_program_test::processor;
+use solana_sdk::account_info::{next_account_info, AccountInfo};
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program::invoke;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+const INTERVAL: i64 = 86_400;
+const FEES: u64 = 1_000_000;
+/// Half a protocol token per fee token, scaled by `BuybackParameters::PRICE_SCALE`
+const MIN_PRICE: u64 = 500_000_000;
+
+/// A pool that takes `amount_in` from the swapper and pays out whatever `amount_out` the route
+/// names. Accounts: authority, source, destination, pool source, pool destination, pool owner,
+/// token program.
+fn mock_dex(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let authority = next_account_info(accounts)?;
+    let source = next_account_info(accounts)?;
+    let destination = next_account_info(accounts)?;
+    let pool_source = next_account_info(accounts)?;
+    let pool_destination = next_account_info(accounts)?;
+    let pool_owner = next_account_info(accounts)?;
+    let token_program = next_account_info(accounts)?;
+    let amount_in = u64::from_le_bytes(data[..8].try_into().unwrap());
+    let amount_out = u64::from_le_bytes(data[8..16].try_into().unwrap());
+
+    let pay_in = spl_token::instruction::transfer(
+        token_program.key,
+        source.key,
+        pool_destination.key,
+        authority.key,
+        &[],
+        amount_in,
+    )?;
+    invoke(&pay_in, &[source.clone(), pool_destination.clone(), authority.clone()])?;
+    let pay_out = spl_token::instruction::transfer(
+        token_program.key,
+        pool_source.key,
+        destination.key,
+        pool_owner.key,
+        &[],
+        amount_out,
+    )?;
+    invoke(
+        &pay_out,
+        &[pool_source.clone(), destination.clone(), pool_owner.clone()],
+    )
+}
+
+struct Market {
+    protocol: Protocol,
+    dex: Pubkey,
+    protocol_mint: Pubkey,
+    pool_owner: Keypair,
+}
+
+/// Protocol with the mock DEX, a pool of protocol tokens, `FEES` collected by the marketplace and
+/// buybacks spending half of them
+async fn market() -> Market {
+    let dex = Pubkey::new_unique();
+    let mut program_test = Harness::program_test();
+    program_test.add_program("mock_dex", dex, processor!(mock_dex));
+    let harness = Harness::with_program_test(program_test).await;
+    let mut protocol = Protocol::bootstrap(harness, ProtocolConfig::default()).await;
+
+    let fee_mint = protocol.mint;
+    let protocol_mint = protocol.harness.create_mint(6).await;
+    let marketplace = pda::marketplace::marketplace();
+    protocol.harness.mint_to(&fee_mint, &marketplace, FEES).await;
+    let pool_owner = protocol.harness.wallet().await;
+    protocol
+        .harness
+        .mint_to(&protocol_mint, &pool_owner.pubkey(), 10 * FEES)
+        .await;
+    protocol.harness.token_account(&pool_owner.pubkey(), &fee_mint).await;
+
+    let initialize = admin::initialize_buyback(
+        &protocol.authority.pubkey(),
+        &fee_mint,
+        &protocol_mint,
+        BuybackParameters {
+            dex_program: dex,
+            buyback_bps: 5_000,
+            interval: INTERVAL,
+            max_slippage_bps: 100,
+            min_price: MIN_PRICE,
+        },
+    );
+    protocol.harness.execute(&[initialize], &[&protocol.authority]).await;
+    Market {
+        protocol,
+        dex,
+        protocol_mint,
+        pool_owner,
+    }
+}
+
+impl Market {
+    /// Buyback whose route swaps the input vault's `amount_in` for `amount_out` from the pool
+    fn buyback(&self, amount_in: u64, amount_out: u64, quoted_out: u64) -> Instruction {
+        let fee_mint = self.protocol.mint;
+        let pool_owner = self.pool_owner.pubkey();
+        let mut data = amount_in.to_le_bytes().to_vec();
+        data.extend_from_slice(&amount_out.to_le_bytes());
+        let route = Instruction {
+            program_id: self.dex,
+            accounts: vec![
+                AccountMeta::new_readonly(pda::marketplace::buyback(), true),
+                AccountMeta::new(pda::marketplace::buyback_input(), false),
+                AccountMeta::new(pda::marketplace::buyback_output(), false),
+                AccountMeta::new(get_associated_token_address(&pool_owner, &self.protocol_mint), false),
+                AccountMeta::new(get_associated_token_address(&pool_owner, &fee_mint), false),
+                AccountMeta::new_readonly(pool_owner, true),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data,
+        };
+        instructions::execute_buyback(&fee_mint, &self.protocol_mint, &route, quoted_out)
+    }
+}
+
+#[tokio::test]
+async fn buyback_swaps_the_fee_share_and_burns_the_proceeds() {
+    let mut market = market().await;
+    let swap = market.buyback(FEES / 2, 400_000, 400_000);
+    market.protocol.harness.execute(&[swap], &[&market.pool_owner]).await;
+
+    let marketplace_token_account =
+        get_associated_token_address(&pda::marketplace::marketplace(), &market.protocol.mint);
+    assert_eq!(
+        market.protocol.harness.token_balance(&marketplace_token_account).await,
+        FEES / 2
+    );
+    let output_vault = pda::marketplace::buyback_output();
+    assert_eq!(market.protocol.harness.token_balance(&output_vault).await, 0);
+    let config: BuybackConfig = market.protocol.harness.fetch(&pda::marketplace::buyback()).await;
+    assert_eq!(config.total_spent, FEES / 2);
+    assert_eq!(config.total_burned, 400_000);
+
+    // The next buyback waits out the interval
+    let swap = market.buyback(FEES / 4, 200_000, 200_000);
+    let result = market.protocol.harness.process(&[swap], &[&market.pool_owner]).await;
+    assert_program_error(result, ErrorCode::BuybackNotDue);
+}
+
+#[tokio::test]
+async fn buyback_below_the_price_floor_is_rejected() {
+    let mut market = market().await;
+    // A zero quote cannot lower the bound below the floor of 250_000 for 500_000 fee tokens
+    let swap = market.buyback(FEES / 2, 200_000, 0);
+    let result = market.protocol.harness.process(&[swap], &[&market.pool_owner]).await;
+    assert_program_error(result, ErrorCode::BuybackSlippageExceeded);
+
+    let swap = market.buyback(FEES / 2, 390_000, 400_000);
+    let result = market.protocol.harness.process(&[swap], &[&market.pool_owner]).await;
+    assert_program_error(result, ErrorCode::BuybackSlippageExceeded);
+}
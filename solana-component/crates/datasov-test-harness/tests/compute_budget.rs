@@ -0,0 +1,147 @@
+//! Compute-budget regression checks for the hot paths: each instruction must stay within the
+//! ceiling `datasov_client::compute` requests for it, so a change that pushes one past fails here
+//! before it fails for users.
+//!
+//! Run with `SBF_OUT_DIR=target/deploy` to meter the built programs; native processors report no
+//! units, so only the instructions' success is checked.
+
+use datasov_client::identity::{AccessPermission, DataType, PermissionType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, AccessCheck, GrantAccessRequest, PurchaseRequest};
+use datasov_client::marketplace::DataType as ListingDataType;
+use datasov_client::{compute, marketplace, pda, storage};
+use datasov_test_harness::{Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+/// Simulate `instruction` and fail if it consumes more than its requested ceiling
+async fn assert_within_budget(protocol: &mut Protocol, name: &str, instruction: Instruction, signers: &[&Keypair]) {
+    let budget = compute::instruction_units(&instruction) as u64;
+    let consumed = protocol.harness.units_consumed(&[instruction], signers).await;
+    assert!(
+        consumed <= budget,
+        "{} consumed {} units, budget is {}",
+        name,
+        consumed,
+        budget
+    );
+}
+
+async fn participants(protocol: &mut Protocol) -> (Participant, Participant) {
+    let owner_id = protocol.unique_identity_id("owner");
+    let owner = protocol
+        .verified_participant(&owner_id, VerificationLevel::Enhanced)
+        .await;
+    let consumer_id = protocol.unique_identity_id("consumer");
+    let consumer = protocol
+        .verified_participant(&consumer_id, VerificationLevel::Enhanced)
+        .await;
+    (owner, consumer)
+}
+
+#[tokio::test]
+async fn register_identity_within_budget() {
+    let mut protocol = Protocol::start().await;
+    let wallet = protocol.harness.wallet().await;
+    let identity_id = protocol.unique_identity_id("budget");
+
+    let instruction =
+        instructions::register_identity(&wallet.pubkey(), &identity_id, storage::placeholder("budget-tx"));
+    assert_within_budget(&mut protocol, "register_identity", instruction, &[&wallet]).await;
+}
+
+#[tokio::test]
+async fn grant_access_within_budget() {
+    let mut protocol = Protocol::start().await;
+    let (owner, consumer) = participants(&mut protocol).await;
+
+    let instruction = instructions::grant_access(GrantAccessRequest {
+        owner: owner.pubkey(),
+        identity_id: owner.identity_id.clone(),
+        consumer: consumer.pubkey(),
+        permission_type: PermissionType::ReadOnly,
+        data_types: vec![DataType::LocationHistory],
+        expires_at: None,
+        permission_pointer: storage::placeholder("budget-grant"),
+        consumer_staked: false,
+        guardian: None,
+        audit_entry_count: protocol.harness.audit_entry_count(&owner.identity()).await,
+    });
+    assert_within_budget(&mut protocol, "grant_access", instruction, &[&owner.wallet]).await;
+}
+
+#[tokio::test]
+async fn validate_access_within_budget() {
+    let mut protocol = Protocol::start().await;
+    let (owner, consumer) = participants(&mut protocol).await;
+    protocol
+        .grant(&owner, &consumer, vec![DataType::LocationHistory], None)
+        .await;
+
+    let audit_entry_count = protocol.harness.audit_entry_count(&owner.identity()).await;
+    let instruction = instructions::validate_access(
+        &consumer.pubkey(),
+        &owner.identity_id,
+        DataType::LocationHistory,
+        0,
+        false,
+        audit_entry_count,
+    );
+    assert_within_budget(&mut protocol, "validate_access", instruction, &[&consumer.wallet]).await;
+}
+
+#[tokio::test]
+async fn full_validate_access_batch_within_budget() {
+    let mut protocol = Protocol::start().await;
+    let (owner, consumer) = participants(&mut protocol).await;
+    protocol
+        .grant(&owner, &consumer, vec![DataType::LocationHistory], None)
+        .await;
+
+    let checks: Vec<AccessCheck> = (0..AccessPermission::MAX_BATCH_CHECKS)
+        .map(|_| AccessCheck {
+            identity_id: owner.identity_id.clone(),
+            consumer: consumer.pubkey(),
+            data_type: DataType::LocationHistory,
+        })
+        .collect();
+    let instruction = instructions::validate_access_batch(&checks, &[]);
+    assert_within_budget(&mut protocol, "validate_access_batch", instruction, &[]).await;
+}
+
+#[tokio::test]
+async fn purchase_data_within_budget() {
+    let mut protocol = Protocol::start().await;
+    let (seller, buyer) = participants(&mut protocol).await;
+    let price = 1_000_000;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, price, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, price).await;
+    let mint = protocol.mint;
+    protocol.harness.token_account(&seller.pubkey(), &mint).await;
+
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let instruction = instructions::purchase_data(PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet: seller.pubkey(),
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: None,
+        max_staleness: None,
+        day: marketplace::PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    });
+    assert_within_budget(&mut protocol, "purchase_data", instruction, &[&buyer.wallet]).await;
+}
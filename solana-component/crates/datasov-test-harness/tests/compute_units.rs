@@ -0,0 +1,58 @@
+//! Compute units of the identity instructions keyed by an identity ID seed.
+//!
+//! Run with `SBF_OUT_DIR=target/deploy` for the units of the built programs; under the native
+//! processors only the behaviour is checked.
+
+use datasov_client::identity::{self, IdentityAccount, VerificationLevel};
+use datasov_client::{compute, instructions, pda, storage};
+use datasov_test_harness::Protocol;
+
+/// Longest identity ID the identity program accepts
+const MAX_IDENTITY_ID_LEN: usize = 64;
+
+/// Raw ID seeds capped identity IDs at 32 bytes; hashed seeds take IDs of any allowed length
+#[tokio::test]
+async fn full_length_identity_id_registers() {
+    let mut protocol = Protocol::start().await;
+    let identity_id = "i".repeat(MAX_IDENTITY_ID_LEN);
+
+    let participant = protocol
+        .verified_participant(&identity_id, VerificationLevel::Basic)
+        .await;
+
+    let identity: IdentityAccount = protocol.harness.fetch(&pda::identity::identity(&identity_id)).await;
+    assert_eq!(identity.identity_id, identity_id);
+    assert_eq!(identity.owner, participant.pubkey());
+}
+
+/// Hashing keeps the seed 32 bytes, so validating an identity PDA costs the same for any ID length
+#[tokio::test]
+async fn identity_units_do_not_grow_with_id_length() {
+    let mut protocol = Protocol::start().await;
+    let mut units = Vec::new();
+
+    for identity_id in ["i".to_string(), "i".repeat(MAX_IDENTITY_ID_LEN)] {
+        let participant = protocol.register_identity(&identity_id).await;
+        protocol.verify(&participant, VerificationLevel::Basic).await;
+
+        let instruction = instructions::identity(
+            identity::accounts::UpdateIdentity {
+                identity: participant.identity(),
+                owner: participant.pubkey(),
+                oracle_registry: pda::identity::oracle_registry(),
+            },
+            identity::instruction::UpdateIdentity {
+                new_storage_pointer: storage::placeholder("update"),
+            },
+        );
+        let consumed = protocol
+            .harness
+            .units_consumed(std::slice::from_ref(&instruction), &[&participant.wallet])
+            .await;
+        assert!(consumed <= compute::instruction_units(&instruction) as u64);
+        units.push(consumed);
+    }
+
+    // Only copying and logging the longer ID costs more
+    assert!(units[1].abs_diff(units[0]) < 1_000, "units by ID length: {:?}", units);
+}
@@ -0,0 +1,605 @@
+//! Confidential purchases: a private listing is bought with Token-2022 confidential transfers. Proofs
+//! verified into context state accounts show that the payment and the fee add up to the listing's
+//! price commitment and that the fee is the marketplace's cut, without revealing either amount. The
+//! marketplace then applies and withdraws the fees it received.
+
+use curve25519_dalek::scalar::Scalar;
+use datasov_client::identity::{self, DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, ErrorCode, PurchaseReceipt};
+use datasov_client::{compute, instructions, pda};
+use datasov_test_harness::{assert_program_error, Harness, Participant, Protocol, ProtocolConfig};
+use solana_program_test::processor;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::{system_instruction, system_program};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::confidential_transfer::{self, ConfidentialTransferAccount};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensionsOwned};
+use spl_token_2022::proof::ProofLocation;
+use spl_token_2022::solana_zk_token_sdk::encryption::auth_encryption::AeKey;
+use spl_token_2022::solana_zk_token_sdk::encryption::elgamal::{ElGamalCiphertext, ElGamalKeypair, ElGamalPubkey};
+use spl_token_2022::solana_zk_token_sdk::encryption::pedersen::{Pedersen, PedersenCommitment, PedersenOpening};
+use spl_token_2022::solana_zk_token_sdk::instruction::transfer::TransferAmountCiphertext;
+use spl_token_2022::solana_zk_token_sdk::instruction::{
+    BatchedRangeProofU64Data, CiphertextCommitmentEqualityProofData, PubkeyValidityData, TransferData, WithdrawData,
+    ZkProofData,
+};
+use spl_token_2022::solana_zk_token_sdk::zk_token_elgamal::pod;
+use spl_token_2022::solana_zk_token_sdk::zk_token_proof_instruction::{ContextStateInfo, ProofInstruction};
+use spl_token_2022::solana_zk_token_sdk::zk_token_proof_program;
+use spl_token_2022::solana_zk_token_sdk::zk_token_proof_state::ProofContextState;
+use spl_token_2022::state::{Account, Mint};
+
+const PRICE: u64 = 12_345;
+const SALT: [u8; 32] = [7; 32];
+const DECIMALS: u8 = 6;
+/// What the buyer deposits into their confidential balance
+const BALANCE: u64 = 50_000;
+
+/// A Token-2022 account configured for confidential transfers, with the keys to its balances
+struct ConfidentialAccount {
+    address: Pubkey,
+    elgamal: ElGamalKeypair,
+    aes: AeKey,
+}
+
+/// A private listing of `seller`'s, a buyer allowed and granted access to it, and confidential
+/// accounts for both and for the marketplace's fees
+struct Sale {
+    protocol: Protocol,
+    mint: Pubkey,
+    seller: Participant,
+    buyer: Participant,
+    listing_id: u64,
+    buyer_account: ConfidentialAccount,
+    seller_account: ConfidentialAccount,
+    fees: ConfidentialAccount,
+}
+
+/// Context state accounts proving a confidential payment
+struct PaymentProofs {
+    owner_transfer: Pubkey,
+    fee_transfer: Pubkey,
+    price_equality: Pubkey,
+    fee_equality: Pubkey,
+    fee_range: Pubkey,
+    /// Buyer's available balance after each transfer, encrypted under their AES key
+    owner_new_decryptable_balance: [u8; 36],
+    fee_new_decryptable_balance: [u8; 36],
+}
+
+fn price_opening() -> PedersenOpening {
+    PedersenOpening::from_bytes(&SALT).expect("canonical salt")
+}
+
+/// Verify `proof` into a new context state account owned by the harness payer
+async fn verify_proof<T, U>(harness: &mut Harness, instruction: ProofInstruction, proof: &T) -> Pubkey
+where
+    T: bytemuck::Pod + ZkProofData<U>,
+    U: bytemuck::Pod,
+{
+    let context_state = Keypair::new();
+    let payer = harness.payer().pubkey();
+    let space = std::mem::size_of::<ProofContextState<U>>();
+    let rent = harness.context.banks_client.get_rent().await.expect("get_rent");
+    let create = system_instruction::create_account(
+        &payer,
+        &context_state.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        &zk_token_proof_program::id(),
+    );
+    let context_state_info = ContextStateInfo {
+        context_state_account: &context_state.pubkey(),
+        context_state_authority: &payer,
+    };
+    let verify = instruction.encode_verify_proof(Some(context_state_info), proof);
+    harness.execute(&[create, verify], &[&context_state]).await;
+    context_state.pubkey()
+}
+
+/// New mint with the confidential transfer extension, auto-approving accounts and with no auditor
+async fn create_confidential_mint(harness: &mut Harness) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = harness.payer().pubkey();
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::ConfidentialTransferMint])
+        .expect("mint length");
+    let rent = harness.context.banks_client.get_rent().await.expect("get_rent");
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        confidential_transfer::instruction::initialize_mint(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            Some(payer),
+            true,
+            None,
+        )
+        .expect("initialize_mint"),
+        spl_token_2022::instruction::initialize_mint2(&spl_token_2022::ID, &mint.pubkey(), &payer, None, DECIMALS)
+            .expect("initialize_mint2"),
+    ];
+    harness.execute(&instructions, &[&mint]).await;
+    mint.pubkey()
+}
+
+/// `owner`'s associated token account for `mint`, configured for confidential transfers
+async fn confidential_account(harness: &mut Harness, owner: &Keypair, mint: &Pubkey) -> ConfidentialAccount {
+    let payer = harness.payer().pubkey();
+    let address = get_associated_token_address_with_program_id(&owner.pubkey(), mint, &spl_token_2022::ID);
+    let elgamal = ElGamalKeypair::new_rand();
+    let aes = AeKey::new_rand();
+    let pubkey_validity = PubkeyValidityData::new(&elgamal).expect("pubkey validity proof");
+    let mut instructions = vec![
+        create_associated_token_account_idempotent(&payer, &owner.pubkey(), mint, &spl_token_2022::ID),
+        spl_token_2022::instruction::reallocate(
+            &spl_token_2022::ID,
+            &address,
+            &payer,
+            &owner.pubkey(),
+            &[],
+            &[ExtensionType::ConfidentialTransferAccount],
+        )
+        .expect("reallocate"),
+    ];
+    instructions.extend(
+        confidential_transfer::instruction::configure_account(
+            &spl_token_2022::ID,
+            &address,
+            mint,
+            aes.encrypt(0),
+            65_536,
+            &owner.pubkey(),
+            &[],
+            ProofLocation::InstructionOffset(1.try_into().unwrap(), &pubkey_validity),
+        )
+        .expect("configure_account"),
+    );
+    harness.execute(&instructions, &[owner]).await;
+    ConfidentialAccount { address, elgamal, aes }
+}
+
+/// The marketplace's fee account for `mint`, configured through `configure_confidential_fees`
+async fn confidential_fee_account(protocol: &mut Protocol, mint: &Pubkey) -> ConfidentialAccount {
+    let marketplace = pda::marketplace::marketplace();
+    let payer = protocol.harness.payer().pubkey();
+    let address = get_associated_token_address_with_program_id(&marketplace, mint, &spl_token_2022::ID);
+    let create = create_associated_token_account_idempotent(&payer, &marketplace, mint, &spl_token_2022::ID);
+    protocol.harness.execute(&[create], &[]).await;
+
+    let elgamal = ElGamalKeypair::new_rand();
+    let aes = AeKey::new_rand();
+    let pubkey_validity = PubkeyValidityData::new(&elgamal).expect("pubkey validity proof");
+    let pubkey_validity_proof = verify_proof(
+        &mut protocol.harness,
+        ProofInstruction::VerifyPubkeyValidity,
+        &pubkey_validity,
+    )
+    .await;
+    let configure = instructions::marketplace(
+        marketplace::accounts::ConfigureConfidentialFees {
+            marketplace,
+            authority: protocol.authority.pubkey(),
+            mint: *mint,
+            marketplace_token_account: address,
+            pubkey_validity_proof,
+            token_program: spl_token_2022::ID,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::ConfigureConfidentialFees {
+            decryptable_zero_balance: aes.encrypt(0).to_bytes(),
+            maximum_pending_balance_credit_counter: 65_536,
+        },
+    );
+    protocol.harness.execute(&[configure], &[&protocol.authority]).await;
+    ConfidentialAccount { address, elgamal, aes }
+}
+
+async fn confidential_state(harness: &mut Harness, address: &Pubkey) -> ConfidentialTransferAccount {
+    let account = harness
+        .context
+        .banks_client
+        .get_account(*address)
+        .await
+        .expect("get_account")
+        .expect("token account");
+    let state = StateWithExtensionsOwned::<Account>::unpack(account.data).expect("token account");
+    *state
+        .get_extension::<ConfidentialTransferAccount>()
+        .expect("confidential transfer account")
+}
+
+async fn available_balance(harness: &mut Harness, address: &Pubkey) -> ElGamalCiphertext {
+    let state = confidential_state(harness, address).await;
+    state.available_balance.try_into().expect("available balance")
+}
+
+/// Pending balance of an account that has received less than 2^16 since it was last applied
+async fn pending_balance(harness: &mut Harness, account: &ConfidentialAccount) -> u64 {
+    let state = confidential_state(harness, &account.address).await;
+    let pending: ElGamalCiphertext = state.pending_balance_lo.try_into().expect("pending balance");
+    pending.decrypt_u32(account.elgamal.secret()).expect("pending balance")
+}
+
+async fn token_amount(harness: &mut Harness, address: &Pubkey) -> u64 {
+    let account = harness
+        .context
+        .banks_client
+        .get_account(*address)
+        .await
+        .expect("get_account")
+        .expect("token account");
+    StateWithExtensionsOwned::<Account>::unpack(account.data)
+        .expect("token account")
+        .base
+        .amount
+}
+
+/// A transfer's whole amount encrypted under the source key, as the marketplace recombines it
+fn transfer_source_ciphertext(transfer: &TransferData) -> ElGamalCiphertext {
+    let source = |ciphertext: pod::TransferAmountCiphertext| {
+        let ciphertext: TransferAmountCiphertext = TryFrom::try_from(ciphertext).expect("transfer ciphertext");
+        ElGamalCiphertext {
+            commitment: *ciphertext.get_commitment(),
+            handle: *ciphertext.get_source_handle(),
+        }
+    };
+    let lo = source(transfer.context.ciphertext_lo);
+    let hi = source(transfer.context.ciphertext_hi);
+    lo + Scalar::from(1u64 << 16) * hi
+}
+
+async fn setup() -> Sale {
+    // Token-2022 runs natively, also alongside built programs: the build solana-program-test
+    // bundles leaves out the confidential transfer instructions that move balances
+    let mut program_test = Harness::program_test();
+    program_test.prefer_bpf(false);
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::ID,
+        processor!(spl_token_2022::processor::Processor::process),
+    );
+    let harness = Harness::with_program_test(program_test).await;
+    let mut protocol = Protocol::bootstrap(harness, ProtocolConfig::default()).await;
+    let mint = create_confidential_mint(&mut protocol.harness).await;
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+
+    let listing_id = protocol.unique_listing_id();
+    let listing = pda::marketplace::listing(listing_id);
+    let price_commitment = DataListing::price_commitment(PRICE, &SALT).expect("price commitment");
+    let create = instructions::marketplace(
+        marketplace::accounts::CreatePrivateListing {
+            listing,
+            allowlist: pda::marketplace::allowlist(&listing),
+            marketplace: pda::marketplace::marketplace(),
+            seller_identity: seller.identity(),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            owner: seller.pubkey(),
+            guardian: None,
+            identity_program: identity::ID,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::CreatePrivateListing {
+            listing_id,
+            price_commitment,
+            description_hash: [5; 32],
+            data_type: ListingDataType::LocationHistory,
+            identity_id: seller.identity_id.clone(),
+            allowed_buyers: vec![buyer.pubkey()],
+        },
+    );
+    protocol.harness.execute(&[create], &[&seller.wallet]).await;
+
+    let buyer_account = confidential_account(&mut protocol.harness, &buyer.wallet, &mint).await;
+    let seller_account = confidential_account(&mut protocol.harness, &seller.wallet, &mint).await;
+    let fees = confidential_fee_account(&mut protocol, &mint).await;
+
+    // Move the buyer's tokens into their confidential balance
+    let payer = protocol.harness.payer().pubkey();
+    let fund = [
+        spl_token_2022::instruction::mint_to(&spl_token_2022::ID, &mint, &buyer_account.address, &payer, &[], BALANCE)
+            .expect("mint_to"),
+        confidential_transfer::instruction::deposit(
+            &spl_token_2022::ID,
+            &buyer_account.address,
+            &mint,
+            BALANCE,
+            DECIMALS,
+            &buyer.pubkey(),
+            &[],
+        )
+        .expect("deposit"),
+        confidential_transfer::instruction::apply_pending_balance(
+            &spl_token_2022::ID,
+            &buyer_account.address,
+            1,
+            buyer_account.aes.encrypt(BALANCE),
+            &buyer.pubkey(),
+            &[],
+        )
+        .expect("apply_pending_balance"),
+    ];
+    protocol.harness.execute(&fund, &[&buyer.wallet]).await;
+
+    Sale {
+        protocol,
+        mint,
+        seller,
+        buyer,
+        listing_id,
+        buyer_account,
+        seller_account,
+        fees,
+    }
+}
+
+impl Sale {
+    /// The marketplace's cut of the price, rounded down
+    fn fee(&self) -> u64 {
+        PRICE * self.protocol.config.fee_basis_points as u64 / 10_000
+    }
+
+    /// Prove a payment of `owner_amount` to the seller and the fee to the marketplace, against a
+    /// commitment to `committed_price` under the listing's salt
+    async fn prove_payment(&mut self, owner_amount: u64, committed_price: u64) -> PaymentProofs {
+        let fee = self.fee();
+        let fee_basis_points = Scalar::from(self.protocol.config.fee_basis_points as u64);
+        let harness = &mut self.protocol.harness;
+        let buyer = &self.buyer_account.elgamal;
+        let auditor = ElGamalPubkey::default();
+
+        let available = available_balance(harness, &self.buyer_account.address).await;
+        let owner_transfer = TransferData::new(
+            owner_amount,
+            (BALANCE, &available),
+            buyer,
+            (self.seller_account.elgamal.pubkey(), &auditor),
+        )
+        .expect("owner transfer proof");
+        let after_owner: ElGamalCiphertext = owner_transfer
+            .context
+            .new_source_ciphertext
+            .try_into()
+            .expect("new source ciphertext");
+        let fee_transfer = TransferData::new(
+            fee,
+            (BALANCE - owner_amount, &after_owner),
+            buyer,
+            (self.fees.elgamal.pubkey(), &auditor),
+        )
+        .expect("fee transfer proof");
+        let owner_paid = transfer_source_ciphertext(&owner_transfer);
+        let fee_paid = transfer_source_ciphertext(&fee_transfer);
+
+        let commitment = Pedersen::with(committed_price, &price_opening());
+        let price_equality = CiphertextCommitmentEqualityProofData::new(
+            buyer,
+            &(owner_paid + fee_paid),
+            &commitment,
+            &price_opening(),
+            committed_price,
+        )
+        .expect("price equality proof");
+
+        let (fee_commitment, fee_opening) = Pedersen::new(fee);
+        let fee_equality =
+            CiphertextCommitmentEqualityProofData::new(buyer, &fee_paid, &fee_commitment, &fee_opening, fee)
+                .expect("fee equality proof");
+
+        // remainder = bps * price - 10_000 * fee and remainder + 2^14 - 10_000 both fit 14 bits
+        // only if the remainder is below 10_000, i.e. the fee is the rounded-down cut
+        let listing_commitment = Pedersen::with(PRICE, &price_opening());
+        let ten_thousand = Scalar::from(10_000u64);
+        let remainder_value = PRICE * self.protocol.config.fee_basis_points as u64 - 10_000 * fee;
+        let remainder: PedersenCommitment = fee_basis_points * listing_commitment - ten_thousand * fee_commitment;
+        let remainder_opening = price_opening() * fee_basis_points - &fee_opening * ten_thousand;
+        let headroom = remainder + Pedersen::encode((1u64 << 14) - 10_000);
+        let (padding, padding_opening) = Pedersen::new(0u64);
+        let fee_range = BatchedRangeProofU64Data::new(
+            vec![&remainder, &headroom, &padding],
+            vec![remainder_value, remainder_value + (1 << 14) - 10_000, 0],
+            vec![14, 14, 36],
+            vec![&remainder_opening, &remainder_opening, &padding_opening],
+        )
+        .expect("fee range proof");
+
+        PaymentProofs {
+            owner_transfer: verify_proof(harness, ProofInstruction::VerifyTransfer, &owner_transfer).await,
+            fee_transfer: verify_proof(harness, ProofInstruction::VerifyTransfer, &fee_transfer).await,
+            price_equality: verify_proof(
+                harness,
+                ProofInstruction::VerifyCiphertextCommitmentEquality,
+                &price_equality,
+            )
+            .await,
+            fee_equality: verify_proof(
+                harness,
+                ProofInstruction::VerifyCiphertextCommitmentEquality,
+                &fee_equality,
+            )
+            .await,
+            fee_range: verify_proof(harness, ProofInstruction::VerifyBatchedRangeProofU64, &fee_range).await,
+            owner_new_decryptable_balance: self.buyer_account.aes.encrypt(BALANCE - owner_amount).to_bytes(),
+            fee_new_decryptable_balance: self.buyer_account.aes.encrypt(BALANCE - owner_amount - fee).to_bytes(),
+        }
+    }
+
+    async fn purchase(&mut self, proofs: &PaymentProofs) -> Vec<Instruction> {
+        let listing = pda::marketplace::listing(self.listing_id);
+        let seller_identity = self.seller.identity();
+        let audit_entry_count = self.protocol.harness.audit_entry_count(&seller_identity).await;
+        let listing_account: DataListing = self.protocol.harness.fetch(&listing).await;
+        let purchase = instructions::marketplace(
+            marketplace::accounts::PurchaseDataConfidential {
+                listing,
+                marketplace: pda::marketplace::marketplace(),
+                seller_identity,
+                buyer_identity: self.buyer.identity(),
+                buyer_permission: pda::identity::permission(&seller_identity, &self.buyer.pubkey()),
+                compliance_matrix: pda::identity::compliance_matrix(),
+                blacklist: pda::identity::blacklist(),
+                buyer_profile: pda::identity::consumer_profile(&self.buyer.pubkey()),
+                buyer_stake: None,
+                allowlist: listing_account
+                    .is_private()
+                    .then(|| pda::marketplace::allowlist(&listing)),
+                buyer: self.buyer.pubkey(),
+                mint: self.mint,
+                buyer_token_account: self.buyer_account.address,
+                owner_token_account: self.seller_account.address,
+                marketplace_token_account: self.fees.address,
+                owner_transfer_proof: proofs.owner_transfer,
+                fee_transfer_proof: Some(proofs.fee_transfer),
+                price_equality_proof: proofs.price_equality,
+                fee_equality_proof: Some(proofs.fee_equality),
+                fee_range_proof: Some(proofs.fee_range),
+                receipt: pda::marketplace::receipt(&listing),
+                identity_program: identity::ID,
+                token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+                audit: instructions::marketplace_audit_accounts(&seller_identity, audit_entry_count),
+            },
+            marketplace::instruction::PurchaseDataConfidential {
+                listing_id: self.listing_id,
+                purpose: Purpose::Research,
+                owner_new_decryptable_balance: proofs.owner_new_decryptable_balance,
+                fee_new_decryptable_balance: Some(proofs.fee_new_decryptable_balance),
+                max_staleness: None,
+            },
+        );
+        let mut instructions = compute::budget_instructions(compute::instruction_units(&purchase), 0);
+        instructions.push(purchase);
+        instructions
+    }
+}
+
+#[tokio::test]
+async fn confidential_purchases_pay_the_committed_price_and_fee() {
+    let mut sale = setup().await;
+    let fee = sale.fee();
+    let proofs = sale.prove_payment(PRICE - fee, PRICE).await;
+    let purchase = sale.purchase(&proofs).await;
+    sale.protocol.harness.execute(&purchase, &[&sale.buyer.wallet]).await;
+
+    let harness = &mut sale.protocol.harness;
+    assert_eq!(pending_balance(harness, &sale.seller_account).await, PRICE - fee);
+    assert_eq!(pending_balance(harness, &sale.fees).await, fee);
+    let receipt: PurchaseReceipt = harness
+        .fetch(&pda::marketplace::receipt(&pda::marketplace::listing(sale.listing_id)))
+        .await;
+    assert!(receipt.is_confidential);
+    assert_eq!(receipt.amount, 0);
+    assert_eq!(
+        receipt.amount_commitment,
+        DataListing::price_commitment(PRICE, &SALT).unwrap()
+    );
+
+    // The marketplace credits the fee to its available balance and withdraws it in the clear
+    let marketplace = pda::marketplace::marketplace();
+    let authority = sale.protocol.authority.pubkey();
+    let apply = instructions::marketplace(
+        marketplace::accounts::ApplyConfidentialFees {
+            marketplace,
+            authority,
+            mint: sale.mint,
+            marketplace_token_account: sale.fees.address,
+            token_program: spl_token_2022::ID,
+        },
+        marketplace::instruction::ApplyConfidentialFees {
+            expected_pending_balance_credit_counter: 1,
+            new_decryptable_available_balance: sale.fees.aes.encrypt(fee).to_bytes(),
+        },
+    );
+    harness.execute(&[apply], &[&sale.protocol.authority]).await;
+
+    let available = available_balance(harness, &sale.fees.address).await;
+    let withdraw = WithdrawData::new(fee, &sale.fees.elgamal, fee, &available).expect("withdraw proof");
+    let withdraw_proof = verify_proof(harness, ProofInstruction::VerifyWithdraw, &withdraw).await;
+    let authority_token_account =
+        get_associated_token_address_with_program_id(&authority, &sale.mint, &spl_token_2022::ID);
+    let payer = harness.payer().pubkey();
+    let create = create_associated_token_account_idempotent(&payer, &authority, &sale.mint, &spl_token_2022::ID);
+    let withdraw = instructions::marketplace(
+        marketplace::accounts::WithdrawConfidentialFees {
+            marketplace,
+            authority,
+            mint: sale.mint,
+            marketplace_token_account: sale.fees.address,
+            authority_token_account,
+            withdraw_proof,
+            token_program: spl_token_2022::ID,
+        },
+        marketplace::instruction::WithdrawConfidentialFees {
+            amount: fee,
+            new_decryptable_available_balance: sale.fees.aes.encrypt(0).to_bytes(),
+        },
+    );
+    harness.execute(&[create, withdraw], &[&sale.protocol.authority]).await;
+    assert_eq!(token_amount(harness, &authority_token_account).await, fee);
+}
+
+#[tokio::test]
+async fn underpaying_the_committed_price_is_rejected() {
+    let mut sale = setup().await;
+    let fee = sale.fee();
+
+    // Every proof verifies, but the equality is against a commitment to a lower price
+    let proofs = sale.prove_payment(PRICE - fee - 1, PRICE - 1).await;
+    let purchase = sale.purchase(&proofs).await;
+    let result = sale.protocol.harness.process(&purchase, &[&sale.buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::ConfidentialAmountMismatch);
+}
+
+#[tokio::test]
+async fn payment_proofs_must_be_verified_proof_contexts() {
+    let mut sale = setup().await;
+    let fee = sale.fee();
+    let mut proofs = sale.prove_payment(PRICE - fee, PRICE).await;
+
+    // A context of the wrong proof type
+    proofs.price_equality = proofs.fee_range;
+    let purchase = sale.purchase(&proofs).await;
+    let result = sale.protocol.harness.process(&purchase, &[&sale.buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::InvalidProofContext);
+
+    // An account the proof program does not own
+    proofs.price_equality = sale.buyer.pubkey();
+    sale.protocol.harness.refresh_blockhash().await;
+    let purchase = sale.purchase(&proofs).await;
+    let result = sale.protocol.harness.process(&purchase, &[&sale.buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::InvalidProofContext);
+}
+
+#[tokio::test]
+async fn public_listings_cannot_be_bought_confidentially() {
+    let mut sale = setup().await;
+    let fee = sale.fee();
+    let proofs = sale.prove_payment(PRICE - fee, PRICE).await;
+    sale.listing_id = sale
+        .protocol
+        .create_listing(&sale.seller, PRICE, ListingDataType::LocationHistory)
+        .await;
+
+    let purchase = sale.purchase(&proofs).await;
+    let result = sale.protocol.harness.process(&purchase, &[&sale.buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::ConfidentialPublicListing);
+}
@@ -0,0 +1,181 @@
+//! Featured-listing curation: stakers vote listings into each epoch's featured set with their
+//! staking weight, bonding protocol tokens that they lose if the listing is later flagged as
+//! disputed.
+
+use datasov_client::admin;
+use datasov_client::identity::VerificationLevel;
+use datasov_client::instructions;
+use datasov_client::marketplace::{DataType as ListingDataType, ErrorCode, FeaturedSet, ListingTally};
+use datasov_client::pda;
+use datasov_client::staking::{self, StakeRole};
+use datasov_test_harness::{assert_program_error, Protocol};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const EPOCH: i64 = 86_400;
+const BOND: u64 = 100;
+const DISPUTE_WINDOW: i64 = 7 * 86_400;
+
+/// Staking and curation in one protocol token; returns its mint
+async fn initialize_curation(protocol: &mut Protocol) -> Pubkey {
+    let mint = protocol.harness.create_mint(6).await;
+    let authority = protocol.authority.pubkey();
+    let initialize_staking = instructions::staking(
+        staking::accounts::InitializeStaking {
+            config: pda::staking::config(),
+            mint,
+            vault: pda::staking::vault(),
+            authority,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::InitializeStaking {
+            unstake_cooldown: 0,
+            consumer_slash_bps: 0,
+            juror_slash_bps: 0,
+            vote_authority: pda::governance::governance(),
+        },
+    );
+    let initialize_curation = admin::initialize_curation(&authority, &mint, EPOCH, BOND, DISPUTE_WINDOW);
+    protocol
+        .harness
+        .execute(&[initialize_staking, initialize_curation], &[&protocol.authority])
+        .await;
+    mint
+}
+
+/// A wallet with `weight` protocol tokens staked and two bonds to spare
+async fn curator(protocol: &mut Protocol, mint: &Pubkey, weight: u64) -> Keypair {
+    let curator = protocol.harness.wallet().await;
+    protocol
+        .harness
+        .mint_to(mint, &curator.pubkey(), weight + 2 * BOND)
+        .await;
+    let stake = instructions::stake(&curator.pubkey(), mint, StakeRole::Consumer, &curator.pubkey(), weight);
+    protocol.harness.execute(&[stake], &[&curator]).await;
+    curator
+}
+
+/// Two active listings from a verified seller
+async fn listings(protocol: &mut Protocol) -> [u64; 2] {
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    [
+        protocol
+            .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+            .await,
+        protocol
+            .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+            .await,
+    ]
+}
+
+#[tokio::test]
+async fn featured_set_ranks_listings_by_staked_votes() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_curation(&mut protocol).await;
+    let [first, second] = listings(&mut protocol).await;
+    let large = curator(&mut protocol, &mint, 3_000).await;
+    let small = curator(&mut protocol, &mint, 1_000).await;
+
+    let vote = instructions::vote_featured(&large.pubkey(), &mint, first, 0);
+    protocol.harness.execute(&[vote], &[&large]).await;
+    for listing_id in [first, second] {
+        let vote = instructions::vote_featured(&small.pubkey(), &mint, listing_id, 0);
+        protocol.harness.execute(&[vote], &[&small]).await;
+    }
+
+    let featured_set: FeaturedSet = protocol.harness.fetch(&pda::marketplace::featured_set(0)).await;
+    let ranked: Vec<_> = featured_set
+        .listings
+        .iter()
+        .map(|featured| (featured.listing, featured.votes))
+        .collect();
+    assert_eq!(
+        ranked,
+        [
+            (pda::marketplace::listing(first), 4_000),
+            (pda::marketplace::listing(second), 1_000),
+        ]
+    );
+    let curator_token_account = get_associated_token_address(&small.pubkey(), &mint);
+    assert_eq!(protocol.harness.token_balance(&curator_token_account).await, 0);
+    assert_eq!(
+        protocol
+            .harness
+            .token_balance(&pda::marketplace::curation_vault())
+            .await,
+        3 * BOND
+    );
+
+    // Votes count toward the running epoch only
+    protocol.harness.advance_clock(EPOCH).await;
+    let vote = instructions::vote_featured(&large.pubkey(), &mint, second, 0);
+    let result = protocol.harness.process(&[vote], &[&large]).await;
+    assert_program_error(result, ErrorCode::CurationEpochNotCurrent);
+}
+
+#[tokio::test]
+async fn curators_of_a_disputed_listing_forfeit_their_bonds() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_curation(&mut protocol).await;
+    let [disputed, clean] = listings(&mut protocol).await;
+    let careless = curator(&mut protocol, &mint, 2_000).await;
+    let careful = curator(&mut protocol, &mint, 1_000).await;
+    let marketplace_token_account = protocol
+        .harness
+        .token_account(&pda::marketplace::marketplace(), &mint)
+        .await;
+
+    let vote = instructions::vote_featured(&careless.pubkey(), &mint, disputed, 0);
+    protocol.harness.execute(&[vote], &[&careless]).await;
+    let vote = instructions::vote_featured(&careful.pubkey(), &mint, clean, 0);
+    protocol.harness.execute(&[vote], &[&careful]).await;
+
+    let disputed_listing = pda::marketplace::listing(disputed);
+    let clean_listing = pda::marketplace::listing(clean);
+    let flag = admin::flag_curated_listing(&protocol.authority.pubkey(), &disputed_listing, 0);
+    protocol.harness.execute(&[flag], &[&protocol.authority]).await;
+    let featured_set: FeaturedSet = protocol.harness.fetch(&pda::marketplace::featured_set(0)).await;
+    assert!(!featured_set.contains(&disputed_listing));
+    assert!(featured_set.contains(&clean_listing));
+
+    // Bonds stay locked until the dispute window after the epoch closes
+    let settle = instructions::settle_curation_vote(&careful.pubkey(), &mint, &clean_listing, 0);
+    let result = protocol.harness.process(std::slice::from_ref(&settle), &[]).await;
+    assert_program_error(result, ErrorCode::CurationBondLocked);
+
+    protocol.harness.advance_clock(EPOCH + DISPUTE_WINDOW).await;
+    let forfeit = instructions::settle_curation_vote(&careless.pubkey(), &mint, &disputed_listing, 0);
+    protocol.harness.execute(&[settle, forfeit], &[]).await;
+    assert_eq!(
+        protocol
+            .harness
+            .token_balance(&get_associated_token_address(&careful.pubkey(), &mint))
+            .await,
+        2 * BOND
+    );
+    assert_eq!(
+        protocol
+            .harness
+            .token_balance(&get_associated_token_address(&careless.pubkey(), &mint))
+            .await,
+        BOND
+    );
+    assert_eq!(protocol.harness.token_balance(&marketplace_token_account).await, BOND);
+
+    // The flag stays on the tally, and no listing can be flagged once the dispute window closes
+    let tally: ListingTally = protocol
+        .harness
+        .fetch(&pda::marketplace::listing_tally(0, &disputed_listing))
+        .await;
+    assert!(tally.disputed);
+    let flag = admin::flag_curated_listing(&protocol.authority.pubkey(), &clean_listing, 0);
+    let result = protocol.harness.process(&[flag], &[&protocol.authority]).await;
+    assert_program_error(result, ErrorCode::CurationDisputeWindowClosed);
+}
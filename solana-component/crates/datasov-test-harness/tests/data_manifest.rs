@@ -0,0 +1,94 @@
+//! Chunked dataset manifests: a seller lists the Arweave transactions a large dataset is split
+//! across, with each chunk's size and hash, and seals the list so buyers can verify every chunk
+//! they fetch.
+
+use datasov_client::identity::VerificationLevel;
+use datasov_client::instructions;
+use datasov_client::marketplace::{self, DataManifest, DataType as ListingDataType, ErrorCode, ManifestChunk};
+use datasov_client::{pda, storage};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::signer::Signer;
+
+/// Manifest entry for `len` bytes of `index`, under a transaction ID of its own
+fn chunk(index: u8, len: usize) -> ManifestChunk {
+    let tx_id = storage::chunk_tx_id(&ManifestChunk {
+        tx_id: [index; 32],
+        size: 0,
+        hash: [0; 32],
+    });
+    storage::manifest_chunk(&tx_id, &vec![index; len]).unwrap()
+}
+
+async fn seller_listing(protocol: &mut Protocol) -> (Participant, u64) {
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    (seller, listing_id)
+}
+
+#[tokio::test]
+async fn sealed_manifest_commits_to_every_chunk() {
+    let mut protocol = Protocol::start().await;
+    let (seller, listing_id) = seller_listing(&mut protocol).await;
+    let chunks: Vec<_> = (0..30).map(|index| chunk(index, 1_000 + index as usize)).collect();
+
+    let instructions = instructions::data_manifest(&seller.pubkey(), listing_id, &chunks);
+    // 30 chunks take a create, two appends and the seal
+    assert_eq!(instructions.len(), 4);
+    for instruction in instructions {
+        protocol.harness.execute(&[instruction], &[&seller.wallet]).await;
+    }
+
+    let listing = pda::marketplace::listing(listing_id);
+    let manifest: DataManifest = protocol
+        .harness
+        .fetch(&pda::marketplace::data_manifest(&listing))
+        .await;
+    assert_eq!(manifest.listing, listing);
+    assert!(manifest.is_sealed);
+    assert_eq!(manifest.chunks, chunks);
+    assert_eq!(manifest.total_size, chunks.iter().map(|chunk| chunk.size).sum::<u64>());
+    assert_eq!(manifest.manifest_hash, manifest.compute_hash());
+    assert_eq!(storage::chunk_tx_id(&manifest.chunks[3]), storage::chunk_tx_id(&chunk(3, 1)));
+
+    // Sealed manifests no longer change
+    let append = instructions::marketplace(
+        marketplace::accounts::UpdateDataManifest {
+            listing,
+            manifest: pda::marketplace::data_manifest(&listing),
+            owner: seller.pubkey(),
+            marketplace: pda::marketplace::marketplace(),
+        },
+        marketplace::instruction::AppendManifestChunks {
+            chunks: vec![chunk(99, 10)],
+        },
+    );
+    let result = protocol.harness.process(&[append], &[&seller.wallet]).await;
+    assert_program_error(result, ErrorCode::ManifestSealed);
+}
+
+#[tokio::test]
+async fn manifests_reject_empty_chunks_and_other_sellers() {
+    let mut protocol = Protocol::start().await;
+    let (seller, listing_id) = seller_listing(&mut protocol).await;
+
+    let create = instructions::data_manifest(&seller.pubkey(), listing_id, &[chunk(1, 0)]).remove(0);
+    let result = protocol.harness.process(&[create], &[&seller.wallet]).await;
+    assert_program_error(result, ErrorCode::EmptyManifestChunk);
+
+    let other = protocol.harness.wallet().await;
+    let create = instructions::data_manifest(&other.pubkey(), listing_id, &[chunk(1, 10)]).remove(0);
+    let result = protocol.harness.process(&[create], &[&other]).await;
+    assert!(result.is_err());
+
+    // Nothing to commit to yet
+    let mut instructions = instructions::data_manifest(&seller.pubkey(), listing_id, &[]);
+    let seal = instructions.pop().unwrap();
+    protocol.harness.execute(&instructions, &[&seller.wallet]).await;
+    let result = protocol.harness.process(&[seal], &[&seller.wallet]).await;
+    assert_program_error(result, ErrorCode::EmptyManifest);
+}
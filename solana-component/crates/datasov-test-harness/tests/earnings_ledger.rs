@@ -0,0 +1,176 @@
+//! Earnings ledger: every settled sale credits the seller identity's lifetime earnings, net of the
+//! marketplace fee, by the data type sold and by the consumer who paid. Escrowed proceeds count
+//! as they are released to the seller.
+
+use datasov_client::identity::{self, DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::{self, DataType as ListingDataType, EarningsLedger, Marketplace, PurchaseReceipt};
+use datasov_client::{admin, instructions, pda};
+use datasov_test_harness::{Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+async fn sale(
+    protocol: &mut Protocol,
+    seller: &Participant,
+    buyer: &Participant,
+    price: u64,
+    data_type: ListingDataType,
+) {
+    let listing_id = protocol.create_listing(seller, price, data_type).await;
+    protocol.fund_tokens(buyer, price).await;
+    protocol.purchase(buyer, seller, listing_id, Purpose::Research).await;
+}
+
+fn release_proceeds(protocol: &Protocol, seller: &Participant, listing_id: u64, permission: &Pubkey) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    instructions::marketplace(
+        marketplace::accounts::ReleaseProceeds {
+            marketplace: pda::marketplace::marketplace(),
+            listing,
+            receipt: pda::marketplace::receipt(&listing),
+            permission: *permission,
+            escrow_token_account: pda::marketplace::escrow(&listing),
+            seller_token_account: get_associated_token_address(&seller.pubkey(), &protocol.mint),
+            seller_identity: seller.identity(),
+            earnings_ledger: pda::marketplace::earnings_ledger(&seller.identity()),
+            seller: seller.pubkey(),
+            identity_program: identity::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::ReleaseProceeds {
+            _listing_id: listing_id,
+        },
+    )
+}
+
+#[tokio::test]
+async fn sales_accumulate_by_data_type_and_consumer() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let mut buyers = Vec::new();
+    for name in ["alice", "bob"] {
+        let buyer_id = protocol.unique_identity_id(name);
+        let buyer = protocol
+            .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+            .await;
+        protocol
+            .grant(
+                &seller,
+                &buyer,
+                vec![DataType::LocationHistory, DataType::AppUsage],
+                None,
+            )
+            .await;
+        buyers.push(buyer);
+    }
+
+    sale(
+        &mut protocol,
+        &seller,
+        &buyers[0],
+        2_000,
+        ListingDataType::LocationHistory,
+    )
+    .await;
+    sale(&mut protocol, &seller, &buyers[0], 4_000, ListingDataType::AppUsage).await;
+    sale(
+        &mut protocol,
+        &seller,
+        &buyers[1],
+        10_000,
+        ListingDataType::LocationHistory,
+    )
+    .await;
+
+    let marketplace: Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let net = |price: u64| price - price * marketplace.fee_basis_points as u64 / 10_000;
+    let ledger: EarningsLedger = protocol
+        .harness
+        .fetch(&pda::marketplace::earnings_ledger(&seller.identity()))
+        .await;
+    assert_eq!(ledger.identity, seller.identity());
+    assert_eq!(ledger.payment_count, 3);
+    assert_eq!(ledger.total, net(2_000) + net(4_000) + net(10_000));
+    assert_eq!(
+        ledger.by_data_type[ListingDataType::LocationHistory.index()],
+        net(2_000) + net(10_000)
+    );
+    assert_eq!(ledger.by_data_type[ListingDataType::AppUsage.index()], net(4_000));
+
+    // Consumers are listed in order of their first payment
+    let by_consumer: Vec<_> = ledger
+        .by_consumer
+        .iter()
+        .map(|entry| (entry.consumer, entry.amount, entry.payment_count))
+        .collect();
+    assert_eq!(
+        by_consumer,
+        vec![
+            (buyers[0].pubkey(), net(2_000) + net(4_000), 2),
+            (buyers[1].pubkey(), net(10_000), 1),
+        ]
+    );
+    assert_eq!(ledger.other_consumers, 0);
+}
+
+#[tokio::test]
+async fn escrowed_proceeds_are_credited_as_they_are_released() {
+    let mut protocol = Protocol::start().await;
+    let window = admin::set_refund_window(&protocol.authority.pubkey(), 3600);
+    protocol.harness.execute(&[window], &[&protocol.authority]).await;
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    let permission = protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 10_000, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, 10_000).await;
+    protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+    protocol.harness.token_account(&seller.pubkey(), &protocol.mint).await;
+
+    // Nothing is earned while the proceeds sit in escrow, since the buyer may still be refunded
+    let ledger_address = pda::marketplace::earnings_ledger(&seller.identity());
+    let ledger: EarningsLedger = protocol.harness.fetch(&ledger_address).await;
+    assert_eq!((ledger.total, ledger.payment_count), (0, 0));
+
+    protocol.harness.advance_clock(1800).await;
+    let release = release_proceeds(&protocol, &seller, listing_id, &permission);
+    protocol.harness.execute(&[release], &[&seller.wallet]).await;
+    let receipt: PurchaseReceipt = protocol
+        .harness
+        .fetch(&pda::marketplace::receipt(&pda::marketplace::listing(listing_id)))
+        .await;
+    let ledger: EarningsLedger = protocol.harness.fetch(&ledger_address).await;
+    assert!(receipt.released_amount > 0 && receipt.released_amount < receipt.escrowed_amount);
+    assert_eq!(ledger.total, receipt.released_amount);
+    assert_eq!(
+        ledger.by_data_type[ListingDataType::LocationHistory.index()],
+        receipt.released_amount
+    );
+
+    protocol.harness.advance_clock(1800).await;
+    protocol.harness.refresh_blockhash().await;
+    let release = release_proceeds(&protocol, &seller, listing_id, &permission);
+    protocol.harness.execute(&[release], &[&seller.wallet]).await;
+    let ledger: EarningsLedger = protocol.harness.fetch(&ledger_address).await;
+    assert_eq!(ledger.total, receipt.escrowed_amount);
+    assert_eq!(ledger.payment_count, 2);
+    assert_eq!(ledger.by_consumer[0].consumer, buyer.pubkey());
+    assert_eq!(ledger.by_consumer[0].amount, receipt.escrowed_amount);
+}
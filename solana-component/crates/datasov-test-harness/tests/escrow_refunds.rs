@@ -0,0 +1,231 @@
+//! Escrow refunds: with a refund window set, purchase proceeds vest to the seller over the window,
+//! and revoking the buyer's access before they have fully vested refunds the rest to whoever paid.
+
+use datasov_client::identity::{self, DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::{self, DataType as ListingDataType, PurchaseReceipt};
+use datasov_client::{admin, instructions, pda};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const WINDOW: i64 = 3600;
+
+fn release_proceeds(protocol: &Protocol, seller: &Participant, listing_id: u64, permission: &Pubkey) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    instructions::marketplace(
+        marketplace::accounts::ReleaseProceeds {
+            marketplace: pda::marketplace::marketplace(),
+            listing,
+            receipt: pda::marketplace::receipt(&listing),
+            permission: *permission,
+            escrow_token_account: pda::marketplace::escrow(&listing),
+            seller_token_account: get_associated_token_address(&seller.pubkey(), &protocol.mint),
+            seller_identity: seller.identity(),
+            earnings_ledger: pda::marketplace::earnings_ledger(&seller.identity()),
+            seller: seller.pubkey(),
+            identity_program: identity::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::ReleaseProceeds {
+            _listing_id: listing_id,
+        },
+    )
+}
+
+fn claim_revocation_refund(
+    protocol: &Protocol,
+    buyer: &Participant,
+    listing_id: u64,
+    permission: &Pubkey,
+) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    instructions::marketplace(
+        marketplace::accounts::ClaimRevocationRefund {
+            marketplace: pda::marketplace::marketplace(),
+            listing,
+            receipt: pda::marketplace::receipt(&listing),
+            permission: *permission,
+            escrow_token_account: pda::marketplace::escrow(&listing),
+            payer_token_account: get_associated_token_address(&buyer.pubkey(), &protocol.mint),
+            claimant: buyer.pubkey(),
+            token_program: spl_token::ID,
+        },
+        marketplace::instruction::ClaimRevocationRefund {
+            _listing_id: listing_id,
+        },
+    )
+}
+
+/// A sale of `seller`'s location history to `buyer`, its proceeds escrowed over `WINDOW`
+struct EscrowedSale {
+    protocol: Protocol,
+    seller: Participant,
+    buyer: Participant,
+    permission: Pubkey,
+    listing_id: u64,
+    receipt: Pubkey,
+}
+
+impl EscrowedSale {
+    async fn start() -> Self {
+        let mut protocol = Protocol::start().await;
+        let window = admin::set_refund_window(&protocol.authority.pubkey(), WINDOW);
+        protocol.harness.execute(&[window], &[&protocol.authority]).await;
+
+        let seller_id = protocol.unique_identity_id("seller");
+        let seller = protocol
+            .verified_participant(&seller_id, VerificationLevel::Enhanced)
+            .await;
+        let buyer_id = protocol.unique_identity_id("buyer");
+        let buyer = protocol
+            .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+            .await;
+        let permission = protocol
+            .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+            .await;
+        let listing_id = protocol
+            .create_listing(&seller, 10_000, ListingDataType::LocationHistory)
+            .await;
+        protocol.fund_tokens(&buyer, 10_000).await;
+        let receipt = protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+        protocol.harness.token_account(&seller.pubkey(), &protocol.mint).await;
+        Self {
+            protocol,
+            seller,
+            buyer,
+            permission,
+            listing_id,
+            receipt,
+        }
+    }
+
+    fn release(&self) -> Instruction {
+        release_proceeds(&self.protocol, &self.seller, self.listing_id, &self.permission)
+    }
+
+    fn claim_refund(&self) -> Instruction {
+        claim_revocation_refund(&self.protocol, &self.buyer, self.listing_id, &self.permission)
+    }
+
+    async fn receipt(&mut self) -> PurchaseReceipt {
+        self.protocol.harness.fetch(&self.receipt).await
+    }
+
+    async fn balance(&mut self, owner: &Pubkey) -> u64 {
+        let token_account = get_associated_token_address(owner, &self.protocol.mint);
+        self.protocol.harness.token_balance(&token_account).await
+    }
+}
+
+#[tokio::test]
+async fn proceeds_vest_to_the_seller_over_the_refund_window() {
+    let mut sale = EscrowedSale::start().await;
+    let escrow = pda::marketplace::escrow(&pda::marketplace::listing(sale.listing_id));
+    let escrowed_amount = sale.receipt().await.escrowed_amount;
+    assert_eq!(sale.protocol.harness.token_balance(&escrow).await, escrowed_amount);
+
+    // Nothing has vested at the purchase, and the buyer has no refund while access stands
+    let release = sale.release();
+    let result = sale.protocol.harness.process(&[release], &[&sale.seller.wallet]).await;
+    assert_program_error(result, marketplace::ErrorCode::NothingToRelease);
+    let refund = sale.claim_refund();
+    let result = sale.protocol.harness.process(&[refund], &[&sale.buyer.wallet]).await;
+    assert_program_error(result, marketplace::ErrorCode::AccessNotRevoked);
+
+    sale.protocol.harness.advance_clock(WINDOW / 2).await;
+    sale.protocol.harness.refresh_blockhash().await;
+    let release = sale.release();
+    sale.protocol.harness.execute(&[release], &[&sale.seller.wallet]).await;
+    assert_eq!(sale.receipt().await.released_amount, escrowed_amount / 2);
+    let seller = sale.seller.pubkey();
+    assert_eq!(sale.balance(&seller).await, escrowed_amount / 2);
+
+    // Past the window the rest is released and the escrow is empty
+    sale.protocol.harness.advance_clock(WINDOW).await;
+    sale.protocol.harness.refresh_blockhash().await;
+    let release = sale.release();
+    sale.protocol.harness.execute(&[release], &[&sale.seller.wallet]).await;
+    assert_eq!(sale.receipt().await.released_amount, escrowed_amount);
+    assert_eq!(sale.balance(&seller).await, escrowed_amount);
+    assert_eq!(sale.protocol.harness.token_balance(&escrow).await, 0);
+}
+
+#[tokio::test]
+async fn revocation_refunds_the_unvested_proceeds() {
+    let mut sale = EscrowedSale::start().await;
+    let escrowed_amount = sale.receipt().await.escrowed_amount;
+    let vested = escrowed_amount * (WINDOW / 4) as u64 / WINDOW as u64;
+
+    sale.protocol.harness.advance_clock(WINDOW / 4).await;
+    sale.protocol.revoke(&sale.seller, &sale.buyer).await;
+    let buyer = sale.buyer.pubkey();
+    let buyer_balance = sale.balance(&buyer).await;
+    let refund = sale.claim_refund();
+    sale.protocol.harness.execute(&[refund], &[&sale.buyer.wallet]).await;
+    assert_eq!(sale.balance(&buyer).await, buyer_balance + escrowed_amount - vested);
+    assert_eq!(sale.receipt().await.refunded_amount, escrowed_amount - vested);
+
+    sale.protocol.harness.refresh_blockhash().await;
+    let refund = sale.claim_refund();
+    let result = sale.protocol.harness.process(&[refund], &[&sale.buyer.wallet]).await;
+    assert_program_error(result, marketplace::ErrorCode::RefundAlreadyClaimed);
+
+    // Long after the window, the seller still only gets what vested before the revocation
+    sale.protocol.harness.advance_clock(WINDOW).await;
+    let release = sale.release();
+    sale.protocol.harness.execute(&[release], &[&sale.seller.wallet]).await;
+    let seller = sale.seller.pubkey();
+    assert_eq!(sale.balance(&seller).await, vested);
+    assert_eq!(sale.receipt().await.released_amount, vested);
+}
+
+#[tokio::test]
+async fn released_proceeds_are_not_refunded_after_a_second_revocation() {
+    let EscrowedSale {
+        mut protocol,
+        seller,
+        buyer,
+        permission,
+        listing_id,
+        receipt: receipt_address,
+    } = EscrowedSale::start().await;
+
+    // A quarter of the way through the window, revoke and release what vested up to then
+    protocol.harness.advance_clock(WINDOW / 4).await;
+    protocol.revoke(&seller, &buyer).await;
+    let release = release_proceeds(&protocol, &seller, listing_id, &permission);
+    protocol.harness.execute(&[release], &[&seller.wallet]).await;
+    let receipt: PurchaseReceipt = protocol.harness.fetch(&receipt_address).await;
+    assert!(receipt.released_amount > 0 && receipt.released_amount < receipt.escrowed_amount);
+
+    // Re-granting and revoking again puts the revocation two epochs past the purchase, which
+    // moves the cutoff back to the purchase itself
+    protocol.harness.refresh_blockhash().await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol.harness.refresh_blockhash().await;
+    protocol.revoke(&seller, &buyer).await;
+
+    // Only what the seller has not already been paid comes back
+    let escrow = pda::marketplace::escrow(&pda::marketplace::listing(listing_id));
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &protocol.mint);
+    let buyer_balance = protocol.harness.token_balance(&buyer_token_account).await;
+    let refund = claim_revocation_refund(&protocol, &buyer, listing_id, &permission);
+    protocol.harness.execute(&[refund], &[&buyer.wallet]).await;
+    assert_eq!(
+        protocol.harness.token_balance(&buyer_token_account).await,
+        buyer_balance + receipt.escrowed_amount - receipt.released_amount
+    );
+    assert_eq!(protocol.harness.token_balance(&escrow).await, 0);
+
+    // And the seller cannot release past what the earlier release already took
+    protocol.harness.refresh_blockhash().await;
+    let release = release_proceeds(&protocol, &seller, listing_id, &permission);
+    let result = protocol.harness.process(&[release], &[&seller.wallet]).await;
+    assert_program_error(result, marketplace::ErrorCode::NothingToRelease);
+}
@@ -0,0 +1,50 @@
+//! Event sequence numbers: every event the identity and marketplace programs emit takes the next
+//! number from the sequence kept on the program's registry account, so an indexer that sees a
+//! number skip knows it missed an event.
+
+use datasov_client::identity::{DataType, KYCOracleRegistry, Purpose, VerificationLevel};
+use datasov_client::marketplace::{DataType as ListingDataType, Marketplace};
+use datasov_client::pda;
+use datasov_test_harness::Protocol;
+
+async fn identity_sequence(protocol: &mut Protocol) -> u64 {
+    let registry: KYCOracleRegistry = protocol.harness.fetch(&pda::identity::oracle_registry()).await;
+    registry.event_sequence
+}
+
+async fn marketplace_sequence(protocol: &mut Protocol) -> u64 {
+    let marketplace: Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    marketplace.event_sequence
+}
+
+#[tokio::test]
+async fn each_event_advances_its_program_sequence() {
+    let mut protocol = Protocol::start().await;
+    let start = identity_sequence(&mut protocol).await;
+
+    // Registration and verification each emit one identity event
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol.register_identity(&seller_id).await;
+    assert_eq!(identity_sequence(&mut protocol).await, start + 1);
+    protocol.verify(&seller, VerificationLevel::Enhanced).await;
+    assert_eq!(identity_sequence(&mut protocol).await, start + 2);
+
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    let before_grant = identity_sequence(&mut protocol).await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    assert_eq!(identity_sequence(&mut protocol).await, before_grant + 1);
+
+    // The marketplace numbers its own events independently
+    let before_purchase = marketplace_sequence(&mut protocol).await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+    assert_eq!(marketplace_sequence(&mut protocol).await, before_purchase + 1);
+}
@@ -0,0 +1,111 @@
+//! Feature flags: the marketplace authority switches flagged subsystems on and off per cluster,
+//! and a flagged subsystem refuses new work while its bit is clear.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::{self, DataType as ListingDataType, ErrorCode, FeatureFlags};
+use datasov_client::{admin, identity as identity_program, instructions, pda};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const PRICE: u64 = 1_000;
+
+fn create_compute_job(protocol: &Protocol, seller: &Participant, buyer: &Participant, listing_id: u64) -> Instruction {
+    let listing = pda::marketplace::listing(listing_id);
+    let compute_job = pda::marketplace::compute_job(&listing, &buyer.pubkey(), 1);
+    instructions::marketplace(
+        marketplace::accounts::CreateComputeJob {
+            compute_job,
+            listing,
+            marketplace: pda::marketplace::marketplace(),
+            feature_flags: pda::marketplace::feature_flags(),
+            seller_identity: seller.identity(),
+            buyer_identity: buyer.identity(),
+            buyer_permission: pda::identity::permission(&seller.identity(), &buyer.pubkey()),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            blacklist: pda::identity::blacklist(),
+            buyer_profile: pda::identity::consumer_profile(&buyer.pubkey()),
+            buyer_stake: None,
+            allowlist: None,
+            buyer: buyer.pubkey(),
+            buyer_token_account: get_associated_token_address(&buyer.pubkey(), &protocol.mint),
+            settlement_mint: protocol.mint,
+            escrow_token_account: pda::marketplace::job_escrow(&compute_job),
+            identity_program: identity_program::ID,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::CreateComputeJob {
+            listing_id,
+            job_id: 1,
+            purpose: Purpose::Research,
+            spec_hash: [3; 32],
+            provider: None,
+            timeout_seconds: 3_600,
+            max_attestation_age: None,
+            price_reveal: None,
+        },
+    )
+}
+
+#[tokio::test]
+async fn compute_jobs_follow_their_flag() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, PRICE, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, PRICE).await;
+
+    // Until the flags are first set, every flagged subsystem is off
+    let create = create_compute_job(&protocol, &seller, &buyer, listing_id);
+    let result = protocol
+        .harness
+        .process(std::slice::from_ref(&create), &[&buyer.wallet])
+        .await;
+    assert!(result.is_err());
+
+    let authority = protocol.authority.pubkey();
+    let disable = admin::set_feature_flags(&authority, 0);
+    protocol.harness.execute(&[disable], &[&protocol.authority]).await;
+    protocol.harness.refresh_blockhash().await;
+    let result = protocol
+        .harness
+        .process(std::slice::from_ref(&create), &[&buyer.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::FeatureDisabled);
+
+    let enable = admin::set_feature_flags(&authority, FeatureFlags::COMPUTE_TO_DATA);
+    protocol.harness.execute(&[enable], &[&protocol.authority]).await;
+    let flags: FeatureFlags = protocol.harness.fetch(&pda::marketplace::feature_flags()).await;
+    assert!(flags.is_enabled(FeatureFlags::COMPUTE_TO_DATA));
+    protocol.harness.refresh_blockhash().await;
+    protocol.harness.execute(&[create], &[&buyer.wallet]).await;
+}
+
+#[tokio::test]
+async fn only_the_authority_sets_known_flags() {
+    let mut protocol = Protocol::start().await;
+    let authority = protocol.authority.pubkey();
+
+    let unknown = admin::set_feature_flags(&authority, 1 << 63);
+    let result = protocol.harness.process(&[unknown], &[&protocol.authority]).await;
+    assert_program_error(result, ErrorCode::UnknownFeatureFlag);
+
+    let outsider = protocol.harness.wallet().await;
+    let set = admin::set_feature_flags(&outsider.pubkey(), FeatureFlags::COMPUTE_TO_DATA);
+    let result = protocol.harness.process(&[set], &[&outsider]).await;
+    assert!(result.is_err());
+}
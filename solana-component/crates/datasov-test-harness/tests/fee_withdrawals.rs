@@ -0,0 +1,54 @@
+//! Fee withdrawals: the marketplace authority withdraws accrued fees from the marketplace's
+//! associated token accounts, and cannot reach the escrows and vaults the marketplace also owns.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::DataType as ListingDataType;
+use datasov_client::{admin, pda};
+use datasov_test_harness::Protocol;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+#[tokio::test]
+async fn fees_are_withdrawn_but_escrows_are_not() {
+    let mut protocol = Protocol::start().await;
+    let authority = protocol.authority.pubkey();
+    let mint = protocol.mint;
+    let window = admin::set_refund_window(&authority, 3600);
+    protocol.harness.execute(&[window], &[&protocol.authority]).await;
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+
+    let authority_token_account = protocol.harness.token_account(&authority, &mint).await;
+    let escrow = pda::marketplace::escrow(&pda::marketplace::listing(listing_id));
+    let escrowed = protocol.harness.token_balance(&escrow).await;
+    assert!(escrowed > 0);
+
+    // The escrow is the marketplace's too, but it holds the buyer's money, not fees
+    let mut drain = admin::withdraw_fees(&authority, &mint, escrowed);
+    drain.accounts[2].pubkey = escrow;
+    let result = protocol.harness.process(&[drain], &[&protocol.authority]).await;
+    assert!(result.is_err());
+    assert_eq!(protocol.harness.token_balance(&escrow).await, escrowed);
+
+    let fee_account = get_associated_token_address(&pda::marketplace::marketplace(), &mint);
+    let fees = protocol.harness.token_balance(&fee_account).await;
+    assert!(fees > 0);
+    let withdraw = admin::withdraw_fees(&authority, &mint, fees);
+    protocol.harness.execute(&[withdraw], &[&protocol.authority]).await;
+    assert_eq!(protocol.harness.token_balance(&authority_token_account).await, fees);
+}
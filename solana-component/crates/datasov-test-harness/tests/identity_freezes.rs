@@ -0,0 +1,217 @@
+//! Identity freezes: a compliance officer suspends an identity pending investigation, blocking its
+//! grants and sales until the registry authority or the officer lifts the freeze, or it expires.
+
+use datasov_client::identity::{
+    DataType, ErrorCode, IdentityAccount, IdentityFreeze, IdentityStatus, PermissionType, Purpose, VerificationLevel,
+};
+use datasov_client::instructions::{self, GrantAccessRequest, PurchaseRequest};
+use datasov_client::marketplace::{self, DataType as ListingDataType, ErrorCode as MarketplaceError};
+use datasov_client::{admin, pda, storage};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+const PRICE: u64 = 1_000;
+
+/// A key the registry authority has appointed compliance officer
+async fn compliance_officer(protocol: &mut Protocol) -> Keypair {
+    let officer = protocol.harness.wallet().await;
+    let add = admin::add_compliance_officer(&protocol.authority.pubkey(), &officer.pubkey());
+    protocol.harness.execute(&[add], &[&protocol.authority]).await;
+    officer
+}
+
+async fn purchase(protocol: &mut Protocol, buyer: &Participant, seller: &Participant, listing_id: u64) -> Instruction {
+    let mint = protocol.mint;
+    protocol.harness.token_account(&seller.pubkey(), &mint).await;
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    instructions::purchase_data(PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet: seller.pubkey(),
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: None,
+        max_staleness: None,
+        day: marketplace::PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    })
+}
+
+async fn status(protocol: &mut Protocol, participant: &Participant) -> IdentityStatus {
+    let identity: IdentityAccount = protocol.harness.fetch(&participant.identity()).await;
+    identity.status
+}
+
+#[tokio::test]
+async fn frozen_identities_cannot_trade_until_lifted() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    let consumer_id = protocol.unique_identity_id("consumer");
+    let consumer = protocol
+        .verified_participant(&consumer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, PRICE, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, PRICE).await;
+
+    let officer = compliance_officer(&mut protocol).await;
+    let freeze = instructions::freeze_identity(&officer.pubkey(), &seller.identity_id, 86_400, [7; 32]);
+    protocol.harness.execute(&[freeze], &[&officer]).await;
+    assert_eq!(status(&mut protocol, &seller).await, IdentityStatus::Suspended);
+    let record: IdentityFreeze = protocol
+        .harness
+        .fetch(&pda::identity::identity_freeze(&seller.identity()))
+        .await;
+    assert_eq!(record.officer, officer.pubkey());
+    assert_eq!(record.prior_status, IdentityStatus::Verified);
+    assert_eq!(record.expires_at, record.frozen_at + 86_400);
+
+    // Neither new grants nor sales go through while frozen
+    let grant = instructions::grant_access(GrantAccessRequest {
+        owner: seller.pubkey(),
+        identity_id: seller.identity_id.clone(),
+        consumer: consumer.pubkey(),
+        permission_type: PermissionType::ReadOnly,
+        data_types: vec![DataType::LocationHistory],
+        expires_at: None,
+        permission_pointer: storage::placeholder("frozen-grant"),
+        consumer_staked: false,
+        guardian: None,
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    });
+    let result = protocol.harness.process(&[grant], &[&seller.wallet]).await;
+    assert_program_error(result, ErrorCode::IdentityNotVerified);
+    let sale = purchase(&mut protocol, &buyer, &seller, listing_id).await;
+    let result = protocol.harness.process(&[sale], &[&buyer.wallet]).await;
+    assert_program_error(result, MarketplaceError::SellerNotVerified);
+
+    // Only the registry authority or the officer may lift it before expiry
+    let outsider = protocol.harness.wallet().await;
+    let lift = instructions::lift_freeze(&outsider.pubkey(), &seller.identity_id, &officer.pubkey());
+    let result = protocol.harness.process(&[lift], &[&outsider]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    let officer_lamports = protocol.harness.lamports(&officer.pubkey()).await;
+    let lift = instructions::lift_freeze(&protocol.authority.pubkey(), &seller.identity_id, &officer.pubkey());
+    protocol.harness.execute(&[lift], &[&protocol.authority]).await;
+    assert_eq!(status(&mut protocol, &seller).await, IdentityStatus::Verified);
+    assert!(protocol
+        .harness
+        .account::<IdentityFreeze>(&pda::identity::identity_freeze(&seller.identity()))
+        .await
+        .is_none());
+    assert!(protocol.harness.lamports(&officer.pubkey()).await > officer_lamports);
+
+    protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+}
+
+#[tokio::test]
+async fn freezes_expire_and_take_one_appeal() {
+    let mut protocol = Protocol::start().await;
+    let owner_id = protocol.unique_identity_id("owner");
+    let owner = protocol
+        .verified_participant(&owner_id, VerificationLevel::Enhanced)
+        .await;
+    let officer = compliance_officer(&mut protocol).await;
+
+    let too_long = instructions::freeze_identity(
+        &officer.pubkey(),
+        &owner.identity_id,
+        IdentityFreeze::MAX_DURATION + 1,
+        [7; 32],
+    );
+    let result = protocol.harness.process(&[too_long], &[&officer]).await;
+    assert_program_error(result, ErrorCode::InvalidFreezeDuration);
+
+    let impostor = protocol.harness.wallet().await;
+    let freeze = instructions::freeze_identity(&impostor.pubkey(), &owner.identity_id, 3_600, [7; 32]);
+    let result = protocol.harness.process(&[freeze], &[&impostor]).await;
+    assert!(result.is_err());
+
+    let freeze = instructions::freeze_identity(&officer.pubkey(), &owner.identity_id, 3_600, [7; 32]);
+    protocol.harness.execute(&[freeze], &[&officer]).await;
+
+    let appeal = instructions::appeal_freeze(&owner.pubkey(), &owner.identity_id, [9; 32]);
+    protocol.harness.execute(&[appeal], &[&owner.wallet]).await;
+    let record: IdentityFreeze = protocol
+        .harness
+        .fetch(&pda::identity::identity_freeze(&owner.identity()))
+        .await;
+    assert_eq!(record.appeal_hash, Some([9; 32]));
+    assert!(record.appealed_at.is_some());
+    let again = instructions::appeal_freeze(&owner.pubkey(), &owner.identity_id, [10; 32]);
+    let result = protocol.harness.process(&[again], &[&owner.wallet]).await;
+    assert_program_error(result, ErrorCode::FreezeAlreadyAppealed);
+
+    // Once expired, anyone may lift the freeze
+    protocol.harness.advance_clock(3_600).await;
+    let outsider = protocol.harness.wallet().await;
+    let lift = instructions::lift_freeze(&outsider.pubkey(), &owner.identity_id, &officer.pubkey());
+    protocol.harness.execute(&[lift], &[&outsider]).await;
+    assert_eq!(status(&mut protocol, &owner).await, IdentityStatus::Verified);
+}
+
+#[tokio::test]
+async fn access_resumes_when_a_freeze_expires_before_it_is_lifted() {
+    let mut protocol = Protocol::start().await;
+    let owner_id = protocol.unique_identity_id("owner");
+    let owner = protocol
+        .verified_participant(&owner_id, VerificationLevel::Enhanced)
+        .await;
+    let consumer_id = protocol.unique_identity_id("consumer");
+    let consumer = protocol
+        .verified_participant(&consumer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&owner, &consumer, vec![DataType::LocationHistory], None)
+        .await;
+    let officer = compliance_officer(&mut protocol).await;
+    let freeze = instructions::freeze_identity(&officer.pubkey(), &owner.identity_id, 3_600, [7; 32]);
+    protocol.harness.execute(&[freeze], &[&officer]).await;
+
+    let audit_entry_count = protocol.harness.audit_entry_count(&owner.identity()).await;
+    let validate = instructions::validate_access(
+        &consumer.pubkey(),
+        &owner.identity_id,
+        DataType::LocationHistory,
+        0,
+        false,
+        audit_entry_count,
+    );
+    let result = protocol.harness.process(&[validate], &[&consumer.wallet]).await;
+    assert_program_error(result, ErrorCode::IdentityNotVerified);
+
+    // Nobody has lifted the freeze, but it no longer blocks access
+    protocol.harness.advance_clock(3_600).await;
+    protocol.harness.refresh_blockhash().await;
+    let validate = instructions::validate_access(
+        &consumer.pubkey(),
+        &owner.identity_id,
+        DataType::LocationHistory,
+        0,
+        false,
+        audit_entry_count,
+    );
+    protocol.harness.execute(&[validate], &[&consumer.wallet]).await;
+    assert_eq!(status(&mut protocol, &owner).await, IdentityStatus::Suspended);
+}
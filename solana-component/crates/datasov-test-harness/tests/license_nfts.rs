@@ -0,0 +1,203 @@
+//! License NFTs: the buyer of a licensed purchase mints a Token-2022 NFT whose metadata records the
+//! license template, scope and expiry, transferable only if the listing's rights are transferable
+//! and the template allows redistribution.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions;
+use datasov_client::marketplace::{self, DataType as ListingDataType, ErrorCode, LicenseTerms, PurchaseReceipt};
+use datasov_client::{admin, pda};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::non_transferable::NonTransferable;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensionsOwned};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+const RETENTION_DAYS: u32 = 30;
+
+/// A purchase of a listing licensed under a new template with `terms`; returns the buyer and listing ID
+async fn licensed_purchase(
+    protocol: &mut Protocol,
+    template_id: u32,
+    terms: LicenseTerms,
+    rights_transferable: bool,
+) -> (Participant, u64) {
+    let create = admin::create_license_template(&protocol.authority.pubkey(), template_id, terms, [7; 32]);
+    protocol.harness.execute(&[create], &[&protocol.authority]).await;
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let set_license = instructions::marketplace(
+        marketplace::accounts::SetListingLicense {
+            listing: pda::marketplace::listing(listing_id),
+            license_template: Some(pda::marketplace::license_template(template_id)),
+            owner: seller.pubkey(),
+        },
+        marketplace::instruction::SetListingLicense {},
+    );
+    let set_transferable = instructions::marketplace(
+        marketplace::accounts::SetListingRightsTransferable {
+            listing: pda::marketplace::listing(listing_id),
+            owner: seller.pubkey(),
+        },
+        marketplace::instruction::SetListingRightsTransferable { rights_transferable },
+    );
+    protocol
+        .harness
+        .execute(&[set_license, set_transferable], &[&seller.wallet])
+        .await;
+
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+    (buyer, listing_id)
+}
+
+fn license_mint(listing_id: u64) -> Pubkey {
+    pda::marketplace::license_mint(&pda::marketplace::receipt(&pda::marketplace::listing(listing_id)))
+}
+
+fn license_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::ID)
+}
+
+async fn raw_data(protocol: &mut Protocol, address: &Pubkey) -> Vec<u8> {
+    protocol
+        .harness
+        .context
+        .banks_client
+        .get_account(*address)
+        .await
+        .expect("get_account")
+        .expect("account exists")
+        .data
+}
+
+/// Move the buyer's license NFT to a fresh wallet
+async fn transfer_license(protocol: &mut Protocol, buyer: &Participant, mint: &Pubkey) -> Result<(), ()> {
+    let recipient = protocol.harness.wallet().await;
+    let create =
+        create_associated_token_account_idempotent(&buyer.pubkey(), &recipient.pubkey(), mint, &spl_token_2022::ID);
+    let transfer = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::ID,
+        &license_account(&buyer.pubkey(), mint),
+        mint,
+        &license_account(&recipient.pubkey(), mint),
+        &buyer.pubkey(),
+        &[],
+        1,
+        0,
+    )
+    .unwrap();
+    protocol
+        .harness
+        .process(&[create, transfer], &[&buyer.wallet])
+        .await
+        .map_err(|_| ())
+}
+
+#[tokio::test]
+async fn license_nfts_carry_the_purchased_rights() {
+    let mut protocol = Protocol::start().await;
+    let terms = LicenseTerms {
+        commercial_use: false,
+        research_only: true,
+        redistribution: false,
+        retention_days: Some(RETENTION_DAYS),
+    };
+    let (buyer, listing_id) = licensed_purchase(&mut protocol, 1, terms, true).await;
+
+    let mint_license = instructions::mint_license(&buyer.pubkey(), listing_id, 1);
+    protocol
+        .harness
+        .execute(std::slice::from_ref(&mint_license), &[&buyer.wallet])
+        .await;
+
+    let mint = license_mint(listing_id);
+    let data = raw_data(&mut protocol, &mint).await;
+    let state = StateWithExtensionsOwned::<Mint>::unpack(data).unwrap();
+    assert_eq!(state.base.supply, 1);
+    assert_eq!(state.base.decimals, 0);
+    assert!(state.base.mint_authority.is_none());
+    assert!(state.get_extension::<NonTransferable>().is_ok());
+
+    let receipt: PurchaseReceipt = protocol
+        .harness
+        .fetch(&pda::marketplace::receipt(&pda::marketplace::listing(listing_id)))
+        .await;
+    let metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+    let field = |key: &str| {
+        metadata
+            .additional_metadata
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.clone())
+    };
+    assert_eq!(field("template_id").as_deref(), Some("1"));
+    assert_eq!(field("scope").as_deref(), Some("LocationHistory/Research"));
+    let expires_at = receipt.purchased_at + i64::from(RETENTION_DAYS) * 86_400;
+    assert_eq!(field("expires_at"), Some(expires_at.to_string()));
+
+    let data = raw_data(&mut protocol, &license_account(&buyer.pubkey(), &mint)).await;
+    let holding = StateWithExtensionsOwned::<TokenAccount>::unpack(data).unwrap();
+    assert_eq!(holding.base.amount, 1);
+
+    // Research-only rights stay with the buyer, and each purchase has one license
+    assert!(transfer_license(&mut protocol, &buyer, &mint).await.is_err());
+    protocol.harness.refresh_blockhash().await;
+    let result = protocol.harness.process(&[mint_license], &[&buyer.wallet]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn redistributable_licenses_trade() {
+    let mut protocol = Protocol::start().await;
+    let terms = LicenseTerms {
+        commercial_use: true,
+        research_only: false,
+        redistribution: true,
+        retention_days: None,
+    };
+    let (buyer, listing_id) = licensed_purchase(&mut protocol, 2, terms.clone(), true).await;
+
+    // The template must be the one the purchase was licensed under
+    let other = admin::create_license_template(&protocol.authority.pubkey(), 3, terms.clone(), [8; 32]);
+    protocol.harness.execute(&[other], &[&protocol.authority]).await;
+    let wrong = instructions::mint_license(&buyer.pubkey(), listing_id, 3);
+    let result = protocol.harness.process(&[wrong], &[&buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::UnlicensedReceipt);
+
+    let mint_license = instructions::mint_license(&buyer.pubkey(), listing_id, 2);
+    protocol.harness.execute(&[mint_license], &[&buyer.wallet]).await;
+    let mint = license_mint(listing_id);
+    let state = StateWithExtensionsOwned::<Mint>::unpack(raw_data(&mut protocol, &mint).await).unwrap();
+    assert!(state.get_extension::<NonTransferable>().is_err());
+    let metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert!(metadata
+        .additional_metadata
+        .contains(&("expires_at".to_string(), "never".to_string())));
+
+    assert!(transfer_license(&mut protocol, &buyer, &mint).await.is_ok());
+
+    // Redistribution alone is not enough; the listing must also make its rights transferable
+    let (buyer, listing_id) = licensed_purchase(&mut protocol, 4, terms, false).await;
+    let mint_license = instructions::mint_license(&buyer.pubkey(), listing_id, 4);
+    protocol.harness.execute(&[mint_license], &[&buyer.wallet]).await;
+    let data = raw_data(&mut protocol, &license_mint(listing_id)).await;
+    let state = StateWithExtensionsOwned::<Mint>::unpack(data).unwrap();
+    assert!(state.get_extension::<NonTransferable>().is_ok());
+}
@@ -0,0 +1,145 @@
+//! License templates: the marketplace authority publishes machine-readable license terms, sellers
+//! license a listing's sales under one, and each purchase receipt records the template it was sold under.
+
+use anchor_lang::solana_program::hash::hash;
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions;
+use datasov_client::marketplace::{
+    self, DataListing, DataType as ListingDataType, ErrorCode, LicenseTemplate, LicenseTerms, PurchaseReceipt,
+};
+use datasov_client::{admin, pda};
+use datasov_test_harness::{assert_program_error, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+const TEMPLATE_ID: u32 = 1;
+
+fn research_terms() -> LicenseTerms {
+    LicenseTerms {
+        commercial_use: false,
+        research_only: true,
+        redistribution: false,
+        retention_days: Some(365),
+    }
+}
+
+fn set_license(listing_id: u64, owner: &Pubkey, template_id: Option<u32>) -> Instruction {
+    instructions::marketplace(
+        marketplace::accounts::SetListingLicense {
+            listing: pda::marketplace::listing(listing_id),
+            license_template: template_id.map(pda::marketplace::license_template),
+            owner: *owner,
+        },
+        marketplace::instruction::SetListingLicense {},
+    )
+}
+
+#[tokio::test]
+async fn receipts_record_the_listing_license() {
+    let mut protocol = Protocol::start().await;
+    let authority = protocol.authority.pubkey();
+    let create = admin::create_license_template(
+        &authority,
+        TEMPLATE_ID,
+        research_terms(),
+        hash(b"research license v1").to_bytes(),
+    );
+    protocol.harness.execute(&[create], &[&protocol.authority]).await;
+    let template: LicenseTemplate = protocol
+        .harness
+        .fetch(&pda::marketplace::license_template(TEMPLATE_ID))
+        .await;
+    assert_eq!(template.terms, research_terms());
+    assert!(template.is_active);
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    protocol
+        .harness
+        .execute(
+            &[set_license(listing_id, &seller.pubkey(), Some(TEMPLATE_ID))],
+            &[&seller.wallet],
+        )
+        .await;
+    let listing: DataListing = protocol.harness.fetch(&pda::marketplace::listing(listing_id)).await;
+    assert_eq!(listing.license_template(), Some(TEMPLATE_ID));
+
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    let receipt = protocol
+        .purchase(&buyer, &seller, listing_id, Purpose::Research)
+        .await;
+    let receipt: PurchaseReceipt = protocol.harness.fetch(&receipt).await;
+    assert_eq!(receipt.license_template, Some(TEMPLATE_ID));
+}
+
+#[tokio::test]
+async fn retired_templates_are_not_adopted() {
+    let mut protocol = Protocol::start().await;
+    let authority = protocol.authority.pubkey();
+
+    // Research-only terms cannot also allow commercial use
+    let contradictory = LicenseTerms {
+        commercial_use: true,
+        ..research_terms()
+    };
+    let create = admin::create_license_template(&authority, TEMPLATE_ID, contradictory, [1; 32]);
+    let result = protocol.harness.process(&[create], &[&protocol.authority]).await;
+    assert_program_error(result, ErrorCode::InvalidLicenseTerms);
+
+    let create = admin::create_license_template(&authority, TEMPLATE_ID, research_terms(), [1; 32]);
+    let retire = admin::set_license_template_active(&authority, TEMPLATE_ID, false);
+    protocol
+        .harness
+        .execute(&[create, retire], &[&protocol.authority])
+        .await;
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let result = protocol
+        .harness
+        .process(
+            &[set_license(listing_id, &seller.pubkey(), Some(TEMPLATE_ID))],
+            &[&seller.wallet],
+        )
+        .await;
+    assert_program_error(result, ErrorCode::LicenseTemplateInactive);
+
+    // Only the authority retires templates
+    let reinstate = admin::set_license_template_active(&seller.pubkey(), TEMPLATE_ID, true);
+    let result = protocol.harness.process(&[reinstate], &[&seller.wallet]).await;
+    assert!(result.is_err());
+
+    let reinstate = admin::set_license_template_active(&authority, TEMPLATE_ID, true);
+    protocol.harness.execute(&[reinstate], &[&protocol.authority]).await;
+    protocol
+        .harness
+        .execute(
+            &[set_license(listing_id, &seller.pubkey(), Some(TEMPLATE_ID))],
+            &[&seller.wallet],
+        )
+        .await;
+    protocol
+        .harness
+        .execute(&[set_license(listing_id, &seller.pubkey(), None)], &[&seller.wallet])
+        .await;
+    let listing: DataListing = protocol.harness.fetch(&pda::marketplace::listing(listing_id)).await;
+    assert_eq!(listing.license_template(), None);
+}
@@ -0,0 +1,81 @@
+//! Listing data updates: sellers rotate an active listing to refreshed data, committing to its
+//! content hash so earlier buyers can tell the data changed and verify the new copy.
+
+use anchor_lang::solana_program::hash::hash;
+use datasov_client::identity::{StoragePointer, VerificationLevel};
+use datasov_client::instructions;
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, ErrorCode};
+use datasov_client::{pda, storage};
+use datasov_test_harness::{assert_program_error, Protocol};
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn sellers_rotate_listing_data_under_a_new_hash() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let listing = pda::marketplace::listing(listing_id);
+    let update = |data_pointer: StoragePointer, data_hash: [u8; 32]| {
+        instructions::marketplace(
+            marketplace::accounts::UpdateListingData {
+                listing,
+                owner: seller.pubkey(),
+                marketplace: pda::marketplace::marketplace(),
+            },
+            marketplace::instruction::UpdateListingData { data_pointer, data_hash },
+        )
+    };
+
+    let first = storage::placeholder("january");
+    let first_hash = hash(b"january").to_bytes();
+    protocol
+        .harness
+        .execute(&[update(first.clone(), first_hash)], &[&seller.wallet])
+        .await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.data_pointer(), Some(first.clone()));
+    assert_eq!(account.data_hash, first_hash);
+
+    // Every update commits to new content
+    let second = storage::placeholder("february");
+    let result = protocol
+        .harness
+        .process(&[update(second.clone(), [0; 32])], &[&seller.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::MissingDataHash);
+    let result = protocol
+        .harness
+        .process(&[update(second.clone(), first_hash)], &[&seller.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::ListingDataUnchanged);
+
+    let second_hash = hash(b"february").to_bytes();
+    protocol
+        .harness
+        .execute(&[update(second.clone(), second_hash)], &[&seller.wallet])
+        .await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.data_pointer(), Some(second));
+    assert_eq!(account.data_hash, second_hash);
+
+    // Only the seller rotates the data
+    let other = protocol.harness.wallet().await;
+    let rotate = instructions::marketplace(
+        marketplace::accounts::UpdateListingData {
+            listing,
+            owner: other.pubkey(),
+            marketplace: pda::marketplace::marketplace(),
+        },
+        marketplace::instruction::UpdateListingData {
+            data_pointer: first,
+            data_hash: hash(b"march").to_bytes(),
+        },
+    );
+    let result = protocol.harness.process(&[rotate], &[&other]).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,142 @@
+//! Freshness attestations: the seller of a continuously produced dataset, or the oracle they
+//! delegate to, attests when its data was last refreshed, and buyers can refuse stale listings.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, PurchaseRequest};
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, ErrorCode};
+use datasov_client::pda;
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+const PRICE: u64 = 1_000;
+
+fn attest(listing_id: u64, attestor: &Pubkey, refreshed_at: i64) -> Instruction {
+    instructions::marketplace(
+        marketplace::accounts::AttestListingFreshness {
+            listing: pda::marketplace::listing(listing_id),
+            attestor: *attestor,
+            marketplace: pda::marketplace::marketplace(),
+        },
+        marketplace::instruction::AttestListingFreshness { refreshed_at },
+    )
+}
+
+async fn seller_listing(protocol: &mut Protocol) -> (Participant, u64) {
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, PRICE, ListingDataType::LocationHistory)
+        .await;
+    (seller, listing_id)
+}
+
+#[tokio::test]
+async fn sellers_or_their_oracle_attest_freshness() {
+    let mut protocol = Protocol::start().await;
+    let (seller, listing_id) = seller_listing(&mut protocol).await;
+    let listing = pda::marketplace::listing(listing_id);
+    let created: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(created.last_refreshed_at, created.created_at);
+
+    protocol.harness.advance_clock(600).await;
+    let now = protocol.harness.now().await;
+    protocol
+        .harness
+        .execute(&[attest(listing_id, &seller.pubkey(), now)], &[&seller.wallet])
+        .await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.last_refreshed_at, now);
+
+    // Refreshes can neither go back in time nor be claimed ahead of it
+    let result = protocol
+        .harness
+        .process(&[attest(listing_id, &seller.pubkey(), now - 1)], &[&seller.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::InvalidRefreshTime);
+    let result = protocol
+        .harness
+        .process(&[attest(listing_id, &seller.pubkey(), now + 60)], &[&seller.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::InvalidRefreshTime);
+
+    // Once an oracle is set, only it attests
+    let oracle = protocol.harness.wallet().await;
+    let set_oracle = instructions::marketplace(
+        marketplace::accounts::SetListingFreshnessOracle {
+            listing,
+            owner: seller.pubkey(),
+        },
+        marketplace::instruction::SetListingFreshnessOracle {
+            freshness_oracle: Some(oracle.pubkey()),
+        },
+    );
+    protocol.harness.execute(&[set_oracle], &[&seller.wallet]).await;
+    protocol.harness.advance_clock(60).await;
+    let now = protocol.harness.now().await;
+    let result = protocol
+        .harness
+        .process(&[attest(listing_id, &seller.pubkey(), now)], &[&seller.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::NotFreshnessAttestor);
+    protocol
+        .harness
+        .execute(&[attest(listing_id, &oracle.pubkey(), now)], &[&oracle])
+        .await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.last_refreshed_at, now);
+}
+
+#[tokio::test]
+async fn buyers_refuse_stale_listings() {
+    let mut protocol = Protocol::start().await;
+    let (seller, listing_id) = seller_listing(&mut protocol).await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol.fund_tokens(&buyer, PRICE).await;
+    let mint = protocol.mint;
+    protocol.harness.token_account(&seller.pubkey(), &mint).await;
+
+    protocol.harness.advance_clock(2 * 3_600).await;
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let purchase = instructions::purchase_data(PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet: seller.pubkey(),
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: None,
+        max_staleness: Some(3_600),
+        day: marketplace::PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    });
+    let result = protocol
+        .harness
+        .process(std::slice::from_ref(&purchase), &[&buyer.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::ListingStale);
+
+    let now = protocol.harness.now().await;
+    protocol
+        .harness
+        .execute(&[attest(listing_id, &seller.pubkey(), now)], &[&seller.wallet])
+        .await;
+    protocol.harness.execute(&[purchase], &[&buyer.wallet]).await;
+    let account: DataListing = protocol.harness.fetch(&pda::marketplace::listing(listing_id)).await;
+    assert_eq!(account.buyer(), Some(buyer.pubkey()));
+}
@@ -0,0 +1,57 @@
+//! Listing previews: sellers publish an unencrypted sample of their data and commit to its hash,
+//! so buyers can evaluate a dataset before purchasing it.
+
+use anchor_lang::solana_program::hash::hash;
+use datasov_client::identity::VerificationLevel;
+use datasov_client::instructions;
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, ErrorCode, ListingPreview};
+use datasov_client::{pda, storage};
+use datasov_test_harness::{assert_program_error, Protocol};
+
+#[tokio::test]
+async fn sellers_commit_to_a_preview_sample() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let listing = pda::marketplace::listing(listing_id);
+    let set_preview = |preview| {
+        instructions::marketplace(
+            marketplace::accounts::SetListingPreview {
+                listing,
+                owner: seller.pubkey(),
+            },
+            marketplace::instruction::SetListingPreview { preview },
+        )
+    };
+
+    let preview = ListingPreview {
+        pointer: storage::placeholder("preview"),
+        hash: hash(b"lat,lng,timestamp\n").to_bytes(),
+    };
+    protocol
+        .harness
+        .execute(&[set_preview(Some(preview.clone()))], &[&seller.wallet])
+        .await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.preview(), Some(preview.clone()));
+
+    // A preview without a hash commits buyers to nothing
+    let uncommitted = ListingPreview {
+        hash: [0; 32],
+        ..preview
+    };
+    let result = protocol
+        .harness
+        .process(&[set_preview(Some(uncommitted))], &[&seller.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::MissingPreviewHash);
+
+    protocol.harness.execute(&[set_preview(None)], &[&seller.wallet]).await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.preview(), None);
+}
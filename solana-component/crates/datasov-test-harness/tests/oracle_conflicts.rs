@@ -0,0 +1,161 @@
+//! Oracle conflicts of interest: an oracle cannot verify an identity, business or enclave it
+//! controls, though any other oracle still can. The attempt succeeds without verifying anything, so
+//! its event and the count against the oracle survive.
+
+use datasov_client::identity::{
+    self, ConsumerProfile, IdentityAccount, IdentityStatus, KYCOracle, KYCOracleRegistry, TeeAttestation, TeeType,
+    VerificationLevel,
+};
+use datasov_client::{instructions, pda, storage};
+use datasov_test_harness::{Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+
+fn verify(oracle: &Keypair, participant: &Participant) -> Instruction {
+    instructions::identity(
+        identity::accounts::VerifyIdentity {
+            identity: participant.identity(),
+            oracle: pda::identity::oracle(&oracle.pubkey()),
+            oracle_registry: pda::identity::oracle_registry(),
+            oracle_authority: oracle.pubkey(),
+        },
+        identity::instruction::VerifyIdentity {
+            verification_level: VerificationLevel::Enhanced,
+            kyc_pointer: storage::placeholder(&format!("{}-kyc", participant.identity_id)),
+        },
+    )
+}
+
+fn verify_business(oracle: &Keypair, consumer: &Pubkey) -> Instruction {
+    instructions::identity(
+        identity::accounts::VerifyBusiness {
+            consumer_profile: pda::identity::consumer_profile(consumer),
+            oracle: pda::identity::oracle(&oracle.pubkey()),
+            oracle_authority: oracle.pubkey(),
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        identity::instruction::VerifyBusiness {
+            arweave_business_registry_tx_id: "business-registry".to_string(),
+        },
+    )
+}
+
+fn verify_tee_attestation(oracle: &Keypair, provider: &Pubkey) -> Instruction {
+    instructions::identity(
+        identity::accounts::VerifyTeeAttestation {
+            tee_attestation: pda::identity::tee_attestation(provider),
+            oracle: pda::identity::oracle(&oracle.pubkey()),
+            oracle_authority: oracle.pubkey(),
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        identity::instruction::VerifyTeeAttestation { report_hash: [3; 32] },
+    )
+}
+
+/// An identity owned by the first oracle's own authority
+async fn oracle_identity(protocol: &mut Protocol) -> Participant {
+    let wallet = protocol.oracles[0].insecure_clone();
+    protocol.harness.fund(&wallet.pubkey(), 1_000_000_000).await;
+    let identity_id = protocol.unique_identity_id("oracle");
+    let register = instructions::register_identity(
+        &wallet.pubkey(),
+        &identity_id,
+        storage::placeholder(&format!("{}-document", identity_id)),
+    );
+    protocol.harness.execute(&[register], &[&wallet]).await;
+    Participant { wallet, identity_id }
+}
+
+async fn event_sequence(protocol: &mut Protocol) -> u64 {
+    let registry: KYCOracleRegistry = protocol.harness.fetch(&pda::identity::oracle_registry()).await;
+    registry.event_sequence
+}
+
+async fn oracle(protocol: &mut Protocol, index: usize) -> KYCOracle {
+    let authority = protocol.oracles[index].pubkey();
+    protocol.harness.fetch(&pda::identity::oracle(&authority)).await
+}
+
+#[tokio::test]
+async fn oracles_cannot_verify_their_own_identity() {
+    let mut protocol = Protocol::start().await;
+    let participant = oracle_identity(&mut protocol).await;
+    let sequence = event_sequence(&mut protocol).await;
+    let before = oracle(&mut protocol, 0).await;
+
+    // The attempt is kept on chain: it emits an event and counts against the oracle
+    let own = verify(&protocol.oracles[0], &participant);
+    protocol.harness.execute(&[own], &[&protocol.oracles[0]]).await;
+    let identity: IdentityAccount = protocol.harness.fetch(&participant.identity()).await;
+    assert_eq!(identity.status, IdentityStatus::Pending);
+    assert_eq!(event_sequence(&mut protocol).await, sequence + 1);
+    let after = oracle(&mut protocol, 0).await;
+    assert_eq!(after.verification_count, before.verification_count + 1);
+    assert_eq!(after.successful_verifications, before.successful_verifications);
+
+    // Another oracle has no stake in the outcome
+    let independent = verify(&protocol.oracles[1], &participant);
+    protocol.harness.execute(&[independent], &[&protocol.oracles[1]]).await;
+    let identity: IdentityAccount = protocol.harness.fetch(&participant.identity()).await;
+    assert_eq!(identity.status, IdentityStatus::Verified);
+}
+
+#[tokio::test]
+async fn oracles_cannot_verify_their_own_business() {
+    let mut protocol = Protocol::start().await;
+    let participant = oracle_identity(&mut protocol).await;
+    protocol.register_consumer(&participant).await;
+    let consumer = participant.pubkey();
+    let sequence = event_sequence(&mut protocol).await;
+
+    let own = verify_business(&protocol.oracles[0], &consumer);
+    protocol.harness.execute(&[own], &[&protocol.oracles[0]]).await;
+    let profile: ConsumerProfile = protocol
+        .harness
+        .fetch(&pda::identity::consumer_profile(&consumer))
+        .await;
+    assert_eq!(profile.kyb_verified_by, None);
+    assert_eq!(event_sequence(&mut protocol).await, sequence + 1);
+
+    let independent = verify_business(&protocol.oracles[1], &consumer);
+    protocol.harness.execute(&[independent], &[&protocol.oracles[1]]).await;
+    let profile: ConsumerProfile = protocol
+        .harness
+        .fetch(&pda::identity::consumer_profile(&consumer))
+        .await;
+    assert_eq!(profile.kyb_verified_by, Some(protocol.oracles[1].pubkey()));
+}
+
+#[tokio::test]
+async fn oracles_cannot_verify_their_own_enclave() {
+    let mut protocol = Protocol::start().await;
+    let provider = protocol.oracles[0].insecure_clone();
+    protocol.harness.fund(&provider.pubkey(), 1_000_000_000).await;
+    let tee_attestation = pda::identity::tee_attestation(&provider.pubkey());
+    let post = instructions::identity(
+        identity::accounts::PostTeeAttestation {
+            tee_attestation,
+            provider: provider.pubkey(),
+            system_program: system_program::ID,
+        },
+        identity::instruction::PostTeeAttestation {
+            tee_type: TeeType::IntelSgx,
+            report_hash: [3; 32],
+            measurement: [4; 32],
+        },
+    );
+    protocol.harness.execute(&[post], &[&provider]).await;
+
+    let own = verify_tee_attestation(&protocol.oracles[0], &provider.pubkey());
+    protocol.harness.execute(&[own], &[&protocol.oracles[0]]).await;
+    let attestation: TeeAttestation = protocol.harness.fetch(&tee_attestation).await;
+    assert_eq!(attestation.verified_by, None);
+
+    let independent = verify_tee_attestation(&protocol.oracles[1], &provider.pubkey());
+    protocol.harness.execute(&[independent], &[&protocol.oracles[1]]).await;
+    let attestation: TeeAttestation = protocol.harness.fetch(&tee_attestation).await;
+    assert_eq!(attestation.verified_by, Some(protocol.oracles[1].pubkey()));
+}
@@ -0,0 +1,90 @@
+//! Price candles: each settled sale folds its price into the day's open, high, low and close for
+//! the listing's data type in the settlement mint.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::marketplace::{DataType as ListingDataType, PriceCandle};
+use datasov_client::pda;
+use datasov_test_harness::{Participant, Protocol};
+
+/// Sell a fresh `seller` listing of `data_type` at `price` to `buyer`
+async fn sale(
+    protocol: &mut Protocol,
+    seller: &Participant,
+    buyer: &Participant,
+    price: u64,
+    data_type: ListingDataType,
+) {
+    let listing_id = protocol.create_listing(seller, price, data_type).await;
+    protocol.fund_tokens(buyer, price).await;
+    protocol.purchase(buyer, seller, listing_id, Purpose::Research).await;
+}
+
+async fn candle(protocol: &mut Protocol, data_type: &ListingDataType, day: i64) -> PriceCandle {
+    let mint = protocol.mint;
+    protocol
+        .harness
+        .fetch(&pda::marketplace::price_candle(&mint, data_type, day))
+        .await
+}
+
+#[tokio::test]
+async fn sales_build_daily_candles() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(
+            &seller,
+            &buyer,
+            vec![DataType::LocationHistory, DataType::AppUsage],
+            None,
+        )
+        .await;
+
+    let today = PriceCandle::day_of(protocol.harness.now().await);
+    for price in [2_000, 5_000, 1_000, 3_000] {
+        sale(&mut protocol, &seller, &buyer, price, ListingDataType::LocationHistory).await;
+    }
+    sale(&mut protocol, &seller, &buyer, 9_000, ListingDataType::AppUsage).await;
+
+    let candle_today = candle(&mut protocol, &ListingDataType::LocationHistory, today).await;
+    assert_eq!(candle_today.mint, protocol.mint);
+    assert_eq!(candle_today.day, today);
+    assert_eq!(
+        (
+            candle_today.open,
+            candle_today.high,
+            candle_today.low,
+            candle_today.close
+        ),
+        (2_000, 5_000, 1_000, 3_000)
+    );
+    assert_eq!(candle_today.volume, 11_000);
+    assert_eq!(candle_today.trade_count, 4);
+
+    // Each data type keeps its own candle
+    let app_usage = candle(&mut protocol, &ListingDataType::AppUsage, today).await;
+    assert_eq!(
+        (app_usage.open, app_usage.close, app_usage.trade_count),
+        (9_000, 9_000, 1)
+    );
+
+    // The next day's first sale opens a new candle and leaves yesterday's closed
+    protocol.harness.advance_clock(PriceCandle::DURATION).await;
+    sale(&mut protocol, &seller, &buyer, 4_000, ListingDataType::LocationHistory).await;
+    let tomorrow = candle(&mut protocol, &ListingDataType::LocationHistory, today + 1).await;
+    assert_eq!(
+        (tomorrow.open, tomorrow.high, tomorrow.low, tomorrow.close),
+        (4_000, 4_000, 4_000, 4_000)
+    );
+    assert_eq!(tomorrow.trade_count, 1);
+    let yesterday = candle(&mut protocol, &ListingDataType::LocationHistory, today).await;
+    assert_eq!(yesterday.close, 3_000);
+    assert_eq!(yesterday.trade_count, 4);
+}
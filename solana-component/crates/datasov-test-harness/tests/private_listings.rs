@@ -0,0 +1,168 @@
+//! Private listings: the price stays off chain behind a commitment, and only allowlisted buyers can
+//! purchase, by opening the commitment to the price they pay.
+
+use datasov_client::identity::{self, DataType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, PurchaseRequest};
+use datasov_client::marketplace::{
+    self, DataListing, DataType as ListingDataType, ErrorCode, PriceCandle, PriceReveal, PurchaseReceipt,
+};
+use datasov_client::pda;
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const PRICE: u64 = 10_000;
+const SALT: [u8; 32] = [7; 32];
+
+/// List data from `seller`'s identity privately, for `allowed_buyers` only; returns the listing id
+async fn create_private_listing(protocol: &mut Protocol, seller: &Participant, allowed_buyers: &[&Participant]) -> u64 {
+    let listing_id = protocol.unique_listing_id();
+    let listing = pda::marketplace::listing(listing_id);
+    let create = instructions::marketplace(
+        marketplace::accounts::CreatePrivateListing {
+            listing,
+            allowlist: pda::marketplace::allowlist(&listing),
+            marketplace: pda::marketplace::marketplace(),
+            seller_identity: seller.identity(),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            owner: seller.pubkey(),
+            guardian: None,
+            identity_program: identity::ID,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::CreatePrivateListing {
+            listing_id,
+            price_commitment: DataListing::price_commitment(PRICE, &SALT).expect("price commitment"),
+            description_hash: [5; 32],
+            data_type: ListingDataType::LocationHistory,
+            identity_id: seller.identity_id.clone(),
+            allowed_buyers: allowed_buyers.iter().map(|buyer| buyer.pubkey()).collect(),
+        },
+    );
+    protocol.harness.execute(&[create], &[&seller.wallet]).await;
+    listing_id
+}
+
+async fn purchase_request(
+    protocol: &mut Protocol,
+    buyer: &Participant,
+    seller: &Participant,
+    listing_id: u64,
+) -> PurchaseRequest {
+    let mint = protocol.mint;
+    protocol.harness.token_account(&seller.pubkey(), &mint).await;
+    PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet: seller.pubkey(),
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint: protocol.mint,
+        escrowed: false,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: None,
+        max_staleness: None,
+        day: PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    }
+}
+
+/// A verified participant granted access to `seller`'s location history and holding `PRICE`
+async fn funded_buyer(protocol: &mut Protocol, seller: &Participant, name: &str) -> Participant {
+    let buyer_id = protocol.unique_identity_id(name);
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol.fund_tokens(&buyer, PRICE).await;
+    buyer
+}
+
+#[tokio::test]
+async fn private_listings_sell_at_the_committed_price() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer = funded_buyer(&mut protocol, &seller, "buyer").await;
+    let listing_id = create_private_listing(&mut protocol, &seller, &[&buyer]).await;
+    let listing: DataListing = protocol.harness.fetch(&pda::marketplace::listing(listing_id)).await;
+    assert!(listing.is_private());
+    assert_eq!(listing.price, 0);
+    assert!(listing.description().is_empty());
+
+    // Neither a lower price nor the right price under another salt opens the commitment
+    for price_reveal in [
+        PriceReveal {
+            price: PRICE - 1,
+            salt: SALT,
+        },
+        PriceReveal {
+            price: PRICE,
+            salt: [8; 32],
+        },
+    ] {
+        let request = purchase_request(&mut protocol, &buyer, &seller, listing_id).await;
+        let purchase = instructions::purchase_private_data(request, price_reveal);
+        let result = protocol.harness.process(&[purchase], &[&buyer.wallet]).await;
+        assert_program_error(result, ErrorCode::PriceCommitmentMismatch);
+    }
+
+    let request = purchase_request(&mut protocol, &buyer, &seller, listing_id).await;
+    let purchase = instructions::purchase_private_data(
+        request,
+        PriceReveal {
+            price: PRICE,
+            salt: SALT,
+        },
+    );
+    protocol.harness.execute(&[purchase], &[&buyer.wallet]).await;
+
+    let receipt: PurchaseReceipt = protocol
+        .harness
+        .fetch(&pda::marketplace::receipt(&pda::marketplace::listing(listing_id)))
+        .await;
+    assert_eq!(receipt.amount, PRICE);
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &protocol.mint);
+    assert_eq!(protocol.harness.token_balance(&buyer_token_account).await, 0);
+    let fee = PRICE * protocol.config.fee_basis_points as u64 / 10_000;
+    let seller_token_account = get_associated_token_address(&seller.pubkey(), &protocol.mint);
+    assert_eq!(protocol.harness.token_balance(&seller_token_account).await, PRICE - fee);
+}
+
+#[tokio::test]
+async fn only_allowlisted_buyers_can_purchase_a_private_listing() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer = funded_buyer(&mut protocol, &seller, "buyer").await;
+    let outsider = funded_buyer(&mut protocol, &seller, "outsider").await;
+    let listing_id = create_private_listing(&mut protocol, &seller, &[&buyer]).await;
+
+    // Knowing the price is not enough without a place on the allowlist
+    let request = purchase_request(&mut protocol, &outsider, &seller, listing_id).await;
+    let purchase = instructions::purchase_private_data(
+        request,
+        PriceReveal {
+            price: PRICE,
+            salt: SALT,
+        },
+    );
+    let result = protocol.harness.process(&[purchase], &[&outsider.wallet]).await;
+    assert_program_error(result, ErrorCode::BuyerNotAllowlisted);
+
+    // Nor can a private listing be bought as a public one, at its zero on-chain price
+    let request = purchase_request(&mut protocol, &buyer, &seller, listing_id).await;
+    let purchase = instructions::purchase_data(request);
+    let result = protocol.harness.process(&[purchase], &[&buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::BuyerNotAllowlisted);
+}
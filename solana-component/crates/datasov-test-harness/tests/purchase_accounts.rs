@@ -0,0 +1,180 @@
+//! `purchase_data` only settles between the buyer's token account and one held by the listing's
+//! recorded payout wallet.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, PurchaseRequest};
+use datasov_client::marketplace::{self, DataType as ListingDataType, ErrorCode};
+use datasov_client::{identity as identity_program, pda};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const PRICE: u64 = 1_000_000;
+
+/// A funded buyer holding a grant on the seller's listing, and the purchase instruction for it
+async fn purchase(protocol: &mut Protocol) -> (Participant, Participant, Instruction) {
+    purchase_paying(protocol, None).await
+}
+
+/// As [`purchase`], with the listing's proceeds going to `payout_wallet` rather than the seller
+async fn purchase_paying(
+    protocol: &mut Protocol,
+    payout_wallet: Option<Pubkey>,
+) -> (Participant, Participant, Instruction) {
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    let listing_id = protocol.unique_listing_id();
+    let create = instructions::marketplace(
+        marketplace::accounts::CreateDataListing {
+            listing: pda::marketplace::listing(listing_id),
+            marketplace: pda::marketplace::marketplace(),
+            seller_identity: seller.identity(),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            data_trust: None,
+            trust_membership: None,
+            owner: seller.pubkey(),
+            guardian: None,
+            identity_program: identity_program::ID,
+            system_program: system_program::ID,
+            payout_wallet,
+        },
+        marketplace::instruction::CreateDataListing {
+            listing_id,
+            price: PRICE,
+            data_type: ListingDataType::LocationHistory,
+            description: "Location history".to_string(),
+            identity_id: seller.identity_id.clone(),
+        },
+    );
+    protocol.harness.execute(&[create], &[&seller.wallet]).await;
+    protocol.fund_tokens(&buyer, PRICE).await;
+    let mint = protocol.mint;
+    let payout_wallet = payout_wallet.unwrap_or(seller.pubkey());
+    protocol.harness.token_account(&payout_wallet, &mint).await;
+
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let instruction = instructions::purchase_data(PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet,
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: None,
+        max_staleness: None,
+        day: marketplace::PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    });
+    (seller, buyer, instruction)
+}
+
+fn replace_account(instruction: &mut Instruction, from: &Pubkey, to: &Pubkey) {
+    let meta = instruction
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == *from)
+        .expect("account in instruction");
+    meta.pubkey = *to;
+}
+
+#[tokio::test]
+async fn payout_to_a_non_owner_account_is_rejected() {
+    let mut protocol = Protocol::start().await;
+    let (seller, buyer, mut instruction) = purchase(&mut protocol).await;
+    let mint = protocol.mint;
+
+    // The buyer routes the seller's payment back to themselves
+    replace_account(
+        &mut instruction,
+        &get_associated_token_address(&seller.pubkey(), &mint),
+        &get_associated_token_address(&buyer.pubkey(), &mint),
+    );
+    let result = protocol.harness.process(&[instruction], &[&buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::InvalidPayoutAccount);
+}
+
+#[tokio::test]
+async fn paying_from_another_wallets_account_is_rejected() {
+    let mut protocol = Protocol::start().await;
+    let (_, buyer, mut instruction) = purchase(&mut protocol).await;
+    let mint = protocol.mint;
+    let other = protocol.harness.wallet().await;
+    let other_token_account = protocol.harness.mint_to(&mint, &other.pubkey(), PRICE).await;
+
+    replace_account(
+        &mut instruction,
+        &get_associated_token_address(&buyer.pubkey(), &mint),
+        &other_token_account,
+    );
+    let result = protocol.harness.process(&[instruction], &[&buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn purchase_pays_the_listing_owner() {
+    let mut protocol = Protocol::start().await;
+    let (seller, buyer, instruction) = purchase(&mut protocol).await;
+    let mint = protocol.mint;
+
+    protocol.harness.execute(&[instruction], &[&buyer.wallet]).await;
+    // No refund window by default, so the seller is paid at once
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let (owner_amount, _) = marketplace_account.split_payment(PRICE).unwrap();
+    let seller_token_account = get_associated_token_address(&seller.pubkey(), &mint);
+    assert_eq!(
+        protocol.harness.token_balance(&seller_token_account).await,
+        owner_amount
+    );
+}
+
+#[tokio::test]
+async fn purchase_pays_the_recorded_payout_wallet() {
+    let mut protocol = Protocol::start().await;
+    let treasury = protocol.harness.wallet().await;
+    let (_, buyer, instruction) = purchase_paying(&mut protocol, Some(treasury.pubkey())).await;
+    let mint = protocol.mint;
+
+    protocol.harness.execute(&[instruction], &[&buyer.wallet]).await;
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let (owner_amount, _) = marketplace_account.split_payment(PRICE).unwrap();
+    let treasury_token_account = get_associated_token_address(&treasury.pubkey(), &mint);
+    assert_eq!(
+        protocol.harness.token_balance(&treasury_token_account).await,
+        owner_amount
+    );
+}
+
+#[tokio::test]
+async fn paying_the_owner_instead_of_the_payout_wallet_is_rejected() {
+    let mut protocol = Protocol::start().await;
+    let treasury = protocol.harness.wallet().await;
+    let (seller, buyer, mut instruction) = purchase_paying(&mut protocol, Some(treasury.pubkey())).await;
+    let mint = protocol.mint;
+    let seller_token_account = protocol.harness.token_account(&seller.pubkey(), &mint).await;
+
+    replace_account(
+        &mut instruction,
+        &get_associated_token_address(&treasury.pubkey(), &mint),
+        &seller_token_account,
+    );
+    let result = protocol.harness.process(&[instruction], &[&buyer.wallet]).await;
+    assert_program_error(result, ErrorCode::InvalidPayoutAccount);
+}
@@ -0,0 +1,200 @@
+//! Fee rebates: buyers and sellers who open a ledger for an epoch share its protocol-token budget
+//! in proportion to the marketplace fees their purchases carried, claimable once the epoch ends.
+
+use datasov_client::admin;
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, PurchaseRequest, RebateLedgers};
+use datasov_client::marketplace::{self, DataType as ListingDataType, ErrorCode, RebateEpoch};
+use datasov_client::pda;
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const EPOCH: i64 = 86_400;
+const EMISSION: u64 = 1_000_000;
+const PRICE: u64 = 1_000_000;
+
+/// Start rebates with a vault holding `funded` protocol tokens; returns the protocol token mint
+async fn initialize_rebates(protocol: &mut Protocol, funded: u64) -> Pubkey {
+    let mint = protocol.harness.create_mint(6).await;
+    let initialize = admin::initialize_rebates(&protocol.authority.pubkey(), &mint, EPOCH, EMISSION);
+    protocol.harness.execute(&[initialize], &[&protocol.authority]).await;
+
+    let payer = protocol.harness.payer().pubkey();
+    let mint_to = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &mint,
+        &pda::marketplace::rebate_vault(),
+        &payer,
+        &[],
+        funded,
+    )
+    .expect("mint_to");
+    protocol.harness.execute(&[mint_to], &[]).await;
+    mint
+}
+
+fn open_rebate_epoch(payer: &Pubkey, epoch: u64) -> Instruction {
+    instructions::marketplace(
+        marketplace::accounts::OpenRebateEpoch {
+            rebate_program: pda::marketplace::rebate_program(),
+            vault: pda::marketplace::rebate_vault(),
+            rebate_epoch: pda::marketplace::rebate_epoch(epoch),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::OpenRebateEpoch { epoch },
+    )
+}
+
+fn open_rebate_ledger(participant: &Pubkey, epoch: u64) -> Instruction {
+    instructions::marketplace(
+        marketplace::accounts::OpenRebateLedger {
+            rebate_epoch: pda::marketplace::rebate_epoch(epoch),
+            ledger: pda::marketplace::rebate_ledger(epoch, participant),
+            participant: *participant,
+            system_program: system_program::ID,
+        },
+        marketplace::instruction::OpenRebateLedger { epoch },
+    )
+}
+
+fn claim_rebate(participant: &Pubkey, epoch: u64, mint: &Pubkey) -> Instruction {
+    instructions::marketplace(
+        marketplace::accounts::ClaimRebate {
+            rebate_program: pda::marketplace::rebate_program(),
+            rebate_epoch: pda::marketplace::rebate_epoch(epoch),
+            ledger: pda::marketplace::rebate_ledger(epoch, participant),
+            marketplace: pda::marketplace::marketplace(),
+            vault: pda::marketplace::rebate_vault(),
+            participant_token_account: get_associated_token_address(participant, mint),
+            participant: *participant,
+            token_program: spl_token::ID,
+        },
+        marketplace::instruction::ClaimRebate {},
+    )
+}
+
+/// Buy a fresh listing from `seller`, crediting the fee to whichever sides opted into `rebate`
+async fn purchase(protocol: &mut Protocol, seller: &Participant, buyer: &Participant, rebate: RebateLedgers) {
+    let listing_id = protocol
+        .create_listing(seller, PRICE, ListingDataType::LocationHistory)
+        .await;
+    let settlement_mint = protocol.mint;
+    protocol.fund_tokens(buyer, PRICE).await;
+    protocol.harness.token_account(&seller.pubkey(), &settlement_mint).await;
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let purchase = instructions::purchase_data(PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet: seller.pubkey(),
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint: settlement_mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: Some(rebate),
+        max_staleness: None,
+        day: marketplace::PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    });
+    protocol.harness.execute(&[purchase], &[&buyer.wallet]).await;
+}
+
+#[tokio::test]
+async fn participants_share_the_epoch_budget_in_proportion_to_fees() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_rebates(&mut protocol, 10 * EMISSION).await;
+    let payer = protocol.harness.payer().pubkey();
+    protocol.harness.execute(&[open_rebate_epoch(&payer, 0)], &[]).await;
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol
+        .harness
+        .execute(&[open_rebate_ledger(&buyer.pubkey(), 0)], &[&buyer.wallet])
+        .await;
+    protocol
+        .harness
+        .execute(&[open_rebate_ledger(&seller.pubkey(), 0)], &[&seller.wallet])
+        .await;
+
+    // The buyer is credited for two purchases, the seller for one
+    let both = RebateLedgers {
+        epoch: 0,
+        buyer: true,
+        seller: true,
+    };
+    purchase(&mut protocol, &seller, &buyer, both).await;
+    purchase(&mut protocol, &seller, &buyer, RebateLedgers { seller: false, ..both }).await;
+
+    let buyer_token_account = protocol.harness.token_account(&buyer.pubkey(), &mint).await;
+    let seller_token_account = protocol.harness.token_account(&seller.pubkey(), &mint).await;
+    let result = protocol
+        .harness
+        .process(&[claim_rebate(&buyer.pubkey(), 0, &mint)], &[&buyer.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::RebateEpochOpen);
+
+    protocol.harness.refresh_blockhash().await;
+    protocol.harness.advance_clock(EPOCH).await;
+    protocol
+        .harness
+        .execute(&[claim_rebate(&buyer.pubkey(), 0, &mint)], &[&buyer.wallet])
+        .await;
+    protocol
+        .harness
+        .execute(&[claim_rebate(&seller.pubkey(), 0, &mint)], &[&seller.wallet])
+        .await;
+
+    let buyer_rebate = protocol.harness.token_balance(&buyer_token_account).await;
+    let seller_rebate = protocol.harness.token_balance(&seller_token_account).await;
+    assert_eq!(buyer_rebate, 2 * EMISSION / 3);
+    assert_eq!(seller_rebate, EMISSION / 3);
+
+    let rebate_epoch: RebateEpoch = protocol.harness.fetch(&pda::marketplace::rebate_epoch(0)).await;
+    assert_eq!(rebate_epoch.claimed, buyer_rebate + seller_rebate);
+    assert!(protocol
+        .harness
+        .account::<marketplace::RebateLedger>(&pda::marketplace::rebate_ledger(0, &buyer.pubkey()))
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn an_epoch_budget_is_capped_by_the_unreserved_vault_balance() {
+    let mut protocol = Protocol::start().await;
+    initialize_rebates(&mut protocol, EMISSION / 2).await;
+
+    // Emission set above what the vault holds, as a governance proposal would
+    let authority = protocol.authority.pubkey();
+    let set_emission = admin::set_rebate_emission(&authority, 2 * EMISSION);
+    protocol.harness.execute(&[set_emission], &[&protocol.authority]).await;
+
+    let payer = protocol.harness.payer().pubkey();
+    let result = protocol.harness.process(&[open_rebate_epoch(&payer, 1)], &[]).await;
+    assert_program_error(result, ErrorCode::RebateEpochNotCurrent);
+    protocol.harness.execute(&[open_rebate_epoch(&payer, 0)], &[]).await;
+
+    let rebate_epoch: RebateEpoch = protocol.harness.fetch(&pda::marketplace::rebate_epoch(0)).await;
+    assert_eq!(rebate_epoch.budget, EMISSION / 2);
+    let rebate_program: marketplace::RebateProgram = protocol.harness.fetch(&pda::marketplace::rebate_program()).await;
+    assert_eq!(rebate_program.reserved, EMISSION / 2);
+    assert_eq!(rebate_program.emission_per_epoch, 2 * EMISSION);
+}
@@ -0,0 +1,193 @@
+//! Rent reclamation: anyone may close a long-finalized permission or sold listing, keeping a share
+//! of the rent it recovers while whoever paid the rent gets the rest back.
+
+use datasov_client::identity::{self, AccessPermission, DataType, Purpose, RentReclaim, VerificationLevel};
+use datasov_client::instructions;
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, PurchaseReceipt};
+use datasov_client::pda;
+use datasov_test_harness::scenarios::ExpiringPermission;
+use datasov_test_harness::{assert_program_error, Protocol};
+use solana_sdk::signer::Signer;
+
+fn caller_share(rent: u64) -> u64 {
+    rent / 10_000 * RentReclaim::CALLER_SHARE_BASIS_POINTS
+}
+
+#[tokio::test]
+async fn expired_permissions_are_reclaimed_after_the_grace_period() {
+    let mut protocol = Protocol::start().await;
+    let scenario = ExpiringPermission::new().ttl(60).build(&mut protocol).await;
+    let owner = scenario.owner.pubkey();
+    let consumer = scenario.consumer.pubkey();
+    let caller = protocol.harness.wallet().await;
+
+    let permission: AccessPermission = protocol.harness.fetch(&scenario.permission).await;
+    assert_eq!(permission.rent_payer, owner);
+
+    // Expired, but not for long enough
+    protocol.harness.advance_clock(61).await;
+    let early = instructions::reclaim_permission_rent(&caller.pubkey(), &scenario.owner.identity_id, &consumer, &owner);
+    let result = protocol.harness.process(&[early], &[&caller]).await;
+    assert_program_error(result, identity::ErrorCode::NotReclaimable);
+
+    protocol.harness.advance_clock(AccessPermission::RECLAIM_GRACE).await;
+    protocol.harness.refresh_blockhash().await;
+
+    // The rest of the rent only goes back to whoever paid it
+    let misdirected =
+        instructions::reclaim_permission_rent(&caller.pubkey(), &scenario.owner.identity_id, &consumer, &consumer);
+    let result = protocol.harness.process(&[misdirected], &[&caller]).await;
+    assert_program_error(result, identity::ErrorCode::Unauthorized);
+
+    let rent = protocol.harness.lamports(&scenario.permission).await;
+    let caller_lamports = protocol.harness.lamports(&caller.pubkey()).await;
+    let owner_lamports = protocol.harness.lamports(&owner).await;
+    let reclaim =
+        instructions::reclaim_permission_rent(&caller.pubkey(), &scenario.owner.identity_id, &consumer, &owner);
+    protocol.harness.execute(&[reclaim], &[&caller]).await;
+
+    assert_eq!(protocol.harness.lamports(&scenario.permission).await, 0);
+    let reward = caller_share(rent);
+    assert_eq!(
+        protocol.harness.lamports(&caller.pubkey()).await,
+        caller_lamports + reward
+    );
+    assert_eq!(protocol.harness.lamports(&owner).await, owner_lamports + rent - reward);
+}
+
+#[tokio::test]
+async fn sold_listings_are_reclaimed_once_settled_and_access_has_ended() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    let expires_at = protocol.harness.now().await + 3600;
+    let permission = protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], Some(expires_at))
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    let receipt = protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+    let listing = pda::marketplace::listing(listing_id);
+    let listing_account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(listing_account.rent_payer, seller.pubkey());
+
+    let caller = protocol.harness.wallet().await;
+    let early = instructions::reclaim_listing_rent(
+        &caller.pubkey(),
+        listing_id,
+        &permission,
+        &seller.pubkey(),
+        &buyer.pubkey(),
+    );
+    let result = protocol.harness.process(&[early], &[&caller]).await;
+    assert_program_error(result, marketplace::ErrorCode::NotReclaimable);
+
+    protocol.harness.advance_clock(DataListing::RECLAIM_DELAY).await;
+    protocol.harness.refresh_blockhash().await;
+
+    // The receipt's rent only goes back to the buyer who paid it
+    let misdirected = instructions::reclaim_listing_rent(
+        &caller.pubkey(),
+        listing_id,
+        &permission,
+        &seller.pubkey(),
+        &seller.pubkey(),
+    );
+    let result = protocol.harness.process(&[misdirected], &[&caller]).await;
+    assert_program_error(result, marketplace::ErrorCode::Unauthorized);
+
+    let rent = protocol.harness.lamports(&listing).await;
+    let receipt_rent = protocol.harness.lamports(&receipt).await;
+    let caller_lamports = protocol.harness.lamports(&caller.pubkey()).await;
+    let seller_lamports = protocol.harness.lamports(&seller.pubkey()).await;
+    let buyer_lamports = protocol.harness.lamports(&buyer.pubkey()).await;
+    let reclaim = instructions::reclaim_listing_rent(
+        &caller.pubkey(),
+        listing_id,
+        &permission,
+        &seller.pubkey(),
+        &buyer.pubkey(),
+    );
+    protocol.harness.execute(&[reclaim], &[&caller]).await;
+
+    assert_eq!(protocol.harness.lamports(&listing).await, 0);
+    assert_eq!(protocol.harness.lamports(&receipt).await, 0);
+    assert_eq!(
+        protocol.harness.lamports(&buyer.pubkey()).await,
+        buyer_lamports + receipt_rent
+    );
+    let reward = caller_share(rent);
+    assert_eq!(
+        protocol.harness.lamports(&caller.pubkey()).await,
+        caller_lamports + reward
+    );
+    assert_eq!(
+        protocol.harness.lamports(&seller.pubkey()).await,
+        seller_lamports + rent - reward
+    );
+}
+
+#[tokio::test]
+async fn sold_listings_are_reclaimed_after_their_grant_is() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    let expires_at = protocol.harness.now().await + 3600;
+    let permission = protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], Some(expires_at))
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+
+    protocol
+        .harness
+        .advance_clock(3600 + AccessPermission::RECLAIM_GRACE.max(DataListing::RECLAIM_DELAY))
+        .await;
+    protocol.harness.refresh_blockhash().await;
+    let caller = protocol.harness.wallet().await;
+    let reclaim_permission =
+        instructions::reclaim_permission_rent(&caller.pubkey(), &seller.identity_id, &buyer.pubkey(), &seller.pubkey());
+    protocol.harness.execute(&[reclaim_permission], &[&caller]).await;
+    assert_eq!(protocol.harness.lamports(&permission).await, 0);
+
+    // With the grant gone there is no access left to outlive
+    let listing = pda::marketplace::listing(listing_id);
+    let reclaim = instructions::reclaim_listing_rent(
+        &caller.pubkey(),
+        listing_id,
+        &permission,
+        &seller.pubkey(),
+        &buyer.pubkey(),
+    );
+    protocol.harness.execute(&[reclaim], &[&caller]).await;
+    assert_eq!(protocol.harness.lamports(&listing).await, 0);
+
+    // The listing ID is free again, receipt included
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol
+        .create_listing_with_id(&seller, listing_id, 1_000, ListingDataType::LocationHistory)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    let receipt = protocol.purchase(&buyer, &seller, listing_id, Purpose::Research).await;
+    let receipt: PurchaseReceipt = protocol.harness.fetch(&receipt).await;
+    assert_eq!(receipt.rent_payer, buyer.pubkey());
+}
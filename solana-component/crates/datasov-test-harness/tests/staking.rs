@@ -0,0 +1,250 @@
+//! Staked protocol tokens back a subject in shares: slashes cut every backer in proportion,
+//! unstaking waits out the cooldown, and a buyer's staking weight discounts the marketplace fee.
+
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, PurchaseRequest};
+use datasov_client::marketplace::{self, DataType as ListingDataType};
+use datasov_client::pda;
+use datasov_client::staking::{self, ErrorCode, FeeTier, StakeRole, StakeWeight};
+use datasov_test_harness::{assert_program_error, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const COOLDOWN: i64 = 7 * 86_400;
+const PRICE: u64 = 1_000_000;
+
+/// Initialize staking with the protocol authority; returns the protocol token mint
+async fn initialize_staking(protocol: &mut Protocol) -> Pubkey {
+    let mint = protocol.harness.create_mint(6).await;
+    let instruction = instructions::staking(
+        staking::accounts::InitializeStaking {
+            config: pda::staking::config(),
+            mint,
+            vault: pda::staking::vault(),
+            authority: protocol.authority.pubkey(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::InitializeStaking {
+            unstake_cooldown: COOLDOWN,
+            consumer_slash_bps: 1000,
+            juror_slash_bps: 500,
+            vote_authority: pda::governance::governance(),
+        },
+    );
+    protocol.harness.execute(&[instruction], &[&protocol.authority]).await;
+    mint
+}
+
+/// A wallet holding `amount` protocol tokens, all staked behind `subject`
+async fn staker(protocol: &mut Protocol, mint: &Pubkey, role: StakeRole, subject: &Pubkey, amount: u64) -> Keypair {
+    let staker = protocol.harness.wallet().await;
+    protocol.harness.mint_to(mint, &staker.pubkey(), amount).await;
+    let instruction = instructions::stake(&staker.pubkey(), mint, role, subject, amount);
+    protocol.harness.execute(&[instruction], &[&staker]).await;
+    staker
+}
+
+fn request_unstake(staker: &Pubkey, backing: &Pubkey, shares: u64) -> Instruction {
+    instructions::staking(
+        staking::accounts::RequestUnstake {
+            config: pda::staking::config(),
+            backing: *backing,
+            position: pda::staking::position(backing, staker),
+            weight: pda::staking::stake_weight(staker),
+            staker: *staker,
+        },
+        staking::instruction::RequestUnstake { shares },
+    )
+}
+
+fn withdraw_stake(staker: &Pubkey, backing: &Pubkey, mint: &Pubkey) -> Instruction {
+    instructions::staking(
+        staking::accounts::WithdrawStake {
+            config: pda::staking::config(),
+            backing: *backing,
+            position: pda::staking::position(backing, staker),
+            weight: pda::staking::stake_weight(staker),
+            vault: pda::staking::vault(),
+            staker_token_account: get_associated_token_address(staker, mint),
+            staker: *staker,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::WithdrawStake {},
+    )
+}
+
+fn sync_position(staker: &Pubkey, backing: &Pubkey) -> Instruction {
+    instructions::staking(
+        staking::accounts::SyncPosition {
+            backing: *backing,
+            position: pda::staking::position(backing, staker),
+            weight: pda::staking::stake_weight(staker),
+        },
+        staking::instruction::SyncPosition {},
+    )
+}
+
+async fn weight_of(protocol: &mut Protocol, staker: &Pubkey) -> u64 {
+    let weight: StakeWeight = protocol.harness.fetch(&pda::staking::stake_weight(staker)).await;
+    weight.weight
+}
+
+#[tokio::test]
+async fn unstaked_tokens_return_after_the_cooldown() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_staking(&mut protocol).await;
+    let oracle = protocol.oracles[0].pubkey();
+    let staker = staker(&mut protocol, &mint, StakeRole::Oracle, &oracle, 5_000).await;
+    let backing = pda::staking::backing(StakeRole::Oracle, &oracle);
+    assert_eq!(weight_of(&mut protocol, &staker.pubkey()).await, 5_000);
+
+    protocol
+        .harness
+        .execute(&[request_unstake(&staker.pubkey(), &backing, 5_000)], &[&staker])
+        .await;
+    // Shares under cooldown stop counting as weight at once
+    assert_eq!(weight_of(&mut protocol, &staker.pubkey()).await, 0);
+
+    let result = protocol
+        .harness
+        .process(&[withdraw_stake(&staker.pubkey(), &backing, &mint)], &[&staker])
+        .await;
+    assert_program_error(result, ErrorCode::CooldownNotElapsed);
+
+    protocol.harness.refresh_blockhash().await;
+    protocol.harness.advance_clock(COOLDOWN).await;
+    protocol
+        .harness
+        .execute(&[withdraw_stake(&staker.pubkey(), &backing, &mint)], &[&staker])
+        .await;
+    let staker_token_account = get_associated_token_address(&staker.pubkey(), &mint);
+    assert_eq!(protocol.harness.token_balance(&staker_token_account).await, 5_000);
+}
+
+#[tokio::test]
+async fn slashing_a_backing_cuts_every_backer_in_proportion() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_staking(&mut protocol).await;
+    let consumer = protocol.harness.wallet().await.pubkey();
+    let large = staker(&mut protocol, &mint, StakeRole::Consumer, &consumer, 3_000).await;
+    let small = staker(&mut protocol, &mint, StakeRole::Consumer, &consumer, 1_000).await;
+    let backing = pda::staking::backing(StakeRole::Consumer, &consumer);
+
+    let authority = protocol.authority.pubkey();
+    let recipient_token_account = protocol.harness.token_account(&authority, &mint).await;
+    let slash = instructions::staking(
+        staking::accounts::SlashBacking {
+            config: pda::staking::config(),
+            backing,
+            vault: pda::staking::vault(),
+            recipient_token_account,
+            authority,
+            token_program: spl_token::ID,
+        },
+        staking::instruction::SlashBacking {
+            amount: 2_000,
+            evidence_tx_id: "consumer-misuse".to_string(),
+        },
+    );
+    protocol.harness.execute(&[slash], &[&protocol.authority]).await;
+    assert_eq!(protocol.harness.token_balance(&recipient_token_account).await, 2_000);
+
+    protocol
+        .harness
+        .execute(
+            &[
+                sync_position(&large.pubkey(), &backing),
+                sync_position(&small.pubkey(), &backing),
+            ],
+            &[],
+        )
+        .await;
+    assert_eq!(weight_of(&mut protocol, &large.pubkey()).await, 1_500);
+    assert_eq!(weight_of(&mut protocol, &small.pubkey()).await, 500);
+}
+
+#[tokio::test]
+async fn staking_weight_discounts_the_buyers_marketplace_fee() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_staking(&mut protocol).await;
+    let set_fee_tiers = instructions::staking(
+        staking::accounts::UpdateStakingConfig {
+            config: pda::staking::config(),
+            authority: protocol.authority.pubkey(),
+        },
+        staking::instruction::SetFeeTiers {
+            fee_tiers: vec![
+                FeeTier {
+                    min_weight: 1_000,
+                    discount_bps: 2_500,
+                },
+                FeeTier {
+                    min_weight: 10_000,
+                    discount_bps: 5_000,
+                },
+            ],
+        },
+    );
+    protocol.harness.execute(&[set_fee_tiers], &[&protocol.authority]).await;
+
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, PRICE, ListingDataType::LocationHistory)
+        .await;
+
+    // The buyer's own stake reaches the first tier
+    protocol.harness.mint_to(&mint, &buyer.pubkey(), 1_000).await;
+    let stake = instructions::stake(&buyer.pubkey(), &mint, StakeRole::Consumer, &buyer.pubkey(), 1_000);
+    protocol.harness.execute(&[stake], &[&buyer.wallet]).await;
+
+    let settlement_mint = protocol.mint;
+    let buyer_token_account = protocol.fund_tokens(&buyer, PRICE).await;
+    let seller_token_account = protocol.harness.token_account(&seller.pubkey(), &settlement_mint).await;
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let purchase = instructions::purchase_data(PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet: seller.pubkey(),
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint: settlement_mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: true,
+        rebate: None,
+        max_staleness: None,
+        day: marketplace::PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    });
+    protocol.harness.execute(&[purchase], &[&buyer.wallet]).await;
+
+    let (owner_amount, fee_amount) = marketplace_account.split_payment_with_discount(PRICE, 2_500).unwrap();
+    let (_, full_fee) = marketplace_account.split_payment(PRICE).unwrap();
+    assert!(fee_amount < full_fee);
+    assert_eq!(
+        protocol.harness.token_balance(&seller_token_account).await,
+        owner_amount
+    );
+    assert_eq!(
+        protocol.harness.token_balance(&buyer_token_account).await,
+        PRICE - owner_amount - fee_amount
+    );
+}
@@ -0,0 +1,132 @@
+//! Storage oracles: keys the marketplace authority trusts report whether a listing's data still
+//! resolves and matches its hash, and a failed check pauses the listing's sales until one passes.
+
+use anchor_lang::solana_program::hash::hash;
+use datasov_client::identity::{DataType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, PurchaseRequest};
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, ErrorCode, StorageHealth};
+use datasov_client::{admin, pda, storage};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+/// A listing whose data is committed on chain, and a storage oracle to check it
+async fn checked_listing(protocol: &mut Protocol) -> (Participant, u64, Keypair) {
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let update = instructions::marketplace(
+        marketplace::accounts::UpdateListingData {
+            listing: pda::marketplace::listing(listing_id),
+            owner: seller.pubkey(),
+            marketplace: pda::marketplace::marketplace(),
+        },
+        marketplace::instruction::UpdateListingData {
+            data_pointer: storage::placeholder("dataset"),
+            data_hash: hash(b"dataset").to_bytes(),
+        },
+    );
+    protocol.harness.execute(&[update], &[&seller.wallet]).await;
+
+    let oracle = protocol.harness.wallet().await;
+    let add = admin::add_storage_oracle(&protocol.authority.pubkey(), &oracle.pubkey());
+    protocol.harness.execute(&[add], &[&protocol.authority]).await;
+    (seller, listing_id, oracle)
+}
+
+#[tokio::test]
+async fn failed_checks_pause_sales_until_one_passes() {
+    let mut protocol = Protocol::start().await;
+    let (seller, listing_id, oracle) = checked_listing(&mut protocol).await;
+    let listing = pda::marketplace::listing(listing_id);
+
+    let fail = instructions::record_storage_check(&oracle.pubkey(), listing_id, false);
+    protocol.harness.execute(&[fail], &[&oracle]).await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert!(account.storage_paused());
+    let health: StorageHealth = protocol
+        .harness
+        .fetch(&pda::marketplace::storage_health(&listing))
+        .await;
+    assert!(!health.healthy);
+    assert_eq!(health.consecutive_failures, 1);
+    assert_eq!(health.data_hash, account.data_hash);
+    assert_eq!(health.last_oracle, oracle.pubkey());
+
+    let buyer_id = protocol.unique_identity_id("buyer");
+    let buyer = protocol
+        .verified_participant(&buyer_id, VerificationLevel::Enhanced)
+        .await;
+    protocol
+        .grant(&seller, &buyer, vec![DataType::LocationHistory], None)
+        .await;
+    protocol.fund_tokens(&buyer, 1_000).await;
+    let mint = protocol.mint;
+    protocol.harness.token_account(&seller.pubkey(), &mint).await;
+    let marketplace_account: marketplace::Marketplace = protocol.harness.fetch(&pda::marketplace::marketplace()).await;
+    let purchase = instructions::purchase_data(PurchaseRequest {
+        buyer: buyer.pubkey(),
+        buyer_identity_id: buyer.identity_id.clone(),
+        listing_id,
+        payout_wallet: seller.pubkey(),
+        seller_identity_id: seller.identity_id.clone(),
+        data_union: None,
+        data_type: ListingDataType::LocationHistory,
+        purpose: Purpose::Research,
+        mint,
+        escrowed: marketplace_account.refund_window > 0,
+        buyer_staked: false,
+        buyer_weighted: false,
+        rebate: None,
+        max_staleness: None,
+        day: marketplace::PriceCandle::day_of(protocol.harness.now().await),
+        audit_entry_count: protocol.harness.audit_entry_count(&seller.identity()).await,
+    });
+    let result = protocol
+        .harness
+        .process(std::slice::from_ref(&purchase), &[&buyer.wallet])
+        .await;
+    assert_program_error(result, ErrorCode::ListingStoragePaused);
+
+    let pass = instructions::record_storage_check(&oracle.pubkey(), listing_id, true);
+    protocol.harness.execute(&[pass], &[&oracle]).await;
+    let health: StorageHealth = protocol
+        .harness
+        .fetch(&pda::marketplace::storage_health(&listing))
+        .await;
+    assert!(health.healthy);
+    assert_eq!(health.consecutive_failures, 0);
+    assert_eq!(health.check_count, 2);
+    protocol.harness.execute(&[purchase], &[&buyer.wallet]).await;
+}
+
+#[tokio::test]
+async fn only_storage_oracles_record_checks() {
+    let mut protocol = Protocol::start().await;
+    let (seller, listing_id, oracle) = checked_listing(&mut protocol).await;
+
+    let check = instructions::record_storage_check(&seller.pubkey(), listing_id, true);
+    let result = protocol.harness.process(&[check], &[&seller.wallet]).await;
+    assert!(result.is_err());
+
+    let remove = admin::remove_storage_oracle(&protocol.authority.pubkey(), &oracle.pubkey());
+    protocol.harness.execute(&[remove], &[&protocol.authority]).await;
+    let check = instructions::record_storage_check(&oracle.pubkey(), listing_id, false);
+    let result = protocol.harness.process(&[check], &[&oracle]).await;
+    assert!(result.is_err());
+
+    // Listings without data have nothing to check
+    let oracle = protocol.harness.wallet().await;
+    let add = admin::add_storage_oracle(&protocol.authority.pubkey(), &oracle.pubkey());
+    protocol.harness.execute(&[add], &[&protocol.authority]).await;
+    let empty_listing = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let check = instructions::record_storage_check(&oracle.pubkey(), empty_listing, true);
+    let result = protocol.harness.process(&[check], &[&oracle]).await;
+    assert_program_error(result, ErrorCode::NoListingData);
+}
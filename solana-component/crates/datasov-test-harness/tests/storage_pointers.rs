@@ -0,0 +1,104 @@
+//! Storage pointers: identity documents, grant proofs and listing data may live on Arweave, IPFS,
+//! Shadow Drive or Filecoin, and each reference is checked against its backend's format.
+
+use datasov_client::identity::{self, IdentityAccount, StorageBackend, StoragePointer, VerificationLevel};
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType};
+use datasov_client::{instructions, pda, storage};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::signer::Signer;
+
+const CID: &str = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+#[tokio::test]
+async fn identity_documents_move_between_backends() {
+    let mut protocol = Protocol::start().await;
+    let owner = protocol.harness.wallet().await;
+    let identity_id = protocol.unique_identity_id("storage");
+
+    // Filecoin references are decimal deal IDs
+    let register = instructions::register_identity(
+        &owner.pubkey(),
+        &identity_id,
+        StoragePointer {
+            backend: StorageBackend::Filecoin,
+            reference: "deal-42".to_string(),
+        },
+    );
+    let result = protocol.harness.process(&[register], &[&owner]).await;
+    assert_program_error(result, identity::ErrorCode::InvalidStoragePointer);
+
+    let document = storage::parse(CID).unwrap();
+    let register = instructions::register_identity(&owner.pubkey(), &identity_id, document.clone());
+    protocol.harness.execute(&[register], &[&owner]).await;
+    let identity_address = pda::identity::identity(&identity_id);
+    let account: IdentityAccount = protocol.harness.fetch(&identity_address).await;
+    assert_eq!(account.storage_pointer, document);
+
+    let participant = Participant {
+        wallet: owner,
+        identity_id,
+    };
+    protocol.verify(&participant, VerificationLevel::Basic).await;
+    let moved = storage::parse(&format!("shdw://{}/identity.json", participant.pubkey())).unwrap();
+    let update = instructions::identity(
+        identity::accounts::UpdateIdentity {
+            identity: identity_address,
+            owner: participant.pubkey(),
+            oracle_registry: pda::identity::oracle_registry(),
+        },
+        identity::instruction::UpdateIdentity {
+            new_storage_pointer: moved.clone(),
+        },
+    );
+    protocol.harness.execute(&[update], &[&participant.wallet]).await;
+    let account: IdentityAccount = protocol.harness.fetch(&identity_address).await;
+    assert_eq!(account.storage_pointer, moved);
+    assert_eq!(account.storage_pointer.backend, StorageBackend::ShadowDrive);
+}
+
+#[tokio::test]
+async fn sellers_point_listings_at_their_data() {
+    let mut protocol = Protocol::start().await;
+    let seller_id = protocol.unique_identity_id("seller");
+    let seller = protocol
+        .verified_participant(&seller_id, VerificationLevel::Enhanced)
+        .await;
+    let listing_id = protocol
+        .create_listing(&seller, 1_000, ListingDataType::LocationHistory)
+        .await;
+    let listing = pda::marketplace::listing(listing_id);
+    let set_data_pointer = |data_pointer| {
+        instructions::marketplace(
+            marketplace::accounts::SetListingDataPointer {
+                listing,
+                owner: seller.pubkey(),
+            },
+            marketplace::instruction::SetListingDataPointer { data_pointer },
+        )
+    };
+
+    let data_pointer = storage::parse("fil://5131").unwrap();
+    protocol
+        .harness
+        .execute(&[set_data_pointer(Some(data_pointer.clone()))], &[&seller.wallet])
+        .await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.data_pointer(), Some(data_pointer));
+
+    let malformed = StoragePointer {
+        backend: StorageBackend::Ipfs,
+        reference: "QmNotACid".to_string(),
+    };
+    let result = protocol
+        .harness
+        .process(&[set_data_pointer(Some(malformed))], &[&seller.wallet])
+        .await;
+    assert_program_error(result, marketplace::ErrorCode::InvalidStoragePointer);
+
+    protocol
+        .harness
+        .execute(&[set_data_pointer(None)], &[&seller.wallet])
+        .await;
+    let account: DataListing = protocol.harness.fetch(&listing).await;
+    assert_eq!(account.data_pointer(), None);
+}
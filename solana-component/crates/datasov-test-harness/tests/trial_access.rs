@@ -0,0 +1,97 @@
+//! Trial access: an owner may grant a consumer a free, capped, short-lived permission for one data
+//! type, and each consumer gets one trial of each data type across every identity.
+
+use datasov_client::identity::{self, AccessPermission, DataType, ErrorCode, VerificationLevel};
+use datasov_client::{instructions, pda, storage};
+use datasov_test_harness::{assert_program_error, Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::system_program;
+
+async fn grant_trial_access(
+    protocol: &mut Protocol,
+    owner: &Participant,
+    consumer: &Participant,
+    data_type: DataType,
+) -> Instruction {
+    let audit_entry_count = protocol.harness.audit_entry_count(&owner.identity()).await;
+    instructions::identity(
+        identity::accounts::GrantTrialAccess {
+            permission: pda::identity::permission(&owner.identity(), &consumer.pubkey()),
+            trial_record: pda::identity::trial_record(&consumer.pubkey()),
+            identity: owner.identity(),
+            blacklist: pda::identity::blacklist(),
+            consumer_profile: pda::identity::consumer_profile(&consumer.pubkey()),
+            compliance_matrix: pda::identity::compliance_matrix(),
+            consumer_stake: None,
+            consumer: consumer.pubkey(),
+            owner: owner.pubkey(),
+            guardian: None,
+            audit: instructions::identity_audit_accounts(&owner.identity(), audit_entry_count),
+            oracle_registry: pda::identity::oracle_registry(),
+            system_program: system_program::ID,
+        },
+        identity::instruction::GrantTrialAccess {
+            data_type,
+            duration_seconds: 3_600,
+            max_accesses: 3,
+            permission_pointer: storage::placeholder("trial"),
+        },
+    )
+}
+
+#[tokio::test]
+async fn trials_reuse_an_inactive_grant_but_not_a_live_one() {
+    let mut protocol = Protocol::start().await;
+    let owner_id = protocol.unique_identity_id("owner");
+    let owner = protocol
+        .verified_participant(&owner_id, VerificationLevel::Enhanced)
+        .await;
+    let consumer_id = protocol.unique_identity_id("consumer");
+    let consumer = protocol
+        .verified_participant(&consumer_id, VerificationLevel::Enhanced)
+        .await;
+    let permission = protocol.grant(&owner, &consumer, vec![DataType::AppUsage], None).await;
+
+    let trial = grant_trial_access(&mut protocol, &owner, &consumer, DataType::LocationHistory).await;
+    let result = protocol.harness.process(&[trial], &[&owner.wallet]).await;
+    assert_program_error(result, ErrorCode::PermissionAlreadyActive);
+
+    protocol.revoke(&owner, &consumer).await;
+    protocol.harness.refresh_blockhash().await;
+    let trial = grant_trial_access(&mut protocol, &owner, &consumer, DataType::LocationHistory).await;
+    protocol.harness.execute(&[trial], &[&owner.wallet]).await;
+    let permission: AccessPermission = protocol.harness.fetch(&permission).await;
+    assert!(permission.is_active && permission.is_trial);
+    assert_eq!(permission.data_types, vec![DataType::LocationHistory]);
+    assert_eq!(permission.max_accesses, Some(3));
+    assert_eq!(permission.rent_payer, owner.pubkey());
+}
+
+#[tokio::test]
+async fn a_consumer_gets_one_trial_of_each_data_type() {
+    let mut protocol = Protocol::start().await;
+    let mut owners = Vec::new();
+    for name in ["alice", "bob"] {
+        let owner_id = protocol.unique_identity_id(name);
+        owners.push(
+            protocol
+                .verified_participant(&owner_id, VerificationLevel::Enhanced)
+                .await,
+        );
+    }
+    let consumer_id = protocol.unique_identity_id("consumer");
+    let consumer = protocol
+        .verified_participant(&consumer_id, VerificationLevel::Enhanced)
+        .await;
+
+    let trial = grant_trial_access(&mut protocol, &owners[0], &consumer, DataType::LocationHistory).await;
+    protocol.harness.execute(&[trial], &[&owners[0].wallet]).await;
+
+    // Another identity cannot hand out a second free trial of the same data type
+    let trial = grant_trial_access(&mut protocol, &owners[1], &consumer, DataType::LocationHistory).await;
+    let result = protocol.harness.process(&[trial], &[&owners[1].wallet]).await;
+    assert_program_error(result, ErrorCode::TrialAlreadyUsed);
+
+    let trial = grant_trial_access(&mut protocol, &owners[1], &consumer, DataType::AppUsage).await;
+    protocol.harness.execute(&[trial], &[&owners[1].wallet]).await;
+}
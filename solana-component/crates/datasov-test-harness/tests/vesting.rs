@@ -0,0 +1,141 @@
+//! Vesting: protocol tokens locked for a beneficiary vest linearly after a cliff, are claimed as
+//! they vest, and stop vesting when the staking authority (governance) revokes the schedule.
+
+use datasov_client::admin;
+use datasov_client::instructions;
+use datasov_client::pda;
+use datasov_client::staking::{self, instruction::CreateVesting, ErrorCode, Vesting, VestingKind};
+use datasov_test_harness::{assert_program_error, Protocol};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+const QUARTER: i64 = 90 * 86_400;
+const TOTAL: u64 = 4_000_000;
+
+/// Initialize staking with the protocol authority; returns the protocol token mint
+async fn initialize_staking(protocol: &mut Protocol) -> Pubkey {
+    let mint = protocol.harness.create_mint(6).await;
+    let instruction = instructions::staking(
+        staking::accounts::InitializeStaking {
+            config: pda::staking::config(),
+            mint,
+            vault: pda::staking::vault(),
+            authority: protocol.authority.pubkey(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        staking::instruction::InitializeStaking {
+            unstake_cooldown: 0,
+            consumer_slash_bps: 0,
+            juror_slash_bps: 0,
+            vote_authority: pda::governance::governance(),
+        },
+    );
+    protocol.harness.execute(&[instruction], &[&protocol.authority]).await;
+    mint
+}
+
+/// A funder locking `TOTAL` for a new beneficiary over a year with a one-quarter cliff, starting now
+async fn vesting(protocol: &mut Protocol, mint: &Pubkey) -> (Keypair, Keypair) {
+    let funder = protocol.harness.wallet().await;
+    let beneficiary = protocol.harness.wallet().await;
+    protocol.harness.mint_to(mint, &funder.pubkey(), TOTAL).await;
+    protocol.harness.token_account(&beneficiary.pubkey(), mint).await;
+
+    let now = protocol.harness.now().await;
+    let create = instructions::create_vesting(
+        &funder.pubkey(),
+        &beneficiary.pubkey(),
+        mint,
+        CreateVesting {
+            vesting_id: 1,
+            kind: VestingKind::OracleIncentive,
+            total: TOTAL,
+            start_at: now,
+            cliff_at: now + QUARTER,
+            end_at: now + 4 * QUARTER,
+        },
+    );
+    protocol.harness.execute(&[create], &[&funder]).await;
+    (funder, beneficiary)
+}
+
+#[tokio::test]
+async fn tokens_vest_linearly_after_the_cliff() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_staking(&mut protocol).await;
+    let (_, beneficiary) = vesting(&mut protocol, &mint).await;
+    let beneficiary_token_account = get_associated_token_address(&beneficiary.pubkey(), &mint);
+
+    let claim = instructions::claim_vested(&beneficiary.pubkey(), &mint, 1);
+    let result = protocol
+        .harness
+        .process(std::slice::from_ref(&claim), &[&beneficiary])
+        .await;
+    assert_program_error(result, ErrorCode::NothingVested);
+
+    // Vesting counts from the start once past the cliff: half the total after two quarters
+    protocol.harness.refresh_blockhash().await;
+    protocol.harness.advance_clock(2 * QUARTER).await;
+    protocol
+        .harness
+        .execute(std::slice::from_ref(&claim), &[&beneficiary])
+        .await;
+    assert_eq!(
+        protocol.harness.token_balance(&beneficiary_token_account).await,
+        TOTAL / 2
+    );
+
+    protocol.harness.refresh_blockhash().await;
+    protocol.harness.advance_clock(4 * QUARTER).await;
+    protocol.harness.execute(&[claim], &[&beneficiary]).await;
+    assert_eq!(protocol.harness.token_balance(&beneficiary_token_account).await, TOTAL);
+
+    let vesting: Vesting = protocol
+        .harness
+        .fetch(&pda::staking::vesting(&beneficiary.pubkey(), 1))
+        .await;
+    assert_eq!(vesting.claimed, TOTAL);
+}
+
+#[tokio::test]
+async fn revoking_returns_the_unvested_tokens_to_the_funder() {
+    let mut protocol = Protocol::start().await;
+    let mint = initialize_staking(&mut protocol).await;
+    let (funder, beneficiary) = vesting(&mut protocol, &mint).await;
+
+    protocol.harness.advance_clock(QUARTER).await;
+    let vesting: Vesting = protocol
+        .harness
+        .fetch(&pda::staking::vesting(&beneficiary.pubkey(), 1))
+        .await;
+    let authority = protocol.authority.pubkey();
+    let revoke = admin::revoke_vesting(&authority, &mint, &vesting);
+    protocol
+        .harness
+        .execute(std::slice::from_ref(&revoke), &[&protocol.authority])
+        .await;
+
+    let funder_token_account = get_associated_token_address(&funder.pubkey(), &mint);
+    assert_eq!(
+        protocol.harness.token_balance(&funder_token_account).await,
+        3 * TOTAL / 4
+    );
+
+    // What vested before the revocation stays claimable, and nothing more vests
+    protocol.harness.advance_clock(4 * QUARTER).await;
+    let claim = instructions::claim_vested(&beneficiary.pubkey(), &mint, 1);
+    protocol.harness.execute(&[claim], &[&beneficiary]).await;
+    let beneficiary_token_account = get_associated_token_address(&beneficiary.pubkey(), &mint);
+    assert_eq!(
+        protocol.harness.token_balance(&beneficiary_token_account).await,
+        TOTAL / 4
+    );
+
+    protocol.harness.refresh_blockhash().await;
+    let result = protocol.harness.process(&[revoke], &[&protocol.authority]).await;
+    assert_program_error(result, ErrorCode::VestingRevoked);
+}
@@ -0,0 +1,86 @@
+//! Types of the `datasov_audit` program.
+
+use crate::{AccountData, EventData, Pubkey};
+use alloc::string::String;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditLog {
+    pub subject: Pubkey,
+    pub entry_count: u64,
+    pub checkpointed_entries: u64,
+    pub checkpoint_count: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for AuditLog {
+    const DISCRIMINATOR: [u8; 8] = [230, 207, 176, 233, 170, 130, 101, 244];
+}
+
+/// Zero-copy `repr(C)` layout: appending an entry writes its slot instead of re-encoding the page
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditPage {
+    pub log: Pubkey,
+    pub page_index: u64,
+    pub entries: [AuditEntry; 16],
+    /// Slots of `entries` filled so far
+    pub entry_count: u8,
+    pub bump: u8,
+    pub _padding: [u8; 6],
+}
+
+impl AccountData for AuditPage {
+    const DISCRIMINATOR: [u8; 8] = [127, 118, 126, 6, 75, 65, 123, 95];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub actor: Pubkey,
+    pub reference: Pubkey,
+    pub value: u64,
+    pub timestamp: i64,
+    /// `AuditAction` variant index
+    pub action: u8,
+    pub _padding: [u8; 7],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditCheckpoint {
+    pub log: Pubkey,
+    pub epoch: u64,
+    pub from_entry: u64,
+    pub to_entry: u64,
+    pub merkle_root: [u8; 32],
+
+    pub arweave_export_tx_id: String,
+    pub posted_by: Pubkey,
+    pub posted_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for AuditCheckpoint {
+    const DISCRIMINATOR: [u8; 8] = [95, 94, 222, 149, 44, 70, 246, 100];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AuditAction {
+    Grant,
+    Revoke,
+    Validate,
+    Purchase,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditCheckpointedEvent {
+    pub subject: Pubkey,
+    pub epoch: u64,
+    pub from_entry: u64,
+    pub to_entry: u64,
+    pub merkle_root: [u8; 32],
+    pub arweave_export_tx_id: String,
+}
+
+impl EventData for AuditCheckpointedEvent {
+    const DISCRIMINATOR: [u8; 8] = [138, 44, 196, 49, 118, 211, 201, 148];
+}
@@ -0,0 +1,142 @@
+//! Types of the `datasov_governance` program.
+
+use crate::identity::{DataType, VerificationLevel};
+use crate::marketplace::LicenseTerms;
+use crate::{AccountData, EventData, Pubkey};
+use alloc::string::String;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Governance {
+    /// Seconds a proposal is open for voting
+    pub voting_period: i64,
+    /// Least stake, yes and no combined, that must vote for a proposal to pass
+    pub quorum: u64,
+    /// Share of the votes cast that must approve, in basis points
+    pub approval_threshold_bps: u16,
+    /// Least stake a proposer must hold
+    pub proposal_threshold: u64,
+    pub proposal_count: u64,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+impl AccountData for Governance {
+    const DISCRIMINATOR: [u8; 8] = [18, 143, 88, 13, 73, 217, 47, 49];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakeAccount {
+    pub staker: Pubkey,
+    pub amount: u64,
+    /// End of the latest vote the stake was cast in; withdrawals wait until then
+    pub locked_until: i64,
+    pub bump: u8,
+}
+
+impl AccountData for StakeAccount {
+    const DISCRIMINATOR: [u8; 8] = [80, 158, 67, 124, 50, 189, 192, 255];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    /// Where the rationale for the change is published
+    pub description_uri: String,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub executed_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for Proposal {
+    const DISCRIMINATOR: [u8; 8] = [26, 94, 189, 187, 116, 136, 53, 33];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub approve: bool,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl AccountData for VoteRecord {
+    const DISCRIMINATOR: [u8; 8] = [112, 9, 123, 165, 234, 9, 157, 167];
+}
+
+/// A parameter change a proposal applies once passed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ProposalAction {
+    /// Marketplace fee charged on purchases
+    SetMarketplaceFee { fee_basis_points: u16 },
+    /// Minimum verification levels and consumer stake for a data type
+    SetComplianceRule {
+        data_type: DataType,
+        min_buyer_level: VerificationLevel,
+        min_seller_level: VerificationLevel,
+        min_consumer_stake: u64,
+    },
+    /// Stake KYC oracles must post and the amount slashed per upheld dispute
+    SetOracleRegistryParameters { minimum_stake: u64, slash_amount: u64 },
+    /// Protocol tokens each marketplace fee rebate epoch may pay out
+    SetRebateEmission { emission_per_epoch: u64 },
+    /// Stop a vesting, returning its unvested tokens to the funder
+    RevokeVesting { vesting: Pubkey },
+    /// Post the Merkle root of an airdrop's allocations
+    CreateAirdrop {
+        airdrop_id: u64,
+        merkle_root: [u8; 32],
+        leaf_count: u32,
+    },
+    /// Publish license terms for listings to reference
+    CreateLicenseTemplate {
+        template_id: u32,
+        terms: LicenseTerms,
+        terms_hash: [u8; 32],
+    },
+    /// Retire a license template from new listings, or reinstate it
+    SetLicenseTemplateActive { template_id: u32, is_active: bool },
+    /// Replace the set of marketplace subsystems enabled on this cluster
+    SetFeatureFlags { flags: u64 },
+    /// Lift a compliance freeze on an identity, upholding its owner's appeal
+    LiftIdentityFreeze { identity: Pubkey },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalCreatedEvent {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub voting_ends_at: i64,
+}
+
+impl EventData for ProposalCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [154, 240, 33, 66, 194, 233, 203, 209];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteCastEvent {
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub approve: bool,
+    pub weight: u64,
+}
+
+impl EventData for VoteCastEvent {
+    const DISCRIMINATOR: [u8; 8] = [241, 151, 159, 134, 250, 234, 71, 234];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: u64,
+}
+
+impl EventData for ProposalExecutedEvent {
+    const DISCRIMINATOR: [u8; 8] = [120, 242, 13, 36, 223, 3, 110, 180];
+}
@@ -0,0 +1,1281 @@
+//! Types of the `datasov_identity` program.
+
+use crate::{AccountData, EventData, Pubkey};
+use alloc::{string::String, vec::Vec};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KYCOracleRegistry {
+    pub authority: Pubkey,
+    pub minimum_stake: u64,
+    pub slash_amount: u64,
+    pub oracle_count: u32,
+    /// Sequence number of the program's latest event
+    pub event_sequence: u64,
+    pub bump: u8,
+}
+
+impl AccountData for KYCOracleRegistry {
+    const DISCRIMINATOR: [u8; 8] = [97, 225, 226, 131, 232, 162, 60, 244];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KYCOracle {
+    pub oracle_pubkey: Pubkey,
+
+    pub provider_name: String,
+    pub stake_amount: u64,
+    pub verification_count: u64,
+    pub successful_verifications: u64,
+    pub reputation_score: u16,
+    pub is_active: bool,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for KYCOracle {
+    const DISCRIMINATOR: [u8; 8] = [56, 0, 141, 71, 230, 243, 179, 133];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityAccount {
+    pub owner: Pubkey,
+    pub status: IdentityStatus,
+    pub verification_level: VerificationLevel,
+    /// Expiry of the freeze suspending the identity, or 0 when not frozen
+    pub frozen_until: i64,
+    /// Whether the identity was verified when frozen, and so counts as verified again once the freeze expires
+    pub frozen_while_verified: bool,
+    /// Zeroed; room for future fixed-offset fields
+    pub reserved: [u8; 7],
+
+    pub identity_id: String,
+    /// Identity document, or the KYC or revocation record that last replaced it
+    pub storage_pointer: StoragePointer,
+    pub verified_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub guardian: Option<Pubkey>,
+    pub emancipation_at: Option<i64>,
+
+    pub sns_domain: String,
+    pub sns_domain_account: Option<Pubkey>,
+    /// X25519 public key for envelope encryption to the owner
+    pub encryption_key: Option<[u8; 32]>,
+    pub bump: u8,
+}
+
+impl AccountData for IdentityAccount {
+    const DISCRIMINATOR: [u8; 8] = [194, 90, 181, 160, 182, 206, 116, 158];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessPermission {
+    pub consumer: Pubkey,
+    pub is_active: bool,
+    pub permission_type: PermissionType,
+    /// Zeroed; room for future fixed-offset fields
+    pub reserved: [u8; 16],
+
+    pub identity_id: String,
+
+    pub data_types: Vec<DataType>,
+    pub granted_at: i64,
+    pub expires_at: Option<i64>,
+    /// Signed grant terms, or the revocation record once revoked
+    pub proof_pointer: StoragePointer,
+    pub is_trial: bool,
+    pub max_accesses: Option<u32>,
+    pub access_count: u32,
+    pub revocation_epoch: u32,
+    pub revoked_at: Option<i64>,
+    pub allowed_purposes: u16,
+    pub consent_hash: [u8; 32],
+    pub consent_version: u32,
+    pub privacy_budget: Option<u32>,
+    pub privacy_spent: u32,
+    pub attestation_count: u32,
+    /// Paid the account's rent, and gets most of it back when the grant is reclaimed; unset on
+    /// grants from before payers were recorded, which refund the identity owner
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl AccountData for AccessPermission {
+    const DISCRIMINATOR: [u8; 8] = [34, 37, 11, 32, 226, 12, 217, 33];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComplianceMatrix {
+    pub authority: Pubkey,
+
+    pub rules: Vec<ComplianceRule>,
+    pub bump: u8,
+}
+
+impl AccountData for ComplianceMatrix {
+    const DISCRIMINATOR: [u8; 8] = [95, 29, 115, 208, 108, 109, 24, 132];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CrankFund {
+    pub authority: Pubkey,
+    pub reward_per_account: u64,
+    pub total_swept: u64,
+    pub total_paid: u64,
+    pub bump: u8,
+}
+
+impl AccountData for CrankFund {
+    const DISCRIMINATOR: [u8; 8] = [66, 76, 62, 71, 239, 57, 204, 198];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Blacklist {
+    pub authority: Pubkey,
+
+    pub consumers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl AccountData for Blacklist {
+    const DISCRIMINATOR: [u8; 8] = [131, 9, 212, 250, 58, 186, 247, 3];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerProfile {
+    pub consumer: Pubkey,
+    pub organization_name_hash: [u8; 32],
+
+    pub contact_tx_id: String,
+
+    pub kyb_attestation_tx_id: String,
+    pub completed_purchases: u64,
+    pub disputes_lost: u32,
+    pub revocations_received: u32,
+    pub kyb_verified_by: Option<Pubkey>,
+    pub kyb_verified_at: Option<i64>,
+    pub overdue_exports: u32,
+    pub terms_hash: [u8; 32],
+    pub terms_version: u32,
+    /// X25519 public key for envelope encryption to the consumer
+    pub encryption_key: Option<[u8; 32]>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for ConsumerProfile {
+    const DISCRIMINATOR: [u8; 8] = [17, 59, 48, 93, 87, 199, 218, 207];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerStake {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub slashed_total: u64,
+    pub unlock_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for ConsumerStake {
+    const DISCRIMINATOR: [u8; 8] = [82, 130, 203, 248, 33, 165, 70, 5];
+}
+
+/// Data types a consumer has had a free trial of, from any identity
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TrialRecord {
+    pub consumer: Pubkey,
+    pub used_data_types: u16,
+    pub bump: u8,
+}
+
+impl AccountData for TrialRecord {
+    const DISCRIMINATOR: [u8; 8] = [20, 232, 15, 241, 73, 241, 246, 68];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct JurorPool {
+    pub authority: Pubkey,
+    pub vrf_authority: Pubkey,
+
+    pub jurors: Vec<Pubkey>,
+    pub dispute_count: u64,
+    pub bump: u8,
+}
+
+impl AccountData for JurorPool {
+    const DISCRIMINATOR: [u8; 8] = [217, 104, 42, 167, 209, 1, 171, 33];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Juror {
+    pub authority: Pubkey,
+    pub stake: u64,
+    pub active_cases: u32,
+    pub cases_voted: u64,
+    pub majority_votes: u64,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for Juror {
+    const DISCRIMINATOR: [u8; 8] = [209, 201, 239, 217, 237, 84, 189, 152];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Dispute {
+    pub dispute_id: u64,
+    pub claimant: Pubkey,
+    pub consumer: Pubkey,
+
+    pub evidence_tx_id: String,
+    pub requested_slash: u64,
+    pub jury_fee: u64,
+
+    pub panel: Vec<Pubkey>,
+
+    pub votes: Vec<Option<bool>>,
+    pub status: DisputeStatus,
+    pub opened_at: i64,
+    pub vote_deadline: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for Dispute {
+    const DISCRIMINATOR: [u8; 8] = [36, 49, 241, 67, 40, 36, 241, 74];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TeeAttestation {
+    pub provider: Pubkey,
+    pub tee_type: TeeType,
+    pub report_hash: [u8; 32],
+    pub measurement: [u8; 32],
+    pub posted_at: i64,
+    pub verified_by: Option<Pubkey>,
+    pub verified_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for TeeAttestation {
+    const DISCRIMINATOR: [u8; 8] = [184, 170, 157, 43, 122, 186, 77, 221];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataTrust {
+    pub creator: Pubkey,
+    pub trust_id: u64,
+
+    pub trustees: Vec<Pubkey>,
+    pub charter_hash: [u8; 32],
+    pub allowed_data_types: u16,
+    pub max_grant_duration: i64,
+    pub member_count: u32,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for DataTrust {
+    const DISCRIMINATOR: [u8; 8] = [3, 17, 189, 115, 69, 40, 113, 128];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TrustMembership {
+    pub data_trust: Pubkey,
+    pub identity: Pubkey,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for TrustMembership {
+    const DISCRIMINATOR: [u8; 8] = [27, 252, 66, 223, 47, 242, 190, 56];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExportRequest {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub owner_encryption_key: [u8; 32],
+    pub requested_at: i64,
+    pub deadline: i64,
+    pub status: ExportStatus,
+
+    pub package_tx_id: String,
+    pub package_hash: [u8; 32],
+    pub fulfilled_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for ExportRequest {
+    const DISCRIMINATOR: [u8; 8] = [105, 123, 34, 221, 133, 110, 47, 69];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumptionAttestation {
+    pub permission: Pubkey,
+    pub consumer: Pubkey,
+    pub sequence: u32,
+    pub data_type: DataType,
+    pub purpose: Purpose,
+    pub accessed_at: i64,
+    pub record_count: u64,
+    pub manifest_hash: [u8; 32],
+    pub access_count: u32,
+    pub tee_attestation: Option<Pubkey>,
+    pub attested_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for ConsumptionAttestation {
+    const DISCRIMINATOR: [u8; 8] = [222, 174, 226, 117, 155, 10, 104, 247];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WebProofVerifier {
+    pub verifier_id: u32,
+    pub verification_level: VerificationLevel,
+    pub verifying_key: Groth16VerifyingKey,
+    pub is_active: bool,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for WebProofVerifier {
+    const DISCRIMINATOR: [u8; 8] = [183, 121, 236, 67, 241, 33, 130, 80];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WebProofNullifier {
+    pub verifier: Pubkey,
+    pub identity: Pubkey,
+    pub used_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for WebProofNullifier {
+    const DISCRIMINATOR: [u8; 8] = [38, 31, 148, 197, 51, 190, 162, 15];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AttestationSource {
+    pub kind: AttestationKind,
+    pub issuer: Pubkey,
+    pub schema: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl AccountData for AttestationSource {
+    const DISCRIMINATOR: [u8; 8] = [200, 208, 20, 181, 61, 109, 0, 174];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DomainLink {
+    pub domain_account: Pubkey,
+    pub identity: Pubkey,
+    pub linked_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for DomainLink {
+    const DISCRIMINATOR: [u8; 8] = [197, 93, 114, 53, 197, 139, 105, 222];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoucherNonce {
+    pub identity: Pubkey,
+    pub nonce: u64,
+    pub redeemed_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for VoucherNonce {
+    const DISCRIMINATOR: [u8; 8] = [116, 54, 188, 76, 176, 242, 199, 184];
+}
+
+/// A consumer's paid offer for a grant; its lamports above rent are the escrowed price
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessRequest {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub permission_type: PermissionType,
+    pub data_types: Vec<DataType>,
+    /// Seconds the permission runs from acceptance
+    pub duration: i64,
+    /// Lamports paid to the owner on acceptance
+    pub price: u64,
+    pub requested_at: i64,
+    /// Last moment the owner may accept
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for AccessRequest {
+    const DISCRIMINATOR: [u8; 8] = [165, 109, 87, 16, 193, 252, 188, 174];
+}
+
+/// Merkle root over every identity's ID, status, verification level and owner, for light clients.
+/// The crank folds identities in over several transactions, then publishes the root.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StateSnapshot {
+    /// Builds and publishes snapshots
+    pub crank: Pubkey,
+    /// Snapshots published so far
+    pub epoch: u64,
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+    /// Slot the published snapshot began building at; identities are as of this slot or later
+    pub slot: u64,
+    pub published_at: i64,
+    pub building: bool,
+    /// Rightmost filled node at each level of the tree being built
+    pub frontier: [[u8; 32]; 32],
+    pub pending_count: u64,
+    pub pending_slot: u64,
+    /// Last identity folded in; each must have a greater address than the one before
+    pub last_identity: Pubkey,
+    pub bump: u8,
+}
+
+impl AccountData for StateSnapshot {
+    const DISCRIMINATOR: [u8; 8] = [217, 41, 55, 206, 2, 172, 128, 169];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComplianceOfficer {
+    pub officer: Pubkey,
+    pub appointed_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for ComplianceOfficer {
+    const DISCRIMINATOR: [u8; 8] = [125, 199, 11, 150, 44, 180, 52, 226];
+}
+
+/// An identity's suspension pending investigation; closed when lifted
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityFreeze {
+    pub identity: Pubkey,
+    pub officer: Pubkey,
+    /// Hash of the officer's off-chain case notes
+    pub reason_hash: [u8; 32],
+    /// Status restored when the freeze lifts
+    pub prior_status: IdentityStatus,
+    pub frozen_at: i64,
+    pub expires_at: i64,
+    /// Hash of the owner's off-chain appeal, once lodged
+    pub appeal_hash: Option<[u8; 32]>,
+    pub appealed_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for IdentityFreeze {
+    const DISCRIMINATOR: [u8; 8] = [159, 210, 62, 12, 213, 184, 164, 1];
+}
+
+/// Return data of `validate_access`: the grant as it stands after the validated access
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessValidation {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub permission_type: PermissionType,
+    pub allowed_purposes: u16,
+    pub expires_at: Option<i64>,
+    /// Including this access
+    pub access_count: u32,
+    pub max_accesses: Option<u32>,
+    /// Epsilon left in the privacy budget, if the grant has one
+    pub privacy_remaining: Option<u32>,
+    pub consent_hash: [u8; 32],
+    pub consent_version: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComplianceRule {
+    pub data_type: DataType,
+    pub min_buyer_level: VerificationLevel,
+    pub min_seller_level: VerificationLevel,
+    pub min_consumer_stake: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    pub ic: [[u8; 64]; Groth16VerifyingKey::IC_LEN],
+}
+
+impl Groth16VerifyingKey {
+    /// Identity binding and nullifier
+    pub const PUBLIC_INPUTS: usize = 2;
+    /// One point per public input plus the constant term
+    pub const IC_LEN: usize = Self::PUBLIC_INPUTS + 1;
+}
+
+/// Grant terms an identity owner signs off chain for a consumer to redeem
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GrantVoucher {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub permission_type: PermissionType,
+    pub data_types: Vec<DataType>,
+    pub expires_at: Option<i64>,
+    pub valid_until: i64,
+    pub nonce: u64,
+    pub permission_pointer: StoragePointer,
+}
+
+/// On-chain layout of a Civic gateway token
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CivicGatewayToken {
+    pub features: u8,
+    pub parent_gateway_token: Option<Pubkey>,
+    pub owner_wallet: Pubkey,
+    pub owner_identity: Option<Pubkey>,
+    pub gatekeeper_network: Pubkey,
+    pub issuing_gatekeeper: Pubkey,
+    pub state: u8,
+    pub expire_time: Option<i64>,
+}
+
+/// On-chain layout of a Solana Attestation Service attestation, whose nonce is the subject wallet
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SasAttestation {
+    pub discriminator: u8,
+    pub nonce: Pubkey,
+    pub credential: Pubkey,
+    pub schema: Pubkey,
+    pub data: Vec<u8>,
+    pub signer: Pubkey,
+    pub expiry: i64,
+}
+
+/// Location of an off-chain document on one of the supported storage networks
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StoragePointer {
+    pub backend: StorageBackend,
+    /// Transaction ID, CID, `<storage account>/<file name>` or deal ID, by backend
+    pub reference: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum IdentityStatus {
+    Pending,
+    Verified,
+    Revoked,
+    Suspended,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum VerificationLevel {
+    None,
+    Basic,
+    Enhanced,
+    High,
+    Credential,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PermissionType {
+    ReadOnly,
+    ReadWrite,
+    Share,
+    Analyze,
+    Export,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DataType {
+    LocationHistory,
+    AppUsage,
+    PurchaseHistory,
+    HealthData,
+    SocialMediaActivity,
+    SearchHistory,
+    FinancialData,
+    CommunicationData,
+    Custom,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Purpose {
+    Research,
+    Analytics,
+    Marketing,
+    Advertising,
+    ProductDevelopment,
+    Personalization,
+    Compliance,
+    ModelTraining,
+    Other,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ExportStatus {
+    #[default]
+    None,
+    Pending,
+    Fulfilled,
+    Overdue,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AttestationKind {
+    CivicPass,
+    SolanaAttestationService,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TeeType {
+    IntelSgx,
+    AmdSev,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DisputeStatus {
+    AwaitingPanel,
+    Voting,
+    Upheld,
+    Dismissed,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageBackend {
+    Arweave,
+    Ipfs,
+    ShadowDrive,
+    Filecoin,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OracleRegisteredEvent {
+    pub oracle_pubkey: Pubkey,
+    pub provider_name: String,
+    pub stake_amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for OracleRegisteredEvent {
+    const DISCRIMINATOR: [u8; 8] = [37, 91, 38, 138, 40, 152, 82, 79];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityRegisteredEvent {
+    pub identity_id: String,
+    pub owner: Pubkey,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for IdentityRegisteredEvent {
+    const DISCRIMINATOR: [u8; 8] = [112, 105, 60, 169, 239, 101, 136, 146];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityVerifiedEvent {
+    pub identity_id: String,
+    pub verification_level: VerificationLevel,
+    pub oracle_pubkey: Pubkey,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for IdentityVerifiedEvent {
+    const DISCRIMINATOR: [u8; 8] = [192, 245, 94, 164, 254, 72, 170, 156];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityUpdatedEvent {
+    pub identity_id: String,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for IdentityUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [231, 70, 137, 19, 34, 252, 160, 227];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityRevokedEvent {
+    pub identity_id: String,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for IdentityRevokedEvent {
+    const DISCRIMINATOR: [u8; 8] = [130, 201, 136, 208, 162, 180, 141, 56];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessGrantedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub permission_type: PermissionType,
+    pub data_types: Vec<DataType>,
+    pub proof_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for AccessGrantedEvent {
+    const DISCRIMINATOR: [u8; 8] = [103, 235, 124, 243, 55, 15, 168, 192];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessRevokedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub proof_pointer: StoragePointer,
+    pub revocation_epoch: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for AccessRevokedEvent {
+    const DISCRIMINATOR: [u8; 8] = [0, 123, 250, 9, 94, 246, 235, 158];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComplianceRuleUpdatedEvent {
+    pub data_type: DataType,
+    pub min_buyer_level: VerificationLevel,
+    pub min_seller_level: VerificationLevel,
+    pub min_consumer_stake: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ComplianceRuleUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [39, 113, 125, 203, 204, 17, 213, 7];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExpiredSweptEvent {
+    pub caller: Pubkey,
+    pub swept: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ExpiredSweptEvent {
+    const DISCRIMINATOR: [u8; 8] = [191, 142, 195, 209, 169, 84, 85, 229];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerBlacklistedEvent {
+    pub consumer: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsumerBlacklistedEvent {
+    const DISCRIMINATOR: [u8; 8] = [202, 220, 101, 21, 12, 54, 192, 102];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerUnblacklistedEvent {
+    pub consumer: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsumerUnblacklistedEvent {
+    const DISCRIMINATOR: [u8; 8] = [158, 45, 91, 72, 238, 214, 225, 4];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlacklistedGrantEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub permission: Pubkey,
+    pub is_active: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for BlacklistedGrantEvent {
+    const DISCRIMINATOR: [u8; 8] = [198, 136, 187, 183, 186, 100, 109, 127];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerRegisteredEvent {
+    pub consumer: Pubkey,
+    pub organization_name_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsumerRegisteredEvent {
+    const DISCRIMINATOR: [u8; 8] = [16, 108, 240, 200, 91, 38, 75, 142];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerStakedEvent {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub total: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsumerStakedEvent {
+    const DISCRIMINATOR: [u8; 8] = [120, 95, 224, 231, 156, 206, 46, 100];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerSlashedEvent {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub evidence_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsumerSlashedEvent {
+    const DISCRIMINATOR: [u8; 8] = [100, 191, 198, 198, 241, 23, 171, 83];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BusinessVerifiedEvent {
+    pub consumer: Pubkey,
+    pub oracle_pubkey: Pubkey,
+    pub arweave_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for BusinessVerifiedEvent {
+    const DISCRIMINATOR: [u8; 8] = [5, 70, 247, 63, 172, 207, 34, 82];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TrialAccessGrantedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub data_type: DataType,
+    pub expires_at: i64,
+    pub max_accesses: u32,
+    pub proof_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for TrialAccessGrantedEvent {
+    const DISCRIMINATOR: [u8; 8] = [12, 207, 73, 93, 91, 222, 111, 185];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DisputeOpenedEvent {
+    pub dispute_id: u64,
+    pub claimant: Pubkey,
+    pub consumer: Pubkey,
+    pub evidence_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DisputeOpenedEvent {
+    const DISCRIMINATOR: [u8; 8] = [91, 164, 118, 191, 199, 195, 118, 21];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DisputePanelSelectedEvent {
+    pub dispute_id: u64,
+    pub panel: Vec<Pubkey>,
+    pub vote_deadline: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DisputePanelSelectedEvent {
+    const DISCRIMINATOR: [u8; 8] = [190, 233, 218, 61, 127, 9, 215, 21];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DisputeResolvedEvent {
+    pub dispute_id: u64,
+    pub upheld: bool,
+    pub slashed: u64,
+    pub juror_share: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DisputeResolvedEvent {
+    const DISCRIMINATOR: [u8; 8] = [152, 37, 98, 245, 229, 39, 150, 78];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PermissionPurposesUpdatedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub purposes: Vec<Purpose>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for PermissionPurposesUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [240, 52, 251, 66, 213, 224, 0, 99];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TeeAttestationVerifiedEvent {
+    pub provider: Pubkey,
+    pub oracle_pubkey: Pubkey,
+    pub tee_type: TeeType,
+    pub report_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for TeeAttestationVerifiedEvent {
+    const DISCRIMINATOR: [u8; 8] = [249, 78, 187, 104, 5, 96, 77, 72];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataTrustCreatedEvent {
+    pub data_trust: Pubkey,
+    pub trustees: Vec<Pubkey>,
+    pub charter_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DataTrustCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [160, 162, 190, 87, 9, 194, 86, 193];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GuardianshipUpdatedEvent {
+    pub identity_id: String,
+    pub guardian: Option<Pubkey>,
+    pub emancipation_at: Option<i64>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for GuardianshipUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [132, 107, 14, 175, 52, 235, 236, 122];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExportRequestedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub owner_encryption_key: [u8; 32],
+    pub deadline: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ExportRequestedEvent {
+    const DISCRIMINATOR: [u8; 8] = [149, 229, 48, 28, 182, 216, 227, 226];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExportFulfilledEvent {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub package_tx_id: String,
+    pub package_hash: [u8; 32],
+    pub late: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ExportFulfilledEvent {
+    const DISCRIMINATOR: [u8; 8] = [119, 40, 84, 195, 10, 124, 137, 138];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExportOverdueEvent {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub deadline: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ExportOverdueEvent {
+    const DISCRIMINATOR: [u8; 8] = [79, 18, 243, 98, 237, 147, 130, 242];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerTermsPublishedEvent {
+    pub consumer: Pubkey,
+    pub terms_hash: [u8; 32],
+    pub terms_version: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsumerTermsPublishedEvent {
+    const DISCRIMINATOR: [u8; 8] = [174, 173, 25, 35, 100, 183, 222, 121];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsentUpdatedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub previous_version: u32,
+    pub consent_version: u32,
+    pub consent_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsentUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [96, 199, 39, 103, 91, 221, 251, 133];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PrivacyBudgetUpdatedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub privacy_budget: Option<u32>,
+    pub privacy_spent: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for PrivacyBudgetUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [57, 190, 225, 179, 212, 153, 181, 132];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumptionAttestedEvent {
+    pub permission: Pubkey,
+    pub consumer: Pubkey,
+    pub sequence: u32,
+    pub data_type: DataType,
+    pub purpose: Purpose,
+    pub record_count: u64,
+    pub manifest_hash: [u8; 32],
+    pub tee_attestation: Option<Pubkey>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ConsumptionAttestedEvent {
+    const DISCRIMINATOR: [u8; 8] = [113, 76, 196, 19, 65, 174, 101, 1];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WebProofVerifiedEvent {
+    pub identity_id: String,
+    pub verifier_id: u32,
+    pub verification_level: VerificationLevel,
+    pub nullifier: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for WebProofVerifiedEvent {
+    const DISCRIMINATOR: [u8; 8] = [134, 231, 47, 248, 111, 86, 145, 238];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExternalAttestationVerifiedEvent {
+    pub identity_id: String,
+    pub kind: AttestationKind,
+    pub issuer: Pubkey,
+    pub attestation: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ExternalAttestationVerifiedEvent {
+    const DISCRIMINATOR: [u8; 8] = [219, 59, 78, 10, 246, 131, 99, 46];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DomainLinkedEvent {
+    pub identity_id: String,
+    pub owner: Pubkey,
+    pub domain: String,
+    pub domain_account: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DomainLinkedEvent {
+    const DISCRIMINATOR: [u8; 8] = [140, 95, 228, 75, 142, 148, 95, 134];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DomainUnlinkedEvent {
+    pub identity_id: String,
+    pub domain: String,
+    pub domain_account: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DomainUnlinkedEvent {
+    const DISCRIMINATOR: [u8; 8] = [176, 173, 6, 91, 219, 244, 251, 243];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EncryptionKeyUpdatedEvent {
+    /// Identity or consumer profile account
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub encryption_key: Option<[u8; 32]>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for EncryptionKeyUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [98, 135, 218, 220, 122, 227, 74, 183];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StateSnapshotPublishedEvent {
+    pub epoch: u64,
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+    pub slot: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for StateSnapshotPublishedEvent {
+    const DISCRIMINATOR: [u8; 8] = [166, 24, 65, 114, 3, 229, 56, 23];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OracleRegistryUpdatedEvent {
+    pub minimum_stake: u64,
+    pub slash_amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for OracleRegistryUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [131, 228, 51, 64, 116, 131, 176, 97];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegistryAuthorityChangedEvent {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for RegistryAuthorityChangedEvent {
+    const DISCRIMINATOR: [u8; 8] = [250, 96, 182, 138, 26, 16, 142, 216];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityFrozenEvent {
+    pub identity_id: String,
+    pub officer: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub expires_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for IdentityFrozenEvent {
+    const DISCRIMINATOR: [u8; 8] = [127, 151, 121, 34, 120, 224, 199, 101];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FreezeAppealedEvent {
+    pub identity_id: String,
+    pub appeal_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for FreezeAppealedEvent {
+    const DISCRIMINATOR: [u8; 8] = [201, 55, 133, 220, 3, 141, 239, 153];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdentityFreezeLiftedEvent {
+    pub identity_id: String,
+    pub lifted_by: Pubkey,
+    /// Status the identity was left in
+    pub status: IdentityStatus,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for IdentityFreezeLiftedEvent {
+    const DISCRIMINATOR: [u8; 8] = [170, 206, 193, 91, 116, 120, 17, 203];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessRequestedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub data_types: Vec<DataType>,
+    pub duration: i64,
+    pub price: u64,
+    pub expires_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for AccessRequestedEvent {
+    const DISCRIMINATOR: [u8; 8] = [249, 202, 160, 33, 201, 212, 151, 122];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessRequestAcceptedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub permission: Pubkey,
+    pub price: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for AccessRequestAcceptedEvent {
+    const DISCRIMINATOR: [u8; 8] = [195, 148, 220, 235, 150, 224, 9, 145];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RentReclaimedEvent {
+    pub account: Pubkey,
+    pub rent_payer: Pubkey,
+    pub caller: Pubkey,
+    pub refund: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for RentReclaimedEvent {
+    const DISCRIMINATOR: [u8; 8] = [120, 78, 100, 109, 136, 74, 173, 130];
+}
+
+/// A verifier vouching for an account it controls; the account is left unverified
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SelfVerificationRejectedEvent {
+    /// Identity, consumer profile or enclave attestation account
+    pub account: Pubkey,
+    /// Oracle authority or attestation issuer
+    pub verifier: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for SelfVerificationRejectedEvent {
+    const DISCRIMINATOR: [u8; 8] = [201, 34, 2, 119, 191, 46, 101, 126];
+}
@@ -0,0 +1,153 @@
+//! Account, event and enum types of the DataSov programs, for code that decodes their data but
+//! cannot or should not depend on the programs themselves: embedded agents, WASM clients and
+//! lightweight off-chain services.
+//!
+//! Every type mirrors its program definition field for field, so the Borsh encodings are
+//! identical. Accounts and events carry their Anchor discriminators; [`AccountData::decode`] and
+//! [`EventData::decode`] check and strip them. The crate is `no_std` (with `alloc`) once the
+//! default `std` feature is off; `solana` adds conversions to the Solana SDK's `Pubkey`.
+//!
+//! The `pda` feature derives every program-derived address without the Solana SDK, and `wasm`
+//! exports the common ones through `wasm-bindgen`, so the crate builds for
+//! `wasm32-unknown-unknown` and browser apps share the programs' encodings and seeds. Other
+//! behavior, such as permission checks, stays in the programs and `datasov-client`.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod audit;
+pub mod governance;
+pub mod identity;
+pub mod marketplace;
+#[cfg(feature = "pda")]
+pub mod pda;
+pub mod staking;
+pub mod timelock;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use core::fmt;
+use core::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// A 32-byte account address, encoded like the Solana SDK's `Pubkey`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pubkey(pub [u8; 32]);
+
+impl Pubkey {
+    pub const fn new_from_array(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Pubkey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
+impl fmt::Debug for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A string that is not a base58-encoded 32-byte address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsePubkeyError;
+
+impl fmt::Display for ParsePubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid base58 public key")
+    }
+}
+
+impl FromStr for Pubkey {
+    type Err = ParsePubkeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0u8; 32];
+        match bs58::decode(s).onto(&mut bytes) {
+            Ok(32) => Ok(Self(bytes)),
+            _ => Err(ParsePubkeyError),
+        }
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<solana_program::pubkey::Pubkey> for Pubkey {
+    fn from(pubkey: solana_program::pubkey::Pubkey) -> Self {
+        Self(pubkey.to_bytes())
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<Pubkey> for solana_program::pubkey::Pubkey {
+    fn from(pubkey: Pubkey) -> Self {
+        Self::new_from_array(pubkey.0)
+    }
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Data shorter than a discriminator, or one belonging to another type
+    Discriminator,
+    /// Fields that do not decode as the type
+    Layout(borsh::io::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Discriminator => f.write_str("discriminator mismatch"),
+            Self::Layout(err) => write!(f, "invalid layout: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Data of a program account, as stored on chain
+pub trait AccountData: BorshDeserialize {
+    /// First 8 bytes of `sha256("account:<name>")`
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Decode account data, ignoring any unused space after the fields
+    fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        decode(&Self::DISCRIMINATOR, data)
+    }
+}
+
+/// Payload of a program event, as logged by `emit!` after base64 decoding
+pub trait EventData: BorshDeserialize {
+    /// First 8 bytes of `sha256("event:<name>")`
+    const DISCRIMINATOR: [u8; 8];
+
+    fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        decode(&Self::DISCRIMINATOR, data)
+    }
+}
+
+fn decode<T: BorshDeserialize>(discriminator: &[u8; 8], data: &[u8]) -> Result<T, DecodeError> {
+    match data.split_at_checked(8) {
+        Some((prefix, mut fields)) if prefix == discriminator => {
+            T::deserialize(&mut fields).map_err(DecodeError::Layout)
+        }
+        _ => Err(DecodeError::Discriminator),
+    }
+}
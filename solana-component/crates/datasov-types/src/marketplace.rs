@@ -0,0 +1,997 @@
+//! Types of the `datasov_solana` marketplace program.
+
+use crate::identity::{DataType as IdentityDataType, Purpose, StoragePointer};
+use crate::{AccountData, EventData, Pubkey};
+use alloc::{string::String, vec::Vec};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Marketplace {
+    pub authority: Pubkey,
+    pub fee_basis_points: u16,
+    pub total_listings: u64,
+    pub total_volume: u64,
+    pub halted_data_types: u16,
+    pub stablecoin_only: bool,
+
+    pub approved_mints: Vec<Pubkey>,
+    pub refund_window: i64,
+    /// Sequence number of the program's latest event
+    pub event_sequence: u64,
+    pub bump: u8,
+}
+
+impl AccountData for Marketplace {
+    const DISCRIMINATOR: [u8; 8] = [70, 222, 41, 62, 78, 3, 32, 174];
+}
+
+/// Zero-copy `repr(C)` layout: text sits in fixed-size buffers after its length, and each
+/// optional field after a `has_` flag
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataListing {
+    pub id: u64,
+    pub owner: Pubkey,
+    pub price: u64,
+    pub is_active: u8,
+    /// Zeroed; room for future fixed-offset fields
+    pub reserved: [u8; 16],
+    /// `DataType` variant index; a custom type's name follows it
+    pub data_type: u8,
+    pub custom_data_type_len: u8,
+    pub custom_data_type: [u8; 32],
+    pub description_len: u8,
+    pub description: [u8; 200],
+    pub identity_id_len: u8,
+    pub identity_id: [u8; 64],
+    pub is_private: u8,
+    pub requires_kyb: u8,
+    /// Set while the last storage oracle check failed; no new sales until one passes
+    pub storage_paused: u8,
+    /// Buyers may transfer their access right, and their license NFT, to another verified identity
+    pub rights_transferable: u8,
+    pub has_sold_at: u8,
+    pub has_cancelled_at: u8,
+    pub has_buyer: u8,
+    pub has_expires_at: u8,
+    pub has_union: u8,
+    pub has_freshness_oracle: u8,
+    pub has_license_template: u8,
+    pub bump: u8,
+    /// Where the listed data is stored, once the seller publishes it
+    pub data_pointer: StorageSlot,
+    /// Public sample of the data for buyers to evaluate before purchasing
+    pub preview: StorageSlot,
+    /// SHA-256 of the preview sample as stored
+    pub preview_hash: [u8; 32],
+    pub _padding: [u8; 1],
+    pub created_at: i64,
+    pub sold_at: i64,
+    pub cancelled_at: i64,
+    pub expires_at: i64,
+    /// When the data was last attested fresh; its creation until then
+    pub last_refreshed_at: i64,
+    pub buyer: Pubkey,
+    /// Private listings only: Pedersen commitment to the price, `price * G + salt * H`
+    pub price_commitment: [u8; 32],
+    pub description_hash: [u8; 32],
+    pub union: Pubkey,
+    /// Wallet whose token accounts receive the seller's proceeds
+    pub payout_wallet: Pubkey,
+    /// Hash of the content behind `data_pointer` as of its last update; zeroed while uncommitted
+    pub data_hash: [u8; 32],
+    /// Attests freshness instead of the seller, when set
+    pub freshness_oracle: Pubkey,
+    /// Paid the account's rent, and gets most of it back when the listing is reclaimed
+    pub rent_payer: Pubkey,
+    /// `LicenseTemplate` the listing's sales are licensed under
+    pub license_template: u32,
+    pub allowed_purposes: u16,
+    pub _padding_end: [u8; 2],
+}
+
+impl AccountData for DataListing {
+    const DISCRIMINATOR: [u8; 8] = [75, 232, 231, 86, 134, 68, 100, 8];
+}
+
+/// A `StoragePointer` in a fixed-size slot of a zero-copy account
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StorageSlot {
+    pub is_some: u8,
+    /// `StorageBackend` variant index
+    pub backend: u8,
+    pub reference_len: u8,
+    pub reference: [u8; 128],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListingPreview {
+    pub pointer: StoragePointer,
+    /// SHA-256 of the sample as stored
+    pub hash: [u8; 32],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListingAllowlist {
+    pub listing: Pubkey,
+
+    pub buyers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl AccountData for ListingAllowlist {
+    const DISCRIMINATOR: [u8; 8] = [204, 162, 203, 225, 185, 169, 136, 38];
+}
+
+/// Chunks of a listing's dataset, for data too large for one Arweave transaction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataManifest {
+    pub listing: Pubkey,
+    pub total_size: u64,
+    pub is_sealed: bool,
+    pub manifest_hash: [u8; 32],
+
+    pub chunks: Vec<ManifestChunk>,
+    pub bump: u8,
+}
+
+impl AccountData for DataManifest {
+    const DISCRIMINATOR: [u8; 8] = [123, 119, 218, 249, 133, 115, 65, 245];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ManifestChunk {
+    /// Arweave transaction ID, decoded from its base64url form
+    pub tx_id: [u8; 32],
+    pub size: u64,
+    /// SHA-256 of the stored bytes
+    pub hash: [u8; 32],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PurchaseReceipt {
+    pub listing: Pubkey,
+    pub listing_id: u64,
+    pub buyer: Pubkey,
+    pub payer: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub is_confidential: bool,
+    pub amount_commitment: [u8; 32],
+    pub permission: Pubkey,
+    pub permission_epoch: u32,
+    pub escrowed_amount: u64,
+    pub released_amount: u64,
+    pub refunded_amount: u64,
+    pub refund_window: i64,
+    pub purpose: Purpose,
+    pub watermark_commitment: [u8; 32],
+    /// `LicenseTemplate` the sale was licensed under, if the listing had one
+    pub license_template: Option<u32>,
+    pub purchased_at: i64,
+    /// Paid the account's rent, and gets it back when the listing is reclaimed
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl AccountData for PurchaseReceipt {
+    const DISCRIMINATOR: [u8; 8] = [79, 127, 222, 137, 154, 131, 150, 134];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CrankFund {
+    pub authority: Pubkey,
+    pub reward_per_account: u64,
+    pub total_swept: u64,
+    pub total_paid: u64,
+    pub bump: u8,
+}
+
+impl AccountData for CrankFund {
+    const DISCRIMINATOR: [u8; 8] = [66, 76, 62, 71, 239, 57, 204, 198];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Coupon {
+    pub seller: Pubkey,
+    pub code_hash: [u8; 32],
+    pub discount_basis_points: u16,
+    pub max_uses: u32,
+    pub remaining_uses: u32,
+    pub expires_at: Option<i64>,
+    pub listing: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl AccountData for Coupon {
+    const DISCRIMINATOR: [u8; 8] = [24, 230, 224, 210, 200, 206, 79, 57];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PurchaseDelegate {
+    pub consumer: Pubkey,
+    pub delegate: Pubkey,
+    pub token_account: Pubkey,
+    pub spend_limit: u64,
+    pub spent: u64,
+    pub max_per_purchase: u64,
+    pub expires_at: i64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for PurchaseDelegate {
+    const DISCRIMINATOR: [u8; 8] = [108, 49, 241, 250, 25, 21, 210, 102];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComputeJob {
+    pub job_id: u64,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub provider: Pubkey,
+    pub spec_hash: [u8; 32],
+    pub purpose: Purpose,
+    pub amount: u64,
+    pub status: ComputeJobStatus,
+    pub result_hash: [u8; 32],
+
+    pub result_arweave_tx_id: String,
+    pub created_at: i64,
+    pub result_deadline: i64,
+    pub result_posted_at: Option<i64>,
+    pub max_attestation_age: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for ComputeJob {
+    const DISCRIMINATOR: [u8; 8] = [154, 228, 128, 159, 58, 118, 194, 64];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FlJob {
+    pub consumer: Pubkey,
+    pub job_id: u64,
+    pub data_type: IdentityDataType,
+    pub model_spec_hash: [u8; 32],
+    pub reward_pool: u64,
+    pub max_participants: u32,
+    pub participant_count: u32,
+    pub total_weight: u64,
+    pub opt_in_deadline: i64,
+    pub status: FlJobStatus,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for FlJob {
+    const DISCRIMINATOR: [u8; 8] = [171, 96, 234, 182, 61, 29, 136, 133];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FlParticipant {
+    pub fl_job: Pubkey,
+    pub identity: Pubkey,
+    pub owner: Pubkey,
+    pub permission: Pubkey,
+    pub weight: u64,
+    pub update_hash: [u8; 32],
+    pub claimed: bool,
+    pub bump: u8,
+}
+
+impl AccountData for FlParticipant {
+    const DISCRIMINATOR: [u8; 8] = [149, 166, 157, 65, 168, 222, 17, 29];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataUnion {
+    pub operator: Pubkey,
+    pub union_id: u64,
+    pub vault: Pubkey,
+    pub scope: u16,
+    pub member_count: u32,
+    pub members_in_scope: [u32; 9],
+    pub reward_per_member: [u128; 9],
+    pub total_proceeds: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for DataUnion {
+    const DISCRIMINATOR: [u8; 8] = [131, 39, 198, 204, 214, 239, 93, 168];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnionMember {
+    pub data_union: Pubkey,
+    pub identity: Pubkey,
+    pub owner: Pubkey,
+    pub scope: u16,
+    pub reward_debt: [u128; 9],
+    pub claimable: u64,
+    pub is_active: bool,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for UnionMember {
+    const DISCRIMINATOR: [u8; 8] = [154, 153, 222, 60, 214, 223, 117, 78];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RebateProgram {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub started_at: i64,
+    pub epoch_duration: i64,
+    pub emission_per_epoch: u64,
+    /// Opened epochs' budgets not yet claimed
+    pub reserved: u64,
+    pub bump: u8,
+}
+
+impl AccountData for RebateProgram {
+    const DISCRIMINATOR: [u8; 8] = [96, 225, 199, 9, 193, 67, 202, 177];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RebateEpoch {
+    pub epoch: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub budget: u64,
+    /// Fees credited to every ledger in the epoch; a fee counts once per side that opted in
+    pub fee_credits: u64,
+    pub claimed: u64,
+    pub bump: u8,
+}
+
+impl AccountData for RebateEpoch {
+    const DISCRIMINATOR: [u8; 8] = [156, 53, 8, 107, 181, 216, 175, 161];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RebateLedger {
+    pub epoch: u64,
+    pub participant: Pubkey,
+    pub fee_credits: u64,
+    pub bump: u8,
+}
+
+impl AccountData for RebateLedger {
+    const DISCRIMINATOR: [u8; 8] = [95, 212, 181, 29, 221, 134, 48, 146];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackConfig {
+    pub fee_mint: Pubkey,
+    pub protocol_mint: Pubkey,
+    pub input_vault: Pubkey,
+    pub output_vault: Pubkey,
+    pub parameters: BuybackParameters,
+    pub last_executed_at: i64,
+    pub total_spent: u64,
+    pub total_burned: u64,
+    pub bump: u8,
+}
+
+impl AccountData for BuybackConfig {
+    const DISCRIMINATOR: [u8; 8] = [226, 30, 39, 139, 66, 159, 153, 171];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackParameters {
+    /// Program the swap route is sent to, e.g. Jupiter or Orca
+    pub dex_program: Pubkey,
+    /// Share of the accumulated fee balance each buyback spends
+    pub buyback_bps: u16,
+    /// Seconds between buybacks
+    pub interval: i64,
+    /// Largest shortfall accepted against the caller's quote
+    pub max_slippage_bps: u16,
+    /// Floor on protocol tokens received per fee token, scaled by `PRICE_SCALE`, so a stale or
+    /// low quote cannot sell below it; must be positive
+    pub min_price: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CurationConfig {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub started_at: i64,
+    pub epoch_duration: i64,
+    /// Protocol tokens bonded per vote
+    pub vote_bond: u64,
+    /// Seconds after an epoch ends during which its curated listings may be flagged as disputed
+    pub dispute_window: i64,
+    pub bump: u8,
+}
+
+impl AccountData for CurationConfig {
+    const DISCRIMINATOR: [u8; 8] = [80, 21, 113, 171, 100, 209, 4, 120];
+}
+
+/// The most-voted listings of an epoch, most votes first; front ends feature the set of the last
+/// ended epoch
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeaturedSet {
+    pub epoch: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+
+    pub listings: Vec<FeaturedListing>,
+    pub bump: u8,
+}
+
+impl AccountData for FeaturedSet {
+    const DISCRIMINATOR: [u8; 8] = [244, 203, 107, 160, 156, 196, 112, 128];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeaturedListing {
+    pub listing: Pubkey,
+    pub votes: u64,
+}
+
+/// Curation votes for a listing in an epoch
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListingTally {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    /// Staking weight of every curator who voted
+    pub votes: u64,
+    pub curators: u32,
+    /// When the dispute window closes and bonds can be settled
+    pub settles_at: i64,
+    pub disputed: bool,
+    pub bump: u8,
+}
+
+impl AccountData for ListingTally {
+    const DISCRIMINATOR: [u8; 8] = [178, 216, 12, 185, 214, 126, 167, 179];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CurationVote {
+    pub curator: Pubkey,
+    pub tally: Pubkey,
+    pub weight: u64,
+    pub bond: u64,
+    pub bump: u8,
+}
+
+impl AccountData for CurationVote {
+    const DISCRIMINATOR: [u8; 8] = [60, 140, 152, 125, 105, 68, 42, 192];
+}
+
+/// Machine-readable terms a buyer takes a listing's data under
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LicenseTerms {
+    pub commercial_use: bool,
+    /// Use is limited to research; excludes commercial use
+    pub research_only: bool,
+    /// Buyers may pass the data on to third parties
+    pub redistribution: bool,
+    /// Days buyers may keep the data after purchase; unlimited when unset
+    pub retention_days: Option<u32>,
+}
+
+/// License terms maintained by the marketplace authority (governance) for listings to reference
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LicenseTemplate {
+    pub template_id: u32,
+    pub terms: LicenseTerms,
+    /// SHA-256 of the full license text the terms summarize
+    pub terms_hash: [u8; 32],
+    pub is_active: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for LicenseTemplate {
+    const DISCRIMINATOR: [u8; 8] = [123, 77, 27, 80, 165, 205, 133, 179];
+}
+
+/// A key the marketplace authority trusts to check listings' stored data
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StorageOracle {
+    pub oracle: Pubkey,
+    pub check_count: u64,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+impl AccountData for StorageOracle {
+    const DISCRIMINATOR: [u8; 8] = [178, 209, 51, 239, 160, 241, 30, 214];
+}
+
+/// Subsystems enabled on this cluster, one bit each
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeatureFlags {
+    pub flags: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl FeatureFlags {
+    pub const COMPUTE_TO_DATA: u64 = 1 << 0;
+
+    pub fn is_enabled(&self, flag: u64) -> bool {
+        self.flags & flag == flag
+    }
+}
+
+impl AccountData for FeatureFlags {
+    const DISCRIMINATOR: [u8; 8] = [36, 26, 173, 194, 167, 151, 43, 210];
+}
+
+/// Open, high, low and close sale prices of one data type in one settlement mint over a UTC day
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PriceCandle {
+    pub mint: Pubkey,
+    /// Position of the data type sold; all custom types share one
+    pub data_type_index: u8,
+    /// Days since the unix epoch
+    pub day: i64,
+    pub open: u64,
+    pub high: u64,
+    pub low: u64,
+    pub close: u64,
+    pub volume: u64,
+    pub trade_count: u32,
+    pub bump: u8,
+}
+
+impl AccountData for PriceCandle {
+    const DISCRIMINATOR: [u8; 8] = [75, 82, 52, 156, 7, 240, 135, 197];
+}
+
+/// Lifetime revenue an identity's data has earned its owner, net of marketplace fees
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EarningsLedger {
+    pub identity: Pubkey,
+    /// Earnings per data type position; all custom types share one
+    pub by_data_type: [u64; 9],
+    pub by_consumer: Vec<ConsumerEarnings>,
+    /// Earnings from consumers beyond the first 32
+    pub other_consumers: u64,
+    pub total: u64,
+    pub payment_count: u32,
+    pub last_paid_at: i64,
+    pub bump: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerEarnings {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub payment_count: u32,
+}
+
+impl AccountData for EarningsLedger {
+    const DISCRIMINATOR: [u8; 8] = [91, 144, 51, 242, 58, 224, 8, 188];
+}
+
+/// Outcome of the latest storage oracle check of a listing's data
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StorageHealth {
+    pub listing: Pubkey,
+    pub data_hash: [u8; 32],
+    pub last_checked_at: i64,
+    pub last_oracle: Pubkey,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub check_count: u64,
+    pub bump: u8,
+}
+
+impl AccountData for StorageHealth {
+    const DISCRIMINATOR: [u8; 8] = [92, 177, 204, 6, 15, 89, 201, 63];
+}
+
+/// Opening of a private listing's price commitment
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PriceReveal {
+    pub price: u64,
+    /// The commitment's blinding scalar, little-endian
+    pub salt: [u8; 32],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DataType {
+    LocationHistory,
+    AppUsage,
+    PurchaseHistory,
+    HealthData,
+    SocialMediaActivity,
+    SearchHistory,
+    Custom(String),
+    // Appended after Custom to keep existing variant indices stable
+    FinancialData,
+    CommunicationData,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ComputeJobStatus {
+    Pending,
+    ResultPosted,
+    Completed,
+    Refunded,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum FlJobStatus {
+    Open,
+    Finalized,
+    Cancelled,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataTypeHaltUpdatedEvent {
+    pub data_type: DataType,
+    pub halted: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DataTypeHaltUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [250, 160, 69, 47, 254, 13, 32, 177];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeatureFlagsUpdatedEvent {
+    pub flags: u64,
+    pub updated_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for FeatureFlagsUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [223, 1, 203, 3, 186, 82, 235, 73];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExpiredSweptEvent {
+    pub caller: Pubkey,
+    pub swept: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ExpiredSweptEvent {
+    const DISCRIMINATOR: [u8; 8] = [191, 142, 195, 209, 169, 84, 85, 229];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RentReclaimedEvent {
+    pub account: Pubkey,
+    pub rent_payer: Pubkey,
+    pub caller: Pubkey,
+    pub refund: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for RentReclaimedEvent {
+    const DISCRIMINATOR: [u8; 8] = [120, 78, 100, 109, 136, 74, 173, 130];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SettlementMintsUpdatedEvent {
+    pub stablecoin_only: bool,
+    pub approved_mints: Vec<Pubkey>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for SettlementMintsUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [144, 251, 92, 224, 79, 154, 238, 108];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RevocationRefundedEvent {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub revoked_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for RevocationRefundedEvent {
+    const DISCRIMINATOR: [u8; 8] = [114, 74, 94, 206, 239, 138, 49, 255];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComputeJobCreatedEvent {
+    pub job: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub provider: Pubkey,
+    pub spec_hash: [u8; 32],
+    pub amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ComputeJobCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [129, 231, 11, 104, 118, 142, 148, 40];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ComputeResultPostedEvent {
+    pub job: Pubkey,
+    pub result_hash: [u8; 32],
+    pub result_arweave_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ComputeResultPostedEvent {
+    const DISCRIMINATOR: [u8; 8] = [219, 93, 35, 246, 217, 195, 229, 6];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FlJobCreatedEvent {
+    pub fl_job: Pubkey,
+    pub consumer: Pubkey,
+    pub model_spec_hash: [u8; 32],
+    pub reward_pool: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for FlJobCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [48, 152, 102, 248, 11, 156, 121, 231];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FlContributionRecordedEvent {
+    pub fl_job: Pubkey,
+    pub identity: Pubkey,
+    pub weight: u64,
+    pub update_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for FlContributionRecordedEvent {
+    const DISCRIMINATOR: [u8; 8] = [154, 20, 196, 106, 131, 34, 78, 42];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataUnionMembershipEvent {
+    pub data_union: Pubkey,
+    pub identity: Pubkey,
+    pub scope: u16,
+    pub joined: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DataUnionMembershipEvent {
+    const DISCRIMINATOR: [u8; 8] = [125, 95, 204, 103, 120, 180, 189, 14];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PurchaseWatermarkEvent {
+    pub receipt: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub watermark_commitment: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for PurchaseWatermarkEvent {
+    const DISCRIMINATOR: [u8; 8] = [149, 51, 35, 15, 196, 85, 31, 75];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarketplaceFeeUpdatedEvent {
+    pub fee_basis_points: u16,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for MarketplaceFeeUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [201, 191, 142, 78, 109, 146, 244, 128];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarketplaceAuthorityChangedEvent {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for MarketplaceAuthorityChangedEvent {
+    const DISCRIMINATOR: [u8; 8] = [1, 242, 243, 242, 201, 37, 82, 49];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RebateEmissionUpdatedEvent {
+    pub emission_per_epoch: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for RebateEmissionUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [186, 243, 96, 131, 154, 40, 236, 166];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RebateClaimedEvent {
+    pub epoch: u64,
+    pub participant: Pubkey,
+    pub fee_credits: u64,
+    pub amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for RebateClaimedEvent {
+    const DISCRIMINATOR: [u8; 8] = [72, 199, 246, 127, 146, 81, 179, 23];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackExecutedEvent {
+    pub spent: u64,
+    pub burned: u64,
+    pub executed_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for BuybackExecutedEvent {
+    const DISCRIMINATOR: [u8; 8] = [226, 67, 5, 180, 55, 80, 51, 251];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListingCuratedEvent {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    pub curator: Pubkey,
+    pub weight: u64,
+    pub votes: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ListingCuratedEvent {
+    const DISCRIMINATOR: [u8; 8] = [134, 106, 148, 243, 170, 235, 83, 60];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CuratedListingDisputedEvent {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    pub curators: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for CuratedListingDisputedEvent {
+    const DISCRIMINATOR: [u8; 8] = [184, 148, 176, 109, 153, 14, 236, 204];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CurationVoteSettledEvent {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    pub curator: Pubkey,
+    pub bond: u64,
+    pub forfeited: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for CurationVoteSettledEvent {
+    const DISCRIMINATOR: [u8; 8] = [241, 164, 105, 101, 52, 78, 21, 123];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListingFreshnessAttestedEvent {
+    pub listing: Pubkey,
+    pub attestor: Pubkey,
+    pub refreshed_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ListingFreshnessAttestedEvent {
+    const DISCRIMINATOR: [u8; 8] = [32, 80, 248, 39, 88, 192, 149, 2];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListingDataUpdatedEvent {
+    pub listing: Pubkey,
+    pub data_pointer: StoragePointer,
+    pub previous_hash: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub updated_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for ListingDataUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [71, 221, 150, 253, 241, 167, 241, 246];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LicenseTemplateCreatedEvent {
+    pub template_id: u32,
+    pub terms: LicenseTerms,
+    pub terms_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for LicenseTemplateCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [182, 89, 79, 31, 25, 127, 190, 151];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LicenseMintedEvent {
+    pub receipt: Pubkey,
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub template_id: u32,
+    pub transferable: bool,
+    pub expires_at: Option<i64>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for LicenseMintedEvent {
+    const DISCRIMINATOR: [u8; 8] = [124, 7, 105, 6, 106, 69, 227, 223];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessRightTransferredEvent {
+    pub receipt: Pubkey,
+    pub listing: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub transferred_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for AccessRightTransferredEvent {
+    const DISCRIMINATOR: [u8; 8] = [87, 221, 82, 127, 129, 182, 238, 16];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StorageCheckedEvent {
+    pub listing: Pubkey,
+    pub oracle: Pubkey,
+    pub data_hash: [u8; 32],
+    pub healthy: bool,
+    pub checked_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for StorageCheckedEvent {
+    const DISCRIMINATOR: [u8; 8] = [251, 103, 39, 198, 214, 168, 65, 108];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataManifestSealedEvent {
+    pub listing: Pubkey,
+    pub chunk_count: u32,
+    pub total_size: u64,
+    pub manifest_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+impl EventData for DataManifestSealedEvent {
+    const DISCRIMINATOR: [u8; 8] = [13, 74, 57, 242, 87, 46, 15, 51];
+}
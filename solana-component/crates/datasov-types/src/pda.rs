@@ -0,0 +1,524 @@
+//! Program-derived addresses, mirroring `datasov_client::pda` without the Solana SDK.
+//!
+//! Derivation follows the runtime: SHA-256 of the seeds, program ID and `"ProgramDerivedAddress"`,
+//! trying bumps from 255 down until the hash is off the Ed25519 curve.
+
+use core::str::FromStr;
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+
+use crate::Pubkey;
+
+/// Most seeds an address may be derived from, the bump included
+pub const MAX_SEEDS: usize = 16;
+
+/// Longest a single seed may be
+pub const MAX_SEED_LEN: usize = 32;
+
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// Address of `seeds` under `program_id`, or `None` if the seeds are too many or too long, or
+/// the hash lands on the curve
+pub fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<Pubkey> {
+    if seeds.len() > MAX_SEEDS || seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+        return None;
+    }
+    let mut hasher = Sha256::new();
+    for seed in seeds {
+        hasher.update(seed);
+    }
+    hasher.update(program_id);
+    hasher.update(PDA_MARKER);
+    let hash: [u8; 32] = hasher.finalize().into();
+    match CompressedEdwardsY(hash).decompress() {
+        Some(_) => None,
+        None => Some(Pubkey(hash)),
+    }
+}
+
+/// Address and bump of `seeds` under `program_id`, with the highest bump that is off the curve.
+/// Panics, like the runtime, if no bump yields an address.
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    assert!(seeds.len() < MAX_SEEDS, "too many seeds to add a bump");
+    for bump in (1..=u8::MAX).rev() {
+        let bump_seed = [bump];
+        let mut with_bump = [&[][..]; MAX_SEEDS];
+        with_bump[..seeds.len()].copy_from_slice(seeds);
+        with_bump[seeds.len()] = &bump_seed;
+        if let Some(address) = create_program_address(&with_bump[..=seeds.len()], program_id) {
+            return (address, bump);
+        }
+    }
+    panic!("no viable bump seed");
+}
+
+fn find(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    find_program_address(seeds, program_id).0
+}
+
+fn program(id: &str) -> Pubkey {
+    Pubkey::from_str(id).expect("program ID is base58")
+}
+
+/// PDAs owned by `datasov_identity`
+pub mod identity {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        program("DataSovIdentity11111111111111111111111111111")
+    }
+
+    /// Seed of an identity ID: its SHA-256, so IDs longer than a seed still derive an address
+    pub fn identity_id_seed(identity_id: &str) -> [u8; 32] {
+        Sha256::digest(identity_id.as_bytes()).into()
+    }
+
+    pub fn oracle_registry() -> Pubkey {
+        find(&[b"oracle_registry"], &program_id())
+    }
+
+    pub fn oracle(authority: &Pubkey) -> Pubkey {
+        find(&[b"oracle", authority.as_ref()], &program_id())
+    }
+
+    pub fn identity(identity_id: &str) -> Pubkey {
+        find(&[b"identity", &identity_id_seed(identity_id)], &program_id())
+    }
+
+    pub fn permission(identity: &Pubkey, consumer: &Pubkey) -> Pubkey {
+        find(&[b"permission", identity.as_ref(), consumer.as_ref()], &program_id())
+    }
+
+    pub fn trial_record(consumer: &Pubkey) -> Pubkey {
+        find(&[b"trial", consumer.as_ref()], &program_id())
+    }
+
+    pub fn compliance_matrix() -> Pubkey {
+        find(&[b"compliance_matrix"], &program_id())
+    }
+
+    pub fn crank_fund() -> Pubkey {
+        find(&[b"crank_fund"], &program_id())
+    }
+
+    pub fn blacklist() -> Pubkey {
+        find(&[b"blacklist"], &program_id())
+    }
+
+    pub fn consumer_profile(consumer: &Pubkey) -> Pubkey {
+        find(&[b"consumer", consumer.as_ref()], &program_id())
+    }
+
+    pub fn consumer_stake(consumer: &Pubkey) -> Pubkey {
+        find(&[b"consumer_stake", consumer.as_ref()], &program_id())
+    }
+
+    pub fn juror_pool() -> Pubkey {
+        find(&[b"juror_pool"], &program_id())
+    }
+
+    pub fn juror(authority: &Pubkey) -> Pubkey {
+        find(&[b"juror", authority.as_ref()], &program_id())
+    }
+
+    pub fn dispute(dispute_id: u64) -> Pubkey {
+        find(&[b"dispute", &dispute_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn tee_attestation(provider: &Pubkey) -> Pubkey {
+        find(&[b"tee_attestation", provider.as_ref()], &program_id())
+    }
+
+    pub fn data_trust(creator: &Pubkey, trust_id: u64) -> Pubkey {
+        find(&[b"trust", creator.as_ref(), &trust_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn trust_membership(data_trust: &Pubkey, identity: &Pubkey) -> Pubkey {
+        find(
+            &[b"trust_member", data_trust.as_ref(), identity.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn export_request(identity: &Pubkey, consumer: &Pubkey) -> Pubkey {
+        find(&[b"export", identity.as_ref(), consumer.as_ref()], &program_id())
+    }
+
+    pub fn consumption_attestation(permission: &Pubkey, sequence: u32) -> Pubkey {
+        find(
+            &[b"consumption", permission.as_ref(), &sequence.to_le_bytes()],
+            &program_id(),
+        )
+    }
+
+    pub fn web_proof_verifier(verifier_id: u32) -> Pubkey {
+        find(&[b"web_proof_verifier", &verifier_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn web_proof_nullifier(verifier: &Pubkey, nullifier: &[u8; 32]) -> Pubkey {
+        find(&[b"web_proof_nullifier", verifier.as_ref(), nullifier], &program_id())
+    }
+
+    pub fn attestation_source(issuer: &Pubkey, schema: &Pubkey) -> Pubkey {
+        find(
+            &[b"attestation_source", issuer.as_ref(), schema.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn domain_link(domain_record: &Pubkey) -> Pubkey {
+        find(&[b"domain_link", domain_record.as_ref()], &program_id())
+    }
+
+    pub fn voucher_nonce(identity: &Pubkey, nonce: u64) -> Pubkey {
+        find(
+            &[b"voucher_nonce", identity.as_ref(), &nonce.to_le_bytes()],
+            &program_id(),
+        )
+    }
+
+    pub fn access_request(identity: &Pubkey, consumer: &Pubkey) -> Pubkey {
+        find(&[b"access_request", identity.as_ref(), consumer.as_ref()], &program_id())
+    }
+
+    pub fn state_snapshot() -> Pubkey {
+        find(&[b"state_snapshot"], &program_id())
+    }
+
+    pub fn compliance_officer(officer: &Pubkey) -> Pubkey {
+        find(&[b"compliance_officer", officer.as_ref()], &program_id())
+    }
+
+    pub fn identity_freeze(identity: &Pubkey) -> Pubkey {
+        find(&[b"identity_freeze", identity.as_ref()], &program_id())
+    }
+
+    pub fn audit_writer() -> Pubkey {
+        find(&[b"audit_writer"], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_solana`
+pub mod marketplace {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        program("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS")
+    }
+
+    pub fn marketplace() -> Pubkey {
+        find(&[b"marketplace"], &program_id())
+    }
+
+    pub fn crank_fund() -> Pubkey {
+        find(&[b"crank_fund"], &program_id())
+    }
+
+    pub fn listing(listing_id: u64) -> Pubkey {
+        find(&[b"listing", &listing_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn allowlist(listing: &Pubkey) -> Pubkey {
+        find(&[b"allowlist", listing.as_ref()], &program_id())
+    }
+
+    pub fn data_manifest(listing: &Pubkey) -> Pubkey {
+        find(&[b"data_manifest", listing.as_ref()], &program_id())
+    }
+
+    pub fn receipt(listing: &Pubkey) -> Pubkey {
+        find(&[b"receipt", listing.as_ref()], &program_id())
+    }
+
+    pub fn escrow(listing: &Pubkey) -> Pubkey {
+        find(&[b"escrow", listing.as_ref()], &program_id())
+    }
+
+    pub fn coupon(seller: &Pubkey, code_hash: &[u8; 32]) -> Pubkey {
+        find(&[b"coupon", seller.as_ref(), code_hash], &program_id())
+    }
+
+    pub fn purchase_delegate(consumer: &Pubkey, delegate: &Pubkey) -> Pubkey {
+        find(
+            &[b"purchase_delegate", consumer.as_ref(), delegate.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn compute_job(listing: &Pubkey, buyer: &Pubkey, job_id: u64) -> Pubkey {
+        find(
+            &[b"compute_job", listing.as_ref(), buyer.as_ref(), &job_id.to_le_bytes()],
+            &program_id(),
+        )
+    }
+
+    pub fn job_escrow(compute_job: &Pubkey) -> Pubkey {
+        find(&[b"job_escrow", compute_job.as_ref()], &program_id())
+    }
+
+    pub fn fl_job(consumer: &Pubkey, job_id: u64) -> Pubkey {
+        find(&[b"fl_job", consumer.as_ref(), &job_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn fl_escrow(fl_job: &Pubkey) -> Pubkey {
+        find(&[b"fl_escrow", fl_job.as_ref()], &program_id())
+    }
+
+    pub fn fl_participant(fl_job: &Pubkey, identity: &Pubkey) -> Pubkey {
+        find(&[b"fl_participant", fl_job.as_ref(), identity.as_ref()], &program_id())
+    }
+
+    pub fn data_union(operator: &Pubkey, union_id: u64) -> Pubkey {
+        find(&[b"union", operator.as_ref(), &union_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn union_vault(data_union: &Pubkey) -> Pubkey {
+        find(&[b"union_vault", data_union.as_ref()], &program_id())
+    }
+
+    pub fn union_member(data_union: &Pubkey, identity: &Pubkey) -> Pubkey {
+        find(
+            &[b"union_member", data_union.as_ref(), identity.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn rebate_program() -> Pubkey {
+        find(&[b"rebate_program"], &program_id())
+    }
+
+    pub fn rebate_vault() -> Pubkey {
+        find(&[b"rebate_vault"], &program_id())
+    }
+
+    pub fn rebate_epoch(epoch: u64) -> Pubkey {
+        find(&[b"rebate_epoch", epoch.to_le_bytes().as_ref()], &program_id())
+    }
+
+    pub fn rebate_ledger(epoch: u64, participant: &Pubkey) -> Pubkey {
+        find(
+            &[b"rebate_ledger", epoch.to_le_bytes().as_ref(), participant.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn buyback() -> Pubkey {
+        find(&[b"buyback"], &program_id())
+    }
+
+    pub fn buyback_input() -> Pubkey {
+        find(&[b"buyback_input"], &program_id())
+    }
+
+    pub fn buyback_output() -> Pubkey {
+        find(&[b"buyback_output"], &program_id())
+    }
+
+    pub fn curation() -> Pubkey {
+        find(&[b"curation"], &program_id())
+    }
+
+    pub fn curation_vault() -> Pubkey {
+        find(&[b"curation_vault"], &program_id())
+    }
+
+    pub fn featured_set(epoch: u64) -> Pubkey {
+        find(&[b"featured_set", epoch.to_le_bytes().as_ref()], &program_id())
+    }
+
+    pub fn listing_tally(epoch: u64, listing: &Pubkey) -> Pubkey {
+        find(
+            &[b"listing_tally", epoch.to_le_bytes().as_ref(), listing.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn curation_vote(tally: &Pubkey, curator: &Pubkey) -> Pubkey {
+        find(&[b"curation_vote", tally.as_ref(), curator.as_ref()], &program_id())
+    }
+
+    pub fn storage_oracle(oracle: &Pubkey) -> Pubkey {
+        find(&[b"storage_oracle", oracle.as_ref()], &program_id())
+    }
+
+    pub fn storage_health(listing: &Pubkey) -> Pubkey {
+        find(&[b"storage_health", listing.as_ref()], &program_id())
+    }
+
+    pub fn license_template(template_id: u32) -> Pubkey {
+        find(&[b"license_template", &template_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn license_mint(receipt: &Pubkey) -> Pubkey {
+        find(&[b"license_mint", receipt.as_ref()], &program_id())
+    }
+
+    pub fn price_candle(mint: &Pubkey, data_type_index: u8, day: i64) -> Pubkey {
+        find(
+            &[
+                b"price_candle",
+                mint.as_ref(),
+                &[data_type_index],
+                day.to_le_bytes().as_ref(),
+            ],
+            &program_id(),
+        )
+    }
+
+    pub fn earnings_ledger(identity: &Pubkey) -> Pubkey {
+        find(&[b"earnings_ledger", identity.as_ref()], &program_id())
+    }
+
+    pub fn feature_flags() -> Pubkey {
+        find(&[b"feature_flags"], &program_id())
+    }
+
+    pub fn audit_writer() -> Pubkey {
+        find(&[b"audit_writer"], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_audit`
+pub mod audit {
+    use super::*;
+
+    /// Entry slots per `AuditPage`
+    pub const PAGE_ENTRIES: u64 = 16;
+
+    pub fn program_id() -> Pubkey {
+        program("AvUNiG4Nhv6PUSLrYzqpKs5cFHrs5weifwyBoin45TWW")
+    }
+
+    pub fn audit_log(subject: &Pubkey) -> Pubkey {
+        find(&[b"audit_log", subject.as_ref()], &program_id())
+    }
+
+    pub fn audit_page(audit_log: &Pubkey, page_index: u64) -> Pubkey {
+        find(
+            &[b"audit_page", audit_log.as_ref(), &page_index.to_le_bytes()],
+            &program_id(),
+        )
+    }
+
+    /// Page that the log's next entry lands on, given its current entry count
+    pub fn next_audit_page(audit_log: &Pubkey, entry_count: u64) -> Pubkey {
+        audit_page(audit_log, entry_count / PAGE_ENTRIES)
+    }
+
+    pub fn audit_checkpoint(audit_log: &Pubkey, epoch: u64) -> Pubkey {
+        find(
+            &[b"audit_checkpoint", audit_log.as_ref(), &epoch.to_le_bytes()],
+            &program_id(),
+        )
+    }
+}
+
+/// PDAs owned by `datasov_governance`
+pub mod governance {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        program("AAM7nwhGKLEqhzGrCKJyTbSMPeUUhSTVJ4hkbPp3DsGg")
+    }
+
+    pub fn governance() -> Pubkey {
+        find(&[b"governance"], &program_id())
+    }
+
+    pub fn stake(staker: &Pubkey) -> Pubkey {
+        find(&[b"stake", staker.as_ref()], &program_id())
+    }
+
+    pub fn proposal(proposal_id: u64) -> Pubkey {
+        find(&[b"proposal", &proposal_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn vote(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+        find(&[b"vote", proposal.as_ref(), voter.as_ref()], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_timelock`
+pub mod timelock {
+    use super::*;
+
+    pub fn program_id() -> Pubkey {
+        program("22ayit18pgtWvxLH1yzrJiR7FhAdu99cGu8Dj6Zr9vLT")
+    }
+
+    /// Also the upgrade and admin authority of the programs under the timelock
+    pub fn timelock() -> Pubkey {
+        find(&[b"timelock"], &program_id())
+    }
+
+    pub fn operation(operation_id: u64) -> Pubkey {
+        find(&[b"operation", &operation_id.to_le_bytes()], &program_id())
+    }
+}
+
+/// PDAs owned by `datasov_staking`
+pub mod staking {
+    use super::*;
+    use crate::staking::StakeRole;
+
+    pub fn program_id() -> Pubkey {
+        program("E3XWsHo9j4KEBkFFtEm4KFTJB2zKCGbrJ3iiutnijvvH")
+    }
+
+    pub fn config() -> Pubkey {
+        find(&[b"staking_config"], &program_id())
+    }
+
+    /// Token account holding every staked token
+    pub fn vault() -> Pubkey {
+        find(&[b"staking_vault"], &program_id())
+    }
+
+    pub fn backing(role: StakeRole, subject: &Pubkey) -> Pubkey {
+        find(&[b"backing", &[role as u8], subject.as_ref()], &program_id())
+    }
+
+    pub fn position(backing: &Pubkey, staker: &Pubkey) -> Pubkey {
+        find(&[b"position", backing.as_ref(), staker.as_ref()], &program_id())
+    }
+
+    pub fn stake_weight(staker: &Pubkey) -> Pubkey {
+        find(&[b"stake_weight", staker.as_ref()], &program_id())
+    }
+
+    pub fn dispute_slash(dispute: &Pubkey, backing: &Pubkey) -> Pubkey {
+        find(&[b"dispute_slash", dispute.as_ref(), backing.as_ref()], &program_id())
+    }
+
+    pub fn vesting(beneficiary: &Pubkey, vesting_id: u64) -> Pubkey {
+        find(
+            &[b"vesting", beneficiary.as_ref(), &vesting_id.to_le_bytes()],
+            &program_id(),
+        )
+    }
+
+    pub fn vesting_vault(vesting: &Pubkey) -> Pubkey {
+        find(&[b"vesting_vault", vesting.as_ref()], &program_id())
+    }
+
+    pub fn airdrop(airdrop_id: u64) -> Pubkey {
+        find(&[b"airdrop", &airdrop_id.to_le_bytes()], &program_id())
+    }
+
+    pub fn airdrop_vault(airdrop: &Pubkey) -> Pubkey {
+        find(&[b"airdrop_vault", airdrop.as_ref()], &program_id())
+    }
+
+    /// Allocations per airdrop bitmap chunk
+    pub const AIRDROP_BITMAP_BITS: u32 = 8 * 1024;
+
+    /// Claimed flags of the chunk of allocations holding `index`
+    pub fn airdrop_bitmap(airdrop: &Pubkey, index: u32) -> Pubkey {
+        let chunk = index / AIRDROP_BITMAP_BITS;
+        find(
+            &[b"airdrop_bitmap", airdrop.as_ref(), &chunk.to_le_bytes()],
+            &program_id(),
+        )
+    }
+}
@@ -0,0 +1,281 @@
+//! Types of the `datasov_staking` program.
+
+use crate::{AccountData, EventData, Pubkey};
+use alloc::{string::String, vec::Vec};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakingConfig {
+    pub authority: Pubkey,
+    /// The protocol token
+    pub mint: Pubkey,
+    /// May lock stake behind a vote; the governance PDA
+    pub vote_authority: Pubkey,
+    /// Seconds between an unstake request and the withdrawal
+    pub unstake_cooldown: i64,
+    /// Share of a consumer's backing slashed per upheld dispute, in basis points
+    pub consumer_slash_bps: u16,
+    /// Share of a juror's backing slashed per dispute the juror voted against or missed
+    pub juror_slash_bps: u16,
+    /// Marketplace fee discounts by staking weight, lowest tier first
+    pub fee_tiers: Vec<FeeTier>,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+impl AccountData for StakingConfig {
+    const DISCRIMINATOR: [u8; 8] = [45, 134, 252, 82, 37, 57, 84, 25];
+}
+
+/// Stake behind one oracle, consumer or juror, pooled across its backers
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Backing {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    /// Tokens backing the subject, net of slashes
+    pub amount: u64,
+    /// Shares issued to backers; each is worth `amount / shares` tokens
+    pub shares: u64,
+    pub slashed_total: u64,
+    pub bump: u8,
+}
+
+impl AccountData for Backing {
+    const DISCRIMINATOR: [u8; 8] = [183, 154, 102, 51, 83, 11, 152, 65];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakePosition {
+    pub staker: Pubkey,
+    pub backing: Pubkey,
+    pub shares: u64,
+    /// Value of the shares not under cooldown when last synced, as counted in the staker's weight
+    pub weight: u64,
+    /// Shares under cooldown, withdrawable at `unlock_at`
+    pub unstaking_shares: u64,
+    pub unlock_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl AccountData for StakePosition {
+    const DISCRIMINATOR: [u8; 8] = [78, 165, 30, 111, 171, 125, 11, 220];
+}
+
+/// A staker's weight across positions: their governance voting power and fee tier
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakeWeight {
+    pub owner: Pubkey,
+    pub weight: u64,
+    /// End of the latest vote the weight was cast in; unstaking waits until then
+    pub locked_until: i64,
+    pub bump: u8,
+}
+
+impl AccountData for StakeWeight {
+    const DISCRIMINATOR: [u8; 8] = [58, 50, 210, 120, 53, 105, 70, 182];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DisputeSlash {
+    pub dispute: Pubkey,
+    pub backing: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl AccountData for DisputeSlash {
+    const DISCRIMINATOR: [u8; 8] = [12, 225, 38, 8, 153, 29, 63, 232];
+}
+
+/// Protocol tokens paid out to a beneficiary on a cliff and linear schedule
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Vesting {
+    pub beneficiary: Pubkey,
+    /// Paid in the tokens; unvested tokens return to it on revocation
+    pub funder: Pubkey,
+    pub vesting_id: u64,
+    pub kind: VestingKind,
+    /// Tokens the schedule pays in all, cut to what had vested on revocation
+    pub total: u64,
+    pub claimed: u64,
+    /// Vesting accrues linearly from `start_at` to `end_at`
+    pub start_at: i64,
+    /// Nothing is claimable before the cliff
+    pub cliff_at: i64,
+    pub end_at: i64,
+    pub revoked_at: Option<i64>,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl AccountData for Vesting {
+    const DISCRIMINATOR: [u8; 8] = [100, 149, 66, 138, 95, 200, 128, 241];
+}
+
+/// Protocol tokens allocated by a Merkle tree of (wallet, amount) leaves, claimed with proofs
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Airdrop {
+    pub airdrop_id: u64,
+    pub merkle_root: [u8; 32],
+    /// Allocations in the tree; proofs are as deep as the smallest power of two that holds them
+    pub leaf_count: u32,
+    pub claimed_count: u32,
+    pub claimed_amount: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl AccountData for Airdrop {
+    const DISCRIMINATOR: [u8; 8] = [31, 112, 159, 158, 124, 237, 9, 241];
+}
+
+/// Claimed flags of one chunk of an airdrop's allocations
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropBitmap {
+    pub airdrop: Pubkey,
+    pub chunk: u32,
+    pub bits: [u8; 1024],
+    pub bump: u8,
+}
+
+impl AccountData for AirdropBitmap {
+    const DISCRIMINATOR: [u8; 8] = [176, 254, 63, 238, 211, 51, 27, 129];
+}
+
+/// What a backing stands behind
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StakeRole {
+    Oracle,
+    Consumer,
+    Juror,
+}
+
+/// What a vesting pays for
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VestingKind {
+    /// Long-term incentive for running a KYC oracle
+    OracleIncentive,
+    /// Allocation to a protocol contributor
+    ContributorAllocation,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeeTier {
+    /// Least staking weight that qualifies
+    pub min_weight: u64,
+    /// Discount off the marketplace fee, in basis points
+    pub discount_bps: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakedEvent {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+impl EventData for StakedEvent {
+    const DISCRIMINATOR: [u8; 8] = [81, 221, 186, 176, 240, 160, 193, 69];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakeWithdrawnEvent {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+impl EventData for StakeWithdrawnEvent {
+    const DISCRIMINATOR: [u8; 8] = [81, 16, 131, 169, 191, 220, 177, 238];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BackingSlashedEvent {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub evidence_tx_id: String,
+}
+
+impl EventData for BackingSlashedEvent {
+    const DISCRIMINATOR: [u8; 8] = [143, 79, 141, 210, 217, 175, 163, 13];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeeTiersUpdatedEvent {
+    pub fee_tiers: Vec<FeeTier>,
+}
+
+impl EventData for FeeTiersUpdatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [60, 23, 248, 104, 80, 137, 173, 41];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestingCreatedEvent {
+    pub beneficiary: Pubkey,
+    pub vesting_id: u64,
+    pub kind: VestingKind,
+    pub total: u64,
+    pub start_at: i64,
+    pub cliff_at: i64,
+    pub end_at: i64,
+}
+
+impl EventData for VestingCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [4, 165, 166, 177, 42, 4, 74, 121];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestedClaimedEvent {
+    pub beneficiary: Pubkey,
+    pub vesting_id: u64,
+    pub amount: u64,
+    /// Claimed over the vesting's life, this claim included
+    pub claimed: u64,
+}
+
+impl EventData for VestedClaimedEvent {
+    const DISCRIMINATOR: [u8; 8] = [185, 244, 185, 6, 161, 102, 249, 116];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestingRevokedEvent {
+    pub beneficiary: Pubkey,
+    pub vesting_id: u64,
+    /// Tokens vested at revocation, which stay claimable
+    pub vested: u64,
+    /// Unvested tokens returned to the funder
+    pub returned: u64,
+}
+
+impl EventData for VestingRevokedEvent {
+    const DISCRIMINATOR: [u8; 8] = [215, 33, 53, 32, 218, 231, 244, 80];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropCreatedEvent {
+    pub airdrop_id: u64,
+    pub merkle_root: [u8; 32],
+    pub leaf_count: u32,
+}
+
+impl EventData for AirdropCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [34, 35, 133, 242, 182, 253, 47, 61];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AirdropClaimedEvent {
+    pub airdrop_id: u64,
+    pub index: u32,
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+impl EventData for AirdropClaimedEvent {
+    const DISCRIMINATOR: [u8; 8] = [152, 147, 197, 184, 147, 73, 167, 29];
+}
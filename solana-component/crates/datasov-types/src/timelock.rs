@@ -0,0 +1,104 @@
+//! Types of the `datasov_timelock` program.
+
+use crate::{AccountData, EventData, Pubkey};
+use alloc::vec::Vec;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Timelock {
+    /// Queues and cancels operations
+    pub admin: Pubkey,
+    /// Seconds between queueing an operation and when it can execute
+    pub delay: i64,
+    pub operation_count: u64,
+    pub bump: u8,
+}
+
+impl AccountData for Timelock {
+    const DISCRIMINATOR: [u8; 8] = [189, 33, 78, 75, 205, 31, 4, 177];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Operation {
+    pub id: u64,
+    pub program_id: Pubkey,
+
+    pub accounts: Vec<OperationAccount>,
+
+    pub data: Vec<u8>,
+    pub queued_at: i64,
+    /// Earliest time the operation can execute
+    pub eta: i64,
+    pub status: OperationStatus,
+    pub bump: u8,
+}
+
+impl AccountData for Operation {
+    const DISCRIMINATOR: [u8; 8] = [171, 150, 196, 17, 229, 166, 58, 44];
+}
+
+/// An account of a queued instruction; the `Timelock` PDA is its signer
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperationAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum OperationStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperationQueuedEvent {
+    pub operation_id: u64,
+    pub program_id: Pubkey,
+    pub accounts: Vec<OperationAccount>,
+    pub data: Vec<u8>,
+    pub eta: i64,
+}
+
+impl EventData for OperationQueuedEvent {
+    const DISCRIMINATOR: [u8; 8] = [139, 199, 70, 192, 74, 63, 124, 120];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperationCancelledEvent {
+    pub operation_id: u64,
+}
+
+impl EventData for OperationCancelledEvent {
+    const DISCRIMINATOR: [u8; 8] = [149, 8, 193, 1, 100, 119, 102, 122];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperationExecutedEvent {
+    pub operation_id: u64,
+}
+
+impl EventData for OperationExecutedEvent {
+    const DISCRIMINATOR: [u8; 8] = [210, 224, 175, 122, 117, 133, 29, 48];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TimelockDelayChangedEvent {
+    pub previous_delay: i64,
+    pub delay: i64,
+}
+
+impl EventData for TimelockDelayChangedEvent {
+    const DISCRIMINATOR: [u8; 8] = [178, 7, 179, 245, 226, 27, 202, 50];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TimelockAdminChangedEvent {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+impl EventData for TimelockAdminChangedEvent {
+    const DISCRIMINATOR: [u8; 8] = [171, 163, 47, 167, 126, 250, 117, 156];
+}
@@ -0,0 +1,64 @@
+//! `wasm-bindgen` wrappers of the [`crate::pda`] helpers most browser apps need. Addresses go in
+//! and out as base58 strings, IDs and counts as `bigint`.
+
+use alloc::string::{String, ToString};
+use core::str::FromStr;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{pda, Pubkey};
+
+fn pubkey(address: &str) -> Result<Pubkey, JsError> {
+    Pubkey::from_str(address).map_err(|err| JsError::new(&err.to_string()))
+}
+
+#[wasm_bindgen(js_name = identityAddress)]
+pub fn identity_address(identity_id: &str) -> String {
+    pda::identity::identity(identity_id).to_string()
+}
+
+#[wasm_bindgen(js_name = permissionAddress)]
+pub fn permission_address(identity: &str, consumer: &str) -> Result<String, JsError> {
+    Ok(pda::identity::permission(&pubkey(identity)?, &pubkey(consumer)?).to_string())
+}
+
+#[wasm_bindgen(js_name = consumerProfileAddress)]
+pub fn consumer_profile_address(consumer: &str) -> Result<String, JsError> {
+    Ok(pda::identity::consumer_profile(&pubkey(consumer)?).to_string())
+}
+
+#[wasm_bindgen(js_name = consumerStakeAddress)]
+pub fn consumer_stake_address(consumer: &str) -> Result<String, JsError> {
+    Ok(pda::identity::consumer_stake(&pubkey(consumer)?).to_string())
+}
+
+#[wasm_bindgen(js_name = marketplaceAddress)]
+pub fn marketplace_address() -> String {
+    pda::marketplace::marketplace().to_string()
+}
+
+#[wasm_bindgen(js_name = listingAddress)]
+pub fn listing_address(listing_id: u64) -> String {
+    pda::marketplace::listing(listing_id).to_string()
+}
+
+#[wasm_bindgen(js_name = receiptAddress)]
+pub fn receipt_address(listing: &str) -> Result<String, JsError> {
+    Ok(pda::marketplace::receipt(&pubkey(listing)?).to_string())
+}
+
+#[wasm_bindgen(js_name = escrowAddress)]
+pub fn escrow_address(listing: &str) -> Result<String, JsError> {
+    Ok(pda::marketplace::escrow(&pubkey(listing)?).to_string())
+}
+
+#[wasm_bindgen(js_name = auditLogAddress)]
+pub fn audit_log_address(subject: &str) -> Result<String, JsError> {
+    Ok(pda::audit::audit_log(&pubkey(subject)?).to_string())
+}
+
+/// Page that the log's next entry lands on, given its current entry count
+#[wasm_bindgen(js_name = nextAuditPageAddress)]
+pub fn next_audit_page_address(audit_log: &str, entry_count: u64) -> Result<String, JsError> {
+    Ok(pda::audit::next_audit_page(&pubkey(audit_log)?, entry_count).to_string())
+}
@@ -0,0 +1,658 @@
+use anchor_lang::Discriminator;
+use datasov_types::{AccountData, EventData};
+
+#[test]
+fn account_discriminators_match_programs() {
+    assert_eq!(
+        <datasov_types::audit::AuditLog as AccountData>::DISCRIMINATOR,
+        datasov_audit::AuditLog::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::audit::AuditPage as AccountData>::DISCRIMINATOR,
+        datasov_audit::AuditPage::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::audit::AuditCheckpoint as AccountData>::DISCRIMINATOR,
+        datasov_audit::AuditCheckpoint::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::KYCOracleRegistry as AccountData>::DISCRIMINATOR,
+        datasov_identity::KYCOracleRegistry::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::KYCOracle as AccountData>::DISCRIMINATOR,
+        datasov_identity::KYCOracle::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityAccount as AccountData>::DISCRIMINATOR,
+        datasov_identity::IdentityAccount::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::AccessPermission as AccountData>::DISCRIMINATOR,
+        datasov_identity::AccessPermission::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ComplianceMatrix as AccountData>::DISCRIMINATOR,
+        datasov_identity::ComplianceMatrix::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::CrankFund as AccountData>::DISCRIMINATOR,
+        datasov_identity::CrankFund::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::Blacklist as AccountData>::DISCRIMINATOR,
+        datasov_identity::Blacklist::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerProfile as AccountData>::DISCRIMINATOR,
+        datasov_identity::ConsumerProfile::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerStake as AccountData>::DISCRIMINATOR,
+        datasov_identity::ConsumerStake::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::TrialRecord as AccountData>::DISCRIMINATOR,
+        datasov_identity::TrialRecord::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::JurorPool as AccountData>::DISCRIMINATOR,
+        datasov_identity::JurorPool::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::Juror as AccountData>::DISCRIMINATOR,
+        datasov_identity::Juror::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::Dispute as AccountData>::DISCRIMINATOR,
+        datasov_identity::Dispute::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::TeeAttestation as AccountData>::DISCRIMINATOR,
+        datasov_identity::TeeAttestation::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DataTrust as AccountData>::DISCRIMINATOR,
+        datasov_identity::DataTrust::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::TrustMembership as AccountData>::DISCRIMINATOR,
+        datasov_identity::TrustMembership::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ExportRequest as AccountData>::DISCRIMINATOR,
+        datasov_identity::ExportRequest::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumptionAttestation as AccountData>::DISCRIMINATOR,
+        datasov_identity::ConsumptionAttestation::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::WebProofVerifier as AccountData>::DISCRIMINATOR,
+        datasov_identity::WebProofVerifier::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::WebProofNullifier as AccountData>::DISCRIMINATOR,
+        datasov_identity::WebProofNullifier::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::AttestationSource as AccountData>::DISCRIMINATOR,
+        datasov_identity::AttestationSource::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DomainLink as AccountData>::DISCRIMINATOR,
+        datasov_identity::DomainLink::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::VoucherNonce as AccountData>::DISCRIMINATOR,
+        datasov_identity::VoucherNonce::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::AccessRequest as AccountData>::DISCRIMINATOR,
+        datasov_identity::AccessRequest::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::StateSnapshot as AccountData>::DISCRIMINATOR,
+        datasov_identity::StateSnapshot::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ComplianceOfficer as AccountData>::DISCRIMINATOR,
+        datasov_identity::ComplianceOfficer::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityFreeze as AccountData>::DISCRIMINATOR,
+        datasov_identity::IdentityFreeze::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::Marketplace as AccountData>::DISCRIMINATOR,
+        datasov_solana::Marketplace::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::DataListing as AccountData>::DISCRIMINATOR,
+        datasov_solana::DataListing::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ListingAllowlist as AccountData>::DISCRIMINATOR,
+        datasov_solana::ListingAllowlist::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::PurchaseReceipt as AccountData>::DISCRIMINATOR,
+        datasov_solana::PurchaseReceipt::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::CrankFund as AccountData>::DISCRIMINATOR,
+        datasov_solana::CrankFund::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::Coupon as AccountData>::DISCRIMINATOR,
+        datasov_solana::Coupon::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::PurchaseDelegate as AccountData>::DISCRIMINATOR,
+        datasov_solana::PurchaseDelegate::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ComputeJob as AccountData>::DISCRIMINATOR,
+        datasov_solana::ComputeJob::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::FlJob as AccountData>::DISCRIMINATOR,
+        datasov_solana::FlJob::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::FlParticipant as AccountData>::DISCRIMINATOR,
+        datasov_solana::FlParticipant::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::DataUnion as AccountData>::DISCRIMINATOR,
+        datasov_solana::DataUnion::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::UnionMember as AccountData>::DISCRIMINATOR,
+        datasov_solana::UnionMember::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::RebateProgram as AccountData>::DISCRIMINATOR,
+        datasov_solana::RebateProgram::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::RebateEpoch as AccountData>::DISCRIMINATOR,
+        datasov_solana::RebateEpoch::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::RebateLedger as AccountData>::DISCRIMINATOR,
+        datasov_solana::RebateLedger::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::BuybackConfig as AccountData>::DISCRIMINATOR,
+        datasov_solana::BuybackConfig::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::DataManifest as AccountData>::DISCRIMINATOR,
+        datasov_solana::DataManifest::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::CurationConfig as AccountData>::DISCRIMINATOR,
+        datasov_solana::CurationConfig::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::FeaturedSet as AccountData>::DISCRIMINATOR,
+        datasov_solana::FeaturedSet::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ListingTally as AccountData>::DISCRIMINATOR,
+        datasov_solana::ListingTally::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::CurationVote as AccountData>::DISCRIMINATOR,
+        datasov_solana::CurationVote::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::StorageOracle as AccountData>::DISCRIMINATOR,
+        datasov_solana::StorageOracle::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::StorageHealth as AccountData>::DISCRIMINATOR,
+        datasov_solana::StorageHealth::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::LicenseTemplate as AccountData>::DISCRIMINATOR,
+        datasov_solana::LicenseTemplate::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::PriceCandle as AccountData>::DISCRIMINATOR,
+        datasov_solana::PriceCandle::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::EarningsLedger as AccountData>::DISCRIMINATOR,
+        datasov_solana::EarningsLedger::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::FeatureFlags as AccountData>::DISCRIMINATOR,
+        datasov_solana::FeatureFlags::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::governance::Governance as AccountData>::DISCRIMINATOR,
+        datasov_governance::Governance::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::governance::StakeAccount as AccountData>::DISCRIMINATOR,
+        datasov_governance::StakeAccount::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::governance::Proposal as AccountData>::DISCRIMINATOR,
+        datasov_governance::Proposal::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::governance::VoteRecord as AccountData>::DISCRIMINATOR,
+        datasov_governance::VoteRecord::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::timelock::Timelock as AccountData>::DISCRIMINATOR,
+        datasov_timelock::Timelock::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::timelock::Operation as AccountData>::DISCRIMINATOR,
+        datasov_timelock::Operation::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::StakingConfig as AccountData>::DISCRIMINATOR,
+        datasov_staking::StakingConfig::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::Backing as AccountData>::DISCRIMINATOR,
+        datasov_staking::Backing::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::StakePosition as AccountData>::DISCRIMINATOR,
+        datasov_staking::StakePosition::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::StakeWeight as AccountData>::DISCRIMINATOR,
+        datasov_staking::StakeWeight::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::DisputeSlash as AccountData>::DISCRIMINATOR,
+        datasov_staking::DisputeSlash::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::Vesting as AccountData>::DISCRIMINATOR,
+        datasov_staking::Vesting::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::Airdrop as AccountData>::DISCRIMINATOR,
+        datasov_staking::Airdrop::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::AirdropBitmap as AccountData>::DISCRIMINATOR,
+        datasov_staking::AirdropBitmap::DISCRIMINATOR
+    );
+}
+
+#[test]
+fn event_discriminators_match_programs() {
+    assert_eq!(
+        <datasov_types::audit::AuditCheckpointedEvent as EventData>::DISCRIMINATOR,
+        datasov_audit::AuditCheckpointedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::OracleRegisteredEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::OracleRegisteredEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityRegisteredEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::IdentityRegisteredEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityVerifiedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::IdentityVerifiedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::IdentityUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityRevokedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::IdentityRevokedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::AccessGrantedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::AccessGrantedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::AccessRevokedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::AccessRevokedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ComplianceRuleUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ComplianceRuleUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ExpiredSweptEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ExpiredSweptEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerBlacklistedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsumerBlacklistedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerUnblacklistedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsumerUnblacklistedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::BlacklistedGrantEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::BlacklistedGrantEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerRegisteredEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsumerRegisteredEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerStakedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsumerStakedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerSlashedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsumerSlashedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::BusinessVerifiedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::BusinessVerifiedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::TrialAccessGrantedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::TrialAccessGrantedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DisputeOpenedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::DisputeOpenedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DisputePanelSelectedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::DisputePanelSelectedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DisputeResolvedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::DisputeResolvedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::PermissionPurposesUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::PermissionPurposesUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::TeeAttestationVerifiedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::TeeAttestationVerifiedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DataTrustCreatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::DataTrustCreatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::GuardianshipUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::GuardianshipUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ExportRequestedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ExportRequestedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ExportFulfilledEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ExportFulfilledEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ExportOverdueEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ExportOverdueEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumerTermsPublishedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsumerTermsPublishedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsentUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsentUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::PrivacyBudgetUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::PrivacyBudgetUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ConsumptionAttestedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ConsumptionAttestedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::WebProofVerifiedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::WebProofVerifiedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::ExternalAttestationVerifiedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::ExternalAttestationVerifiedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DomainLinkedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::DomainLinkedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::DomainUnlinkedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::DomainUnlinkedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::EncryptionKeyUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::EncryptionKeyUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::StateSnapshotPublishedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::StateSnapshotPublishedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::OracleRegistryUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::OracleRegistryUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::RegistryAuthorityChangedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::RegistryAuthorityChangedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityFrozenEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::IdentityFrozenEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::FreezeAppealedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::FreezeAppealedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::IdentityFreezeLiftedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::IdentityFreezeLiftedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::AccessRequestedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::AccessRequestedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::AccessRequestAcceptedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::AccessRequestAcceptedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::RentReclaimedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::RentReclaimedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::identity::SelfVerificationRejectedEvent as EventData>::DISCRIMINATOR,
+        datasov_identity::SelfVerificationRejectedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::DataTypeHaltUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::DataTypeHaltUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::FeatureFlagsUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::FeatureFlagsUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ExpiredSweptEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::ExpiredSweptEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::RentReclaimedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::RentReclaimedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::SettlementMintsUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::SettlementMintsUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::RevocationRefundedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::RevocationRefundedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ComputeJobCreatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::ComputeJobCreatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ComputeResultPostedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::ComputeResultPostedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::FlJobCreatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::FlJobCreatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::FlContributionRecordedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::FlContributionRecordedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::DataUnionMembershipEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::DataUnionMembershipEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::PurchaseWatermarkEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::PurchaseWatermarkEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::MarketplaceFeeUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::MarketplaceFeeUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::MarketplaceAuthorityChangedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::MarketplaceAuthorityChangedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::RebateEmissionUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::RebateEmissionUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::RebateClaimedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::RebateClaimedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::BuybackExecutedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::BuybackExecutedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ListingCuratedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::ListingCuratedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::CuratedListingDisputedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::CuratedListingDisputedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::CurationVoteSettledEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::CurationVoteSettledEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ListingFreshnessAttestedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::ListingFreshnessAttestedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::ListingDataUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::ListingDataUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::StorageCheckedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::StorageCheckedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::LicenseTemplateCreatedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::LicenseTemplateCreatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::LicenseMintedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::LicenseMintedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::AccessRightTransferredEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::AccessRightTransferredEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::marketplace::DataManifestSealedEvent as EventData>::DISCRIMINATOR,
+        datasov_solana::DataManifestSealedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::governance::ProposalCreatedEvent as EventData>::DISCRIMINATOR,
+        datasov_governance::ProposalCreatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::governance::VoteCastEvent as EventData>::DISCRIMINATOR,
+        datasov_governance::VoteCastEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::governance::ProposalExecutedEvent as EventData>::DISCRIMINATOR,
+        datasov_governance::ProposalExecutedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::timelock::OperationQueuedEvent as EventData>::DISCRIMINATOR,
+        datasov_timelock::OperationQueuedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::timelock::OperationCancelledEvent as EventData>::DISCRIMINATOR,
+        datasov_timelock::OperationCancelledEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::timelock::OperationExecutedEvent as EventData>::DISCRIMINATOR,
+        datasov_timelock::OperationExecutedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::timelock::TimelockDelayChangedEvent as EventData>::DISCRIMINATOR,
+        datasov_timelock::TimelockDelayChangedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::timelock::TimelockAdminChangedEvent as EventData>::DISCRIMINATOR,
+        datasov_timelock::TimelockAdminChangedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::StakedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::StakedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::StakeWithdrawnEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::StakeWithdrawnEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::BackingSlashedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::BackingSlashedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::FeeTiersUpdatedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::FeeTiersUpdatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::VestingCreatedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::VestingCreatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::VestedClaimedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::VestedClaimedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::VestingRevokedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::VestingRevokedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::AirdropCreatedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::AirdropCreatedEvent::DISCRIMINATOR
+    );
+    assert_eq!(
+        <datasov_types::staking::AirdropClaimedEvent as EventData>::DISCRIMINATOR,
+        datasov_staking::AirdropClaimedEvent::DISCRIMINATOR
+    );
+}
@@ -0,0 +1,223 @@
+//! The SDK-free derivation matches `datasov_client::pda`, which uses the Solana SDK's.
+
+use datasov_client::pda as client;
+use datasov_types::{pda, Pubkey};
+
+fn key(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+fn sdk(pubkey: Pubkey) -> anchor_lang::prelude::Pubkey {
+    anchor_lang::prelude::Pubkey::new_from_array(pubkey.to_bytes())
+}
+
+fn assert_same(ours: Pubkey, theirs: anchor_lang::prelude::Pubkey) {
+    assert_eq!(ours.to_bytes(), theirs.to_bytes());
+}
+
+#[test]
+fn program_ids_match_programs() {
+    assert_same(pda::identity::program_id(), client::identity::program_id());
+    assert_same(pda::marketplace::program_id(), client::marketplace::program_id());
+    assert_same(pda::audit::program_id(), client::audit::program_id());
+    assert_same(pda::governance::program_id(), client::governance::program_id());
+    assert_same(pda::timelock::program_id(), client::timelock::program_id());
+    assert_same(pda::staking::program_id(), client::staking::program_id());
+}
+
+#[test]
+fn identity_addresses_match_client() {
+    let (a, b) = (key(1), key(2));
+    assert_same(pda::identity::oracle_registry(), client::identity::oracle_registry());
+    assert_same(pda::identity::identity("alice"), client::identity::identity("alice"));
+    assert_same(
+        pda::identity::identity(&"i".repeat(64)),
+        client::identity::identity(&"i".repeat(64)),
+    );
+    assert_same(
+        pda::identity::permission(&a, &b),
+        client::identity::permission(&sdk(a), &sdk(b)),
+    );
+    assert_same(
+        pda::identity::consumer_profile(&a),
+        client::identity::consumer_profile(&sdk(a)),
+    );
+    assert_same(
+        pda::identity::consumer_stake(&a),
+        client::identity::consumer_stake(&sdk(a)),
+    );
+    assert_same(pda::identity::dispute(7), client::identity::dispute(7));
+    assert_same(
+        pda::identity::consumption_attestation(&a, 3),
+        client::identity::consumption_attestation(&sdk(a), 3),
+    );
+    assert_same(
+        pda::identity::web_proof_verifier(9),
+        client::identity::web_proof_verifier(9),
+    );
+    assert_same(
+        pda::identity::compliance_officer(&a),
+        client::identity::compliance_officer(&sdk(a)),
+    );
+    assert_same(
+        pda::identity::identity_freeze(&a),
+        client::identity::identity_freeze(&sdk(a)),
+    );
+    assert_same(
+        pda::identity::access_request(&a, &b),
+        client::identity::access_request(&sdk(a), &sdk(b)),
+    );
+    assert_same(pda::identity::audit_writer(), client::identity::audit_writer());
+}
+
+#[test]
+fn marketplace_addresses_match_client() {
+    let (a, b) = (key(3), key(4));
+    let listing = pda::marketplace::listing(42);
+    assert_same(pda::marketplace::marketplace(), client::marketplace::marketplace());
+    assert_same(listing, client::marketplace::listing(42));
+    assert_same(
+        pda::marketplace::receipt(&listing),
+        client::marketplace::receipt(&sdk(listing)),
+    );
+    assert_same(
+        pda::marketplace::escrow(&listing),
+        client::marketplace::escrow(&sdk(listing)),
+    );
+    assert_same(
+        pda::marketplace::data_manifest(&listing),
+        client::marketplace::data_manifest(&sdk(listing)),
+    );
+    assert_same(
+        pda::marketplace::coupon(&a, &[5; 32]),
+        client::marketplace::coupon(&sdk(a), &[5; 32]),
+    );
+    assert_same(
+        pda::marketplace::compute_job(&listing, &b, 1),
+        client::marketplace::compute_job(&sdk(listing), &sdk(b), 1),
+    );
+    assert_same(
+        pda::marketplace::data_union(&a, 2),
+        client::marketplace::data_union(&sdk(a), 2),
+    );
+    assert_same(
+        pda::marketplace::rebate_ledger(3, &a),
+        client::marketplace::rebate_ledger(3, &sdk(a)),
+    );
+    assert_same(
+        pda::marketplace::listing_tally(4, &a),
+        client::marketplace::listing_tally(4, &sdk(a)),
+    );
+    assert_same(
+        pda::marketplace::curation_vote(&a, &b),
+        client::marketplace::curation_vote(&sdk(a), &sdk(b)),
+    );
+    assert_same(
+        pda::marketplace::storage_oracle(&a),
+        client::marketplace::storage_oracle(&sdk(a)),
+    );
+    assert_same(
+        pda::marketplace::storage_health(&a),
+        client::marketplace::storage_health(&sdk(a)),
+    );
+    assert_same(
+        pda::marketplace::license_template(7),
+        client::marketplace::license_template(7),
+    );
+    assert_same(
+        pda::marketplace::license_mint(&a),
+        client::marketplace::license_mint(&sdk(a)),
+    );
+    assert_same(
+        pda::marketplace::price_candle(&a, 8, 19_723),
+        client::marketplace::price_candle(
+            &sdk(a),
+            &datasov_client::marketplace::DataType::CommunicationData,
+            19_723,
+        ),
+    );
+    assert_same(
+        pda::marketplace::earnings_ledger(&a),
+        client::marketplace::earnings_ledger(&sdk(a)),
+    );
+    assert_same(pda::marketplace::feature_flags(), client::marketplace::feature_flags());
+    assert_same(pda::marketplace::buyback(), client::marketplace::buyback());
+    assert_same(pda::marketplace::audit_writer(), client::marketplace::audit_writer());
+}
+
+#[test]
+fn audit_governance_and_timelock_addresses_match_client() {
+    let subject = key(6);
+    let audit_log = pda::audit::audit_log(&subject);
+    assert_same(audit_log, client::audit::audit_log(&sdk(subject)));
+    assert_eq!(pda::audit::PAGE_ENTRIES, datasov_audit::AuditPage::MAX_ENTRIES as u64);
+    assert_same(
+        pda::audit::next_audit_page(&audit_log, 40),
+        client::audit::next_audit_page(&sdk(audit_log), 40),
+    );
+    assert_same(
+        pda::audit::audit_checkpoint(&audit_log, 1),
+        client::audit::audit_checkpoint(&sdk(audit_log), 1),
+    );
+    assert_same(pda::governance::proposal(5), client::governance::proposal(5));
+    assert_same(
+        pda::governance::vote(&subject, &key(7)),
+        client::governance::vote(&sdk(subject), &sdk(key(7))),
+    );
+    assert_same(pda::timelock::timelock(), client::timelock::timelock());
+    assert_same(pda::timelock::operation(8), client::timelock::operation(8));
+}
+
+#[test]
+fn staking_addresses_match_client() {
+    let (subject, staker) = (key(9), key(10));
+    assert_same(pda::staking::config(), client::staking::config());
+    assert_same(pda::staking::vault(), client::staking::vault());
+    let backing = pda::staking::backing(datasov_types::staking::StakeRole::Juror, &subject);
+    assert_same(
+        backing,
+        client::staking::backing(datasov_staking::StakeRole::Juror, &sdk(subject)),
+    );
+    assert_same(
+        pda::staking::position(&backing, &staker),
+        client::staking::position(&sdk(backing), &sdk(staker)),
+    );
+    assert_same(
+        pda::staking::stake_weight(&staker),
+        client::staking::stake_weight(&sdk(staker)),
+    );
+    assert_same(
+        pda::staking::dispute_slash(&subject, &backing),
+        client::staking::dispute_slash(&sdk(subject), &sdk(backing)),
+    );
+    let vesting = pda::staking::vesting(&staker, 7);
+    assert_same(vesting, client::staking::vesting(&sdk(staker), 7));
+    assert_same(
+        pda::staking::vesting_vault(&vesting),
+        client::staking::vesting_vault(&sdk(vesting)),
+    );
+    let airdrop = pda::staking::airdrop(3);
+    assert_same(airdrop, client::staking::airdrop(3));
+    assert_same(
+        pda::staking::airdrop_vault(&airdrop),
+        client::staking::airdrop_vault(&sdk(airdrop)),
+    );
+    assert_same(
+        pda::staking::airdrop_bitmap(&airdrop, 20_000),
+        client::staking::airdrop_bitmap(&sdk(airdrop), 20_000),
+    );
+}
+
+#[test]
+fn bump_is_the_highest_off_curve() {
+    let program_id = pda::marketplace::program_id();
+    let (address, bump) = pda::find_program_address(&[b"listing", &42u64.to_le_bytes()], &program_id);
+    let (expected, expected_bump) =
+        anchor_lang::prelude::Pubkey::find_program_address(&[b"listing", &42u64.to_le_bytes()], &sdk(program_id));
+    assert_same(address, expected);
+    assert_eq!(bump, expected_bump);
+    assert_eq!(
+        pda::create_program_address(&[b"listing", &42u64.to_le_bytes(), &[bump]], &program_id),
+        Some(address)
+    );
+}
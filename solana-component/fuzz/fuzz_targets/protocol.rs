@@ -0,0 +1,9 @@
+use honggfuzz::fuzz;
+
+fn main() {
+    loop {
+        fuzz!(|data: &[u8]| {
+            datasov_fuzz::run(data);
+        });
+    }
+}
@@ -0,0 +1,73 @@
+use arbitrary::Arbitrary;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+/// One protocol operation. Participant and listing fields are indices, taken modulo the number of
+/// participants or listings created so far.
+#[derive(Debug, Arbitrary)]
+pub enum Action {
+    /// `data_types` is a bit mask over [`crate::world::DATA_TYPES`]; `ttl` is seconds from now
+    Grant {
+        owner: u8,
+        consumer: u8,
+        data_types: u8,
+        ttl: Option<u16>,
+    },
+    Revoke {
+        owner: u8,
+        consumer: u8,
+    },
+    Validate {
+        owner: u8,
+        consumer: u8,
+        data_type: u8,
+    },
+    CreateListing {
+        seller: u8,
+        price: u32,
+        data_type: u8,
+    },
+    CancelListing {
+        listing: u8,
+    },
+    Purchase {
+        buyer: u8,
+        listing: u8,
+    },
+    ReleaseProceeds {
+        listing: u8,
+    },
+    ClaimRevocationRefund {
+        listing: u8,
+    },
+    SetRefundWindow {
+        seconds: u16,
+    },
+    AdvanceClock {
+        seconds: u16,
+    },
+}
+
+/// Replace one account of the instruction with another protocol address, keeping its signer and
+/// writable flags
+#[derive(Debug, Arbitrary)]
+pub struct Mutation {
+    pub account: u8,
+    pub replacement: u8,
+}
+
+impl Mutation {
+    pub fn apply(&self, instruction: &mut Instruction, universe: &[Pubkey]) {
+        if instruction.accounts.is_empty() || universe.is_empty() {
+            return;
+        }
+        let index = self.account as usize % instruction.accounts.len();
+        instruction.accounts[index].pubkey = universe[self.replacement as usize % universe.len()];
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+pub struct Step {
+    pub action: Action,
+    pub mutation: Option<Mutation>,
+}
@@ -0,0 +1,57 @@
+//! Stateful fuzzing of the DataSov identity and marketplace programs.
+//!
+//! Each input decodes (with `arbitrary`) into a sequence of [`Step`]s: grants, revocations,
+//! access validations, listings, purchases, escrow settlement and clock moves between a handful of
+//! verified participants, each optionally with one account swapped for another protocol address.
+//! Steps run against a fresh [`datasov_test_harness::Protocol`], and after every step the
+//! [`World`] checks its invariants:
+//!
+//! - settlement tokens are conserved: the participants' accounts, the marketplace fee account and
+//!   the escrows always hold exactly what was minted, and a purchase moves exactly the listing price
+//! - no permission validates, or backs a purchase, after it was revoked or past its expiry
+//!
+//! A violated invariant panics, which the fuzzer records as a crash.
+//!
+//! ```text
+//! cargo install honggfuzz
+//! cd fuzz
+//! cargo hfuzz run protocol
+//! cargo hfuzz run-debug protocol hfuzz_workspace/protocol/<crash>.fuzz
+//! ```
+
+mod actions;
+mod world;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+pub use actions::{Action, Mutation, Step};
+pub use world::World;
+
+/// Steps per input beyond which the rest is ignored, to keep iterations fast
+pub const MAX_STEPS: usize = 32;
+
+/// Decode `data` into steps and run them against a fresh protocol
+pub fn run(data: &[u8]) {
+    let mut unstructured = Unstructured::new(data);
+    let mut steps = Vec::new();
+    while steps.len() < MAX_STEPS {
+        match Step::arbitrary(&mut unstructured) {
+            Ok(step) => steps.push(step),
+            Err(_) => break,
+        }
+    }
+    if steps.is_empty() {
+        return;
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime");
+    runtime.block_on(async {
+        let mut world = World::setup().await;
+        for step in &steps {
+            world.step(step).await;
+        }
+    });
+}
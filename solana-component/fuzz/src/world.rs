@@ -0,0 +1,482 @@
+use std::collections::{HashMap, HashSet};
+
+use datasov_client::identity::{self, PermissionType, Purpose, VerificationLevel};
+use datasov_client::instructions::{self, GrantAccessRequest, PurchaseRequest};
+use datasov_client::marketplace::{self, DataListing, DataType as ListingDataType, Marketplace};
+use datasov_client::{pda, storage};
+use datasov_test_harness::{Participant, Protocol};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::actions::{Action, Step};
+
+/// Verified participants; each can grant, buy and sell
+pub const PARTICIPANTS: usize = 4;
+/// Settlement tokens minted to each participant at setup
+pub const TOKENS_PER_PARTICIPANT: u64 = 10_000_000_000;
+
+/// Listing data types the fuzzer picks from, with the identity data type a purchase needs
+pub const DATA_TYPES: [ListingDataType; 4] = [
+    ListingDataType::LocationHistory,
+    ListingDataType::AppUsage,
+    ListingDataType::PurchaseHistory,
+    ListingDataType::HealthData,
+];
+
+/// What a successful step means for the model, with the positions of the accounts it concerns
+/// (read back after any mutation)
+enum Effect {
+    Grant { permission: usize, expires_at: Option<i64> },
+    Revoke { permission: usize },
+    Validate { permission: usize },
+    CreateListing { listing_id: u64, seller: usize },
+    Purchase { permission: usize, price: u64 },
+    None,
+}
+
+/// Protocol state under test and the model the invariants are checked against
+pub struct World {
+    protocol: Protocol,
+    participants: Vec<Participant>,
+    /// Listing id and seller index of every listing created
+    listings: Vec<(u64, usize)>,
+    /// Permissions revoked and not granted again since
+    revoked: HashSet<Pubkey>,
+    /// Expiry of each permission's latest grant
+    expiries: HashMap<Pubkey, Option<i64>>,
+    /// Settlement tokens in circulation; the tracked token accounts always hold exactly this
+    minted: u64,
+}
+
+impl World {
+    pub async fn setup() -> Self {
+        let mut protocol = Protocol::start().await;
+        let mut participants = Vec::with_capacity(PARTICIPANTS);
+        for index in 0..PARTICIPANTS {
+            let participant = protocol
+                .verified_participant(&format!("fuzz-{}", index), VerificationLevel::Enhanced)
+                .await;
+            protocol.fund_tokens(&participant, TOKENS_PER_PARTICIPANT).await;
+            participants.push(participant);
+        }
+        Self {
+            protocol,
+            participants,
+            listings: Vec::new(),
+            revoked: HashSet::new(),
+            expiries: HashMap::new(),
+            minted: TOKENS_PER_PARTICIPANT * PARTICIPANTS as u64,
+        }
+    }
+
+    /// Run one step and check the invariants; steps the fuzzer cannot sign are skipped
+    pub async fn step(&mut self, step: &Step) {
+        let Some((mut instruction, effect)) = self.plan(&step.action).await else {
+            return;
+        };
+        if let Some(mutation) = &step.mutation {
+            mutation.apply(&mut instruction, &self.universe());
+        }
+
+        let before = self.token_balances().await;
+        let Protocol { harness, authority, .. } = &mut self.protocol;
+        let signers: Option<Vec<&Keypair>> = instruction
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| {
+                std::iter::once(&*authority)
+                    .chain(self.participants.iter().map(|participant| &participant.wallet))
+                    .find(|keypair| keypair.pubkey() == meta.pubkey)
+            })
+            .collect();
+        let Some(signers) = signers else {
+            return;
+        };
+        let succeeded = harness.process(&[instruction.clone()], &signers).await.is_ok();
+        let after = self.token_balances().await;
+
+        let held: u64 = after.values().sum();
+        assert_eq!(held, self.minted, "settlement tokens not conserved after {:?}", step);
+        if succeeded {
+            self.observe(effect, &instruction, &before, &after, step).await;
+        }
+    }
+
+    /// Build the instruction for `action` and the effect it has if it succeeds, or `None` when
+    /// the action has nothing to act on
+    async fn plan(&mut self, action: &Action) -> Option<(Instruction, Effect)> {
+        let harness = &mut self.protocol.harness;
+        let participant = |index: u8| index as usize % PARTICIPANTS;
+        let planned = match action {
+            Action::Grant {
+                owner,
+                consumer,
+                data_types,
+                ttl,
+            } => {
+                let owner = &self.participants[participant(*owner)];
+                let consumer = &self.participants[participant(*consumer)];
+                let now = harness.now().await;
+                let expires_at = ttl.map(|ttl| now + ttl as i64);
+                let request = GrantAccessRequest {
+                    owner: owner.pubkey(),
+                    identity_id: owner.identity_id.clone(),
+                    consumer: consumer.pubkey(),
+                    permission_type: PermissionType::ReadOnly,
+                    data_types: DATA_TYPES
+                        .iter()
+                        .enumerate()
+                        .filter(|(bit, _)| data_types & (1 << bit) != 0)
+                        .map(|(_, data_type)| data_type.to_identity_data_type())
+                        .collect(),
+                    expires_at,
+                    permission_pointer: storage::placeholder("fuzz-grant"),
+                    consumer_staked: false,
+                    guardian: None,
+                    audit_entry_count: harness.audit_entry_count(&owner.identity()).await,
+                };
+                let permission = pda::identity::permission(&owner.identity(), &consumer.pubkey());
+                let instruction = instructions::grant_access(request);
+                let effect = Effect::Grant {
+                    permission: position(&instruction, &permission),
+                    expires_at,
+                };
+                (instruction, effect)
+            }
+            Action::Revoke { owner, consumer } => {
+                let owner = &self.participants[participant(*owner)];
+                let consumer = &self.participants[participant(*consumer)];
+                let permission = pda::identity::permission(&owner.identity(), &consumer.pubkey());
+                let instruction = instructions::revoke_access(
+                    &owner.pubkey(),
+                    &owner.identity_id,
+                    &consumer.pubkey(),
+                    storage::placeholder("fuzz-revoke"),
+                    harness.audit_entry_count(&owner.identity()).await,
+                );
+                let effect = Effect::Revoke {
+                    permission: position(&instruction, &permission),
+                };
+                (instruction, effect)
+            }
+            Action::Validate {
+                owner,
+                consumer,
+                data_type,
+            } => {
+                let owner = &self.participants[participant(*owner)];
+                let consumer = &self.participants[participant(*consumer)];
+                let permission = pda::identity::permission(&owner.identity(), &consumer.pubkey());
+                let instruction = instructions::validate_access(
+                    &consumer.pubkey(),
+                    &owner.identity_id,
+                    data_type_at(*data_type).to_identity_data_type(),
+                    0,
+                    false,
+                    harness.audit_entry_count(&owner.identity()).await,
+                );
+                let effect = Effect::Validate {
+                    permission: position(&instruction, &permission),
+                };
+                (instruction, effect)
+            }
+            Action::CreateListing {
+                seller,
+                price,
+                data_type,
+            } => {
+                let seller_index = participant(*seller);
+                let seller = &self.participants[seller_index];
+                let listing_id = self.protocol.unique_listing_id();
+                let instruction = instructions::marketplace(
+                    marketplace::accounts::CreateDataListing {
+                        listing: pda::marketplace::listing(listing_id),
+                        marketplace: pda::marketplace::marketplace(),
+                        seller_identity: seller.identity(),
+                        compliance_matrix: pda::identity::compliance_matrix(),
+                        data_trust: None,
+                        trust_membership: None,
+                        owner: seller.pubkey(),
+                        guardian: None,
+                        identity_program: identity::ID,
+                        system_program: system_program::ID,
+                        payout_wallet: None,
+                    },
+                    marketplace::instruction::CreateDataListing {
+                        listing_id,
+                        price: *price as u64,
+                        data_type: data_type_at(*data_type),
+                        description: format!("Fuzz listing {}", listing_id),
+                        identity_id: seller.identity_id.clone(),
+                    },
+                );
+                let effect = Effect::CreateListing {
+                    listing_id,
+                    seller: seller_index,
+                };
+                (instruction, effect)
+            }
+            Action::CancelListing { listing } => {
+                let (listing_id, seller) = listing_at(&self.listings, *listing)?;
+                let instruction = instructions::marketplace(
+                    marketplace::accounts::CancelListing {
+                        listing: pda::marketplace::listing(listing_id),
+                        owner: self.participants[seller].pubkey(),
+                    },
+                    marketplace::instruction::CancelListing {},
+                );
+                (instruction, Effect::None)
+            }
+            Action::Purchase { buyer, listing } => {
+                let (listing_id, seller) = listing_at(&self.listings, *listing)?;
+                let buyer = &self.participants[participant(*buyer)];
+                let seller = &self.participants[seller];
+                let listing: DataListing = harness.fetch(&pda::marketplace::listing(listing_id)).await;
+                let marketplace_account: Marketplace = harness.fetch(&pda::marketplace::marketplace()).await;
+                let request = PurchaseRequest {
+                    buyer: buyer.pubkey(),
+                    buyer_identity_id: buyer.identity_id.clone(),
+                    listing_id,
+                    payout_wallet: listing.payout_wallet,
+                    seller_identity_id: seller.identity_id.clone(),
+                    data_union: None,
+                    data_type: listing.data_type(),
+                    purpose: Purpose::Research,
+                    mint: self.protocol.mint,
+                    escrowed: marketplace_account.refund_window > 0,
+                    buyer_staked: false,
+                    buyer_weighted: false,
+                    rebate: None,
+                    max_staleness: None,
+                    day: marketplace::PriceCandle::day_of(harness.now().await),
+                    audit_entry_count: harness.audit_entry_count(&seller.identity()).await,
+                };
+                let permission = pda::identity::permission(&seller.identity(), &buyer.pubkey());
+                let instruction = instructions::purchase_data(request);
+                let effect = Effect::Purchase {
+                    permission: position(&instruction, &permission),
+                    price: listing.price,
+                };
+                (instruction, effect)
+            }
+            Action::ReleaseProceeds { listing } => {
+                let (listing_id, seller) = listing_at(&self.listings, *listing)?;
+                let seller = &self.participants[seller];
+                let listing = pda::marketplace::listing(listing_id);
+                let receipt: marketplace::PurchaseReceipt =
+                    harness.account(&pda::marketplace::receipt(&listing)).await?;
+                let instruction = instructions::marketplace(
+                    marketplace::accounts::ReleaseProceeds {
+                        marketplace: pda::marketplace::marketplace(),
+                        listing,
+                        receipt: pda::marketplace::receipt(&listing),
+                        permission: receipt.permission,
+                        escrow_token_account: pda::marketplace::escrow(&listing),
+                        seller_token_account: self.token_account(&seller.pubkey()),
+                        seller_identity: seller.identity(),
+                        earnings_ledger: pda::marketplace::earnings_ledger(&seller.identity()),
+                        seller: seller.pubkey(),
+                        identity_program: identity::ID,
+                        token_program: spl_token::ID,
+                        system_program: system_program::ID,
+                    },
+                    marketplace::instruction::ReleaseProceeds {
+                        _listing_id: listing_id,
+                    },
+                );
+                (instruction, Effect::None)
+            }
+            Action::ClaimRevocationRefund { listing } => {
+                let (listing_id, _) = listing_at(&self.listings, *listing)?;
+                let listing = pda::marketplace::listing(listing_id);
+                let receipt: marketplace::PurchaseReceipt =
+                    harness.account(&pda::marketplace::receipt(&listing)).await?;
+                let instruction = instructions::marketplace(
+                    marketplace::accounts::ClaimRevocationRefund {
+                        marketplace: pda::marketplace::marketplace(),
+                        listing,
+                        receipt: pda::marketplace::receipt(&listing),
+                        permission: receipt.permission,
+                        escrow_token_account: pda::marketplace::escrow(&listing),
+                        payer_token_account: self.token_account(&receipt.payer),
+                        claimant: receipt.buyer,
+                        token_program: spl_token::ID,
+                    },
+                    marketplace::instruction::ClaimRevocationRefund {
+                        _listing_id: listing_id,
+                    },
+                );
+                (instruction, Effect::None)
+            }
+            Action::SetRefundWindow { seconds } => {
+                let instruction = instructions::marketplace(
+                    marketplace::accounts::SetRefundWindow {
+                        marketplace: pda::marketplace::marketplace(),
+                        authority: self.protocol.authority.pubkey(),
+                    },
+                    marketplace::instruction::SetRefundWindow {
+                        refund_window: *seconds as i64,
+                    },
+                );
+                (instruction, Effect::None)
+            }
+            Action::AdvanceClock { seconds } => {
+                harness.advance_clock(*seconds as i64).await;
+                return None;
+            }
+        };
+        Some(planned)
+    }
+
+    /// Update the model after a successful step and check the access and settlement invariants
+    async fn observe(
+        &mut self,
+        effect: Effect,
+        instruction: &Instruction,
+        before: &HashMap<Pubkey, u64>,
+        after: &HashMap<Pubkey, u64>,
+        step: &Step,
+    ) {
+        let account = |index: usize| instruction.accounts[index].pubkey;
+        match effect {
+            Effect::Grant { permission, expires_at } => {
+                self.revoked.remove(&account(permission));
+                self.expiries.insert(account(permission), expires_at);
+            }
+            Effect::Revoke { permission } => {
+                self.revoked.insert(account(permission));
+            }
+            Effect::Validate { permission } => {
+                self.check_permission_usable(&account(permission), step).await;
+            }
+            Effect::CreateListing { listing_id, seller } => {
+                self.listings.push((listing_id, seller));
+            }
+            Effect::Purchase { permission, price } => {
+                self.check_permission_usable(&account(permission), step).await;
+                let debited: u64 = before
+                    .iter()
+                    .map(|(address, balance)| balance.saturating_sub(after[address]))
+                    .sum();
+                let credited: u64 = after
+                    .iter()
+                    .map(|(address, balance)| balance.saturating_sub(before[address]))
+                    .sum();
+                assert_eq!(
+                    debited, price,
+                    "purchase debited a different amount than the price: {:?}",
+                    step
+                );
+                assert_eq!(
+                    credited, price,
+                    "purchase credited a different amount than the price: {:?}",
+                    step
+                );
+            }
+            Effect::None => {}
+        }
+    }
+
+    /// A permission that was revoked, or whose grant expired, must not have been accepted
+    async fn check_permission_usable(&mut self, permission: &Pubkey, step: &Step) {
+        assert!(
+            !self.revoked.contains(permission),
+            "revoked permission {} was accepted: {:?}",
+            permission,
+            step
+        );
+        if let Some(Some(expires_at)) = self.expiries.get(permission) {
+            let now = self.protocol.harness.now().await;
+            assert!(
+                now < *expires_at,
+                "permission {} was accepted at {} after expiring at {}: {:?}",
+                permission,
+                now,
+                expires_at,
+                step
+            );
+        }
+    }
+
+    fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address(owner, &self.protocol.mint)
+    }
+
+    /// Every token account settlement tokens may legitimately sit in
+    fn token_accounts(&self) -> Vec<Pubkey> {
+        let mut accounts: Vec<Pubkey> = self
+            .participants
+            .iter()
+            .map(|participant| self.token_account(&participant.pubkey()))
+            .collect();
+        accounts.push(self.token_account(&pda::marketplace::marketplace()));
+        accounts.extend(
+            self.listings
+                .iter()
+                .map(|(listing_id, _)| pda::marketplace::escrow(&pda::marketplace::listing(*listing_id))),
+        );
+        accounts
+    }
+
+    async fn token_balances(&mut self) -> HashMap<Pubkey, u64> {
+        let mut balances = HashMap::new();
+        for address in self.token_accounts() {
+            let balance = self.protocol.harness.token_balance(&address).await;
+            balances.insert(address, balance);
+        }
+        balances
+    }
+
+    /// Addresses a [`crate::Mutation`] can swap in: wallets, identities, profiles, permissions,
+    /// listings, receipts, token accounts and the global accounts
+    fn universe(&self) -> Vec<Pubkey> {
+        let mut universe = vec![
+            pda::identity::oracle_registry(),
+            pda::identity::compliance_matrix(),
+            pda::identity::blacklist(),
+            pda::marketplace::marketplace(),
+            self.protocol.authority.pubkey(),
+            self.protocol.mint,
+        ];
+        universe.extend(self.token_accounts());
+        for owner in &self.participants {
+            universe.push(owner.pubkey());
+            universe.push(owner.identity());
+            universe.push(pda::identity::consumer_profile(&owner.pubkey()));
+            for consumer in &self.participants {
+                universe.push(pda::identity::permission(&owner.identity(), &consumer.pubkey()));
+            }
+        }
+        for (listing_id, _) in &self.listings {
+            let listing = pda::marketplace::listing(*listing_id);
+            universe.push(listing);
+            universe.push(pda::marketplace::receipt(&listing));
+        }
+        universe
+    }
+}
+
+fn data_type_at(index: u8) -> ListingDataType {
+    DATA_TYPES[index as usize % DATA_TYPES.len()].clone()
+}
+
+fn listing_at(listings: &[(u64, usize)], index: u8) -> Option<(u64, usize)> {
+    match listings.len() {
+        0 => None,
+        count => Some(listings[index as usize % count]),
+    }
+}
+
+/// Index of `address` among the instruction's accounts
+fn position(instruction: &Instruction, address: &Pubkey) -> usize {
+    instruction
+        .accounts
+        .iter()
+        .position(|meta| meta.pubkey == *address)
+        .expect("instruction references the account")
+}
@@ -0,0 +1,346 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+declare_id!("AvUNiG4Nhv6PUSLrYzqpKs5cFHrs5weifwyBoin45TWW");
+
+/// DataSov identity program, which logs grants, revocations and access validations
+pub mod identity_program {
+    use super::*;
+    declare_id!("DataSovIdentity11111111111111111111111111111");
+}
+
+/// DataSov marketplace program, which logs purchases
+pub mod marketplace_program {
+    use super::*;
+    declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+}
+
+/// PDA seed prefixes
+pub mod seeds {
+    /// `AuditLog`, per subject
+    pub const AUDIT_LOG: &[u8] = b"audit_log";
+    /// `AuditPage`, per log and page index
+    pub const AUDIT_PAGE: &[u8] = b"audit_page";
+    /// `AuditCheckpoint`, per log and checkpoint index
+    pub const AUDIT_CHECKPOINT: &[u8] = b"audit_checkpoint";
+    /// Signer PDA a writer program derives under its own ID to append entries
+    pub const AUDIT_WRITER: &[u8] = b"audit_writer";
+}
+
+#[program]
+pub mod datasov_audit {
+    use super::*;
+
+    /// Append an entry to an identity's audit log (writer PDAs of the DataSov programs only)
+    pub fn append_entry(
+        ctx: Context<AppendEntry>,
+        subject: Pubkey,
+        action: AuditAction,
+        actor: Pubkey,
+        reference: Pubkey,
+        value: u64,
+    ) -> Result<()> {
+        require!(is_writer(&ctx.accounts.writer.key()), ErrorCode::UnauthorizedWriter);
+
+        let log = &mut ctx.accounts.audit_log;
+        let now = Clock::get()?.unix_timestamp;
+
+        if log.entry_count == 0 {
+            log.subject = subject;
+            log.created_at = now;
+            log.bump = ctx.bumps.audit_log;
+        }
+        // A log's pages are created full of zeroes, one whenever the previous page fills up
+        let slot = (log.entry_count % AuditPage::MAX_ENTRIES as u64) as usize;
+        let mut page = if slot == 0 {
+            let mut page = ctx.accounts.audit_page.load_init()?;
+            page.log = log.key();
+            page.page_index = log.page_index();
+            page.bump = ctx.bumps.audit_page;
+            page
+        } else {
+            ctx.accounts.audit_page.load_mut()?
+        };
+
+        page.entries[slot] = AuditEntry {
+            actor: actor,
+            reference: reference,
+            value: value,
+            timestamp: now,
+            action: action.clone() as u8,
+            _padding: [0; 7],
+        };
+        page.entry_count += 1;
+        log.entry_count += 1;
+
+        msg!("Audit entry {} recorded for {}: {:?}", log.entry_count - 1, subject, action);
+        Ok(())
+    }
+
+    /// Anchor a Merkle root of the entries since the last checkpoint, with the Arweave tx of the full export.
+    /// Callable by any crank; the pages holding those entries are passed in order as remaining accounts.
+    pub fn checkpoint_audit_log<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckpointAuditLog<'info>>,
+        arweave_export_tx_id: String,
+    ) -> Result<()> {
+        let log = &mut ctx.accounts.audit_log;
+        let from_entry = log.checkpointed_entries;
+        let to_entry = log.entry_count.min(from_entry + AuditCheckpoint::MAX_ENTRIES);
+
+        require!(to_entry > from_entry, ErrorCode::NothingToCheckpoint);
+        require!(arweave_export_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+
+        let first_page = from_entry / AuditPage::MAX_ENTRIES as u64;
+        let last_page = (to_entry - 1) / AuditPage::MAX_ENTRIES as u64;
+        require!(
+            ctx.remaining_accounts.len() as u64 == last_page - first_page + 1,
+            ErrorCode::AuditPageMismatch
+        );
+
+        let mut leaves = Vec::with_capacity((to_entry - from_entry) as usize);
+        for (offset, account_info) in ctx.remaining_accounts.iter().enumerate() {
+            let loader = AccountLoader::<AuditPage>::try_from(account_info)?;
+            let page = loader.load()?;
+            require!(
+                page.log == log.key() && page.page_index == first_page + offset as u64,
+                ErrorCode::AuditPageMismatch
+            );
+
+            for (slot, entry) in page.entries[..page.entry_count as usize].iter().enumerate() {
+                let index = page.page_index * AuditPage::MAX_ENTRIES as u64 + slot as u64;
+                if index >= from_entry && index < to_entry {
+                    leaves.push(entry.leaf(index));
+                }
+            }
+        }
+        require!(leaves.len() as u64 == to_entry - from_entry, ErrorCode::AuditPageMismatch);
+
+        let checkpoint = &mut ctx.accounts.checkpoint;
+        checkpoint.log = log.key();
+        checkpoint.epoch = log.checkpoint_count;
+        checkpoint.from_entry = from_entry;
+        checkpoint.to_entry = to_entry;
+        checkpoint.merkle_root = merkle_root(leaves);
+        checkpoint.arweave_export_tx_id = arweave_export_tx_id.clone();
+        checkpoint.posted_by = ctx.accounts.caller.key();
+        checkpoint.posted_at = Clock::get()?.unix_timestamp;
+        checkpoint.bump = ctx.bumps.checkpoint;
+
+        log.checkpointed_entries = to_entry;
+        log.checkpoint_count += 1;
+
+        emit!(AuditCheckpointedEvent {
+            subject: log.subject,
+            epoch: checkpoint.epoch,
+            from_entry: from_entry,
+            to_entry: to_entry,
+            merkle_root: checkpoint.merkle_root,
+            arweave_export_tx_id: arweave_export_tx_id,
+        });
+
+        msg!("Audit log {} checkpointed at epoch {}", log.subject, checkpoint.epoch);
+        Ok(())
+    }
+}
+
+/// Writers are the `audit_writer` PDAs of the identity and marketplace programs
+fn is_writer(key: &Pubkey) -> bool {
+    [identity_program::ID, marketplace_program::ID]
+        .iter()
+        .any(|program_id| Pubkey::find_program_address(&[seeds::AUDIT_WRITER], program_id).0 == *key)
+}
+
+/// Pairwise-hash leaves up to a single root, carrying an odd node up unchanged
+fn merkle_root(mut level: Vec<[u8; 32]>) -> [u8; 32] {
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hashv(&[left.as_ref(), right.as_ref()]).to_bytes(),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+// Account structures
+
+#[derive(Accounts)]
+#[instruction(subject: Pubkey)]
+pub struct AppendEntry<'info> {
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + AuditLog::INIT_SPACE,
+        seeds = [seeds::AUDIT_LOG, subject.as_ref()],
+        bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + AuditPage::INIT_SPACE,
+        seeds = [seeds::AUDIT_PAGE, audit_log.key().as_ref(), audit_log.page_index().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub audit_page: AccountLoader<'info, AuditPage>,
+
+    pub writer: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CheckpointAuditLog<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::AUDIT_LOG, audit_log.subject.as_ref()],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AuditLog>,
+
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + AuditCheckpoint::INIT_SPACE,
+        seeds = [seeds::AUDIT_CHECKPOINT, audit_log.key().as_ref(), audit_log.checkpoint_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub checkpoint: Account<'info, AuditCheckpoint>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Account data structures
+
+#[account]
+#[derive(InitSpace)]
+pub struct AuditLog {
+    pub subject: Pubkey,
+    pub entry_count: u64,
+    pub checkpointed_entries: u64,
+    pub checkpoint_count: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AuditLog {
+    /// Page the next entry is appended to
+    pub fn page_index(&self) -> u64 {
+        self.entry_count / AuditPage::MAX_ENTRIES as u64
+    }
+}
+
+/// Zero-copy `repr(C)` layout: appending an entry writes its slot instead of re-encoding the page
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct AuditPage {
+    pub log: Pubkey,
+    pub page_index: u64,
+    pub entries: [AuditEntry; 16],
+    /// Slots of `entries` filled so far
+    pub entry_count: u8,
+    pub bump: u8,
+    pub _padding: [u8; 6],
+}
+
+impl AuditPage {
+    pub const MAX_ENTRIES: usize = 16;
+}
+
+impl Space for AuditPage {
+    const INIT_SPACE: usize = std::mem::size_of::<AuditPage>();
+}
+
+#[zero_copy]
+#[derive(Debug)]
+pub struct AuditEntry {
+    pub actor: Pubkey,
+    pub reference: Pubkey,
+    pub value: u64,
+    pub timestamp: i64,
+    /// `AuditAction` variant index
+    pub action: u8,
+    pub _padding: [u8; 7],
+}
+
+impl AuditEntry {
+    /// Merkle leaf for the entry at `index` in its log. Hashes the Borsh encoding of
+    /// `(AuditAction, actor, reference, value, timestamp)` that leaves have always committed to.
+    pub fn leaf(&self, index: u64) -> [u8; 32] {
+        hashv(&[
+            &index.to_le_bytes(),
+            &[self.action],
+            self.actor.as_ref(),
+            self.reference.as_ref(),
+            &self.value.to_le_bytes(),
+            &self.timestamp.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AuditCheckpoint {
+    pub log: Pubkey,
+    pub epoch: u64,
+    pub from_entry: u64,
+    pub to_entry: u64,
+    pub merkle_root: [u8; 32],
+    #[max_len(128)]
+    pub arweave_export_tx_id: String,
+    pub posted_by: Pubkey,
+    pub posted_at: i64,
+    pub bump: u8,
+}
+
+impl AuditCheckpoint {
+    /// Entries one checkpoint may cover, keeping the page accounts within a single transaction
+    pub const MAX_ENTRIES: u64 = 4 * AuditPage::MAX_ENTRIES as u64;
+}
+
+// Enums
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum AuditAction {
+    Grant,
+    Revoke,
+    Validate,
+    Purchase,
+}
+
+// Events
+
+#[event]
+pub struct AuditCheckpointedEvent {
+    pub subject: Pubkey,
+    pub epoch: u64,
+    pub from_entry: u64,
+    pub to_entry: u64,
+    pub merkle_root: [u8; 32],
+    pub arweave_export_tx_id: String,
+}
+
+// Error codes
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Signer is not an authorized audit writer")]
+    UnauthorizedWriter,
+    #[msg("No new audit entries to checkpoint")]
+    NothingToCheckpoint,
+    #[msg("Audit pages do not cover the checkpoint range in order")]
+    AuditPageMismatch,
+    #[msg("Arweave transaction ID too long")]
+    ArweaveTxIdTooLong,
+}
@@ -0,0 +1,100 @@
+//! Every account, filled to the limits the program enforces, must serialize within the space `InitSpace` derives for it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use datasov_audit::{AuditAction, AuditCheckpoint, AuditEntry, AuditLog, AuditPage};
+use proptest::prelude::*;
+
+/// Serialize with the discriminator, check the encoding fits and round-trips
+fn assert_fits<T: AccountSerialize + AccountDeserialize + Space>(account: &T) {
+    let len = 8 + T::INIT_SPACE;
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= len, "{} bytes serialized, {} allocated", data.len(), len);
+
+    let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+    let mut reencoded = Vec::new();
+    decoded.try_serialize(&mut reencoded).unwrap();
+    assert_eq!(data, reencoded);
+}
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+/// ASCII string of exactly `len` bytes, the longest the program accepts
+fn text(len: usize) -> impl Strategy<Value = String> {
+    proptest::collection::vec(0x20u8..0x7f, len).prop_map(|bytes| String::from_utf8(bytes).unwrap())
+}
+
+fn audit_action() -> impl Strategy<Value = AuditAction> {
+    prop_oneof![
+        Just(AuditAction::Grant),
+        Just(AuditAction::Revoke),
+        Just(AuditAction::Validate),
+        Just(AuditAction::Purchase),
+    ]
+}
+
+fn audit_entry() -> impl Strategy<Value = AuditEntry> {
+    (audit_action(), pubkey(), pubkey(), any::<u64>(), any::<i64>()).prop_map(
+        |(action, actor, reference, value, timestamp)| AuditEntry {
+            actor,
+            reference,
+            value,
+            timestamp,
+            action: action as u8,
+            _padding: [0; 7],
+        },
+    )
+}
+
+#[test]
+fn audit_log_fits() {
+    let log = AuditLog {
+        subject: Pubkey::new_unique(),
+        entry_count: u64::MAX,
+        checkpointed_entries: u64::MAX,
+        checkpoint_count: u64::MAX,
+        created_at: i64::MAX,
+        bump: u8::MAX,
+    };
+    assert_fits(&log);
+}
+
+proptest! {
+    #[test]
+    fn audit_page_fits(entries in proptest::collection::vec(audit_entry(), AuditPage::MAX_ENTRIES)) {
+        let page = AuditPage {
+            log: Pubkey::new_unique(),
+            page_index: u64::MAX,
+            entries: std::array::from_fn(|slot| entries[slot]),
+            entry_count: AuditPage::MAX_ENTRIES as u8,
+            bump: u8::MAX,
+            _padding: [0; 6],
+        };
+
+        // Zero-copy: the account is the page's bytes after the discriminator
+        let mut data = AuditPage::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&page));
+        assert_eq!(data.len(), 8 + AuditPage::INIT_SPACE);
+        let decoded: &AuditPage = bytemuck::from_bytes(&data[8..]);
+        assert_eq!(bytemuck::bytes_of(decoded), bytemuck::bytes_of(&page));
+    }
+
+    #[test]
+    fn audit_checkpoint_fits(merkle_root in any::<[u8; 32]>(), arweave_export_tx_id in text(128)) {
+        let checkpoint = AuditCheckpoint {
+            log: Pubkey::new_unique(),
+            epoch: u64::MAX,
+            from_entry: u64::MAX,
+            to_entry: u64::MAX,
+            merkle_root,
+            arweave_export_tx_id,
+            posted_by: Pubkey::new_unique(),
+            posted_at: i64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&checkpoint);
+    }
+}
@@ -0,0 +1,1199 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use datasov_identity::program::DatasovIdentity;
+use datasov_identity::{
+    ComplianceMatrix, DataType, IdentityAccount, IdentityFreeze, KYCOracleRegistry, VerificationLevel,
+};
+use datasov_solana::program::DatasovSolana;
+use datasov_solana::{FeatureFlags, LicenseTemplate, LicenseTerms, Marketplace, RebateProgram};
+use datasov_staking::program::DatasovStaking;
+use datasov_staking::{StakeWeight, StakingConfig, Vesting};
+
+declare_id!("AAM7nwhGKLEqhzGrCKJyTbSMPeUUhSTVJ4hkbPp3DsGg");
+
+/// PDA seed prefixes
+pub mod seeds {
+    /// `Governance` config, also the authority the governed programs are handed to
+    pub const GOVERNANCE: &[u8] = b"governance";
+    /// `StakeAccount`, per staker
+    pub const STAKE: &[u8] = b"stake";
+    /// `Proposal`, per proposal ID
+    pub const PROPOSAL: &[u8] = b"proposal";
+    /// `VoteRecord`, per proposal and voter
+    pub const VOTE: &[u8] = b"vote";
+}
+
+#[program]
+pub mod datasov_governance {
+    use super::*;
+
+    /// Create the governance config. The identity registry and marketplace come under governance
+    /// once their authorities hand over to the `Governance` PDA.
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        voting_period: i64,
+        quorum: u64,
+        approval_threshold_bps: u16,
+        proposal_threshold: u64,
+    ) -> Result<()> {
+        require!(voting_period > 0, ErrorCode::InvalidGovernanceConfig);
+        require!(
+            approval_threshold_bps > 0 && approval_threshold_bps <= 10000,
+            ErrorCode::InvalidGovernanceConfig
+        );
+
+        let governance = &mut ctx.accounts.governance;
+        governance.voting_period = voting_period;
+        governance.quorum = quorum;
+        governance.approval_threshold_bps = approval_threshold_bps;
+        governance.proposal_threshold = proposal_threshold;
+        governance.proposal_count = 0;
+        governance.total_staked = 0;
+        governance.bump = ctx.bumps.governance;
+
+        msg!("Governance initialized with a {} second voting period", voting_period);
+        Ok(())
+    }
+
+    /// Lock lamports as voting stake
+    pub fn deposit_stake(
+        ctx: Context<DepositStake>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.staker.to_account_info(),
+                to: ctx.accounts.stake.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let stake = &mut ctx.accounts.stake;
+        if stake.staker == Pubkey::default() {
+            stake.staker = ctx.accounts.staker.key();
+            stake.bump = ctx.bumps.stake;
+        }
+        stake.amount += amount;
+        ctx.accounts.governance.total_staked += amount;
+
+        msg!("Governance stake deposited: {} lamports", amount);
+        Ok(())
+    }
+
+    /// Withdraw voting stake once every proposal it voted on has closed
+    pub fn withdraw_stake(
+        ctx: Context<WithdrawStake>,
+        amount: u64,
+    ) -> Result<()> {
+        let stake = &mut ctx.accounts.stake;
+
+        require!(Clock::get()?.unix_timestamp >= stake.locked_until, ErrorCode::StakeLocked);
+        require!(amount <= stake.amount, ErrorCode::InvalidAmount);
+
+        stake.amount -= amount;
+        ctx.accounts.governance.total_staked -= amount;
+        **stake.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.staker.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        msg!("Governance stake withdrawn: {} lamports", amount);
+        Ok(())
+    }
+
+    /// Propose a parameter change (stakers holding at least the proposal threshold, lamport stake
+    /// and staked protocol tokens combined)
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: ProposalAction,
+        description_uri: String,
+    ) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+
+        let weight = voting_weight(ctx.accounts.stake.as_deref(), ctx.accounts.proposer_weight.as_deref());
+        require!(weight >= governance.proposal_threshold, ErrorCode::InsufficientStake);
+        require!(description_uri.len() <= 128, ErrorCode::DescriptionUriTooLong);
+        action.validate()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = governance.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.action = action.clone();
+        proposal.description_uri = description_uri;
+        proposal.created_at = now;
+        proposal.voting_ends_at = now + governance.voting_period;
+        proposal.yes_votes = 0;
+        proposal.no_votes = 0;
+        proposal.executed_at = None;
+        proposal.bump = ctx.bumps.proposal;
+        governance.proposal_count += 1;
+
+        emit!(ProposalCreatedEvent {
+            proposal_id: proposal.id,
+            proposer: proposal.proposer,
+            action: action,
+            voting_ends_at: proposal.voting_ends_at,
+        });
+
+        msg!("Proposal {} created", proposal.id);
+        Ok(())
+    }
+
+    /// Vote on an open proposal with the voter's whole lamport stake and staking weight, both of
+    /// which stay locked until voting closes
+    pub fn cast_vote(
+        ctx: Context<CastVote>,
+        approve: bool,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let voting_ends_at = proposal.voting_ends_at;
+
+        require!(Clock::get()?.unix_timestamp < voting_ends_at, ErrorCode::VotingClosed);
+        let weight = voting_weight(ctx.accounts.stake.as_deref(), ctx.accounts.voter_weight.as_deref());
+        require!(weight > 0, ErrorCode::InsufficientStake);
+
+        if approve {
+            proposal.yes_votes += weight;
+        } else {
+            proposal.no_votes += weight;
+        }
+
+        if let Some(stake) = ctx.accounts.stake.as_mut() {
+            stake.locked_until = stake.locked_until.max(voting_ends_at);
+        }
+        if let Some(voter_weight) = &ctx.accounts.voter_weight {
+            let (Some(staking_config), Some(staking_program)) =
+                (&ctx.accounts.staking_config, &ctx.accounts.staking_program)
+            else {
+                return err!(ErrorCode::StakingAccountsMissing);
+            };
+            let bump = ctx.accounts.governance.bump;
+            let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+            datasov_staking::cpi::lock_stake(
+                CpiContext::new_with_signer(
+                    staking_program.to_account_info(),
+                    datasov_staking::cpi::accounts::LockStake {
+                        config: staking_config.to_account_info(),
+                        weight: voter_weight.to_account_info(),
+                        authority: ctx.accounts.governance.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                voting_ends_at,
+            )?;
+        }
+
+        let vote = &mut ctx.accounts.vote;
+        vote.proposal = ctx.accounts.proposal.key();
+        vote.voter = ctx.accounts.voter.key();
+        vote.approve = approve;
+        vote.weight = weight;
+        vote.bump = ctx.bumps.vote;
+
+        emit!(VoteCastEvent {
+            proposal_id: ctx.accounts.proposal.id,
+            voter: vote.voter,
+            approve: approve,
+            weight: vote.weight,
+        });
+
+        msg!("Vote cast on proposal {}: approve = {}", ctx.accounts.proposal.id, approve);
+        Ok(())
+    }
+
+    /// Apply a passed marketplace fee proposal (callable by anyone)
+    pub fn execute_set_marketplace_fee(
+        ctx: Context<ExecuteMarketplaceProposal>,
+    ) -> Result<()> {
+        let fee_basis_points = match ctx.accounts.proposal.action {
+            ProposalAction::SetMarketplaceFee { fee_basis_points } => fee_basis_points,
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_solana::cpi::set_marketplace_fee(
+            CpiContext::new_with_signer(
+                ctx.accounts.marketplace_program.to_account_info(),
+                datasov_solana::cpi::accounts::UpdateMarketplace {
+                    marketplace: ctx.accounts.marketplace.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fee_basis_points,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed compliance rule proposal (callable by anyone)
+    pub fn execute_set_compliance_rule(
+        ctx: Context<ExecuteComplianceProposal>,
+    ) -> Result<()> {
+        let (data_type, min_buyer_level, min_seller_level, min_consumer_stake) = match &ctx.accounts.proposal.action {
+            ProposalAction::SetComplianceRule {
+                data_type,
+                min_buyer_level,
+                min_seller_level,
+                min_consumer_stake,
+            } => (data_type.clone(), min_buyer_level.clone(), min_seller_level.clone(), *min_consumer_stake),
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_identity::cpi::set_compliance_rule(
+            CpiContext::new_with_signer(
+                ctx.accounts.identity_program.to_account_info(),
+                datasov_identity::cpi::accounts::SetComplianceRule {
+                    compliance_matrix: ctx.accounts.compliance_matrix.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                    oracle_registry: ctx.accounts.oracle_registry.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            data_type,
+            min_buyer_level,
+            min_seller_level,
+            min_consumer_stake,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed oracle minimums proposal (callable by anyone)
+    pub fn execute_set_oracle_registry_parameters(
+        ctx: Context<ExecuteOracleRegistryProposal>,
+    ) -> Result<()> {
+        let (minimum_stake, slash_amount) = match ctx.accounts.proposal.action {
+            ProposalAction::SetOracleRegistryParameters { minimum_stake, slash_amount } => (minimum_stake, slash_amount),
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_identity::cpi::set_oracle_registry_parameters(
+            CpiContext::new_with_signer(
+                ctx.accounts.identity_program.to_account_info(),
+                datasov_identity::cpi::accounts::UpdateOracleRegistry {
+                    oracle_registry: ctx.accounts.oracle_registry.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            minimum_stake,
+            slash_amount,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed fee rebate emission proposal (callable by anyone)
+    pub fn execute_set_rebate_emission(
+        ctx: Context<ExecuteRebateProposal>,
+    ) -> Result<()> {
+        let emission_per_epoch = match ctx.accounts.proposal.action {
+            ProposalAction::SetRebateEmission { emission_per_epoch } => emission_per_epoch,
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_solana::cpi::set_rebate_emission(
+            CpiContext::new_with_signer(
+                ctx.accounts.marketplace_program.to_account_info(),
+                datasov_solana::cpi::accounts::UpdateRebateProgram {
+                    marketplace: ctx.accounts.marketplace.to_account_info(),
+                    rebate_program: ctx.accounts.rebate_program.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            emission_per_epoch,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed vesting revocation proposal (callable by anyone)
+    pub fn execute_revoke_vesting(
+        ctx: Context<ExecuteVestingProposal>,
+    ) -> Result<()> {
+        let vesting = match ctx.accounts.proposal.action {
+            ProposalAction::RevokeVesting { vesting } => vesting,
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        require_keys_eq!(ctx.accounts.vesting.key(), vesting, ErrorCode::ProposalActionMismatch);
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_staking::cpi::revoke_vesting(CpiContext::new_with_signer(
+            ctx.accounts.staking_program.to_account_info(),
+            datasov_staking::cpi::accounts::RevokeVesting {
+                config: ctx.accounts.staking_config.to_account_info(),
+                vesting: ctx.accounts.vesting.to_account_info(),
+                vault: ctx.accounts.vault.to_account_info(),
+                funder_token_account: ctx.accounts.funder_token_account.to_account_info(),
+                authority: ctx.accounts.governance.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed airdrop proposal (callable by anyone), the executor paying the airdrop's rent
+    pub fn execute_create_airdrop(
+        ctx: Context<ExecuteAirdropProposal>,
+    ) -> Result<()> {
+        let (airdrop_id, merkle_root, leaf_count) = match ctx.accounts.proposal.action {
+            ProposalAction::CreateAirdrop {
+                airdrop_id,
+                merkle_root,
+                leaf_count,
+            } => (airdrop_id, merkle_root, leaf_count),
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_staking::cpi::create_airdrop(
+            CpiContext::new_with_signer(
+                ctx.accounts.staking_program.to_account_info(),
+                datasov_staking::cpi::accounts::CreateAirdrop {
+                    config: ctx.accounts.staking_config.to_account_info(),
+                    airdrop: ctx.accounts.airdrop.to_account_info(),
+                    vault: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                    payer: ctx.accounts.payer.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            airdrop_id,
+            merkle_root,
+            leaf_count,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed license template proposal (callable by anyone), the executor paying the
+    /// template's rent
+    pub fn execute_create_license_template(
+        ctx: Context<ExecuteCreateLicenseTemplateProposal>,
+    ) -> Result<()> {
+        let (template_id, terms, terms_hash) = match &ctx.accounts.proposal.action {
+            ProposalAction::CreateLicenseTemplate {
+                template_id,
+                terms,
+                terms_hash,
+            } => (*template_id, terms.clone(), *terms_hash),
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_solana::cpi::create_license_template(
+            CpiContext::new_with_signer(
+                ctx.accounts.marketplace_program.to_account_info(),
+                datasov_solana::cpi::accounts::CreateLicenseTemplate {
+                    marketplace: ctx.accounts.marketplace.to_account_info(),
+                    license_template: ctx.accounts.license_template.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                    payer: ctx.accounts.payer.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            template_id,
+            terms,
+            terms_hash,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed proposal retiring or reinstating a license template (callable by anyone)
+    pub fn execute_set_license_template_active(
+        ctx: Context<ExecuteUpdateLicenseTemplateProposal>,
+    ) -> Result<()> {
+        let (template_id, is_active) = match ctx.accounts.proposal.action {
+            ProposalAction::SetLicenseTemplateActive { template_id, is_active } => (template_id, is_active),
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        require!(
+            ctx.accounts.license_template.template_id == template_id,
+            ErrorCode::ProposalActionMismatch
+        );
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_solana::cpi::set_license_template_active(
+            CpiContext::new_with_signer(
+                ctx.accounts.marketplace_program.to_account_info(),
+                datasov_solana::cpi::accounts::UpdateLicenseTemplate {
+                    marketplace: ctx.accounts.marketplace.to_account_info(),
+                    license_template: ctx.accounts.license_template.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            is_active,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed feature flags proposal (callable by anyone), the executor paying the flags
+    /// account's rent if this is the first
+    pub fn execute_set_feature_flags(ctx: Context<ExecuteFeatureFlagsProposal>) -> Result<()> {
+        let flags = match ctx.accounts.proposal.action {
+            ProposalAction::SetFeatureFlags { flags } => flags,
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_solana::cpi::set_feature_flags(
+            CpiContext::new_with_signer(
+                ctx.accounts.marketplace_program.to_account_info(),
+                datasov_solana::cpi::accounts::SetFeatureFlags {
+                    marketplace: ctx.accounts.marketplace.to_account_info(),
+                    feature_flags: ctx.accounts.feature_flags.to_account_info(),
+                    authority: ctx.accounts.governance.to_account_info(),
+                    payer: ctx.accounts.payer.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            flags,
+        )?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+
+    /// Apply a passed identity freeze appeal (callable by anyone)
+    pub fn execute_lift_identity_freeze(
+        ctx: Context<ExecuteIdentityFreezeProposal>,
+    ) -> Result<()> {
+        let identity = match ctx.accounts.proposal.action {
+            ProposalAction::LiftIdentityFreeze { identity } => identity,
+            _ => return err!(ErrorCode::ProposalActionMismatch),
+        };
+        require_keys_eq!(ctx.accounts.identity.key(), identity, ErrorCode::ProposalActionMismatch);
+        ctx.accounts.proposal.execute(&ctx.accounts.governance)?;
+
+        let bump = ctx.accounts.governance.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::GOVERNANCE, &[bump]]];
+        datasov_identity::cpi::lift_freeze(CpiContext::new_with_signer(
+            ctx.accounts.identity_program.to_account_info(),
+            datasov_identity::cpi::accounts::LiftFreeze {
+                identity: ctx.accounts.identity.to_account_info(),
+                freeze: ctx.accounts.freeze.to_account_info(),
+                officer: ctx.accounts.officer.to_account_info(),
+                oracle_registry: ctx.accounts.oracle_registry.to_account_info(),
+                authority: ctx.accounts.governance.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        emit!(ProposalExecutedEvent {
+            proposal_id: ctx.accounts.proposal.id,
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.id);
+        Ok(())
+    }
+}
+
+// Account validation structs
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Governance::INIT_SPACE,
+        seeds = [seeds::GOVERNANCE],
+        bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositStake<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + StakeAccount::INIT_SPACE,
+        seeds = [seeds::STAKE, staker.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, StakeAccount>,
+
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::STAKE, staker.key().as_ref()],
+        bump = stake.bump,
+        has_one = staker
+    )]
+    pub stake: Account<'info, StakeAccount>,
+
+    #[account(mut)]
+    pub staker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [seeds::PROPOSAL, &governance.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [seeds::STAKE, proposer.key().as_ref()],
+        bump = stake.bump,
+        constraint = stake.staker == proposer.key() @ ErrorCode::InsufficientStake
+    )]
+    pub stake: Option<Account<'info, StakeAccount>>,
+
+    /// The proposer's staked protocol tokens
+    #[account(
+        seeds = [datasov_staking::seeds::STAKE_WEIGHT, proposer.key().as_ref()],
+        bump = proposer_weight.bump,
+        seeds::program = datasov_staking::ID
+    )]
+    pub proposer_weight: Option<Account<'info, StakeWeight>>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    /// Signs the lock on the voter's staked tokens
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [seeds::STAKE, voter.key().as_ref()],
+        bump = stake.bump,
+        constraint = stake.staker == voter.key() @ ErrorCode::InsufficientStake
+    )]
+    pub stake: Option<Account<'info, StakeAccount>>,
+
+    /// The voter's staked protocol tokens, locked by the staking program until voting closes
+    #[account(
+        mut,
+        seeds = [datasov_staking::seeds::STAKE_WEIGHT, voter.key().as_ref()],
+        bump = voter_weight.bump,
+        seeds::program = datasov_staking::ID
+    )]
+    pub voter_weight: Option<Account<'info, StakeWeight>>,
+
+    /// Required with `voter_weight`
+    pub staking_config: Option<Account<'info, StakingConfig>>,
+
+    /// Required with `voter_weight`
+    pub staking_program: Option<Program<'info, DatasovStaking>>,
+
+    /// One vote per voter and proposal
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + VoteRecord::INIT_SPACE,
+        seeds = [seeds::VOTE, proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, VoteRecord>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMarketplaceProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub marketplace_program: Program<'info, DatasovSolana>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteComplianceProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(mut)]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteOracleRegistryProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRebateProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(mut)]
+    pub rebate_program: Account<'info, RebateProgram>,
+
+    pub marketplace_program: Program<'info, DatasovSolana>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteVestingProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub staking_config: Account<'info, StakingConfig>,
+
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+
+    /// CHECK: The vesting's vault, verified by the staking program
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: The funder's token account, verified by the staking program
+    #[account(mut)]
+    pub funder_token_account: UncheckedAccount<'info>,
+
+    pub staking_program: Program<'info, DatasovStaking>,
+
+    /// CHECK: The SPL token program, verified by the staking program
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAirdropProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub staking_config: Account<'info, StakingConfig>,
+
+    /// CHECK: Created by the staking program at its airdrop PDA
+    #[account(mut)]
+    pub airdrop: UncheckedAccount<'info>,
+
+    /// CHECK: Created by the staking program at the airdrop's vault PDA
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: The protocol token, verified by the staking program
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub staking_program: Program<'info, DatasovStaking>,
+
+    /// CHECK: The SPL token program, verified by the staking program
+    pub token_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCreateLicenseTemplateProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// CHECK: Created by the marketplace program at the template's PDA
+    #[account(mut)]
+    pub license_template: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub marketplace_program: Program<'info, DatasovSolana>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteUpdateLicenseTemplateProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(mut)]
+    pub license_template: Account<'info, LicenseTemplate>,
+
+    pub marketplace_program: Program<'info, DatasovSolana>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteFeatureFlagsProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// CHECK: Created on first use and validated by the marketplace program
+    #[account(mut)]
+    pub feature_flags: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub marketplace_program: Program<'info, DatasovSolana>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteIdentityFreezeProposal<'info> {
+    #[account(
+        seeds = [seeds::GOVERNANCE],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PROPOSAL, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(mut)]
+    pub freeze: Account<'info, IdentityFreeze>,
+
+    /// CHECK: The officer who placed the freeze, verified by the identity program
+    #[account(mut)]
+    pub officer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+}
+
+// Account structures
+
+#[account]
+#[derive(InitSpace)]
+pub struct Governance {
+    /// Seconds a proposal is open for voting
+    pub voting_period: i64,
+    /// Least stake, yes and no combined, that must vote for a proposal to pass
+    pub quorum: u64,
+    /// Share of the votes cast that must approve, in basis points
+    pub approval_threshold_bps: u16,
+    /// Least stake a proposer must hold
+    pub proposal_threshold: u64,
+    pub proposal_count: u64,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakeAccount {
+    pub staker: Pubkey,
+    pub amount: u64,
+    /// End of the latest vote the stake was cast in; withdrawals wait until then
+    pub locked_until: i64,
+    pub bump: u8,
+}
+
+/// Votes a staker carries: lamports locked in `stake` plus the weight of their staked protocol
+/// tokens
+pub fn voting_weight(stake: Option<&StakeAccount>, weight: Option<&StakeWeight>) -> u64 {
+    stake.map_or(0, |stake| stake.amount) + weight.map_or(0, |weight| weight.weight)
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    /// Where the rationale for the change is published
+    #[max_len(128)]
+    pub description_uri: String,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub executed_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl Proposal {
+    /// Whether the proposal passed under `governance`'s quorum and threshold
+    pub fn passed(&self, governance: &Governance) -> bool {
+        let cast = self.yes_votes as u128 + self.no_votes as u128;
+        cast > 0
+            && cast >= governance.quorum as u128
+            && self.yes_votes as u128 * 10000 >= cast * governance.approval_threshold_bps as u128
+    }
+
+    /// Mark a closed, passed proposal executed
+    fn execute(&mut self, governance: &Governance) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(self.executed_at.is_none(), ErrorCode::ProposalAlreadyExecuted);
+        require!(now >= self.voting_ends_at, ErrorCode::VotingOpen);
+        require!(self.passed(governance), ErrorCode::ProposalNotPassed);
+
+        self.executed_at = Some(now);
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub approve: bool,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+// Enums
+
+/// A parameter change a proposal applies once passed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum ProposalAction {
+    /// Marketplace fee charged on purchases
+    SetMarketplaceFee { fee_basis_points: u16 },
+    /// Minimum verification levels and consumer stake for a data type
+    SetComplianceRule {
+        data_type: DataType,
+        min_buyer_level: VerificationLevel,
+        min_seller_level: VerificationLevel,
+        min_consumer_stake: u64,
+    },
+    /// Stake KYC oracles must post and the amount slashed per upheld dispute
+    SetOracleRegistryParameters { minimum_stake: u64, slash_amount: u64 },
+    /// Protocol tokens each marketplace fee rebate epoch may pay out
+    SetRebateEmission { emission_per_epoch: u64 },
+    /// Stop a vesting, returning its unvested tokens to the funder
+    RevokeVesting { vesting: Pubkey },
+    /// Post the Merkle root of an airdrop's allocations
+    CreateAirdrop {
+        airdrop_id: u64,
+        merkle_root: [u8; 32],
+        leaf_count: u32,
+    },
+    /// Publish license terms for listings to reference
+    CreateLicenseTemplate {
+        template_id: u32,
+        terms: LicenseTerms,
+        terms_hash: [u8; 32],
+    },
+    /// Retire a license template from new listings, or reinstate it
+    SetLicenseTemplateActive { template_id: u32, is_active: bool },
+    /// Replace the set of marketplace subsystems enabled on this cluster
+    SetFeatureFlags { flags: u64 },
+    /// Lift a compliance freeze on an identity, upholding its owner's appeal
+    LiftIdentityFreeze { identity: Pubkey },
+}
+
+impl ProposalAction {
+    /// Reject actions the governed program would refuse, before anyone votes on them
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ProposalAction::SetMarketplaceFee { fee_basis_points } => {
+                require!(*fee_basis_points <= 10000, ErrorCode::InvalidProposalAction);
+            }
+            ProposalAction::CreateAirdrop { leaf_count, .. } => {
+                require!(*leaf_count > 0, ErrorCode::InvalidProposalAction);
+            }
+            ProposalAction::CreateLicenseTemplate { terms, .. } => {
+                require!(terms.is_valid(), ErrorCode::InvalidProposalAction);
+            }
+            ProposalAction::SetFeatureFlags { flags } => {
+                require!(*flags & !FeatureFlags::KNOWN == 0, ErrorCode::InvalidProposalAction);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// Events
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct VoteCastEvent {
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub approve: bool,
+    pub weight: u64,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: u64,
+}
+
+// Error codes
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Voting period must be positive and the approval threshold between 1 and 10000 basis points")]
+    InvalidGovernanceConfig,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Stake is locked until the proposals it voted on close")]
+    StakeLocked,
+    #[msg("Insufficient governance stake")]
+    InsufficientStake,
+    #[msg("Description URI too long")]
+    DescriptionUriTooLong,
+    #[msg("Proposal action has invalid parameters")]
+    InvalidProposalAction,
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    #[msg("Voting on this proposal is still open")]
+    VotingOpen,
+    #[msg("Proposal did not reach quorum or the approval threshold")]
+    ProposalNotPassed,
+    #[msg("Proposal already executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Proposal action does not match the instruction")]
+    ProposalActionMismatch,
+    #[msg("Voting with staked tokens requires the staking config and program")]
+    StakingAccountsMissing,
+}
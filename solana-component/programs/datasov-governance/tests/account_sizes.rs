@@ -0,0 +1,163 @@
+//! Every account, filled to the limits the program enforces, must serialize within the space `InitSpace` derives for it.
+
+use anchor_lang::prelude::*;
+use datasov_governance::{Governance, Proposal, ProposalAction, StakeAccount, VoteRecord};
+use datasov_identity::{DataType, VerificationLevel};
+use datasov_solana::LicenseTerms;
+use proptest::prelude::*;
+
+/// Serialize with the discriminator, check the encoding fits and round-trips
+fn assert_fits<T: AccountSerialize + AccountDeserialize + Space>(account: &T) {
+    let len = 8 + T::INIT_SPACE;
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= len, "{} bytes serialized, {} allocated", data.len(), len);
+
+    let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+    let mut reencoded = Vec::new();
+    decoded.try_serialize(&mut reencoded).unwrap();
+    assert_eq!(data, reencoded);
+}
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+/// ASCII string of exactly `len` bytes, the longest the program accepts
+fn text(len: usize) -> impl Strategy<Value = String> {
+    proptest::collection::vec(0x20u8..0x7f, len).prop_map(|bytes| String::from_utf8(bytes).unwrap())
+}
+
+fn data_type() -> impl Strategy<Value = DataType> {
+    prop_oneof![
+        Just(DataType::LocationHistory),
+        Just(DataType::AppUsage),
+        Just(DataType::PurchaseHistory),
+        Just(DataType::HealthData),
+        Just(DataType::SocialMediaActivity),
+        Just(DataType::SearchHistory),
+        Just(DataType::FinancialData),
+        Just(DataType::CommunicationData),
+        Just(DataType::Custom),
+    ]
+}
+
+fn verification_level() -> impl Strategy<Value = VerificationLevel> {
+    prop_oneof![
+        Just(VerificationLevel::None),
+        Just(VerificationLevel::Basic),
+        Just(VerificationLevel::Enhanced),
+        Just(VerificationLevel::High),
+        Just(VerificationLevel::Credential),
+    ]
+}
+
+fn proposal_action() -> impl Strategy<Value = ProposalAction> {
+    prop_oneof![
+        any::<u16>().prop_map(|fee_basis_points| ProposalAction::SetMarketplaceFee { fee_basis_points }),
+        (data_type(), verification_level(), verification_level(), any::<u64>()).prop_map(
+            |(data_type, min_buyer_level, min_seller_level, min_consumer_stake)| ProposalAction::SetComplianceRule {
+                data_type,
+                min_buyer_level,
+                min_seller_level,
+                min_consumer_stake,
+            },
+        ),
+        (any::<u64>(), any::<u64>()).prop_map(|(minimum_stake, slash_amount)| {
+            ProposalAction::SetOracleRegistryParameters {
+                minimum_stake,
+                slash_amount,
+            }
+        }),
+        any::<u64>().prop_map(|emission_per_epoch| ProposalAction::SetRebateEmission { emission_per_epoch }),
+        pubkey().prop_map(|vesting| ProposalAction::RevokeVesting { vesting }),
+        (any::<u64>(), any::<[u8; 32]>(), any::<u32>()).prop_map(|(airdrop_id, merkle_root, leaf_count)| {
+            ProposalAction::CreateAirdrop {
+                airdrop_id,
+                merkle_root,
+                leaf_count,
+            }
+        }),
+        (
+            any::<u32>(),
+            any::<(bool, bool, bool)>(),
+            proptest::option::of(any::<u32>()),
+            any::<[u8; 32]>()
+        )
+            .prop_map(
+                |(template_id, (commercial_use, research_only, redistribution), retention_days, terms_hash)| {
+                    ProposalAction::CreateLicenseTemplate {
+                        template_id,
+                        terms: LicenseTerms {
+                            commercial_use,
+                            research_only,
+                            redistribution,
+                            retention_days,
+                        },
+                        terms_hash,
+                    }
+                }
+            ),
+        (any::<u32>(), any::<bool>()).prop_map(|(template_id, is_active)| {
+            ProposalAction::SetLicenseTemplateActive { template_id, is_active }
+        }),
+        any::<u64>().prop_map(|flags| ProposalAction::SetFeatureFlags { flags }),
+        pubkey().prop_map(|identity| ProposalAction::LiftIdentityFreeze { identity }),
+    ]
+}
+
+#[test]
+fn governance_fits() {
+    let governance = Governance {
+        voting_period: i64::MAX,
+        quorum: u64::MAX,
+        approval_threshold_bps: u16::MAX,
+        proposal_threshold: u64::MAX,
+        proposal_count: u64::MAX,
+        total_staked: u64::MAX,
+        bump: u8::MAX,
+    };
+    assert_fits(&governance);
+}
+
+#[test]
+fn stake_account_fits() {
+    let stake = StakeAccount {
+        staker: Pubkey::new_unique(),
+        amount: u64::MAX,
+        locked_until: i64::MAX,
+        bump: u8::MAX,
+    };
+    assert_fits(&stake);
+}
+
+proptest! {
+    #[test]
+    fn proposal_fits(proposer in pubkey(), action in proposal_action(), description_uri in text(128)) {
+        let proposal = Proposal {
+            id: u64::MAX,
+            proposer,
+            action,
+            description_uri,
+            created_at: i64::MAX,
+            voting_ends_at: i64::MAX,
+            yes_votes: u64::MAX,
+            no_votes: u64::MAX,
+            executed_at: Some(i64::MAX),
+            bump: u8::MAX,
+        };
+        assert_fits(&proposal);
+    }
+
+    #[test]
+    fn vote_record_fits(proposal in pubkey(), voter in pubkey(), approve in any::<bool>()) {
+        let vote = VoteRecord {
+            proposal,
+            voter,
+            approve,
+            weight: u64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&vote);
+    }
+}
@@ -1,7 +1,106 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::alt_bn128::prelude::{
+    alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing,
+};
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_lang::system_program;
+use datasov_audit::program::DatasovAudit;
+use datasov_audit::AuditAction;
 
 declare_id!("DataSovIdentity11111111111111111111111111111");
 
+/// DataSov marketplace program, allowed to record settlement outcomes on consumer profiles
+pub mod marketplace_program {
+    use super::*;
+    declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+    /// Seed of the marketplace config PDA, which signs the marketplace's CPIs into this program
+    pub const MARKETPLACE_SEED: &[u8] = b"marketplace";
+}
+
+/// Civic gateway program, which issues Civic Pass gateway tokens
+pub mod civic_gateway_program {
+    use super::*;
+    declare_id!("gatem74V238djXdzWnJf94Wo1DcnuGkfijbf3AuBhfs");
+}
+
+/// Solana Attestation Service program
+pub mod attestation_service_program {
+    use super::*;
+    declare_id!("22zoJMtdu4tQc2PzL74ZUT7FrwgB1Udec8DdW4yw4BdG");
+}
+
+/// PDA seed prefixes
+pub mod seeds {
+    pub const ORACLE_REGISTRY: &[u8] = b"oracle_registry";
+    /// `KYCOracle`, per authority
+    pub const ORACLE: &[u8] = b"oracle";
+    /// `IdentityAccount`, per [`identity_id`] hash
+    pub const IDENTITY: &[u8] = b"identity";
+    /// `AccessPermission`, per identity and consumer
+    pub const PERMISSION: &[u8] = b"permission";
+    /// `TrialRecord`, per consumer
+    pub const TRIAL: &[u8] = b"trial";
+    pub const COMPLIANCE_MATRIX: &[u8] = b"compliance_matrix";
+    pub const CRANK_FUND: &[u8] = b"crank_fund";
+    pub const BLACKLIST: &[u8] = b"blacklist";
+    /// `ConsumerProfile`, per consumer
+    pub const CONSUMER: &[u8] = b"consumer";
+    /// `ConsumerStake`, per consumer
+    pub const CONSUMER_STAKE: &[u8] = b"consumer_stake";
+    pub const JUROR_POOL: &[u8] = b"juror_pool";
+    /// `Juror`, per authority
+    pub const JUROR: &[u8] = b"juror";
+    /// `Dispute`, per dispute ID
+    pub const DISPUTE: &[u8] = b"dispute";
+    /// `DataTrust`, per creator and trust ID
+    pub const TRUST: &[u8] = b"trust";
+    /// `TrustMembership`, per trust and identity
+    pub const TRUST_MEMBER: &[u8] = b"trust_member";
+    /// `ExportRequest`, per identity and consumer
+    pub const EXPORT: &[u8] = b"export";
+    /// `ConsumptionAttestation`, per permission and attestation index
+    pub const CONSUMPTION: &[u8] = b"consumption";
+    /// `AttestationSource`, per issuer and schema
+    pub const ATTESTATION_SOURCE: &[u8] = b"attestation_source";
+    /// `TeeAttestation`, per compute provider
+    pub const TEE_ATTESTATION: &[u8] = b"tee_attestation";
+    /// `DomainLink`, per SNS domain account
+    pub const DOMAIN_LINK: &[u8] = b"domain_link";
+    /// `WebProofVerifier`, per verifier ID
+    pub const WEB_PROOF_VERIFIER: &[u8] = b"web_proof_verifier";
+    /// `WebProofNullifier`, per verifier and nullifier
+    pub const WEB_PROOF_NULLIFIER: &[u8] = b"web_proof_nullifier";
+    /// `VoucherNonce`, per identity and voucher nonce
+    pub const VOUCHER_NONCE: &[u8] = b"voucher_nonce";
+    /// `AccessRequest`, per identity and consumer
+    pub const ACCESS_REQUEST: &[u8] = b"access_request";
+    pub const STATE_SNAPSHOT: &[u8] = b"state_snapshot";
+    /// `ComplianceOfficer`, per officer
+    pub const COMPLIANCE_OFFICER: &[u8] = b"compliance_officer";
+    /// `IdentityFreeze`, per identity
+    pub const IDENTITY_FREEZE: &[u8] = b"identity_freeze";
+
+    /// Seed of an identity ID: its SHA-256, a fixed 32 bytes however long the ID (a raw seed
+    /// cannot exceed 32 bytes, and IDs run to 64)
+    pub fn identity_id(identity_id: &str) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(identity_id.as_bytes()).to_bytes()
+    }
+}
+
+/// Solana Name Service program and the `.sol` top-level domain it resolves under
+pub mod name_service_program {
+    use super::*;
+    declare_id!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+
+    pub mod sol_tld {
+        use super::*;
+        declare_id!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
+    }
+}
+
 #[program]
 pub mod datasov_identity {
     use super::*;
@@ -17,6 +116,7 @@ pub mod datasov_identity {
         registry.minimum_stake = minimum_stake;
         registry.slash_amount = slash_amount;
         registry.oracle_count = 0;
+        registry.event_sequence = 0;
         registry.bump = ctx.bumps.oracle_registry;
 
         msg!("KYC Oracle Registry initialized with minimum stake: {} lamports", minimum_stake);
@@ -33,6 +133,7 @@ pub mod datasov_identity {
         let oracle = &mut ctx.accounts.oracle;
 
         require!(stake_amount >= registry.minimum_stake, ErrorCode::InsufficientStake);
+        require!(provider_name.len() <= 64, ErrorCode::ProviderNameTooLong);
 
         oracle.oracle_pubkey = ctx.accounts.oracle_authority.key();
         oracle.provider_name = provider_name.clone();
@@ -50,6 +151,8 @@ pub mod datasov_identity {
             oracle_pubkey: oracle.oracle_pubkey,
             provider_name: provider_name,
             stake_amount: stake_amount,
+            event_sequence: registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
         });
 
         msg!("KYC Oracle registered: {}", oracle.oracle_pubkey);
@@ -60,27 +163,34 @@ pub mod datasov_identity {
     pub fn register_identity(
         ctx: Context<RegisterIdentity>,
         identity_id: String,
-        arweave_tx_id: String,
+        storage_pointer: StoragePointer,
     ) -> Result<()> {
         let identity = &mut ctx.accounts.identity;
 
         require!(identity_id.len() <= 64, ErrorCode::IdentityIdTooLong);
-        require!(arweave_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(storage_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
 
         identity.identity_id = identity_id.clone();
         identity.owner = ctx.accounts.owner.key();
-        identity.arweave_tx_id = arweave_tx_id.clone();
+        identity.storage_pointer = storage_pointer.clone();
         identity.status = IdentityStatus::Pending;
         identity.verification_level = VerificationLevel::None;
         identity.verified_at = None;
         identity.created_at = Clock::get()?.unix_timestamp;
         identity.updated_at = Clock::get()?.unix_timestamp;
+        identity.guardian = None;
+        identity.emancipation_at = None;
+        identity.sns_domain = String::new();
+        identity.sns_domain_account = None;
+        identity.encryption_key = None;
         identity.bump = ctx.bumps.identity;
 
         emit!(IdentityRegisteredEvent {
             identity_id: identity_id,
             owner: identity.owner,
-            arweave_tx_id: arweave_tx_id,
+            storage_pointer: storage_pointer,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
         });
 
         msg!("Identity registered: {}", identity.identity_id);
@@ -91,19 +201,33 @@ pub mod datasov_identity {
     pub fn verify_identity(
         ctx: Context<VerifyIdentity>,
         verification_level: VerificationLevel,
-        arweave_kyc_tx_id: String,
+        kyc_pointer: StoragePointer,
     ) -> Result<()> {
         let identity = &mut ctx.accounts.identity;
         let oracle = &mut ctx.accounts.oracle;
 
         require!(identity.status == IdentityStatus::Pending, ErrorCode::InvalidStatus);
         require!(oracle.is_active, ErrorCode::OracleNotActive);
-        require!(arweave_kyc_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(kyc_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
+
+        // An oracle cannot vouch for an identity it controls. The attempt succeeds without verifying
+        // anything, so its event and the count against the oracle are kept rather than rolled back.
+        if identity.is_linked_wallet(&ctx.accounts.oracle_authority.key()) {
+            oracle.verification_count += 1;
+            emit!(SelfVerificationRejectedEvent {
+                account: identity.key(),
+                verifier: ctx.accounts.oracle_authority.key(),
+                event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+                event_slot: Clock::get()?.slot,
+            });
+            msg!("Self-verification rejected: {}", identity.identity_id);
+            return Ok(());
+        }
 
         identity.status = IdentityStatus::Verified;
         identity.verification_level = verification_level.clone();
         identity.verified_at = Some(Clock::get()?.unix_timestamp);
-        identity.arweave_tx_id = arweave_kyc_tx_id.clone();
+        identity.storage_pointer = kyc_pointer.clone();
         identity.updated_at = Clock::get()?.unix_timestamp;
 
         // Update oracle statistics
@@ -114,7 +238,9 @@ pub mod datasov_identity {
             identity_id: identity.identity_id.clone(),
             verification_level: verification_level,
             oracle_pubkey: oracle.oracle_pubkey,
-            arweave_tx_id: arweave_kyc_tx_id,
+            storage_pointer: kyc_pointer,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
         });
 
         msg!("Identity verified: {} at level: {:?}", identity.identity_id, identity.verification_level);
@@ -124,20 +250,22 @@ pub mod datasov_identity {
     /// Update identity information
     pub fn update_identity(
         ctx: Context<UpdateIdentity>,
-        new_arweave_tx_id: String,
+        new_storage_pointer: StoragePointer,
     ) -> Result<()> {
         let identity = &mut ctx.accounts.identity;
 
         require!(identity.status == IdentityStatus::Verified, ErrorCode::IdentityNotVerified);
         require!(identity.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
-        require!(new_arweave_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(new_storage_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
 
-        identity.arweave_tx_id = new_arweave_tx_id.clone();
+        identity.storage_pointer = new_storage_pointer.clone();
         identity.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(IdentityUpdatedEvent {
             identity_id: identity.identity_id.clone(),
-            arweave_tx_id: new_arweave_tx_id,
+            storage_pointer: new_storage_pointer,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
         });
 
         msg!("Identity updated: {}", identity.identity_id);
@@ -147,20 +275,22 @@ pub mod datasov_identity {
     /// Revoke an identity
     pub fn revoke_identity(
         ctx: Context<RevokeIdentity>,
-        arweave_revocation_tx_id: String,
+        revocation_pointer: StoragePointer,
     ) -> Result<()> {
         let identity = &mut ctx.accounts.identity;
 
         require!(identity.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
-        require!(arweave_revocation_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(revocation_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
 
         identity.status = IdentityStatus::Revoked;
-        identity.arweave_tx_id = arweave_revocation_tx_id.clone();
+        identity.storage_pointer = revocation_pointer.clone();
         identity.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(IdentityRevokedEvent {
             identity_id: identity.identity_id.clone(),
-            arweave_tx_id: arweave_revocation_tx_id,
+            storage_pointer: revocation_pointer,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
         });
 
         msg!("Identity revoked: {}", identity.identity_id);
@@ -173,342 +303,5623 @@ pub mod datasov_identity {
         permission_type: PermissionType,
         data_types: Vec<DataType>,
         expires_at: Option<i64>,
-        arweave_permission_tx_id: String,
+        permission_pointer: StoragePointer,
     ) -> Result<()> {
-        let permission = &mut ctx.accounts.permission;
-        let identity = &ctx.accounts.identity;
-
-        require!(identity.status == IdentityStatus::Verified, ErrorCode::IdentityNotVerified);
-        require!(identity.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
-        require!(data_types.len() > 0, ErrorCode::NoDataTypes);
-        require!(data_types.len() <= 10, ErrorCode::TooManyDataTypes);
-        require!(arweave_permission_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
-
-        permission.identity_id = identity.identity_id.clone();
-        permission.consumer = ctx.accounts.consumer.key();
-        permission.permission_type = permission_type.clone();
-        permission.data_types = data_types.clone();
-        permission.granted_at = Clock::get()?.unix_timestamp;
-        permission.expires_at = expires_at;
-        permission.is_active = true;
-        permission.arweave_proof_tx_id = arweave_permission_tx_id.clone();
-        permission.bump = ctx.bumps.permission;
-
-        emit!(AccessGrantedEvent {
-            identity_id: identity.identity_id.clone(),
-            consumer: ctx.accounts.consumer.key(),
-            permission_type: permission_type,
-            data_types: data_types,
-            arweave_tx_id: arweave_permission_tx_id,
-        });
+        require!(ctx.accounts.identity.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
 
-        msg!("Access granted for identity: {} to consumer: {}", identity.identity_id, ctx.accounts.consumer.key());
-        Ok(())
+        let data_type_mask = DataType::mask(&data_types);
+        ctx.accounts.apply_grant(
+            ctx.bumps.permission,
+            permission_type,
+            data_types,
+            expires_at,
+            permission_pointer,
+        )?;
+        ctx.accounts.record_grant(ctx.bumps.audit.audit_writer, data_type_mask)
     }
 
     /// Revoke access permission
     pub fn revoke_access(
         ctx: Context<RevokeAccess>,
-        arweave_revocation_tx_id: String,
+        revocation_pointer: StoragePointer,
     ) -> Result<()> {
+        require!(ctx.accounts.identity.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+
+        revoke_permission(
+            &mut ctx.accounts.permission,
+            &ctx.accounts.identity,
+            &mut ctx.accounts.oracle_registry,
+            &mut ctx.accounts.consumer_profile,
+            revocation_pointer,
+        )?;
+        ctx.accounts.audit.record(
+            ctx.bumps.audit.audit_writer,
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.identity.key(),
+            AuditAction::Revoke,
+            ctx.accounts.owner.key(),
+            ctx.accounts.permission.key(),
+            0,
+        )
+    }
+
+    /// Validate access (can be called by marketplace or other programs); returns the grant's
+    /// terms and remaining allowances so callers can act on them without re-reading the permission
+    pub fn validate_access(
+        ctx: Context<ValidateAccess>,
+        data_type: DataType,
+        epsilon_cost: u32,
+    ) -> Result<AccessValidation> {
         let permission = &mut ctx.accounts.permission;
         let identity = &ctx.accounts.identity;
+        let now = Clock::get()?.unix_timestamp;
 
-        require!(identity.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        require!(identity.is_verified(now), ErrorCode::IdentityNotVerified);
         require!(permission.is_active, ErrorCode::PermissionNotActive);
-        require!(arweave_revocation_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(permission.data_types.contains(&data_type), ErrorCode::DataTypeNotAuthorized);
+        require!(!ctx.accounts.blacklist.contains(&permission.consumer), ErrorCode::ConsumerBlacklisted);
+        require!(
+            ConsumerStake::covers(
+                ctx.accounts.consumer_stake.as_deref(),
+                ctx.accounts.compliance_matrix.required_consumer_stake(&[data_type.clone()])
+            ),
+            ErrorCode::InsufficientConsumerStake
+        );
+
+        // Check expiration
+        if let Some(expires_at) = permission.expires_at {
+            require!(now < expires_at, ErrorCode::PermissionExpired);
+        }
+
+        // Enforce capped access counts (e.g. trial grants)
+        if let Some(max_accesses) = permission.max_accesses {
+            require!(permission.access_count < max_accesses, ErrorCode::AccessLimitReached);
+        }
+        permission.access_count += 1;
+        permission.charge_privacy(epsilon_cost)?;
 
-        permission.is_active = false;
-        permission.arweave_proof_tx_id = arweave_revocation_tx_id.clone();
+        ctx.accounts.audit.record(
+            ctx.bumps.audit.audit_writer,
+            ctx.accounts.consumer.to_account_info(),
+            identity.key(),
+            AuditAction::Validate,
+            permission.consumer,
+            permission.key(),
+            data_type.bit() as u64,
+        )?;
 
-        emit!(AccessRevokedEvent {
-            identity_id: identity.identity_id.clone(),
+        // Compact on the hot path: the audit entry records the consumer, permission and data type
+        msg!("Access validated");
+        Ok(AccessValidation {
+            identity: identity.key(),
             consumer: permission.consumer,
-            arweave_tx_id: arweave_revocation_tx_id,
-        });
+            permission_type: permission.permission_type.clone(),
+            allowed_purposes: permission.allowed_purposes,
+            expires_at: permission.expires_at,
+            access_count: permission.access_count,
+            max_accesses: permission.max_accesses,
+            privacy_remaining: permission.privacy_budget.map(|budget| budget - permission.privacy_spent),
+            consent_hash: permission.consent_hash,
+            consent_version: permission.consent_version,
+        })
+    }
+
+    /// Check (permission, data type) pairs without metering them: bit `i` of the returned mask is
+    /// set when check `i` would pass `validate_access`. Remaining accounts are each check's
+    /// permission and identity, then the stake account of any consumer that has one.
+    pub fn validate_access_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ValidateAccessBatch<'info>>,
+        data_types: Vec<DataType>,
+    ) -> Result<u64> {
+        require!(data_types.len() <= AccessPermission::MAX_BATCH_CHECKS, ErrorCode::BatchTooLarge);
+        require!(ctx.remaining_accounts.len() >= data_types.len() * 2, ErrorCode::BatchAccountMismatch);
+
+        let (check_accounts, stake_accounts) = ctx.remaining_accounts.split_at(data_types.len() * 2);
+        let stakes = stake_accounts
+            .iter()
+            .map(Account::<ConsumerStake>::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        let now = Clock::get()?.unix_timestamp;
+        let mut mask: u64 = 0;
+
+        for (index, (data_type, accounts)) in data_types.iter().zip(check_accounts.chunks(2)).enumerate() {
+            let permission = Account::<AccessPermission>::try_from(&accounts[0])?;
+            let identity = Account::<IdentityAccount>::try_from(&accounts[1])?;
+            require!(permission.identity_id == identity.identity_id, ErrorCode::BatchAccountMismatch);
+
+            let stake = stakes.iter().find(|stake| stake.consumer == permission.consumer);
+            let required_stake = ctx.accounts.compliance_matrix.required_consumer_stake(&[data_type.clone()]);
+            if identity.is_verified(now)
+                && permission.permits(data_type, now)
+                && !ctx.accounts.blacklist.contains(&permission.consumer)
+                && ConsumerStake::covers(stake.map(|stake| &**stake), required_stake)
+            {
+                mask |= 1 << index;
+            }
+        }
+
+        msg!("Access batch validated");
+        Ok(mask)
+    }
 
-        msg!("Access revoked for identity: {} from consumer: {}", identity.identity_id, permission.consumer);
+    /// Initialize the compliance matrix (registry authority only)
+    pub fn initialize_compliance_matrix(
+        ctx: Context<InitializeComplianceMatrix>,
+    ) -> Result<()> {
+        let matrix = &mut ctx.accounts.compliance_matrix;
+        matrix.authority = ctx.accounts.authority.key();
+        matrix.rules = Vec::new();
+        matrix.bump = ctx.bumps.compliance_matrix;
+
+        msg!("Compliance matrix initialized");
         Ok(())
     }
 
-    /// Validate access (can be called by marketplace or other programs)
-    pub fn validate_access(
-        ctx: Context<ValidateAccess>,
+    /// Set the minimum buyer and seller verification levels for a data type
+    pub fn set_compliance_rule(
+        ctx: Context<SetComplianceRule>,
         data_type: DataType,
+        min_buyer_level: VerificationLevel,
+        min_seller_level: VerificationLevel,
+        min_consumer_stake: u64,
     ) -> Result<()> {
-        let permission = &ctx.accounts.permission;
-        let identity = &ctx.accounts.identity;
+        let matrix = &mut ctx.accounts.compliance_matrix;
 
-        require!(identity.status == IdentityStatus::Verified, ErrorCode::IdentityNotVerified);
-        require!(permission.is_active, ErrorCode::PermissionNotActive);
-        require!(permission.data_types.contains(&data_type), ErrorCode::DataTypeNotAuthorized);
+        require!(matrix.authority == ctx.accounts.authority.key(), ErrorCode::Unauthorized);
 
-        // Check expiration
-        if let Some(expires_at) = permission.expires_at {
-            require!(Clock::get()?.unix_timestamp < expires_at, ErrorCode::PermissionExpired);
+        let rule = ComplianceRule {
+            data_type: data_type.clone(),
+            min_buyer_level: min_buyer_level.clone(),
+            min_seller_level: min_seller_level.clone(),
+            min_consumer_stake: min_consumer_stake,
+        };
+        match matrix.rules.iter_mut().find(|r| r.data_type == data_type) {
+            Some(existing) => *existing = rule,
+            None => matrix.rules.push(rule),
         }
 
-        msg!("Access validated for identity: {} consumer: {} data_type: {:?}",
-             identity.identity_id, permission.consumer, data_type);
+        emit!(ComplianceRuleUpdatedEvent {
+            data_type: data_type,
+            min_buyer_level: min_buyer_level,
+            min_seller_level: min_seller_level,
+            min_consumer_stake: min_consumer_stake,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Compliance rule updated");
         Ok(())
     }
-}
 
-// Account structures
+    /// Initialize the crank fund that pays callers for cleaning up expired state
+    pub fn initialize_crank_fund(
+        ctx: Context<InitializeCrankFund>,
+        reward_per_account: u64,
+    ) -> Result<()> {
+        let crank_fund = &mut ctx.accounts.crank_fund;
+        crank_fund.authority = ctx.accounts.authority.key();
+        crank_fund.reward_per_account = reward_per_account;
+        crank_fund.total_swept = 0;
+        crank_fund.total_paid = 0;
+        crank_fund.bump = ctx.bumps.crank_fund;
 
-#[derive(Accounts)]
-pub struct InitializeOracleRegistry<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = KYCOracleRegistry::LEN,
-        seeds = [b"oracle_registry"],
-        bump
-    )]
-    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+        msg!("Crank fund initialized with reward: {} lamports per account", reward_per_account);
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Deactivate expired permissions passed as remaining accounts and pay the caller a bounty
+    pub fn sweep_expired<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepExpired<'info>>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let mut swept: u64 = 0;
 
-    pub system_program: Program<'info, System>,
-}
+        for account_info in ctx.remaining_accounts.iter() {
+            let mut permission = Account::<AccessPermission>::try_from(account_info)?;
+            require!(account_info.is_writable, ErrorCode::AccountNotWritable);
 
-#[derive(Accounts)]
-pub struct RegisterOracle<'info> {
-    #[account(
-        init,
-        payer = oracle_authority,
-        space = KYCOracle::LEN,
-        seeds = [b"oracle", oracle_authority.key().as_ref()],
-        bump
-    )]
-    pub oracle: Account<'info, KYCOracle>,
+            if !permission.is_active {
+                continue;
+            }
+            match permission.expires_at {
+                Some(expires_at) if expires_at <= now => {}
+                _ => continue,
+            }
 
-    #[account(
-        mut,
-        seeds = [b"oracle_registry"],
-        bump = oracle_registry.bump
-    )]
-    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+            permission.is_active = false;
+            permission.exit(&crate::ID)?;
+            swept += 1;
+        }
 
-    #[account(mut)]
-    pub oracle_authority: Signer<'info>,
+        let reward = CrankFund::pay_reward(&mut ctx.accounts.crank_fund, swept, &ctx.accounts.caller)?;
 
-    pub system_program: Program<'info, System>,
-}
+        emit!(ExpiredSweptEvent {
+            caller: ctx.accounts.caller.key(),
+            swept: swept,
+            reward: reward,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-#[derive(Accounts)]
-#[instruction(identity_id: String)]
-pub struct RegisterIdentity<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = IdentityAccount::LEN,
-        seeds = [b"identity", identity_id.as_bytes()],
-        bump
-    )]
-    pub identity: Account<'info, IdentityAccount>,
+        msg!("Swept {} expired permissions, reward: {} lamports", swept, reward);
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    /// Close a grant that expired more than `AccessPermission::RECLAIM_GRACE` ago (anyone may call).
+    /// The caller keeps `RentReclaim::CALLER_SHARE_BASIS_POINTS` of the recovered rent; the rest
+    /// goes back to whoever paid it.
+    pub fn reclaim_permission_rent(ctx: Context<ReclaimPermissionRent>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(ctx.accounts.permission.reclaimable(now), ErrorCode::NotReclaimable);
 
-    pub system_program: Program<'info, System>,
-}
+        let permission_info = ctx.accounts.permission.to_account_info();
+        let reward = RentReclaim::pay_caller(&permission_info, &ctx.accounts.caller)?;
+        let refund = permission_info.lamports();
 
-#[derive(Accounts)]
-pub struct VerifyIdentity<'info> {
-    #[account(
-        mut,
-        seeds = [b"identity", identity.identity_id.as_bytes()],
-        bump = identity.bump
-    )]
-    pub identity: Account<'info, IdentityAccount>,
+        emit!(RentReclaimedEvent {
+            account: permission_info.key(),
+            rent_payer: ctx.accounts.rent_payer.key(),
+            caller: ctx.accounts.caller.key(),
+            refund: refund,
+            reward: reward,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-    #[account(
-        mut,
-        seeds = [b"oracle", oracle_authority.key().as_ref()],
-        bump = oracle.bump
-    )]
-    pub oracle: Account<'info, KYCOracle>,
+        msg!("Permission {} reclaimed: {} lamports refunded, {} rewarded", permission_info.key(), refund, reward);
+        Ok(())
+    }
 
-    #[account(
-        seeds = [b"oracle_registry"],
-        bump = oracle_registry.bump
-    )]
-    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+    /// Initialize the consumer blacklist (registry authority only)
+    pub fn initialize_blacklist(
+        ctx: Context<InitializeBlacklist>,
+    ) -> Result<()> {
+        let blacklist = &mut ctx.accounts.blacklist;
+        blacklist.authority = ctx.accounts.authority.key();
+        blacklist.consumers = Vec::new();
+        blacklist.bump = ctx.bumps.blacklist;
 
-    pub oracle_authority: Signer<'info>,
-}
+        msg!("Consumer blacklist initialized");
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct UpdateIdentity<'info> {
-    #[account(
-        mut,
-        seeds = [b"identity", identity.identity_id.as_bytes()],
-        bump = identity.bump,
-        has_one = owner
-    )]
-    pub identity: Account<'info, IdentityAccount>,
+    /// Blacklist a consumer; existing grants passed as remaining accounts are flagged to their owners
+    pub fn add_to_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateBlacklist<'info>>,
+        consumer: Pubkey,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        let blacklist = &mut ctx.accounts.blacklist;
 
-    pub owner: Signer<'info>,
-}
+        require!(blacklist.authority == ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+        require!(!blacklist.contains(&consumer), ErrorCode::ConsumerAlreadyBlacklisted);
+        require!(blacklist.consumers.len() < Blacklist::MAX_CONSUMERS, ErrorCode::BlacklistFull);
 
-#[derive(Accounts)]
-pub struct RevokeIdentity<'info> {
-    #[account(
-        mut,
-        seeds = [b"identity", identity.identity_id.as_bytes()],
-        bump = identity.bump,
-        has_one = owner
-    )]
-    pub identity: Account<'info, IdentityAccount>,
+        blacklist.consumers.push(consumer);
 
-    pub owner: Signer<'info>,
-}
+        emit!(ConsumerBlacklistedEvent {
+            consumer: consumer,
+            reason_hash: reason_hash,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-#[derive(Accounts)]
-pub struct GrantAccess<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = AccessPermission::LEN,
-        seeds = [
-            b"permission",
-            identity.key().as_ref(),
-            consumer.key().as_ref()
-        ],
-        bump
-    )]
-    pub permission: Account<'info, AccessPermission>,
+        // Notify owners of grants that reference the blacklisted consumer
+        for account_info in ctx.remaining_accounts.iter() {
+            let permission = Account::<AccessPermission>::try_from(account_info)?;
+            require!(permission.consumer == consumer, ErrorCode::ConsumerMismatch);
 
-    #[account(
-        seeds = [b"identity", identity.identity_id.as_bytes()],
-        bump = identity.bump
-    )]
-    pub identity: Account<'info, IdentityAccount>,
+            emit!(BlacklistedGrantEvent {
+                identity_id: permission.identity_id.clone(),
+                consumer: consumer,
+                permission: account_info.key(),
+                is_active: permission.is_active,
+                event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+                event_slot: Clock::get()?.slot,
+            });
+        }
 
-    /// CHECK: This is the consumer who will receive access permissions
-    pub consumer: AccountInfo<'info>,
+        msg!("Consumer blacklisted: {}", consumer);
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    /// Remove a consumer from the blacklist
+    pub fn remove_from_blacklist(
+        ctx: Context<UpdateBlacklist>,
+        consumer: Pubkey,
+    ) -> Result<()> {
+        let blacklist = &mut ctx.accounts.blacklist;
 
-    pub system_program: Program<'info, System>,
-}
+        require!(blacklist.authority == ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+        require!(blacklist.contains(&consumer), ErrorCode::ConsumerNotBlacklisted);
 
-#[derive(Accounts)]
-pub struct RevokeAccess<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"permission",
-            identity.key().as_ref(),
-            permission.consumer.as_ref()
-        ],
-        bump = permission.bump
-    )]
-    pub permission: Account<'info, AccessPermission>,
+        blacklist.consumers.retain(|c| c != &consumer);
 
-    #[account(
-        seeds = [b"identity", identity.identity_id.as_bytes()],
-        bump = identity.bump,
-        has_one = owner
-    )]
-    pub identity: Account<'info, IdentityAccount>,
+        emit!(ConsumerUnblacklistedEvent {
+            consumer: consumer,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-    pub owner: Signer<'info>,
-}
+        msg!("Consumer removed from blacklist: {}", consumer);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct ValidateAccess<'info> {
-    #[account(
-        seeds = [
-            b"permission",
-            identity.key().as_ref(),
-            consumer.key().as_ref()
-        ],
-        bump = permission.bump
-    )]
-    pub permission: Account<'info, AccessPermission>,
+    /// Register a consumer profile (required before receiving grants or purchasing data)
+    pub fn register_consumer(
+        ctx: Context<RegisterConsumer>,
+        organization_name_hash: [u8; 32],
+        contact_tx_id: String,
+        kyb_attestation_tx_id: String,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.consumer_profile;
 
-    #[account(
-        seeds = [b"identity", identity.identity_id.as_bytes()],
-        bump = identity.bump
-    )]
-    pub identity: Account<'info, IdentityAccount>,
+        require!(contact_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(kyb_attestation_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
 
-    pub consumer: Signer<'info>,
-}
+        profile.consumer = ctx.accounts.consumer.key();
+        profile.organization_name_hash = organization_name_hash;
+        profile.contact_tx_id = contact_tx_id;
+        profile.kyb_attestation_tx_id = kyb_attestation_tx_id;
+        profile.completed_purchases = 0;
+        profile.disputes_lost = 0;
+        profile.revocations_received = 0;
+        profile.overdue_exports = 0;
+        profile.terms_hash = [0u8; 32];
+        profile.terms_version = 0;
+        profile.kyb_verified_by = None;
+        profile.kyb_verified_at = None;
+        profile.encryption_key = None;
+        profile.created_at = Clock::get()?.unix_timestamp;
+        profile.updated_at = Clock::get()?.unix_timestamp;
+        profile.bump = ctx.bumps.consumer_profile;
 
-// Account data structures
+        emit!(ConsumerRegisteredEvent {
+            consumer: profile.consumer,
+            organization_name_hash: organization_name_hash,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-#[account]
-pub struct KYCOracleRegistry {
-    pub authority: Pubkey,
-    pub minimum_stake: u64,
-    pub slash_amount: u64,
-    pub oracle_count: u32,
-    pub bump: u8,
-}
+        msg!("Consumer registered: {}", profile.consumer);
+        Ok(())
+    }
 
-impl KYCOracleRegistry {
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 4 + 1;
-}
+    /// Update a consumer profile's contact and KYB pointers
+    pub fn update_consumer_profile(
+        ctx: Context<UpdateConsumerProfile>,
+        contact_tx_id: String,
+        kyb_attestation_tx_id: String,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.consumer_profile;
 
-#[account]
-pub struct KYCOracle {
-    pub oracle_pubkey: Pubkey,
-    pub provider_name: String,
-    pub stake_amount: u64,
-    pub verification_count: u64,
-    pub successful_verifications: u64,
-    pub reputation_score: u16,
-    pub is_active: bool,
-    pub registered_at: i64,
-    pub bump: u8,
-}
+        require!(contact_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(kyb_attestation_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
 
-impl KYCOracle {
-    pub const LEN: usize = 8 + 32 + (4 + 64) + 8 + 8 + 8 + 2 + 1 + 8 + 1;
-}
+        profile.contact_tx_id = contact_tx_id;
+        profile.updated_at = Clock::get()?.unix_timestamp;
+
+        // Changing the KYB document invalidates any existing business verification
+        if profile.kyb_attestation_tx_id != kyb_attestation_tx_id {
+            profile.kyb_attestation_tx_id = kyb_attestation_tx_id;
+            profile.kyb_verified_by = None;
+            profile.kyb_verified_at = None;
+        }
+
+        msg!("Consumer profile updated: {}", profile.consumer);
+        Ok(())
+    }
+
+    /// Record a completed purchase on a consumer profile (marketplace program only)
+    pub fn record_consumer_purchase(
+        ctx: Context<RecordConsumerPurchase>,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.consumer_profile;
+
+        profile.completed_purchases += 1;
+        profile.updated_at = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
+    /// Lock lamports as consumer stake, required for sensitive data types
+    pub fn stake_consumer(
+        ctx: Context<StakeConsumer>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.consumer.to_account_info(),
+                to: ctx.accounts.consumer_stake.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let stake = &mut ctx.accounts.consumer_stake;
+        if stake.consumer == Pubkey::default() {
+            stake.consumer = ctx.accounts.consumer.key();
+            stake.bump = ctx.bumps.consumer_stake;
+        }
+        stake.amount += amount;
+        stake.unlock_at = None;
+
+        emit!(ConsumerStakedEvent {
+            consumer: stake.consumer,
+            amount: amount,
+            total: stake.amount,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Consumer staked: {} lamports", amount);
+        Ok(())
+    }
+
+    /// Start the unstaking cooldown; the stake stops counting towards requirements immediately
+    pub fn request_consumer_unstake(
+        ctx: Context<ConsumerStakeAction>,
+    ) -> Result<()> {
+        let stake = &mut ctx.accounts.consumer_stake;
+
+        require!(stake.unlock_at.is_none(), ErrorCode::UnstakeAlreadyRequested);
+
+        stake.unlock_at = Some(Clock::get()?.unix_timestamp + ConsumerStake::UNSTAKE_COOLDOWN);
+
+        msg!("Consumer unstake requested: {}", stake.consumer);
+        Ok(())
+    }
+
+    /// Withdraw consumer stake once the cooldown has passed
+    pub fn withdraw_consumer_stake(
+        ctx: Context<ConsumerStakeAction>,
+        amount: u64,
+    ) -> Result<()> {
+        let stake = &mut ctx.accounts.consumer_stake;
+
+        let unlock_at = stake.unlock_at.ok_or(ErrorCode::UnstakeNotRequested)?;
+        require!(Clock::get()?.unix_timestamp >= unlock_at, ErrorCode::StakeLocked);
+        require!(amount <= stake.amount, ErrorCode::InvalidAmount);
+
+        stake.amount -= amount;
+        **stake.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.consumer.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        msg!("Consumer stake withdrawn: {} lamports", amount);
+        Ok(())
+    }
+
+    /// Slash a consumer's stake for proven misuse, paying the slashed lamports to the recipient
+    pub fn slash_consumer_stake(
+        ctx: Context<SlashConsumerStake>,
+        amount: u64,
+        evidence_tx_id: String,
+    ) -> Result<()> {
+        require!(evidence_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+
+        let stake = &mut ctx.accounts.consumer_stake;
+        let slashed = ConsumerStake::slash(stake, amount, &ctx.accounts.recipient)?;
+
+        emit!(ConsumerSlashedEvent {
+            consumer: stake.consumer,
+            amount: slashed,
+            recipient: ctx.accounts.recipient.key(),
+            evidence_tx_id: evidence_tx_id,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Consumer stake slashed: {} lamports", slashed);
+        Ok(())
+    }
+
+    /// Verify a consumer as a registered business (called by KYC oracle)
+    pub fn verify_business(
+        ctx: Context<VerifyBusiness>,
+        arweave_business_registry_tx_id: String,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.consumer_profile;
+        let oracle = &mut ctx.accounts.oracle;
+
+        require!(oracle.is_active, ErrorCode::OracleNotActive);
+        require!(arweave_business_registry_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+
+        // As in verify_identity, the attempt is recorded rather than failed
+        if profile.consumer == ctx.accounts.oracle_authority.key() {
+            oracle.verification_count += 1;
+            emit!(SelfVerificationRejectedEvent {
+                account: profile.key(),
+                verifier: ctx.accounts.oracle_authority.key(),
+                event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+                event_slot: Clock::get()?.slot,
+            });
+            msg!("Self-verification rejected: {}", profile.consumer);
+            return Ok(());
+        }
+
+        profile.kyb_attestation_tx_id = arweave_business_registry_tx_id.clone();
+        profile.kyb_verified_by = Some(oracle.oracle_pubkey);
+        profile.kyb_verified_at = Some(Clock::get()?.unix_timestamp);
+        profile.updated_at = Clock::get()?.unix_timestamp;
+
+        oracle.verification_count += 1;
+        oracle.successful_verifications += 1;
+
+        emit!(BusinessVerifiedEvent {
+            consumer: profile.consumer,
+            oracle_pubkey: oracle.oracle_pubkey,
+            arweave_tx_id: arweave_business_registry_tx_id,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Business verified: {}", profile.consumer);
+        Ok(())
+    }
+
+    /// Grant a free, short-lived trial permission for a single data type. A consumer gets one trial
+    /// of each data type, whichever identity grants it.
+    pub fn grant_trial_access(
+        ctx: Context<GrantTrialAccess>,
+        data_type: DataType,
+        duration_seconds: i64,
+        max_accesses: u32,
+        permission_pointer: StoragePointer,
+    ) -> Result<()> {
+        let permission = &mut ctx.accounts.permission;
+        let trial_record = &mut ctx.accounts.trial_record;
+        let identity = &ctx.accounts.identity;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(identity.status == IdentityStatus::Verified, ErrorCode::IdentityNotVerified);
+        require!(identity.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        require!(
+            identity.guardian_approved(ctx.accounts.guardian.as_ref().map(|g| g.key()), now),
+            ErrorCode::GuardianSignatureRequired
+        );
+        require!(
+            duration_seconds > 0 && duration_seconds <= AccessPermission::MAX_TRIAL_DURATION,
+            ErrorCode::InvalidTrialTerms
+        );
+        require!(
+            max_accesses > 0 && max_accesses <= AccessPermission::MAX_TRIAL_ACCESSES,
+            ErrorCode::InvalidTrialTerms
+        );
+        require!(permission_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
+        require!(!ctx.accounts.blacklist.contains(&ctx.accounts.consumer.key()), ErrorCode::ConsumerBlacklisted);
+        require!(
+            ConsumerStake::covers(
+                ctx.accounts.consumer_stake.as_deref(),
+                ctx.accounts.compliance_matrix.required_consumer_stake(&[data_type.clone()])
+            ),
+            ErrorCode::InsufficientConsumerStake
+        );
+        require!(trial_record.used_data_types & data_type.bit() == 0, ErrorCode::TrialAlreadyUsed);
+        // A trial may reuse an inactive grant's account, but never overwrite a live one
+        require!(!permission.is_active, ErrorCode::PermissionAlreadyActive);
+
+        // A re-grant leaves the rent with whoever created the account
+        if permission.consumer == Pubkey::default() {
+            permission.rent_payer = ctx.accounts.owner.key();
+        }
+        permission.identity_id = identity.identity_id.clone();
+        permission.consumer = ctx.accounts.consumer.key();
+        permission.permission_type = PermissionType::ReadOnly;
+        permission.data_types = vec![data_type.clone()];
+        permission.granted_at = now;
+        permission.expires_at = Some(now + duration_seconds);
+        permission.is_active = true;
+        permission.proof_pointer = permission_pointer.clone();
+        permission.is_trial = true;
+        permission.max_accesses = Some(max_accesses);
+        permission.access_count = 0;
+        permission.allowed_purposes = Purpose::ALL;
+        permission.consent_hash = ctx.accounts.consumer_profile.terms_hash;
+        permission.consent_version = ctx.accounts.consumer_profile.terms_version;
+        // privacy_budget, privacy_spent and attestation_count carry over re-grants, as in grant_permission
+        permission.bump = ctx.bumps.permission;
+
+        trial_record.consumer = ctx.accounts.consumer.key();
+        trial_record.used_data_types |= data_type.bit();
+        trial_record.bump = ctx.bumps.trial_record;
+
+        ctx.accounts.audit.record(
+            ctx.bumps.audit.audit_writer,
+            ctx.accounts.owner.to_account_info(),
+            identity.key(),
+            AuditAction::Grant,
+            ctx.accounts.consumer.key(),
+            permission.key(),
+            data_type.bit() as u64,
+        )?;
+
+        emit!(TrialAccessGrantedEvent {
+            identity_id: identity.identity_id.clone(),
+            consumer: ctx.accounts.consumer.key(),
+            data_type: data_type,
+            expires_at: now + duration_seconds,
+            max_accesses: max_accesses,
+            proof_pointer: permission_pointer,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Trial access granted for identity: {} to consumer: {}", identity.identity_id, ctx.accounts.consumer.key());
+        Ok(())
+    }
+
+    /// Initialize the juror pool used to empanel dispute juries
+    pub fn initialize_juror_pool(
+        ctx: Context<InitializeJurorPool>,
+        vrf_authority: Pubkey,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.juror_pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.vrf_authority = vrf_authority;
+        pool.jurors = Vec::new();
+        pool.dispute_count = 0;
+        pool.bump = ctx.bumps.juror_pool;
+
+        msg!("Juror pool initialized with VRF authority: {}", vrf_authority);
+        Ok(())
+    }
+
+    /// Opt a staked KYC oracle into the juror pool
+    pub fn join_jury(
+        ctx: Context<JoinJury>,
+        stake_amount: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.juror_pool;
+
+        require!(ctx.accounts.oracle.is_active, ErrorCode::OracleNotActive);
+        require!(stake_amount >= ctx.accounts.oracle_registry.minimum_stake, ErrorCode::InsufficientStake);
+        require!(pool.jurors.len() < JurorPool::MAX_JURORS, ErrorCode::JurorPoolFull);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.juror.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, stake_amount)?;
+
+        let juror = &mut ctx.accounts.juror;
+        juror.authority = ctx.accounts.authority.key();
+        juror.stake = stake_amount;
+        juror.active_cases = 0;
+        juror.cases_voted = 0;
+        juror.majority_votes = 0;
+        juror.joined_at = Clock::get()?.unix_timestamp;
+        juror.bump = ctx.bumps.juror;
+
+        pool.jurors.push(juror.authority);
+
+        msg!("Juror joined: {} with stake {} lamports", juror.authority, stake_amount);
+        Ok(())
+    }
+
+    /// Leave the juror pool and withdraw the juror stake once no cases are pending
+    pub fn leave_jury(
+        ctx: Context<LeaveJury>,
+    ) -> Result<()> {
+        require!(ctx.accounts.juror.active_cases == 0, ErrorCode::JurorHasActiveCases);
+
+        let authority = ctx.accounts.authority.key();
+        ctx.accounts.juror_pool.jurors.retain(|juror| juror != &authority);
+
+        msg!("Juror left: {}", authority);
+        Ok(())
+    }
+
+    /// Open a dispute against a consumer, funding the jury fee
+    pub fn open_dispute(
+        ctx: Context<OpenDispute>,
+        evidence_tx_id: String,
+        requested_slash: u64,
+        jury_fee: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.juror_pool;
+
+        require!(evidence_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+        require!(jury_fee > 0, ErrorCode::InvalidAmount);
+        require!(pool.jurors.len() >= Dispute::PANEL_SIZE, ErrorCode::NotEnoughJurors);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.claimant.to_account_info(),
+                to: ctx.accounts.dispute.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, jury_fee)?;
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.dispute_id = pool.dispute_count;
+        dispute.claimant = ctx.accounts.claimant.key();
+        dispute.consumer = ctx.accounts.permission.consumer;
+        dispute.evidence_tx_id = evidence_tx_id.clone();
+        dispute.requested_slash = requested_slash;
+        dispute.jury_fee = jury_fee;
+        dispute.panel = Vec::new();
+        dispute.votes = Vec::new();
+        dispute.status = DisputeStatus::AwaitingPanel;
+        dispute.opened_at = Clock::get()?.unix_timestamp;
+        dispute.vote_deadline = None;
+        dispute.bump = ctx.bumps.dispute;
+
+        pool.dispute_count += 1;
+
+        emit!(DisputeOpenedEvent {
+            dispute_id: dispute.dispute_id,
+            claimant: dispute.claimant,
+            consumer: dispute.consumer,
+            evidence_tx_id: evidence_tx_id,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Dispute opened: {}", dispute.dispute_id);
+        Ok(())
+    }
+
+    /// Select a dispute's jury from VRF output (called by the VRF authority).
+    /// Remaining accounts: the selected jurors' `Juror` accounts, in panel order.
+    pub fn fulfill_dispute_randomness<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FulfillDisputeRandomness<'info>>,
+        randomness: [u8; 32],
+    ) -> Result<()> {
+        let pool = &ctx.accounts.juror_pool;
+        let dispute = &mut ctx.accounts.dispute;
+
+        require!(dispute.status == DisputeStatus::AwaitingPanel, ErrorCode::InvalidDisputeStatus);
+
+        let panel = pool.select_panel(&randomness, &[dispute.claimant, dispute.consumer])?;
+        require!(ctx.remaining_accounts.len() == panel.len(), ErrorCode::JurorAccountMismatch);
+
+        for (account_info, juror_key) in ctx.remaining_accounts.iter().zip(panel.iter()) {
+            let mut juror = Account::<Juror>::try_from(account_info)?;
+            require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+            require!(juror.authority == *juror_key, ErrorCode::JurorAccountMismatch);
+            juror.active_cases += 1;
+            juror.exit(&crate::ID)?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        dispute.votes = vec![None; panel.len()];
+        dispute.panel = panel.clone();
+        dispute.status = DisputeStatus::Voting;
+        dispute.vote_deadline = Some(now + Dispute::VOTING_PERIOD);
+
+        emit!(DisputePanelSelectedEvent {
+            dispute_id: dispute.dispute_id,
+            panel: panel,
+            vote_deadline: now + Dispute::VOTING_PERIOD,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Jury selected for dispute: {}", dispute.dispute_id);
+        Ok(())
+    }
+
+    /// Cast a juror's vote on whether to uphold a dispute
+    pub fn cast_juror_vote(
+        ctx: Context<CastJurorVote>,
+        uphold: bool,
+    ) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        let juror = ctx.accounts.juror_authority.key();
+
+        require!(dispute.status == DisputeStatus::Voting, ErrorCode::InvalidDisputeStatus);
+        require!(
+            Clock::get()?.unix_timestamp < dispute.vote_deadline.unwrap_or(0),
+            ErrorCode::VotingClosed
+        );
+
+        let seat = dispute
+            .panel
+            .iter()
+            .position(|member| member == &juror)
+            .ok_or(ErrorCode::NotOnPanel)?;
+        require!(dispute.votes[seat].is_none(), ErrorCode::AlreadyVoted);
+
+        dispute.votes[seat] = Some(uphold);
+
+        msg!("Juror {} voted on dispute {}", juror, dispute.dispute_id);
+        Ok(())
+    }
+
+    /// Resolve a dispute once every juror voted or the deadline passed.
+    /// Majority jurors split the fee and the minority's forfeits; an upheld dispute slashes the consumer.
+    /// Remaining accounts: the panel's `Juror` accounts, in panel order.
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
+    ) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+
+        require!(dispute.status == DisputeStatus::Voting, ErrorCode::InvalidDisputeStatus);
+        require!(
+            dispute.votes.iter().all(|vote| vote.is_some())
+                || Clock::get()?.unix_timestamp >= dispute.vote_deadline.unwrap_or(0),
+            ErrorCode::VotingStillOpen
+        );
+        require!(ctx.remaining_accounts.len() == dispute.panel.len(), ErrorCode::JurorAccountMismatch);
+
+        // A tie or an unattended panel dismisses the dispute
+        let upholds = dispute.votes.iter().filter(|vote| **vote == Some(true)).count();
+        let dismissals = dispute.votes.iter().filter(|vote| **vote == Some(false)).count();
+        let upheld = upholds > dismissals;
+
+        let dispute_info = dispute.to_account_info();
+        let mut pot = dispute.jury_fee;
+        let mut jurors = Vec::with_capacity(dispute.panel.len());
+        for (account_info, juror_key) in ctx.remaining_accounts.iter().zip(dispute.panel.iter()) {
+            let juror = Account::<Juror>::try_from(account_info)?;
+            require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+            require!(juror.authority == *juror_key, ErrorCode::JurorAccountMismatch);
+            jurors.push(juror);
+        }
+
+        // Minority and absent jurors forfeit part of their stake into the pot
+        for (juror, vote) in jurors.iter_mut().zip(dispute.votes.iter()) {
+            juror.active_cases -= 1;
+            if vote.is_some() {
+                juror.cases_voted += 1;
+            }
+            if *vote == Some(upheld) {
+                juror.majority_votes += 1;
+            } else {
+                let penalty = juror.stake * Juror::MINORITY_PENALTY_BPS / 10000;
+                juror.stake -= penalty;
+                **juror.to_account_info().try_borrow_mut_lamports()? -= penalty;
+                **dispute_info.try_borrow_mut_lamports()? += penalty;
+                pot += penalty;
+            }
+        }
+
+        // Majority jurors split the pot; dust, or the whole pot without a majority, returns to the claimant
+        let winners = dispute.votes.iter().filter(|vote| **vote == Some(upheld)).count() as u64;
+        let share = if winners > 0 { pot / winners } else { 0 };
+        for (juror, vote) in jurors.iter_mut().zip(dispute.votes.iter()) {
+            if *vote == Some(upheld) && share > 0 {
+                juror.stake += share;
+                **dispute_info.try_borrow_mut_lamports()? -= share;
+                **juror.to_account_info().try_borrow_mut_lamports()? += share;
+            }
+        }
+        let remainder = pot - share * winners;
+        if remainder > 0 {
+            **dispute_info.try_borrow_mut_lamports()? -= remainder;
+            **ctx.accounts.claimant.to_account_info().try_borrow_mut_lamports()? += remainder;
+        }
+
+        for juror in jurors.iter() {
+            juror.exit(&crate::ID)?;
+        }
+
+        let mut slashed = 0;
+        if upheld {
+            if let Some(stake) = ctx.accounts.consumer_stake.as_mut() {
+                slashed = ConsumerStake::slash(stake, dispute.requested_slash, &ctx.accounts.claimant)?;
+            }
+            let profile = &mut ctx.accounts.consumer_profile;
+            profile.disputes_lost += 1;
+            profile.updated_at = Clock::get()?.unix_timestamp;
+        }
+
+        dispute.status = if upheld { DisputeStatus::Upheld } else { DisputeStatus::Dismissed };
+
+        emit!(DisputeResolvedEvent {
+            dispute_id: dispute.dispute_id,
+            upheld: upheld,
+            slashed: slashed,
+            juror_share: share,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Dispute {} resolved: upheld = {}", dispute.dispute_id, upheld);
+        Ok(())
+    }
+
+    /// Restrict the purposes a consumer may use granted data for
+    pub fn restrict_permission_purposes(
+        ctx: Context<RestrictPermissionPurposes>,
+        purposes: Vec<Purpose>,
+    ) -> Result<()> {
+        let permission = &mut ctx.accounts.permission;
+
+        require!(!purposes.is_empty(), ErrorCode::NoPurposes);
+        require!(permission.is_active, ErrorCode::PermissionNotActive);
+
+        permission.allowed_purposes = Purpose::mask(&purposes);
+
+        emit!(PermissionPurposesUpdatedEvent {
+            identity_id: ctx.accounts.identity.identity_id.clone(),
+            consumer: permission.consumer,
+            purposes: purposes,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Permission purposes restricted for consumer: {}", permission.consumer);
+        Ok(())
+    }
+
+    /// Post (or refresh) a compute provider's enclave attestation report hash
+    pub fn post_tee_attestation(
+        ctx: Context<PostTeeAttestation>,
+        tee_type: TeeType,
+        report_hash: [u8; 32],
+        measurement: [u8; 32],
+    ) -> Result<()> {
+        let attestation = &mut ctx.accounts.tee_attestation;
+
+        attestation.provider = ctx.accounts.provider.key();
+        attestation.tee_type = tee_type;
+        attestation.report_hash = report_hash;
+        attestation.measurement = measurement;
+        attestation.posted_at = Clock::get()?.unix_timestamp;
+        attestation.verified_by = None;
+        attestation.verified_at = None;
+        attestation.bump = ctx.bumps.tee_attestation;
+
+        msg!("TEE attestation posted by provider: {}", attestation.provider);
+        Ok(())
+    }
+
+    /// Verify a posted enclave attestation report (called by KYC oracle)
+    pub fn verify_tee_attestation(
+        ctx: Context<VerifyTeeAttestation>,
+        report_hash: [u8; 32],
+    ) -> Result<()> {
+        let attestation = &mut ctx.accounts.tee_attestation;
+        let oracle = &mut ctx.accounts.oracle;
+
+        require!(oracle.is_active, ErrorCode::OracleNotActive);
+        require!(attestation.report_hash == report_hash, ErrorCode::AttestationReportMismatch);
+
+        // As in verify_identity, a provider's own oracle cannot vouch for its enclave
+        if attestation.provider == ctx.accounts.oracle_authority.key() {
+            oracle.verification_count += 1;
+            emit!(SelfVerificationRejectedEvent {
+                account: attestation.key(),
+                verifier: ctx.accounts.oracle_authority.key(),
+                event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+                event_slot: Clock::get()?.slot,
+            });
+            msg!("Self-verification rejected: {}", attestation.provider);
+            return Ok(());
+        }
+
+        attestation.verified_by = Some(oracle.oracle_pubkey);
+        attestation.verified_at = Some(Clock::get()?.unix_timestamp);
+
+        oracle.verification_count += 1;
+        oracle.successful_verifications += 1;
+
+        emit!(TeeAttestationVerifiedEvent {
+            provider: attestation.provider,
+            oracle_pubkey: oracle.oracle_pubkey,
+            tee_type: attestation.tee_type.clone(),
+            report_hash: report_hash,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("TEE attestation verified for provider: {}", attestation.provider);
+        Ok(())
+    }
+
+    /// Create a data trust whose trustees steward members' grants and listings within its charter
+    pub fn create_data_trust(
+        ctx: Context<CreateDataTrust>,
+        trust_id: u64,
+        trustees: Vec<Pubkey>,
+        charter_hash: [u8; 32],
+        allowed_data_types: Vec<DataType>,
+        max_grant_duration: i64,
+    ) -> Result<()> {
+        require!(!trustees.is_empty(), ErrorCode::InvalidTrustTerms);
+        require!(trustees.len() <= DataTrust::MAX_TRUSTEES, ErrorCode::InvalidTrustTerms);
+        require!(!allowed_data_types.is_empty(), ErrorCode::NoDataTypes);
+        require!(max_grant_duration > 0, ErrorCode::InvalidTrustTerms);
+
+        let trust = &mut ctx.accounts.data_trust;
+        trust.creator = ctx.accounts.creator.key();
+        trust.trust_id = trust_id;
+        trust.trustees = trustees.clone();
+        trust.charter_hash = charter_hash;
+        trust.allowed_data_types = DataType::mask(&allowed_data_types);
+        trust.max_grant_duration = max_grant_duration;
+        trust.member_count = 0;
+        trust.created_at = Clock::get()?.unix_timestamp;
+        trust.bump = ctx.bumps.data_trust;
+
+        emit!(DataTrustCreatedEvent {
+            data_trust: trust.key(),
+            trustees: trustees,
+            charter_hash: charter_hash,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Data trust {} created by: {}", trust_id, trust.creator);
+        Ok(())
+    }
+
+    /// Place an identity under a data trust's stewardship
+    pub fn join_data_trust(
+        ctx: Context<JoinDataTrust>,
+    ) -> Result<()> {
+        let trust = &mut ctx.accounts.data_trust;
+        let membership = &mut ctx.accounts.trust_membership;
+
+        membership.data_trust = trust.key();
+        membership.identity = ctx.accounts.identity.key();
+        membership.joined_at = Clock::get()?.unix_timestamp;
+        membership.bump = ctx.bumps.trust_membership;
+
+        trust.member_count += 1;
+
+        msg!("Identity {} joined data trust {}", ctx.accounts.identity.identity_id, trust.trust_id);
+        Ok(())
+    }
+
+    /// Withdraw an identity from a data trust; existing grants remain until revoked
+    pub fn leave_data_trust(
+        ctx: Context<LeaveDataTrust>,
+    ) -> Result<()> {
+        let trust = &mut ctx.accounts.data_trust;
+        trust.member_count -= 1;
+
+        msg!("Identity {} left data trust {}", ctx.accounts.identity.identity_id, trust.trust_id);
+        Ok(())
+    }
+
+    /// Grant access on a trust member's behalf, within the trust's charter constraints
+    pub fn trustee_grant_access(
+        ctx: Context<GrantAccess>,
+        permission_type: PermissionType,
+        data_types: Vec<DataType>,
+        duration_seconds: i64,
+        permission_pointer: StoragePointer,
+    ) -> Result<()> {
+        let trust = ctx.accounts.data_trust.as_ref().ok_or(ErrorCode::TrustRequired)?;
+        let membership = ctx.accounts.trust_membership.as_ref().ok_or(ErrorCode::TrustRequired)?;
+
+        require!(membership.data_trust == trust.key(), ErrorCode::NotTrustMember);
+        require!(trust.is_trustee(&ctx.accounts.owner.key()), ErrorCode::NotTrustee);
+        require!(trust.allows(&data_types), ErrorCode::DataTypeNotAuthorized);
+        require!(
+            duration_seconds > 0 && duration_seconds <= trust.max_grant_duration,
+            ErrorCode::GrantExceedsTrustCharter
+        );
+
+        let expires_at = Clock::get()?.unix_timestamp + duration_seconds;
+        let data_type_mask = DataType::mask(&data_types);
+        ctx.accounts.apply_grant(
+            ctx.bumps.permission,
+            permission_type,
+            data_types,
+            Some(expires_at),
+            permission_pointer,
+        )?;
+        ctx.accounts.record_grant(ctx.bumps.audit.audit_writer, data_type_mask)
+    }
+
+    /// Revoke a trust member's permission as one of the trust's trustees
+    pub fn trustee_revoke_access(
+        ctx: Context<TrusteeRevokeAccess>,
+        revocation_pointer: StoragePointer,
+    ) -> Result<()> {
+        require!(ctx.accounts.data_trust.is_trustee(&ctx.accounts.trustee.key()), ErrorCode::NotTrustee);
+
+        revoke_permission(
+            &mut ctx.accounts.permission,
+            &ctx.accounts.identity,
+            &mut ctx.accounts.oracle_registry,
+            &mut ctx.accounts.consumer_profile,
+            revocation_pointer,
+        )?;
+        ctx.accounts.audit.record(
+            ctx.bumps.audit.audit_writer,
+            ctx.accounts.trustee.to_account_info(),
+            ctx.accounts.identity.key(),
+            AuditAction::Revoke,
+            ctx.accounts.trustee.key(),
+            ctx.accounts.permission.key(),
+            0,
+        )
+    }
+
+    /// Place a ward identity under a guardian identity until the emancipation time
+    pub fn establish_guardianship(
+        ctx: Context<EstablishGuardianship>,
+        emancipation_at: i64,
+    ) -> Result<()> {
+        let ward = &mut ctx.accounts.ward_identity;
+        let guardian_identity = &ctx.accounts.guardian_identity;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(ward.guardian.is_none(), ErrorCode::GuardianAlreadySet);
+        require!(guardian_identity.status == IdentityStatus::Verified, ErrorCode::IdentityNotVerified);
+        require!(guardian_identity.key() != ward.key(), ErrorCode::InvalidGuardianship);
+        require!(emancipation_at > now, ErrorCode::InvalidGuardianship);
+
+        ward.guardian = Some(guardian_identity.owner);
+        ward.emancipation_at = Some(emancipation_at);
+        ward.updated_at = now;
+
+        emit!(GuardianshipUpdatedEvent {
+            identity_id: ward.identity_id.clone(),
+            guardian: ward.guardian,
+            emancipation_at: ward.emancipation_at,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Guardianship established for identity: {}", ward.identity_id);
+        Ok(())
+    }
+
+    /// Release a ward identity from guardianship; the guardian may do so early, anyone once the time has come
+    pub fn emancipate(
+        ctx: Context<Emancipate>,
+    ) -> Result<()> {
+        let ward = &mut ctx.accounts.ward_identity;
+        let now = Clock::get()?.unix_timestamp;
+
+        let guardian = ward.guardian.ok_or(ErrorCode::NoGuardian)?;
+        require!(
+            ctx.accounts.caller.key() == guardian || !ward.is_under_guardianship(now),
+            ErrorCode::EmancipationNotDue
+        );
+
+        ward.guardian = None;
+        ward.emancipation_at = None;
+        ward.updated_at = now;
+
+        emit!(GuardianshipUpdatedEvent {
+            identity_id: ward.identity_id.clone(),
+            guardian: None,
+            emancipation_at: None,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Identity emancipated: {}", ward.identity_id);
+        Ok(())
+    }
+
+    /// Ask a consumer to export the identity's data, encrypted to the owner's key, before a deadline
+    pub fn request_export(
+        ctx: Context<RequestExport>,
+        owner_encryption_key: [u8; 32],
+    ) -> Result<()> {
+        let request = &mut ctx.accounts.export_request;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(request.status != ExportStatus::Pending, ErrorCode::ExportAlreadyPending);
+
+        request.identity = ctx.accounts.identity.key();
+        request.consumer = ctx.accounts.permission.consumer;
+        request.owner_encryption_key = owner_encryption_key;
+        request.requested_at = now;
+        request.deadline = now + ExportRequest::FULFILLMENT_PERIOD;
+        request.status = ExportStatus::Pending;
+        request.package_tx_id = String::new();
+        request.package_hash = [0u8; 32];
+        request.fulfilled_at = None;
+        request.bump = ctx.bumps.export_request;
+
+        emit!(ExportRequestedEvent {
+            identity_id: ctx.accounts.identity.identity_id.clone(),
+            consumer: request.consumer,
+            owner_encryption_key: owner_encryption_key,
+            deadline: request.deadline,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Export requested from consumer: {}", request.consumer);
+        Ok(())
+    }
+
+    /// Fulfill an export request by posting the encrypted data package's Arweave transaction
+    pub fn fulfill_export(
+        ctx: Context<FulfillExport>,
+        package_tx_id: String,
+        package_hash: [u8; 32],
+    ) -> Result<()> {
+        let request = &mut ctx.accounts.export_request;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            request.status == ExportStatus::Pending || request.status == ExportStatus::Overdue,
+            ErrorCode::ExportNotPending
+        );
+        require!(package_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+
+        request.package_tx_id = package_tx_id.clone();
+        request.package_hash = package_hash;
+        request.fulfilled_at = Some(now);
+        request.status = ExportStatus::Fulfilled;
+
+        emit!(ExportFulfilledEvent {
+            identity: request.identity,
+            consumer: request.consumer,
+            package_tx_id: package_tx_id,
+            package_hash: package_hash,
+            late: now > request.deadline,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Export fulfilled by consumer: {}", request.consumer);
+        Ok(())
+    }
+
+    /// Mark an unfulfilled export request as overdue, recording it on the consumer's profile
+    pub fn flag_overdue_export(
+        ctx: Context<FlagOverdueExport>,
+    ) -> Result<()> {
+        let request = &mut ctx.accounts.export_request;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(request.status == ExportStatus::Pending, ErrorCode::ExportNotPending);
+        require!(now > request.deadline, ErrorCode::ExportNotOverdue);
+
+        request.status = ExportStatus::Overdue;
+
+        let profile = &mut ctx.accounts.consumer_profile;
+        profile.overdue_exports += 1;
+        profile.updated_at = now;
+
+        emit!(ExportOverdueEvent {
+            identity: request.identity,
+            consumer: request.consumer,
+            deadline: request.deadline,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Export overdue for consumer: {}", request.consumer);
+        Ok(())
+    }
+
+    /// Publish a new version of the consumer's data-use terms; existing grants keep their recorded version
+    pub fn publish_consumer_terms(
+        ctx: Context<UpdateConsumerProfile>,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.consumer_profile;
+
+        profile.terms_hash = terms_hash;
+        profile.terms_version += 1;
+        profile.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(ConsumerTermsPublishedEvent {
+            consumer: profile.consumer,
+            terms_hash: terms_hash,
+            terms_version: profile.terms_version,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Consumer {} published terms version {}", profile.consumer, profile.terms_version);
+        Ok(())
+    }
+
+    /// Explicitly upgrade a grant to the consumer's current terms
+    pub fn reconsent(
+        ctx: Context<Reconsent>,
+        terms_version: u32,
+    ) -> Result<()> {
+        let permission = &mut ctx.accounts.permission;
+        let profile = &ctx.accounts.consumer_profile;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(permission.is_active, ErrorCode::PermissionNotActive);
+        require!(
+            ctx.accounts.identity.guardian_approved(ctx.accounts.guardian.as_ref().map(|g| g.key()), now),
+            ErrorCode::GuardianSignatureRequired
+        );
+        // The owner names the version they read, so terms published in between cannot slip in
+        require!(terms_version == profile.terms_version, ErrorCode::TermsVersionMismatch);
+        require!(terms_version > permission.consent_version, ErrorCode::AlreadyOnCurrentTerms);
+
+        let previous_version = permission.consent_version;
+        permission.consent_hash = profile.terms_hash;
+        permission.consent_version = terms_version;
+
+        emit!(ConsentUpdatedEvent {
+            identity_id: ctx.accounts.identity.identity_id.clone(),
+            consumer: permission.consumer,
+            previous_version: previous_version,
+            consent_version: terms_version,
+            consent_hash: profile.terms_hash,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Consent for consumer {} upgraded to terms version {}", permission.consumer, terms_version);
+        Ok(())
+    }
+
+    /// Cap the cumulative differential-privacy loss a consumer may incur, in milli-epsilon
+    pub fn set_privacy_budget(
+        ctx: Context<RestrictPermissionPurposes>,
+        privacy_budget: Option<u32>,
+    ) -> Result<()> {
+        let permission = &mut ctx.accounts.permission;
+
+        require!(permission.is_active, ErrorCode::PermissionNotActive);
+        if let Some(budget) = privacy_budget {
+            require!(budget >= permission.privacy_spent, ErrorCode::InvalidPrivacyBudget);
+        }
+
+        permission.privacy_budget = privacy_budget;
+
+        emit!(PrivacyBudgetUpdatedEvent {
+            identity_id: ctx.accounts.identity.identity_id.clone(),
+            consumer: permission.consumer,
+            privacy_budget: privacy_budget,
+            privacy_spent: permission.privacy_spent,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Privacy budget updated for consumer: {}", permission.consumer);
+        Ok(())
+    }
+
+    /// Record what a consumer actually accessed under a permission, optionally co-signed by its TEE
+    pub fn attest_consumption(
+        ctx: Context<AttestConsumption>,
+        data_type: DataType,
+        purpose: Purpose,
+        accessed_at: i64,
+        record_count: u64,
+        manifest_hash: [u8; 32],
+    ) -> Result<()> {
+        let permission = &mut ctx.accounts.permission;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(permission.data_types.contains(&data_type), ErrorCode::DataTypeNotAuthorized);
+        require!(
+            accessed_at >= permission.granted_at && accessed_at <= now,
+            ErrorCode::InvalidAccessTimestamp
+        );
+
+        let tee_attestation = match (&ctx.accounts.tee_attestation, &ctx.accounts.tee_provider) {
+            (Some(attestation), Some(provider)) => {
+                require!(attestation.provider == provider.key(), ErrorCode::Unauthorized);
+                require!(attestation.verified_at.is_some(), ErrorCode::TeeAttestationNotVerified);
+                Some(attestation.key())
+            }
+            (None, None) => None,
+            _ => return err!(ErrorCode::TeeAttestationNotVerified),
+        };
+
+        let record = &mut ctx.accounts.consumption_attestation;
+        record.permission = permission.key();
+        record.consumer = permission.consumer;
+        record.sequence = permission.attestation_count;
+        record.data_type = data_type.clone();
+        record.purpose = purpose.clone();
+        record.accessed_at = accessed_at;
+        record.record_count = record_count;
+        record.manifest_hash = manifest_hash;
+        record.access_count = permission.access_count;
+        record.tee_attestation = tee_attestation;
+        record.attested_at = now;
+        record.bump = ctx.bumps.consumption_attestation;
+
+        permission.attestation_count += 1;
+
+        emit!(ConsumptionAttestedEvent {
+            permission: record.permission,
+            consumer: record.consumer,
+            sequence: record.sequence,
+            data_type: data_type,
+            purpose: purpose,
+            record_count: record_count,
+            manifest_hash: manifest_hash,
+            tee_attestation: tee_attestation,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Consumption attested by consumer: {} (sequence {})", record.consumer, record.sequence);
+        Ok(())
+    }
+
+    /// Register a Groth16 verifying key for a zero-knowledge KYC/web-proof circuit (registry authority only)
+    pub fn register_web_proof_verifier(
+        ctx: Context<RegisterWebProofVerifier>,
+        verifier_id: u32,
+        verification_level: VerificationLevel,
+        verifying_key: Groth16VerifyingKey,
+    ) -> Result<()> {
+        let verifier = &mut ctx.accounts.verifier;
+        verifier.verifier_id = verifier_id;
+        verifier.verification_level = verification_level.clone();
+        verifier.verifying_key = verifying_key;
+        verifier.is_active = true;
+        verifier.registered_at = Clock::get()?.unix_timestamp;
+        verifier.bump = ctx.bumps.verifier;
+
+        msg!("Web proof verifier {} registered at level: {:?}", verifier_id, verification_level);
+        Ok(())
+    }
+
+    /// Enable or disable a web-proof verifier, e.g. after a circuit vulnerability (registry authority only)
+    pub fn set_web_proof_verifier_active(
+        ctx: Context<SetWebProofVerifierActive>,
+        is_active: bool,
+    ) -> Result<()> {
+        let verifier = &mut ctx.accounts.verifier;
+        verifier.is_active = is_active;
+
+        msg!("Web proof verifier {} active: {}", verifier.verifier_id, is_active);
+        Ok(())
+    }
+
+    /// Verify an identity from a zero-knowledge proof of an off-chain KYC or website claim.
+    /// The proof's public inputs are the identity binding and a nullifier that stops one claim verifying many identities.
+    pub fn verify_web_proof(
+        ctx: Context<VerifyWebProof>,
+        proof_a: [u8; 64],
+        proof_b: [u8; 128],
+        proof_c: [u8; 64],
+        nullifier: [u8; 32],
+    ) -> Result<()> {
+        let verifier = &ctx.accounts.verifier;
+        let identity = &mut ctx.accounts.identity;
+
+        require!(verifier.is_active, ErrorCode::WebProofVerifierInactive);
+        require!(
+            identity.status == IdentityStatus::Pending
+                || (identity.status == IdentityStatus::Verified
+                    && !identity.verification_level.meets(&verifier.verification_level)),
+            ErrorCode::InvalidStatus
+        );
+
+        let public_inputs = [WebProofVerifier::identity_binding(&identity.key()), nullifier];
+        require!(
+            verifier.verify(&proof_a, &proof_b, &proof_c, &public_inputs)?,
+            ErrorCode::InvalidWebProof
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        identity.status = IdentityStatus::Verified;
+        identity.verification_level = verifier.verification_level.clone();
+        identity.verified_at = Some(now);
+        identity.updated_at = now;
+
+        let used = &mut ctx.accounts.nullifier_record;
+        used.verifier = verifier.key();
+        used.identity = identity.key();
+        used.used_at = now;
+        used.bump = ctx.bumps.nullifier_record;
+
+        emit!(WebProofVerifiedEvent {
+            identity_id: identity.identity_id.clone(),
+            verifier_id: verifier.verifier_id,
+            verification_level: verifier.verification_level.clone(),
+            nullifier: nullifier,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Identity verified by web proof: {} at level: {:?}", identity.identity_id, identity.verification_level);
+        Ok(())
+    }
+
+    /// Accept a third-party attestation issuer as a verification path (registry authority only)
+    pub fn register_attestation_source(
+        ctx: Context<RegisterAttestationSource>,
+        kind: AttestationKind,
+        issuer: Pubkey,
+        schema: Pubkey,
+        verification_level: VerificationLevel,
+    ) -> Result<()> {
+        let source = &mut ctx.accounts.attestation_source;
+        source.kind = kind.clone();
+        source.issuer = issuer;
+        source.schema = schema;
+        source.verification_level = verification_level.clone();
+        source.is_active = true;
+        source.bump = ctx.bumps.attestation_source;
+
+        msg!("Attestation source {:?} {} registered at level: {:?}", kind, issuer, verification_level);
+        Ok(())
+    }
+
+    /// Enable or disable a third-party attestation source (registry authority only)
+    pub fn set_attestation_source_active(
+        ctx: Context<SetAttestationSourceActive>,
+        is_active: bool,
+    ) -> Result<()> {
+        let source = &mut ctx.accounts.attestation_source;
+        source.is_active = is_active;
+
+        msg!("Attestation source {} active: {}", source.issuer, is_active);
+        Ok(())
+    }
+
+    /// Verify an identity from a Civic Pass gateway token or SAS attestation held by the owner's wallet
+    pub fn verify_with_attestation(
+        ctx: Context<VerifyWithAttestation>,
+    ) -> Result<()> {
+        let source = &ctx.accounts.attestation_source;
+        let identity = &mut ctx.accounts.identity;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(source.is_active, ErrorCode::AttestationSourceInactive);
+        require!(
+            identity.status == IdentityStatus::Pending
+                || (identity.status == IdentityStatus::Verified
+                    && !identity.verification_level.meets(&source.verification_level)),
+            ErrorCode::InvalidStatus
+        );
+        require!(
+            source.accepts(&ctx.accounts.attestation, &identity.owner, now)?,
+            ErrorCode::InvalidExternalAttestation
+        );
+
+        // An issuer attesting a wallet it controls is self-verification too
+        if identity.is_linked_wallet(&source.issuer) {
+            emit!(SelfVerificationRejectedEvent {
+                account: identity.key(),
+                verifier: source.issuer,
+                event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+                event_slot: Clock::get()?.slot,
+            });
+            msg!("Self-verification rejected: {}", identity.identity_id);
+            return Ok(());
+        }
+
+        identity.status = IdentityStatus::Verified;
+        identity.verification_level = source.verification_level.clone();
+        identity.verified_at = Some(now);
+        identity.updated_at = now;
+
+        emit!(ExternalAttestationVerifiedEvent {
+            identity_id: identity.identity_id.clone(),
+            kind: source.kind.clone(),
+            issuer: source.issuer,
+            attestation: ctx.accounts.attestation.key(),
+            verification_level: source.verification_level.clone(),
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Identity verified by {:?} attestation: {}", source.kind, identity.identity_id);
+        Ok(())
+    }
+
+    /// Link a `.sol` domain owned by the identity owner, so consumers can resolve it to this identity
+    pub fn link_domain(
+        ctx: Context<LinkDomain>,
+        domain: String,
+    ) -> Result<()> {
+        let identity = &mut ctx.accounts.identity;
+        let domain_account = ctx.accounts.domain_record.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(!domain.is_empty() && domain.len() <= 64, ErrorCode::InvalidDomain);
+        require!(
+            IdentityAccount::sns_domain_address(&domain) == domain_account,
+            ErrorCode::InvalidDomain
+        );
+        require!(
+            IdentityAccount::sns_domain_owner(&ctx.accounts.domain_record)? == identity.owner,
+            ErrorCode::DomainNotOwned
+        );
+
+        identity.sns_domain = domain.clone();
+        identity.sns_domain_account = Some(domain_account);
+        identity.updated_at = now;
+
+        let link = &mut ctx.accounts.domain_link;
+        link.domain_account = domain_account;
+        link.identity = identity.key();
+        link.linked_at = now;
+        link.bump = ctx.bumps.domain_link;
+
+        emit!(DomainLinkedEvent {
+            identity_id: identity.identity_id.clone(),
+            owner: identity.owner,
+            domain: domain,
+            domain_account: domain_account,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Domain {}.sol linked to identity: {}", identity.sns_domain, identity.identity_id);
+        Ok(())
+    }
+
+    /// Remove an identity's `.sol` domain link
+    pub fn unlink_domain(
+        ctx: Context<UnlinkDomain>,
+    ) -> Result<()> {
+        let identity = &mut ctx.accounts.identity;
+
+        emit!(DomainUnlinkedEvent {
+            identity_id: identity.identity_id.clone(),
+            domain: identity.sns_domain.clone(),
+            domain_account: ctx.accounts.domain_link.domain_account,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        identity.sns_domain = String::new();
+        identity.sns_domain_account = None;
+        identity.updated_at = Clock::get()?.unix_timestamp;
+
+        msg!("Domain unlinked from identity: {}", identity.identity_id);
+        Ok(())
+    }
+
+    /// Publish (or clear) the X25519 public key buyers encrypt key handoffs to for this identity's data
+    pub fn set_identity_encryption_key(
+        ctx: Context<UpdateIdentity>,
+        encryption_key: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let identity = &mut ctx.accounts.identity;
+
+        identity.encryption_key = encryption_key;
+        identity.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(EncryptionKeyUpdatedEvent {
+            account: identity.key(),
+            authority: identity.owner,
+            encryption_key: encryption_key,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Encryption key updated for identity: {}", identity.identity_id);
+        Ok(())
+    }
+
+    /// Publish (or clear) the X25519 public key sellers encrypt content keys to for this consumer
+    pub fn set_consumer_encryption_key(
+        ctx: Context<UpdateConsumerProfile>,
+        encryption_key: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.consumer_profile;
+
+        profile.encryption_key = encryption_key;
+        profile.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(EncryptionKeyUpdatedEvent {
+            account: profile.key(),
+            authority: profile.consumer,
+            encryption_key: encryption_key,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Encryption key updated for consumer: {}", profile.consumer);
+        Ok(())
+    }
+
+    /// Redeem an owner-signed off-chain grant voucher, creating the permission without an owner transaction.
+    /// The owner's signature is checked by an Ed25519 program instruction placed immediately before this one.
+    pub fn redeem_grant_voucher(
+        ctx: Context<RedeemGrantVoucher>,
+        voucher: GrantVoucher,
+    ) -> Result<()> {
+        let identity = &ctx.accounts.identity;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(voucher.identity == identity.key(), ErrorCode::InvalidVoucher);
+        require!(voucher.consumer == ctx.accounts.consumer.key(), ErrorCode::InvalidVoucher);
+        require!(now <= voucher.valid_until, ErrorCode::VoucherExpired);
+        // Guardians co-sign on chain, which a voucher cannot carry
+        require!(!identity.is_under_guardianship(now), ErrorCode::GuardianSignatureRequired);
+        verify_ed25519_signature(&ctx.accounts.instructions, &identity.owner, &voucher.message()?)?;
+
+        let nonce = &mut ctx.accounts.voucher_nonce;
+        nonce.identity = identity.key();
+        nonce.nonce = voucher.nonce;
+        nonce.redeemed_at = now;
+        nonce.bump = ctx.bumps.voucher_nonce;
+
+        let data_type_mask = DataType::mask(&voucher.data_types);
+        grant_permission(
+            &mut ctx.accounts.permission,
+            identity,
+            &mut ctx.accounts.oracle_registry,
+            &ctx.accounts.blacklist,
+            &ctx.accounts.consumer_profile,
+            &ctx.accounts.compliance_matrix,
+            ctx.accounts.consumer_stake.as_deref(),
+            ctx.accounts.consumer.key(),
+            ctx.bumps.permission,
+            ctx.accounts.consumer.key(),
+            voucher.permission_type,
+            voucher.data_types,
+            voucher.expires_at,
+            voucher.permission_pointer,
+        )?;
+
+        ctx.accounts.audit.record(
+            ctx.bumps.audit.audit_writer,
+            ctx.accounts.consumer.to_account_info(),
+            ctx.accounts.identity.key(),
+            AuditAction::Grant,
+            ctx.accounts.consumer.key(),
+            ctx.accounts.permission.key(),
+            data_type_mask as u64,
+        )
+    }
+
+    /// Offer to pay for a grant: the consumer escrows `price` lamports against the scope and
+    /// duration they want, which the identity owner may accept until `expires_at`
+    pub fn request_access(
+        ctx: Context<RequestAccess>,
+        permission_type: PermissionType,
+        data_types: Vec<DataType>,
+        duration: i64,
+        price: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(data_types.len() > 0, ErrorCode::NoDataTypes);
+        require!(data_types.len() <= 10, ErrorCode::TooManyDataTypes);
+        require!(duration > 0 && price > 0 && expires_at > now, ErrorCode::InvalidAccessRequest);
+        require!(ctx.accounts.identity.status == IdentityStatus::Verified, ErrorCode::IdentityNotVerified);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.consumer.to_account_info(),
+                to: ctx.accounts.access_request.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, price)?;
+
+        let request = &mut ctx.accounts.access_request;
+        request.identity = ctx.accounts.identity.key();
+        request.consumer = ctx.accounts.consumer.key();
+        request.permission_type = permission_type;
+        request.data_types = data_types.clone();
+        request.duration = duration;
+        request.price = price;
+        request.requested_at = now;
+        request.expires_at = expires_at;
+        request.bump = ctx.bumps.access_request;
+
+        emit!(AccessRequestedEvent {
+            identity_id: ctx.accounts.identity.identity_id.clone(),
+            consumer: request.consumer,
+            data_types: data_types,
+            duration: duration,
+            price: price,
+            expires_at: expires_at,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Access requested for identity: {} at {} lamports", ctx.accounts.identity.identity_id, price);
+        Ok(())
+    }
+
+    /// Accept a consumer's paid access request as the identity owner: grants the requested scope for
+    /// the requested duration and releases the escrowed payment to the owner
+    pub fn accept_access_request(
+        ctx: Context<AcceptAccessRequest>,
+        permission_pointer: StoragePointer,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let request = &ctx.accounts.access_request;
+        require!(now <= request.expires_at, ErrorCode::AccessRequestExpired);
+        require!(
+            ctx.accounts.identity.guardian_approved(ctx.accounts.guardian.as_ref().map(|g| g.key()), now),
+            ErrorCode::GuardianSignatureRequired
+        );
+
+        let data_type_mask = DataType::mask(&request.data_types);
+        grant_permission(
+            &mut ctx.accounts.permission,
+            &ctx.accounts.identity,
+            &mut ctx.accounts.oracle_registry,
+            &ctx.accounts.blacklist,
+            &ctx.accounts.consumer_profile,
+            &ctx.accounts.compliance_matrix,
+            ctx.accounts.consumer_stake.as_deref(),
+            request.consumer,
+            ctx.bumps.permission,
+            ctx.accounts.owner.key(),
+            request.permission_type.clone(),
+            request.data_types.clone(),
+            Some(now + request.duration),
+            permission_pointer,
+        )?;
+
+        let price = request.price;
+        emit!(AccessRequestAcceptedEvent {
+            identity_id: ctx.accounts.identity.identity_id.clone(),
+            consumer: ctx.accounts.consumer.key(),
+            permission: ctx.accounts.permission.key(),
+            price: price,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        ctx.accounts.audit.record(
+            ctx.bumps.audit.audit_writer,
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.identity.key(),
+            AuditAction::Grant,
+            ctx.accounts.consumer.key(),
+            ctx.accounts.permission.key(),
+            data_type_mask as u64,
+        )?;
+
+        // The escrow leaves the request; its rent goes back to the consumer when it closes. Moved
+        // only after the audit CPI, which must see the owner's balance unchanged
+        **ctx.accounts.access_request.to_account_info().try_borrow_mut_lamports()? -= price;
+        **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += price;
+        Ok(())
+    }
+
+    /// Withdraw an unaccepted access request, refunding the escrowed payment (consumer only)
+    pub fn cancel_access_request(
+        ctx: Context<CancelAccessRequest>,
+    ) -> Result<()> {
+        msg!("Access request withdrawn by consumer: {}", ctx.accounts.consumer.key());
+        Ok(())
+    }
+
+    /// Rewrite an identity created under an earlier account layout in the current one
+    pub fn migrate_identity_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<IdentityAccount>(account, IdentityAccount::LEGACY_SPACES)?;
+        let migrated = IdentityAccount::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write(8 + IdentityAccount::INIT_SPACE, &migrated)?;
+
+        msg!("Identity {} migrated from layout version {}", migrated.identity_id, version);
+        Ok(())
+    }
+
+    /// Rewrite a permission created under an earlier account layout in the current one
+    pub fn migrate_access_permission(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<AccessPermission>(account, AccessPermission::LEGACY_SPACES)?;
+        let migrated = AccessPermission::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write(8 + AccessPermission::INIT_SPACE, &migrated)?;
+
+        msg!("Permission {} migrated from layout version {}", account.key(), version);
+        Ok(())
+    }
+
+    /// Rewrite a consumer profile created under an earlier account layout in the current one
+    pub fn migrate_consumer_profile(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<ConsumerProfile>(account, ConsumerProfile::LEGACY_SPACES)?;
+        let migrated = ConsumerProfile::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write(8 + ConsumerProfile::INIT_SPACE, &migrated)?;
+
+        msg!("Consumer profile {} migrated from layout version {}", migrated.consumer, version);
+        Ok(())
+    }
+
+    /// Rewrite the oracle registry created under an earlier account layout in the current one
+    pub fn migrate_oracle_registry(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<KYCOracleRegistry>(account, KYCOracleRegistry::LEGACY_SPACES)?;
+        let migrated = KYCOracleRegistry::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write(8 + KYCOracleRegistry::INIT_SPACE, &migrated)?;
+
+        msg!("Oracle registry migrated from layout version {}", version);
+        Ok(())
+    }
+
+    /// Create the identity state snapshot and name the crank that builds it (registry authority only)
+    pub fn initialize_state_snapshot(
+        ctx: Context<InitializeStateSnapshot>,
+        crank: Pubkey,
+    ) -> Result<()> {
+        let snapshot = &mut ctx.accounts.state_snapshot;
+        snapshot.crank = crank;
+        snapshot.epoch = 0;
+        snapshot.root = StateSnapshot::root_of(&[[0u8; 32]; StateSnapshot::DEPTH], 0);
+        snapshot.leaf_count = 0;
+        snapshot.slot = 0;
+        snapshot.published_at = 0;
+        snapshot.building = false;
+        snapshot.frontier = [[0u8; 32]; StateSnapshot::DEPTH];
+        snapshot.pending_count = 0;
+        snapshot.pending_slot = 0;
+        snapshot.last_identity = Pubkey::default();
+        snapshot.bump = ctx.bumps.state_snapshot;
+
+        msg!("State snapshot initialized with crank: {}", crank);
+        Ok(())
+    }
+
+    /// Start building the next snapshot, discarding any unpublished one
+    pub fn begin_state_snapshot(
+        ctx: Context<BuildStateSnapshot>,
+    ) -> Result<()> {
+        let snapshot = &mut ctx.accounts.state_snapshot;
+        snapshot.building = true;
+        snapshot.frontier = [[0u8; 32]; StateSnapshot::DEPTH];
+        snapshot.pending_count = 0;
+        snapshot.pending_slot = Clock::get()?.slot;
+        snapshot.last_identity = Pubkey::default();
+
+        msg!("Building state snapshot epoch {}", snapshot.epoch + 1);
+        Ok(())
+    }
+
+    /// Fold the identities passed as remaining accounts, in increasing address order, into the snapshot being built
+    pub fn extend_state_snapshot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuildStateSnapshot<'info>>,
+    ) -> Result<()> {
+        let snapshot = &mut ctx.accounts.state_snapshot;
+        require!(snapshot.building, ErrorCode::SnapshotNotBuilding);
+
+        for account_info in ctx.remaining_accounts.iter() {
+            require!(account_info.key() > snapshot.last_identity, ErrorCode::SnapshotOutOfOrder);
+            let identity = Account::<IdentityAccount>::try_from(account_info)?;
+            snapshot.append(StateSnapshot::leaf(
+                &identity.identity_id,
+                &identity.status,
+                &identity.verification_level,
+                &identity.owner,
+            ))?;
+            snapshot.last_identity = account_info.key();
+        }
+
+        msg!("State snapshot extended to {} identities", snapshot.pending_count);
+        Ok(())
+    }
+
+    /// Publish the snapshot being built as the current root
+    pub fn publish_state_snapshot(
+        ctx: Context<BuildStateSnapshot>,
+    ) -> Result<()> {
+        let snapshot = &mut ctx.accounts.state_snapshot;
+        require!(snapshot.building, ErrorCode::SnapshotNotBuilding);
+
+        snapshot.root = StateSnapshot::root_of(&snapshot.frontier, snapshot.pending_count);
+        snapshot.leaf_count = snapshot.pending_count;
+        snapshot.slot = snapshot.pending_slot;
+        snapshot.published_at = Clock::get()?.unix_timestamp;
+        snapshot.epoch += 1;
+        snapshot.building = false;
+
+        emit!(StateSnapshotPublishedEvent {
+            epoch: snapshot.epoch,
+            root: snapshot.root,
+            leaf_count: snapshot.leaf_count,
+            slot: snapshot.slot,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("State snapshot epoch {} published over {} identities", snapshot.epoch, snapshot.leaf_count);
+        Ok(())
+    }
+
+    /// Change the stake oracles must post and the amount slashed per upheld dispute
+    pub fn set_oracle_registry_parameters(
+        ctx: Context<UpdateOracleRegistry>,
+        minimum_stake: u64,
+        slash_amount: u64,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.oracle_registry;
+        registry.minimum_stake = minimum_stake;
+        registry.slash_amount = slash_amount;
+
+        emit!(OracleRegistryUpdatedEvent {
+            minimum_stake: minimum_stake,
+            slash_amount: slash_amount,
+            event_sequence: registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Oracle minimum stake set to {} lamports, slash amount to {}", minimum_stake, slash_amount);
+        Ok(())
+    }
+
+    /// Hand the registry, compliance matrix and blacklist to another key or program, such as the
+    /// governance program
+    pub fn set_registry_authority(
+        ctx: Context<SetRegistryAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let previous_authority = ctx.accounts.oracle_registry.authority;
+        ctx.accounts.oracle_registry.authority = new_authority;
+        ctx.accounts.compliance_matrix.authority = new_authority;
+        ctx.accounts.blacklist.authority = new_authority;
+
+        emit!(RegistryAuthorityChangedEvent {
+            previous_authority: previous_authority,
+            new_authority: new_authority,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Registry authority changed to {}", new_authority);
+        Ok(())
+    }
+
+    /// Appoint a compliance officer, who may freeze identities pending investigation (registry authority only)
+    pub fn add_compliance_officer(
+        ctx: Context<AddComplianceOfficer>,
+        officer: Pubkey,
+    ) -> Result<()> {
+        let compliance_officer = &mut ctx.accounts.compliance_officer;
+        compliance_officer.officer = officer;
+        compliance_officer.appointed_at = Clock::get()?.unix_timestamp;
+        compliance_officer.bump = ctx.bumps.compliance_officer;
+
+        msg!("Compliance officer appointed: {}", officer);
+        Ok(())
+    }
+
+    /// Dismiss a compliance officer; freezes they already placed run on to expiry (registry authority only)
+    pub fn remove_compliance_officer(
+        ctx: Context<RemoveComplianceOfficer>,
+    ) -> Result<()> {
+        msg!("Compliance officer dismissed: {}", ctx.accounts.compliance_officer.officer);
+        Ok(())
+    }
+
+    /// Freeze an identity pending investigation, blocking its grants, listings and purchases until
+    /// the freeze is lifted or expires (compliance officers only)
+    pub fn freeze_identity(
+        ctx: Context<FreezeIdentity>,
+        duration: i64,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            duration > 0 && duration <= IdentityFreeze::MAX_DURATION,
+            ErrorCode::InvalidFreezeDuration
+        );
+
+        let identity = &mut ctx.accounts.identity;
+        require!(
+            identity.status == IdentityStatus::Verified || identity.status == IdentityStatus::Pending,
+            ErrorCode::InvalidStatus
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let freeze = &mut ctx.accounts.freeze;
+        freeze.identity = identity.key();
+        freeze.officer = ctx.accounts.officer.key();
+        freeze.reason_hash = reason_hash;
+        freeze.prior_status = identity.status.clone();
+        freeze.frozen_at = now;
+        freeze.expires_at = now + duration;
+        freeze.appeal_hash = None;
+        freeze.appealed_at = None;
+        freeze.bump = ctx.bumps.freeze;
+
+        identity.frozen_until = freeze.expires_at;
+        identity.frozen_while_verified = identity.status == IdentityStatus::Verified;
+        identity.status = IdentityStatus::Suspended;
+        identity.updated_at = now;
+
+        emit!(IdentityFrozenEvent {
+            identity_id: identity.identity_id.clone(),
+            officer: freeze.officer,
+            reason_hash: reason_hash,
+            expires_at: freeze.expires_at,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Identity frozen: {} until {}", identity.identity_id, freeze.expires_at);
+        Ok(())
+    }
+
+    /// Appeal a freeze to governance, which may lift it early by proposal (identity owner only, once)
+    pub fn appeal_freeze(
+        ctx: Context<AppealFreeze>,
+        appeal_hash: [u8; 32],
+    ) -> Result<()> {
+        let freeze = &mut ctx.accounts.freeze;
+        require!(freeze.appealed_at.is_none(), ErrorCode::FreezeAlreadyAppealed);
+
+        freeze.appeal_hash = Some(appeal_hash);
+        freeze.appealed_at = Some(Clock::get()?.unix_timestamp);
+
+        emit!(FreezeAppealedEvent {
+            identity_id: ctx.accounts.identity.identity_id.clone(),
+            appeal_hash: appeal_hash,
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Freeze appealed: {}", ctx.accounts.identity.identity_id);
+        Ok(())
+    }
+
+    /// Lift a freeze, restoring the identity's prior status and returning the freeze's rent to the officer.
+    /// The registry authority (governance, upholding an appeal) or the freezing officer may lift it early;
+    /// anyone may once it has expired.
+    pub fn lift_freeze(
+        ctx: Context<LiftFreeze>,
+    ) -> Result<()> {
+        let freeze = &ctx.accounts.freeze;
+        let authority = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            freeze.is_expired(now)
+                || authority == ctx.accounts.oracle_registry.authority
+                || authority == freeze.officer,
+            ErrorCode::Unauthorized
+        );
+
+        // The owner may have revoked the identity meanwhile; that is left in place
+        let identity = &mut ctx.accounts.identity;
+        if identity.status == IdentityStatus::Suspended {
+            identity.status = freeze.prior_status.clone();
+            identity.updated_at = now;
+        }
+        identity.frozen_until = 0;
+        identity.frozen_while_verified = false;
+
+        emit!(IdentityFreezeLiftedEvent {
+            identity_id: identity.identity_id.clone(),
+            lifted_by: authority,
+            status: identity.status.clone(),
+            event_sequence: ctx.accounts.oracle_registry.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Freeze lifted: {}", identity.identity_id);
+        Ok(())
+    }
+}
+
+/// Layout version of a legacy `T` account: the index of its allocated size in `legacy_spaces`
+fn legacy_version<T: anchor_lang::Discriminator>(account: &AccountInfo, legacy_spaces: &[usize]) -> Result<usize> {
+    let data = account.try_borrow_data()?;
+    require!(data.len() >= 8 && data[..8] == T::DISCRIMINATOR, ErrorCode::NotLegacyAccount);
+    legacy_spaces
+        .iter()
+        .position(|space| *space == data.len())
+        .ok_or_else(|| error!(ErrorCode::NotLegacyAccount))
+}
+
+/// Read the next field of a legacy account
+fn read_field<T: AnchorDeserialize>(data: &mut &[u8]) -> Result<T> {
+    T::deserialize(data).map_err(|_| error!(ErrorCode::NotLegacyAccount))
+}
+
+/// Validate and write a standing grant, shared by owner, trustee and voucher grants
+fn grant_permission(
+    permission: &mut AccessPermission,
+    identity: &IdentityAccount,
+    oracle_registry: &mut KYCOracleRegistry,
+    blacklist: &Blacklist,
+    consumer_profile: &ConsumerProfile,
+    compliance_matrix: &ComplianceMatrix,
+    consumer_stake: Option<&ConsumerStake>,
+    consumer: Pubkey,
+    bump: u8,
+    rent_payer: Pubkey,
+    permission_type: PermissionType,
+    data_types: Vec<DataType>,
+    expires_at: Option<i64>,
+    permission_pointer: StoragePointer,
+) -> Result<()> {
+    require!(identity.status == IdentityStatus::Verified, ErrorCode::IdentityNotVerified);
+    // An existing permission may only be re-granted once inactive, or to upgrade a trial
+    require!(!permission.is_active || permission.is_trial, ErrorCode::PermissionAlreadyActive);
+    require!(data_types.len() > 0, ErrorCode::NoDataTypes);
+    require!(data_types.len() <= 10, ErrorCode::TooManyDataTypes);
+    require!(permission_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
+    require!(!blacklist.contains(&consumer), ErrorCode::ConsumerBlacklisted);
+    require!(
+        ConsumerStake::covers(consumer_stake, compliance_matrix.required_consumer_stake(&data_types)),
+        ErrorCode::InsufficientConsumerStake
+    );
+
+    // A re-grant leaves the rent with whoever created the account
+    if permission.consumer == Pubkey::default() {
+        permission.rent_payer = rent_payer;
+    }
+    permission.identity_id = identity.identity_id.clone();
+    permission.consumer = consumer;
+    permission.permission_type = permission_type.clone();
+    permission.data_types = data_types.clone();
+    permission.granted_at = Clock::get()?.unix_timestamp;
+    permission.expires_at = expires_at;
+    permission.is_active = true;
+    permission.proof_pointer = permission_pointer.clone();
+    permission.is_trial = false;
+    permission.max_accesses = None;
+    permission.access_count = 0;
+    permission.allowed_purposes = Purpose::ALL;
+    permission.consent_hash = consumer_profile.terms_hash;
+    permission.consent_version = consumer_profile.terms_version;
+    // privacy_budget and privacy_spent carry over re-grants: privacy loss is cumulative.
+    // attestation_count does too, so consumption records never collide across grants.
+    permission.bump = bump;
+
+    emit!(AccessGrantedEvent {
+        identity_id: identity.identity_id.clone(),
+        consumer: consumer,
+        permission_type: permission_type,
+        data_types: data_types,
+        proof_pointer: permission_pointer,
+        event_sequence: oracle_registry.next_event_sequence(),
+        event_slot: Clock::get()?.slot,
+    });
+
+    msg!("Access granted for identity: {} to consumer: {}", identity.identity_id, consumer);
+    Ok(())
+}
+
+/// Require that the instruction before the current one is an Ed25519 program check of `signer` over `message`
+fn verify_ed25519_signature(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, ErrorCode::MissingEd25519Instruction);
+    let ix = load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    require!(ix.program_id == ed25519_program::ID, ErrorCode::MissingEd25519Instruction);
+
+    // Layout: signature count, padding, then one offsets record of seven u16s pointing into this same instruction
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidVoucherSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let (signature_ix, public_key_offset, public_key_ix) = (read_u16(4), read_u16(6), read_u16(8));
+    let (message_offset, message_size, message_ix) = (read_u16(10), read_u16(12), read_u16(14));
+    require!(
+        signature_ix == u16::MAX as usize && public_key_ix == u16::MAX as usize && message_ix == u16::MAX as usize,
+        ErrorCode::InvalidVoucherSignature
+    );
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32).ok_or(ErrorCode::InvalidVoucherSignature)?;
+    let signed_message = data.get(message_offset..message_offset + message_size).ok_or(ErrorCode::InvalidVoucherSignature)?;
+    require!(public_key == signer.as_ref(), ErrorCode::InvalidVoucherSignature);
+    require!(signed_message == message, ErrorCode::InvalidVoucherSignature);
+    Ok(())
+}
+
+/// Deactivate a permission, bumping its revocation epoch and the consumer's revocation count
+fn revoke_permission(
+    permission: &mut AccessPermission,
+    identity: &IdentityAccount,
+    oracle_registry: &mut KYCOracleRegistry,
+    consumer_profile: &mut ConsumerProfile,
+    revocation_pointer: StoragePointer,
+) -> Result<()> {
+    require!(permission.is_active, ErrorCode::PermissionNotActive);
+    require!(revocation_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
+
+    permission.is_active = false;
+    permission.proof_pointer = revocation_pointer.clone();
+    permission.revocation_epoch += 1;
+    permission.revoked_at = Some(Clock::get()?.unix_timestamp);
+
+    consumer_profile.revocations_received += 1;
+    consumer_profile.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(AccessRevokedEvent {
+        identity_id: identity.identity_id.clone(),
+        consumer: permission.consumer,
+        proof_pointer: revocation_pointer,
+        revocation_epoch: permission.revocation_epoch,
+        event_sequence: oracle_registry.next_event_sequence(),
+        event_slot: Clock::get()?.slot,
+    });
+
+    msg!("Access revoked for identity: {} from consumer: {}", identity.identity_id, permission.consumer);
+    Ok(())
+}
+
+// Account structures
+
+#[derive(Accounts)]
+pub struct InitializeOracleRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + KYCOracleRegistry::INIT_SPACE,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterOracle<'info> {
+    #[account(
+        init,
+        payer = oracle_authority,
+        space = 8 + KYCOracle::INIT_SPACE,
+        seeds = [seeds::ORACLE, oracle_authority.key().as_ref()],
+        bump
+    )]
+    pub oracle: Account<'info, KYCOracle>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub oracle_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(identity_id: String)]
+pub struct RegisterIdentity<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + IdentityAccount::INIT_SPACE,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity_id)],
+        bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE, oracle_authority.key().as_ref()],
+        bump = oracle.bump
+    )]
+    pub oracle: Account<'info, KYCOracle>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub oracle_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct GrantAccess<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AccessPermission::INIT_SPACE,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [seeds::CONSUMER, consumer.key().as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [seeds::CONSUMER_STAKE, consumer.key().as_ref()],
+        bump = consumer_stake.bump
+    )]
+    pub consumer_stake: Option<Account<'info, ConsumerStake>>,
+
+    /// CHECK: This is the consumer who will receive access permissions
+    pub consumer: AccountInfo<'info>,
+
+    #[account(
+        seeds = [seeds::TRUST, data_trust.creator.as_ref(), data_trust.trust_id.to_le_bytes().as_ref()],
+        bump = data_trust.bump
+    )]
+    pub data_trust: Option<Account<'info, DataTrust>>,
+
+    #[account(
+        seeds = [seeds::TRUST_MEMBER, trust_membership.data_trust.as_ref(), identity.key().as_ref()],
+        bump = trust_membership.bump
+    )]
+    pub trust_membership: Option<Account<'info, TrustMembership>>,
+
+    /// Identity owner, or a trustee when granting through a data trust
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Co-signs grants for a ward identity until emancipation
+    pub guardian: Option<Signer<'info>>,
+
+    pub audit: AuditAccounts<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> GrantAccess<'info> {
+    /// Checks and writes shared by owner and trustee grants
+    fn apply_grant(
+        &mut self,
+        bump: u8,
+        permission_type: PermissionType,
+        data_types: Vec<DataType>,
+        expires_at: Option<i64>,
+        permission_pointer: StoragePointer,
+    ) -> Result<()> {
+        require!(
+            self.identity.guardian_approved(self.guardian.as_ref().map(|g| g.key()), Clock::get()?.unix_timestamp),
+            ErrorCode::GuardianSignatureRequired
+        );
+
+        grant_permission(
+            &mut self.permission,
+            &self.identity,
+            &mut self.oracle_registry,
+            &self.blacklist,
+            &self.consumer_profile,
+            &self.compliance_matrix,
+            self.consumer_stake.as_deref(),
+            self.consumer.key(),
+            bump,
+            self.owner.key(),
+            permission_type,
+            data_types,
+            expires_at,
+            permission_pointer,
+        )
+    }
+
+    /// Log the grant to the identity's audit trail, paid by the granting signer
+    fn record_grant(&self, audit_bump: u8, data_type_mask: u16) -> Result<()> {
+        self.audit.record(
+            audit_bump,
+            self.owner.to_account_info(),
+            self.identity.key(),
+            AuditAction::Grant,
+            self.consumer.key(),
+            self.permission.key(),
+            data_type_mask as u64,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevokeAccess<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            permission.consumer.as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER, permission.consumer.as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub audit: AuditAccounts<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct ValidateAccess<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [seeds::CONSUMER_STAKE, consumer.key().as_ref()],
+        bump = consumer_stake.bump
+    )]
+    pub consumer_stake: Option<Account<'info, ConsumerStake>>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    pub audit: AuditAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ValidateAccessBatch<'info> {
+    #[account(
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeComplianceMatrix<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ComplianceMatrix::INIT_SPACE,
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetComplianceRule<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        has_one = authority
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCrankFund<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CrankFund::INIT_SPACE,
+        seeds = [seeds::CRANK_FUND],
+        bump
+    )]
+    pub crank_fund: Account<'info, CrankFund>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepExpired<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CRANK_FUND],
+        bump = crank_fund.bump
+    )]
+    pub crank_fund: Account<'info, CrankFund>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimPermissionRent<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            permission.consumer.as_ref()
+        ],
+        bump = permission.bump,
+        close = rent_payer
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&permission.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    /// CHECK: Receives the rest of the rent; must be the one who paid it
+    #[account(
+        mut,
+        address = permission.refund_recipient(identity.owner) @ ErrorCode::Unauthorized
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBlacklist<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Blacklist::INIT_SPACE,
+        seeds = [seeds::BLACKLIST],
+        bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBlacklist<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump,
+        has_one = authority
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterConsumer<'info> {
+    #[account(
+        init,
+        payer = consumer,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [seeds::CONSUMER, consumer.key().as_ref()],
+        bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConsumerProfile<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER, consumer.key().as_ref()],
+        bump = consumer_profile.bump,
+        has_one = consumer
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct RecordConsumerPurchase<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER, consumer_profile.consumer.as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    /// The marketplace PDA, signing via CPI from the marketplace program
+    #[account(
+        seeds = [marketplace_program::MARKETPLACE_SEED],
+        bump,
+        seeds::program = marketplace_program::ID
+    )]
+    pub marketplace: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StakeConsumer<'info> {
+    #[account(
+        init_if_needed,
+        payer = consumer,
+        space = 8 + ConsumerStake::INIT_SPACE,
+        seeds = [seeds::CONSUMER_STAKE, consumer.key().as_ref()],
+        bump
+    )]
+    pub consumer_stake: Account<'info, ConsumerStake>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConsumerStakeAction<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER_STAKE, consumer.key().as_ref()],
+        bump = consumer_stake.bump,
+        has_one = consumer
+    )]
+    pub consumer_stake: Account<'info, ConsumerStake>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SlashConsumerStake<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER_STAKE, consumer_stake.consumer.as_ref()],
+        bump = consumer_stake.bump
+    )]
+    pub consumer_stake: Account<'info, ConsumerStake>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    /// CHECK: Receives the slashed lamports (e.g. the harmed identity owner)
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyBusiness<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER, consumer_profile.consumer.as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE, oracle_authority.key().as_ref()],
+        bump = oracle.bump
+    )]
+    pub oracle: Account<'info, KYCOracle>,
+
+    pub oracle_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct GrantTrialAccess<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AccessPermission::INIT_SPACE,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + TrialRecord::INIT_SPACE,
+        seeds = [
+            seeds::TRIAL,
+            consumer.key().as_ref()
+        ],
+        bump
+    )]
+    pub trial_record: Account<'info, TrialRecord>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [seeds::CONSUMER, consumer.key().as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [seeds::CONSUMER_STAKE, consumer.key().as_ref()],
+        bump = consumer_stake.bump
+    )]
+    pub consumer_stake: Option<Account<'info, ConsumerStake>>,
+
+    /// CHECK: This is the consumer who will receive trial access
+    pub consumer: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Co-signs grants for a ward identity until emancipation
+    pub guardian: Option<Signer<'info>>,
+
+    pub audit: AuditAccounts<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJurorPool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + JurorPool::INIT_SPACE,
+        seeds = [seeds::JUROR_POOL],
+        bump
+    )]
+    pub juror_pool: Account<'info, JurorPool>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinJury<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Juror::INIT_SPACE,
+        seeds = [seeds::JUROR, authority.key().as_ref()],
+        bump
+    )]
+    pub juror: Account<'info, Juror>,
+
+    #[account(
+        mut,
+        seeds = [seeds::JUROR_POOL],
+        bump = juror_pool.bump
+    )]
+    pub juror_pool: Account<'info, JurorPool>,
+
+    #[account(
+        seeds = [seeds::ORACLE, authority.key().as_ref()],
+        bump = oracle.bump
+    )]
+    pub oracle: Account<'info, KYCOracle>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveJury<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::JUROR, authority.key().as_ref()],
+        bump = juror.bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub juror: Account<'info, Juror>,
+
+    #[account(
+        mut,
+        seeds = [seeds::JUROR_POOL],
+        bump = juror_pool.bump
+    )]
+    pub juror_pool: Account<'info, JurorPool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [seeds::DISPUTE, juror_pool.dispute_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::JUROR_POOL],
+        bump = juror_pool.bump
+    )]
+    pub juror_pool: Account<'info, JurorPool>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        constraint = identity.owner == claimant.key() @ ErrorCode::Unauthorized
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            permission.consumer.as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillDisputeRandomness<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::DISPUTE, dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        seeds = [seeds::JUROR_POOL],
+        bump = juror_pool.bump,
+        has_one = vrf_authority
+    )]
+    pub juror_pool: Account<'info, JurorPool>,
+
+    pub vrf_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct CastJurorVote<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::DISPUTE, dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    pub juror_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::DISPUTE, dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump,
+        has_one = claimant
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER, dispute.consumer.as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER_STAKE, dispute.consumer.as_ref()],
+        bump = consumer_stake.bump
+    )]
+    pub consumer_stake: Option<Account<'info, ConsumerStake>>,
+
+    /// CHECK: Receives slashed stake and any undistributed fee; checked against the dispute
+    #[account(mut)]
+    pub claimant: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct RestrictPermissionPurposes<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            permission.consumer.as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct PostTeeAttestation<'info> {
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = 8 + TeeAttestation::INIT_SPACE,
+        seeds = [seeds::TEE_ATTESTATION, provider.key().as_ref()],
+        bump
+    )]
+    pub tee_attestation: Box<Account<'info, TeeAttestation>>,
+
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyTeeAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::TEE_ATTESTATION, tee_attestation.provider.as_ref()],
+        bump = tee_attestation.bump
+    )]
+    pub tee_attestation: Box<Account<'info, TeeAttestation>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE, oracle_authority.key().as_ref()],
+        bump = oracle.bump
+    )]
+    pub oracle: Account<'info, KYCOracle>,
+
+    pub oracle_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(trust_id: u64)]
+pub struct CreateDataTrust<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + DataTrust::INIT_SPACE,
+        seeds = [seeds::TRUST, creator.key().as_ref(), trust_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub data_trust: Account<'info, DataTrust>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinDataTrust<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::TRUST, data_trust.creator.as_ref(), data_trust.trust_id.to_le_bytes().as_ref()],
+        bump = data_trust.bump
+    )]
+    pub data_trust: Account<'info, DataTrust>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TrustMembership::INIT_SPACE,
+        seeds = [seeds::TRUST_MEMBER, data_trust.key().as_ref(), identity.key().as_ref()],
+        bump
+    )]
+    pub trust_membership: Account<'info, TrustMembership>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveDataTrust<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::TRUST, data_trust.creator.as_ref(), data_trust.trust_id.to_le_bytes().as_ref()],
+        bump = data_trust.bump
+    )]
+    pub data_trust: Account<'info, DataTrust>,
+
+    #[account(
+        mut,
+        seeds = [seeds::TRUST_MEMBER, data_trust.key().as_ref(), identity.key().as_ref()],
+        bump = trust_membership.bump,
+        close = owner
+    )]
+    pub trust_membership: Account<'info, TrustMembership>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TrusteeRevokeAccess<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            permission.consumer.as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::TRUST, data_trust.creator.as_ref(), data_trust.trust_id.to_le_bytes().as_ref()],
+        bump = data_trust.bump
+    )]
+    pub data_trust: Account<'info, DataTrust>,
+
+    #[account(
+        seeds = [seeds::TRUST_MEMBER, data_trust.key().as_ref(), identity.key().as_ref()],
+        bump = trust_membership.bump
+    )]
+    pub trust_membership: Account<'info, TrustMembership>,
+
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER, permission.consumer.as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(mut)]
+    pub trustee: Signer<'info>,
+
+    pub audit: AuditAccounts<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct EstablishGuardianship<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&ward_identity.identity_id)],
+        bump = ward_identity.bump,
+        constraint = ward_identity.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub ward_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&guardian_identity.identity_id)],
+        bump = guardian_identity.bump,
+        constraint = guardian_identity.owner == guardian.key() @ ErrorCode::Unauthorized
+    )]
+    pub guardian_identity: Box<Account<'info, IdentityAccount>>,
+
+    pub owner: Signer<'info>,
+
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct Emancipate<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&ward_identity.identity_id)],
+        bump = ward_identity.bump
+    )]
+    pub ward_identity: Box<Account<'info, IdentityAccount>>,
+
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct RequestExport<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ExportRequest::INIT_SPACE,
+        seeds = [seeds::EXPORT, identity.key().as_ref(), permission.consumer.as_ref()],
+        bump
+    )]
+    pub export_request: Box<Account<'info, ExportRequest>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            permission.consumer.as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillExport<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::EXPORT, export_request.identity.as_ref(), export_request.consumer.as_ref()],
+        bump = export_request.bump,
+        has_one = consumer
+    )]
+    pub export_request: Box<Account<'info, ExportRequest>>,
+
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct FlagOverdueExport<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::EXPORT, export_request.identity.as_ref(), export_request.consumer.as_ref()],
+        bump = export_request.bump
+    )]
+    pub export_request: Box<Account<'info, ExportRequest>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::CONSUMER, export_request.consumer.as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct Reconsent<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            permission.consumer.as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::CONSUMER, permission.consumer.as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    pub owner: Signer<'info>,
+
+    /// Co-signs consent changes for a ward identity until emancipation
+    pub guardian: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct AttestConsumption<'info> {
+    #[account(
+        init,
+        payer = consumer,
+        space = 8 + ConsumptionAttestation::INIT_SPACE,
+        seeds = [
+            seeds::CONSUMPTION,
+            permission.key().as_ref(),
+            permission.attestation_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub consumption_attestation: Box<Account<'info, ConsumptionAttestation>>,
+
+    #[account(
+        mut,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump = permission.bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::TEE_ATTESTATION, tee_attestation.provider.as_ref()],
+        bump = tee_attestation.bump
+    )]
+    pub tee_attestation: Option<Box<Account<'info, TeeAttestation>>>,
+
+    /// Enclave key co-signing the record when processing ran inside a TEE
+    pub tee_provider: Option<Signer<'info>>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts needed to append an entry to the audit program's log for an identity
+#[derive(Accounts)]
+pub struct AuditAccounts<'info> {
+    /// CHECK: Initialized and validated by the audit program
+    #[account(mut)]
+    pub audit_log: AccountInfo<'info>,
+
+    /// CHECK: Initialized and validated by the audit program
+    #[account(mut)]
+    pub audit_page: AccountInfo<'info>,
+
+    /// CHECK: PDA this program signs audit entries with
+    #[account(
+        seeds = [datasov_audit::seeds::AUDIT_WRITER],
+        bump
+    )]
+    pub audit_writer: AccountInfo<'info>,
+
+    pub audit_program: Program<'info, DatasovAudit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(verifier_id: u32)]
+pub struct RegisterWebProofVerifier<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + WebProofVerifier::INIT_SPACE,
+        seeds = [seeds::WEB_PROOF_VERIFIER, verifier_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub verifier: Box<Account<'info, WebProofVerifier>>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetWebProofVerifierActive<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::WEB_PROOF_VERIFIER, verifier.verifier_id.to_le_bytes().as_ref()],
+        bump = verifier.bump
+    )]
+    pub verifier: Box<Account<'info, WebProofVerifier>>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(proof_a: [u8; 64], proof_b: [u8; 128], proof_c: [u8; 64], nullifier: [u8; 32])]
+pub struct VerifyWebProof<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::WEB_PROOF_VERIFIER, verifier.verifier_id.to_le_bytes().as_ref()],
+        bump = verifier.bump
+    )]
+    pub verifier: Box<Account<'info, WebProofVerifier>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + WebProofNullifier::INIT_SPACE,
+        seeds = [seeds::WEB_PROOF_NULLIFIER, verifier.key().as_ref(), nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_record: Account<'info, WebProofNullifier>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: AttestationKind, issuer: Pubkey, schema: Pubkey)]
+pub struct RegisterAttestationSource<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AttestationSource::INIT_SPACE,
+        seeds = [seeds::ATTESTATION_SOURCE, issuer.as_ref(), schema.as_ref()],
+        bump
+    )]
+    pub attestation_source: Account<'info, AttestationSource>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAttestationSourceActive<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::ATTESTATION_SOURCE, attestation_source.issuer.as_ref(), attestation_source.schema.as_ref()],
+        bump = attestation_source.bump
+    )]
+    pub attestation_source: Account<'info, AttestationSource>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyWithAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::ATTESTATION_SOURCE, attestation_source.issuer.as_ref(), attestation_source.schema.as_ref()],
+        bump = attestation_source.bump
+    )]
+    pub attestation_source: Account<'info, AttestationSource>,
+
+    /// CHECK: Civic gateway token or SAS attestation; owner program and contents checked against the source
+    pub attestation: AccountInfo<'info>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct LinkDomain<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    /// CHECK: SNS name record; address derived from the domain and owner read from its header
+    #[account(owner = name_service_program::ID)]
+    pub domain_record: AccountInfo<'info>,
+
+    // A transferred domain can be relinked by its new owner, replacing the previous link
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + DomainLink::INIT_SPACE,
+        seeds = [seeds::DOMAIN_LINK, domain_record.key().as_ref()],
+        bump
+    )]
+    pub domain_link: Account<'info, DomainLink>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlinkDomain<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::DOMAIN_LINK, domain_link.domain_account.as_ref()],
+        bump = domain_link.bump,
+        constraint = domain_link.identity == identity.key() @ ErrorCode::DomainNotOwned,
+        close = owner
+    )]
+    pub domain_link: Account<'info, DomainLink>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(voucher: GrantVoucher)]
+pub struct RedeemGrantVoucher<'info> {
+    #[account(
+        init_if_needed,
+        payer = consumer,
+        space = 8 + AccessPermission::INIT_SPACE,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        init,
+        payer = consumer,
+        space = 8 + VoucherNonce::INIT_SPACE,
+        seeds = [seeds::VOUCHER_NONCE, identity.key().as_ref(), voucher.nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub voucher_nonce: Account<'info, VoucherNonce>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [seeds::CONSUMER, consumer.key().as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [seeds::CONSUMER_STAKE, consumer.key().as_ref()],
+        bump = consumer_stake.bump
+    )]
+    pub consumer_stake: Option<Account<'info, ConsumerStake>>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    /// CHECK: Instructions sysvar, used to find the Ed25519 signature check
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub audit: AuditAccounts<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestAccess<'info> {
+    #[account(
+        init,
+        payer = consumer,
+        space = 8 + AccessRequest::INIT_SPACE,
+        seeds = [seeds::ACCESS_REQUEST, identity.key().as_ref(), consumer.key().as_ref()],
+        bump
+    )]
+    pub access_request: Account<'info, AccessRequest>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAccessRequest<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AccessPermission::INIT_SPACE,
+        seeds = [
+            seeds::PERMISSION,
+            identity.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ACCESS_REQUEST, identity.key().as_ref(), consumer.key().as_ref()],
+        bump = access_request.bump,
+        has_one = identity,
+        has_one = consumer,
+        close = consumer
+    )]
+    pub access_request: Account<'info, AccessRequest>,
+
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [seeds::CONSUMER, consumer.key().as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [seeds::CONSUMER_STAKE, consumer.key().as_ref()],
+        bump = consumer_stake.bump
+    )]
+    pub consumer_stake: Option<Account<'info, ConsumerStake>>,
+
+    /// CHECK: The requesting consumer, refunded the request's rent; matched by `has_one`
+    #[account(mut)]
+    pub consumer: AccountInfo<'info>,
+
+    /// Identity owner, paid the escrowed price
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Co-signs grants for a ward identity until emancipation
+    pub guardian: Option<Signer<'info>>,
+
+    pub audit: AuditAccounts<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAccessRequest<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::ACCESS_REQUEST, access_request.identity.as_ref(), consumer.key().as_ref()],
+        bump = access_request.bump,
+        has_one = consumer,
+        close = consumer
+    )]
+    pub access_request: Account<'info, AccessRequest>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+}
+
+/// Accounts for rewriting a legacy-layout account in place; anyone may pay for the migration
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Legacy layouts do not deserialize as the current type; the handler checks its discriminator and size
+    #[account(mut, owner = crate::ID)]
+    pub account: AccountInfo<'info>,
+
+    /// Covers the rent for the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateAccount<'info> {
+    /// Grow the account to `space`, topping up its rent from the payer, and overwrite it with `migrated`
+    fn write<T: AccountSerialize>(&self, space: usize, migrated: &T) -> Result<()> {
+        let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(self.account.lamports());
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.account.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_ctx, shortfall)?;
+        }
+        self.account.realloc(space, false)?;
+
+        let mut data = self.account.try_borrow_mut_data()?;
+        migrated.try_serialize(&mut &mut data[..])
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeStateSnapshot<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StateSnapshot::INIT_SPACE,
+        seeds = [seeds::STATE_SNAPSHOT],
+        bump
+    )]
+    pub state_snapshot: Box<Account<'info, StateSnapshot>>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuildStateSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::STATE_SNAPSHOT],
+        bump = state_snapshot.bump,
+        has_one = crank
+    )]
+    pub state_snapshot: Box<Account<'info, StateSnapshot>>,
+
+    pub crank: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOracleRegistry<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRegistryAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(
+        mut,
+        seeds = [seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        has_one = authority
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        mut,
+        seeds = [seeds::BLACKLIST],
+        bump = blacklist.bump,
+        has_one = authority
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(officer: Pubkey)]
+pub struct AddComplianceOfficer<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ComplianceOfficer::INIT_SPACE,
+        seeds = [seeds::COMPLIANCE_OFFICER, officer.as_ref()],
+        bump
+    )]
+    pub compliance_officer: Account<'info, ComplianceOfficer>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveComplianceOfficer<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::COMPLIANCE_OFFICER, compliance_officer.officer.as_ref()],
+        bump = compliance_officer.bump,
+        close = authority
+    )]
+    pub compliance_officer: Account<'info, ComplianceOfficer>,
+
+    #[account(
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump,
+        has_one = authority
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        init,
+        payer = officer,
+        space = 8 + IdentityFreeze::INIT_SPACE,
+        seeds = [seeds::IDENTITY_FREEZE, identity.key().as_ref()],
+        bump
+    )]
+    pub freeze: Account<'info, IdentityFreeze>,
+
+    #[account(
+        seeds = [seeds::COMPLIANCE_OFFICER, officer.key().as_ref()],
+        bump = compliance_officer.bump,
+        has_one = officer
+    )]
+    pub compliance_officer: Account<'info, ComplianceOfficer>,
+
+    #[account(mut)]
+    pub officer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AppealFreeze<'info> {
+    #[account(
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump,
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY_FREEZE, identity.key().as_ref()],
+        bump = freeze.bump,
+        has_one = identity
+    )]
+    pub freeze: Account<'info, IdentityFreeze>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct LiftFreeze<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY, &seeds::identity_id(&identity.identity_id)],
+        bump = identity.bump
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::IDENTITY_FREEZE, identity.key().as_ref()],
+        bump = freeze.bump,
+        has_one = identity,
+        has_one = officer,
+        close = officer
+    )]
+    pub freeze: Account<'info, IdentityFreeze>,
+
+    /// CHECK: The officer who placed the freeze, refunded its rent; matched by `has_one`
+    #[account(mut)]
+    pub officer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ORACLE_REGISTRY],
+        bump = oracle_registry.bump
+    )]
+    pub oracle_registry: Account<'info, KYCOracleRegistry>,
+
+    /// Registry authority, the freezing officer, or anyone once the freeze has expired
+    pub authority: Signer<'info>,
+}
+
+impl<'info> AuditAccounts<'info> {
+    /// CPI into the audit program, signing as this program's audit writer
+    fn record(
+        &self,
+        bump: u8,
+        payer: AccountInfo<'info>,
+        subject: Pubkey,
+        action: AuditAction,
+        actor: Pubkey,
+        reference: Pubkey,
+        value: u64,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] = &[datasov_audit::seeds::AUDIT_WRITER, &[bump]];
+        let signer = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.audit_program.to_account_info(),
+            datasov_audit::cpi::accounts::AppendEntry {
+                audit_log: self.audit_log.to_account_info(),
+                audit_page: self.audit_page.to_account_info(),
+                writer: self.audit_writer.to_account_info(),
+                payer: payer,
+                system_program: self.system_program.to_account_info(),
+            },
+            signer,
+        );
+        datasov_audit::cpi::append_entry(cpi_ctx, subject, action, actor, reference, value)
+    }
+}
+
+// Account data structures
+
+#[account]
+#[derive(InitSpace)]
+pub struct KYCOracleRegistry {
+    pub authority: Pubkey,
+    pub minimum_stake: u64,
+    pub slash_amount: u64,
+    pub oracle_count: u32,
+    /// Sequence number of the program's latest event; every event carries the next one and the
+    /// slot it was emitted in, so indexers can order events and detect gaps. Every instruction that
+    /// emits therefore write-locks this account, and the program's emitting transactions run one
+    /// after another rather than in parallel; a single program-wide order is worth that cost.
+    pub event_sequence: u64,
+    pub bump: u8,
+}
+
+impl KYCOracleRegistry {
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size
+    pub const LEGACY_SPACES: &[usize] = &[61];
+
+    /// Decode an account written in legacy layout `version`, leaving fields added since at their defaults
+    pub fn from_legacy(_version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        Ok(Self {
+            authority: read_field(data)?,
+            minimum_stake: read_field(data)?,
+            slash_amount: read_field(data)?,
+            oracle_count: read_field(data)?,
+            event_sequence: 0,
+            bump: read_field(data)?,
+        })
+    }
+
+    /// Advance the event sequence, returning the number the event being emitted carries
+    pub fn next_event_sequence(&mut self) -> u64 {
+        self.event_sequence += 1;
+        self.event_sequence
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct KYCOracle {
+    pub oracle_pubkey: Pubkey,
+    #[max_len(64)]
+    pub provider_name: String,
+    pub stake_amount: u64,
+    pub verification_count: u64,
+    pub successful_verifications: u64,
+    pub reputation_score: u16,
+    pub is_active: bool,
+    pub registered_at: i64,
+    pub bump: u8,
+}
 
 #[account]
+#[derive(InitSpace)]
 pub struct IdentityAccount {
-    pub identity_id: String,
     pub owner: Pubkey,
-    pub arweave_tx_id: String,
     pub status: IdentityStatus,
     pub verification_level: VerificationLevel,
+    /// Expiry of the freeze suspending the identity, or 0 when not frozen
+    pub frozen_until: i64,
+    /// Whether the identity was verified when frozen, and so counts as verified again once the freeze expires
+    pub frozen_while_verified: bool,
+    /// Zeroed; room for future fixed-offset fields
+    pub reserved: [u8; 7],
+    #[max_len(64)]
+    pub identity_id: String,
+    /// Identity document, or the KYC or revocation record that last replaced it
+    pub storage_pointer: StoragePointer,
     pub verified_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
+    pub guardian: Option<Pubkey>,
+    pub emancipation_at: Option<i64>,
+    #[max_len(64)]
+    pub sns_domain: String,
+    pub sns_domain_account: Option<Pubkey>,
+    /// X25519 public key for envelope encryption to the owner
+    pub encryption_key: Option<[u8; 32]>,
+    pub bump: u8,
+}
+
+impl IdentityAccount {
+    /// Byte offsets, discriminator included, of the fields `getProgramAccounts` filters match on.
+    /// They come before every variable-length field and do not move as the layout grows.
+    pub const OWNER_OFFSET: usize = 8;
+    pub const STATUS_OFFSET: usize = 40;
+    pub const VERIFICATION_LEVEL_OFFSET: usize = 41;
+
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size.
+    /// Layouts up to version 3 lead with the variable-length identity ID; up to version 4 the
+    /// document is a bare Arweave transaction ID.
+    pub const LEGACY_SPACES: &[usize] = &[268, 310, 411, 444, 460];
+
+    /// Decode an account written in legacy layout `version`, leaving fields added since at their defaults
+    pub fn from_legacy(version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        if version >= 4 {
+            return Ok(Self {
+                owner: read_field(data)?,
+                status: read_field(data)?,
+                verification_level: read_field(data)?,
+                frozen_until: read_field(data)?,
+                frozen_while_verified: read_field(data)?,
+                reserved: read_field(data)?,
+                identity_id: read_field(data)?,
+                storage_pointer: StoragePointer::arweave(read_field::<String>(data)?),
+                verified_at: read_field(data)?,
+                created_at: read_field(data)?,
+                updated_at: read_field(data)?,
+                guardian: read_field(data)?,
+                emancipation_at: read_field(data)?,
+                sns_domain: read_field(data)?,
+                sns_domain_account: read_field(data)?,
+                encryption_key: read_field(data)?,
+                bump: read_field(data)?,
+            });
+        }
+        Ok(Self {
+            identity_id: read_field(data)?,
+            owner: read_field(data)?,
+            storage_pointer: StoragePointer::arweave(read_field::<String>(data)?),
+            status: read_field(data)?,
+            verification_level: read_field(data)?,
+            frozen_until: 0,
+            frozen_while_verified: false,
+            reserved: [0; 7],
+            verified_at: read_field(data)?,
+            created_at: read_field(data)?,
+            updated_at: read_field(data)?,
+            guardian: if version >= 1 { read_field(data)? } else { None },
+            emancipation_at: if version >= 1 { read_field(data)? } else { None },
+            sns_domain: if version >= 2 { read_field(data)? } else { String::new() },
+            sns_domain_account: if version >= 2 { read_field(data)? } else { None },
+            encryption_key: if version >= 3 { read_field(data)? } else { None },
+            bump: read_field(data)?,
+        })
+    }
+
+    /// Whether the identity is verified at `now`, counting a freeze that has expired but not yet
+    /// been lifted as over
+    pub fn is_verified(&self, now: i64) -> bool {
+        match self.status {
+            IdentityStatus::Verified => true,
+            IdentityStatus::Suspended => self.frozen_while_verified && self.frozen_until <= now,
+            _ => false,
+        }
+    }
+
+    /// Address of the SNS name record for `<domain>.sol`
+    pub fn sns_domain_address(domain: &str) -> Pubkey {
+        let hashed_name = hashv(&[b"SPL Name Service", domain.as_bytes()]).to_bytes();
+        Pubkey::find_program_address(
+            &[hashed_name.as_ref(), Pubkey::default().as_ref(), name_service_program::sol_tld::ID.as_ref()],
+            &name_service_program::ID,
+        )
+        .0
+    }
+
+    /// Owner recorded in an SNS name record header (parent, owner, class)
+    pub fn sns_domain_owner(domain_record: &AccountInfo) -> Result<Pubkey> {
+        let data = domain_record.try_borrow_data()?;
+        require!(data.len() >= 96, ErrorCode::InvalidDomain);
+        Ok(Pubkey::try_from(&data[32..64]).unwrap())
+    }
+
+    /// Whether a guardian still controls this (ward) identity
+    pub fn is_under_guardianship(&self, now: i64) -> bool {
+        self.guardian.is_some() && self.emancipation_at.map_or(true, |at| now < at)
+    }
+
+    /// Whether grants and listings for this identity carry any required guardian co-signature
+    pub fn guardian_approved(&self, guardian_signer: Option<Pubkey>, now: i64) -> bool {
+        !self.is_under_guardianship(now) || guardian_signer == self.guardian
+    }
+
+    /// Whether `wallet` controls this identity, as its owner or its guardian
+    pub fn is_linked_wallet(&self, wallet: &Pubkey) -> bool {
+        *wallet == self.owner || self.guardian == Some(*wallet)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AccessPermission {
+    pub consumer: Pubkey,
+    pub is_active: bool,
+    pub permission_type: PermissionType,
+    /// Zeroed; room for future fixed-offset fields
+    pub reserved: [u8; 16],
+    #[max_len(64)]
+    pub identity_id: String,
+    #[max_len(10)]
+    pub data_types: Vec<DataType>,
+    pub granted_at: i64,
+    pub expires_at: Option<i64>,
+    /// Signed grant terms, or the revocation record once revoked
+    pub proof_pointer: StoragePointer,
+    pub is_trial: bool,
+    pub max_accesses: Option<u32>,
+    pub access_count: u32,
+    pub revocation_epoch: u32,
+    pub revoked_at: Option<i64>,
+    pub allowed_purposes: u16,
+    pub consent_hash: [u8; 32],
+    pub consent_version: u32,
+    pub privacy_budget: Option<u32>,
+    pub privacy_spent: u32,
+    pub attestation_count: u32,
+    /// Paid the account's rent, and gets most of it back when the grant is reclaimed; unset on
+    /// grants from before payers were recorded, which refund the identity owner
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl AccessPermission {
+    pub const MAX_TRIAL_DURATION: i64 = 7 * 24 * 60 * 60;
+    /// How long past its expiry a grant stays before anyone may reclaim its rent; well beyond the
+    /// longest marketplace refund window, so proceeds escrowed under it have long vested
+    pub const RECLAIM_GRACE: i64 = 90 * 24 * 60 * 60;
+    pub const MAX_TRIAL_ACCESSES: u32 = 100;
+    /// Most checks `validate_access_batch` takes, one per bit of its mask
+    pub const MAX_BATCH_CHECKS: usize = 64;
+    /// Byte offsets, discriminator included, of the fields `getProgramAccounts` filters match on.
+    /// They come before every variable-length field and do not move as the layout grows.
+    pub const CONSUMER_OFFSET: usize = 8;
+    pub const IS_ACTIVE_OFFSET: usize = 40;
+    pub const PERMISSION_TYPE_OFFSET: usize = 41;
+    /// The identity ID is the first variable-length field, so its length prefix is at a fixed offset too
+    pub const IDENTITY_ID_OFFSET: usize = 58;
+
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size.
+    /// Layouts up to version 7 lead with the variable-length identity ID; up to version 8 the proof
+    /// is a bare Arweave transaction ID.
+    pub const LEGACY_SPACES: &[usize] = &[284, 294, 298, 307, 309, 345, 354, 348, 364, 365];
+
+    /// Decode an account written in legacy layout `version`, leaving fields added since at their defaults.
+    /// Grants from before purposes were recorded allow every purpose, as they did then.
+    pub fn from_legacy(version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        if version >= 8 {
+            return Ok(Self {
+                consumer: read_field(data)?,
+                is_active: read_field(data)?,
+                permission_type: read_field(data)?,
+                reserved: read_field(data)?,
+                identity_id: read_field(data)?,
+                data_types: read_field(data)?,
+                granted_at: read_field(data)?,
+                expires_at: read_field(data)?,
+                proof_pointer: if version >= 9 {
+                    read_field(data)?
+                } else {
+                    StoragePointer::arweave(read_field::<String>(data)?)
+                },
+                is_trial: read_field(data)?,
+                max_accesses: read_field(data)?,
+                access_count: read_field(data)?,
+                revocation_epoch: read_field(data)?,
+                revoked_at: read_field(data)?,
+                allowed_purposes: read_field(data)?,
+                consent_hash: read_field(data)?,
+                consent_version: read_field(data)?,
+                privacy_budget: read_field(data)?,
+                privacy_spent: read_field(data)?,
+                attestation_count: read_field(data)?,
+                rent_payer: Pubkey::default(),
+                bump: read_field(data)?,
+            });
+        }
+        Ok(Self {
+            identity_id: read_field(data)?,
+            consumer: read_field(data)?,
+            permission_type: read_field(data)?,
+            data_types: read_field(data)?,
+            granted_at: read_field(data)?,
+            expires_at: read_field(data)?,
+            is_active: read_field(data)?,
+            reserved: [0; 16],
+            proof_pointer: StoragePointer::arweave(read_field::<String>(data)?),
+            is_trial: if version >= 1 { read_field(data)? } else { false },
+            max_accesses: if version >= 1 { read_field(data)? } else { None },
+            access_count: if version >= 1 { read_field(data)? } else { 0 },
+            revocation_epoch: if version >= 2 { read_field(data)? } else { 0 },
+            revoked_at: if version >= 3 { read_field(data)? } else { None },
+            allowed_purposes: if version >= 4 { read_field(data)? } else { Purpose::ALL },
+            consent_hash: if version >= 5 { read_field(data)? } else { [0; 32] },
+            consent_version: if version >= 5 { read_field(data)? } else { 0 },
+            privacy_budget: if version >= 6 { read_field(data)? } else { None },
+            privacy_spent: if version >= 6 { read_field(data)? } else { 0 },
+            attestation_count: if version >= 7 { read_field(data)? } else { 0 },
+            rent_payer: Pubkey::default(),
+            bump: read_field(data)?,
+        })
+    }
+
+    pub fn allows_purpose(&self, purpose: &Purpose) -> bool {
+        self.allowed_purposes & purpose.bit() != 0
+    }
+
+    /// Deduct a declared epsilon cost (in milli-epsilon) from the privacy budget, if one is set
+    pub fn charge_privacy(&mut self, epsilon_cost: u32) -> Result<()> {
+        if let Some(budget) = self.privacy_budget {
+            require!(epsilon_cost > 0, ErrorCode::EpsilonCostRequired);
+            let spent = self.privacy_spent.saturating_add(epsilon_cost);
+            require!(spent <= budget, ErrorCode::PrivacyBudgetExhausted);
+            self.privacy_spent = spent;
+        }
+        Ok(())
+    }
+
+    /// Whether the grant allows an access to `data_type` at `now`; identity, blacklist and stake
+    /// checks aside
+    pub fn permits(&self, data_type: &DataType, now: i64) -> bool {
+        self.is_active
+            && self.data_types.contains(data_type)
+            && self.expires_at.map_or(true, |expires_at| now < expires_at)
+            && self.max_accesses.map_or(true, |max_accesses| self.access_count < max_accesses)
+            && self.privacy_budget.map_or(true, |budget| self.privacy_spent < budget)
+    }
+
+    /// Whether the grant expired long enough before `now` to be reclaimed. Grants whose counters
+    /// must outlive them (a revocation, attested consumption, spent privacy budget) are kept, as
+    /// a re-grant into a fresh account would start them over.
+    pub fn reclaimable(&self, now: i64) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| now >= expires_at.saturating_add(Self::RECLAIM_GRACE))
+            && self.revocation_epoch == 0
+            && self.attestation_count == 0
+            && self.privacy_spent == 0
+    }
+
+    /// Who reclaimed rent goes back to, given the identity owner
+    pub fn refund_recipient(&self, identity_owner: Pubkey) -> Pubkey {
+        if self.rent_payer == Pubkey::default() {
+            identity_owner
+        } else {
+            self.rent_payer
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ComplianceMatrix {
+    pub authority: Pubkey,
+    #[max_len(9)]
+    pub rules: Vec<ComplianceRule>,
+    pub bump: u8,
+}
+
+impl ComplianceMatrix {
+    pub const MAX_RULES: usize = 9;
+
+    /// Minimum (buyer, seller) verification levels for a data type; unlisted types require none
+    pub fn required_levels(&self, data_type: &DataType) -> (VerificationLevel, VerificationLevel) {
+        self.rules
+            .iter()
+            .find(|r| &r.data_type == data_type)
+            .map(|r| (r.min_buyer_level.clone(), r.min_seller_level.clone()))
+            .unwrap_or((VerificationLevel::None, VerificationLevel::None))
+    }
+
+    pub fn buyer_allowed(&self, data_type: &DataType, level: &VerificationLevel) -> bool {
+        level.meets(&self.required_levels(data_type).0)
+    }
+
+    pub fn seller_allowed(&self, data_type: &DataType, level: &VerificationLevel) -> bool {
+        level.meets(&self.required_levels(data_type).1)
+    }
+
+    /// Largest consumer stake required across the given data types
+    pub fn required_consumer_stake(&self, data_types: &[DataType]) -> u64 {
+        self.rules
+            .iter()
+            .filter(|r| data_types.contains(&r.data_type))
+            .map(|r| r.min_consumer_stake)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CrankFund {
+    pub authority: Pubkey,
+    pub reward_per_account: u64,
+    pub total_swept: u64,
+    pub total_paid: u64,
+    pub bump: u8,
+}
+
+impl CrankFund {
+    /// Pay `reward_per_account * swept` to the caller, capped at the fund's balance above rent
+    pub fn pay_reward<'info>(
+        fund: &mut Account<'info, CrankFund>,
+        swept: u64,
+        caller: &AccountInfo<'info>,
+    ) -> Result<u64> {
+        let fund_info = fund.to_account_info();
+        let rent_exempt = Rent::get()?.minimum_balance(fund_info.data_len());
+        let available = fund_info.lamports().saturating_sub(rent_exempt);
+        let reward = fund.reward_per_account.saturating_mul(swept).min(available);
+
+        if reward > 0 {
+            **fund_info.try_borrow_mut_lamports()? -= reward;
+            **caller.try_borrow_mut_lamports()? += reward;
+        }
+
+        fund.total_swept += swept;
+        fund.total_paid += reward;
+        Ok(reward)
+    }
+}
+
+/// Rent reclamation of long-finalized accounts: anyone may close one, keeping a share of the rent
+/// it recovers, and whoever paid the rent gets the rest back
+pub struct RentReclaim;
+
+impl RentReclaim {
+    /// The caller's share of recovered rent, in basis points
+    pub const CALLER_SHARE_BASIS_POINTS: u64 = 1_000;
+
+    /// Move the caller's share of `account`'s lamports to `caller` before the account closes to
+    /// its rent payer, returning the share
+    pub fn pay_caller<'info>(account: &AccountInfo<'info>, caller: &AccountInfo<'info>) -> Result<u64> {
+        let reward = account.lamports() / 10_000 * Self::CALLER_SHARE_BASIS_POINTS;
+        **account.try_borrow_mut_lamports()? -= reward;
+        **caller.try_borrow_mut_lamports()? += reward;
+        Ok(reward)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Blacklist {
+    pub authority: Pubkey,
+    #[max_len(64)]
+    pub consumers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Blacklist {
+    pub const MAX_CONSUMERS: usize = 64;
+
+    pub fn contains(&self, consumer: &Pubkey) -> bool {
+        self.consumers.contains(consumer)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ConsumerProfile {
+    pub consumer: Pubkey,
+    pub organization_name_hash: [u8; 32],
+    #[max_len(128)]
+    pub contact_tx_id: String,
+    #[max_len(128)]
+    pub kyb_attestation_tx_id: String,
+    pub completed_purchases: u64,
+    pub disputes_lost: u32,
+    pub revocations_received: u32,
+    pub kyb_verified_by: Option<Pubkey>,
+    pub kyb_verified_at: Option<i64>,
+    pub overdue_exports: u32,
+    pub terms_hash: [u8; 32],
+    pub terms_version: u32,
+    /// X25519 public key for envelope encryption to the consumer
+    pub encryption_key: Option<[u8; 32]>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ConsumerProfile {
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size
+    pub const LEGACY_SPACES: &[usize] = &[369, 411, 415, 451];
+
+    /// Decode an account written in legacy layout `version`, leaving fields added since at their defaults
+    pub fn from_legacy(version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        Ok(Self {
+            consumer: read_field(data)?,
+            organization_name_hash: read_field(data)?,
+            contact_tx_id: read_field(data)?,
+            kyb_attestation_tx_id: read_field(data)?,
+            completed_purchases: read_field(data)?,
+            disputes_lost: read_field(data)?,
+            revocations_received: read_field(data)?,
+            kyb_verified_by: if version >= 1 { read_field(data)? } else { None },
+            kyb_verified_at: if version >= 1 { read_field(data)? } else { None },
+            overdue_exports: if version >= 2 { read_field(data)? } else { 0 },
+            terms_hash: if version >= 3 { read_field(data)? } else { [0; 32] },
+            terms_version: if version >= 3 { read_field(data)? } else { 0 },
+            encryption_key: None,
+            created_at: read_field(data)?,
+            updated_at: read_field(data)?,
+            bump: read_field(data)?,
+        })
+    }
+
+    pub fn is_business_verified(&self) -> bool {
+        self.kyb_verified_at.is_some()
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ConsumerStake {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub slashed_total: u64,
+    pub unlock_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl ConsumerStake {
+    pub const UNSTAKE_COOLDOWN: i64 = 7 * 24 * 60 * 60;
+
+    /// Stake that counts towards requirements; nothing counts once unstaking has started
+    pub fn active_amount(&self) -> u64 {
+        if self.unlock_at.is_some() {
+            0
+        } else {
+            self.amount
+        }
+    }
+
+    /// Whether an (optional) stake account satisfies a required amount
+    pub fn covers(stake: Option<&ConsumerStake>, required: u64) -> bool {
+        required == 0 || stake.map_or(false, |s| s.active_amount() >= required)
+    }
+
+    /// Move up to `amount` of staked lamports to `recipient`, returning what was actually slashed
+    pub fn slash<'info>(
+        stake: &mut Account<'info, ConsumerStake>,
+        amount: u64,
+        recipient: &AccountInfo<'info>,
+    ) -> Result<u64> {
+        let slashed = amount.min(stake.amount);
+        stake.amount -= slashed;
+        stake.slashed_total += slashed;
+        **stake.to_account_info().try_borrow_mut_lamports()? -= slashed;
+        **recipient.try_borrow_mut_lamports()? += slashed;
+        Ok(slashed)
+    }
+}
+
+/// Data types a consumer has had a free trial of, from any identity
+#[account]
+#[derive(InitSpace)]
+pub struct TrialRecord {
+    pub consumer: Pubkey,
+    pub used_data_types: u16,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct JurorPool {
+    pub authority: Pubkey,
+    pub vrf_authority: Pubkey,
+    #[max_len(32)]
+    pub jurors: Vec<Pubkey>,
+    pub dispute_count: u64,
+    pub bump: u8,
+}
+
+impl JurorPool {
+    pub const MAX_JURORS: usize = 32;
+
+    /// Draw a panel of distinct jurors from VRF output, skipping the parties to the dispute
+    pub fn select_panel(&self, randomness: &[u8; 32], parties: &[Pubkey]) -> Result<Vec<Pubkey>> {
+        let eligible: Vec<Pubkey> = self
+            .jurors
+            .iter()
+            .filter(|juror| !parties.contains(juror))
+            .cloned()
+            .collect();
+        require!(eligible.len() >= Dispute::PANEL_SIZE, ErrorCode::NotEnoughJurors);
+
+        let mut panel = Vec::with_capacity(Dispute::PANEL_SIZE);
+        let mut nonce: u64 = 0;
+        while panel.len() < Dispute::PANEL_SIZE {
+            let draw = hashv(&[randomness.as_ref(), &nonce.to_le_bytes()]).to_bytes();
+            let index = u64::from_le_bytes(draw[..8].try_into().unwrap()) as usize % eligible.len();
+            if !panel.contains(&eligible[index]) {
+                panel.push(eligible[index]);
+            }
+            nonce += 1;
+        }
+        Ok(panel)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Juror {
+    pub authority: Pubkey,
+    pub stake: u64,
+    pub active_cases: u32,
+    pub cases_voted: u64,
+    pub majority_votes: u64,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+impl Juror {
+    pub const MINORITY_PENALTY_BPS: u64 = 500;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub dispute_id: u64,
+    pub claimant: Pubkey,
+    pub consumer: Pubkey,
+    #[max_len(128)]
+    pub evidence_tx_id: String,
+    pub requested_slash: u64,
+    pub jury_fee: u64,
+    #[max_len(5)]
+    pub panel: Vec<Pubkey>,
+    #[max_len(5)]
+    pub votes: Vec<Option<bool>>,
+    pub status: DisputeStatus,
+    pub opened_at: i64,
+    pub vote_deadline: Option<i64>,
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub const PANEL_SIZE: usize = 5;
+    pub const VOTING_PERIOD: i64 = 3 * 24 * 60 * 60;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TeeAttestation {
+    pub provider: Pubkey,
+    pub tee_type: TeeType,
+    pub report_hash: [u8; 32],
+    pub measurement: [u8; 32],
+    pub posted_at: i64,
+    pub verified_by: Option<Pubkey>,
+    pub verified_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl TeeAttestation {
+    /// Whether an oracle verified this report no more than `max_age` seconds ago
+    pub fn is_fresh(&self, now: i64, max_age: i64) -> bool {
+        self.verified_at.map_or(false, |verified_at| now - verified_at <= max_age)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DataTrust {
+    pub creator: Pubkey,
+    pub trust_id: u64,
+    #[max_len(5)]
+    pub trustees: Vec<Pubkey>,
+    pub charter_hash: [u8; 32],
+    pub allowed_data_types: u16,
+    pub max_grant_duration: i64,
+    pub member_count: u32,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl DataTrust {
+    pub const MAX_TRUSTEES: usize = 5;
+
+    pub fn is_trustee(&self, key: &Pubkey) -> bool {
+        self.trustees.contains(key)
+    }
+
+    /// Whether every data type falls within the trust's charter
+    pub fn allows(&self, data_types: &[DataType]) -> bool {
+        DataType::mask(data_types) & !self.allowed_data_types == 0
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TrustMembership {
+    pub data_trust: Pubkey,
+    pub identity: Pubkey,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ExportRequest {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub owner_encryption_key: [u8; 32],
+    pub requested_at: i64,
+    pub deadline: i64,
+    pub status: ExportStatus,
+    #[max_len(128)]
+    pub package_tx_id: String,
+    pub package_hash: [u8; 32],
+    pub fulfilled_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl ExportRequest {
+    pub const FULFILLMENT_PERIOD: i64 = 30 * 24 * 60 * 60;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ConsumptionAttestation {
+    pub permission: Pubkey,
+    pub consumer: Pubkey,
+    pub sequence: u32,
+    pub data_type: DataType,
+    pub purpose: Purpose,
+    pub accessed_at: i64,
+    pub record_count: u64,
+    pub manifest_hash: [u8; 32],
+    pub access_count: u32,
+    pub tee_attestation: Option<Pubkey>,
+    pub attested_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WebProofVerifier {
+    pub verifier_id: u32,
+    pub verification_level: VerificationLevel,
+    pub verifying_key: Groth16VerifyingKey,
+    pub is_active: bool,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl WebProofVerifier {
+    /// BN254 scalar field modulus, big-endian; public inputs must be canonical field elements
+    const FIELD_MODULUS: [u8; 32] = [
+        0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+        0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+    ];
+
+    /// Public input tying a proof to one identity account, truncated to fit the scalar field
+    pub fn identity_binding(identity: &Pubkey) -> [u8; 32] {
+        let mut binding = hashv(&[b"datasov_web_proof", identity.as_ref()]).to_bytes();
+        binding[0] = 0;
+        binding
+    }
+
+    /// Groth16 check e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1.
+    /// Provers submit A already negated, as common Solana Groth16 tooling does.
+    pub fn verify(
+        &self,
+        proof_a: &[u8; 64],
+        proof_b: &[u8; 128],
+        proof_c: &[u8; 64],
+        public_inputs: &[[u8; 32]; Groth16VerifyingKey::PUBLIC_INPUTS],
+    ) -> Result<bool> {
+        let vk = &self.verifying_key;
+
+        let mut vk_x = vk.ic[0];
+        for (input, ic) in public_inputs.iter().zip(vk.ic[1..].iter()) {
+            require!(*input < Self::FIELD_MODULUS, ErrorCode::InvalidWebProof);
+            let product = alt_bn128_multiplication(&[ic.as_ref(), input.as_ref()].concat())
+                .map_err(|_| error!(ErrorCode::InvalidWebProof))?;
+            let sum = alt_bn128_addition(&[vk_x.as_ref(), product.as_slice()].concat())
+                .map_err(|_| error!(ErrorCode::InvalidWebProof))?;
+            vk_x.copy_from_slice(&sum);
+        }
+
+        let pairing_input = [
+            proof_a.as_ref(),
+            proof_b.as_ref(),
+            vk.alpha_g1.as_ref(),
+            vk.beta_g2.as_ref(),
+            vk_x.as_ref(),
+            vk.gamma_g2.as_ref(),
+            proof_c.as_ref(),
+            vk.delta_g2.as_ref(),
+        ]
+        .concat();
+        let result = alt_bn128_pairing(&pairing_input).map_err(|_| error!(ErrorCode::InvalidWebProof))?;
+        Ok(result[..31].iter().all(|byte| *byte == 0) && result[31] == 1)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WebProofNullifier {
+    pub verifier: Pubkey,
+    pub identity: Pubkey,
+    pub used_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AttestationSource {
+    pub kind: AttestationKind,
+    pub issuer: Pubkey,
+    pub schema: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl AttestationSource {
+    /// Whether `attestation` is a live credential from this source for `wallet`.
+    /// For Civic Pass the issuer is the gatekeeper network; for SAS it is the credential, with its schema.
+    pub fn accepts(&self, attestation: &AccountInfo, wallet: &Pubkey, now: i64) -> Result<bool> {
+        let data = attestation.try_borrow_data()?;
+        match self.kind {
+            AttestationKind::CivicPass => {
+                if *attestation.owner != civic_gateway_program::ID {
+                    return Ok(false);
+                }
+                let token = CivicGatewayToken::deserialize(&mut &data[..])
+                    .map_err(|_| error!(ErrorCode::InvalidExternalAttestation))?;
+                Ok(token.owner_wallet == *wallet
+                    && token.gatekeeper_network == self.issuer
+                    && token.state == CivicGatewayToken::STATE_ACTIVE
+                    && token.expire_time.map_or(true, |expire_time| expire_time > now))
+            }
+            AttestationKind::SolanaAttestationService => {
+                if *attestation.owner != attestation_service_program::ID {
+                    return Ok(false);
+                }
+                let record = SasAttestation::deserialize(&mut &data[..])
+                    .map_err(|_| error!(ErrorCode::InvalidExternalAttestation))?;
+                Ok(record.discriminator == SasAttestation::DISCRIMINATOR
+                    && record.nonce == *wallet
+                    && record.credential == self.issuer
+                    && record.schema == self.schema
+                    && (record.expiry == 0 || record.expiry > now))
+            }
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DomainLink {
+    pub domain_account: Pubkey,
+    pub identity: Pubkey,
+    pub linked_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VoucherNonce {
+    pub identity: Pubkey,
+    pub nonce: u64,
+    pub redeemed_at: i64,
+    pub bump: u8,
+}
+
+/// A consumer's paid offer for a grant; its lamports above rent are the escrowed price
+#[account]
+#[derive(InitSpace)]
+pub struct AccessRequest {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub permission_type: PermissionType,
+    #[max_len(10)]
+    pub data_types: Vec<DataType>,
+    /// Seconds the permission runs from acceptance
+    pub duration: i64,
+    /// Lamports paid to the owner on acceptance
+    pub price: u64,
+    pub requested_at: i64,
+    /// Last moment the owner may accept
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+/// Merkle root over every identity's ID, status, verification level and owner, for light clients.
+/// The crank folds identities in over several transactions, then publishes the root.
+#[account]
+#[derive(InitSpace)]
+pub struct StateSnapshot {
+    /// Builds and publishes snapshots
+    pub crank: Pubkey,
+    /// Snapshots published so far
+    pub epoch: u64,
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+    /// Slot the published snapshot began building at; identities are as of this slot or later
+    pub slot: u64,
+    pub published_at: i64,
+    pub building: bool,
+    /// Rightmost filled node at each level of the tree being built
+    pub frontier: [[u8; 32]; 32],
+    pub pending_count: u64,
+    pub pending_slot: u64,
+    /// Last identity folded in; each must have a greater address than the one before
+    pub last_identity: Pubkey,
+    pub bump: u8,
+}
+
+impl StateSnapshot {
+    /// Tree depth, room for 2^32 identities
+    pub const DEPTH: usize = 32;
+
+    /// Leaf committing to one identity; prefixed apart from inner nodes
+    pub fn leaf(identity_id: &str, status: &IdentityStatus, level: &VerificationLevel, owner: &Pubkey) -> [u8; 32] {
+        hashv(&[
+            &[0u8],
+            &(identity_id.len() as u32).to_le_bytes(),
+            identity_id.as_bytes(),
+            &[status.clone() as u8, level.clone() as u8],
+            owner.as_ref(),
+        ])
+        .to_bytes()
+    }
+
+    pub fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&[1u8], left, right]).to_bytes()
+    }
+
+    /// Root of a tree holding `count` leaves whose frontier is `frontier`, empty leaves being zero
+    pub fn root_of(frontier: &[[u8; 32]; Self::DEPTH], count: u64) -> [u8; 32] {
+        let mut node = [0u8; 32];
+        let mut zero = [0u8; 32];
+        let mut size = count;
+        for level in 0..Self::DEPTH {
+            node = if size & 1 == 1 {
+                Self::node(&frontier[level], &node)
+            } else {
+                Self::node(&node, &zero)
+            };
+            zero = Self::node(&zero, &zero);
+            size >>= 1;
+        }
+        node
+    }
+
+    /// Add the next leaf to the tree being built
+    fn append(&mut self, leaf: [u8; 32]) -> Result<()> {
+        require!(self.pending_count < 1u64 << Self::DEPTH, ErrorCode::SnapshotFull);
+        let mut node = leaf;
+        let mut index = self.pending_count;
+        for level in 0..Self::DEPTH {
+            if index & 1 == 0 {
+                self.frontier[level] = node;
+                break;
+            }
+            node = Self::node(&self.frontier[level], &node);
+            index >>= 1;
+        }
+        self.pending_count += 1;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ComplianceOfficer {
+    pub officer: Pubkey,
+    pub appointed_at: i64,
+    pub bump: u8,
+}
+
+/// An identity's suspension pending investigation; closed when lifted
+#[account]
+#[derive(InitSpace)]
+pub struct IdentityFreeze {
+    pub identity: Pubkey,
+    pub officer: Pubkey,
+    /// Hash of the officer's off-chain case notes
+    pub reason_hash: [u8; 32],
+    /// Status restored when the freeze lifts
+    pub prior_status: IdentityStatus,
+    pub frozen_at: i64,
+    pub expires_at: i64,
+    /// Hash of the owner's off-chain appeal, once lodged
+    pub appeal_hash: Option<[u8; 32]>,
+    pub appealed_at: Option<i64>,
     pub bump: u8,
 }
 
-impl IdentityAccount {
-    pub const LEN: usize = 8 + (4 + 64) + 32 + (4 + 128) + 1 + 1 + (1 + 8) + 8 + 8 + 1;
+impl IdentityFreeze {
+    /// Longest a freeze may run before anyone can lift it
+    pub const MAX_DURATION: i64 = 30 * 24 * 60 * 60;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
 }
 
-#[account]
-pub struct AccessPermission {
-    pub identity_id: String,
+/// Return data of `validate_access`: the grant as it stands after the validated access
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct AccessValidation {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub permission_type: PermissionType,
+    pub allowed_purposes: u16,
+    pub expires_at: Option<i64>,
+    /// Including this access
+    pub access_count: u32,
+    pub max_accesses: Option<u32>,
+    /// Epsilon left in the privacy budget, if the grant has one
+    pub privacy_remaining: Option<u32>,
+    pub consent_hash: [u8; 32],
+    pub consent_version: u32,
+}
+
+impl AccessValidation {
+    pub fn allows_purpose(&self, purpose: &Purpose) -> bool {
+        self.allowed_purposes & purpose.bit() != 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct ComplianceRule {
+    pub data_type: DataType,
+    pub min_buyer_level: VerificationLevel,
+    pub min_seller_level: VerificationLevel,
+    pub min_consumer_stake: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    pub ic: [[u8; 64]; Groth16VerifyingKey::IC_LEN],
+}
+
+impl Groth16VerifyingKey {
+    /// Identity binding and nullifier
+    pub const PUBLIC_INPUTS: usize = 2;
+    /// One point per public input plus the constant term
+    pub const IC_LEN: usize = Self::PUBLIC_INPUTS + 1;
+}
+
+/// Grant terms an identity owner signs off chain for a consumer to redeem
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct GrantVoucher {
+    pub identity: Pubkey,
     pub consumer: Pubkey,
     pub permission_type: PermissionType,
     pub data_types: Vec<DataType>,
-    pub granted_at: i64,
     pub expires_at: Option<i64>,
-    pub is_active: bool,
-    pub arweave_proof_tx_id: String,
-    pub bump: u8,
+    pub valid_until: i64,
+    pub nonce: u64,
+    pub permission_pointer: StoragePointer,
 }
 
-impl AccessPermission {
-    pub const LEN: usize = 8 + (4 + 64) + 32 + 1 + (4 + 10 * 2) + 8 + (1 + 8) + 1 + (4 + 128) + 1;
+impl GrantVoucher {
+    /// Bytes the owner signs: a domain tag and this program's ID ahead of the Borsh-encoded voucher
+    pub fn message(&self) -> Result<Vec<u8>> {
+        Ok([b"datasov_grant_voucher".as_ref(), crate::ID.as_ref(), &self.try_to_vec()?].concat())
+    }
+}
+
+/// On-chain layout of a Civic gateway token
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CivicGatewayToken {
+    pub features: u8,
+    pub parent_gateway_token: Option<Pubkey>,
+    pub owner_wallet: Pubkey,
+    pub owner_identity: Option<Pubkey>,
+    pub gatekeeper_network: Pubkey,
+    pub issuing_gatekeeper: Pubkey,
+    pub state: u8,
+    pub expire_time: Option<i64>,
+}
+
+impl CivicGatewayToken {
+    pub const STATE_ACTIVE: u8 = 0;
+}
+
+/// On-chain layout of a Solana Attestation Service attestation, whose nonce is the subject wallet
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SasAttestation {
+    pub discriminator: u8,
+    pub nonce: Pubkey,
+    pub credential: Pubkey,
+    pub schema: Pubkey,
+    pub data: Vec<u8>,
+    pub signer: Pubkey,
+    pub expiry: i64,
+}
+
+impl SasAttestation {
+    pub const DISCRIMINATOR: u8 = 2;
+}
+
+/// Location of an off-chain document on one of the supported storage networks
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct StoragePointer {
+    pub backend: StorageBackend,
+    /// Transaction ID, CID, `<storage account>/<file name>` or deal ID, by backend
+    #[max_len(128)]
+    pub reference: String,
+}
+
+impl StoragePointer {
+    pub const MAX_REFERENCE_LEN: usize = 128;
+    /// Base64url of a 32-byte transaction hash, unpadded
+    pub const ARWEAVE_TX_ID_LEN: usize = 43;
+    /// Base58 multihash of a CIDv0, always a SHA-256 one
+    pub const CID_V0_LEN: usize = 46;
+
+    /// Pointer to an Arweave transaction, the only backend earlier layouts recorded
+    pub fn arweave(tx_id: String) -> Self {
+        Self {
+            backend: StorageBackend::Arweave,
+            reference: tx_id,
+        }
+    }
+
+    /// Whether the reference is well formed for its backend
+    pub fn is_valid(&self) -> bool {
+        let reference = self.reference.as_str();
+        if reference.is_empty() || reference.len() > Self::MAX_REFERENCE_LEN {
+            return false;
+        }
+        match self.backend {
+            StorageBackend::Arweave => {
+                reference.len() == Self::ARWEAVE_TX_ID_LEN
+                    && reference.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            }
+            // CIDv0 is a bare base58 multihash; CIDv1 carries a multibase prefix, `b` for base32
+            StorageBackend::Ipfs => match reference.strip_prefix('b') {
+                Some(cid) => !cid.is_empty() && cid.bytes().all(|b| b.is_ascii_lowercase() || (b'2'..=b'7').contains(&b)),
+                None => {
+                    reference.len() == Self::CID_V0_LEN
+                        && reference.starts_with("Qm")
+                        && reference
+                            .bytes()
+                            .all(|b| b.is_ascii_alphanumeric() && !matches!(b, b'0' | b'O' | b'I' | b'l'))
+                }
+            },
+            StorageBackend::ShadowDrive => match reference.split_once('/') {
+                Some((storage_account, file_name)) => {
+                    storage_account.parse::<Pubkey>().is_ok() && !file_name.is_empty() && !file_name.contains('/')
+                }
+                None => false,
+            },
+            StorageBackend::Filecoin => reference.bytes().all(|b| b.is_ascii_digit()) && reference.parse::<u64>().is_ok(),
+        }
+    }
+}
+
+impl std::fmt::Display for StoragePointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}", self.backend.scheme(), self.reference)
+    }
 }
 
 // Enums
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
 pub enum IdentityStatus {
     Pending,
     Verified,
@@ -516,7 +5927,7 @@ pub enum IdentityStatus {
     Suspended,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
 pub enum VerificationLevel {
     None,
     Basic,
@@ -525,7 +5936,24 @@ pub enum VerificationLevel {
     Credential,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+impl VerificationLevel {
+    /// Relative strength of a verification level, used for minimum-level checks
+    pub fn rank(&self) -> u8 {
+        match self {
+            VerificationLevel::None => 0,
+            VerificationLevel::Basic => 1,
+            VerificationLevel::Enhanced => 2,
+            VerificationLevel::High => 3,
+            VerificationLevel::Credential => 4,
+        }
+    }
+
+    pub fn meets(&self, required: &VerificationLevel) -> bool {
+        self.rank() >= required.rank()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
 pub enum PermissionType {
     ReadOnly,
     ReadWrite,
@@ -534,7 +5962,7 @@ pub enum PermissionType {
     Export,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
 pub enum DataType {
     LocationHistory,
     AppUsage,
@@ -547,6 +5975,123 @@ pub enum DataType {
     Custom,
 }
 
+impl DataType {
+    /// Bit used for this data type in per-type bitmasks
+    pub fn bit(&self) -> u16 {
+        let index = match self {
+            DataType::LocationHistory => 0,
+            DataType::AppUsage => 1,
+            DataType::PurchaseHistory => 2,
+            DataType::HealthData => 3,
+            DataType::SocialMediaActivity => 4,
+            DataType::SearchHistory => 5,
+            DataType::FinancialData => 6,
+            DataType::CommunicationData => 7,
+            DataType::Custom => 8,
+        };
+        1 << index
+    }
+
+    /// Bitmask covering the given data types
+    pub fn mask(data_types: &[DataType]) -> u16 {
+        data_types.iter().fold(0, |mask, data_type| mask | data_type.bit())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum Purpose {
+    Research,
+    Analytics,
+    Marketing,
+    Advertising,
+    ProductDevelopment,
+    Personalization,
+    Compliance,
+    ModelTraining,
+    Other,
+}
+
+impl Purpose {
+    pub const ALL: u16 = u16::MAX;
+
+    /// Bit used for this purpose in purpose bitmasks
+    pub fn bit(&self) -> u16 {
+        let index = match self {
+            Purpose::Research => 0,
+            Purpose::Analytics => 1,
+            Purpose::Marketing => 2,
+            Purpose::Advertising => 3,
+            Purpose::ProductDevelopment => 4,
+            Purpose::Personalization => 5,
+            Purpose::Compliance => 6,
+            Purpose::ModelTraining => 7,
+            Purpose::Other => 8,
+        };
+        1 << index
+    }
+
+    /// Bitmask covering the given purposes
+    pub fn mask(purposes: &[Purpose]) -> u16 {
+        purposes.iter().fold(0, |mask, purpose| mask | purpose.bit())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Default, InitSpace)]
+pub enum ExportStatus {
+    #[default]
+    None,
+    Pending,
+    Fulfilled,
+    Overdue,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum AttestationKind {
+    CivicPass,
+    SolanaAttestationService,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum TeeType {
+    IntelSgx,
+    AmdSev,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum DisputeStatus {
+    AwaitingPanel,
+    Voting,
+    Upheld,
+    Dismissed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum StorageBackend {
+    Arweave,
+    Ipfs,
+    ShadowDrive,
+    Filecoin,
+}
+
+impl StorageBackend {
+    pub const ALL: [StorageBackend; 4] = [
+        StorageBackend::Arweave,
+        StorageBackend::Ipfs,
+        StorageBackend::ShadowDrive,
+        StorageBackend::Filecoin,
+    ];
+
+    /// URI scheme pointers to this backend are written with
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            StorageBackend::Arweave => "ar",
+            StorageBackend::Ipfs => "ipfs",
+            StorageBackend::ShadowDrive => "shdw",
+            StorageBackend::Filecoin => "fil",
+        }
+    }
+}
+
 // Events
 
 #[event]
@@ -554,13 +6099,17 @@ pub struct OracleRegisteredEvent {
     pub oracle_pubkey: Pubkey,
     pub provider_name: String,
     pub stake_amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 #[event]
 pub struct IdentityRegisteredEvent {
     pub identity_id: String,
     pub owner: Pubkey,
-    pub arweave_tx_id: String,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 #[event]
@@ -568,19 +6117,25 @@ pub struct IdentityVerifiedEvent {
     pub identity_id: String,
     pub verification_level: VerificationLevel,
     pub oracle_pubkey: Pubkey,
-    pub arweave_tx_id: String,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 #[event]
 pub struct IdentityUpdatedEvent {
     pub identity_id: String,
-    pub arweave_tx_id: String,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 #[event]
 pub struct IdentityRevokedEvent {
     pub identity_id: String,
-    pub arweave_tx_id: String,
+    pub storage_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 #[event]
@@ -589,14 +6144,399 @@ pub struct AccessGrantedEvent {
     pub consumer: Pubkey,
     pub permission_type: PermissionType,
     pub data_types: Vec<DataType>,
-    pub arweave_tx_id: String,
+    pub proof_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 #[event]
 pub struct AccessRevokedEvent {
     pub identity_id: String,
     pub consumer: Pubkey,
+    pub proof_pointer: StoragePointer,
+    pub revocation_epoch: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ComplianceRuleUpdatedEvent {
+    pub data_type: DataType,
+    pub min_buyer_level: VerificationLevel,
+    pub min_seller_level: VerificationLevel,
+    pub min_consumer_stake: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ExpiredSweptEvent {
+    pub caller: Pubkey,
+    pub swept: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsumerBlacklistedEvent {
+    pub consumer: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsumerUnblacklistedEvent {
+    pub consumer: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct BlacklistedGrantEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub permission: Pubkey,
+    pub is_active: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsumerRegisteredEvent {
+    pub consumer: Pubkey,
+    pub organization_name_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsumerStakedEvent {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub total: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsumerSlashedEvent {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub evidence_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct BusinessVerifiedEvent {
+    pub consumer: Pubkey,
+    pub oracle_pubkey: Pubkey,
     pub arweave_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct TrialAccessGrantedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub data_type: DataType,
+    pub expires_at: i64,
+    pub max_accesses: u32,
+    pub proof_pointer: StoragePointer,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DisputeOpenedEvent {
+    pub dispute_id: u64,
+    pub claimant: Pubkey,
+    pub consumer: Pubkey,
+    pub evidence_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DisputePanelSelectedEvent {
+    pub dispute_id: u64,
+    pub panel: Vec<Pubkey>,
+    pub vote_deadline: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DisputeResolvedEvent {
+    pub dispute_id: u64,
+    pub upheld: bool,
+    pub slashed: u64,
+    pub juror_share: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct PermissionPurposesUpdatedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub purposes: Vec<Purpose>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct TeeAttestationVerifiedEvent {
+    pub provider: Pubkey,
+    pub oracle_pubkey: Pubkey,
+    pub tee_type: TeeType,
+    pub report_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DataTrustCreatedEvent {
+    pub data_trust: Pubkey,
+    pub trustees: Vec<Pubkey>,
+    pub charter_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct GuardianshipUpdatedEvent {
+    pub identity_id: String,
+    pub guardian: Option<Pubkey>,
+    pub emancipation_at: Option<i64>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ExportRequestedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub owner_encryption_key: [u8; 32],
+    pub deadline: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ExportFulfilledEvent {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub package_tx_id: String,
+    pub package_hash: [u8; 32],
+    pub late: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ExportOverdueEvent {
+    pub identity: Pubkey,
+    pub consumer: Pubkey,
+    pub deadline: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsumerTermsPublishedEvent {
+    pub consumer: Pubkey,
+    pub terms_hash: [u8; 32],
+    pub terms_version: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsentUpdatedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub previous_version: u32,
+    pub consent_version: u32,
+    pub consent_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct PrivacyBudgetUpdatedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub privacy_budget: Option<u32>,
+    pub privacy_spent: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ConsumptionAttestedEvent {
+    pub permission: Pubkey,
+    pub consumer: Pubkey,
+    pub sequence: u32,
+    pub data_type: DataType,
+    pub purpose: Purpose,
+    pub record_count: u64,
+    pub manifest_hash: [u8; 32],
+    pub tee_attestation: Option<Pubkey>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct WebProofVerifiedEvent {
+    pub identity_id: String,
+    pub verifier_id: u32,
+    pub verification_level: VerificationLevel,
+    pub nullifier: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ExternalAttestationVerifiedEvent {
+    pub identity_id: String,
+    pub kind: AttestationKind,
+    pub issuer: Pubkey,
+    pub attestation: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DomainLinkedEvent {
+    pub identity_id: String,
+    pub owner: Pubkey,
+    pub domain: String,
+    pub domain_account: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DomainUnlinkedEvent {
+    pub identity_id: String,
+    pub domain: String,
+    pub domain_account: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct EncryptionKeyUpdatedEvent {
+    /// Identity or consumer profile account
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub encryption_key: Option<[u8; 32]>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct StateSnapshotPublishedEvent {
+    pub epoch: u64,
+    pub root: [u8; 32],
+    pub leaf_count: u64,
+    pub slot: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct OracleRegistryUpdatedEvent {
+    pub minimum_stake: u64,
+    pub slash_amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct RegistryAuthorityChangedEvent {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct IdentityFrozenEvent {
+    pub identity_id: String,
+    pub officer: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub expires_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct FreezeAppealedEvent {
+    pub identity_id: String,
+    pub appeal_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct IdentityFreezeLiftedEvent {
+    pub identity_id: String,
+    pub lifted_by: Pubkey,
+    /// Status the identity was left in
+    pub status: IdentityStatus,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct AccessRequestedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub data_types: Vec<DataType>,
+    pub duration: i64,
+    pub price: u64,
+    pub expires_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct AccessRequestAcceptedEvent {
+    pub identity_id: String,
+    pub consumer: Pubkey,
+    pub permission: Pubkey,
+    pub price: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct RentReclaimedEvent {
+    pub account: Pubkey,
+    pub rent_payer: Pubkey,
+    pub caller: Pubkey,
+    pub refund: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+/// A verifier vouching for an account it controls; the account is left unverified
+#[event]
+pub struct SelfVerificationRejectedEvent {
+    /// Identity, consumer profile or enclave attestation account
+    pub account: Pubkey,
+    /// Oracle authority or attestation issuer
+    pub verifier: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 // Error codes
@@ -627,4 +6567,142 @@ pub enum ErrorCode {
     NoDataTypes,
     #[msg("Too many data types (max 10)")]
     TooManyDataTypes,
+    #[msg("Account must be writable")]
+    AccountNotWritable,
+    #[msg("Consumer is blacklisted")]
+    ConsumerBlacklisted,
+    #[msg("Consumer is already blacklisted")]
+    ConsumerAlreadyBlacklisted,
+    #[msg("Consumer is not blacklisted")]
+    ConsumerNotBlacklisted,
+    #[msg("Blacklist is full (max 64)")]
+    BlacklistFull,
+    #[msg("Permission does not belong to this consumer")]
+    ConsumerMismatch,
+    #[msg("Consumer stake is below the requirement for these data types")]
+    InsufficientConsumerStake,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Unstake has already been requested")]
+    UnstakeAlreadyRequested,
+    #[msg("Unstake has not been requested")]
+    UnstakeNotRequested,
+    #[msg("Stake is still locked")]
+    StakeLocked,
+    #[msg("Permission is already active")]
+    PermissionAlreadyActive,
+    #[msg("Access limit reached for this permission")]
+    AccessLimitReached,
+    #[msg("Invalid trial terms")]
+    InvalidTrialTerms,
+    #[msg("Consumer has already used a trial for this data type")]
+    TrialAlreadyUsed,
+    #[msg("Juror pool is full")]
+    JurorPoolFull,
+    #[msg("Juror still has pending dispute cases")]
+    JurorHasActiveCases,
+    #[msg("Not enough eligible jurors to empanel a jury")]
+    NotEnoughJurors,
+    #[msg("Dispute is not in the required status")]
+    InvalidDisputeStatus,
+    #[msg("Juror accounts do not match the panel")]
+    JurorAccountMismatch,
+    #[msg("Voting period has closed")]
+    VotingClosed,
+    #[msg("Voting period is still open")]
+    VotingStillOpen,
+    #[msg("Signer is not on the dispute panel")]
+    NotOnPanel,
+    #[msg("Juror has already voted")]
+    AlreadyVoted,
+    #[msg("At least one purpose is required")]
+    NoPurposes,
+    #[msg("Attestation report hash does not match the posted report")]
+    AttestationReportMismatch,
+    #[msg("Invalid data trust terms")]
+    InvalidTrustTerms,
+    #[msg("Data trust and membership accounts are required")]
+    TrustRequired,
+    #[msg("Identity is not a member of this data trust")]
+    NotTrustMember,
+    #[msg("Signer is not a trustee of this data trust")]
+    NotTrustee,
+    #[msg("Grant exceeds the data trust's charter")]
+    GrantExceedsTrustCharter,
+    #[msg("Guardian co-signature required for a ward identity")]
+    GuardianSignatureRequired,
+    #[msg("Identity already has a guardian")]
+    GuardianAlreadySet,
+    #[msg("Invalid guardianship terms")]
+    InvalidGuardianship,
+    #[msg("Identity has no guardian")]
+    NoGuardian,
+    #[msg("Emancipation time has not been reached")]
+    EmancipationNotDue,
+    #[msg("An export request is already pending")]
+    ExportAlreadyPending,
+    #[msg("Export request is not pending")]
+    ExportNotPending,
+    #[msg("Export request deadline has not passed")]
+    ExportNotOverdue,
+    #[msg("Terms version does not match the consumer's current terms")]
+    TermsVersionMismatch,
+    #[msg("Permission already consents to the current terms")]
+    AlreadyOnCurrentTerms,
+    #[msg("Metered access must declare an epsilon cost")]
+    EpsilonCostRequired,
+    #[msg("Privacy budget exhausted")]
+    PrivacyBudgetExhausted,
+    #[msg("Privacy budget cannot be below the epsilon already spent")]
+    InvalidPrivacyBudget,
+    #[msg("Access timestamp is outside the permission's lifetime")]
+    InvalidAccessTimestamp,
+    #[msg("TEE attestation must be verified and co-signed by its provider")]
+    TeeAttestationNotVerified,
+    #[msg("Web proof verifier is not active")]
+    WebProofVerifierInactive,
+    #[msg("Zero-knowledge proof failed verification")]
+    InvalidWebProof,
+    #[msg("Attestation source is not active")]
+    AttestationSourceInactive,
+    #[msg("Attestation is not a live credential from this source for the owner's wallet")]
+    InvalidExternalAttestation,
+    #[msg("Domain name does not match the SNS name record")]
+    InvalidDomain,
+    #[msg("Domain is not owned by the identity owner")]
+    DomainNotOwned,
+    #[msg("Voucher does not match this identity and consumer")]
+    InvalidVoucher,
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+    #[msg("Expected an Ed25519 signature check immediately before this instruction")]
+    MissingEd25519Instruction,
+    #[msg("Ed25519 signature check does not cover this voucher and owner")]
+    InvalidVoucherSignature,
+    #[msg("Provider name too long")]
+    ProviderNameTooLong,
+    #[msg("Account is not in an earlier layout of this type")]
+    NotLegacyAccount,
+    #[msg("No state snapshot is being built")]
+    SnapshotNotBuilding,
+    #[msg("Identities must be added to a snapshot in increasing address order")]
+    SnapshotOutOfOrder,
+    #[msg("State snapshot is full")]
+    SnapshotFull,
+    #[msg("Too many checks in one batch (max 64)")]
+    BatchTooLarge,
+    #[msg("Batch accounts do not match the checks")]
+    BatchAccountMismatch,
+    #[msg("Storage reference is not well formed for its backend")]
+    InvalidStoragePointer,
+    #[msg("Freeze duration must be positive and at most 30 days")]
+    InvalidFreezeDuration,
+    #[msg("Freeze has already been appealed")]
+    FreezeAlreadyAppealed,
+    #[msg("Access request needs a price, a duration and an acceptance deadline in the future")]
+    InvalidAccessRequest,
+    #[msg("Access request has expired")]
+    AccessRequestExpired,
+    #[msg("Account is not finalized long enough to reclaim its rent")]
+    NotReclaimable,
 }
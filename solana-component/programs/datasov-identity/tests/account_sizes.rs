@@ -0,0 +1,537 @@
+//! Every account, filled to the limits the program enforces, must serialize within the space `InitSpace` derives for it.
+
+use anchor_lang::prelude::*;
+use datasov_identity::{
+    AccessPermission, AccessRequest, AttestationKind, AttestationSource, Blacklist, ComplianceMatrix, ComplianceOfficer,
+    ComplianceRule, ConsumerProfile, ConsumerStake, ConsumptionAttestation, CrankFund, DataTrust, DataType, Dispute,
+    DisputeStatus, DomainLink, ExportRequest, ExportStatus, Groth16VerifyingKey, IdentityAccount, IdentityFreeze,
+    IdentityStatus, Juror, JurorPool, KYCOracle, KYCOracleRegistry, PermissionType, Purpose, StorageBackend,
+    StoragePointer, TeeAttestation, TeeType, TrialRecord, TrustMembership, VerificationLevel, VoucherNonce,
+    WebProofNullifier, WebProofVerifier,
+};
+use proptest::option::weighted;
+use proptest::prelude::*;
+
+/// Serialize with the discriminator, check the encoding fits and round-trips
+fn assert_fits<T: AccountSerialize + AccountDeserialize + Space>(account: &T) {
+    let len = 8 + T::INIT_SPACE;
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= len, "{} bytes serialized, {} allocated", data.len(), len);
+
+    let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+    let mut reencoded = Vec::new();
+    decoded.try_serialize(&mut reencoded).unwrap();
+    assert_eq!(data, reencoded);
+}
+
+/// Check `field` is encoded at `offset` of the account data, discriminator included
+fn assert_at<T: AccountSerialize, F: AnchorSerialize>(account: &T, offset: usize, field: &F) {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert!(data[offset..].starts_with(&field.try_to_vec().unwrap()), "field not at offset {}", offset);
+}
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+/// ASCII string of exactly `len` bytes, the longest the program accepts
+fn text(len: usize) -> impl Strategy<Value = String> {
+    proptest::collection::vec(0x20u8..0x7f, len).prop_map(|bytes| String::from_utf8(bytes).unwrap())
+}
+
+/// Mostly `Some`, the larger encoding
+fn optional<S: Strategy>(strategy: S) -> impl Strategy<Value = Option<S::Value>> {
+    weighted(0.9, strategy)
+}
+
+fn storage_pointer() -> impl Strategy<Value = StoragePointer> {
+    (
+        proptest::sample::select(StorageBackend::ALL.to_vec()),
+        text(StoragePointer::MAX_REFERENCE_LEN),
+    )
+        .prop_map(|(backend, reference)| StoragePointer { backend, reference })
+}
+
+fn data_type() -> impl Strategy<Value = DataType> {
+    prop_oneof![
+        Just(DataType::LocationHistory),
+        Just(DataType::AppUsage),
+        Just(DataType::PurchaseHistory),
+        Just(DataType::HealthData),
+        Just(DataType::SocialMediaActivity),
+        Just(DataType::SearchHistory),
+        Just(DataType::FinancialData),
+        Just(DataType::CommunicationData),
+        Just(DataType::Custom),
+    ]
+}
+
+fn verification_level() -> impl Strategy<Value = VerificationLevel> {
+    prop_oneof![
+        Just(VerificationLevel::None),
+        Just(VerificationLevel::Basic),
+        Just(VerificationLevel::Enhanced),
+        Just(VerificationLevel::High),
+        Just(VerificationLevel::Credential),
+    ]
+}
+
+fn identity_status() -> impl Strategy<Value = IdentityStatus> {
+    prop_oneof![
+        Just(IdentityStatus::Pending),
+        Just(IdentityStatus::Verified),
+        Just(IdentityStatus::Revoked),
+        Just(IdentityStatus::Suspended),
+    ]
+}
+
+fn permission_type() -> impl Strategy<Value = PermissionType> {
+    prop_oneof![
+        Just(PermissionType::ReadOnly),
+        Just(PermissionType::ReadWrite),
+        Just(PermissionType::Share),
+        Just(PermissionType::Analyze),
+        Just(PermissionType::Export),
+    ]
+}
+
+fn purpose() -> impl Strategy<Value = Purpose> {
+    prop_oneof![
+        Just(Purpose::Research),
+        Just(Purpose::Analytics),
+        Just(Purpose::Marketing),
+        Just(Purpose::Advertising),
+        Just(Purpose::ProductDevelopment),
+        Just(Purpose::Personalization),
+        Just(Purpose::Compliance),
+        Just(Purpose::ModelTraining),
+        Just(Purpose::Other),
+    ]
+}
+
+fn dispute_status() -> impl Strategy<Value = DisputeStatus> {
+    prop_oneof![
+        Just(DisputeStatus::AwaitingPanel),
+        Just(DisputeStatus::Voting),
+        Just(DisputeStatus::Upheld),
+        Just(DisputeStatus::Dismissed),
+    ]
+}
+
+fn export_status() -> impl Strategy<Value = ExportStatus> {
+    prop_oneof![
+        Just(ExportStatus::None),
+        Just(ExportStatus::Pending),
+        Just(ExportStatus::Fulfilled),
+        Just(ExportStatus::Overdue),
+    ]
+}
+
+#[test]
+fn fixed_size_accounts_fit() {
+    assert_fits(&KYCOracleRegistry {
+        authority: Pubkey::new_unique(),
+        minimum_stake: u64::MAX,
+        slash_amount: u64::MAX,
+        oracle_count: u32::MAX,
+        event_sequence: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&CrankFund {
+        authority: Pubkey::new_unique(),
+        reward_per_account: u64::MAX,
+        total_swept: u64::MAX,
+        total_paid: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&TrialRecord {
+        consumer: Pubkey::new_unique(),
+        used_data_types: u16::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&Juror {
+        authority: Pubkey::new_unique(),
+        stake: u64::MAX,
+        active_cases: u32::MAX,
+        cases_voted: u64::MAX,
+        majority_votes: u64::MAX,
+        joined_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&TrustMembership {
+        data_trust: Pubkey::new_unique(),
+        identity: Pubkey::new_unique(),
+        joined_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&WebProofVerifier {
+        verifier_id: u32::MAX,
+        verification_level: VerificationLevel::Credential,
+        verifying_key: Groth16VerifyingKey {
+            alpha_g1: [u8::MAX; 64],
+            beta_g2: [u8::MAX; 128],
+            gamma_g2: [u8::MAX; 128],
+            delta_g2: [u8::MAX; 128],
+            ic: [[u8::MAX; 64]; Groth16VerifyingKey::IC_LEN],
+        },
+        is_active: true,
+        registered_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&WebProofNullifier {
+        verifier: Pubkey::new_unique(),
+        identity: Pubkey::new_unique(),
+        used_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&DomainLink {
+        domain_account: Pubkey::new_unique(),
+        identity: Pubkey::new_unique(),
+        linked_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&VoucherNonce {
+        identity: Pubkey::new_unique(),
+        nonce: u64::MAX,
+        redeemed_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&ComplianceOfficer {
+        officer: Pubkey::new_unique(),
+        appointed_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&IdentityFreeze {
+        identity: Pubkey::new_unique(),
+        officer: Pubkey::new_unique(),
+        reason_hash: [u8::MAX; 32],
+        prior_status: IdentityStatus::Verified,
+        frozen_at: i64::MAX,
+        expires_at: i64::MAX,
+        appeal_hash: Some([u8::MAX; 32]),
+        appealed_at: Some(i64::MAX),
+        bump: u8::MAX,
+    });
+}
+
+proptest! {
+    #[test]
+    fn kyc_oracle_fits(provider_name in text(64)) {
+        let oracle = KYCOracle {
+            oracle_pubkey: Pubkey::new_unique(),
+            provider_name,
+            stake_amount: u64::MAX,
+            verification_count: u64::MAX,
+            successful_verifications: u64::MAX,
+            reputation_score: u16::MAX,
+            is_active: true,
+            registered_at: i64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&oracle);
+    }
+
+    #[test]
+    fn identity_account_fits(
+        identity_id in text(64),
+        storage_pointer in storage_pointer(),
+        status in identity_status(),
+        verification_level in verification_level(),
+        (verified_at, guardian, emancipation_at) in (optional(any::<i64>()), optional(pubkey()), optional(any::<i64>())),
+        sns_domain in text(64),
+        sns_domain_account in optional(pubkey()),
+        encryption_key in optional(any::<[u8; 32]>()),
+    ) {
+        let identity = IdentityAccount {
+            owner: Pubkey::new_unique(),
+            status,
+            verification_level,
+            frozen_until: i64::MAX,
+            frozen_while_verified: true,
+            reserved: [0; 7],
+            identity_id,
+            storage_pointer,
+            verified_at,
+            created_at: i64::MAX,
+            updated_at: i64::MAX,
+            guardian,
+            emancipation_at,
+            sns_domain,
+            sns_domain_account,
+            encryption_key,
+            bump: u8::MAX,
+        };
+        assert_fits(&identity);
+        assert_at(&identity, IdentityAccount::OWNER_OFFSET, &identity.owner);
+        assert_at(&identity, IdentityAccount::STATUS_OFFSET, &identity.status);
+        assert_at(&identity, IdentityAccount::VERIFICATION_LEVEL_OFFSET, &identity.verification_level);
+    }
+
+    #[test]
+    fn access_permission_fits(
+        identity_id in text(64),
+        permission_type in permission_type(),
+        data_types in proptest::collection::vec(data_type(), 10),
+        expires_at in optional(any::<i64>()),
+        proof_pointer in storage_pointer(),
+        (max_accesses, revoked_at, privacy_budget) in (optional(any::<u32>()), optional(any::<i64>()), optional(any::<u32>())),
+        consent_hash in any::<[u8; 32]>(),
+    ) {
+        let permission = AccessPermission {
+            consumer: Pubkey::new_unique(),
+            is_active: true,
+            permission_type,
+            reserved: [0; 16],
+            identity_id,
+            data_types,
+            granted_at: i64::MAX,
+            expires_at,
+            proof_pointer,
+            is_trial: true,
+            max_accesses,
+            access_count: u32::MAX,
+            revocation_epoch: u32::MAX,
+            revoked_at,
+            allowed_purposes: u16::MAX,
+            consent_hash,
+            consent_version: u32::MAX,
+            privacy_budget,
+            privacy_spent: u32::MAX,
+            attestation_count: u32::MAX,
+            rent_payer: Pubkey::new_unique(),
+            bump: u8::MAX,
+        };
+        assert_fits(&permission);
+        assert_at(&permission, AccessPermission::CONSUMER_OFFSET, &permission.consumer);
+        assert_at(&permission, AccessPermission::IS_ACTIVE_OFFSET, &permission.is_active);
+        assert_at(&permission, AccessPermission::PERMISSION_TYPE_OFFSET, &permission.permission_type);
+        assert_at(&permission, AccessPermission::IDENTITY_ID_OFFSET, &permission.identity_id);
+    }
+
+    #[test]
+    fn compliance_matrix_fits(
+        rules in proptest::collection::vec(
+            (data_type(), verification_level(), verification_level(), any::<u64>()),
+            ComplianceMatrix::MAX_RULES,
+        ),
+    ) {
+        let matrix = ComplianceMatrix {
+            authority: Pubkey::new_unique(),
+            rules: rules
+                .into_iter()
+                .map(|(data_type, min_buyer_level, min_seller_level, min_consumer_stake)| ComplianceRule {
+                    data_type,
+                    min_buyer_level,
+                    min_seller_level,
+                    min_consumer_stake,
+                })
+                .collect(),
+            bump: u8::MAX,
+        };
+        assert_fits(&matrix);
+    }
+
+    #[test]
+    fn blacklist_fits(consumers in proptest::collection::vec(pubkey(), Blacklist::MAX_CONSUMERS)) {
+        let blacklist = Blacklist {
+            authority: Pubkey::new_unique(),
+            consumers,
+            bump: u8::MAX,
+        };
+        assert_fits(&blacklist);
+    }
+
+    #[test]
+    fn consumer_profile_fits(
+        contact_tx_id in text(128),
+        kyb_attestation_tx_id in text(128),
+        kyb_verified_by in optional(pubkey()),
+        kyb_verified_at in optional(any::<i64>()),
+        encryption_key in optional(any::<[u8; 32]>()),
+    ) {
+        let profile = ConsumerProfile {
+            consumer: Pubkey::new_unique(),
+            organization_name_hash: [u8::MAX; 32],
+            contact_tx_id,
+            kyb_attestation_tx_id,
+            completed_purchases: u64::MAX,
+            disputes_lost: u32::MAX,
+            revocations_received: u32::MAX,
+            kyb_verified_by,
+            kyb_verified_at,
+            overdue_exports: u32::MAX,
+            terms_hash: [u8::MAX; 32],
+            terms_version: u32::MAX,
+            encryption_key,
+            created_at: i64::MAX,
+            updated_at: i64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&profile);
+    }
+
+    #[test]
+    fn consumer_stake_fits(unlock_at in optional(any::<i64>())) {
+        let stake = ConsumerStake {
+            consumer: Pubkey::new_unique(),
+            amount: u64::MAX,
+            slashed_total: u64::MAX,
+            unlock_at,
+            bump: u8::MAX,
+        };
+        assert_fits(&stake);
+    }
+
+    #[test]
+    fn juror_pool_fits(jurors in proptest::collection::vec(pubkey(), JurorPool::MAX_JURORS)) {
+        let pool = JurorPool {
+            authority: Pubkey::new_unique(),
+            vrf_authority: Pubkey::new_unique(),
+            jurors,
+            dispute_count: u64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&pool);
+    }
+
+    #[test]
+    fn dispute_fits(
+        evidence_tx_id in text(128),
+        panel in proptest::collection::vec(pubkey(), Dispute::PANEL_SIZE),
+        votes in proptest::collection::vec(optional(any::<bool>()), Dispute::PANEL_SIZE),
+        status in dispute_status(),
+        vote_deadline in optional(any::<i64>()),
+    ) {
+        let dispute = Dispute {
+            dispute_id: u64::MAX,
+            claimant: Pubkey::new_unique(),
+            consumer: Pubkey::new_unique(),
+            evidence_tx_id,
+            requested_slash: u64::MAX,
+            jury_fee: u64::MAX,
+            panel,
+            votes,
+            status,
+            opened_at: i64::MAX,
+            vote_deadline,
+            bump: u8::MAX,
+        };
+        assert_fits(&dispute);
+    }
+
+    #[test]
+    fn tee_attestation_fits(
+        tee_type in prop_oneof![Just(TeeType::IntelSgx), Just(TeeType::AmdSev)],
+        verified_by in optional(pubkey()),
+        verified_at in optional(any::<i64>()),
+    ) {
+        let attestation = TeeAttestation {
+            provider: Pubkey::new_unique(),
+            tee_type,
+            report_hash: [u8::MAX; 32],
+            measurement: [u8::MAX; 32],
+            posted_at: i64::MAX,
+            verified_by,
+            verified_at,
+            bump: u8::MAX,
+        };
+        assert_fits(&attestation);
+    }
+
+    #[test]
+    fn data_trust_fits(trustees in proptest::collection::vec(pubkey(), DataTrust::MAX_TRUSTEES)) {
+        let trust = DataTrust {
+            creator: Pubkey::new_unique(),
+            trust_id: u64::MAX,
+            trustees,
+            charter_hash: [u8::MAX; 32],
+            allowed_data_types: u16::MAX,
+            max_grant_duration: i64::MAX,
+            member_count: u32::MAX,
+            created_at: i64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&trust);
+    }
+
+    #[test]
+    fn export_request_fits(
+        status in export_status(),
+        package_tx_id in text(128),
+        fulfilled_at in optional(any::<i64>()),
+    ) {
+        let request = ExportRequest {
+            identity: Pubkey::new_unique(),
+            consumer: Pubkey::new_unique(),
+            owner_encryption_key: [u8::MAX; 32],
+            requested_at: i64::MAX,
+            deadline: i64::MAX,
+            status,
+            package_tx_id,
+            package_hash: [u8::MAX; 32],
+            fulfilled_at,
+            bump: u8::MAX,
+        };
+        assert_fits(&request);
+    }
+
+    #[test]
+    fn consumption_attestation_fits(
+        data_type in data_type(),
+        purpose in purpose(),
+        tee_attestation in optional(pubkey()),
+    ) {
+        let attestation = ConsumptionAttestation {
+            permission: Pubkey::new_unique(),
+            consumer: Pubkey::new_unique(),
+            sequence: u32::MAX,
+            data_type,
+            purpose,
+            accessed_at: i64::MAX,
+            record_count: u64::MAX,
+            manifest_hash: [u8::MAX; 32],
+            access_count: u32::MAX,
+            tee_attestation,
+            attested_at: i64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&attestation);
+    }
+
+    #[test]
+    fn attestation_source_fits(
+        kind in prop_oneof![Just(AttestationKind::CivicPass), Just(AttestationKind::SolanaAttestationService)],
+        verification_level in verification_level(),
+    ) {
+        let source = AttestationSource {
+            kind,
+            issuer: Pubkey::new_unique(),
+            schema: Pubkey::new_unique(),
+            verification_level,
+            is_active: true,
+            bump: u8::MAX,
+        };
+        assert_fits(&source);
+    }
+
+    #[test]
+    fn access_request_fits(
+        permission_type in permission_type(),
+        data_types in proptest::collection::vec(data_type(), 10),
+    ) {
+        let request = AccessRequest {
+            identity: Pubkey::new_unique(),
+            consumer: Pubkey::new_unique(),
+            permission_type,
+            data_types,
+            duration: i64::MAX,
+            price: u64::MAX,
+            requested_at: i64::MAX,
+            expires_at: i64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&request);
+    }
+}
@@ -1,16 +1,134 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Approve, Burn, Revoke, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::confidential_transfer;
+use anchor_spl::token_2022::spl_token_2022::extension::confidential_transfer::ciphertext_extraction::transfer_amount_source_ciphertext;
+use anchor_spl::token_2022::spl_token_2022::extension::confidential_transfer::instruction::{
+    ConfidentialTransferInstruction, ConfigureAccountInstructionData,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::{metadata_pointer, ExtensionType};
+use anchor_spl::token_2022::spl_token_2022::instruction::{AuthorityType, TokenInstruction};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_2022::spl_token_2022::proof::ProofLocation;
+use anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::curve25519::ristretto::{
+    add_ristretto, multiply_ristretto, subtract_ristretto, PodRistrettoPoint,
+};
+use anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::curve25519::scalar::PodScalar;
+use anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::instruction::{
+    transfer::TransferProofContext, BatchedRangeProofContext, CiphertextCommitmentEqualityProofContext, ProofType,
+};
+use anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::zk_token_elgamal::ops;
+use anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::zk_token_elgamal::pod::{AeCiphertext, ElGamalCiphertext};
+use anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::zk_token_proof_program;
+use anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::zk_token_proof_state::ProofContextState;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{self, Mint, TokenAccount as InterfaceTokenAccount, TransferChecked};
 use anchor_spl::associated_token::AssociatedToken;
+use datasov_audit::program::DatasovAudit;
+use datasov_audit::AuditAction;
+use datasov_identity::seeds as identity_seeds;
 use datasov_identity::{
     program::DatasovIdentity,
     IdentityAccount,
     AccessPermission,
+    Blacklist,
+    ComplianceMatrix,
+    ConsumerProfile,
+    ConsumerStake,
     IdentityStatus,
+    DataTrust,
+    TrustMembership,
+    TeeAttestation,
     DataType as IdentityDataType,
+    Purpose,
+    RentReclaim,
+    StorageBackend,
+    StoragePointer,
 };
+use datasov_staking::{StakeWeight, StakingConfig};
+use bytemuck::Zeroable;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
+/// PDA seed prefixes
+pub mod seeds {
+    pub const MARKETPLACE: &[u8] = b"marketplace";
+    /// `DataListing`, per listing ID
+    pub const LISTING: &[u8] = b"listing";
+    /// `PurchaseReceipt`, per listing
+    pub const RECEIPT: &[u8] = b"receipt";
+    /// Token account holding a purchase's escrowed payment, per listing
+    pub const ESCROW: &[u8] = b"escrow";
+    /// `ListingAllowlist`, per listing
+    pub const ALLOWLIST: &[u8] = b"allowlist";
+    /// `DataManifest`, per listing
+    pub const DATA_MANIFEST: &[u8] = b"data_manifest";
+    /// `Coupon`, per seller and code hash
+    pub const COUPON: &[u8] = b"coupon";
+    /// `PurchaseDelegate`, per consumer and delegate
+    pub const PURCHASE_DELEGATE: &[u8] = b"purchase_delegate";
+    pub const CRANK_FUND: &[u8] = b"crank_fund";
+    /// `ComputeJob`, per listing, buyer and job ID
+    pub const COMPUTE_JOB: &[u8] = b"compute_job";
+    /// Token account holding a compute job's payment, per job
+    pub const JOB_ESCROW: &[u8] = b"job_escrow";
+    /// `FlJob`, per consumer and job ID
+    pub const FL_JOB: &[u8] = b"fl_job";
+    /// Token account holding a federated learning job's reward pool, per job
+    pub const FL_ESCROW: &[u8] = b"fl_escrow";
+    /// `FlParticipant`, per job and identity
+    pub const FL_PARTICIPANT: &[u8] = b"fl_participant";
+    /// `DataUnion`, per operator and union ID
+    pub const UNION: &[u8] = b"union";
+    /// Token account holding a union's undistributed proceeds, per union
+    pub const UNION_VAULT: &[u8] = b"union_vault";
+    /// `UnionMember`, per union and identity
+    pub const UNION_MEMBER: &[u8] = b"union_member";
+    /// `RebateProgram`, the fee rebate config
+    pub const REBATE_PROGRAM: &[u8] = b"rebate_program";
+    /// Token account holding the protocol tokens rebates are paid from
+    pub const REBATE_VAULT: &[u8] = b"rebate_vault";
+    /// `RebateEpoch`, per epoch index
+    pub const REBATE_EPOCH: &[u8] = b"rebate_epoch";
+    /// `RebateLedger`, per epoch and participant
+    pub const REBATE_LEDGER: &[u8] = b"rebate_ledger";
+    /// `BuybackConfig`, also the authority of the buyback vaults
+    pub const BUYBACK: &[u8] = b"buyback";
+    /// Token account holding fee tokens set aside for the next swap
+    pub const BUYBACK_INPUT: &[u8] = b"buyback_input";
+    /// Token account receiving bought protocol tokens until they are burned
+    pub const BUYBACK_OUTPUT: &[u8] = b"buyback_output";
+    /// `CurationConfig`, the featured-listing curation config
+    pub const CURATION: &[u8] = b"curation";
+    /// Token account holding curators' vote bonds
+    pub const CURATION_VAULT: &[u8] = b"curation_vault";
+    /// `FeaturedSet`, per epoch index
+    pub const FEATURED_SET: &[u8] = b"featured_set";
+    /// `ListingTally`, per epoch and listing
+    pub const LISTING_TALLY: &[u8] = b"listing_tally";
+    /// `CurationVote`, per tally and curator
+    pub const CURATION_VOTE: &[u8] = b"curation_vote";
+    /// `StorageOracle`, per oracle
+    pub const STORAGE_ORACLE: &[u8] = b"storage_oracle";
+    /// `StorageHealth`, per listing
+    pub const STORAGE_HEALTH: &[u8] = b"storage_health";
+    /// `LicenseTemplate`, per template ID
+    pub const LICENSE_TEMPLATE: &[u8] = b"license_template";
+    /// Token-2022 mint of a purchase's license NFT, per receipt
+    pub const LICENSE_MINT: &[u8] = b"license_mint";
+    /// `PriceCandle`, per settlement mint, data type index and UTC day
+    pub const PRICE_CANDLE: &[u8] = b"price_candle";
+    /// `EarningsLedger`, per identity
+    pub const EARNINGS_LEDGER: &[u8] = b"earnings_ledger";
+    /// `FeatureFlags`, the subsystems enabled on this cluster
+    pub const FEATURE_FLAGS: &[u8] = b"feature_flags";
+}
+
 #[program]
 pub mod datasov_solana {
     use super::*;
@@ -25,6 +143,11 @@ pub mod datasov_solana {
         marketplace.fee_basis_points = marketplace_fee_basis_points;
         marketplace.total_listings = 0;
         marketplace.total_volume = 0;
+        marketplace.halted_data_types = 0;
+        marketplace.stablecoin_only = false;
+        marketplace.approved_mints = Vec::new();
+        marketplace.refund_window = 0;
+        marketplace.event_sequence = 0;
         marketplace.bump = ctx.bumps.marketplace;
         
         msg!("DataSov marketplace initialized with fee: {} basis points", marketplace_fee_basis_points);
@@ -40,27 +163,91 @@ pub mod datasov_solana {
         description: String,
         identity_id: String,
     ) -> Result<()> {
-        let listing = &mut ctx.accounts.listing;
+        require!(ctx.accounts.seller_identity.owner == ctx.accounts.owner.key(), ErrorCode::IdentityMismatch);
+
+        ctx.accounts.create_listing(ctx.bumps.listing, listing_id, price, data_type, description, identity_id)
+    }
+
+    /// Create a private listing whose price and description are only revealed to allowlisted buyers
+    pub fn create_private_listing(
+        ctx: Context<CreatePrivateListing>,
+        listing_id: u64,
+        price_commitment: [u8; 32],
+        description_hash: [u8; 32],
+        data_type: DataType,
+        identity_id: String,
+        allowed_buyers: Vec<Pubkey>,
+    ) -> Result<()> {
+        let allowlist = &mut ctx.accounts.allowlist;
         let marketplace = &mut ctx.accounts.marketplace;
         let seller_identity = &ctx.accounts.seller_identity;
 
         // Validate seller identity
         require!(seller_identity.status == IdentityStatus::Verified, ErrorCode::SellerNotVerified);
         require!(seller_identity.owner == ctx.accounts.owner.key(), ErrorCode::IdentityMismatch);
+        require!(
+            seller_identity.guardian_approved(
+                ctx.accounts.guardian.as_ref().map(|g| g.key()),
+                Clock::get()?.unix_timestamp
+            ),
+            ErrorCode::GuardianSignatureRequired
+        );
+        require!(!marketplace.is_halted(&data_type), ErrorCode::DataTypeHalted);
+        require!(
+            ctx.accounts.compliance_matrix.seller_allowed(
+                &data_type.to_identity_data_type(),
+                &seller_identity.verification_level
+            ),
+            ErrorCode::SellerLevelTooLow
+        );
+        require!(data_type.fits(), ErrorCode::CustomDataTypeTooLong);
+        require!(allowed_buyers.len() > 0, ErrorCode::EmptyAllowlist);
+        require!(allowed_buyers.len() <= ListingAllowlist::MAX_BUYERS, ErrorCode::AllowlistTooLong);
 
+        // Price and description stay off chain; only their commitments are stored. The listing
+        // starts zeroed, so everything not set here is unset, false or empty.
+        let mut listing = ctx.accounts.listing.load_init()?;
         listing.id = listing_id;
         listing.owner = ctx.accounts.owner.key();
-        listing.price = price;
-        listing.data_type = data_type;
-        listing.description = description;
-        listing.identity_id = identity_id;
-        listing.is_active = true;
+        listing.set_data_type(&data_type)?;
+        listing.set_identity_id(&identity_id)?;
+        listing.is_active = u8::from(true);
         listing.created_at = Clock::get()?.unix_timestamp;
+        listing.last_refreshed_at = listing.created_at;
+        listing.is_private = u8::from(true);
+        listing.price_commitment = price_commitment;
+        listing.description_hash = description_hash;
+        listing.allowed_purposes = Purpose::ALL;
+        listing.payout_wallet = ctx.accounts.owner.key();
+        listing.rent_payer = ctx.accounts.owner.key();
         listing.bump = ctx.bumps.listing;
 
+        allowlist.listing = ctx.accounts.listing.key();
+        allowlist.buyers = allowed_buyers;
+        allowlist.bump = ctx.bumps.allowlist;
+
         marketplace.total_listings += 1;
 
-        msg!("Data listing created with ID: {} and price: {} lamports", listing_id, price);
+        msg!("Private data listing created with ID: {}", listing_id);
+        Ok(())
+    }
+
+    /// Replace the set of buyers allowed to purchase a private listing
+    pub fn update_listing_allowlist(
+        ctx: Context<UpdateListingAllowlist>,
+        allowed_buyers: Vec<Pubkey>,
+    ) -> Result<()> {
+        let listing = ctx.accounts.listing.load()?;
+        let allowlist = &mut ctx.accounts.allowlist;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        require!(listing.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        require!(allowed_buyers.len() > 0, ErrorCode::EmptyAllowlist);
+        require!(allowed_buyers.len() <= ListingAllowlist::MAX_BUYERS, ErrorCode::AllowlistTooLong);
+
+        allowlist.buyers = allowed_buyers;
+
+        msg!("Allowlist updated for listing: {}", listing.id);
         Ok(())
     }
 
@@ -68,88 +255,161 @@ pub mod datasov_solana {
     pub fn purchase_data(
         ctx: Context<PurchaseData>,
         listing_id: u64,
+        purpose: Purpose,
+        price_reveal: Option<PriceReveal>,
+        coupon_code: Option<String>,
+        max_staleness: Option<i64>,
     ) -> Result<()> {
-        let listing = &mut ctx.accounts.listing;
-        let marketplace = &mut ctx.accounts.marketplace;
-        let seller_identity = &ctx.accounts.seller_identity;
-        let buyer_identity = &ctx.accounts.buyer_identity;
-        let buyer_permission = &ctx.accounts.buyer_permission;
-
-        require!(listing.is_active, ErrorCode::ListingNotActive);
-        require!(listing.id == listing_id, ErrorCode::InvalidListingId);
-
-        // Validate seller identity
-        require!(seller_identity.status == IdentityStatus::Verified, ErrorCode::SellerNotVerified);
-        require!(seller_identity.owner == listing.owner, ErrorCode::IdentityMismatch);
+        let buyer = ctx.accounts.buyer.key();
+        process_purchase(ctx, listing_id, purpose, price_reveal, coupon_code, max_staleness, buyer)
+    }
 
-        // Validate buyer identity
-        require!(buyer_identity.status == IdentityStatus::Verified, ErrorCode::BuyerNotVerified);
-        require!(buyer_identity.owner == ctx.accounts.buyer.key(), ErrorCode::IdentityMismatch);
+    /// Purchase data on behalf of another identity; the payer funds it, the recipient gets the access
+    pub fn purchase_for(
+        ctx: Context<PurchaseData>,
+        listing_id: u64,
+        purpose: Purpose,
+        price_reveal: Option<PriceReveal>,
+        coupon_code: Option<String>,
+        recipient: Pubkey,
+        max_staleness: Option<i64>,
+    ) -> Result<()> {
+        process_purchase(ctx, listing_id, purpose, price_reveal, coupon_code, max_staleness, recipient)
+    }
 
-        // Validate buyer access permission
-        require!(buyer_permission.is_active, ErrorCode::NoAccessPermission);
+    /// Purchase a private listing with Token-2022 confidential transfers, so neither the price nor
+    /// any balance is revealed. Context state accounts prove the payment and fee transfers, that
+    /// together they move exactly the committed price, and that the fee is the marketplace's cut.
+    pub fn purchase_data_confidential(
+        ctx: Context<PurchaseDataConfidential>,
+        listing_id: u64,
+        purpose: Purpose,
+        owner_new_decryptable_balance: [u8; 36],
+        fee_new_decryptable_balance: Option<[u8; 36]>,
+        max_staleness: Option<i64>,
+    ) -> Result<()> {
+        validate_purchase(
+            &ctx.accounts.marketplace,
+            &ctx.accounts.compliance_matrix,
+            &ctx.accounts.blacklist,
+            ctx.accounts.buyer_stake.as_deref(),
+            &ctx.accounts.buyer_profile,
+            &*ctx.accounts.listing.load()?,
+            listing_id,
+            &ctx.accounts.seller_identity,
+            &ctx.accounts.buyer_identity,
+            &ctx.accounts.buyer_permission,
+            ctx.accounts.allowlist.as_deref(),
+            ctx.accounts.buyer.key(),
+            &purpose,
+        )?;
+        ctx.accounts.listing.load()?.check_freshness(max_staleness, Clock::get()?.unix_timestamp)?;
+        ctx.accounts.marketplace.check_settlement_mint(&ctx.accounts.mint.key())?;
 
-        // Convert marketplace DataType to identity DataType for comparison
-        let required_data_type = match listing.data_type {
-            DataType::LocationHistory => IdentityDataType::LocationHistory,
-            DataType::AppUsage => IdentityDataType::AppUsage,
-            DataType::PurchaseHistory => IdentityDataType::PurchaseHistory,
-            DataType::HealthData => IdentityDataType::HealthData,
-            DataType::SocialMediaActivity => IdentityDataType::SocialMediaActivity,
-            DataType::SearchHistory => IdentityDataType::SearchHistory,
-            DataType::Custom(_) => IdentityDataType::Custom,
-        };
+        // A public listing's price is on chain anyway; only a private one has a commitment to prove against
+        require!(ctx.accounts.listing.load()?.is_private(), ErrorCode::ConfidentialPublicListing);
+        ctx.accounts.check_payment_proofs()?;
 
-        require!(
-            buyer_permission.data_types.contains(&required_data_type),
-            ErrorCode::DataTypeNotAuthorized
-        );
+        // Transfer payment to owner; the amount is proven by a pre-verified context state account
+        let owner_transfer_ix = confidential_transfer::instruction::inner_transfer(
+            ctx.accounts.token_program.key,
+            &ctx.accounts.buyer_token_account.key(),
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.owner_token_account.key(),
+            AeCiphertext(owner_new_decryptable_balance),
+            ctx.accounts.buyer.key,
+            &[],
+            ProofLocation::ContextStateAccount(ctx.accounts.owner_transfer_proof.key),
+        )?;
+        invoke(
+            &owner_transfer_ix,
+            &[
+                ctx.accounts.buyer_token_account.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.owner_token_account.to_account_info(),
+                ctx.accounts.owner_transfer_proof.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
+            ],
+        )?;
 
-        // Check permission expiration
-        if let Some(expires_at) = buyer_permission.expires_at {
-            require!(Clock::get()?.unix_timestamp < expires_at, ErrorCode::PermissionExpired);
+        // Transfer fee to marketplace
+        if ctx.accounts.marketplace.fee_basis_points > 0 {
+            let fee_transfer_proof = ctx.accounts.fee_transfer_proof.as_ref().ok_or(ErrorCode::FeeProofRequired)?;
+            let fee_balance = fee_new_decryptable_balance.ok_or(ErrorCode::FeeProofRequired)?;
+            let fee_transfer_ix = confidential_transfer::instruction::inner_transfer(
+                ctx.accounts.token_program.key,
+                &ctx.accounts.buyer_token_account.key(),
+                &ctx.accounts.mint.key(),
+                &ctx.accounts.marketplace_token_account.key(),
+                AeCiphertext(fee_balance),
+                ctx.accounts.buyer.key,
+                &[],
+                ProofLocation::ContextStateAccount(fee_transfer_proof.key),
+            )?;
+            invoke(
+                &fee_transfer_ix,
+                &[
+                    ctx.accounts.buyer_token_account.to_account_info(),
+                    ctx.accounts.mint.to_account_info(),
+                    ctx.accounts.marketplace_token_account.to_account_info(),
+                    fee_transfer_proof.to_account_info(),
+                    ctx.accounts.buyer.to_account_info(),
+                ],
+            )?;
         }
 
-        let purchase_amount = listing.price;
-        let fee_amount = (purchase_amount as u128)
-            .checked_mul(marketplace.fee_basis_points as u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
-        let owner_amount = purchase_amount
-            .checked_sub(fee_amount)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        // Update listing; volume is not tracked since the amount is never revealed
+        let mut listing = ctx.accounts.listing.load_mut()?;
+        let receipt = &mut ctx.accounts.receipt;
+        let now = Clock::get()?.unix_timestamp;
 
-        // Transfer payment to owner
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.buyer_token_account.to_account_info(),
-            to: ctx.accounts.owner_token_account.to_account_info(),
-            authority: ctx.accounts.buyer.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, owner_amount)?;
+        listing.is_active = u8::from(false);
+        listing.set_buyer(Some(ctx.accounts.buyer.key()));
+        listing.set_sold_at(Some(now));
 
-        // Transfer fee to marketplace
-        if fee_amount > 0 {
-            let fee_cpi_accounts = Transfer {
-                from: ctx.accounts.buyer_token_account.to_account_info(),
-                to: ctx.accounts.marketplace_token_account.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            };
-            let fee_cpi_program = ctx.accounts.token_program.to_account_info();
-            let fee_cpi_ctx = CpiContext::new(fee_cpi_program, fee_cpi_accounts);
-            token::transfer(fee_cpi_ctx, fee_amount)?;
-        }
+        receipt.listing = ctx.accounts.listing.key();
+        receipt.listing_id = listing_id;
+        receipt.buyer = ctx.accounts.buyer.key();
+        receipt.payer = ctx.accounts.buyer.key();
+        receipt.seller = listing.owner;
+        receipt.amount = 0;
+        receipt.is_confidential = true;
+        receipt.amount_commitment = listing.price_commitment;
+        receipt.permission = ctx.accounts.buyer_permission.key();
+        receipt.permission_epoch = ctx.accounts.buyer_permission.revocation_epoch;
+        receipt.escrowed_amount = 0;
+        receipt.released_amount = 0;
+        receipt.refunded_amount = 0;
+        receipt.refund_window = 0;
+        receipt.purpose = purpose;
+        receipt.watermark_commitment = PurchaseReceipt::watermark(&receipt.key(), &receipt.buyer, now);
+        receipt.license_template = listing.license_template();
+        receipt.purchased_at = now;
+        receipt.rent_payer = ctx.accounts.buyer.key();
+        receipt.bump = ctx.bumps.receipt;
+        drop(listing);
 
-        // Update listing and marketplace
-        listing.is_active = false;
-        listing.buyer = Some(ctx.accounts.buyer.key());
-        listing.sold_at = Some(Clock::get()?.unix_timestamp);
+        emit!(PurchaseWatermarkEvent {
+            receipt: receipt.key(),
+            listing: receipt.listing,
+            buyer: receipt.buyer,
+            watermark_commitment: receipt.watermark_commitment,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-        marketplace.total_volume += purchase_amount;
+        ctx.accounts.record_buyer_purchase()?;
+        ctx.accounts.audit.record(
+            ctx.bumps.audit.audit_writer,
+            ctx.accounts.buyer.to_account_info(),
+            ctx.accounts.seller_identity.key(),
+            AuditAction::Purchase,
+            ctx.accounts.buyer.key(),
+            ctx.accounts.receipt.key(),
+            0,
+        )?;
 
-        msg!("Data purchased successfully. Listing ID: {}, Amount: {} lamports", listing_id, purchase_amount);
+        msg!("Data purchased confidentially");
         Ok(())
     }
 
@@ -158,10 +418,11 @@ pub mod datasov_solana {
         ctx: Context<UpdateListingPrice>,
         new_price: u64,
     ) -> Result<()> {
-        let listing = &mut ctx.accounts.listing;
+        let mut listing = ctx.accounts.listing.load_mut()?;
         
-        require!(listing.is_active, ErrorCode::ListingNotActive);
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
         require!(listing.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        require!(!listing.is_private(), ErrorCode::PrivateListing);
         
         listing.price = new_price;
         
@@ -173,13 +434,13 @@ pub mod datasov_solana {
     pub fn cancel_listing(
         ctx: Context<CancelListing>,
     ) -> Result<()> {
-        let listing = &mut ctx.accounts.listing;
+        let mut listing = ctx.accounts.listing.load_mut()?;
         
-        require!(listing.is_active, ErrorCode::ListingNotActive);
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
         require!(listing.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
         
-        listing.is_active = false;
-        listing.cancelled_at = Some(Clock::get()?.unix_timestamp);
+        listing.is_active = u8::from(false);
+        listing.set_cancelled_at(Some(Clock::get()?.unix_timestamp));
         
         msg!("Listing cancelled successfully");
         Ok(())
@@ -201,7 +462,7 @@ pub mod datasov_solana {
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let seeds = &[
-            b"marketplace",
+            seeds::MARKETPLACE,
             &[marketplace.bump],
         ];
         let signer = &[&seeds[..]];
@@ -211,215 +472,7117 @@ pub mod datasov_solana {
         msg!("Fees withdrawn: {} lamports", amount);
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-#[instruction(marketplace_fee_basis_points: u16)]
-pub struct InitializeMarketplace<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = Marketplace::LEN,
-        seeds = [b"marketplace"],
-        bump
-    )]
-    pub marketplace: Account<'info, Marketplace>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+    /// Set up the marketplace's Token-2022 fee account to receive confidential purchase fees under
+    /// an ElGamal key the authority holds, proven by a pubkey validity context state account
+    pub fn configure_confidential_fees(
+        ctx: Context<ConfigureConfidentialFees>,
+        decryptable_zero_balance: [u8; 36],
+        maximum_pending_balance_credit_counter: u64,
+    ) -> Result<()> {
+        let token_program = ctx.accounts.token_program.key();
+        let fee_account = ctx.accounts.marketplace_token_account.key();
+        let marketplace_key = ctx.accounts.marketplace.key();
+        let seeds = &[seeds::MARKETPLACE, &[ctx.accounts.marketplace.bump]];
+        let signer = &[&seeds[..]];
 
-#[derive(Accounts)]
-#[instruction(listing_id: u64, _price: u64, _data_type: DataType, _description: String, identity_id: String)]
-pub struct CreateDataListing<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = DataListing::LEN,
-        seeds = [b"listing", listing_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub listing: Account<'info, DataListing>,
+        // Associated token accounts are created without room for confidential transfer state
+        invoke_signed(
+            &spl_token_2022::instruction::reallocate(
+                &token_program,
+                &fee_account,
+                &ctx.accounts.authority.key(),
+                &marketplace_key,
+                &[],
+                &[ExtensionType::ConfidentialTransferAccount],
+            )?,
+            &[
+                ctx.accounts.marketplace_token_account.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.marketplace.to_account_info(),
+            ],
+            signer,
+        )?;
 
-    #[account(
-        mut,
-        seeds = [b"marketplace"],
-        bump = marketplace.bump
-    )]
-    pub marketplace: Account<'info, Marketplace>,
+        // spl-token-2022 only builds this instruction off chain, so it is packed here
+        let mut data = TokenInstruction::ConfidentialTransferExtension.pack();
+        data.push(ConfidentialTransferInstruction::ConfigureAccount.into());
+        data.extend_from_slice(bytemuck::bytes_of(&ConfigureAccountInstructionData {
+            decryptable_zero_balance: AeCiphertext(decryptable_zero_balance),
+            maximum_pending_balance_credit_counter: maximum_pending_balance_credit_counter.into(),
+            proof_instruction_offset: 0,
+        }));
+        let configure_ix = Instruction {
+            program_id: token_program,
+            accounts: vec![
+                AccountMeta::new(fee_account, false),
+                AccountMeta::new_readonly(ctx.accounts.mint.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.pubkey_validity_proof.key(), false),
+                AccountMeta::new_readonly(marketplace_key, true),
+            ],
+            data,
+        };
+        invoke_signed(
+            &configure_ix,
+            &[
+                ctx.accounts.marketplace_token_account.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.pubkey_validity_proof.to_account_info(),
+                ctx.accounts.marketplace.to_account_info(),
+            ],
+            signer,
+        )?;
 
-    #[account(
-        seeds = [b"identity", identity_id.as_bytes()],
-        bump,
-        seeds::program = identity_program.key()
-    )]
-    pub seller_identity: Account<'info, IdentityAccount>,
+        msg!("Confidential fee account configured for mint {}", ctx.accounts.mint.key());
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    /// Credit confidential fees received since the last call to the fee account's available balance
+    pub fn apply_confidential_fees(
+        ctx: Context<ApplyConfidentialFees>,
+        expected_pending_balance_credit_counter: u64,
+        new_decryptable_available_balance: [u8; 36],
+    ) -> Result<()> {
+        let seeds = &[seeds::MARKETPLACE, &[ctx.accounts.marketplace.bump]];
+        let signer = &[&seeds[..]];
+        let apply_ix = confidential_transfer::instruction::inner_apply_pending_balance(
+            ctx.accounts.token_program.key,
+            &ctx.accounts.marketplace_token_account.key(),
+            expected_pending_balance_credit_counter,
+            AeCiphertext(new_decryptable_available_balance),
+            &ctx.accounts.marketplace.key(),
+            &[],
+        )?;
+        invoke_signed(
+            &apply_ix,
+            &[
+                ctx.accounts.marketplace_token_account.to_account_info(),
+                ctx.accounts.marketplace.to_account_info(),
+            ],
+            signer,
+        )?;
 
-    pub identity_program: Program<'info, DatasovIdentity>,
-    pub system_program: Program<'info, System>,
-}
+        msg!("Pending confidential fees applied");
+        Ok(())
+    }
 
-#[derive(Accounts)]
-#[instruction(listing_id: u64)]
-pub struct PurchaseData<'info> {
-    #[account(
-        mut,
-        seeds = [b"listing", listing_id.to_le_bytes().as_ref()],
-        bump = listing.bump
-    )]
-    pub listing: Account<'info, DataListing>,
+    /// Withdraw confidential fees: decrypt `amount` out of the fee account's available balance,
+    /// proven by a withdraw context state account, and transfer it to the authority
+    pub fn withdraw_confidential_fees(
+        ctx: Context<WithdrawConfidentialFees>,
+        amount: u64,
+        new_decryptable_available_balance: [u8; 36],
+    ) -> Result<()> {
+        let decimals = ctx.accounts.mint.decimals;
+        let seeds = &[seeds::MARKETPLACE, &[ctx.accounts.marketplace.bump]];
+        let signer = &[&seeds[..]];
+        let withdraw_ix = confidential_transfer::instruction::inner_withdraw(
+            ctx.accounts.token_program.key,
+            &ctx.accounts.marketplace_token_account.key(),
+            &ctx.accounts.mint.key(),
+            amount,
+            decimals,
+            AeCiphertext(new_decryptable_available_balance),
+            &ctx.accounts.marketplace.key(),
+            &[],
+            ProofLocation::ContextStateAccount(ctx.accounts.withdraw_proof.key),
+        )?;
+        invoke_signed(
+            &withdraw_ix,
+            &[
+                ctx.accounts.marketplace_token_account.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.withdraw_proof.to_account_info(),
+                ctx.accounts.marketplace.to_account_info(),
+            ],
+            signer,
+        )?;
 
-    #[account(
-        mut,
-        seeds = [b"marketplace"],
-        bump = marketplace.bump
-    )]
-    pub marketplace: Account<'info, Marketplace>,
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.marketplace_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.authority_token_account.to_account_info(),
+                authority: ctx.accounts.marketplace.to_account_info(),
+            },
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
-    #[account(
-        seeds = [b"identity", listing.identity_id.as_bytes()],
-        bump,
-        seeds::program = identity_program.key()
-    )]
-    pub seller_identity: Account<'info, IdentityAccount>,
+        msg!("Confidential fees withdrawn: {}", amount);
+        Ok(())
+    }
 
-    #[account(
-        seeds = [b"identity", buyer_identity.identity_id.as_bytes()],
-        bump,
-        seeds::program = identity_program.key()
-    )]
-    pub buyer_identity: Account<'info, IdentityAccount>,
+    /// Halt or resume trading for a single data type
+    pub fn halt_data_type(
+        ctx: Context<HaltDataType>,
+        data_type: DataType,
+        halted: bool,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
 
-    #[account(
-        seeds = [
-            b"permission",
-            seller_identity.key().as_ref(),
-            buyer.key().as_ref()
-        ],
-        bump,
-        seeds::program = identity_program.key()
-    )]
-    pub buyer_permission: Account<'info, AccessPermission>,
+        require!(marketplace.authority == ctx.accounts.authority.key(), ErrorCode::Unauthorized);
 
-    #[account(mut)]
-    pub buyer: Signer<'info>,
+        if halted {
+            marketplace.halted_data_types |= data_type.halt_mask();
+        } else {
+            marketplace.halted_data_types &= !data_type.halt_mask();
+        }
 
-    #[account(mut)]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+        emit!(DataTypeHaltUpdatedEvent {
+            data_type: data_type,
+            halted: halted,
+            event_sequence: marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-    #[account(mut)]
-    pub owner_token_account: Account<'info, TokenAccount>,
+        msg!("Data type halt updated: halted = {}", halted);
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        associated_token::mint = buyer_token_account.mint,
-        associated_token::authority = marketplace
-    )]
-    pub marketplace_token_account: Account<'info, TokenAccount>,
+    /// Replace the set of enabled subsystems, creating the flags account on first use. Subsystems
+    /// check their bit before starting new work, so a faulty one can be switched off on a cluster
+    /// without a redeploy.
+    pub fn set_feature_flags(ctx: Context<SetFeatureFlags>, flags: u64) -> Result<()> {
+        require!(flags & !FeatureFlags::KNOWN == 0, ErrorCode::UnknownFeatureFlag);
 
-    pub identity_program: Program<'info, DatasovIdentity>,
-    pub token_program: Program<'info, Token>,
-}
+        let feature_flags = &mut ctx.accounts.feature_flags;
+        let now = Clock::get()?.unix_timestamp;
+        feature_flags.flags = flags;
+        feature_flags.updated_at = now;
+        feature_flags.bump = ctx.bumps.feature_flags;
 
-#[derive(Accounts)]
-pub struct UpdateListingPrice<'info> {
-    #[account(
-        mut,
-        seeds = [b"listing", listing.id.to_le_bytes().as_ref()],
-        bump = listing.bump,
-        has_one = owner
-    )]
-    pub listing: Account<'info, DataListing>,
-    
-    pub owner: Signer<'info>,
-}
+        emit!(FeatureFlagsUpdatedEvent {
+            flags,
+            updated_at: now,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
 
-#[derive(Accounts)]
-pub struct CancelListing<'info> {
-    #[account(
-        mut,
-        seeds = [b"listing", listing.id.to_le_bytes().as_ref()],
-        bump = listing.bump,
-        has_one = owner
-    )]
-    pub listing: Account<'info, DataListing>,
-    
-    pub owner: Signer<'info>,
-}
+        msg!("Feature flags set to {:#x}", flags);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct WithdrawFees<'info> {
-    #[account(
-        mut,
-        seeds = [b"marketplace"],
-        bump = marketplace.bump,
-        has_one = authority
-    )]
-    pub marketplace: Account<'info, Marketplace>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(mut)]
-    pub marketplace_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        associated_token::mint = marketplace_token_account.mint,
-        associated_token::authority = authority
-    )]
-    pub authority_token_account: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
-}
+    /// Set or clear the time after which a listing can no longer be purchased
+    pub fn set_listing_expiry(
+        ctx: Context<SetListingExpiry>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
 
-#[account]
-pub struct Marketplace {
-    pub authority: Pubkey,
-    pub fee_basis_points: u16,
-    pub total_listings: u64,
-    pub total_volume: u64,
-    pub bump: u8,
-}
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        require!(listing.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
 
-impl Marketplace {
-    pub const LEN: usize = 8 + 32 + 2 + 8 + 8 + 1;
-}
+        listing.set_expires_at(expires_at);
 
-#[account]
-pub struct DataListing {
-    pub id: u64,
-    pub owner: Pubkey,
-    pub price: u64,
-    pub data_type: DataType,
-    pub description: String,
-    pub identity_id: String,
-    pub is_active: bool,
-    pub created_at: i64,
-    pub sold_at: Option<i64>,
-    pub cancelled_at: Option<i64>,
-    pub buyer: Option<Pubkey>,
-    pub bump: u8,
-}
+        msg!("Listing {} expiry updated", listing.id);
+        Ok(())
+    }
 
-impl DataListing {
-    pub const LEN: usize = 8 + 8 + 32 + 8 + 1 + (4 + 200) + (4 + 64) + 1 + 8 + (1 + 8) + (1 + 8) + (1 + 32) + 1;
-}
+    /// Set or clear where buyers fetch a listing's (encrypted) data from
+    pub fn set_listing_data_pointer(
+        ctx: Context<SetListingDataPointer>,
+        data_pointer: Option<StoragePointer>,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum DataType {
-    LocationHistory,
-    AppUsage,
-    PurchaseHistory,
-    HealthData,
-    SocialMediaActivity,
-    SearchHistory,
-    Custom(String),
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        require!(
+            data_pointer.as_ref().map_or(true, StoragePointer::is_valid),
+            ErrorCode::InvalidStoragePointer
+        );
+
+        listing.data_pointer.set(data_pointer.as_ref())?;
+        // Nothing commits to the content behind a pointer set this way
+        listing.data_hash = [0u8; 32];
+
+        msg!("Listing {} data pointer updated", listing.id);
+        Ok(())
+    }
+
+    /// Rotate an active listing's data to `data_pointer`, e.g. a refreshed dataset, committing to
+    /// the hash of its content so buyers can verify what they fetch and learn that it changed
+    pub fn update_listing_data(
+        ctx: Context<UpdateListingData>,
+        data_pointer: StoragePointer,
+        data_hash: [u8; 32],
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        require!(data_pointer.is_valid(), ErrorCode::InvalidStoragePointer);
+        require!(data_hash != [0; 32], ErrorCode::MissingDataHash);
+        require!(data_hash != listing.data_hash, ErrorCode::ListingDataUnchanged);
+
+        let previous_hash = listing.data_hash;
+        listing.data_pointer.set(Some(&data_pointer))?;
+        listing.data_hash = data_hash;
+
+        emit!(ListingDataUpdatedEvent {
+            listing: ctx.accounts.listing.key(),
+            data_pointer,
+            previous_hash,
+            data_hash,
+            updated_at: Clock::get()?.unix_timestamp,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Listing {} data updated", listing.id);
+        Ok(())
+    }
+
+    /// Set or clear the public, unencrypted sample buyers can inspect before purchasing
+    pub fn set_listing_preview(
+        ctx: Context<SetListingPreview>,
+        preview: Option<ListingPreview>,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        if let Some(preview) = &preview {
+            require!(preview.pointer.is_valid(), ErrorCode::InvalidStoragePointer);
+            require!(preview.hash != [0; 32], ErrorCode::MissingPreviewHash);
+        }
+
+        listing.set_preview(preview.as_ref())?;
+
+        msg!("Listing {} preview updated", listing.id);
+        Ok(())
+    }
+
+    /// Set or clear the oracle that attests a listing's freshness in place of its seller
+    pub fn set_listing_freshness_oracle(
+        ctx: Context<SetListingFreshnessOracle>,
+        freshness_oracle: Option<Pubkey>,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+
+        listing.set_freshness_oracle(freshness_oracle);
+
+        msg!("Listing {} freshness oracle updated", listing.id);
+        Ok(())
+    }
+
+    /// Attest that a continuously produced listing's data was refreshed at `refreshed_at`, signing
+    /// as its freshness oracle or, when it has none, its seller
+    pub fn attest_listing_freshness(
+        ctx: Context<AttestListingFreshness>,
+        refreshed_at: i64,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+        let attestor = ctx.accounts.attestor.key();
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        require!(
+            attestor == listing.freshness_oracle().unwrap_or(listing.owner),
+            ErrorCode::NotFreshnessAttestor
+        );
+        require!(
+            refreshed_at >= listing.last_refreshed_at && refreshed_at <= Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidRefreshTime
+        );
+
+        listing.last_refreshed_at = refreshed_at;
+
+        emit!(ListingFreshnessAttestedEvent {
+            listing: ctx.accounts.listing.key(),
+            attestor,
+            refreshed_at,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Listing {} refreshed at {}", listing.id, refreshed_at);
+        Ok(())
+    }
+
+    /// Start the manifest of a dataset stored as many Arweave transactions, with its first chunks
+    pub fn create_data_manifest(
+        ctx: Context<CreateDataManifest>,
+        chunks: Vec<ManifestChunk>,
+    ) -> Result<()> {
+        let listing = ctx.accounts.listing.load()?;
+        let manifest = &mut ctx.accounts.manifest;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+
+        manifest.listing = ctx.accounts.listing.key();
+        manifest.total_size = 0;
+        manifest.is_sealed = false;
+        manifest.manifest_hash = [0; 32];
+        manifest.chunks = Vec::new();
+        manifest.bump = ctx.bumps.manifest;
+        manifest.append(chunks)?;
+
+        msg!("Data manifest created for listing {} with {} chunks", listing.id, manifest.chunks.len());
+        Ok(())
+    }
+
+    /// Add chunks to a manifest that is not sealed yet, in dataset order
+    pub fn append_manifest_chunks(
+        ctx: Context<UpdateDataManifest>,
+        chunks: Vec<ManifestChunk>,
+    ) -> Result<()> {
+        let manifest = &mut ctx.accounts.manifest;
+
+        require!(ctx.accounts.listing.load()?.is_active(), ErrorCode::ListingNotActive);
+        require!(!manifest.is_sealed, ErrorCode::ManifestSealed);
+        manifest.append(chunks)?;
+
+        msg!("Data manifest for listing {} has {} chunks", ctx.accounts.listing.load()?.id, manifest.chunks.len());
+        Ok(())
+    }
+
+    /// Freeze a manifest, committing to its chunks so buyers can verify what they fetch
+    pub fn seal_data_manifest(ctx: Context<UpdateDataManifest>) -> Result<()> {
+        let manifest = &mut ctx.accounts.manifest;
+
+        require!(!manifest.is_sealed, ErrorCode::ManifestSealed);
+        require!(!manifest.chunks.is_empty(), ErrorCode::EmptyManifest);
+
+        manifest.is_sealed = true;
+        manifest.manifest_hash = manifest.compute_hash();
+
+        emit!(DataManifestSealedEvent {
+            listing: manifest.listing,
+            chunk_count: manifest.chunks.len() as u32,
+            total_size: manifest.total_size,
+            manifest_hash: manifest.manifest_hash,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Data manifest sealed for listing {}", ctx.accounts.listing.load()?.id);
+        Ok(())
+    }
+
+    /// Initialize the crank fund that pays callers for cleaning up expired listings
+    pub fn initialize_crank_fund(
+        ctx: Context<InitializeCrankFund>,
+        reward_per_account: u64,
+    ) -> Result<()> {
+        let crank_fund = &mut ctx.accounts.crank_fund;
+        crank_fund.authority = ctx.accounts.authority.key();
+        crank_fund.reward_per_account = reward_per_account;
+        crank_fund.total_swept = 0;
+        crank_fund.total_paid = 0;
+        crank_fund.bump = ctx.bumps.crank_fund;
+
+        msg!("Crank fund initialized with reward: {} lamports per account", reward_per_account);
+        Ok(())
+    }
+
+    /// Deactivate expired listings passed as remaining accounts and pay the caller a bounty
+    pub fn sweep_expired<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepExpired<'info>>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let mut swept: u64 = 0;
+
+        for account_info in ctx.remaining_accounts.iter() {
+            let loader = AccountLoader::<DataListing>::try_from(account_info)?;
+            require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+            let mut listing = loader.load_mut()?;
+
+            if !listing.is_active() {
+                continue;
+            }
+            match listing.expires_at() {
+                Some(expires_at) if expires_at <= now => {}
+                _ => continue,
+            }
+
+            listing.is_active = u8::from(false);
+            swept += 1;
+        }
+
+        let reward = CrankFund::pay_reward(&mut ctx.accounts.crank_fund, swept, &ctx.accounts.caller)?;
+
+        emit!(ExpiredSweptEvent {
+            caller: ctx.accounts.caller.key(),
+            swept: swept,
+            reward: reward,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Swept {} expired listings, reward: {} lamports", swept, reward);
+        Ok(())
+    }
+
+    /// Close a listing sold more than `DataListing::RECLAIM_DELAY` ago whose sale has settled and
+    /// whose buyer's access has ended (anyone may call). The caller keeps
+    /// `RentReclaim::CALLER_SHARE_BASIS_POINTS` of the listing's recovered rent; the rest goes back
+    /// to whoever paid it. The purchase receipt closes with the listing, refunding its own rent payer,
+    /// so a listing created later under the same ID can be bought again.
+    pub fn reclaim_listing_rent(ctx: Context<ReclaimListingRent>, _listing_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let receipt = &ctx.accounts.receipt;
+
+        // A grant already reclaimed leaves nothing to validate the receipt against
+        let permission_info = ctx.accounts.permission.to_account_info();
+        let access_ended = permission_info.data_is_empty() || {
+            require_keys_eq!(
+                *permission_info.owner,
+                datasov_identity::ID,
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+            );
+            let permission = AccessPermission::try_deserialize(&mut &permission_info.try_borrow_data()?[..])?;
+            receipt.is_revoked(&permission) || permission.expires_at.map_or(false, |expires_at| expires_at <= now)
+        };
+        require!(
+            ctx.accounts.listing.load()?.reclaimable(receipt, access_ended, now),
+            ErrorCode::NotReclaimable
+        );
+
+        let listing_info = ctx.accounts.listing.to_account_info();
+        let reward = RentReclaim::pay_caller(&listing_info, &ctx.accounts.caller)?;
+        let refund = listing_info.lamports();
+
+        emit!(RentReclaimedEvent {
+            account: listing_info.key(),
+            rent_payer: ctx.accounts.rent_payer.key(),
+            caller: ctx.accounts.caller.key(),
+            refund: refund,
+            reward: reward,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Listing {} reclaimed: {} lamports refunded, {} rewarded", ctx.accounts.listing.load()?.id, refund, reward);
+        Ok(())
+    }
+
+    /// Require (or stop requiring) business-verified buyers for a listing
+    pub fn set_listing_requires_kyb(
+        ctx: Context<SetListingRequiresKyb>,
+        requires_kyb: bool,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        require!(listing.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+
+        listing.requires_kyb = u8::from(requires_kyb);
+
+        msg!("Listing {} KYB requirement: {}", listing.id, requires_kyb);
+        Ok(())
+    }
+
+    /// Create a discount coupon for the seller's listings
+    pub fn create_coupon(
+        ctx: Context<CreateCoupon>,
+        code_hash: [u8; 32],
+        discount_basis_points: u16,
+        max_uses: u32,
+        expires_at: Option<i64>,
+        listing: Option<Pubkey>,
+    ) -> Result<()> {
+        let coupon = &mut ctx.accounts.coupon;
+
+        require!(
+            discount_basis_points > 0 && discount_basis_points <= 10000,
+            ErrorCode::InvalidDiscount
+        );
+        require!(max_uses > 0, ErrorCode::InvalidDiscount);
+
+        coupon.seller = ctx.accounts.seller.key();
+        coupon.code_hash = code_hash;
+        coupon.discount_basis_points = discount_basis_points;
+        coupon.max_uses = max_uses;
+        coupon.remaining_uses = max_uses;
+        coupon.expires_at = expires_at;
+        coupon.listing = listing;
+        coupon.bump = ctx.bumps.coupon;
+
+        msg!("Coupon created with discount: {} basis points", discount_basis_points);
+        Ok(())
+    }
+
+    /// Close a coupon, returning its rent to the seller
+    pub fn close_coupon(
+        _ctx: Context<CloseCoupon>,
+    ) -> Result<()> {
+        msg!("Coupon closed");
+        Ok(())
+    }
+
+    /// Authorize a custodial operator to purchase on the consumer's behalf within a spend limit
+    pub fn create_purchase_delegate(
+        ctx: Context<CreatePurchaseDelegate>,
+        delegate: Pubkey,
+        spend_limit: u64,
+        max_per_purchase: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        require!(delegate != ctx.accounts.consumer.key(), ErrorCode::InvalidDelegateTerms);
+        require!(spend_limit > 0, ErrorCode::InvalidDelegateTerms);
+        require!(
+            max_per_purchase > 0 && max_per_purchase <= spend_limit,
+            ErrorCode::InvalidDelegateTerms
+        );
+        require!(expires_at > clock.unix_timestamp, ErrorCode::InvalidDelegateTerms);
+
+        // Let the delegate PDA move up to the spend limit out of the consumer's token account
+        let cpi_accounts = Approve {
+            to: ctx.accounts.consumer_token_account.to_account_info(),
+            delegate: ctx.accounts.purchase_delegate.to_account_info(),
+            authority: ctx.accounts.consumer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::approve(cpi_ctx, spend_limit)?;
+
+        let purchase_delegate = &mut ctx.accounts.purchase_delegate;
+        purchase_delegate.consumer = ctx.accounts.consumer.key();
+        purchase_delegate.delegate = delegate;
+        purchase_delegate.token_account = ctx.accounts.consumer_token_account.key();
+        purchase_delegate.spend_limit = spend_limit;
+        purchase_delegate.spent = 0;
+        purchase_delegate.max_per_purchase = max_per_purchase;
+        purchase_delegate.expires_at = expires_at;
+        purchase_delegate.created_at = clock.unix_timestamp;
+        purchase_delegate.bump = ctx.bumps.purchase_delegate;
+
+        msg!("Purchase delegate {} authorized up to {}", delegate, spend_limit);
+        Ok(())
+    }
+
+    /// Revoke a custodial purchase delegate and its token approval
+    pub fn revoke_purchase_delegate(
+        ctx: Context<RevokePurchaseDelegate>,
+    ) -> Result<()> {
+        let cpi_accounts = Revoke {
+            source: ctx.accounts.consumer_token_account.to_account_info(),
+            authority: ctx.accounts.consumer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::revoke(cpi_ctx)?;
+
+        msg!("Purchase delegate {} revoked", ctx.accounts.purchase_delegate.delegate);
+        Ok(())
+    }
+
+    /// Purchase data as a custodial delegate, paid from the consumer's token account
+    pub fn purchase_as_delegate(
+        ctx: Context<PurchaseData>,
+        listing_id: u64,
+        purpose: Purpose,
+        price_reveal: Option<PriceReveal>,
+        coupon_code: Option<String>,
+        max_staleness: Option<i64>,
+    ) -> Result<()> {
+        let consumer = ctx
+            .accounts
+            .purchase_delegate
+            .as_ref()
+            .map(|purchase_delegate| purchase_delegate.consumer)
+            .ok_or(ErrorCode::PurchaseDelegateRequired)?;
+        process_purchase(ctx, listing_id, purpose, price_reveal, coupon_code, max_staleness, consumer)
+    }
+
+    /// Configure stablecoin-only settlement and its approved mint list
+    pub fn set_settlement_mints(
+        ctx: Context<SetSettlementMints>,
+        stablecoin_only: bool,
+        approved_mints: Vec<Pubkey>,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+
+        require!(
+            approved_mints.len() <= Marketplace::MAX_APPROVED_MINTS,
+            ErrorCode::TooManyApprovedMints
+        );
+        require!(!stablecoin_only || !approved_mints.is_empty(), ErrorCode::TooManyApprovedMints);
+
+        marketplace.stablecoin_only = stablecoin_only;
+        marketplace.approved_mints = approved_mints.clone();
+
+        emit!(SettlementMintsUpdatedEvent {
+            stablecoin_only: stablecoin_only,
+            approved_mints: approved_mints,
+            event_sequence: marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Settlement mints updated: stablecoin only = {}", stablecoin_only);
+        Ok(())
+    }
+
+    /// Check that access bought under a receipt has not been revoked by the identity owner
+    pub fn validate_receipt_access(
+        ctx: Context<ValidateReceiptAccess>,
+        _listing_id: u64,
+    ) -> Result<()> {
+        let receipt = &ctx.accounts.receipt;
+
+        require!(
+            !receipt.is_revoked(&ctx.accounts.permission),
+            ErrorCode::ReceiptAccessRevoked
+        );
+
+        msg!("Receipt access valid");
+        Ok(())
+    }
+
+    /// Set how long after purchase buyers can claim a pro-rata refund if access is revoked
+    pub fn set_refund_window(
+        ctx: Context<SetRefundWindow>,
+        refund_window: i64,
+    ) -> Result<()> {
+        require!(
+            refund_window >= 0 && refund_window <= Marketplace::MAX_REFUND_WINDOW,
+            ErrorCode::InvalidRefundWindow
+        );
+
+        ctx.accounts.marketplace.refund_window = refund_window;
+
+        msg!("Refund window set to {} seconds", refund_window);
+        Ok(())
+    }
+
+    /// Release the seller's vested share of escrowed purchase proceeds
+    pub fn release_proceeds(
+        ctx: Context<ReleaseProceeds>,
+        _listing_id: u64,
+    ) -> Result<()> {
+        let receipt = &mut ctx.accounts.receipt;
+        let now = Clock::get()?.unix_timestamp;
+
+        // Vesting stops at the revocation, if the owner cut the buyer's access short
+        let cutoff = receipt.revoked_at(&ctx.accounts.permission).unwrap_or(now).min(now);
+        // A later revocation can move the cutoff back before proceeds already released
+        let releasable = receipt.vested_amount(cutoff)?.saturating_sub(receipt.released_amount);
+        require!(releasable > 0, ErrorCode::NothingToRelease);
+
+        receipt.released_amount = receipt
+            .released_amount
+            .checked_add(releasable)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Marketplace::transfer_signed(
+            &ctx.accounts.marketplace,
+            ctx.accounts.escrow_token_account.to_account_info(),
+            ctx.accounts.seller_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            releasable,
+        )?;
+        ctx.accounts.earnings_ledger.record(
+            ctx.accounts.seller_identity.key(),
+            &ctx.accounts.listing.load()?.data_type(),
+            receipt.buyer,
+            releasable,
+            now,
+            ctx.bumps.earnings_ledger,
+        )?;
+
+        msg!("Proceeds released: {}", releasable);
+        Ok(())
+    }
+
+    /// Claim the unvested share of escrowed proceeds after the owner revokes access within the refund window
+    pub fn claim_revocation_refund(
+        ctx: Context<ClaimRevocationRefund>,
+        _listing_id: u64,
+    ) -> Result<()> {
+        let receipt = &mut ctx.accounts.receipt;
+        let claimant = ctx.accounts.claimant.key();
+
+        require!(claimant == receipt.buyer || claimant == receipt.payer, ErrorCode::Unauthorized);
+        require!(receipt.refunded_amount == 0, ErrorCode::RefundAlreadyClaimed);
+
+        let revoked_at = receipt
+            .revoked_at(&ctx.accounts.permission)
+            .ok_or(ErrorCode::AccessNotRevoked)?;
+        // Proceeds the seller already released stay released, even past the revocation
+        let earned = receipt.vested_amount(revoked_at)?.max(receipt.released_amount);
+        let refund = receipt
+            .escrowed_amount
+            .checked_sub(earned)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(refund > 0, ErrorCode::RefundWindowClosed);
+
+        receipt.refunded_amount = refund;
+
+        Marketplace::transfer_signed(
+            &ctx.accounts.marketplace,
+            ctx.accounts.escrow_token_account.to_account_info(),
+            ctx.accounts.payer_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            refund,
+        )?;
+
+        emit!(RevocationRefundedEvent {
+            listing: receipt.listing,
+            buyer: receipt.buyer,
+            payer: receipt.payer,
+            amount: refund,
+            revoked_at: revoked_at,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Revocation refund claimed: {}", refund);
+        Ok(())
+    }
+
+    /// Restrict the purposes buyers may declare when purchasing a listing
+    pub fn set_listing_purposes(
+        ctx: Context<SetListingPurposes>,
+        purposes: Vec<Purpose>,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+
+        require!(!purposes.is_empty(), ErrorCode::NoPurposes);
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+
+        listing.allowed_purposes = Purpose::mask(&purposes);
+
+        msg!("Listing {} purposes updated", listing.id);
+        Ok(())
+    }
+
+    /// License a listing's sales under a license template, or clear its license by passing none
+    pub fn set_listing_license(ctx: Context<SetListingLicense>) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        if let Some(license_template) = &ctx.accounts.license_template {
+            require!(license_template.is_active, ErrorCode::LicenseTemplateInactive);
+        }
+
+        let license_template = ctx
+            .accounts
+            .license_template
+            .as_ref()
+            .map(|license_template| license_template.template_id);
+        listing.set_license_template(license_template);
+
+        msg!("Listing {} license updated", listing.id);
+        Ok(())
+    }
+
+    /// Let buyers of a listing pass their access right on to another verified identity with
+    /// `transfer_access_right`, and mint license NFTs that can trade
+    pub fn set_listing_rights_transferable(
+        ctx: Context<SetListingRightsTransferable>,
+        rights_transferable: bool,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+
+        listing.rights_transferable = u8::from(rights_transferable);
+
+        msg!("Listing {} rights transferable: {}", listing.id, rights_transferable);
+        Ok(())
+    }
+
+    /// Pay into escrow for an analysis run over a listing's data instead of buying the raw data
+    pub fn create_compute_job(
+        ctx: Context<CreateComputeJob>,
+        listing_id: u64,
+        job_id: u64,
+        purpose: Purpose,
+        spec_hash: [u8; 32],
+        provider: Option<Pubkey>,
+        timeout_seconds: i64,
+        max_attestation_age: Option<i64>,
+        price_reveal: Option<PriceReveal>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.feature_flags.is_enabled(FeatureFlags::COMPUTE_TO_DATA),
+            ErrorCode::FeatureDisabled
+        );
+        validate_purchase(
+            &ctx.accounts.marketplace,
+            &ctx.accounts.compliance_matrix,
+            &ctx.accounts.blacklist,
+            ctx.accounts.buyer_stake.as_deref(),
+            &ctx.accounts.buyer_profile,
+            &*ctx.accounts.listing.load()?,
+            listing_id,
+            &ctx.accounts.seller_identity,
+            &ctx.accounts.buyer_identity,
+            &ctx.accounts.buyer_permission,
+            ctx.accounts.allowlist.as_deref(),
+            ctx.accounts.buyer.key(),
+            &purpose,
+        )?;
+        ctx.accounts.marketplace.check_settlement_mint(&ctx.accounts.settlement_mint.key())?;
+        require!(
+            timeout_seconds > 0 && timeout_seconds <= ComputeJob::MAX_TIMEOUT,
+            ErrorCode::InvalidComputeTimeout
+        );
+        if let Some(max_age) = max_attestation_age {
+            require!(max_age > 0, ErrorCode::InvalidComputeTimeout);
+        }
+
+        let amount = ctx.accounts.listing.load()?.settlement_price(price_reveal)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        let listing = ctx.accounts.listing.load()?;
+        let job = &mut ctx.accounts.compute_job;
+        let now = Clock::get()?.unix_timestamp;
+
+        job.job_id = job_id;
+        job.listing = ctx.accounts.listing.key();
+        job.buyer = ctx.accounts.buyer.key();
+        job.seller = listing.owner;
+        job.provider = provider.unwrap_or(listing.owner);
+        job.spec_hash = spec_hash;
+        job.purpose = purpose;
+        job.amount = amount;
+        job.status = ComputeJobStatus::Pending;
+        job.result_hash = [0u8; 32];
+        job.result_arweave_tx_id = String::new();
+        job.created_at = now;
+        job.result_deadline = now + timeout_seconds;
+        job.result_posted_at = None;
+        job.max_attestation_age = max_attestation_age;
+        job.bump = ctx.bumps.compute_job;
+
+        emit!(ComputeJobCreatedEvent {
+            job: job.key(),
+            listing: job.listing,
+            buyer: job.buyer,
+            provider: job.provider,
+            spec_hash: spec_hash,
+            amount: amount,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Compute job {} created for listing {}", job_id, listing_id);
+        Ok(())
+    }
+
+    /// Post the result of a compute job (called by the seller or designated compute provider)
+    pub fn post_compute_result(
+        ctx: Context<PostComputeResult>,
+        result_hash: [u8; 32],
+        result_arweave_tx_id: String,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.compute_job;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(job.status == ComputeJobStatus::Pending, ErrorCode::InvalidComputeJobStatus);
+        require!(now < job.result_deadline, ErrorCode::ComputeJobTimedOut);
+        require!(result_arweave_tx_id.len() <= 128, ErrorCode::ArweaveTxIdTooLong);
+
+        // Jobs may require the result to come from an enclave with a recently verified attestation
+        if let Some(max_age) = job.max_attestation_age {
+            let attestation = ctx
+                .accounts
+                .tee_attestation
+                .as_ref()
+                .ok_or(ErrorCode::TeeAttestationRequired)?;
+            require!(attestation.is_fresh(now, max_age), ErrorCode::TeeAttestationStale);
+        }
+
+        job.result_hash = result_hash;
+        job.result_arweave_tx_id = result_arweave_tx_id.clone();
+        job.result_posted_at = Some(now);
+        job.status = ComputeJobStatus::ResultPosted;
+
+        emit!(ComputeResultPostedEvent {
+            job: job.key(),
+            result_hash: result_hash,
+            result_arweave_tx_id: result_arweave_tx_id,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Compute result posted for job {}", job.job_id);
+        Ok(())
+    }
+
+    /// Release escrowed compute payment to the seller on buyer confirmation, or to anyone after the confirmation period
+    pub fn release_compute_payment(
+        ctx: Context<ReleaseComputePayment>,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.compute_job;
+
+        require!(job.status == ComputeJobStatus::ResultPosted, ErrorCode::InvalidComputeJobStatus);
+        if ctx.accounts.caller.key() != job.buyer {
+            let posted_at = job.result_posted_at.ok_or(ErrorCode::InvalidComputeJobStatus)?;
+            require!(
+                Clock::get()?.unix_timestamp >= posted_at + ComputeJob::CONFIRMATION_PERIOD,
+                ErrorCode::ConfirmationPeriodActive
+            );
+        }
+
+        job.status = ComputeJobStatus::Completed;
+
+        let (owner_amount, fee_amount) = ctx.accounts.marketplace.split_payment(job.amount)?;
+        Marketplace::transfer_signed(
+            &ctx.accounts.marketplace,
+            ctx.accounts.escrow_token_account.to_account_info(),
+            ctx.accounts.seller_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            owner_amount,
+        )?;
+        if fee_amount > 0 {
+            Marketplace::transfer_signed(
+                &ctx.accounts.marketplace,
+                ctx.accounts.escrow_token_account.to_account_info(),
+                ctx.accounts.marketplace_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                fee_amount,
+            )?;
+        }
+
+        ctx.accounts.marketplace.total_volume += job.amount;
+        ctx.accounts.earnings_ledger.record(
+            ctx.accounts.seller_identity.key(),
+            &ctx.accounts.listing.load()?.data_type(),
+            job.buyer,
+            owner_amount,
+            Clock::get()?.unix_timestamp,
+            ctx.bumps.earnings_ledger,
+        )?;
+
+        msg!("Compute job {} settled: {}", job.job_id, job.amount);
+        Ok(())
+    }
+
+    /// Refund a compute job whose result was not posted before its deadline
+    pub fn refund_compute_job(
+        ctx: Context<RefundComputeJob>,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.compute_job;
+
+        require!(job.status == ComputeJobStatus::Pending, ErrorCode::InvalidComputeJobStatus);
+        require!(Clock::get()?.unix_timestamp >= job.result_deadline, ErrorCode::ComputeJobNotTimedOut);
+
+        job.status = ComputeJobStatus::Refunded;
+
+        Marketplace::transfer_signed(
+            &ctx.accounts.marketplace,
+            ctx.accounts.escrow_token_account.to_account_info(),
+            ctx.accounts.buyer_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            job.amount,
+        )?;
+
+        msg!("Compute job {} refunded: {}", job.job_id, job.amount);
+        Ok(())
+    }
+
+    /// Register a federated-learning round funded from an escrowed reward pool
+    pub fn create_fl_job(
+        ctx: Context<CreateFlJob>,
+        job_id: u64,
+        data_type: IdentityDataType,
+        model_spec_hash: [u8; 32],
+        reward_pool: u64,
+        max_participants: u32,
+        opt_in_deadline: i64,
+    ) -> Result<()> {
+        require!(reward_pool > 0, ErrorCode::InvalidFlJobTerms);
+        require!(max_participants > 0, ErrorCode::InvalidFlJobTerms);
+        require!(opt_in_deadline > Clock::get()?.unix_timestamp, ErrorCode::InvalidFlJobTerms);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.consumer_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.consumer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, reward_pool)?;
+
+        let job = &mut ctx.accounts.fl_job;
+        job.consumer = ctx.accounts.consumer.key();
+        job.job_id = job_id;
+        job.data_type = data_type;
+        job.model_spec_hash = model_spec_hash;
+        job.reward_pool = reward_pool;
+        job.max_participants = max_participants;
+        job.participant_count = 0;
+        job.total_weight = 0;
+        job.opt_in_deadline = opt_in_deadline;
+        job.status = FlJobStatus::Open;
+        job.created_at = Clock::get()?.unix_timestamp;
+        job.bump = ctx.bumps.fl_job;
+
+        emit!(FlJobCreatedEvent {
+            fl_job: job.key(),
+            consumer: job.consumer,
+            model_spec_hash: model_spec_hash,
+            reward_pool: reward_pool,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("FL job {} created with reward pool {}", job_id, reward_pool);
+        Ok(())
+    }
+
+    /// Opt an identity into a federated-learning round under its existing permission
+    pub fn join_fl_job(
+        ctx: Context<JoinFlJob>,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.fl_job;
+        let permission = &ctx.accounts.permission;
+
+        require!(job.status == FlJobStatus::Open, ErrorCode::InvalidFlJobStatus);
+        require!(Clock::get()?.unix_timestamp < job.opt_in_deadline, ErrorCode::FlOptInClosed);
+        require!(job.participant_count < job.max_participants, ErrorCode::FlJobFull);
+        require!(ctx.accounts.identity.status == IdentityStatus::Verified, ErrorCode::SellerNotVerified);
+        require!(permission.is_active, ErrorCode::NoAccessPermission);
+        require!(permission.data_types.contains(&job.data_type), ErrorCode::DataTypeNotAuthorized);
+
+        let participant = &mut ctx.accounts.participant;
+        participant.fl_job = job.key();
+        participant.identity = ctx.accounts.identity.key();
+        participant.owner = ctx.accounts.owner.key();
+        participant.permission = permission.key();
+        participant.weight = 0;
+        participant.update_hash = [0u8; 32];
+        participant.claimed = false;
+        participant.bump = ctx.bumps.participant;
+
+        job.participant_count += 1;
+
+        msg!("Identity {} joined FL job {}", ctx.accounts.identity.identity_id, job.job_id);
+        Ok(())
+    }
+
+    /// Record a participant's contribution to a federated-learning round (called by the job's consumer)
+    pub fn record_fl_contribution(
+        ctx: Context<RecordFlContribution>,
+        weight: u64,
+        update_hash: [u8; 32],
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.fl_job;
+        let participant = &mut ctx.accounts.participant;
+
+        require!(job.status == FlJobStatus::Open, ErrorCode::InvalidFlJobStatus);
+        require!(ctx.accounts.permission.is_active, ErrorCode::NoAccessPermission);
+
+        job.total_weight = job
+            .total_weight
+            .checked_sub(participant.weight)
+            .and_then(|total| total.checked_add(weight))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        participant.weight = weight;
+        participant.update_hash = update_hash;
+
+        emit!(FlContributionRecordedEvent {
+            fl_job: job.key(),
+            identity: participant.identity,
+            weight: weight,
+            update_hash: update_hash,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("FL contribution recorded: weight {}", weight);
+        Ok(())
+    }
+
+    /// Close a federated-learning round; with no recorded contributions the pool returns to the consumer
+    pub fn finalize_fl_job(
+        ctx: Context<FinalizeFlJob>,
+    ) -> Result<()> {
+        let job = &mut ctx.accounts.fl_job;
+
+        require!(job.status == FlJobStatus::Open, ErrorCode::InvalidFlJobStatus);
+
+        if job.total_weight == 0 {
+            job.status = FlJobStatus::Cancelled;
+            Marketplace::transfer_signed(
+                &ctx.accounts.marketplace,
+                ctx.accounts.escrow_token_account.to_account_info(),
+                ctx.accounts.consumer_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                job.reward_pool,
+            )?;
+        } else {
+            job.status = FlJobStatus::Finalized;
+        }
+
+        msg!("FL job {} finalized with total weight {}", job.job_id, job.total_weight);
+        Ok(())
+    }
+
+    /// Claim a participant's pro-rata share of a finalized federated-learning reward pool
+    pub fn claim_fl_reward(
+        ctx: Context<ClaimFlReward>,
+    ) -> Result<()> {
+        let job = &ctx.accounts.fl_job;
+        let participant = &mut ctx.accounts.participant;
+
+        require!(job.status == FlJobStatus::Finalized, ErrorCode::InvalidFlJobStatus);
+        require!(!participant.claimed, ErrorCode::FlRewardAlreadyClaimed);
+
+        let reward = (job.reward_pool as u128)
+            .checked_mul(participant.weight as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(job.total_weight as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        participant.claimed = true;
+
+        if reward > 0 {
+            Marketplace::transfer_signed(
+                &ctx.accounts.marketplace,
+                ctx.accounts.escrow_token_account.to_account_info(),
+                ctx.accounts.owner_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                reward,
+            )?;
+        }
+
+        msg!("FL reward claimed: {}", reward);
+        Ok(())
+    }
+
+    /// Create a data union that pools members' data of the given types into union listings
+    pub fn create_data_union(
+        ctx: Context<CreateDataUnion>,
+        union_id: u64,
+        data_types: Vec<DataType>,
+    ) -> Result<()> {
+        require!(!data_types.is_empty(), ErrorCode::InvalidUnionScope);
+
+        let data_union = &mut ctx.accounts.data_union;
+        data_union.operator = ctx.accounts.operator.key();
+        data_union.union_id = union_id;
+        data_union.vault = ctx.accounts.vault.key();
+        data_union.scope = DataType::mask(&data_types);
+        data_union.member_count = 0;
+        data_union.members_in_scope = [0; DataUnion::SCOPE_SLOTS];
+        data_union.reward_per_member = [0; DataUnion::SCOPE_SLOTS];
+        data_union.total_proceeds = 0;
+        data_union.created_at = Clock::get()?.unix_timestamp;
+        data_union.bump = ctx.bumps.data_union;
+
+        msg!("Data union {} created by operator: {}", union_id, data_union.operator);
+        Ok(())
+    }
+
+    /// Opt an identity into a data union for a subset of the union's data types
+    pub fn join_data_union(
+        ctx: Context<JoinDataUnion>,
+        data_types: Vec<DataType>,
+    ) -> Result<()> {
+        let data_union = &mut ctx.accounts.data_union;
+        let scope = DataType::mask(&data_types);
+
+        require!(ctx.accounts.identity.status == IdentityStatus::Verified, ErrorCode::SellerNotVerified);
+        require!(scope != 0 && scope & !data_union.scope == 0, ErrorCode::InvalidUnionScope);
+
+        let member = &mut ctx.accounts.member;
+        member.data_union = data_union.key();
+        member.identity = ctx.accounts.identity.key();
+        member.owner = ctx.accounts.owner.key();
+        member.scope = scope;
+        member.reward_debt = data_union.reward_per_member;
+        member.claimable = 0;
+        member.is_active = true;
+        member.joined_at = Clock::get()?.unix_timestamp;
+        member.bump = ctx.bumps.member;
+
+        for slot in 0..DataUnion::SCOPE_SLOTS {
+            if scope & (1 << slot) != 0 {
+                data_union.members_in_scope[slot] += 1;
+            }
+        }
+        data_union.member_count += 1;
+
+        emit!(DataUnionMembershipEvent {
+            data_union: data_union.key(),
+            identity: member.identity,
+            scope: scope,
+            joined: true,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Identity {} joined data union {}", ctx.accounts.identity.identity_id, data_union.union_id);
+        Ok(())
+    }
+
+    /// Leave a data union; already-earned proceeds stay claimable but future sales no longer include the member
+    pub fn exit_data_union(
+        ctx: Context<UnionMemberAction>,
+    ) -> Result<()> {
+        let data_union = &mut ctx.accounts.data_union;
+        let member = &mut ctx.accounts.member;
+
+        require!(member.is_active, ErrorCode::NotUnionMember);
+
+        member.settle(data_union)?;
+        member.is_active = false;
+
+        for slot in 0..DataUnion::SCOPE_SLOTS {
+            if member.scope & (1 << slot) != 0 {
+                data_union.members_in_scope[slot] -= 1;
+            }
+        }
+        data_union.member_count -= 1;
+
+        emit!(DataUnionMembershipEvent {
+            data_union: data_union.key(),
+            identity: member.identity,
+            scope: member.scope,
+            joined: false,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Identity left data union {}", data_union.union_id);
+        Ok(())
+    }
+
+    /// Claim a member's accrued share of data union proceeds
+    pub fn claim_union_proceeds(
+        ctx: Context<ClaimUnionProceeds>,
+    ) -> Result<()> {
+        let member = &mut ctx.accounts.member;
+
+        if member.is_active {
+            member.settle(&ctx.accounts.data_union)?;
+        }
+        let amount = member.claimable;
+        require!(amount > 0, ErrorCode::NothingToRelease);
+        member.claimable = 0;
+
+        Marketplace::transfer_signed(
+            &ctx.accounts.marketplace,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.owner_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        msg!("Union proceeds claimed: {}", amount);
+        Ok(())
+    }
+
+    /// Sell a listing on behalf of a data union; its proceeds are split among members in scope
+    pub fn attach_listing_to_union(
+        ctx: Context<AttachListingToUnion>,
+    ) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+        let data_union = &ctx.accounts.data_union;
+
+        require!(listing.is_active(), ErrorCode::ListingNotActive);
+        require!(listing.union().is_none(), ErrorCode::DataUnionMismatch);
+        require!(
+            data_union.members_in_scope[listing.data_type().index()] > 0,
+            ErrorCode::InvalidUnionScope
+        );
+
+        listing.set_union(Some(data_union.key()));
+
+        msg!("Listing {} attached to data union {}", listing.id, data_union.union_id);
+        Ok(())
+    }
+
+    /// List a trust member's data as one of the trust's trustees; the member receives the proceeds
+    pub fn create_trust_listing(
+        ctx: Context<CreateDataListing>,
+        listing_id: u64,
+        price: u64,
+        data_type: DataType,
+        description: String,
+        identity_id: String,
+    ) -> Result<()> {
+        let trust = ctx.accounts.data_trust.as_ref().ok_or(ErrorCode::TrustRequired)?;
+        let membership = ctx.accounts.trust_membership.as_ref().ok_or(ErrorCode::TrustRequired)?;
+
+        require!(membership.data_trust == trust.key(), ErrorCode::NotTrustMember);
+        require!(trust.is_trustee(&ctx.accounts.owner.key()), ErrorCode::NotTrustee);
+        require!(
+            trust.allows(&[data_type.to_identity_data_type()]),
+            ErrorCode::DataTypeNotAuthorized
+        );
+
+        ctx.accounts.create_listing(ctx.bumps.listing, listing_id, price, data_type, description, identity_id)
+    }
+
+    /// Rewrite the marketplace config created under an earlier account layout in the current one
+    pub fn migrate_marketplace(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<Marketplace>(account, Marketplace::LEGACY_SPACES)?;
+        let migrated = Marketplace::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write(8 + Marketplace::INIT_SPACE, &migrated)?;
+
+        msg!("Marketplace migrated from layout version {}", version);
+        Ok(())
+    }
+
+    /// Rewrite a listing created under an earlier account layout in the current one
+    pub fn migrate_listing(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<DataListing>(account, DataListing::LEGACY_SPACES)?;
+        let migrated = DataListing::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write_zero_copy(&migrated)?;
+
+        msg!("Listing {} migrated from layout version {}", migrated.id, version);
+        Ok(())
+    }
+
+    /// Rewrite a purchase receipt created under an earlier account layout in the current one
+    pub fn migrate_purchase_receipt(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<PurchaseReceipt>(account, PurchaseReceipt::LEGACY_SPACES)?;
+        let migrated = PurchaseReceipt::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write(8 + PurchaseReceipt::INIT_SPACE, &migrated)?;
+
+        msg!("Receipt for listing {} migrated from layout version {}", migrated.listing_id, version);
+        Ok(())
+    }
+
+    /// Rewrite a compute job created under an earlier account layout in the current one
+    pub fn migrate_compute_job(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = &ctx.accounts.account;
+        let version = legacy_version::<ComputeJob>(account, ComputeJob::LEGACY_SPACES)?;
+        let migrated = ComputeJob::from_legacy(version, &account.try_borrow_data()?)?;
+        ctx.accounts.write(8 + ComputeJob::INIT_SPACE, &migrated)?;
+
+        msg!("Compute job {} migrated from layout version {}", migrated.job_id, version);
+        Ok(())
+    }
+
+    /// Change the marketplace fee charged on purchases
+    pub fn set_marketplace_fee(
+        ctx: Context<UpdateMarketplace>,
+        fee_basis_points: u16,
+    ) -> Result<()> {
+        require!(fee_basis_points <= 10000, ErrorCode::InvalidFeeBasisPoints);
+
+        ctx.accounts.marketplace.fee_basis_points = fee_basis_points;
+
+        emit!(MarketplaceFeeUpdatedEvent {
+            fee_basis_points: fee_basis_points,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Marketplace fee set to {} basis points", fee_basis_points);
+        Ok(())
+    }
+
+    /// Hand the marketplace authority to another key or program, such as the governance program
+    pub fn set_marketplace_authority(
+        ctx: Context<UpdateMarketplace>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        let previous_authority = marketplace.authority;
+        marketplace.authority = new_authority;
+
+        emit!(MarketplaceAuthorityChangedEvent {
+            previous_authority: previous_authority,
+            new_authority: new_authority,
+            event_sequence: marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Marketplace authority changed to {}", new_authority);
+        Ok(())
+    }
+
+    /// Start paying protocol-token rebates on marketplace fees, one budget per epoch of `epoch_duration`
+    pub fn initialize_rebates(
+        ctx: Context<InitializeRebates>,
+        epoch_duration: i64,
+        emission_per_epoch: u64,
+    ) -> Result<()> {
+        require!(epoch_duration > 0, ErrorCode::InvalidRebateConfig);
+
+        let rebate_program = &mut ctx.accounts.rebate_program;
+        rebate_program.mint = ctx.accounts.mint.key();
+        rebate_program.vault = ctx.accounts.vault.key();
+        rebate_program.started_at = Clock::get()?.unix_timestamp;
+        rebate_program.epoch_duration = epoch_duration;
+        rebate_program.emission_per_epoch = emission_per_epoch;
+        rebate_program.reserved = 0;
+        rebate_program.bump = ctx.bumps.rebate_program;
+
+        msg!("Fee rebates initialized: {} per {} second epoch", emission_per_epoch, epoch_duration);
+        Ok(())
+    }
+
+    /// Change the protocol tokens each future rebate epoch may pay out
+    pub fn set_rebate_emission(
+        ctx: Context<UpdateRebateProgram>,
+        emission_per_epoch: u64,
+    ) -> Result<()> {
+        ctx.accounts.rebate_program.emission_per_epoch = emission_per_epoch;
+
+        emit!(RebateEmissionUpdatedEvent {
+            emission_per_epoch: emission_per_epoch,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Rebate emission set to {} per epoch", emission_per_epoch);
+        Ok(())
+    }
+
+    /// Open the current rebate epoch, reserving its budget from the vault (callable by anyone)
+    pub fn open_rebate_epoch(
+        ctx: Context<OpenRebateEpoch>,
+        epoch: u64,
+    ) -> Result<()> {
+        let rebate_program = &mut ctx.accounts.rebate_program;
+        let now = Clock::get()?.unix_timestamp;
+        require!(rebate_program.current_epoch(now) == epoch, ErrorCode::RebateEpochNotCurrent);
+
+        // Never promise more than the vault holds beyond what earlier epochs still owe
+        let available = ctx.accounts.vault.amount.saturating_sub(rebate_program.reserved);
+        let budget = rebate_program.emission_per_epoch.min(available);
+        rebate_program.reserved += budget;
+
+        let rebate_epoch = &mut ctx.accounts.rebate_epoch;
+        rebate_epoch.epoch = epoch;
+        rebate_epoch.starts_at = rebate_program.started_at + epoch as i64 * rebate_program.epoch_duration;
+        rebate_epoch.ends_at = rebate_epoch.starts_at + rebate_program.epoch_duration;
+        rebate_epoch.budget = budget;
+        rebate_epoch.fee_credits = 0;
+        rebate_epoch.claimed = 0;
+        rebate_epoch.bump = ctx.bumps.rebate_epoch;
+
+        msg!("Rebate epoch {} opened with budget {}", epoch, budget);
+        Ok(())
+    }
+
+    /// Opt into rebates for an open epoch; purchases credit the fees they carry to the ledger
+    pub fn open_rebate_ledger(
+        ctx: Context<OpenRebateLedger>,
+        epoch: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.rebate_epoch.is_current(Clock::get()?.unix_timestamp),
+            ErrorCode::RebateEpochNotCurrent
+        );
+
+        let ledger = &mut ctx.accounts.ledger;
+        ledger.epoch = epoch;
+        ledger.participant = ctx.accounts.participant.key();
+        ledger.fee_credits = 0;
+        ledger.bump = ctx.bumps.ledger;
+
+        msg!("Rebate ledger opened for epoch {}", epoch);
+        Ok(())
+    }
+
+    /// Claim a participant's share of an ended epoch's budget, in proportion to the fees they paid
+    pub fn claim_rebate(
+        ctx: Context<ClaimRebate>,
+    ) -> Result<()> {
+        let rebate_epoch = &mut ctx.accounts.rebate_epoch;
+        require!(Clock::get()?.unix_timestamp >= rebate_epoch.ends_at, ErrorCode::RebateEpochOpen);
+
+        let amount = rebate_epoch.share(ctx.accounts.ledger.fee_credits)?;
+        rebate_epoch.claimed += amount;
+        ctx.accounts.rebate_program.reserved -= amount;
+
+        if amount > 0 {
+            Marketplace::transfer_signed(
+                &ctx.accounts.marketplace,
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.participant_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                amount,
+            )?;
+        }
+
+        emit!(RebateClaimedEvent {
+            epoch: rebate_epoch.epoch,
+            participant: ctx.accounts.participant.key(),
+            fee_credits: ctx.accounts.ledger.fee_credits,
+            amount: amount,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Rebate claimed for epoch {}: {}", rebate_epoch.epoch, amount);
+        Ok(())
+    }
+
+    /// Configure buying back and burning the protocol token with a share of the fees in `fee_mint`
+    pub fn initialize_buyback(
+        ctx: Context<InitializeBuyback>,
+        parameters: BuybackParameters,
+    ) -> Result<()> {
+        parameters.validate()?;
+
+        let buyback_config = &mut ctx.accounts.buyback_config;
+        buyback_config.fee_mint = ctx.accounts.fee_mint.key();
+        buyback_config.protocol_mint = ctx.accounts.protocol_mint.key();
+        buyback_config.input_vault = ctx.accounts.input_vault.key();
+        buyback_config.output_vault = ctx.accounts.output_vault.key();
+        buyback_config.parameters = parameters;
+        buyback_config.last_executed_at = 0;
+        buyback_config.total_spent = 0;
+        buyback_config.total_burned = 0;
+        buyback_config.bump = ctx.bumps.buyback_config;
+
+        msg!("Buyback initialized for fee mint {}", buyback_config.fee_mint);
+        Ok(())
+    }
+
+    /// Change the buyback's DEX program, fee share, schedule and price bounds
+    pub fn set_buyback_parameters(
+        ctx: Context<UpdateBuyback>,
+        parameters: BuybackParameters,
+    ) -> Result<()> {
+        parameters.validate()?;
+        ctx.accounts.buyback_config.parameters = parameters;
+
+        msg!("Buyback parameters updated");
+        Ok(())
+    }
+
+    /// Swap the configured share of accumulated fees for the protocol token through the DEX and burn
+    /// what it returns (callable by anyone once the interval has passed). The route's accounts are
+    /// the remaining accounts; the buyback PDA signs for its vaults and nothing else.
+    pub fn execute_buyback<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteBuyback<'info>>,
+        route_data: Vec<u8>,
+        quoted_out: u64,
+    ) -> Result<()> {
+        let parameters = ctx.accounts.buyback_config.parameters.clone();
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= ctx.accounts.buyback_config.last_executed_at + parameters.interval,
+            ErrorCode::BuybackNotDue
+        );
+
+        // Set the fee share aside in the input vault, which the swap draws on
+        let amount_in = (ctx.accounts.marketplace_token_account.amount as u128 * parameters.buyback_bps as u128
+            / 10000) as u64;
+        if amount_in > 0 {
+            Marketplace::transfer_signed(
+                &ctx.accounts.marketplace,
+                ctx.accounts.marketplace_token_account.to_account_info(),
+                ctx.accounts.input_vault.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                amount_in,
+            )?;
+            ctx.accounts.input_vault.reload()?;
+        }
+        let input_before = ctx.accounts.input_vault.amount;
+        let output_before = ctx.accounts.output_vault.amount;
+        require!(input_before > 0, ErrorCode::NothingToRelease);
+
+        let buyback_key = ctx.accounts.buyback_config.key();
+        let route = Instruction {
+            program_id: ctx.accounts.dex_program.key(),
+            accounts: ctx
+                .remaining_accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.is_signer || account.key() == buyback_key,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: route_data,
+        };
+        let signer: &[&[&[u8]]] = &[&[seeds::BUYBACK, &[ctx.accounts.buyback_config.bump]]];
+        invoke_signed(&route, ctx.remaining_accounts, signer)?;
+
+        ctx.accounts.input_vault.reload()?;
+        ctx.accounts.output_vault.reload()?;
+        let spent = input_before.saturating_sub(ctx.accounts.input_vault.amount);
+        let received = ctx.accounts.output_vault.amount.saturating_sub(output_before);
+        require!(
+            received >= parameters.min_out(spent, quoted_out)?,
+            ErrorCode::BuybackSlippageExceeded
+        );
+
+        let burned = ctx.accounts.output_vault.amount;
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.protocol_mint.to_account_info(),
+                from: ctx.accounts.output_vault.to_account_info(),
+                authority: ctx.accounts.buyback_config.to_account_info(),
+            },
+            signer,
+        );
+        token::burn(cpi_ctx, burned)?;
+
+        let buyback_config = &mut ctx.accounts.buyback_config;
+        buyback_config.last_executed_at = now;
+        buyback_config.total_spent += spent;
+        buyback_config.total_burned += burned;
+
+        emit!(BuybackExecutedEvent {
+            spent: spent,
+            burned: burned,
+            executed_at: now,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Buyback spent {} fee tokens and burned {} protocol tokens", spent, burned);
+        Ok(())
+    }
+
+    /// Start featured-listing curation: stakers vote listings into each epoch's featured set,
+    /// posting `vote_bond` protocol tokens per vote that are lost if the listing is flagged as
+    /// disputed within `dispute_window` seconds of the epoch's end
+    pub fn initialize_curation(
+        ctx: Context<InitializeCuration>,
+        epoch_duration: i64,
+        vote_bond: u64,
+        dispute_window: i64,
+    ) -> Result<()> {
+        require!(epoch_duration > 0 && dispute_window >= 0, ErrorCode::InvalidCurationConfig);
+
+        let curation_config = &mut ctx.accounts.curation_config;
+        curation_config.mint = ctx.accounts.mint.key();
+        curation_config.vault = ctx.accounts.vault.key();
+        curation_config.started_at = Clock::get()?.unix_timestamp;
+        curation_config.epoch_duration = epoch_duration;
+        curation_config.vote_bond = vote_bond;
+        curation_config.dispute_window = dispute_window;
+        curation_config.bump = ctx.bumps.curation_config;
+
+        msg!("Curation initialized: {} second epochs, bond {}", epoch_duration, vote_bond);
+        Ok(())
+    }
+
+    /// Change the bond on future curation votes and the dispute window of listings not yet voted on
+    pub fn set_curation_parameters(
+        ctx: Context<UpdateCuration>,
+        vote_bond: u64,
+        dispute_window: i64,
+    ) -> Result<()> {
+        require!(dispute_window >= 0, ErrorCode::InvalidCurationConfig);
+
+        let curation_config = &mut ctx.accounts.curation_config;
+        curation_config.vote_bond = vote_bond;
+        curation_config.dispute_window = dispute_window;
+
+        msg!("Curation bond set to {}, dispute window {} seconds", vote_bond, dispute_window);
+        Ok(())
+    }
+
+    /// Vote an active listing into the current epoch's featured set with the curator's staking
+    /// weight, bonding the configured protocol tokens until the dispute window closes
+    pub fn vote_featured(
+        ctx: Context<VoteFeatured>,
+        epoch: u64,
+    ) -> Result<()> {
+        let curation_config = &ctx.accounts.curation_config;
+        let now = Clock::get()?.unix_timestamp;
+        require!(curation_config.current_epoch(now) == epoch, ErrorCode::CurationEpochNotCurrent);
+        require!(ctx.accounts.listing.load()?.is_active(), ErrorCode::ListingNotActive);
+        let weight = ctx.accounts.curator_weight.weight;
+        require!(weight > 0, ErrorCode::NoCurationWeight);
+
+        let (starts_at, ends_at) = curation_config.epoch_bounds(epoch);
+        let featured_set = &mut ctx.accounts.featured_set;
+        if featured_set.ends_at == 0 {
+            featured_set.epoch = epoch;
+            featured_set.starts_at = starts_at;
+            featured_set.ends_at = ends_at;
+            featured_set.bump = ctx.bumps.featured_set;
+        }
+
+        let tally = &mut ctx.accounts.tally;
+        if tally.settles_at == 0 {
+            tally.epoch = epoch;
+            tally.listing = ctx.accounts.listing.key();
+            tally.settles_at = ends_at + curation_config.dispute_window;
+            tally.bump = ctx.bumps.tally;
+        }
+        require!(!tally.disputed, ErrorCode::CuratedListingDisputed);
+        tally.votes = tally.votes.checked_add(weight).ok_or(ErrorCode::ArithmeticOverflow)?;
+        tally.curators += 1;
+        featured_set.record(tally.listing, tally.votes);
+
+        let bond = curation_config.vote_bond;
+        if bond > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.curator_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.curator.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::transfer(cpi_ctx, bond)?;
+        }
+
+        let vote = &mut ctx.accounts.vote;
+        vote.curator = ctx.accounts.curator.key();
+        vote.tally = tally.key();
+        vote.weight = weight;
+        vote.bond = bond;
+        vote.bump = ctx.bumps.vote;
+
+        emit!(ListingCuratedEvent {
+            epoch: epoch,
+            listing: tally.listing,
+            curator: vote.curator,
+            weight: weight,
+            votes: tally.votes,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Listing {} has {} curation votes in epoch {}", tally.listing, tally.votes, epoch);
+        Ok(())
+    }
+
+    /// Flag a curated listing as disputed, dropping it from its epoch's featured set and forfeiting
+    /// its curators' bonds (marketplace authority only, until the dispute window closes)
+    pub fn flag_curated_listing(
+        ctx: Context<FlagCuratedListing>,
+    ) -> Result<()> {
+        let tally = &mut ctx.accounts.tally;
+        require!(!tally.disputed, ErrorCode::CuratedListingDisputed);
+        require!(
+            Clock::get()?.unix_timestamp < tally.settles_at,
+            ErrorCode::CurationDisputeWindowClosed
+        );
+        tally.disputed = true;
+        ctx.accounts.featured_set.remove(&tally.listing);
+
+        emit!(CuratedListingDisputedEvent {
+            epoch: tally.epoch,
+            listing: tally.listing,
+            curators: tally.curators,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Curated listing {} flagged as disputed in epoch {}", tally.listing, tally.epoch);
+        Ok(())
+    }
+
+    /// Settle a curation vote once the dispute window has closed (callable by anyone): the bond
+    /// returns to the curator, or joins the marketplace fees if the listing was flagged
+    pub fn settle_curation_vote(
+        ctx: Context<SettleCurationVote>,
+    ) -> Result<()> {
+        let tally = &ctx.accounts.tally;
+        require!(
+            Clock::get()?.unix_timestamp >= tally.settles_at,
+            ErrorCode::CurationBondLocked
+        );
+
+        let bond = ctx.accounts.vote.bond;
+        let destination = if tally.disputed {
+            ctx.accounts.marketplace_token_account.to_account_info()
+        } else {
+            ctx.accounts.curator_token_account.to_account_info()
+        };
+        if bond > 0 {
+            Marketplace::transfer_signed(
+                &ctx.accounts.marketplace,
+                ctx.accounts.vault.to_account_info(),
+                destination,
+                ctx.accounts.token_program.to_account_info(),
+                bond,
+            )?;
+        }
+
+        emit!(CurationVoteSettledEvent {
+            epoch: tally.epoch,
+            listing: tally.listing,
+            curator: ctx.accounts.curator.key(),
+            bond: bond,
+            forfeited: tally.disputed,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Curation vote on {} settled: bond {} forfeited {}", tally.listing, bond, tally.disputed);
+        Ok(())
+    }
+
+    /// Authorize `oracle` to report whether listings' stored data still resolves and matches its hash
+    pub fn add_storage_oracle(ctx: Context<AddStorageOracle>, oracle: Pubkey) -> Result<()> {
+        let storage_oracle = &mut ctx.accounts.storage_oracle;
+        storage_oracle.oracle = oracle;
+        storage_oracle.check_count = 0;
+        storage_oracle.added_at = Clock::get()?.unix_timestamp;
+        storage_oracle.bump = ctx.bumps.storage_oracle;
+
+        msg!("Storage oracle added: {}", oracle);
+        Ok(())
+    }
+
+    /// Revoke a storage oracle; its past checks stand
+    pub fn remove_storage_oracle(ctx: Context<RemoveStorageOracle>) -> Result<()> {
+        msg!("Storage oracle removed: {}", ctx.accounts.storage_oracle.oracle);
+        Ok(())
+    }
+
+    /// Record a storage oracle's check of the data behind a listing. A failed check pauses the
+    /// listing's sales until a later check passes.
+    pub fn record_storage_check(ctx: Context<RecordStorageCheck>, healthy: bool) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+        let storage_health = &mut ctx.accounts.storage_health;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(listing.data_pointer().is_some(), ErrorCode::NoListingData);
+
+        storage_health.listing = ctx.accounts.listing.key();
+        storage_health.data_hash = listing.data_hash;
+        storage_health.last_checked_at = now;
+        storage_health.last_oracle = ctx.accounts.oracle.key();
+        storage_health.healthy = healthy;
+        storage_health.consecutive_failures = if healthy {
+            0
+        } else {
+            storage_health.consecutive_failures.saturating_add(1)
+        };
+        storage_health.check_count = storage_health.check_count.saturating_add(1);
+        storage_health.bump = ctx.bumps.storage_health;
+        listing.storage_paused = u8::from(!healthy);
+
+        let storage_oracle = &mut ctx.accounts.storage_oracle;
+        storage_oracle.check_count = storage_oracle.check_count.saturating_add(1);
+
+        emit!(StorageCheckedEvent {
+            listing: ctx.accounts.listing.key(),
+            oracle: storage_oracle.oracle,
+            data_hash: listing.data_hash,
+            healthy,
+            checked_at: now,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Listing {} storage check: healthy {}", listing.id, healthy);
+        Ok(())
+    }
+
+    /// Publish license terms listings can be sold under. Templates never change once created, so
+    /// the ID a receipt records always means the same terms; retire one with
+    /// `set_license_template_active` instead.
+    pub fn create_license_template(
+        ctx: Context<CreateLicenseTemplate>,
+        template_id: u32,
+        terms: LicenseTerms,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(terms.is_valid(), ErrorCode::InvalidLicenseTerms);
+
+        let license_template = &mut ctx.accounts.license_template;
+        license_template.template_id = template_id;
+        license_template.terms = terms.clone();
+        license_template.terms_hash = terms_hash;
+        license_template.is_active = true;
+        license_template.created_at = Clock::get()?.unix_timestamp;
+        license_template.bump = ctx.bumps.license_template;
+
+        emit!(LicenseTemplateCreatedEvent {
+            template_id,
+            terms,
+            terms_hash,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("License template {} created", template_id);
+        Ok(())
+    }
+
+    /// Retire a license template from new listings, or reinstate it; listings already under it
+    /// keep it
+    pub fn set_license_template_active(ctx: Context<UpdateLicenseTemplate>, is_active: bool) -> Result<()> {
+        let license_template = &mut ctx.accounts.license_template;
+        license_template.is_active = is_active;
+
+        msg!("License template {} active: {}", license_template.template_id, is_active);
+        Ok(())
+    }
+
+    /// Mint the buyer of a licensed purchase an NFT of the rights it bought: a Token-2022 mint of
+    /// supply one whose metadata names the license template, the scope (data type and purpose) and
+    /// the expiry. The NFT is non-transferable unless the listing's rights are transferable and
+    /// the template allows redistribution.
+    pub fn mint_license(ctx: Context<MintLicense>, _listing_id: u64) -> Result<()> {
+        let receipt = &ctx.accounts.receipt;
+        let terms = &ctx.accounts.license_template.terms;
+        require!(receipt.refunded_amount == 0, ErrorCode::RefundAlreadyClaimed);
+        let transferable = ctx.accounts.listing.load()?.rights_transferable() && terms.redistribution;
+
+        let marketplace = &ctx.accounts.marketplace;
+        let marketplace_key = marketplace.key();
+        let marketplace_seeds: &[&[u8]] = &[seeds::MARKETPLACE, &[marketplace.bump]];
+        let receipt_key = receipt.key();
+        let mint_seeds: &[&[u8]] = &[seeds::LICENSE_MINT, receipt_key.as_ref(), &[ctx.bumps.license_mint]];
+        let mint = ctx.accounts.license_mint.key();
+        let token_program = ctx.accounts.token_program.key();
+
+        let expires_at = terms.expires_at(receipt.purchased_at)?;
+        let metadata = TokenMetadata {
+            mint,
+            name: format!("DataSov License {}", receipt.listing_id),
+            symbol: LicenseTerms::NFT_SYMBOL.to_string(),
+            additional_metadata: vec![
+                ("template_id".to_string(), ctx.accounts.license_template.template_id.to_string()),
+                (
+                    "scope".to_string(),
+                    format!("{:?}/{:?}", ctx.accounts.listing.load()?.data_type(), receipt.purpose),
+                ),
+                (
+                    "expires_at".to_string(),
+                    expires_at.map_or_else(|| "never".to_string(), |expires_at| expires_at.to_string()),
+                ),
+            ],
+            ..Default::default()
+        };
+
+        // The metadata lives in the mint itself; the token program grows the account as it is
+        // written, so fund the full size up front
+        let mut extensions = vec![ExtensionType::MetadataPointer];
+        if !transferable {
+            extensions.push(ExtensionType::NonTransferable);
+        }
+        let space = ExtensionType::try_calculate_account_len::<MintState>(&extensions)?;
+        let lamports = Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?);
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.license_mint.to_account_info(),
+                },
+                &[mint_seeds],
+            ),
+            lamports,
+            space as u64,
+            &token_program,
+        )?;
+
+        let mint_accounts = [ctx.accounts.license_mint.to_account_info()];
+        invoke(
+            &metadata_pointer::instruction::initialize(&token_program, &mint, Some(marketplace_key), Some(mint))?,
+            &mint_accounts,
+        )?;
+        if !transferable {
+            invoke(
+                &spl_token_2022::instruction::initialize_non_transferable_mint(&token_program, &mint)?,
+                &mint_accounts,
+            )?;
+        }
+        invoke(
+            &spl_token_2022::instruction::initialize_mint2(&token_program, &mint, &marketplace_key, None, 0)?,
+            &mint_accounts,
+        )?;
+
+        let metadata_accounts = [
+            ctx.accounts.license_mint.to_account_info(),
+            marketplace.to_account_info(),
+        ];
+        invoke_signed(
+            &spl_token_metadata_interface::instruction::initialize(
+                &token_program,
+                &mint,
+                &marketplace_key,
+                &mint,
+                &marketplace_key,
+                metadata.name.clone(),
+                metadata.symbol.clone(),
+                metadata.uri.clone(),
+            ),
+            &metadata_accounts,
+            &[marketplace_seeds],
+        )?;
+        for (key, value) in &metadata.additional_metadata {
+            invoke_signed(
+                &spl_token_metadata_interface::instruction::update_field(
+                    &token_program,
+                    &mint,
+                    &marketplace_key,
+                    Field::Key(key.clone()),
+                    value.clone(),
+                ),
+                &metadata_accounts,
+                &[marketplace_seeds],
+            )?;
+        }
+
+        anchor_spl::associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            anchor_spl::associated_token::Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: ctx.accounts.buyer_token_account.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+                mint: ctx.accounts.license_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
+        // One token, then no mint authority, so the supply stays at one
+        let mint_to_accounts = [
+            ctx.accounts.license_mint.to_account_info(),
+            ctx.accounts.buyer_token_account.to_account_info(),
+            marketplace.to_account_info(),
+        ];
+        invoke_signed(
+            &spl_token_2022::instruction::mint_to(
+                &token_program,
+                &mint,
+                &ctx.accounts.buyer_token_account.key(),
+                &marketplace_key,
+                &[],
+                1,
+            )?,
+            &mint_to_accounts,
+            &[marketplace_seeds],
+        )?;
+        invoke_signed(
+            &spl_token_2022::instruction::set_authority(
+                &token_program,
+                &mint,
+                None,
+                AuthorityType::MintTokens,
+                &marketplace_key,
+                &[],
+            )?,
+            &metadata_accounts,
+            &[marketplace_seeds],
+        )?;
+
+        emit!(LicenseMintedEvent {
+            receipt: receipt_key,
+            mint,
+            buyer: receipt.buyer,
+            template_id: ctx.accounts.license_template.template_id,
+            transferable,
+            expires_at,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("License minted for listing {}", receipt.listing_id);
+        Ok(())
+    }
+
+    /// Hand the access right bought under a receipt to another verified identity, whose owner
+    /// must hold the seller's permission for the listing's data type and the purchase's purpose.
+    /// The listing must allow it, as must the license template the purchase was sold under.
+    pub fn transfer_access_right(ctx: Context<TransferAccessRight>, _listing_id: u64) -> Result<()> {
+        let mut listing = ctx.accounts.listing.load_mut()?;
+        let receipt = &mut ctx.accounts.receipt;
+
+        require!(listing.rights_transferable(), ErrorCode::RightsNotTransferable);
+        if let Some(template_id) = receipt.license_template {
+            let license_template = ctx
+                .accounts
+                .license_template
+                .as_ref()
+                .filter(|license_template| license_template.template_id == template_id)
+                .ok_or(ErrorCode::UnlicensedReceipt)?;
+            require!(license_template.terms.redistribution, ErrorCode::RightsNotTransferable);
+        }
+        require!(!receipt.is_revoked(&ctx.accounts.permission), ErrorCode::ReceiptAccessRevoked);
+        require!(receipt.refunded_amount == 0, ErrorCode::RefundAlreadyClaimed);
+
+        let recipient_identity = &ctx.accounts.recipient_identity;
+        let recipient_permission = &ctx.accounts.recipient_permission;
+        let recipient = recipient_identity.owner;
+        require!(recipient != receipt.buyer, ErrorCode::RecipientAlreadyHolds);
+        require!(recipient_identity.status == IdentityStatus::Verified, ErrorCode::BuyerNotVerified);
+        require!(!ctx.accounts.blacklist.contains(&recipient), ErrorCode::BuyerBlacklisted);
+
+        let required_data_type = listing.data_type().to_identity_data_type();
+        require!(
+            ctx.accounts
+                .compliance_matrix
+                .buyer_allowed(&required_data_type, &recipient_identity.verification_level),
+            ErrorCode::BuyerLevelTooLow
+        );
+        require!(recipient_permission.is_active, ErrorCode::NoAccessPermission);
+        require!(
+            recipient_permission.data_types.contains(&required_data_type),
+            ErrorCode::DataTypeNotAuthorized
+        );
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = recipient_permission.expires_at {
+            require!(now < expires_at, ErrorCode::PermissionExpired);
+        }
+        require!(recipient_permission.allows_purpose(&receipt.purpose), ErrorCode::PurposeNotPermitted);
+
+        let previous_holder = receipt.buyer;
+        receipt.buyer = recipient;
+        receipt.permission = recipient_permission.key();
+        receipt.permission_epoch = recipient_permission.revocation_epoch;
+        listing.set_buyer(Some(recipient));
+
+        emit!(AccessRightTransferredEvent {
+            receipt: receipt.key(),
+            listing: receipt.listing,
+            from: previous_holder,
+            to: recipient,
+            transferred_at: now,
+            event_sequence: ctx.accounts.marketplace.next_event_sequence(),
+            event_slot: Clock::get()?.slot,
+        });
+
+        msg!("Access right to listing {} transferred", listing.id);
+        Ok(())
+    }
+}
+
+/// Layout version of a legacy `T` account: the index of its allocated size in `legacy_spaces`
+fn legacy_version<T: anchor_lang::Discriminator>(account: &AccountInfo, legacy_spaces: &[usize]) -> Result<usize> {
+    let data = account.try_borrow_data()?;
+    require!(data.len() >= 8 && data[..8] == T::DISCRIMINATOR, ErrorCode::NotLegacyAccount);
+    legacy_spaces
+        .iter()
+        .position(|space| *space == data.len())
+        .ok_or_else(|| error!(ErrorCode::NotLegacyAccount))
+}
+
+/// Read the next field of a legacy account
+fn read_field<T: AnchorDeserialize>(data: &mut &[u8]) -> Result<T> {
+    T::deserialize(data).map_err(|_| error!(ErrorCode::NotLegacyAccount))
+}
+
+/// Settle a purchase paid by `buyer` whose access, receipt, and profile credit go to `recipient`
+fn process_purchase(
+    ctx: Context<PurchaseData>,
+    listing_id: u64,
+    purpose: Purpose,
+    price_reveal: Option<PriceReveal>,
+    coupon_code: Option<String>,
+    max_staleness: Option<i64>,
+    recipient: Pubkey,
+) -> Result<()> {
+    require!(ctx.accounts.buyer_identity.owner == recipient, ErrorCode::RecipientMismatch);
+    validate_purchase(
+        &ctx.accounts.marketplace,
+        &ctx.accounts.compliance_matrix,
+        &ctx.accounts.blacklist,
+        ctx.accounts.buyer_stake.as_deref(),
+        &ctx.accounts.buyer_profile,
+        &*ctx.accounts.listing.load()?,
+        listing_id,
+        &ctx.accounts.seller_identity,
+        &ctx.accounts.buyer_identity,
+        &ctx.accounts.buyer_permission,
+        ctx.accounts.allowlist.as_deref(),
+        recipient,
+        &purpose,
+    )?;
+    ctx.accounts.listing.load()?.check_freshness(max_staleness, Clock::get()?.unix_timestamp)?;
+    ctx.accounts.marketplace.check_settlement_mint(&ctx.accounts.buyer_token_account.mint)?;
+    let mut purchase_amount = ctx.accounts.listing.load()?.settlement_price(price_reveal)?;
+
+    // Apply a seller coupon when one is supplied
+    if let Some(coupon) = ctx.accounts.coupon.as_mut() {
+        let code = coupon_code.ok_or(ErrorCode::InvalidCouponCode)?;
+        purchase_amount = coupon.redeem(&code, &ctx.accounts.listing, purchase_amount)?;
+    }
+
+    // Stakers above a fee tier pay a discounted marketplace fee
+    let discount_bps = match (&ctx.accounts.buyer_weight, &ctx.accounts.staking_config) {
+        (Some(buyer_weight), Some(staking_config)) => staking_config.fee_discount_bps(buyer_weight.weight),
+        _ => 0,
+    };
+    let (owner_amount, fee_amount) = ctx
+        .accounts
+        .marketplace
+        .split_payment_with_discount(purchase_amount, discount_bps)?;
+    purchase_amount = owner_amount + fee_amount;
+
+    // Delegated purchases draw on the consumer's approval and must fit the delegate's limits
+    if let Some(purchase_delegate) = ctx.accounts.purchase_delegate.as_mut() {
+        purchase_delegate.spend(purchase_amount)?;
+    }
+
+    // Buyers and sellers who opened a ledger for the current rebate epoch earn credits for the fee.
+    // One fee earns one side's credits when the buyer is also the seller.
+    let self_trade = ctx.accounts.buyer_identity.key() == ctx.accounts.seller_identity.key()
+        || ctx.accounts.buyer_identity.owner == ctx.accounts.listing.load()?.payout_wallet;
+    require!(
+        !self_trade || ctx.accounts.seller_rebate.is_none(),
+        ErrorCode::SelfTradeRebate
+    );
+    if let Some(rebate_epoch) = ctx.accounts.rebate_epoch.as_mut() {
+        require!(
+            rebate_epoch.is_current(Clock::get()?.unix_timestamp),
+            ErrorCode::RebateEpochNotCurrent
+        );
+        let ledgers = [ctx.accounts.buyer_rebate.as_mut(), ctx.accounts.seller_rebate.as_mut()];
+        for ledger in ledgers.into_iter().flatten() {
+            require!(ledger.epoch == rebate_epoch.epoch, ErrorCode::RebateEpochNotCurrent);
+            ledger.fee_credits = ledger
+                .fee_credits
+                .checked_add(fee_amount)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            rebate_epoch.fee_credits = rebate_epoch
+                .fee_credits
+                .checked_add(fee_amount)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+    }
+
+    // Union listings pay the union vault directly and credit members in scope
+    if let Some(union_key) = ctx.accounts.listing.load()?.union() {
+        let data_union = ctx.accounts.data_union.as_mut().ok_or(ErrorCode::DataUnionMismatch)?;
+        require!(data_union.key() == union_key, ErrorCode::DataUnionMismatch);
+        require!(
+            ctx.accounts.owner_token_account.key() == data_union.vault,
+            ErrorCode::DataUnionMismatch
+        );
+        data_union.credit(&ctx.accounts.listing.load()?.data_type(), owner_amount)?;
+    }
+
+    // Transfer payment to owner, or into escrow while the refund window is open
+    let refund_window = if ctx.accounts.listing.load()?.union().is_some() {
+        0
+    } else {
+        ctx.accounts.marketplace.refund_window
+    };
+    require!(
+        (refund_window > 0) == ctx.accounts.escrow_token_account.is_some(),
+        ErrorCode::EscrowAccountMismatch
+    );
+    let (owner_destination, escrowed_amount) = match &ctx.accounts.escrow_token_account {
+        Some(escrow_token_account) => (escrow_token_account.to_account_info(), owner_amount),
+        None => (ctx.accounts.owner_token_account.to_account_info(), 0),
+    };
+    ctx.accounts.pay(owner_destination, owner_amount)?;
+
+    // Transfer fee to marketplace
+    if fee_amount > 0 {
+        let marketplace_token_account = ctx.accounts.marketplace_token_account.to_account_info();
+        ctx.accounts.pay(marketplace_token_account, fee_amount)?;
+    }
+
+    // Update listing and marketplace
+    let mut listing = ctx.accounts.listing.load_mut()?;
+    let marketplace = &mut ctx.accounts.marketplace;
+    let receipt = &mut ctx.accounts.receipt;
+    let now = Clock::get()?.unix_timestamp;
+
+    listing.is_active = u8::from(false);
+    listing.set_buyer(Some(recipient));
+    listing.set_sold_at(Some(now));
+
+    marketplace.total_volume += purchase_amount;
+    ctx.accounts.price_candle.record(
+        ctx.accounts.buyer_token_account.mint,
+        &listing.data_type(),
+        now,
+        purchase_amount,
+        ctx.bumps.price_candle,
+    )?;
+    // Union listings pay the union's members rather than the listing identity, and escrowed
+    // proceeds are credited as they are released
+    if listing.union().is_none() && escrowed_amount == 0 {
+        ctx.accounts.earnings_ledger.record(
+            ctx.accounts.seller_identity.key(),
+            &listing.data_type(),
+            recipient,
+            owner_amount,
+            now,
+            ctx.bumps.earnings_ledger,
+        )?;
+    }
+
+    receipt.listing = ctx.accounts.listing.key();
+    receipt.listing_id = listing_id;
+    receipt.buyer = recipient;
+    receipt.payer = ctx.accounts.buyer_token_account.owner;
+    receipt.seller = listing.owner;
+    receipt.amount = purchase_amount;
+    receipt.is_confidential = false;
+    receipt.amount_commitment = [0u8; 32];
+    receipt.permission = ctx.accounts.buyer_permission.key();
+    receipt.permission_epoch = ctx.accounts.buyer_permission.revocation_epoch;
+    receipt.escrowed_amount = escrowed_amount;
+    receipt.released_amount = 0;
+    receipt.refunded_amount = 0;
+    receipt.refund_window = refund_window;
+    receipt.purpose = purpose;
+    receipt.watermark_commitment = PurchaseReceipt::watermark(&receipt.key(), &recipient, now);
+    receipt.license_template = listing.license_template();
+    receipt.purchased_at = now;
+    receipt.rent_payer = ctx.accounts.buyer.key();
+    receipt.bump = ctx.bumps.receipt;
+    drop(listing);
+
+    emit!(PurchaseWatermarkEvent {
+        receipt: receipt.key(),
+        listing: receipt.listing,
+        buyer: recipient,
+        watermark_commitment: receipt.watermark_commitment,
+        event_sequence: marketplace.next_event_sequence(),
+        event_slot: Clock::get()?.slot,
+    });
+
+    ctx.accounts.record_buyer_purchase()?;
+    ctx.accounts.audit.record(
+        ctx.bumps.audit.audit_writer,
+        ctx.accounts.buyer.to_account_info(),
+        ctx.accounts.seller_identity.key(),
+        AuditAction::Purchase,
+        recipient,
+        ctx.accounts.receipt.key(),
+        purchase_amount,
+    )?;
+
+    // Compact on the hot path: the watermark event and audit entry carry the receipt and amount
+    msg!("Data purchased");
+    Ok(())
+}
+
+/// Checks shared by every purchase path: listing state, both identities, and the buyer's permission
+fn validate_purchase(
+    marketplace: &Marketplace,
+    compliance_matrix: &ComplianceMatrix,
+    blacklist: &Blacklist,
+    buyer_stake: Option<&ConsumerStake>,
+    buyer_profile: &ConsumerProfile,
+    listing: &DataListing,
+    listing_id: u64,
+    seller_identity: &IdentityAccount,
+    buyer_identity: &IdentityAccount,
+    buyer_permission: &AccessPermission,
+    allowlist: Option<&ListingAllowlist>,
+    buyer: Pubkey,
+    purpose: &Purpose,
+) -> Result<()> {
+    require!(listing.is_active(), ErrorCode::ListingNotActive);
+    require!(listing.id == listing_id, ErrorCode::InvalidListingId);
+    require!(!listing.storage_paused(), ErrorCode::ListingStoragePaused);
+    if let Some(expires_at) = listing.expires_at() {
+        require!(Clock::get()?.unix_timestamp < expires_at, ErrorCode::ListingExpired);
+    }
+    require!(!marketplace.is_halted(&listing.data_type()), ErrorCode::DataTypeHalted);
+
+    // Validate seller identity
+    require!(seller_identity.status == IdentityStatus::Verified, ErrorCode::SellerNotVerified);
+    require!(seller_identity.owner == listing.owner, ErrorCode::IdentityMismatch);
+
+    // Validate buyer identity
+    require!(buyer_identity.status == IdentityStatus::Verified, ErrorCode::BuyerNotVerified);
+    require!(buyer_identity.owner == buyer, ErrorCode::IdentityMismatch);
+
+    // Validate buyer access permission
+    require!(buyer_permission.is_active, ErrorCode::NoAccessPermission);
+    require!(!blacklist.contains(&buyer), ErrorCode::BuyerBlacklisted);
+
+    let required_data_type = listing.data_type().to_identity_data_type();
+
+    // Enforce the minimum verification levels configured for this data type
+    require!(
+        compliance_matrix.seller_allowed(&required_data_type, &seller_identity.verification_level),
+        ErrorCode::SellerLevelTooLow
+    );
+    require!(
+        compliance_matrix.buyer_allowed(&required_data_type, &buyer_identity.verification_level),
+        ErrorCode::BuyerLevelTooLow
+    );
+    require!(
+        ConsumerStake::covers(
+            buyer_stake,
+            compliance_matrix.required_consumer_stake(&[required_data_type.clone()])
+        ),
+        ErrorCode::InsufficientConsumerStake
+    );
+
+    require!(
+        buyer_permission.data_types.contains(&required_data_type),
+        ErrorCode::DataTypeNotAuthorized
+    );
+
+    // Check permission expiration
+    if let Some(expires_at) = buyer_permission.expires_at {
+        require!(Clock::get()?.unix_timestamp < expires_at, ErrorCode::PermissionExpired);
+    }
+
+    // The declared purpose must be allowed by both the identity owner and the seller
+    require!(buyer_permission.allows_purpose(purpose), ErrorCode::PurposeNotPermitted);
+    require!(listing.allows_purpose(purpose), ErrorCode::PurposeNotAllowedByListing);
+
+    // Listings may be restricted to business-verified (KYB) buyers
+    if listing.requires_kyb() {
+        require!(buyer_profile.is_business_verified(), ErrorCode::BuyerNotBusinessVerified);
+    }
+
+    // Private listings are only sold to allowlisted buyers
+    if listing.is_private() {
+        let allowlist = allowlist.ok_or(ErrorCode::BuyerNotAllowlisted)?;
+        require!(allowlist.buyers.contains(&buyer), ErrorCode::BuyerNotAllowlisted);
+    }
+
+    Ok(())
+}
+
+/// Pedersen generators Token-2022 confidential transfers commit to amounts with
+pub mod pedersen {
+    /// The compressed Ristretto basepoint; carries the amount
+    pub const G: [u8; 32] = [
+        226, 242, 174, 10, 106, 188, 78, 113, 168, 132, 169, 97, 197, 0, 81, 95, 88, 227, 11, 106, 165, 130, 221, 141,
+        182, 166, 89, 69, 224, 141, 45, 118,
+    ];
+    /// `G` hashed to the curve with SHA3-512; carries the opening
+    pub const H: [u8; 32] = [
+        140, 146, 64, 180, 86, 169, 230, 220, 101, 195, 119, 161, 4, 141, 116, 95, 148, 160, 140, 219, 127, 68, 203, 205,
+        123, 70, 243, 64, 72, 135, 17, 52,
+    ];
+}
+
+/// Bits each fee remainder range proof in a confidential purchase covers; 2^14 is the smallest
+/// power of two above 10_000
+const FEE_REMAINDER_BITS: u8 = 14;
+
+fn scalar(value: u64) -> PodScalar {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&value.to_le_bytes());
+    PodScalar(bytes)
+}
+
+/// Read a proof context the ZK Token proof program verified into a context state account
+fn proof_context<T: bytemuck::Pod>(account: &AccountInfo, proof_type: ProofType) -> Result<T> {
+    require_keys_eq!(*account.owner, zk_token_proof_program::id(), ErrorCode::InvalidProofContext);
+    let data = account.try_borrow_data()?;
+    let state = ProofContextState::<T>::try_from_bytes(&data).map_err(|_| ErrorCode::InvalidProofContext)?;
+    require!(
+        ProofType::try_from(state.proof_type) == Ok(proof_type),
+        ErrorCode::InvalidProofContext
+    );
+    Ok(state.proof_context)
+}
+
+/// A confidential transfer's whole amount encrypted under the source key, recombined from the
+/// low 16 and high bits the transfer proof splits it into
+fn transfer_source_ciphertext(context: &TransferProofContext) -> Result<ElGamalCiphertext> {
+    let lo = transfer_amount_source_ciphertext(&context.ciphertext_lo);
+    let hi = transfer_amount_source_ciphertext(&context.ciphertext_hi);
+    ops::multiply(&scalar(1 << 16), &hi)
+        .and_then(|hi| ops::add(&lo, &hi))
+        .ok_or_else(|| error!(ErrorCode::InvalidProofContext))
+}
+
+#[derive(Accounts)]
+#[instruction(marketplace_fee_basis_points: u16)]
+pub struct InitializeMarketplace<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Marketplace::INIT_SPACE,
+        seeds = [seeds::MARKETPLACE],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64, _price: u64, _data_type: DataType, _description: String, identity_id: String)]
+pub struct CreateDataListing<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DataListing::INIT_SPACE,
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&identity_id)],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [identity_seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [identity_seeds::TRUST, data_trust.creator.as_ref(), data_trust.trust_id.to_le_bytes().as_ref()],
+        bump = data_trust.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub data_trust: Option<Account<'info, DataTrust>>,
+
+    #[account(
+        seeds = [identity_seeds::TRUST_MEMBER, trust_membership.data_trust.as_ref(), seller_identity.key().as_ref()],
+        bump = trust_membership.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub trust_membership: Option<Account<'info, TrustMembership>>,
+
+    /// Identity owner, or a trustee when listing through a data trust
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Co-signs listings for a ward identity until emancipation
+    pub guardian: Option<Signer<'info>>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Wallet whose token accounts receive the proceeds; defaults to the identity owner, who alone may set it
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> CreateDataListing<'info> {
+    /// Checks and writes shared by owner and trustee listings; proceeds go to the identity owner
+    /// unless they chose another payout wallet
+    fn create_listing(
+        &mut self,
+        bump: u8,
+        listing_id: u64,
+        price: u64,
+        data_type: DataType,
+        description: String,
+        identity_id: String,
+    ) -> Result<()> {
+        let marketplace = &mut self.marketplace;
+        let seller_identity = &self.seller_identity;
+
+        // Validate seller identity
+        require!(seller_identity.status == IdentityStatus::Verified, ErrorCode::SellerNotVerified);
+        require!(
+            seller_identity.guardian_approved(self.guardian.as_ref().map(|g| g.key()), Clock::get()?.unix_timestamp),
+            ErrorCode::GuardianSignatureRequired
+        );
+        require!(!marketplace.is_halted(&data_type), ErrorCode::DataTypeHalted);
+        require!(
+            self.compliance_matrix.seller_allowed(
+                &data_type.to_identity_data_type(),
+                &seller_identity.verification_level
+            ),
+            ErrorCode::SellerLevelTooLow
+        );
+        require!(data_type.fits(), ErrorCode::CustomDataTypeTooLong);
+        require!(description.len() <= 200, ErrorCode::DescriptionTooLong);
+        require!(
+            self.payout_wallet.is_none() || self.owner.key() == seller_identity.owner,
+            ErrorCode::Unauthorized
+        );
+
+        // The listing starts zeroed, so everything not set here is unset, false or empty
+        let mut listing = self.listing.load_init()?;
+        listing.id = listing_id;
+        listing.owner = seller_identity.owner;
+        listing.price = price;
+        listing.set_data_type(&data_type)?;
+        listing.set_description(&description)?;
+        listing.set_identity_id(&identity_id)?;
+        listing.is_active = u8::from(true);
+        listing.created_at = Clock::get()?.unix_timestamp;
+        listing.last_refreshed_at = listing.created_at;
+        listing.allowed_purposes = Purpose::ALL;
+        listing.payout_wallet = self.payout_wallet.as_ref().map_or(seller_identity.owner, |wallet| wallet.key());
+        listing.rent_payer = self.owner.key();
+        listing.bump = bump;
+
+        marketplace.total_listings += 1;
+
+        msg!("Data listing created with ID: {} and price: {} lamports", listing_id, price);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64, _price_commitment: [u8; 32], _description_hash: [u8; 32], _data_type: DataType, identity_id: String)]
+pub struct CreatePrivateListing<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DataListing::INIT_SPACE,
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ListingAllowlist::INIT_SPACE,
+        seeds = [seeds::ALLOWLIST, listing.key().as_ref()],
+        bump
+    )]
+    pub allowlist: Account<'info, ListingAllowlist>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&identity_id)],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [identity_seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Co-signs listings for a ward identity until emancipation
+    pub guardian: Option<Signer<'info>>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListingAllowlist<'info> {
+    #[account(
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ALLOWLIST, listing.key().as_ref()],
+        bump = allowlist.bump
+    )]
+    pub allowlist: Account<'info, ListingAllowlist>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct PurchaseData<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(listing.load()?.identity_id())],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&buyer_identity.identity_id)],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [
+            identity_seeds::PERMISSION,
+            seller_identity.key().as_ref(),
+            buyer_identity.owner.as_ref()
+        ],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [identity_seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [identity_seeds::BLACKLIST],
+        bump = blacklist.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        mut,
+        seeds = [identity_seeds::CONSUMER, buyer_identity.owner.as_ref()],
+        bump = buyer_profile.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        seeds = [identity_seeds::CONSUMER_STAKE, buyer_identity.owner.as_ref()],
+        bump = buyer_stake.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_stake: Option<Account<'info, ConsumerStake>>,
+
+    #[account(
+        seeds = [seeds::ALLOWLIST, listing.key().as_ref()],
+        bump = allowlist.bump
+    )]
+    pub allowlist: Option<Account<'info, ListingAllowlist>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::COUPON, listing.load()?.owner.as_ref(), coupon.code_hash.as_ref()],
+        bump = coupon.bump
+    )]
+    pub coupon: Option<Account<'info, Coupon>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::PURCHASE_DELEGATE, buyer_identity.owner.as_ref(), buyer.key().as_ref()],
+        bump = purchase_delegate.bump,
+        constraint = purchase_delegate.token_account == buyer_token_account.key() @ ErrorCode::Unauthorized
+    )]
+    pub purchase_delegate: Option<Account<'info, PurchaseDelegate>>,
+
+    /// Signs and funds rent for the purchase; pays tokens unless acting as a purchase delegate.
+    /// Access goes to `buyer_identity.owner` (the buyer, a gift recipient, or the delegating consumer)
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// The buyer's own, or under a purchase delegate the delegating consumer's
+    #[account(
+        mut,
+        constraint = purchase_delegate.is_some() || buyer_token_account.owner == buyer.key() @ ErrorCode::Unauthorized
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// The listing's payout wallet's, in the settlement mint; a union listing's vault instead
+    #[account(
+        mut,
+        token::mint = buyer_token_account.mint,
+        constraint = listing.load()?.union().is_some() || owner_token_account.owner == listing.load()?.payout_wallet @ ErrorCode::InvalidPayoutAccount
+    )]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = buyer_token_account.mint,
+        associated_token::authority = marketplace
+    )]
+    pub marketplace_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::UNION, data_union.operator.as_ref(), data_union.union_id.to_le_bytes().as_ref()],
+        bump = data_union.bump
+    )]
+    pub data_union: Option<Box<Account<'info, DataUnion>>>,
+
+    #[account(
+        address = buyer_token_account.mint
+    )]
+    pub settlement_mint: Option<Account<'info, token::Mint>>,
+
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [seeds::ESCROW, listing.key().as_ref()],
+        bump,
+        token::mint = settlement_mint,
+        token::authority = marketplace
+    )]
+    pub escrow_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + PurchaseReceipt::INIT_SPACE,
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    /// The recipient's staking weight, for a fee-tier discount
+    #[account(
+        seeds = [datasov_staking::seeds::STAKE_WEIGHT, buyer_identity.owner.as_ref()],
+        bump = buyer_weight.bump,
+        seeds::program = datasov_staking::ID
+    )]
+    pub buyer_weight: Option<Box<Account<'info, StakeWeight>>>,
+
+    /// Required with `buyer_weight`; holds the fee tiers
+    #[account(
+        seeds = [datasov_staking::seeds::CONFIG],
+        bump = staking_config.bump,
+        seeds::program = datasov_staking::ID
+    )]
+    pub staking_config: Option<Box<Account<'info, StakingConfig>>>,
+
+    /// The current rebate epoch, when either side has a ledger open for it
+    #[account(
+        mut,
+        seeds = [seeds::REBATE_EPOCH, rebate_epoch.epoch.to_le_bytes().as_ref()],
+        bump = rebate_epoch.bump
+    )]
+    pub rebate_epoch: Option<Box<Account<'info, RebateEpoch>>>,
+
+    /// The recipient's ledger for `rebate_epoch`
+    #[account(
+        mut,
+        seeds = [seeds::REBATE_LEDGER, buyer_rebate.epoch.to_le_bytes().as_ref(), buyer_identity.owner.as_ref()],
+        bump = buyer_rebate.bump
+    )]
+    pub buyer_rebate: Option<Box<Account<'info, RebateLedger>>>,
+
+    /// The listing payout wallet's ledger for `rebate_epoch`
+    /// Left out on a self-trade, where only the buyer earns credits
+    #[account(
+        mut,
+        seeds = [seeds::REBATE_LEDGER, seller_rebate.epoch.to_le_bytes().as_ref(), listing.load()?.payout_wallet.as_ref()],
+        bump = seller_rebate.bump
+    )]
+    pub seller_rebate: Option<Box<Account<'info, RebateLedger>>>,
+
+    /// Today's price candle for the listing's data type in the settlement mint
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + PriceCandle::INIT_SPACE,
+        seeds = [
+            seeds::PRICE_CANDLE,
+            buyer_token_account.mint.as_ref(),
+            &[listing.load()?.data_type],
+            PriceCandle::day_of(Clock::get()?.unix_timestamp).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub price_candle: Box<Account<'info, PriceCandle>>,
+
+    /// Lifetime earnings of the listing identity
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + EarningsLedger::INIT_SPACE,
+        seeds = [seeds::EARNINGS_LEDGER, seller_identity.key().as_ref()],
+        bump
+    )]
+    pub earnings_ledger: Box<Account<'info, EarningsLedger>>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    pub audit: AuditAccounts<'info>,
+}
+
+impl<'info> PurchaseData<'info> {
+    /// Transfer from the buyer's token account, signing as the purchase delegate PDA when one is used
+    fn pay(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+        match &self.purchase_delegate {
+            Some(purchase_delegate) => {
+                let seeds = &[
+                    seeds::PURCHASE_DELEGATE.as_ref(),
+                    purchase_delegate.consumer.as_ref(),
+                    purchase_delegate.delegate.as_ref(),
+                    &[purchase_delegate.bump],
+                ];
+                let signer = &[&seeds[..]];
+                let cpi_accounts = Transfer {
+                    from: self.buyer_token_account.to_account_info(),
+                    to,
+                    authority: purchase_delegate.to_account_info(),
+                };
+                token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), amount)
+            }
+            None => {
+                let cpi_accounts = Transfer {
+                    from: self.buyer_token_account.to_account_info(),
+                    to,
+                    authority: self.buyer.to_account_info(),
+                };
+                token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)
+            }
+        }
+    }
+
+    /// Count the purchase on the buyer's consumer profile via CPI signed by the marketplace PDA
+    fn record_buyer_purchase(&self) -> Result<()> {
+        let seeds = &[seeds::MARKETPLACE.as_ref(), &[self.marketplace.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.identity_program.to_account_info(),
+            datasov_identity::cpi::accounts::RecordConsumerPurchase {
+                consumer_profile: self.buyer_profile.to_account_info(),
+                marketplace: self.marketplace.to_account_info(),
+            },
+            signer,
+        );
+        datasov_identity::cpi::record_consumer_purchase(cpi_ctx)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct PurchaseDataConfidential<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(listing.load()?.identity_id())],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&buyer_identity.identity_id)],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [
+            identity_seeds::PERMISSION,
+            seller_identity.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [identity_seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [identity_seeds::BLACKLIST],
+        bump = blacklist.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        mut,
+        seeds = [identity_seeds::CONSUMER, buyer.key().as_ref()],
+        bump = buyer_profile.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        seeds = [identity_seeds::CONSUMER_STAKE, buyer.key().as_ref()],
+        bump = buyer_stake.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_stake: Option<Account<'info, ConsumerStake>>,
+
+    #[account(
+        seeds = [seeds::ALLOWLIST, listing.key().as_ref()],
+        bump = allowlist.bump
+    )]
+    pub allowlist: Option<Account<'info, ListingAllowlist>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == mint.key(),
+        constraint = buyer_token_account.owner == buyer.key()
+    )]
+    pub buyer_token_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == mint.key(),
+        constraint = owner_token_account.owner == listing.load()?.payout_wallet @ ErrorCode::InvalidPayoutAccount
+    )]
+    pub owner_token_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// Confidential fees accrue in the marketplace's Token-2022 associated token account, which
+    /// `apply_confidential_fees` and `withdraw_confidential_fees` manage
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = marketplace,
+        associated_token::token_program = token_program
+    )]
+    pub marketplace_token_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// CHECK: Context state account holding the verified transfer proof for the owner payment; validated by the token program
+    pub owner_transfer_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Context state account holding the verified transfer proof for the fee; validated by the token program
+    pub fee_transfer_proof: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Context state account holding a verified ciphertext-commitment equality proof that the
+    /// transfers add up to the listing's price commitment; validated in `check_payment_proofs`
+    pub price_equality_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Context state account holding a verified ciphertext-commitment equality proof that
+    /// commits to the fee transfer's amount; validated in `check_payment_proofs`
+    pub fee_equality_proof: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Context state account holding a verified batched range proof that the committed fee
+    /// is the marketplace's cut of the price; validated in `check_payment_proofs`
+    pub fee_range_proof: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + PurchaseReceipt::INIT_SPACE,
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+
+    pub audit: AuditAccounts<'info>,
+}
+
+impl<'info> PurchaseDataConfidential<'info> {
+    /// Check that the transfer proofs move exactly the listing's committed price from one source
+    /// key, and that the fee transfer is `price * fee_basis_points / 10_000` rounded down
+    fn check_payment_proofs(&self) -> Result<()> {
+        let owner_transfer = proof_context::<TransferProofContext>(&self.owner_transfer_proof, ProofType::Transfer)?;
+        let source = owner_transfer.transfer_pubkeys.source;
+        let mut paid = transfer_source_ciphertext(&owner_transfer)?;
+
+        let fee_basis_points = self.marketplace.fee_basis_points;
+        if fee_basis_points > 0 {
+            let fee_transfer_proof = self.fee_transfer_proof.as_ref().ok_or(ErrorCode::FeeProofRequired)?;
+            let fee_equality_proof = self.fee_equality_proof.as_ref().ok_or(ErrorCode::FeeProofRequired)?;
+            let fee_range_proof = self.fee_range_proof.as_ref().ok_or(ErrorCode::FeeProofRequired)?;
+            let fee_transfer = proof_context::<TransferProofContext>(fee_transfer_proof, ProofType::Transfer)?;
+            require!(fee_transfer.transfer_pubkeys.source.0 == source.0, ErrorCode::ConfidentialAmountMismatch);
+            let fee = transfer_source_ciphertext(&fee_transfer)?;
+
+            // The transfer's own commitment has an opening only the token program's proof knew, so
+            // the buyer recommits to the fee under one they hold
+            let fee_equality = proof_context::<CiphertextCommitmentEqualityProofContext>(
+                fee_equality_proof,
+                ProofType::CiphertextCommitmentEquality,
+            )?;
+            require!(
+                fee_equality.pubkey.0 == source.0 && fee_equality.ciphertext.0 == fee.0,
+                ErrorCode::ConfidentialAmountMismatch
+            );
+
+            // remainder = bps * price - 10_000 * fee lies in [0, 10_000) exactly when the fee is the
+            // rounded-down cut; two 14-bit range proofs bound it from both ends
+            let price = PodRistrettoPoint(self.listing.load()?.price_commitment);
+            let fee_commitment = PodRistrettoPoint(fee_equality.commitment.0);
+            let remainder = subtract_ristretto(
+                &multiply_ristretto(&scalar(fee_basis_points as u64), &price).ok_or(ErrorCode::InvalidProofContext)?,
+                &multiply_ristretto(&scalar(10000), &fee_commitment).ok_or(ErrorCode::InvalidProofContext)?,
+            )
+            .ok_or(ErrorCode::InvalidProofContext)?;
+            let headroom = multiply_ristretto(&scalar((1 << FEE_REMAINDER_BITS) - 10000), &PodRistrettoPoint(pedersen::G))
+                .and_then(|offset| add_ristretto(&remainder, &offset))
+                .ok_or(ErrorCode::InvalidProofContext)?;
+            let range = proof_context::<BatchedRangeProofContext>(fee_range_proof, ProofType::BatchedRangeProofU64)?;
+            require!(
+                range.commitments[0].0 == remainder.0
+                    && range.bit_lengths[0] == FEE_REMAINDER_BITS
+                    && range.commitments[1].0 == headroom.0
+                    && range.bit_lengths[1] == FEE_REMAINDER_BITS,
+                ErrorCode::ConfidentialAmountMismatch
+            );
+
+            paid = ops::add(&paid, &fee).ok_or(ErrorCode::InvalidProofContext)?;
+        }
+
+        let equality = proof_context::<CiphertextCommitmentEqualityProofContext>(
+            &self.price_equality_proof,
+            ProofType::CiphertextCommitmentEquality,
+        )?;
+        require!(
+            equality.pubkey.0 == source.0
+                && equality.ciphertext.0 == paid.0
+                && equality.commitment.0 == self.listing.load()?.price_commitment,
+            ErrorCode::ConfidentialAmountMismatch
+        );
+        Ok(())
+    }
+
+    /// Count the purchase on the buyer's consumer profile via CPI signed by the marketplace PDA
+    fn record_buyer_purchase(&self) -> Result<()> {
+        let seeds = &[seeds::MARKETPLACE.as_ref(), &[self.marketplace.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.identity_program.to_account_info(),
+            datasov_identity::cpi::accounts::RecordConsumerPurchase {
+                consumer_profile: self.buyer_profile.to_account_info(),
+                marketplace: self.marketplace.to_account_info(),
+            },
+            signer,
+        );
+        datasov_identity::cpi::record_consumer_purchase(cpi_ctx)
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateListingPrice<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureConfidentialFees<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// Pays for the fee account's extra space
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = marketplace,
+        associated_token::token_program = token_program
+    )]
+    pub marketplace_token_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// CHECK: Context state account holding the verified pubkey validity proof for the fee account's ElGamal key; validated by the token program
+    pub pubkey_validity_proof: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyConfidentialFees<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = marketplace,
+        associated_token::token_program = token_program
+    )]
+    pub marketplace_token_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawConfidentialFees<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = marketplace,
+        associated_token::token_program = token_program
+    )]
+    pub marketplace_token_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub authority_token_account: Box<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// CHECK: Context state account holding the verified withdraw proof; validated by the token program
+    pub withdraw_proof: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// Fees accrue only in the marketplace's associated token accounts; its escrows and vaults are
+    /// other token accounts it also owns
+    #[account(
+        mut,
+        associated_token::mint = marketplace_token_account.mint,
+        associated_token::authority = marketplace
+    )]
+    pub marketplace_token_account: Box<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = marketplace_token_account.mint,
+        associated_token::authority = authority
+    )]
+    pub authority_token_account: Box<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct HaltDataType<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeatureFlags<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + FeatureFlags::INIT_SPACE,
+        seeds = [seeds::FEATURE_FLAGS],
+        bump
+    )]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    pub authority: Signer<'info>,
+
+    /// Pays the flags account's rent on first use, as the authority may be a PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingExpiry<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingDataPointer<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListingData<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingPreview<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingFreshnessOracle<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AttestListingFreshness<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub attestor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct CreateDataManifest<'info> {
+    #[account(
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DataManifest::INIT_SPACE,
+        seeds = [seeds::DATA_MANIFEST, listing.key().as_ref()],
+        bump
+    )]
+    pub manifest: Box<Account<'info, DataManifest>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDataManifest<'info> {
+    #[account(
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::DATA_MANIFEST, listing.key().as_ref()],
+        bump = manifest.bump
+    )]
+    pub manifest: Box<Account<'info, DataManifest>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCrankFund<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CrankFund::INIT_SPACE,
+        seeds = [seeds::CRANK_FUND],
+        bump
+    )]
+    pub crank_fund: Account<'info, CrankFund>,
+
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepExpired<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CRANK_FUND],
+        bump = crank_fund.bump
+    )]
+    pub crank_fund: Account<'info, CrankFund>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct ReclaimListingRent<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        close = rent_payer
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump = receipt.bump,
+        close = receipt_rent_payer
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    /// CHECK: The grant the receipt is bound to; may already be closed
+    #[account(address = receipt.permission)]
+    pub permission: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the rest of the rent; must be the one who paid it
+    #[account(
+        mut,
+        address = listing.load()?.rent_payer @ ErrorCode::Unauthorized
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the receipt's rent; must be the one who paid it
+    #[account(
+        mut,
+        address = receipt.rent_payer @ ErrorCode::Unauthorized
+    )]
+    pub receipt_rent_payer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingRequiresKyb<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(code_hash: [u8; 32])]
+pub struct CreateCoupon<'info> {
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Coupon::INIT_SPACE,
+        seeds = [seeds::COUPON, seller.key().as_ref(), code_hash.as_ref()],
+        bump
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCoupon<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::COUPON, seller.key().as_ref(), coupon.code_hash.as_ref()],
+        bump = coupon.bump,
+        has_one = seller,
+        close = seller
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct CreatePurchaseDelegate<'info> {
+    #[account(
+        init,
+        payer = consumer,
+        space = 8 + PurchaseDelegate::INIT_SPACE,
+        seeds = [seeds::PURCHASE_DELEGATE, consumer.key().as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub purchase_delegate: Account<'info, PurchaseDelegate>,
+
+    #[account(
+        mut,
+        token::authority = consumer
+    )]
+    pub consumer_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokePurchaseDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::PURCHASE_DELEGATE,
+            consumer.key().as_ref(),
+            purchase_delegate.delegate.as_ref()
+        ],
+        bump = purchase_delegate.bump,
+        has_one = consumer,
+        close = consumer
+    )]
+    pub purchase_delegate: Account<'info, PurchaseDelegate>,
+
+    #[account(
+        mut,
+        address = purchase_delegate.token_account
+    )]
+    pub consumer_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetSettlementMints<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct ValidateReceiptAccess<'info> {
+    #[account(
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump = receipt.bump,
+        has_one = permission
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    pub permission: Box<Account<'info, AccessPermission>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRefundWindow<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct ReleaseProceeds<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump = receipt.bump,
+        has_one = seller,
+        has_one = permission
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW, listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Held by the listing's payout wallet
+    #[account(
+        mut,
+        token::mint = escrow_token_account.mint,
+        constraint = seller_token_account.owner == listing.load()?.payout_wallet @ ErrorCode::InvalidPayoutAccount
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(listing.load()?.identity_id())],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    /// Lifetime earnings of the listing identity
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + EarningsLedger::INIT_SPACE,
+        seeds = [seeds::EARNINGS_LEDGER, seller_identity.key().as_ref()],
+        bump
+    )]
+    pub earnings_ledger: Box<Account<'info, EarningsLedger>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct ClaimRevocationRefund<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump = receipt.bump,
+        has_one = permission
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW, listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = escrow_token_account.mint,
+        token::authority = receipt.payer
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub claimant: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingPurposes<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingLicense<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    /// The template to license sales under; none clears the listing's license
+    #[account(
+        seeds = [seeds::LICENSE_TEMPLATE, license_template.template_id.to_le_bytes().as_ref()],
+        bump = license_template.bump
+    )]
+    pub license_template: Option<Account<'info, LicenseTemplate>>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetListingRightsTransferable<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        has_one = owner
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64, job_id: u64)]
+pub struct CreateComputeJob<'info> {
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + ComputeJob::INIT_SPACE,
+        seeds = [
+            seeds::COMPUTE_JOB,
+            listing.key().as_ref(),
+            buyer.key().as_ref(),
+            job_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub compute_job: Box<Account<'info, ComputeJob>>,
+
+    #[account(
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [seeds::FEATURE_FLAGS],
+        bump = feature_flags.bump
+    )]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(listing.load()?.identity_id())],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&buyer_identity.identity_id)],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [
+            identity_seeds::PERMISSION,
+            seller_identity.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [identity_seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [identity_seeds::BLACKLIST],
+        bump = blacklist.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    #[account(
+        seeds = [identity_seeds::CONSUMER, buyer.key().as_ref()],
+        bump = buyer_profile.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    #[account(
+        seeds = [identity_seeds::CONSUMER_STAKE, buyer.key().as_ref()],
+        bump = buyer_stake.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub buyer_stake: Option<Account<'info, ConsumerStake>>,
+
+    #[account(
+        seeds = [seeds::ALLOWLIST, listing.key().as_ref()],
+        bump = allowlist.bump
+    )]
+    pub allowlist: Option<Account<'info, ListingAllowlist>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = settlement_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub settlement_mint: Account<'info, token::Mint>,
+
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [seeds::JOB_ESCROW, compute_job.key().as_ref()],
+        bump,
+        token::mint = settlement_mint,
+        token::authority = marketplace
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PostComputeResult<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::COMPUTE_JOB,
+            compute_job.listing.as_ref(),
+            compute_job.buyer.as_ref(),
+            compute_job.job_id.to_le_bytes().as_ref()
+        ],
+        bump = compute_job.bump,
+        has_one = provider
+    )]
+    pub compute_job: Box<Account<'info, ComputeJob>>,
+
+    #[account(
+        seeds = [identity_seeds::TEE_ATTESTATION, provider.key().as_ref()],
+        bump = tee_attestation.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub tee_attestation: Option<Box<Account<'info, TeeAttestation>>>,
+
+    pub provider: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseComputePayment<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::COMPUTE_JOB,
+            compute_job.listing.as_ref(),
+            compute_job.buyer.as_ref(),
+            compute_job.job_id.to_le_bytes().as_ref()
+        ],
+        bump = compute_job.bump
+    )]
+    pub compute_job: Box<Account<'info, ComputeJob>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::JOB_ESCROW, compute_job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = escrow_token_account.mint,
+        token::authority = compute_job.seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = escrow_token_account.mint,
+        associated_token::authority = marketplace
+    )]
+    pub marketplace_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = compute_job.listing)]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(listing.load()?.identity_id())],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    /// Lifetime earnings of the listing identity
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + EarningsLedger::INIT_SPACE,
+        seeds = [seeds::EARNINGS_LEDGER, seller_identity.key().as_ref()],
+        bump
+    )]
+    pub earnings_ledger: Box<Account<'info, EarningsLedger>>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundComputeJob<'info> {
+    #[account(
+        mut,
+        seeds = [
+            seeds::COMPUTE_JOB,
+            compute_job.listing.as_ref(),
+            compute_job.buyer.as_ref(),
+            compute_job.job_id.to_le_bytes().as_ref()
+        ],
+        bump = compute_job.bump,
+        has_one = buyer
+    )]
+    pub compute_job: Box<Account<'info, ComputeJob>>,
+
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::JOB_ESCROW, compute_job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = escrow_token_account.mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: u64)]
+pub struct CreateFlJob<'info> {
+    #[account(
+        init,
+        payer = consumer,
+        space = 8 + FlJob::INIT_SPACE,
+        seeds = [seeds::FL_JOB, consumer.key().as_ref(), job_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub fl_job: Account<'info, FlJob>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = consumer
+    )]
+    pub consumer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub reward_mint: Account<'info, token::Mint>,
+
+    #[account(
+        init,
+        payer = consumer,
+        seeds = [seeds::FL_ESCROW, fl_job.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = marketplace
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinFlJob<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::FL_JOB, fl_job.consumer.as_ref(), fl_job.job_id.to_le_bytes().as_ref()],
+        bump = fl_job.bump
+    )]
+    pub fl_job: Account<'info, FlJob>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + FlParticipant::INIT_SPACE,
+        seeds = [seeds::FL_PARTICIPANT, fl_job.key().as_ref(), identity.key().as_ref()],
+        bump
+    )]
+    pub participant: Box<Account<'info, FlParticipant>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&identity.identity_id)],
+        bump,
+        seeds::program = identity_program.key(),
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [
+            identity_seeds::PERMISSION,
+            identity.key().as_ref(),
+            fl_job.consumer.as_ref()
+        ],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordFlContribution<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::FL_JOB, fl_job.consumer.as_ref(), fl_job.job_id.to_le_bytes().as_ref()],
+        bump = fl_job.bump,
+        has_one = consumer
+    )]
+    pub fl_job: Account<'info, FlJob>,
+
+    #[account(
+        mut,
+        seeds = [seeds::FL_PARTICIPANT, fl_job.key().as_ref(), participant.identity.as_ref()],
+        bump = participant.bump,
+        has_one = fl_job,
+        has_one = permission
+    )]
+    pub participant: Box<Account<'info, FlParticipant>>,
+
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeFlJob<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::FL_JOB, fl_job.consumer.as_ref(), fl_job.job_id.to_le_bytes().as_ref()],
+        bump = fl_job.bump,
+        has_one = consumer
+    )]
+    pub fl_job: Account<'info, FlJob>,
+
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::FL_ESCROW, fl_job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = escrow_token_account.mint,
+        token::authority = consumer
+    )]
+    pub consumer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub consumer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFlReward<'info> {
+    #[account(
+        seeds = [seeds::FL_JOB, fl_job.consumer.as_ref(), fl_job.job_id.to_le_bytes().as_ref()],
+        bump = fl_job.bump
+    )]
+    pub fl_job: Account<'info, FlJob>,
+
+    #[account(
+        mut,
+        seeds = [seeds::FL_PARTICIPANT, fl_job.key().as_ref(), participant.identity.as_ref()],
+        bump = participant.bump,
+        has_one = fl_job,
+        has_one = owner
+    )]
+    pub participant: Box<Account<'info, FlParticipant>>,
+
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::FL_ESCROW, fl_job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = escrow_token_account.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(union_id: u64)]
+pub struct CreateDataUnion<'info> {
+    #[account(
+        init,
+        payer = operator,
+        space = 8 + DataUnion::INIT_SPACE,
+        seeds = [seeds::UNION, operator.key().as_ref(), union_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub data_union: Box<Account<'info, DataUnion>>,
+
+    #[account(
+        init,
+        payer = operator,
+        seeds = [seeds::UNION_VAULT, data_union.key().as_ref()],
+        bump,
+        token::mint = settlement_mint,
+        token::authority = marketplace
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub settlement_mint: Account<'info, token::Mint>,
+
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinDataUnion<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::UNION, data_union.operator.as_ref(), data_union.union_id.to_le_bytes().as_ref()],
+        bump = data_union.bump
+    )]
+    pub data_union: Box<Account<'info, DataUnion>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UnionMember::INIT_SPACE,
+        seeds = [seeds::UNION_MEMBER, data_union.key().as_ref(), identity.key().as_ref()],
+        bump
+    )]
+    pub member: Box<Account<'info, UnionMember>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&identity.identity_id)],
+        bump,
+        seeds::program = identity_program.key(),
+        has_one = owner
+    )]
+    pub identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnionMemberAction<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::UNION, data_union.operator.as_ref(), data_union.union_id.to_le_bytes().as_ref()],
+        bump = data_union.bump
+    )]
+    pub data_union: Box<Account<'info, DataUnion>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::UNION_MEMBER, data_union.key().as_ref(), member.identity.as_ref()],
+        bump = member.bump,
+        has_one = owner
+    )]
+    pub member: Box<Account<'info, UnionMember>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimUnionProceeds<'info> {
+    #[account(
+        seeds = [seeds::UNION, data_union.operator.as_ref(), data_union.union_id.to_le_bytes().as_ref()],
+        bump = data_union.bump,
+        has_one = vault
+    )]
+    pub data_union: Box<Account<'info, DataUnion>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::UNION_MEMBER, data_union.key().as_ref(), member.identity.as_ref()],
+        bump = member.bump,
+        has_one = owner
+    )]
+    pub member: Box<Account<'info, UnionMember>>,
+
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(mut)]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AttachListingToUnion<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump,
+        constraint = listing.load()?.owner == operator.key() @ ErrorCode::Unauthorized
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        seeds = [seeds::UNION, data_union.operator.as_ref(), data_union.union_id.to_le_bytes().as_ref()],
+        bump = data_union.bump,
+        has_one = operator
+    )]
+    pub data_union: Box<Account<'info, DataUnion>>,
+
+    pub operator: Signer<'info>,
+}
+
+/// Accounts for rewriting a legacy-layout account in place; anyone may pay for the migration
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Legacy layouts do not deserialize as the current type; the handler checks its discriminator and size
+    #[account(mut, owner = crate::ID)]
+    pub account: AccountInfo<'info>,
+
+    /// Covers the rent for the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateAccount<'info> {
+    /// Grow the account to `space`, topping up its rent from the payer, and overwrite it with `migrated`
+    fn write<T: AccountSerialize>(&self, space: usize, migrated: &T) -> Result<()> {
+        self.resize(space)?;
+        let mut data = self.account.try_borrow_mut_data()?;
+        migrated.try_serialize(&mut &mut data[..])
+    }
+
+    /// Resize the account to fit zero-copy `migrated` and overwrite it with its bytes
+    fn write_zero_copy<T: anchor_lang::ZeroCopy>(&self, migrated: &T) -> Result<()> {
+        self.resize(8 + std::mem::size_of::<T>())?;
+        let mut data = self.account.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(&T::DISCRIMINATOR);
+        data[8..].copy_from_slice(bytemuck::bytes_of(migrated));
+        Ok(())
+    }
+
+    /// Resize the account to `space`, topping up its rent from the payer
+    fn resize(&self, space: usize) -> Result<()> {
+        let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(self.account.lamports());
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.account.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_ctx, shortfall)?;
+        }
+        self.account.realloc(space, false)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketplace<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRebates<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RebateProgram::INIT_SPACE,
+        seeds = [seeds::REBATE_PROGRAM],
+        bump
+    )]
+    pub rebate_program: Account<'info, RebateProgram>,
+
+    /// The protocol token rebates are paid in
+    pub mint: Account<'info, token::Mint>,
+
+    /// Funded by plain token transfers; the marketplace PDA signs payouts
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::REBATE_VAULT],
+        bump,
+        token::mint = mint,
+        token::authority = marketplace
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRebateProgram<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::REBATE_PROGRAM],
+        bump = rebate_program.bump
+    )]
+    pub rebate_program: Account<'info, RebateProgram>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct OpenRebateEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::REBATE_PROGRAM],
+        bump = rebate_program.bump,
+        has_one = vault
+    )]
+    pub rebate_program: Account<'info, RebateProgram>,
+
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RebateEpoch::INIT_SPACE,
+        seeds = [seeds::REBATE_EPOCH, epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub rebate_epoch: Account<'info, RebateEpoch>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct OpenRebateLedger<'info> {
+    #[account(
+        seeds = [seeds::REBATE_EPOCH, epoch.to_le_bytes().as_ref()],
+        bump = rebate_epoch.bump
+    )]
+    pub rebate_epoch: Account<'info, RebateEpoch>,
+
+    #[account(
+        init,
+        payer = participant,
+        space = 8 + RebateLedger::INIT_SPACE,
+        seeds = [seeds::REBATE_LEDGER, epoch.to_le_bytes().as_ref(), participant.key().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, RebateLedger>,
+
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRebate<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::REBATE_PROGRAM],
+        bump = rebate_program.bump,
+        has_one = vault
+    )]
+    pub rebate_program: Account<'info, RebateProgram>,
+
+    #[account(
+        mut,
+        seeds = [seeds::REBATE_EPOCH, rebate_epoch.epoch.to_le_bytes().as_ref()],
+        bump = rebate_epoch.bump
+    )]
+    pub rebate_epoch: Account<'info, RebateEpoch>,
+
+    /// Closed to the participant once claimed
+    #[account(
+        mut,
+        close = participant,
+        seeds = [seeds::REBATE_LEDGER, rebate_epoch.epoch.to_le_bytes().as_ref(), participant.key().as_ref()],
+        bump = ledger.bump
+    )]
+    pub ledger: Account<'info, RebateLedger>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = participant
+    )]
+    pub participant_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBuyback<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BuybackConfig::INIT_SPACE,
+        seeds = [seeds::BUYBACK],
+        bump
+    )]
+    pub buyback_config: Account<'info, BuybackConfig>,
+
+    /// The fee token spent on buybacks
+    pub fee_mint: Account<'info, token::Mint>,
+
+    /// The protocol token bought and burned
+    pub protocol_mint: Account<'info, token::Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::BUYBACK_INPUT],
+        bump,
+        token::mint = fee_mint,
+        token::authority = buyback_config
+    )]
+    pub input_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::BUYBACK_OUTPUT],
+        bump,
+        token::mint = protocol_mint,
+        token::authority = buyback_config
+    )]
+    pub output_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBuyback<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::BUYBACK],
+        bump = buyback_config.bump
+    )]
+    pub buyback_config: Account<'info, BuybackConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteBuyback<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::BUYBACK],
+        bump = buyback_config.bump,
+        has_one = fee_mint,
+        has_one = protocol_mint,
+        has_one = input_vault,
+        has_one = output_vault
+    )]
+    pub buyback_config: Account<'info, BuybackConfig>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// Where purchases pay the marketplace fee; escrows in the same mint are out of reach
+    #[account(
+        mut,
+        associated_token::mint = fee_mint,
+        associated_token::authority = marketplace
+    )]
+    pub marketplace_token_account: Account<'info, TokenAccount>,
+
+    pub fee_mint: Account<'info, token::Mint>,
+
+    #[account(mut)]
+    pub protocol_mint: Account<'info, token::Mint>,
+
+    #[account(mut)]
+    pub input_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub output_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Must be the configured DEX; the route it runs is bounded by the vault balance checks
+    #[account(address = buyback_config.parameters.dex_program @ ErrorCode::InvalidBuybackConfig)]
+    pub dex_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCuration<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CurationConfig::INIT_SPACE,
+        seeds = [seeds::CURATION],
+        bump
+    )]
+    pub curation_config: Account<'info, CurationConfig>,
+
+    /// The protocol token curators bond
+    pub mint: Account<'info, token::Mint>,
+
+    /// Holds the bonds of unsettled votes; the marketplace PDA signs settlements
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::CURATION_VAULT],
+        bump,
+        token::mint = mint,
+        token::authority = marketplace
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCuration<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::CURATION],
+        bump = curation_config.bump
+    )]
+    pub curation_config: Account<'info, CurationConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct VoteFeatured<'info> {
+    #[account(
+        seeds = [seeds::CURATION],
+        bump = curation_config.bump,
+        has_one = vault
+    )]
+    pub curation_config: Box<Account<'info, CurationConfig>>,
+
+    #[account(
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    /// The curator's staking weight, counted toward the listing's votes
+    #[account(
+        seeds = [datasov_staking::seeds::STAKE_WEIGHT, curator.key().as_ref()],
+        bump = curator_weight.bump,
+        seeds::program = datasov_staking::ID
+    )]
+    pub curator_weight: Box<Account<'info, StakeWeight>>,
+
+    #[account(
+        init_if_needed,
+        payer = curator,
+        space = 8 + FeaturedSet::INIT_SPACE,
+        seeds = [seeds::FEATURED_SET, epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub featured_set: Box<Account<'info, FeaturedSet>>,
+
+    #[account(
+        init_if_needed,
+        payer = curator,
+        space = 8 + ListingTally::INIT_SPACE,
+        seeds = [seeds::LISTING_TALLY, epoch.to_le_bytes().as_ref(), listing.key().as_ref()],
+        bump
+    )]
+    pub tally: Box<Account<'info, ListingTally>>,
+
+    /// One vote per curator and listing in an epoch
+    #[account(
+        init,
+        payer = curator,
+        space = 8 + CurationVote::INIT_SPACE,
+        seeds = [seeds::CURATION_VOTE, tally.key().as_ref(), curator.key().as_ref()],
+        bump
+    )]
+    pub vote: Box<Account<'info, CurationVote>>,
+
+    #[account(mut)]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = curator
+    )]
+    pub curator_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub curator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FlagCuratedListing<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::LISTING_TALLY, tally.epoch.to_le_bytes().as_ref(), tally.listing.as_ref()],
+        bump = tally.bump
+    )]
+    pub tally: Account<'info, ListingTally>,
+
+    #[account(
+        mut,
+        seeds = [seeds::FEATURED_SET, tally.epoch.to_le_bytes().as_ref()],
+        bump = featured_set.bump
+    )]
+    pub featured_set: Account<'info, FeaturedSet>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleCurationVote<'info> {
+    #[account(
+        seeds = [seeds::CURATION],
+        bump = curation_config.bump,
+        has_one = vault
+    )]
+    pub curation_config: Box<Account<'info, CurationConfig>>,
+
+    #[account(
+        seeds = [seeds::LISTING_TALLY, tally.epoch.to_le_bytes().as_ref(), tally.listing.as_ref()],
+        bump = tally.bump
+    )]
+    pub tally: Box<Account<'info, ListingTally>>,
+
+    /// Closed to the curator once settled
+    #[account(
+        mut,
+        close = curator,
+        seeds = [seeds::CURATION_VOTE, tally.key().as_ref(), curator.key().as_ref()],
+        bump = vote.bump,
+        has_one = curator
+    )]
+    pub vote: Box<Account<'info, CurationVote>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>,
+
+    #[account(mut)]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = curator
+    )]
+    pub curator_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the bond of a vote on a disputed listing
+    #[account(
+        mut,
+        associated_token::mint = vault.mint,
+        associated_token::authority = marketplace
+    )]
+    pub marketplace_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub curator: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(oracle: Pubkey)]
+pub struct AddStorageOracle<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StorageOracle::INIT_SPACE,
+        seeds = [seeds::STORAGE_ORACLE, oracle.as_ref()],
+        bump
+    )]
+    pub storage_oracle: Account<'info, StorageOracle>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveStorageOracle<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [seeds::STORAGE_ORACLE, storage_oracle.oracle.as_ref()],
+        bump = storage_oracle.bump
+    )]
+    pub storage_oracle: Account<'info, StorageOracle>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: u32)]
+pub struct CreateLicenseTemplate<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LicenseTemplate::INIT_SPACE,
+        seeds = [seeds::LICENSE_TEMPLATE, template_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub license_template: Account<'info, LicenseTemplate>,
+
+    pub authority: Signer<'info>,
+
+    /// Pays the template's rent, as the authority may be a PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLicenseTemplate<'info> {
+    #[account(
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump,
+        has_one = authority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [seeds::LICENSE_TEMPLATE, license_template.template_id.to_le_bytes().as_ref()],
+        bump = license_template.bump
+    )]
+    pub license_template: Account<'info, LicenseTemplate>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct MintLicense<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    #[account(
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump = receipt.bump,
+        has_one = buyer
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    /// The template the purchase was licensed under; it may have been retired since
+    #[account(
+        seeds = [seeds::LICENSE_TEMPLATE, license_template.template_id.to_le_bytes().as_ref()],
+        bump = license_template.bump,
+        constraint = receipt.license_template == Some(license_template.template_id) @ ErrorCode::UnlicensedReceipt
+    )]
+    pub license_template: Account<'info, LicenseTemplate>,
+
+    /// CHECK: Created here as the license NFT's Token-2022 mint; one per receipt
+    #[account(
+        mut,
+        seeds = [seeds::LICENSE_MINT, receipt.key().as_ref()],
+        bump
+    )]
+    pub license_mint: UncheckedAccount<'info>,
+
+    /// CHECK: The buyer's associated token account for the license mint, created here by the
+    /// associated token program, which checks the address
+    #[account(mut)]
+    pub buyer_token_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(listing_id: u64)]
+pub struct TransferAccessRight<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing_id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::RECEIPT, listing.key().as_ref()],
+        bump = receipt.bump,
+        has_one = buyer,
+        has_one = permission
+    )]
+    pub receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    /// The current holder's permission, which the receipt is bound to
+    pub permission: Box<Account<'info, AccessPermission>>,
+
+    /// The template the purchase was licensed under, required if it was
+    #[account(
+        seeds = [seeds::LICENSE_TEMPLATE, license_template.template_id.to_le_bytes().as_ref()],
+        bump = license_template.bump
+    )]
+    pub license_template: Option<Account<'info, LicenseTemplate>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(listing.load()?.identity_id())],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub seller_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [identity_seeds::IDENTITY, &identity_seeds::identity_id(&recipient_identity.identity_id)],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub recipient_identity: Box<Account<'info, IdentityAccount>>,
+
+    #[account(
+        seeds = [
+            identity_seeds::PERMISSION,
+            seller_identity.key().as_ref(),
+            recipient_identity.owner.as_ref()
+        ],
+        bump,
+        seeds::program = identity_program.key()
+    )]
+    pub recipient_permission: Box<Account<'info, AccessPermission>>,
+
+    #[account(
+        seeds = [identity_seeds::COMPLIANCE_MATRIX],
+        bump = compliance_matrix.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub compliance_matrix: Account<'info, ComplianceMatrix>,
+
+    #[account(
+        seeds = [identity_seeds::BLACKLIST],
+        bump = blacklist.bump,
+        seeds::program = identity_program.key()
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    /// The current holder
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub identity_program: Program<'info, DatasovIdentity>,
+}
+
+#[derive(Accounts)]
+pub struct RecordStorageCheck<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::LISTING, listing.load()?.id.to_le_bytes().as_ref()],
+        bump = listing.load()?.bump
+    )]
+    pub listing: AccountLoader<'info, DataListing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::STORAGE_ORACLE, oracle.key().as_ref()],
+        bump = storage_oracle.bump
+    )]
+    pub storage_oracle: Account<'info, StorageOracle>,
+
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = 8 + StorageHealth::INIT_SPACE,
+        seeds = [seeds::STORAGE_HEALTH, listing.key().as_ref()],
+        bump
+    )]
+    pub storage_health: Account<'info, StorageHealth>,
+
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [seeds::MARKETPLACE],
+        bump = marketplace.bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts needed to append an entry to the audit program's log for an identity
+#[derive(Accounts)]
+pub struct AuditAccounts<'info> {
+    /// CHECK: Initialized and validated by the audit program
+    #[account(mut)]
+    pub audit_log: AccountInfo<'info>,
+
+    /// CHECK: Initialized and validated by the audit program
+    #[account(mut)]
+    pub audit_page: AccountInfo<'info>,
+
+    /// CHECK: PDA this program signs audit entries with
+    #[account(
+        seeds = [datasov_audit::seeds::AUDIT_WRITER],
+        bump
+    )]
+    pub audit_writer: AccountInfo<'info>,
+
+    pub audit_program: Program<'info, DatasovAudit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AuditAccounts<'info> {
+    /// CPI into the audit program, signing as this program's audit writer
+    fn record(
+        &self,
+        bump: u8,
+        payer: AccountInfo<'info>,
+        subject: Pubkey,
+        action: AuditAction,
+        actor: Pubkey,
+        reference: Pubkey,
+        value: u64,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] = &[datasov_audit::seeds::AUDIT_WRITER, &[bump]];
+        let signer = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.audit_program.to_account_info(),
+            datasov_audit::cpi::accounts::AppendEntry {
+                audit_log: self.audit_log.to_account_info(),
+                audit_page: self.audit_page.to_account_info(),
+                writer: self.audit_writer.to_account_info(),
+                payer: payer,
+                system_program: self.system_program.to_account_info(),
+            },
+            signer,
+        );
+        datasov_audit::cpi::append_entry(cpi_ctx, subject, action, actor, reference, value)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Marketplace {
+    pub authority: Pubkey,
+    pub fee_basis_points: u16,
+    pub total_listings: u64,
+    pub total_volume: u64,
+    pub halted_data_types: u16,
+    pub stablecoin_only: bool,
+    #[max_len(8)]
+    pub approved_mints: Vec<Pubkey>,
+    pub refund_window: i64,
+    /// Sequence number of the program's latest event; every event carries the next one and the
+    /// slot it was emitted in, so indexers can order events and detect gaps. Every instruction that
+    /// emits therefore write-locks this account, and the program's emitting transactions run one
+    /// after another rather than in parallel; a single program-wide order is worth that cost.
+    pub event_sequence: u64,
+    pub bump: u8,
+}
+
+impl Marketplace {
+    pub const MAX_APPROVED_MINTS: usize = 8;
+    pub const MAX_REFUND_WINDOW: i64 = 30 * 24 * 60 * 60;
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size
+    pub const LEGACY_SPACES: &[usize] = &[59, 61, 322, 330];
+
+    /// Decode an account written in legacy layout `version`, leaving fields added since at their defaults
+    pub fn from_legacy(version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        Ok(Self {
+            authority: read_field(data)?,
+            fee_basis_points: read_field(data)?,
+            total_listings: read_field(data)?,
+            total_volume: read_field(data)?,
+            halted_data_types: if version >= 1 { read_field(data)? } else { 0 },
+            stablecoin_only: if version >= 2 { read_field(data)? } else { false },
+            approved_mints: if version >= 2 { read_field(data)? } else { Vec::new() },
+            refund_window: if version >= 3 { read_field(data)? } else { 0 },
+            event_sequence: 0,
+            bump: read_field(data)?,
+        })
+    }
+
+    pub fn is_halted(&self, data_type: &DataType) -> bool {
+        self.halted_data_types & data_type.halt_mask() != 0
+    }
+
+    /// Advance the event sequence, returning the number the event being emitted carries
+    pub fn next_event_sequence(&mut self) -> u64 {
+        self.event_sequence += 1;
+        self.event_sequence
+    }
+
+    /// Transfer tokens out of a marketplace-owned account, signing as the marketplace PDA
+    pub fn transfer_signed<'info>(
+        marketplace: &Account<'info, Marketplace>,
+        from: AccountInfo<'info>,
+        to: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let seeds = &[seeds::MARKETPLACE.as_ref(), &[marketplace.bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: marketplace.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(token_program, cpi_accounts, signer), amount)
+    }
+
+    /// In stablecoin-only mode, settlement must use one of the approved mints
+    pub fn check_settlement_mint(&self, mint: &Pubkey) -> Result<()> {
+        if self.stablecoin_only {
+            require!(self.approved_mints.contains(mint), ErrorCode::SettlementMintNotApproved);
+        }
+        Ok(())
+    }
+
+    /// Split a purchase amount into (owner_amount, fee_amount)
+    pub fn split_payment(&self, purchase_amount: u64) -> Result<(u64, u64)> {
+        self.split_payment_with_discount(purchase_amount, 0)
+    }
+
+    /// Split a purchase amount as [`Self::split_payment`], then take `discount_bps` off the fee.
+    /// The owner's share is unchanged; the buyer pays the discounted total.
+    pub fn split_payment_with_discount(&self, purchase_amount: u64, discount_bps: u16) -> Result<(u64, u64)> {
+        let fee_amount = (purchase_amount as u128)
+            .checked_mul(self.fee_basis_points as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        let owner_amount = purchase_amount
+            .checked_sub(fee_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let discount = (fee_amount as u128 * discount_bps.min(10000) as u128 / 10000) as u64;
+        Ok((owner_amount, fee_amount - discount))
+    }
+}
+
+/// Zero-copy `repr(C)` layout: instructions read and write the fields they touch in place instead
+/// of decoding the whole listing. Text sits in fixed-size buffers after its length, and each
+/// optional field after a `has_` flag; read them through the accessors below.
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct DataListing {
+    pub id: u64,
+    pub owner: Pubkey,
+    pub price: u64,
+    pub is_active: u8,
+    /// Zeroed; room for future fixed-offset fields
+    pub reserved: [u8; 16],
+    /// `DataType` variant index; a custom type's name follows it
+    pub data_type: u8,
+    pub custom_data_type_len: u8,
+    pub custom_data_type: [u8; 32],
+    pub description_len: u8,
+    pub description: [u8; 200],
+    pub identity_id_len: u8,
+    pub identity_id: [u8; 64],
+    pub is_private: u8,
+    pub requires_kyb: u8,
+    /// Set while the last storage oracle check failed; no new sales until one passes
+    pub storage_paused: u8,
+    /// Buyers may transfer their access right, and their license NFT, to another verified identity
+    pub rights_transferable: u8,
+    pub has_sold_at: u8,
+    pub has_cancelled_at: u8,
+    pub has_buyer: u8,
+    pub has_expires_at: u8,
+    pub has_union: u8,
+    pub has_freshness_oracle: u8,
+    pub has_license_template: u8,
+    pub bump: u8,
+    /// Where the listed data is stored, once the seller publishes it
+    pub data_pointer: StorageSlot,
+    /// Public sample of the data for buyers to evaluate before purchasing
+    pub preview: StorageSlot,
+    /// SHA-256 of the preview sample as stored
+    pub preview_hash: [u8; 32],
+    pub _padding: [u8; 1],
+    pub created_at: i64,
+    pub sold_at: i64,
+    pub cancelled_at: i64,
+    pub expires_at: i64,
+    /// When the data was last attested fresh; its creation until then
+    pub last_refreshed_at: i64,
+    pub buyer: Pubkey,
+    /// Private listings only: Pedersen commitment to the price, see [`DataListing::price_commitment`]
+    pub price_commitment: [u8; 32],
+    pub description_hash: [u8; 32],
+    pub union: Pubkey,
+    /// Wallet whose token accounts receive the seller's proceeds
+    pub payout_wallet: Pubkey,
+    /// Hash of the content behind `data_pointer` as of its last update; zeroed while uncommitted
+    pub data_hash: [u8; 32],
+    /// Attests freshness instead of the seller, when set
+    pub freshness_oracle: Pubkey,
+    /// Paid the account's rent, and gets most of it back when the listing is reclaimed
+    pub rent_payer: Pubkey,
+    /// `LicenseTemplate` the listing's sales are licensed under
+    pub license_template: u32,
+    pub allowed_purposes: u16,
+    pub _padding_end: [u8; 2],
+}
+
+impl Space for DataListing {
+    const INIT_SPACE: usize = std::mem::size_of::<DataListing>();
+}
+
+/// A `StoragePointer` in a fixed-size slot of a zero-copy account
+#[zero_copy]
+#[derive(Debug)]
+pub struct StorageSlot {
+    pub is_some: u8,
+    /// `StorageBackend` variant index
+    pub backend: u8,
+    pub reference_len: u8,
+    pub reference: [u8; 128],
+}
+
+impl StorageSlot {
+    pub fn get(&self) -> Option<StoragePointer> {
+        (self.is_some != 0).then(|| StoragePointer {
+            backend: StorageBackend::ALL[self.backend as usize],
+            reference: text(&self.reference, self.reference_len).to_string(),
+        })
+    }
+
+    pub fn set(&mut self, pointer: Option<&StoragePointer>) -> Result<()> {
+        *self = Self::zeroed();
+        if let Some(pointer) = pointer {
+            self.is_some = 1;
+            self.backend = pointer.backend as u8;
+            self.reference_len = set_text(&mut self.reference, &pointer.reference, ErrorCode::InvalidStoragePointer)?;
+        }
+        Ok(())
+    }
+}
+
+/// The first `len` bytes of a fixed-size text buffer
+fn text(buffer: &[u8], len: u8) -> &str {
+    std::str::from_utf8(&buffer[..len as usize]).unwrap_or_default()
+}
+
+/// Write `value` to the start of a fixed-size, zero-padded text buffer; returns its length
+fn set_text(buffer: &mut [u8], value: &str, too_long: ErrorCode) -> Result<u8> {
+    if value.len() > buffer.len() {
+        return Err(too_long.into());
+    }
+    buffer.fill(0);
+    buffer[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(value.len() as u8)
+}
+
+/// An optional field stored as a `has_` flag and a value, zeroed when absent
+fn get_option<T: Copy>(has: u8, value: T) -> Option<T> {
+    (has != 0).then_some(value)
+}
+
+fn set_option<T: Default>(has: &mut u8, slot: &mut T, value: Option<T>) {
+    *has = u8::from(value.is_some());
+    *slot = value.unwrap_or_default();
+}
+
+impl DataListing {
+    /// Byte offsets, discriminator included, of the fields `getProgramAccounts` filters match on.
+    /// They come before every variable-length field and do not move as the layout grows.
+    pub const OWNER_OFFSET: usize = 16;
+    pub const IS_ACTIVE_OFFSET: usize = 56;
+    /// Of the data type's variant index
+    pub const DATA_TYPE_OFFSET: usize = 73;
+
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size.
+    /// Version 5 has no room for a full-length custom data type name; up to version 6 the active
+    /// flag follows the variable-length fields. Version 16 is the last Borsh layout.
+    pub const LEGACY_SPACES: &[usize] =
+        &[390, 455, 464, 465, 467, 500, 536, 552, 584, 718, 884, 925, 957, 958, 963, 964, 997];
+
+    /// How long after its sale a listing must wait to be reclaimed; well past any refund window
+    pub const RECLAIM_DELAY: i64 = 90 * 24 * 60 * 60;
+
+    pub fn is_active(&self) -> bool {
+        self.is_active != 0
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.is_private != 0
+    }
+
+    pub fn requires_kyb(&self) -> bool {
+        self.requires_kyb != 0
+    }
+
+    pub fn storage_paused(&self) -> bool {
+        self.storage_paused != 0
+    }
+
+    pub fn rights_transferable(&self) -> bool {
+        self.rights_transferable != 0
+    }
+
+    pub fn data_type(&self) -> DataType {
+        DataType::from_index(self.data_type, text(&self.custom_data_type, self.custom_data_type_len))
+    }
+
+    pub fn set_data_type(&mut self, data_type: &DataType) -> Result<()> {
+        self.data_type = data_type.index() as u8;
+        let name = match data_type {
+            DataType::Custom(name) => name.as_str(),
+            _ => "",
+        };
+        self.custom_data_type_len = set_text(&mut self.custom_data_type, name, ErrorCode::CustomDataTypeTooLong)?;
+        Ok(())
+    }
+
+    pub fn description(&self) -> &str {
+        text(&self.description, self.description_len)
+    }
+
+    pub fn set_description(&mut self, description: &str) -> Result<()> {
+        self.description_len = set_text(&mut self.description, description, ErrorCode::DescriptionTooLong)?;
+        Ok(())
+    }
+
+    pub fn identity_id(&self) -> &str {
+        text(&self.identity_id, self.identity_id_len)
+    }
+
+    pub fn set_identity_id(&mut self, identity_id: &str) -> Result<()> {
+        self.identity_id_len = set_text(&mut self.identity_id, identity_id, ErrorCode::IdentityIdTooLong)?;
+        Ok(())
+    }
+
+    pub fn sold_at(&self) -> Option<i64> {
+        get_option(self.has_sold_at, self.sold_at)
+    }
+
+    pub fn set_sold_at(&mut self, sold_at: Option<i64>) {
+        set_option(&mut self.has_sold_at, &mut self.sold_at, sold_at);
+    }
+
+    pub fn cancelled_at(&self) -> Option<i64> {
+        get_option(self.has_cancelled_at, self.cancelled_at)
+    }
+
+    pub fn set_cancelled_at(&mut self, cancelled_at: Option<i64>) {
+        set_option(&mut self.has_cancelled_at, &mut self.cancelled_at, cancelled_at);
+    }
+
+    pub fn buyer(&self) -> Option<Pubkey> {
+        get_option(self.has_buyer, self.buyer)
+    }
+
+    pub fn set_buyer(&mut self, buyer: Option<Pubkey>) {
+        set_option(&mut self.has_buyer, &mut self.buyer, buyer);
+    }
+
+    pub fn expires_at(&self) -> Option<i64> {
+        get_option(self.has_expires_at, self.expires_at)
+    }
+
+    pub fn set_expires_at(&mut self, expires_at: Option<i64>) {
+        set_option(&mut self.has_expires_at, &mut self.expires_at, expires_at);
+    }
+
+    pub fn union(&self) -> Option<Pubkey> {
+        get_option(self.has_union, self.union)
+    }
+
+    pub fn set_union(&mut self, union: Option<Pubkey>) {
+        set_option(&mut self.has_union, &mut self.union, union);
+    }
+
+    pub fn freshness_oracle(&self) -> Option<Pubkey> {
+        get_option(self.has_freshness_oracle, self.freshness_oracle)
+    }
+
+    pub fn set_freshness_oracle(&mut self, freshness_oracle: Option<Pubkey>) {
+        set_option(&mut self.has_freshness_oracle, &mut self.freshness_oracle, freshness_oracle);
+    }
+
+    pub fn license_template(&self) -> Option<u32> {
+        get_option(self.has_license_template, self.license_template)
+    }
+
+    pub fn set_license_template(&mut self, license_template: Option<u32>) {
+        set_option(&mut self.has_license_template, &mut self.license_template, license_template);
+    }
+
+    pub fn data_pointer(&self) -> Option<StoragePointer> {
+        self.data_pointer.get()
+    }
+
+    pub fn preview(&self) -> Option<ListingPreview> {
+        self.preview.get().map(|pointer| ListingPreview {
+            pointer,
+            hash: self.preview_hash,
+        })
+    }
+
+    pub fn set_preview(&mut self, preview: Option<&ListingPreview>) -> Result<()> {
+        self.preview.set(preview.map(|preview| &preview.pointer))?;
+        self.preview_hash = preview.map_or([0; 32], |preview| preview.hash);
+        Ok(())
+    }
+
+    /// Decode an account written in legacy layout `version` into the current one, leaving fields
+    /// added since at their defaults. Listings from before purposes were recorded allow every purpose,
+    /// as they did then, listings from before payout wallets or rent payers were recorded pay and
+    /// refund their owner, and listings from before freshness was attested count as refreshed when created.
+    pub fn from_legacy(version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        let mut listing = Self::zeroed();
+        listing.id = read_field(data)?;
+        listing.owner = read_field(data)?;
+        listing.price = read_field(data)?;
+        let mut is_active = false;
+        if version >= 7 {
+            is_active = read_field(data)?;
+            listing.reserved = read_field(data)?;
+        }
+        listing.set_data_type(&read_field::<DataType>(data)?)?;
+        listing.set_description(&read_field::<String>(data)?)?;
+        listing.set_identity_id(&read_field::<String>(data)?)?;
+        if version < 7 {
+            is_active = read_field(data)?;
+        }
+        listing.is_active = u8::from(is_active);
+        listing.created_at = read_field(data)?;
+        listing.set_sold_at(read_field(data)?);
+        listing.set_cancelled_at(read_field(data)?);
+        listing.set_buyer(read_field(data)?);
+        if version >= 1 {
+            listing.is_private = u8::from(read_field::<bool>(data)?);
+            listing.price_commitment = read_field(data)?;
+            listing.description_hash = read_field(data)?;
+        }
+        if version >= 2 {
+            listing.set_expires_at(read_field(data)?);
+        }
+        if version >= 3 {
+            listing.requires_kyb = u8::from(read_field::<bool>(data)?);
+        }
+        listing.allowed_purposes = if version >= 4 { read_field(data)? } else { Purpose::ALL };
+        if version >= 5 {
+            listing.set_union(read_field(data)?);
+        }
+        listing.payout_wallet = if version >= 8 { read_field(data)? } else { listing.owner };
+        if version >= 9 {
+            listing.data_pointer.set(read_field::<Option<StoragePointer>>(data)?.as_ref())?;
+        }
+        if version >= 12 {
+            listing.data_hash = read_field(data)?;
+        }
+        if version >= 10 {
+            listing.set_preview(read_field::<Option<ListingPreview>>(data)?.as_ref())?;
+        }
+        listing.last_refreshed_at = if version >= 11 { read_field(data)? } else { listing.created_at };
+        if version >= 11 {
+            listing.set_freshness_oracle(read_field(data)?);
+        }
+        if version >= 13 {
+            listing.storage_paused = u8::from(read_field::<bool>(data)?);
+        }
+        if version >= 14 {
+            listing.set_license_template(read_field(data)?);
+        }
+        if version >= 15 {
+            listing.rights_transferable = u8::from(read_field::<bool>(data)?);
+        }
+        listing.rent_payer = if version >= 16 { read_field(data)? } else { listing.owner };
+        listing.bump = read_field(data)?;
+        Ok(listing)
+    }
+
+    /// Whether a sold listing settled long enough before `now` to be reclaimed, given whether the
+    /// buyer's access through it has ended. Listings a license or a transferable right still points
+    /// at are kept.
+    pub fn reclaimable(&self, receipt: &PurchaseReceipt, access_ended: bool, now: i64) -> bool {
+        self.sold_at()
+            .map_or(false, |sold_at| now >= sold_at.saturating_add(Self::RECLAIM_DELAY))
+            && receipt.released_amount + receipt.refunded_amount >= receipt.escrowed_amount
+            && receipt.license_template.is_none()
+            && !self.rights_transferable()
+            && access_ended
+    }
+
+    pub fn allows_purpose(&self, purpose: &Purpose) -> bool {
+        self.allowed_purposes & purpose.bit() != 0
+    }
+
+    /// Refuse a purchase whose buyer requires data refreshed within `max_staleness` seconds of `now`
+    pub fn check_freshness(&self, max_staleness: Option<i64>, now: i64) -> Result<()> {
+        if let Some(max_staleness) = max_staleness {
+            require!(now - self.last_refreshed_at <= max_staleness, ErrorCode::ListingStale);
+        }
+        Ok(())
+    }
+
+    /// Pedersen commitment to a private listing price, `price * G + salt * H`, so confidential
+    /// purchases can prove their transfers against it; `None` unless `salt` is a canonical scalar
+    pub fn price_commitment(price: u64, salt: &[u8; 32]) -> Option<[u8; 32]> {
+        let amount = multiply_ristretto(&scalar(price), &PodRistrettoPoint(pedersen::G))?;
+        let blinding = multiply_ristretto(&PodScalar(*salt), &PodRistrettoPoint(pedersen::H))?;
+        add_ristretto(&amount, &blinding).map(|commitment| commitment.0)
+    }
+
+    /// Price to settle at; private listings require a reveal matching the commitment
+    pub fn settlement_price(&self, price_reveal: Option<PriceReveal>) -> Result<u64> {
+        if !self.is_private() {
+            return Ok(self.price);
+        }
+        let reveal = price_reveal.ok_or(ErrorCode::PriceRevealRequired)?;
+        require!(
+            Self::price_commitment(reveal.price, &reveal.salt) == Some(self.price_commitment),
+            ErrorCode::PriceCommitmentMismatch
+        );
+        Ok(reveal.price)
+    }
+}
+
+/// A slice of a listing's data published unencrypted, so buyers can check its structure and quality
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct ListingPreview {
+    pub pointer: StoragePointer,
+    /// SHA-256 of the sample as stored
+    pub hash: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ListingAllowlist {
+    pub listing: Pubkey,
+    #[max_len(16)]
+    pub buyers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl ListingAllowlist {
+    pub const MAX_BUYERS: usize = 16;
+}
+
+/// Chunks of a listing's dataset, for data too large for one Arweave transaction
+#[account]
+#[derive(InitSpace)]
+pub struct DataManifest {
+    pub listing: Pubkey,
+    /// Sum of the chunk sizes
+    pub total_size: u64,
+    /// Set once the seller has added every chunk; the chunks no longer change
+    pub is_sealed: bool,
+    /// [`DataManifest::compute_hash`] of the chunks, set on sealing
+    pub manifest_hash: [u8; 32],
+    #[max_len(96)]
+    pub chunks: Vec<ManifestChunk>,
+    pub bump: u8,
+}
+
+impl DataManifest {
+    pub const MAX_CHUNKS: usize = 96;
+
+    pub fn append(&mut self, chunks: Vec<ManifestChunk>) -> Result<()> {
+        require!(self.chunks.len() + chunks.len() <= Self::MAX_CHUNKS, ErrorCode::ManifestTooLong);
+        for chunk in &chunks {
+            require!(chunk.size > 0, ErrorCode::EmptyManifestChunk);
+            self.total_size = self.total_size.checked_add(chunk.size).ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+        self.chunks.extend(chunks);
+        Ok(())
+    }
+
+    /// sha256 over every chunk's tx_id || size_le || hash, in order
+    pub fn compute_hash(&self) -> [u8; 32] {
+        let entries: Vec<u8> = self
+            .chunks
+            .iter()
+            .flat_map(|chunk| chunk.tx_id.into_iter().chain(chunk.size.to_le_bytes()).chain(chunk.hash))
+            .collect();
+        hashv(&[&entries]).to_bytes()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct ManifestChunk {
+    /// Arweave transaction ID, decoded from its base64url form
+    pub tx_id: [u8; 32],
+    /// Stored (encrypted) size in bytes
+    pub size: u64,
+    /// SHA-256 of the stored bytes
+    pub hash: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PurchaseReceipt {
+    pub listing: Pubkey,
+    pub listing_id: u64,
+    pub buyer: Pubkey,
+    pub payer: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub is_confidential: bool,
+    pub amount_commitment: [u8; 32],
+    pub permission: Pubkey,
+    pub permission_epoch: u32,
+    pub escrowed_amount: u64,
+    pub released_amount: u64,
+    pub refunded_amount: u64,
+    pub refund_window: i64,
+    pub purpose: Purpose,
+    pub watermark_commitment: [u8; 32],
+    /// `LicenseTemplate` the sale was licensed under, if the listing had one
+    pub license_template: Option<u32>,
+    pub purchased_at: i64,
+    /// Paid the account's rent, and gets it back when the listing is reclaimed
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl PurchaseReceipt {
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size
+    pub const LEGACY_SPACES: &[usize] = &[162, 194, 230, 262, 263, 295, 300];
+
+    /// Decode an account written in legacy layout `version`, leaving fields added since at their defaults.
+    /// Receipts from before permission binding name no permission, so they no longer back access on
+    /// their own; receipts from before escrow were settled at purchase and hold nothing to release;
+    /// receipts from before rent payers were recorded refund their payer.
+    pub fn from_legacy(version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        let listing = read_field(data)?;
+        let listing_id = read_field(data)?;
+        let buyer: Pubkey = read_field(data)?;
+        let payer: Pubkey = if version >= 1 { read_field(data)? } else { buyer };
+        Ok(Self {
+            listing,
+            listing_id,
+            buyer,
+            payer,
+            seller: read_field(data)?,
+            amount: read_field(data)?,
+            is_confidential: read_field(data)?,
+            amount_commitment: read_field(data)?,
+            permission: if version >= 2 { read_field(data)? } else { Pubkey::default() },
+            permission_epoch: if version >= 2 { read_field(data)? } else { 0 },
+            escrowed_amount: if version >= 3 { read_field(data)? } else { 0 },
+            released_amount: if version >= 3 { read_field(data)? } else { 0 },
+            refunded_amount: if version >= 3 { read_field(data)? } else { 0 },
+            refund_window: if version >= 3 { read_field(data)? } else { 0 },
+            purpose: if version >= 4 { read_field(data)? } else { Purpose::Other },
+            watermark_commitment: if version >= 5 { read_field(data)? } else { [0; 32] },
+            license_template: if version >= 6 { read_field(data)? } else { None },
+            purchased_at: read_field(data)?,
+            rent_payer: payer,
+            bump: read_field(data)?,
+        })
+    }
+
+    /// Commitment the seller embeds in the delivered dataset so a leaked copy traces back to this sale
+    pub fn watermark(receipt: &Pubkey, buyer: &Pubkey, purchased_at: i64) -> [u8; 32] {
+        hashv(&[b"watermark", receipt.as_ref(), buyer.as_ref(), &purchased_at.to_le_bytes()]).to_bytes()
+    }
+
+    /// Access bought under this receipt lapses once the owner revokes the underlying permission
+    pub fn is_revoked(&self, permission: &AccessPermission) -> bool {
+        !permission.is_active || permission.revocation_epoch != self.permission_epoch
+    }
+
+    /// When the owner's revocation cut this receipt's access short, if it did.
+    /// Repeated revocations obscure the first one, so they count from the purchase itself.
+    pub fn revoked_at(&self, permission: &AccessPermission) -> Option<i64> {
+        if permission.revocation_epoch == self.permission_epoch {
+            None
+        } else if permission.revocation_epoch == self.permission_epoch + 1 {
+            Some(permission.revoked_at.unwrap_or(self.purchased_at))
+        } else {
+            Some(self.purchased_at)
+        }
+    }
+
+    /// Escrowed proceeds earned by the seller by `until`, vesting linearly over the refund window
+    pub fn vested_amount(&self, until: i64) -> Result<u64> {
+        if self.refund_window == 0 {
+            return Ok(self.escrowed_amount);
+        }
+        let elapsed = until.saturating_sub(self.purchased_at).clamp(0, self.refund_window);
+        Ok((self.escrowed_amount as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(self.refund_window as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CrankFund {
+    pub authority: Pubkey,
+    pub reward_per_account: u64,
+    pub total_swept: u64,
+    pub total_paid: u64,
+    pub bump: u8,
+}
+
+impl CrankFund {
+    /// Pay `reward_per_account * swept` to the caller, capped at the fund's balance above rent
+    pub fn pay_reward<'info>(
+        fund: &mut Account<'info, CrankFund>,
+        swept: u64,
+        caller: &AccountInfo<'info>,
+    ) -> Result<u64> {
+        let fund_info = fund.to_account_info();
+        let rent_exempt = Rent::get()?.minimum_balance(fund_info.data_len());
+        let available = fund_info.lamports().saturating_sub(rent_exempt);
+        let reward = fund.reward_per_account.saturating_mul(swept).min(available);
+
+        if reward > 0 {
+            **fund_info.try_borrow_mut_lamports()? -= reward;
+            **caller.try_borrow_mut_lamports()? += reward;
+        }
+
+        fund.total_swept += swept;
+        fund.total_paid += reward;
+        Ok(reward)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Coupon {
+    pub seller: Pubkey,
+    pub code_hash: [u8; 32],
+    pub discount_basis_points: u16,
+    pub max_uses: u32,
+    pub remaining_uses: u32,
+    pub expires_at: Option<i64>,
+    pub listing: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl Coupon {
+    /// Check the plaintext code and coupon scope, consume one use, and return the discounted amount
+    pub fn redeem(&mut self, code: &str, listing: &AccountLoader<DataListing>, amount: u64) -> Result<u64> {
+        require!(hashv(&[code.as_bytes()]).to_bytes() == self.code_hash, ErrorCode::InvalidCouponCode);
+        require!(self.seller == listing.load()?.owner, ErrorCode::CouponNotApplicable);
+        if let Some(coupon_listing) = self.listing {
+            require!(coupon_listing == listing.key(), ErrorCode::CouponNotApplicable);
+        }
+        if let Some(expires_at) = self.expires_at {
+            require!(Clock::get()?.unix_timestamp < expires_at, ErrorCode::CouponExpired);
+        }
+        require!(self.remaining_uses > 0, ErrorCode::CouponExhausted);
+
+        self.remaining_uses -= 1;
+
+        let discount = (amount as u128)
+            .checked_mul(self.discount_basis_points as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
+        amount.checked_sub(discount).ok_or(ErrorCode::ArithmeticOverflow.into())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PurchaseDelegate {
+    pub consumer: Pubkey,
+    pub delegate: Pubkey,
+    pub token_account: Pubkey,
+    pub spend_limit: u64,
+    pub spent: u64,
+    pub max_per_purchase: u64,
+    pub expires_at: i64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl PurchaseDelegate {
+    /// Check expiry and limits for a delegated purchase and record the spend
+    pub fn spend(&mut self, amount: u64) -> Result<()> {
+        require!(Clock::get()?.unix_timestamp < self.expires_at, ErrorCode::PurchaseDelegateExpired);
+        require!(amount <= self.max_per_purchase, ErrorCode::DelegateSpendLimitExceeded);
+        let spent = self.spent.checked_add(amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(spent <= self.spend_limit, ErrorCode::DelegateSpendLimitExceeded);
+        self.spent = spent;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ComputeJob {
+    pub job_id: u64,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub provider: Pubkey,
+    pub spec_hash: [u8; 32],
+    pub purpose: Purpose,
+    pub amount: u64,
+    pub status: ComputeJobStatus,
+    pub result_hash: [u8; 32],
+    #[max_len(128)]
+    pub result_arweave_tx_id: String,
+    pub created_at: i64,
+    pub result_deadline: i64,
+    pub result_posted_at: Option<i64>,
+    pub max_attestation_age: Option<i64>,
+    pub bump: u8,
+}
+
+impl ComputeJob {
+    pub const MAX_TIMEOUT: i64 = 30 * 24 * 60 * 60;
+    pub const CONFIRMATION_PERIOD: i64 = 3 * 24 * 60 * 60;
+    /// Allocated sizes of earlier layouts, oldest first; an account's layout version is the index of its size
+    pub const LEGACY_SPACES: &[usize] = &[376];
+
+    /// Decode an account written in legacy layout `version`, leaving fields added since at their defaults
+    pub fn from_legacy(_version: usize, data: &[u8]) -> Result<Self> {
+        let data = &mut &data[8..];
+        Ok(Self {
+            job_id: read_field(data)?,
+            listing: read_field(data)?,
+            buyer: read_field(data)?,
+            seller: read_field(data)?,
+            provider: read_field(data)?,
+            spec_hash: read_field(data)?,
+            purpose: read_field(data)?,
+            amount: read_field(data)?,
+            status: read_field(data)?,
+            result_hash: read_field(data)?,
+            result_arweave_tx_id: read_field(data)?,
+            created_at: read_field(data)?,
+            result_deadline: read_field(data)?,
+            result_posted_at: read_field(data)?,
+            max_attestation_age: None,
+            bump: read_field(data)?,
+        })
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct FlJob {
+    pub consumer: Pubkey,
+    pub job_id: u64,
+    pub data_type: IdentityDataType,
+    pub model_spec_hash: [u8; 32],
+    pub reward_pool: u64,
+    pub max_participants: u32,
+    pub participant_count: u32,
+    pub total_weight: u64,
+    pub opt_in_deadline: i64,
+    pub status: FlJobStatus,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct FlParticipant {
+    pub fl_job: Pubkey,
+    pub identity: Pubkey,
+    pub owner: Pubkey,
+    pub permission: Pubkey,
+    pub weight: u64,
+    pub update_hash: [u8; 32],
+    pub claimed: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DataUnion {
+    pub operator: Pubkey,
+    pub union_id: u64,
+    pub vault: Pubkey,
+    pub scope: u16,
+    pub member_count: u32,
+    pub members_in_scope: [u32; 9],
+    pub reward_per_member: [u128; 9],
+    pub total_proceeds: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl DataUnion {
+    pub const SCOPE_SLOTS: usize = 9;
+    pub const REWARD_SCALE: u128 = 1_000_000_000_000;
+
+    /// Split proceeds from a sale of `data_type` evenly across the members currently in that scope
+    pub fn credit(&mut self, data_type: &DataType, amount: u64) -> Result<()> {
+        let slot = data_type.index();
+        require!(self.members_in_scope[slot] > 0, ErrorCode::InvalidUnionScope);
+
+        let per_member = (amount as u128)
+            .checked_mul(Self::REWARD_SCALE)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / self.members_in_scope[slot] as u128;
+        self.reward_per_member[slot] = self.reward_per_member[slot]
+            .checked_add(per_member)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_proceeds += amount;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UnionMember {
+    pub data_union: Pubkey,
+    pub identity: Pubkey,
+    pub owner: Pubkey,
+    pub scope: u16,
+    pub reward_debt: [u128; 9],
+    pub claimable: u64,
+    pub is_active: bool,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+impl UnionMember {
+    /// Move proceeds accrued since the last settlement into `claimable`
+    pub fn settle(&mut self, data_union: &DataUnion) -> Result<()> {
+        for slot in 0..DataUnion::SCOPE_SLOTS {
+            if self.scope & (1 << slot) != 0 {
+                let accrued = (data_union.reward_per_member[slot] - self.reward_debt[slot]) / DataUnion::REWARD_SCALE;
+                self.claimable = self
+                    .claimable
+                    .checked_add(accrued as u64)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+                self.reward_debt[slot] = data_union.reward_per_member[slot];
+            }
+        }
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RebateProgram {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub started_at: i64,
+    pub epoch_duration: i64,
+    pub emission_per_epoch: u64,
+    /// Opened epochs' budgets not yet claimed
+    pub reserved: u64,
+    pub bump: u8,
+}
+
+impl RebateProgram {
+    /// Index of the epoch running at `now`
+    pub fn current_epoch(&self, now: i64) -> u64 {
+        (now.saturating_sub(self.started_at) / self.epoch_duration) as u64
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RebateEpoch {
+    pub epoch: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub budget: u64,
+    /// Fees credited to every ledger in the epoch; a fee counts once per side that opted in
+    pub fee_credits: u64,
+    pub claimed: u64,
+    pub bump: u8,
+}
+
+impl RebateEpoch {
+    pub fn is_current(&self, now: i64) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+
+    /// A ledger's share of the budget for `fee_credits` of the epoch's total
+    pub fn share(&self, fee_credits: u64) -> Result<u64> {
+        if self.fee_credits == 0 {
+            return Ok(0);
+        }
+        let share = (self.budget as u128)
+            .checked_mul(fee_credits as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / self.fee_credits as u128;
+        Ok(share as u64)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RebateLedger {
+    pub epoch: u64,
+    pub participant: Pubkey,
+    pub fee_credits: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BuybackConfig {
+    pub fee_mint: Pubkey,
+    pub protocol_mint: Pubkey,
+    pub input_vault: Pubkey,
+    pub output_vault: Pubkey,
+    pub parameters: BuybackParameters,
+    pub last_executed_at: i64,
+    pub total_spent: u64,
+    pub total_burned: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct BuybackParameters {
+    /// Program the swap route is sent to, e.g. Jupiter or Orca
+    pub dex_program: Pubkey,
+    /// Share of the accumulated fee balance each buyback spends
+    pub buyback_bps: u16,
+    /// Seconds between buybacks
+    pub interval: i64,
+    /// Largest shortfall accepted against the caller's quote
+    pub max_slippage_bps: u16,
+    /// Floor on protocol tokens received per fee token, scaled by `PRICE_SCALE`, so a stale or
+    /// low quote cannot sell below it; must be positive
+    pub min_price: u64,
+}
+
+impl BuybackParameters {
+    pub const PRICE_SCALE: u128 = 1_000_000_000;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (1..=10000).contains(&self.buyback_bps)
+                && self.max_slippage_bps <= 10000
+                && self.interval >= 0
+                && self.min_price > 0,
+            ErrorCode::InvalidBuybackConfig
+        );
+        Ok(())
+    }
+
+    /// Least output accepted for `spent` fee tokens: the caller's quote less slippage, and never
+    /// under the price floor
+    pub fn min_out(&self, spent: u64, quoted_out: u64) -> Result<u64> {
+        let quoted = quoted_out as u128 * (10000 - self.max_slippage_bps as u128) / 10000;
+        let floor = (spent as u128)
+            .checked_mul(self.min_price as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            / Self::PRICE_SCALE;
+        u64::try_from(quoted.max(floor)).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CurationConfig {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub started_at: i64,
+    pub epoch_duration: i64,
+    /// Protocol tokens bonded per vote
+    pub vote_bond: u64,
+    /// Seconds after an epoch ends during which its curated listings may be flagged as disputed
+    pub dispute_window: i64,
+    pub bump: u8,
+}
+
+impl CurationConfig {
+    /// Index of the epoch running at `now`
+    pub fn current_epoch(&self, now: i64) -> u64 {
+        (now.saturating_sub(self.started_at) / self.epoch_duration) as u64
+    }
+
+    /// Start and end of `epoch`
+    pub fn epoch_bounds(&self, epoch: u64) -> (i64, i64) {
+        let starts_at = self.started_at + epoch as i64 * self.epoch_duration;
+        (starts_at, starts_at + self.epoch_duration)
+    }
+}
+
+/// The most-voted listings of an epoch, most votes first; front ends feature the set of the last
+/// ended epoch
+#[account]
+#[derive(InitSpace)]
+pub struct FeaturedSet {
+    pub epoch: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    #[max_len(10)]
+    pub listings: Vec<FeaturedListing>,
+    pub bump: u8,
+}
+
+impl FeaturedSet {
+    pub const MAX_LISTINGS: usize = 10;
+
+    /// Update `listing`'s votes, entering it in place of the least-voted listing once the set is full
+    pub fn record(&mut self, listing: Pubkey, votes: u64) {
+        if let Some(entry) = self.listings.iter_mut().find(|entry| entry.listing == listing) {
+            entry.votes = votes;
+        } else if self.listings.len() < Self::MAX_LISTINGS {
+            self.listings.push(FeaturedListing { listing, votes });
+        } else if let Some(last) = self.listings.last_mut().filter(|last| last.votes < votes) {
+            *last = FeaturedListing { listing, votes };
+        }
+        self.listings.sort_by_key(|entry| std::cmp::Reverse(entry.votes));
+    }
+
+    pub fn remove(&mut self, listing: &Pubkey) {
+        self.listings.retain(|entry| entry.listing != *listing);
+    }
+
+    pub fn contains(&self, listing: &Pubkey) -> bool {
+        self.listings.iter().any(|entry| entry.listing == *listing)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct FeaturedListing {
+    pub listing: Pubkey,
+    pub votes: u64,
+}
+
+/// Machine-readable terms a buyer takes a listing's data under
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct LicenseTerms {
+    pub commercial_use: bool,
+    /// Use is limited to research; excludes commercial use
+    pub research_only: bool,
+    /// Buyers may pass the data on to third parties
+    pub redistribution: bool,
+    /// Days buyers may keep the data after purchase; unlimited when unset
+    pub retention_days: Option<u32>,
+}
+
+impl LicenseTerms {
+    /// Symbol of license NFTs
+    pub const NFT_SYMBOL: &'static str = "DSLIC";
+
+    pub fn is_valid(&self) -> bool {
+        !(self.research_only && self.commercial_use) && self.retention_days != Some(0)
+    }
+
+    /// When rights bought at `purchased_at` lapse; never without a retention limit
+    pub fn expires_at(&self, purchased_at: i64) -> Result<Option<i64>> {
+        self.retention_days
+            .map(|days| {
+                i64::from(days)
+                    .checked_mul(86_400)
+                    .and_then(|seconds| purchased_at.checked_add(seconds))
+                    .ok_or_else(|| error!(ErrorCode::ArithmeticOverflow))
+            })
+            .transpose()
+    }
+}
+
+/// License terms maintained by the marketplace authority (governance) for listings to reference
+#[account]
+#[derive(InitSpace)]
+pub struct LicenseTemplate {
+    pub template_id: u32,
+    pub terms: LicenseTerms,
+    /// SHA-256 of the full license text the terms summarize
+    pub terms_hash: [u8; 32],
+    /// Whether listings may newly adopt the template
+    pub is_active: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// A key the marketplace authority trusts to check listings' stored data
+#[account]
+#[derive(InitSpace)]
+pub struct StorageOracle {
+    pub oracle: Pubkey,
+    pub check_count: u64,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+/// Subsystems enabled on this cluster, one bit each. Absent until the authority first sets it,
+/// which leaves every flagged subsystem off.
+#[account]
+#[derive(InitSpace)]
+pub struct FeatureFlags {
+    pub flags: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl FeatureFlags {
+    /// Compute-to-data jobs; jobs already created still settle while it is off
+    pub const COMPUTE_TO_DATA: u64 = 1 << 0;
+    /// Every bit a subsystem checks
+    pub const KNOWN: u64 = Self::COMPUTE_TO_DATA;
+
+    pub fn is_enabled(&self, flag: u64) -> bool {
+        self.flags & flag == flag
+    }
+}
+
+/// Open, high, low and close sale prices of one data type in one settlement mint over a UTC day,
+/// for charting market history from chain state. Custom data types share one candle.
+#[account]
+#[derive(InitSpace)]
+pub struct PriceCandle {
+    pub mint: Pubkey,
+    /// `DataType::index` of the data type sold
+    pub data_type_index: u8,
+    /// Days since the unix epoch
+    pub day: i64,
+    pub open: u64,
+    pub high: u64,
+    pub low: u64,
+    pub close: u64,
+    /// Sum of the day's sale prices
+    pub volume: u64,
+    pub trade_count: u32,
+    pub bump: u8,
+}
+
+impl PriceCandle {
+    pub const DURATION: i64 = 86_400;
+
+    /// UTC day containing `timestamp`
+    pub fn day_of(timestamp: i64) -> i64 {
+        timestamp.div_euclid(Self::DURATION)
+    }
+
+    /// Fold a sale at `price` into the candle, opening it on the day's first sale
+    pub fn record(&mut self, mint: Pubkey, data_type: &DataType, now: i64, price: u64, bump: u8) -> Result<()> {
+        if self.trade_count == 0 {
+            self.mint = mint;
+            self.data_type_index = data_type.index() as u8;
+            self.day = Self::day_of(now);
+            self.open = price;
+            self.high = price;
+            self.low = price;
+            self.bump = bump;
+        }
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume = self.volume.checked_add(price).ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.trade_count = self.trade_count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Lifetime revenue an identity's data has earned its owner, net of marketplace fees, from sales,
+/// escrowed proceeds as they are released, and compute jobs, so owners can audit their earnings
+/// from chain state alone.
+#[account]
+#[derive(InitSpace)]
+pub struct EarningsLedger {
+    pub identity: Pubkey,
+    /// Earnings per `DataType::index`; custom data types share one slot
+    pub by_data_type: [u64; 9],
+    /// Earnings per paying consumer, in order of first payment
+    #[max_len(32)]
+    pub by_consumer: Vec<ConsumerEarnings>,
+    /// Earnings from consumers who paid after `by_consumer` filled up
+    pub other_consumers: u64,
+    pub total: u64,
+    pub payment_count: u32,
+    pub last_paid_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct ConsumerEarnings {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub payment_count: u32,
+}
+
+impl EarningsLedger {
+    pub const MAX_CONSUMERS: usize = 32;
+
+    /// Credit `amount` paid by `consumer` for `data_type`, opening the ledger on its first payment
+    pub fn record(
+        &mut self,
+        identity: Pubkey,
+        data_type: &DataType,
+        consumer: Pubkey,
+        amount: u64,
+        now: i64,
+        bump: u8,
+    ) -> Result<()> {
+        if self.payment_count == 0 {
+            self.identity = identity;
+            self.bump = bump;
+        }
+        let slot = &mut self.by_data_type[data_type.index()];
+        *slot = slot.checked_add(amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        match self.by_consumer.iter().position(|entry| entry.consumer == consumer) {
+            Some(index) => {
+                let entry = &mut self.by_consumer[index];
+                entry.amount = entry.amount.checked_add(amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+                entry.payment_count += 1;
+            }
+            None if self.by_consumer.len() < Self::MAX_CONSUMERS => self.by_consumer.push(ConsumerEarnings {
+                consumer,
+                amount,
+                payment_count: 1,
+            }),
+            None => {
+                self.other_consumers = self
+                    .other_consumers
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+        }
+
+        self.total = self.total.checked_add(amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.payment_count = self.payment_count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.last_paid_at = now;
+        Ok(())
+    }
+}
+
+/// Outcome of the latest storage oracle check of a listing's data
+#[account]
+#[derive(InitSpace)]
+pub struct StorageHealth {
+    pub listing: Pubkey,
+    /// The listing's data hash when checked; zeroed if the data was uncommitted
+    pub data_hash: [u8; 32],
+    pub last_checked_at: i64,
+    pub last_oracle: Pubkey,
+    /// Whether the data resolved and matched `data_hash`
+    pub healthy: bool,
+    /// Failed checks since the last passing one
+    pub consecutive_failures: u32,
+    pub check_count: u64,
+    pub bump: u8,
+}
+
+/// Curation votes for a listing in an epoch
+#[account]
+#[derive(InitSpace)]
+pub struct ListingTally {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    /// Staking weight of every curator who voted
+    pub votes: u64,
+    pub curators: u32,
+    /// When the dispute window closes and bonds can be settled
+    pub settles_at: i64,
+    pub disputed: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CurationVote {
+    pub curator: Pubkey,
+    pub tally: Pubkey,
+    pub weight: u64,
+    pub bond: u64,
+    pub bump: u8,
+}
+
+/// Opening of a private listing's price commitment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct PriceReveal {
+    pub price: u64,
+    /// The commitment's blinding scalar, little-endian
+    pub salt: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum DataType {
+    LocationHistory,
+    AppUsage,
+    PurchaseHistory,
+    HealthData,
+    SocialMediaActivity,
+    SearchHistory,
+    Custom(#[max_len(32)] String),
+    // Appended after Custom to keep existing variant indices stable
+    FinancialData,
+    CommunicationData,
+}
+
+impl DataType {
+    /// Longest custom data type name a listing can hold
+    pub const MAX_CUSTOM_LEN: usize = 32;
+
+    /// The data type at variant `index`, named `custom_name` if it is a custom one
+    pub fn from_index(index: u8, custom_name: &str) -> Self {
+        match index {
+            0 => DataType::LocationHistory,
+            1 => DataType::AppUsage,
+            2 => DataType::PurchaseHistory,
+            3 => DataType::HealthData,
+            4 => DataType::SocialMediaActivity,
+            5 => DataType::SearchHistory,
+            7 => DataType::FinancialData,
+            8 => DataType::CommunicationData,
+            _ => DataType::Custom(custom_name.to_string()),
+        }
+    }
+
+    /// Whether a custom name fits the space reserved for it
+    pub fn fits(&self) -> bool {
+        match self {
+            DataType::Custom(name) => name.len() <= Self::MAX_CUSTOM_LEN,
+            _ => true,
+        }
+    }
+
+    /// Convert marketplace DataType to the identity program's DataType
+    pub fn to_identity_data_type(&self) -> IdentityDataType {
+        match self {
+            DataType::LocationHistory => IdentityDataType::LocationHistory,
+            DataType::AppUsage => IdentityDataType::AppUsage,
+            DataType::PurchaseHistory => IdentityDataType::PurchaseHistory,
+            DataType::HealthData => IdentityDataType::HealthData,
+            DataType::SocialMediaActivity => IdentityDataType::SocialMediaActivity,
+            DataType::SearchHistory => IdentityDataType::SearchHistory,
+            DataType::Custom(_) => IdentityDataType::Custom,
+            DataType::FinancialData => IdentityDataType::FinancialData,
+            DataType::CommunicationData => IdentityDataType::CommunicationData,
+        }
+    }
+
+    /// Position of this data type in per-type masks and tables; all custom types share one slot
+    pub fn index(&self) -> usize {
+        match self {
+            DataType::LocationHistory => 0,
+            DataType::AppUsage => 1,
+            DataType::PurchaseHistory => 2,
+            DataType::HealthData => 3,
+            DataType::SocialMediaActivity => 4,
+            DataType::SearchHistory => 5,
+            DataType::Custom(_) => 6,
+            DataType::FinancialData => 7,
+            DataType::CommunicationData => 8,
+        }
+    }
+
+    /// Bit used for this data type in `Marketplace::halted_data_types`
+    pub fn halt_mask(&self) -> u16 {
+        1 << self.index()
+    }
+
+    /// Bitmask covering the given data types
+    pub fn mask(data_types: &[DataType]) -> u16 {
+        data_types.iter().fold(0, |mask, data_type| mask | data_type.halt_mask())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ComputeJobStatus {
+    Pending,
+    ResultPosted,
+    Completed,
+    Refunded,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum FlJobStatus {
+    Open,
+    Finalized,
+    Cancelled,
+}
+
+#[event]
+pub struct DataTypeHaltUpdatedEvent {
+    pub data_type: DataType,
+    pub halted: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct FeatureFlagsUpdatedEvent {
+    pub flags: u64,
+    pub updated_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ExpiredSweptEvent {
+    pub caller: Pubkey,
+    pub swept: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct RentReclaimedEvent {
+    pub account: Pubkey,
+    pub rent_payer: Pubkey,
+    pub caller: Pubkey,
+    pub refund: u64,
+    pub reward: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct SettlementMintsUpdatedEvent {
+    pub stablecoin_only: bool,
+    pub approved_mints: Vec<Pubkey>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct RevocationRefundedEvent {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub revoked_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ComputeJobCreatedEvent {
+    pub job: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub provider: Pubkey,
+    pub spec_hash: [u8; 32],
+    pub amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ComputeResultPostedEvent {
+    pub job: Pubkey,
+    pub result_hash: [u8; 32],
+    pub result_arweave_tx_id: String,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct FlJobCreatedEvent {
+    pub fl_job: Pubkey,
+    pub consumer: Pubkey,
+    pub model_spec_hash: [u8; 32],
+    pub reward_pool: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct FlContributionRecordedEvent {
+    pub fl_job: Pubkey,
+    pub identity: Pubkey,
+    pub weight: u64,
+    pub update_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DataUnionMembershipEvent {
+    pub data_union: Pubkey,
+    pub identity: Pubkey,
+    pub scope: u16,
+    pub joined: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct PurchaseWatermarkEvent {
+    pub receipt: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub watermark_commitment: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct MarketplaceFeeUpdatedEvent {
+    pub fee_basis_points: u16,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct MarketplaceAuthorityChangedEvent {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct RebateEmissionUpdatedEvent {
+    pub emission_per_epoch: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct RebateClaimedEvent {
+    pub epoch: u64,
+    pub participant: Pubkey,
+    pub fee_credits: u64,
+    pub amount: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct BuybackExecutedEvent {
+    pub spent: u64,
+    pub burned: u64,
+    pub executed_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ListingCuratedEvent {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    pub curator: Pubkey,
+    pub weight: u64,
+    pub votes: u64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct CuratedListingDisputedEvent {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    pub curators: u32,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct CurationVoteSettledEvent {
+    pub epoch: u64,
+    pub listing: Pubkey,
+    pub curator: Pubkey,
+    pub bond: u64,
+    pub forfeited: bool,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ListingFreshnessAttestedEvent {
+    pub listing: Pubkey,
+    pub attestor: Pubkey,
+    pub refreshed_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct ListingDataUpdatedEvent {
+    pub listing: Pubkey,
+    pub data_pointer: StoragePointer,
+    /// Zeroed when the replaced data was uncommitted
+    pub previous_hash: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub updated_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct LicenseTemplateCreatedEvent {
+    pub template_id: u32,
+    pub terms: LicenseTerms,
+    pub terms_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct LicenseMintedEvent {
+    pub receipt: Pubkey,
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub template_id: u32,
+    pub transferable: bool,
+    pub expires_at: Option<i64>,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct AccessRightTransferredEvent {
+    pub receipt: Pubkey,
+    pub listing: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub transferred_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct StorageCheckedEvent {
+    pub listing: Pubkey,
+    pub oracle: Pubkey,
+    pub data_hash: [u8; 32],
+    pub healthy: bool,
+    pub checked_at: i64,
+    pub event_sequence: u64,
+    pub event_slot: u64,
+}
+
+#[event]
+pub struct DataManifestSealedEvent {
+    pub listing: Pubkey,
+    pub chunk_count: u32,
+    pub total_size: u64,
+    pub manifest_hash: [u8; 32],
+    pub event_sequence: u64,
+    pub event_slot: u64,
 }
 
 #[error_code]
@@ -448,4 +7611,204 @@ pub enum ErrorCode {
     PermissionExpired,
     #[msg("Arithmetic overflow")]
     ArithmeticOverflow,
+    #[msg("Buyer is not on the listing allowlist")]
+    BuyerNotAllowlisted,
+    #[msg("Private listings require a price reveal")]
+    PriceRevealRequired,
+    #[msg("Revealed price does not match the commitment")]
+    PriceCommitmentMismatch,
+    #[msg("Allowlist must contain at least one buyer")]
+    EmptyAllowlist,
+    #[msg("Too many allowlisted buyers (max 16)")]
+    AllowlistTooLong,
+    #[msg("Operation not supported on private listings")]
+    PrivateListing,
+    #[msg("A fee transfer proof is required when marketplace fees are enabled")]
+    FeeProofRequired,
+    #[msg("Trading is halted for this data type")]
+    DataTypeHalted,
+    #[msg("Seller verification level is below the compliance minimum for this data type")]
+    SellerLevelTooLow,
+    #[msg("Buyer verification level is below the compliance minimum for this data type")]
+    BuyerLevelTooLow,
+    #[msg("Listing has expired")]
+    ListingExpired,
+    #[msg("Account must be writable")]
+    AccountNotWritable,
+    #[msg("Buyer is blacklisted")]
+    BuyerBlacklisted,
+    #[msg("Buyer stake is below the requirement for this data type")]
+    InsufficientConsumerStake,
+    #[msg("Listing requires a business-verified buyer")]
+    BuyerNotBusinessVerified,
+    #[msg("Invalid discount terms")]
+    InvalidDiscount,
+    #[msg("Coupon code is missing or does not match")]
+    InvalidCouponCode,
+    #[msg("Coupon does not apply to this listing")]
+    CouponNotApplicable,
+    #[msg("Coupon has expired")]
+    CouponExpired,
+    #[msg("Coupon has no remaining uses")]
+    CouponExhausted,
+    #[msg("Recipient does not own the buyer identity")]
+    RecipientMismatch,
+    #[msg("Invalid purchase delegate terms")]
+    InvalidDelegateTerms,
+    #[msg("Purchase delegate account required")]
+    PurchaseDelegateRequired,
+    #[msg("Purchase delegate has expired")]
+    PurchaseDelegateExpired,
+    #[msg("Purchase exceeds the delegate's spend limit")]
+    DelegateSpendLimitExceeded,
+    #[msg("Settlement mint is not on the approved stablecoin list")]
+    SettlementMintNotApproved,
+    #[msg("Approved mint list must be non-empty in stablecoin-only mode and within capacity")]
+    TooManyApprovedMints,
+    #[msg("Access under this receipt was revoked by the identity owner")]
+    ReceiptAccessRevoked,
+    #[msg("Escrow account must be supplied exactly when a refund window is configured")]
+    EscrowAccountMismatch,
+    #[msg("Refund window is out of range")]
+    InvalidRefundWindow,
+    #[msg("No vested proceeds to release")]
+    NothingToRelease,
+    #[msg("Refund already claimed")]
+    RefundAlreadyClaimed,
+    #[msg("Access under this receipt has not been revoked")]
+    AccessNotRevoked,
+    #[msg("Access was revoked after the refund window closed")]
+    RefundWindowClosed,
+    #[msg("Declared purpose is not permitted by the identity owner")]
+    PurposeNotPermitted,
+    #[msg("Declared purpose is not allowed for this listing")]
+    PurposeNotAllowedByListing,
+    #[msg("At least one purpose is required")]
+    NoPurposes,
+    #[msg("Compute job timeout is out of range")]
+    InvalidComputeTimeout,
+    #[msg("Compute job is not in the required status")]
+    InvalidComputeJobStatus,
+    #[msg("Compute job result deadline has passed")]
+    ComputeJobTimedOut,
+    #[msg("Compute job result deadline has not passed")]
+    ComputeJobNotTimedOut,
+    #[msg("Buyer confirmation period is still active")]
+    ConfirmationPeriodActive,
+    #[msg("Arweave transaction ID too long")]
+    ArweaveTxIdTooLong,
+    #[msg("Invalid federated-learning job terms")]
+    InvalidFlJobTerms,
+    #[msg("Federated-learning job is not in the required status")]
+    InvalidFlJobStatus,
+    #[msg("Federated-learning opt-in period has closed")]
+    FlOptInClosed,
+    #[msg("Federated-learning job is full")]
+    FlJobFull,
+    #[msg("Federated-learning reward already claimed")]
+    FlRewardAlreadyClaimed,
+    #[msg("Compute job requires a TEE attestation from the provider")]
+    TeeAttestationRequired,
+    #[msg("Provider's TEE attestation is unverified or too old")]
+    TeeAttestationStale,
+    #[msg("Data union scope is empty or outside the union's data types")]
+    InvalidUnionScope,
+    #[msg("Data union account does not match the listing")]
+    DataUnionMismatch,
+    #[msg("Identity is not an active union member")]
+    NotUnionMember,
+    #[msg("Data trust and membership accounts are required")]
+    TrustRequired,
+    #[msg("Identity is not a member of this data trust")]
+    NotTrustMember,
+    #[msg("Signer is not a trustee of this data trust")]
+    NotTrustee,
+    #[msg("Guardian co-signature required for a ward identity")]
+    GuardianSignatureRequired,
+    #[msg("Custom data type name too long")]
+    CustomDataTypeTooLong,
+    #[msg("Listing description too long")]
+    DescriptionTooLong,
+    #[msg("Account is not in an earlier layout of this type")]
+    NotLegacyAccount,
+    #[msg("Fee basis points must be at most 10000")]
+    InvalidFeeBasisPoints,
+    #[msg("Payout token account does not belong to the listing's payout wallet")]
+    InvalidPayoutAccount,
+    #[msg("Rebate epoch duration must be positive")]
+    InvalidRebateConfig,
+    #[msg("Rebate epoch is not the one currently running")]
+    RebateEpochNotCurrent,
+    #[msg("Rebate epoch has not ended")]
+    RebateEpochOpen,
+    #[msg("Buyback share must be 1-10000 basis points, slippage at most 10000, the interval non-negative and the price floor positive")]
+    InvalidBuybackConfig,
+    #[msg("Buyback interval has not elapsed")]
+    BuybackNotDue,
+    #[msg("Swap returned fewer protocol tokens than the quote and price floor allow")]
+    BuybackSlippageExceeded,
+    #[msg("Curation epoch duration must be positive and the dispute window non-negative")]
+    InvalidCurationConfig,
+    #[msg("Curation epoch is not the one currently running")]
+    CurationEpochNotCurrent,
+    #[msg("Curator has no staking weight")]
+    NoCurationWeight,
+    #[msg("Curated listing has been flagged as disputed")]
+    CuratedListingDisputed,
+    #[msg("Dispute window for this curated listing has closed")]
+    CurationDisputeWindowClosed,
+    #[msg("Curation bond is locked until the dispute window closes")]
+    CurationBondLocked,
+    #[msg("Storage reference is not well formed for its backend")]
+    InvalidStoragePointer,
+    #[msg("Data manifest is sealed")]
+    ManifestSealed,
+    #[msg("Data manifest has too many chunks")]
+    ManifestTooLong,
+    #[msg("Data manifest chunks must not be empty")]
+    EmptyManifestChunk,
+    #[msg("Data manifest has no chunks")]
+    EmptyManifest,
+    #[msg("Listing preview must commit to the sample's hash")]
+    MissingPreviewHash,
+    #[msg("Signer does not attest this listing's freshness")]
+    NotFreshnessAttestor,
+    #[msg("Refresh time is in the future or before the last refresh")]
+    InvalidRefreshTime,
+    #[msg("Listing data is staler than the buyer allows")]
+    ListingStale,
+    #[msg("Listing data updates must commit to the content's hash")]
+    MissingDataHash,
+    #[msg("Listing data hash matches the current data")]
+    ListingDataUnchanged,
+    #[msg("Listing has no data to check")]
+    NoListingData,
+    #[msg("Listing sales are paused until its storage passes a check")]
+    ListingStoragePaused,
+    #[msg("License terms are contradictory or allow no retention")]
+    InvalidLicenseTerms,
+    #[msg("License template is retired")]
+    LicenseTemplateInactive,
+    #[msg("Purchase was not licensed under this template")]
+    UnlicensedReceipt,
+    #[msg("Access rights bought from this listing cannot be transferred")]
+    RightsNotTransferable,
+    #[msg("Recipient already holds the access right")]
+    RecipientAlreadyHolds,
+    #[msg("Feature is disabled on this cluster")]
+    FeatureDisabled,
+    #[msg("Feature flags set a bit no subsystem checks")]
+    UnknownFeatureFlag,
+    #[msg("Account is not finalized long enough to reclaim its rent")]
+    NotReclaimable,
+    #[msg("Confidential purchases are only available for private listings")]
+    ConfidentialPublicListing,
+    #[msg("A self-trade earns rebate credits for the buyer only; leave out the seller's ledger")]
+    SelfTradeRebate,
+    #[msg("Proof context account is not a verified proof of the expected type")]
+    InvalidProofContext,
+    #[msg("Confidential transfers do not add up to the listing's committed price and fee")]
+    ConfidentialAmountMismatch,
+    #[msg("Identity ID too long")]
+    IdentityIdTooLong,
 }
@@ -0,0 +1,472 @@
+//! Every account, filled to the limits the program enforces, must serialize within the space `InitSpace` derives for it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use bytemuck::Zeroable;
+use datasov_identity::{DataType as IdentityDataType, Purpose, StorageBackend, StoragePointer};
+use datasov_solana::{
+    BuybackConfig, BuybackParameters, ComputeJob, ComputeJobStatus, ConsumerEarnings, Coupon, CrankFund,
+    CurationConfig, CurationVote, DataListing, DataManifest, DataType, DataUnion, EarningsLedger, FeatureFlags,
+    FeaturedListing, FeaturedSet, FlJob, FlJobStatus, FlParticipant, LicenseTemplate, LicenseTerms, ListingAllowlist,
+    ListingPreview, ListingTally, ManifestChunk, Marketplace, PriceCandle, PurchaseDelegate, PurchaseReceipt,
+    RebateEpoch, RebateLedger, RebateProgram, StorageHealth, StorageOracle, UnionMember,
+};
+use proptest::option::weighted;
+use proptest::prelude::*;
+
+/// Serialize with the discriminator, check the encoding fits and round-trips
+fn assert_fits<T: AccountSerialize + AccountDeserialize + Space>(account: &T) {
+    let len = 8 + T::INIT_SPACE;
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= len, "{} bytes serialized, {} allocated", data.len(), len);
+
+    let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+    let mut reencoded = Vec::new();
+    decoded.try_serialize(&mut reencoded).unwrap();
+    assert_eq!(data, reencoded);
+}
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+/// ASCII string of exactly `len` bytes, the longest the program accepts
+fn text(len: usize) -> impl Strategy<Value = String> {
+    proptest::collection::vec(0x20u8..0x7f, len).prop_map(|bytes| String::from_utf8(bytes).unwrap())
+}
+
+/// Mostly `Some`, the larger encoding
+fn optional<S: Strategy>(strategy: S) -> impl Strategy<Value = Option<S::Value>> {
+    weighted(0.9, strategy)
+}
+
+fn data_type() -> impl Strategy<Value = DataType> {
+    prop_oneof![
+        Just(DataType::LocationHistory),
+        Just(DataType::AppUsage),
+        Just(DataType::PurchaseHistory),
+        Just(DataType::HealthData),
+        Just(DataType::SocialMediaActivity),
+        Just(DataType::SearchHistory),
+        Just(DataType::FinancialData),
+        Just(DataType::CommunicationData),
+        text(DataType::MAX_CUSTOM_LEN).prop_map(DataType::Custom),
+    ]
+}
+
+fn storage_pointer() -> impl Strategy<Value = StoragePointer> {
+    (
+        proptest::sample::select(StorageBackend::ALL.to_vec()),
+        text(StoragePointer::MAX_REFERENCE_LEN),
+    )
+        .prop_map(|(backend, reference)| StoragePointer { backend, reference })
+}
+
+fn purpose() -> impl Strategy<Value = Purpose> {
+    prop_oneof![
+        Just(Purpose::Research),
+        Just(Purpose::Analytics),
+        Just(Purpose::Marketing),
+        Just(Purpose::Advertising),
+        Just(Purpose::ProductDevelopment),
+        Just(Purpose::Personalization),
+        Just(Purpose::Compliance),
+        Just(Purpose::ModelTraining),
+        Just(Purpose::Other),
+    ]
+}
+
+#[test]
+fn fixed_size_accounts_fit() {
+    assert_fits(&CrankFund {
+        authority: Pubkey::new_unique(),
+        reward_per_account: u64::MAX,
+        total_swept: u64::MAX,
+        total_paid: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&PurchaseDelegate {
+        consumer: Pubkey::new_unique(),
+        delegate: Pubkey::new_unique(),
+        token_account: Pubkey::new_unique(),
+        spend_limit: u64::MAX,
+        spent: u64::MAX,
+        max_per_purchase: u64::MAX,
+        expires_at: i64::MAX,
+        created_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&FlJob {
+        consumer: Pubkey::new_unique(),
+        job_id: u64::MAX,
+        data_type: IdentityDataType::CommunicationData,
+        model_spec_hash: [u8::MAX; 32],
+        reward_pool: u64::MAX,
+        max_participants: u32::MAX,
+        participant_count: u32::MAX,
+        total_weight: u64::MAX,
+        opt_in_deadline: i64::MAX,
+        status: FlJobStatus::Cancelled,
+        created_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&FlParticipant {
+        fl_job: Pubkey::new_unique(),
+        identity: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        permission: Pubkey::new_unique(),
+        weight: u64::MAX,
+        update_hash: [u8::MAX; 32],
+        claimed: true,
+        bump: u8::MAX,
+    });
+    assert_fits(&DataUnion {
+        operator: Pubkey::new_unique(),
+        union_id: u64::MAX,
+        vault: Pubkey::new_unique(),
+        scope: u16::MAX,
+        member_count: u32::MAX,
+        members_in_scope: [u32::MAX; DataUnion::SCOPE_SLOTS],
+        reward_per_member: [u128::MAX; DataUnion::SCOPE_SLOTS],
+        total_proceeds: u64::MAX,
+        created_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&UnionMember {
+        data_union: Pubkey::new_unique(),
+        identity: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        scope: u16::MAX,
+        reward_debt: [u128::MAX; DataUnion::SCOPE_SLOTS],
+        claimable: u64::MAX,
+        is_active: true,
+        joined_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&RebateProgram {
+        mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        started_at: i64::MAX,
+        epoch_duration: i64::MAX,
+        emission_per_epoch: u64::MAX,
+        reserved: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&RebateEpoch {
+        epoch: u64::MAX,
+        starts_at: i64::MAX,
+        ends_at: i64::MAX,
+        budget: u64::MAX,
+        fee_credits: u64::MAX,
+        claimed: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&RebateLedger {
+        epoch: u64::MAX,
+        participant: Pubkey::new_unique(),
+        fee_credits: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&BuybackConfig {
+        fee_mint: Pubkey::new_unique(),
+        protocol_mint: Pubkey::new_unique(),
+        input_vault: Pubkey::new_unique(),
+        output_vault: Pubkey::new_unique(),
+        parameters: BuybackParameters {
+            dex_program: Pubkey::new_unique(),
+            buyback_bps: u16::MAX,
+            interval: i64::MAX,
+            max_slippage_bps: u16::MAX,
+            min_price: u64::MAX,
+        },
+        last_executed_at: i64::MAX,
+        total_spent: u64::MAX,
+        total_burned: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&CurationConfig {
+        mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        started_at: i64::MAX,
+        epoch_duration: i64::MAX,
+        vote_bond: u64::MAX,
+        dispute_window: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&ListingTally {
+        epoch: u64::MAX,
+        listing: Pubkey::new_unique(),
+        votes: u64::MAX,
+        curators: u32::MAX,
+        settles_at: i64::MAX,
+        disputed: true,
+        bump: u8::MAX,
+    });
+    assert_fits(&CurationVote {
+        curator: Pubkey::new_unique(),
+        tally: Pubkey::new_unique(),
+        weight: u64::MAX,
+        bond: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&StorageOracle {
+        oracle: Pubkey::new_unique(),
+        check_count: u64::MAX,
+        added_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&FeatureFlags {
+        flags: u64::MAX,
+        updated_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&PriceCandle {
+        mint: Pubkey::new_unique(),
+        data_type_index: u8::MAX,
+        day: i64::MAX,
+        open: u64::MAX,
+        high: u64::MAX,
+        low: u64::MAX,
+        close: u64::MAX,
+        volume: u64::MAX,
+        trade_count: u32::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&EarningsLedger {
+        identity: Pubkey::new_unique(),
+        by_data_type: [u64::MAX; 9],
+        by_consumer: vec![
+            ConsumerEarnings {
+                consumer: Pubkey::new_unique(),
+                amount: u64::MAX,
+                payment_count: u32::MAX,
+            };
+            EarningsLedger::MAX_CONSUMERS
+        ],
+        other_consumers: u64::MAX,
+        total: u64::MAX,
+        payment_count: u32::MAX,
+        last_paid_at: i64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&StorageHealth {
+        listing: Pubkey::new_unique(),
+        data_hash: [u8::MAX; 32],
+        last_checked_at: i64::MAX,
+        last_oracle: Pubkey::new_unique(),
+        healthy: true,
+        consecutive_failures: u32::MAX,
+        check_count: u64::MAX,
+        bump: u8::MAX,
+    });
+    assert_fits(&LicenseTemplate {
+        template_id: u32::MAX,
+        terms: LicenseTerms {
+            commercial_use: true,
+            research_only: true,
+            redistribution: true,
+            retention_days: Some(u32::MAX),
+        },
+        terms_hash: [u8::MAX; 32],
+        is_active: true,
+        created_at: i64::MAX,
+        bump: u8::MAX,
+    });
+}
+
+proptest! {
+    #[test]
+    fn marketplace_fits(approved_mints in proptest::collection::vec(pubkey(), Marketplace::MAX_APPROVED_MINTS)) {
+        let marketplace = Marketplace {
+            authority: Pubkey::new_unique(),
+            fee_basis_points: u16::MAX,
+            total_listings: u64::MAX,
+            total_volume: u64::MAX,
+            halted_data_types: u16::MAX,
+            stablecoin_only: true,
+            approved_mints,
+            refund_window: i64::MAX,
+            event_sequence: u64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&marketplace);
+    }
+
+    #[test]
+    fn data_listing_fits(
+        data_type in data_type(),
+        description in text(200),
+        identity_id in text(64),
+        (sold_at, cancelled_at, buyer) in (optional(any::<i64>()), optional(any::<i64>()), optional(pubkey())),
+        expires_at in optional(any::<i64>()),
+        union in optional(pubkey()),
+        data_pointer in optional(storage_pointer()),
+        preview_pointer in optional(storage_pointer()),
+        freshness_oracle in optional(pubkey()),
+    ) {
+        let mut listing = DataListing::zeroed();
+        listing.id = u64::MAX;
+        listing.owner = Pubkey::new_unique();
+        listing.price = u64::MAX;
+        listing.is_active = 1;
+        listing.set_data_type(&data_type).unwrap();
+        listing.set_description(&description).unwrap();
+        listing.set_identity_id(&identity_id).unwrap();
+        listing.created_at = i64::MAX;
+        listing.set_sold_at(sold_at);
+        listing.set_cancelled_at(cancelled_at);
+        listing.set_buyer(buyer);
+        listing.is_private = 1;
+        listing.price_commitment = [u8::MAX; 32];
+        listing.description_hash = [u8::MAX; 32];
+        listing.set_expires_at(expires_at);
+        listing.requires_kyb = 1;
+        listing.allowed_purposes = u16::MAX;
+        listing.set_union(union);
+        listing.payout_wallet = Pubkey::new_unique();
+        listing.data_pointer.set(data_pointer.as_ref()).unwrap();
+        listing.data_hash = [u8::MAX; 32];
+        let preview = preview_pointer.map(|pointer| ListingPreview { pointer, hash: [u8::MAX; 32] });
+        listing.set_preview(preview.as_ref()).unwrap();
+        listing.last_refreshed_at = i64::MAX;
+        listing.set_freshness_oracle(freshness_oracle);
+        listing.storage_paused = 1;
+        listing.set_license_template(Some(u32::MAX));
+        listing.rights_transferable = 1;
+        listing.rent_payer = Pubkey::new_unique();
+        listing.bump = u8::MAX;
+
+        // Zero-copy: the account is the listing's bytes after the discriminator
+        let mut data = DataListing::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&listing));
+        assert_eq!(data.len(), 8 + DataListing::INIT_SPACE);
+        let decoded: &DataListing = bytemuck::from_bytes(&data[8..]);
+        assert_eq!(decoded.data_type(), data_type);
+        assert_eq!(decoded.description(), description);
+        assert_eq!(decoded.identity_id(), identity_id);
+        assert_eq!((decoded.sold_at(), decoded.cancelled_at(), decoded.buyer()), (sold_at, cancelled_at, buyer));
+        assert_eq!((decoded.expires_at(), decoded.union()), (expires_at, union));
+        assert_eq!(decoded.data_pointer(), data_pointer);
+        assert_eq!(decoded.preview(), preview);
+        assert_eq!(decoded.freshness_oracle(), freshness_oracle);
+        assert_eq!(decoded.license_template(), Some(u32::MAX));
+
+        assert_eq!(&data[DataListing::OWNER_OFFSET..][..32], listing.owner.as_ref());
+        assert_eq!(data[DataListing::IS_ACTIVE_OFFSET], 1);
+        assert_eq!(data[DataListing::DATA_TYPE_OFFSET] as usize, data_type.index());
+    }
+
+    #[test]
+    fn featured_set_fits(listings in proptest::collection::vec(pubkey(), FeaturedSet::MAX_LISTINGS)) {
+        let featured_set = FeaturedSet {
+            epoch: u64::MAX,
+            starts_at: i64::MAX,
+            ends_at: i64::MAX,
+            listings: listings
+                .into_iter()
+                .map(|listing| FeaturedListing { listing, votes: u64::MAX })
+                .collect(),
+            bump: u8::MAX,
+        };
+        assert_fits(&featured_set);
+    }
+
+    #[test]
+    fn listing_allowlist_fits(buyers in proptest::collection::vec(pubkey(), ListingAllowlist::MAX_BUYERS)) {
+        let allowlist = ListingAllowlist {
+            listing: Pubkey::new_unique(),
+            buyers,
+            bump: u8::MAX,
+        };
+        assert_fits(&allowlist);
+    }
+
+    #[test]
+    fn data_manifest_fits(
+        chunks in proptest::collection::vec((any::<[u8; 32]>(), any::<[u8; 32]>()), DataManifest::MAX_CHUNKS),
+    ) {
+        let manifest = DataManifest {
+            listing: Pubkey::new_unique(),
+            total_size: u64::MAX,
+            is_sealed: true,
+            manifest_hash: [u8::MAX; 32],
+            chunks: chunks
+                .into_iter()
+                .map(|(tx_id, hash)| ManifestChunk { tx_id, size: u64::MAX, hash })
+                .collect(),
+            bump: u8::MAX,
+        };
+        assert_fits(&manifest);
+    }
+
+    #[test]
+    fn purchase_receipt_fits(purpose in purpose()) {
+        let receipt = PurchaseReceipt {
+            listing: Pubkey::new_unique(),
+            listing_id: u64::MAX,
+            buyer: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            seller: Pubkey::new_unique(),
+            amount: u64::MAX,
+            is_confidential: true,
+            amount_commitment: [u8::MAX; 32],
+            permission: Pubkey::new_unique(),
+            permission_epoch: u32::MAX,
+            escrowed_amount: u64::MAX,
+            released_amount: u64::MAX,
+            refunded_amount: u64::MAX,
+            refund_window: i64::MAX,
+            purpose,
+            watermark_commitment: [u8::MAX; 32],
+            license_template: Some(u32::MAX),
+            purchased_at: i64::MAX,
+            rent_payer: Pubkey::new_unique(),
+            bump: u8::MAX,
+        };
+        assert_fits(&receipt);
+    }
+
+    #[test]
+    fn coupon_fits(expires_at in optional(any::<i64>()), listing in optional(pubkey())) {
+        let coupon = Coupon {
+            seller: Pubkey::new_unique(),
+            code_hash: [u8::MAX; 32],
+            discount_basis_points: u16::MAX,
+            max_uses: u32::MAX,
+            remaining_uses: u32::MAX,
+            expires_at,
+            listing,
+            bump: u8::MAX,
+        };
+        assert_fits(&coupon);
+    }
+
+    #[test]
+    fn compute_job_fits(
+        purpose in purpose(),
+        result_arweave_tx_id in text(128),
+        result_posted_at in optional(any::<i64>()),
+        max_attestation_age in optional(any::<i64>()),
+    ) {
+        let job = ComputeJob {
+            job_id: u64::MAX,
+            listing: Pubkey::new_unique(),
+            buyer: Pubkey::new_unique(),
+            seller: Pubkey::new_unique(),
+            provider: Pubkey::new_unique(),
+            spec_hash: [u8::MAX; 32],
+            purpose,
+            amount: u64::MAX,
+            status: ComputeJobStatus::Refunded,
+            result_hash: [u8::MAX; 32],
+            result_arweave_tx_id,
+            created_at: i64::MAX,
+            result_deadline: i64::MAX,
+            result_posted_at,
+            max_attestation_age,
+            bump: u8::MAX,
+        };
+        assert_fits(&job);
+    }
+}
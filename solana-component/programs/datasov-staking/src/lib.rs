@@ -0,0 +1,1486 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use datasov_identity::{Dispute, DisputeStatus};
+
+declare_id!("E3XWsHo9j4KEBkFFtEm4KFTJB2zKCGbrJ3iiutnijvvH");
+
+/// PDA seed prefixes
+pub mod seeds {
+    /// `StakingConfig`, also the authority of the vault
+    pub const CONFIG: &[u8] = b"staking_config";
+    /// Token account holding every staked token
+    pub const VAULT: &[u8] = b"staking_vault";
+    /// `Backing`, per role and subject
+    pub const BACKING: &[u8] = b"backing";
+    /// `StakePosition`, per backing and staker
+    pub const POSITION: &[u8] = b"position";
+    /// `StakeWeight`, per staker
+    pub const STAKE_WEIGHT: &[u8] = b"stake_weight";
+    /// `DisputeSlash`, per dispute and backing
+    pub const DISPUTE_SLASH: &[u8] = b"dispute_slash";
+    /// `Vesting`, per beneficiary and vesting ID
+    pub const VESTING: &[u8] = b"vesting";
+    /// Token account holding a vesting's unclaimed tokens, per vesting
+    pub const VESTING_VAULT: &[u8] = b"vesting_vault";
+    /// `Airdrop`, per airdrop ID
+    pub const AIRDROP: &[u8] = b"airdrop";
+    /// Token account an airdrop pays out of, per airdrop
+    pub const AIRDROP_VAULT: &[u8] = b"airdrop_vault";
+    /// `AirdropBitmap`, per airdrop and chunk of allocations
+    pub const AIRDROP_BITMAP: &[u8] = b"airdrop_bitmap";
+}
+
+#[program]
+pub mod datasov_staking {
+    use super::*;
+
+    /// Create the staking config and the vault for the protocol token `mint`. `vote_authority`
+    /// (the governance PDA) locks stake that has voted.
+    pub fn initialize_staking(
+        ctx: Context<InitializeStaking>,
+        unstake_cooldown: i64,
+        consumer_slash_bps: u16,
+        juror_slash_bps: u16,
+        vote_authority: Pubkey,
+    ) -> Result<()> {
+        require!(unstake_cooldown >= 0, ErrorCode::InvalidStakingConfig);
+        require!(
+            consumer_slash_bps <= 10000 && juror_slash_bps <= 10000,
+            ErrorCode::InvalidStakingConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.mint = ctx.accounts.mint.key();
+        config.vote_authority = vote_authority;
+        config.unstake_cooldown = unstake_cooldown;
+        config.consumer_slash_bps = consumer_slash_bps;
+        config.juror_slash_bps = juror_slash_bps;
+        config.fee_tiers = Vec::new();
+        config.total_staked = 0;
+        config.bump = ctx.bumps.config;
+
+        msg!("Staking initialized for mint {}", config.mint);
+        Ok(())
+    }
+
+    /// Change the unstaking cooldown, the shares of a backing slashed per upheld dispute, and the
+    /// vote authority
+    pub fn set_staking_parameters(
+        ctx: Context<UpdateStakingConfig>,
+        unstake_cooldown: i64,
+        consumer_slash_bps: u16,
+        juror_slash_bps: u16,
+        vote_authority: Pubkey,
+    ) -> Result<()> {
+        require!(unstake_cooldown >= 0, ErrorCode::InvalidStakingConfig);
+        require!(
+            consumer_slash_bps <= 10000 && juror_slash_bps <= 10000,
+            ErrorCode::InvalidStakingConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.unstake_cooldown = unstake_cooldown;
+        config.consumer_slash_bps = consumer_slash_bps;
+        config.juror_slash_bps = juror_slash_bps;
+        config.vote_authority = vote_authority;
+
+        msg!("Staking parameters updated");
+        Ok(())
+    }
+
+    /// Replace the marketplace fee discounts granted by staking weight, lowest tier first
+    pub fn set_fee_tiers(
+        ctx: Context<UpdateStakingConfig>,
+        fee_tiers: Vec<FeeTier>,
+    ) -> Result<()> {
+        require!(fee_tiers.len() <= StakingConfig::MAX_FEE_TIERS, ErrorCode::TooManyFeeTiers);
+        require!(
+            fee_tiers.iter().all(|tier| tier.discount_bps <= 10000)
+                && fee_tiers.windows(2).all(|pair| pair[0].min_weight < pair[1].min_weight),
+            ErrorCode::InvalidFeeTiers
+        );
+
+        ctx.accounts.config.fee_tiers = fee_tiers.clone();
+
+        emit!(FeeTiersUpdatedEvent {
+            fee_tiers: fee_tiers,
+        });
+
+        msg!("Fee tiers updated");
+        Ok(())
+    }
+
+    /// Hand the staking authority to another key or program, such as the governance program
+    pub fn set_staking_authority(
+        ctx: Context<UpdateStakingConfig>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.config.authority = new_authority;
+
+        msg!("Staking authority changed to {}", new_authority);
+        Ok(())
+    }
+
+    /// Stake protocol tokens behind an oracle, consumer or juror. The position holds shares of the
+    /// backing, so slashes of the subject reduce every backer's stake in proportion.
+    pub fn stake(
+        ctx: Context<Stake>,
+        role: StakeRole,
+        subject: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let backing = &mut ctx.accounts.backing;
+        if backing.subject == Pubkey::default() {
+            backing.role = role;
+            backing.subject = subject;
+            backing.bump = ctx.bumps.backing;
+        }
+        let shares = backing.shares_for(amount)?;
+        require!(shares > 0, ErrorCode::InvalidAmount);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.staker_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.staker.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        backing.amount += amount;
+        backing.shares += shares;
+
+        let position = &mut ctx.accounts.position;
+        if position.staker == Pubkey::default() {
+            position.staker = ctx.accounts.staker.key();
+            position.backing = backing.key();
+            position.bump = ctx.bumps.position;
+        }
+        position.shares += shares;
+
+        let weight = &mut ctx.accounts.weight;
+        if weight.owner == Pubkey::default() {
+            weight.owner = ctx.accounts.staker.key();
+            weight.bump = ctx.bumps.weight;
+        }
+        position.sync(backing, weight);
+
+        ctx.accounts.config.total_staked += amount;
+
+        emit!(StakedEvent {
+            role: role,
+            subject: subject,
+            staker: position.staker,
+            amount: amount,
+            shares: shares,
+        });
+
+        msg!("Staked {} behind {}", amount, subject);
+        Ok(())
+    }
+
+    /// Start the cooldown on `shares` of a position; they stop counting as weight immediately but
+    /// stay slashable until withdrawn. Not allowed while the staker's weight backs an open vote.
+    pub fn request_unstake(
+        ctx: Context<RequestUnstake>,
+        shares: u64,
+    ) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let weight = &mut ctx.accounts.weight;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(now >= weight.locked_until, ErrorCode::StakeLocked);
+        require!(position.unlock_at.is_none(), ErrorCode::UnstakeAlreadyRequested);
+        require!(shares > 0 && shares <= position.shares, ErrorCode::InvalidAmount);
+
+        position.unstaking_shares = shares;
+        position.unlock_at = Some(now + ctx.accounts.config.unstake_cooldown);
+        position.sync(&ctx.accounts.backing, weight);
+
+        msg!("Unstake of {} shares requested", shares);
+        Ok(())
+    }
+
+    /// Withdraw the shares under cooldown, at the backing's value once the cooldown has passed
+    pub fn withdraw_stake(
+        ctx: Context<WithdrawStake>,
+    ) -> Result<()> {
+        let backing = &mut ctx.accounts.backing;
+        let position = &mut ctx.accounts.position;
+
+        let unlock_at = position.unlock_at.ok_or(ErrorCode::UnstakeNotRequested)?;
+        require!(Clock::get()?.unix_timestamp >= unlock_at, ErrorCode::CooldownNotElapsed);
+
+        let shares = position.unstaking_shares;
+        let amount = backing.value_of(shares);
+        backing.amount -= amount;
+        backing.shares -= shares;
+        position.shares -= shares;
+        position.unstaking_shares = 0;
+        position.unlock_at = None;
+        position.sync(backing, &mut ctx.accounts.weight);
+        ctx.accounts.config.total_staked -= amount;
+
+        StakingConfig::transfer_signed(
+            &ctx.accounts.config,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.staker_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(StakeWithdrawnEvent {
+            role: backing.role,
+            subject: backing.subject,
+            staker: position.staker,
+            amount: amount,
+            shares: shares,
+        });
+
+        msg!("Stake withdrawn: {}", amount);
+        Ok(())
+    }
+
+    /// Bring a position's weight in line with its backing after a slash (callable by anyone)
+    pub fn sync_position(
+        ctx: Context<SyncPosition>,
+    ) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.sync(&ctx.accounts.backing, &mut ctx.accounts.weight);
+
+        msg!("Position synced: weight {}", position.weight);
+        Ok(())
+    }
+
+    /// Slash a subject's backing for proven misconduct (staking authority only), paying the
+    /// slashed tokens to the recipient
+    pub fn slash_backing(
+        ctx: Context<SlashBacking>,
+        amount: u64,
+        evidence_tx_id: String,
+    ) -> Result<()> {
+        require!(evidence_tx_id.len() <= 128, ErrorCode::EvidenceTxIdTooLong);
+
+        let slashed = ctx.accounts.backing.slash(amount);
+        ctx.accounts.config.total_staked -= slashed;
+
+        StakingConfig::transfer_signed(
+            &ctx.accounts.config,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.recipient_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            slashed,
+        )?;
+
+        emit!(BackingSlashedEvent {
+            role: ctx.accounts.backing.role,
+            subject: ctx.accounts.backing.subject,
+            amount: slashed,
+            recipient: ctx.accounts.recipient_token_account.owner,
+            evidence_tx_id: evidence_tx_id,
+        });
+
+        msg!("Backing slashed: {}", slashed);
+        Ok(())
+    }
+
+    /// Slash the backing of the consumer of an upheld dispute, or of a juror who voted against
+    /// the outcome or not at all (callable by anyone, once per dispute and backing). The slashed
+    /// tokens compensate the claimant.
+    pub fn apply_dispute_slash(
+        ctx: Context<ApplyDisputeSlash>,
+    ) -> Result<()> {
+        let dispute = &ctx.accounts.dispute;
+        let backing = &mut ctx.accounts.backing;
+        let config = &mut ctx.accounts.config;
+
+        let upheld = match dispute.status {
+            DisputeStatus::Upheld => true,
+            DisputeStatus::Dismissed => false,
+            _ => return err!(ErrorCode::DisputeNotResolved),
+        };
+        let slash_bps = match backing.role {
+            StakeRole::Consumer => {
+                require!(upheld && backing.subject == dispute.consumer, ErrorCode::NotSlashable);
+                config.consumer_slash_bps
+            }
+            StakeRole::Juror => {
+                let seat = dispute
+                    .panel
+                    .iter()
+                    .position(|juror| *juror == backing.subject)
+                    .ok_or(ErrorCode::NotSlashable)?;
+                require!(dispute.votes[seat] != Some(upheld), ErrorCode::NotSlashable);
+                config.juror_slash_bps
+            }
+            StakeRole::Oracle => return err!(ErrorCode::NotSlashable),
+        };
+
+        let amount = (backing.amount as u128 * slash_bps as u128 / 10000) as u64;
+        let slashed = backing.slash(amount);
+        config.total_staked -= slashed;
+
+        let record = &mut ctx.accounts.dispute_slash;
+        record.dispute = dispute.key();
+        record.backing = backing.key();
+        record.amount = slashed;
+        record.bump = ctx.bumps.dispute_slash;
+
+        StakingConfig::transfer_signed(
+            config,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.claimant_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            slashed,
+        )?;
+
+        emit!(BackingSlashedEvent {
+            role: backing.role,
+            subject: backing.subject,
+            amount: slashed,
+            recipient: dispute.claimant,
+            evidence_tx_id: dispute.evidence_tx_id.clone(),
+        });
+
+        msg!("Dispute {} slashed backing of {}: {}", dispute.dispute_id, backing.subject, slashed);
+        Ok(())
+    }
+
+    /// Keep a staker's weight from being unstaked until `until` (vote authority only), so the
+    /// weight behind a vote stays put until voting closes
+    pub fn lock_stake(
+        ctx: Context<LockStake>,
+        until: i64,
+    ) -> Result<()> {
+        let weight = &mut ctx.accounts.weight;
+        weight.locked_until = weight.locked_until.max(until);
+
+        msg!("Stake of {} locked until {}", weight.owner, weight.locked_until);
+        Ok(())
+    }
+
+    /// Lock `total` protocol tokens from the funder for the beneficiary, vesting linearly from
+    /// `start_at` to `end_at` with nothing claimable before `cliff_at`. The staking authority may
+    /// revoke what has not vested yet.
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        vesting_id: u64,
+        kind: VestingKind,
+        total: u64,
+        start_at: i64,
+        cliff_at: i64,
+        end_at: i64,
+    ) -> Result<()> {
+        require!(total > 0, ErrorCode::InvalidAmount);
+        require!(
+            start_at <= cliff_at && cliff_at <= end_at && start_at < end_at,
+            ErrorCode::InvalidVestingSchedule
+        );
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, total)?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.beneficiary = ctx.accounts.beneficiary.key();
+        vesting.funder = ctx.accounts.funder.key();
+        vesting.vesting_id = vesting_id;
+        vesting.kind = kind;
+        vesting.total = total;
+        vesting.claimed = 0;
+        vesting.start_at = start_at;
+        vesting.cliff_at = cliff_at;
+        vesting.end_at = end_at;
+        vesting.revoked_at = None;
+        vesting.bump = ctx.bumps.vesting;
+        vesting.vault_bump = ctx.bumps.vault;
+
+        emit!(VestingCreatedEvent {
+            beneficiary: vesting.beneficiary,
+            vesting_id: vesting_id,
+            kind: kind,
+            total: total,
+            start_at: start_at,
+            cliff_at: cliff_at,
+            end_at: end_at,
+        });
+
+        msg!("Vesting {} of {} created for {}", vesting_id, total, vesting.beneficiary);
+        Ok(())
+    }
+
+    /// Pay the beneficiary everything vested and not yet claimed
+    pub fn claim_vested(
+        ctx: Context<ClaimVested>,
+    ) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        let amount = vesting.claimable(Clock::get()?.unix_timestamp);
+        require!(amount > 0, ErrorCode::NothingVested);
+        vesting.claimed += amount;
+
+        StakingConfig::transfer_signed(
+            &ctx.accounts.config,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.beneficiary_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(VestedClaimedEvent {
+            beneficiary: vesting.beneficiary,
+            vesting_id: vesting.vesting_id,
+            amount: amount,
+            claimed: vesting.claimed,
+        });
+
+        msg!("Claimed {} vested tokens", amount);
+        Ok(())
+    }
+
+    /// Stop a vesting where it stands (staking authority only): what has vested stays claimable,
+    /// the rest returns to the funder
+    pub fn revoke_vesting(
+        ctx: Context<RevokeVesting>,
+    ) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        require!(vesting.revoked_at.is_none(), ErrorCode::VestingRevoked);
+
+        let now = Clock::get()?.unix_timestamp;
+        let vested = vesting.vested_at(now);
+        let unvested = vesting.total - vested;
+        vesting.total = vested;
+        vesting.revoked_at = Some(now);
+
+        StakingConfig::transfer_signed(
+            &ctx.accounts.config,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.funder_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            unvested,
+        )?;
+
+        emit!(VestingRevokedEvent {
+            beneficiary: vesting.beneficiary,
+            vesting_id: vesting.vesting_id,
+            vested: vested,
+            returned: unvested,
+        });
+
+        msg!("Vesting {} revoked, {} returned to the funder", vesting.vesting_id, unvested);
+        Ok(())
+    }
+
+    /// Post the Merkle root of an airdrop's (wallet, amount) allocations (staking authority only).
+    /// The airdrop's vault is funded by plain transfers.
+    pub fn create_airdrop(
+        ctx: Context<CreateAirdrop>,
+        airdrop_id: u64,
+        merkle_root: [u8; 32],
+        leaf_count: u32,
+    ) -> Result<()> {
+        require!(leaf_count > 0, ErrorCode::InvalidAirdrop);
+
+        let airdrop = &mut ctx.accounts.airdrop;
+        airdrop.airdrop_id = airdrop_id;
+        airdrop.merkle_root = merkle_root;
+        airdrop.leaf_count = leaf_count;
+        airdrop.claimed_count = 0;
+        airdrop.claimed_amount = 0;
+        airdrop.bump = ctx.bumps.airdrop;
+        airdrop.vault_bump = ctx.bumps.vault;
+
+        emit!(AirdropCreatedEvent {
+            airdrop_id: airdrop_id,
+            merkle_root: merkle_root,
+            leaf_count: leaf_count,
+        });
+
+        msg!("Airdrop {} created with {} allocations", airdrop_id, leaf_count);
+        Ok(())
+    }
+
+    /// Claim allocation `index` of an airdrop with its Merkle proof, siblings from the leaf up.
+    /// Each allocation pays out once.
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let airdrop = &mut ctx.accounts.airdrop;
+        let claimant = ctx.accounts.claimant.key();
+        require!(index < airdrop.leaf_count, ErrorCode::InvalidMerkleProof);
+        require!(
+            airdrop.verify(&Airdrop::leaf(index, &claimant, amount), index, &proof),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        let bitmap = &mut ctx.accounts.bitmap;
+        if bitmap.airdrop == Pubkey::default() {
+            bitmap.airdrop = airdrop.key();
+            bitmap.chunk = AirdropBitmap::chunk_of(index);
+            bitmap.bump = ctx.bumps.bitmap;
+        }
+        require!(!bitmap.is_claimed(index), ErrorCode::AirdropAlreadyClaimed);
+        bitmap.set_claimed(index);
+
+        airdrop.claimed_count += 1;
+        airdrop.claimed_amount += amount;
+
+        StakingConfig::transfer_signed(
+            &ctx.accounts.config,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.claimant_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(AirdropClaimedEvent {
+            airdrop_id: airdrop.airdrop_id,
+            index: index,
+            claimant: claimant,
+            amount: amount,
+        });
+
+        msg!("Airdrop {} allocation {} claimed: {}", airdrop.airdrop_id, index, amount);
+        Ok(())
+    }
+}
+
+// Account validation structs
+
+#[derive(Accounts)]
+pub struct InitializeStaking<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakingConfig::INIT_SPACE,
+        seeds = [seeds::CONFIG],
+        bump
+    )]
+    pub config: Account<'info, StakingConfig>,
+
+    /// The protocol token
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::VAULT],
+        bump,
+        token::mint = mint,
+        token::authority = config
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStakingConfig<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONFIG],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, StakingConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: StakeRole, subject: Pubkey)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONFIG],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + Backing::INIT_SPACE,
+        seeds = [seeds::BACKING, &[role.seed()], subject.as_ref()],
+        bump
+    )]
+    pub backing: Box<Account<'info, Backing>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [seeds::POSITION, backing.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + StakeWeight::INIT_SPACE,
+        seeds = [seeds::STAKE_WEIGHT, staker.key().as_ref()],
+        bump
+    )]
+    pub weight: Box<Account<'info, StakeWeight>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VAULT],
+        bump
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        token::authority = staker
+    )]
+    pub staker_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(
+        seeds = [seeds::CONFIG],
+        bump = config.bump
+    )]
+    pub config: Account<'info, StakingConfig>,
+
+    #[account(
+        seeds = [seeds::BACKING, &[backing.role.seed()], backing.subject.as_ref()],
+        bump = backing.bump
+    )]
+    pub backing: Account<'info, Backing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::POSITION, backing.key().as_ref(), staker.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_WEIGHT, staker.key().as_ref()],
+        bump = weight.bump
+    )]
+    pub weight: Account<'info, StakeWeight>,
+
+    pub staker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONFIG],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::BACKING, &[backing.role.seed()], backing.subject.as_ref()],
+        bump = backing.bump
+    )]
+    pub backing: Box<Account<'info, Backing>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::POSITION, backing.key().as_ref(), staker.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_WEIGHT, staker.key().as_ref()],
+        bump = weight.bump
+    )]
+    pub weight: Box<Account<'info, StakeWeight>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VAULT],
+        bump
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        token::authority = staker
+    )]
+    pub staker_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub staker: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SyncPosition<'info> {
+    #[account(
+        seeds = [seeds::BACKING, &[backing.role.seed()], backing.subject.as_ref()],
+        bump = backing.bump
+    )]
+    pub backing: Account<'info, Backing>,
+
+    #[account(
+        mut,
+        seeds = [seeds::POSITION, backing.key().as_ref(), position.staker.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_WEIGHT, position.staker.as_ref()],
+        bump = weight.bump
+    )]
+    pub weight: Account<'info, StakeWeight>,
+}
+
+#[derive(Accounts)]
+pub struct SlashBacking<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONFIG],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::BACKING, &[backing.role.seed()], backing.subject.as_ref()],
+        bump = backing.bump
+    )]
+    pub backing: Box<Account<'info, Backing>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VAULT],
+        bump
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyDisputeSlash<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::CONFIG],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::BACKING, &[backing.role.seed()], backing.subject.as_ref()],
+        bump = backing.bump
+    )]
+    pub backing: Box<Account<'info, Backing>>,
+
+    /// One slash per dispute and backing
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DisputeSlash::INIT_SPACE,
+        seeds = [seeds::DISPUTE_SLASH, dispute.key().as_ref(), backing.key().as_ref()],
+        bump
+    )]
+    pub dispute_slash: Account<'info, DisputeSlash>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VAULT],
+        bump
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        token::authority = dispute.claimant
+    )]
+    pub claimant_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LockStake<'info> {
+    #[account(
+        seeds = [seeds::CONFIG],
+        bump = config.bump,
+        constraint = config.vote_authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, StakingConfig>,
+
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_WEIGHT, weight.owner.as_ref()],
+        bump = weight.bump
+    )]
+    pub weight: Account<'info, StakeWeight>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(vesting_id: u64)]
+pub struct CreateVesting<'info> {
+    #[account(
+        seeds = [seeds::CONFIG],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        init,
+        payer = funder,
+        space = 8 + Vesting::INIT_SPACE,
+        seeds = [seeds::VESTING, beneficiary.key().as_ref(), &vesting_id.to_le_bytes()],
+        bump
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    #[account(
+        init,
+        payer = funder,
+        seeds = [seeds::VESTING_VAULT, vesting.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = config
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = config.mint)]
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        token::authority = funder
+    )]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Any wallet may be vested to
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        seeds = [seeds::CONFIG],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VESTING, beneficiary.key().as_ref(), &vesting.vesting_id.to_le_bytes()],
+        bump = vesting.bump,
+        has_one = beneficiary
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VESTING_VAULT, vesting.key().as_ref()],
+        bump = vesting.vault_bump
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        token::authority = beneficiary
+    )]
+    pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub beneficiary: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        seeds = [seeds::CONFIG],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VESTING, vesting.beneficiary.as_ref(), &vesting.vesting_id.to_le_bytes()],
+        bump = vesting.bump
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::VESTING_VAULT, vesting.key().as_ref()],
+        bump = vesting.vault_bump
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        token::authority = vesting.funder
+    )]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(airdrop_id: u64)]
+pub struct CreateAirdrop<'info> {
+    #[account(
+        seeds = [seeds::CONFIG],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Airdrop::INIT_SPACE,
+        seeds = [seeds::AIRDROP, &airdrop_id.to_le_bytes()],
+        bump
+    )]
+    pub airdrop: Box<Account<'info, Airdrop>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [seeds::AIRDROP_VAULT, airdrop.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = config
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = config.mint)]
+    pub mint: Box<Account<'info, Mint>>,
+
+    pub authority: Signer<'info>,
+
+    /// Pays the airdrop's rent, as the authority may be a PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u32)]
+pub struct ClaimAirdrop<'info> {
+    #[account(
+        seeds = [seeds::CONFIG],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, StakingConfig>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::AIRDROP, &airdrop.airdrop_id.to_le_bytes()],
+        bump = airdrop.bump
+    )]
+    pub airdrop: Box<Account<'info, Airdrop>>,
+
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        space = 8 + AirdropBitmap::INIT_SPACE,
+        seeds = [seeds::AIRDROP_BITMAP, airdrop.key().as_ref(), &AirdropBitmap::chunk_of(index).to_le_bytes()],
+        bump
+    )]
+    pub bitmap: Box<Account<'info, AirdropBitmap>>,
+
+    #[account(
+        mut,
+        seeds = [seeds::AIRDROP_VAULT, airdrop.key().as_ref()],
+        bump = airdrop.vault_bump
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = config.mint,
+        token::authority = claimant
+    )]
+    pub claimant_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// Account structures
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakingConfig {
+    pub authority: Pubkey,
+    /// The protocol token
+    pub mint: Pubkey,
+    /// May lock stake behind a vote; the governance PDA
+    pub vote_authority: Pubkey,
+    /// Seconds between an unstake request and the withdrawal
+    pub unstake_cooldown: i64,
+    /// Share of a consumer's backing slashed per upheld dispute, in basis points
+    pub consumer_slash_bps: u16,
+    /// Share of a juror's backing slashed per dispute the juror voted against or missed
+    pub juror_slash_bps: u16,
+    /// Marketplace fee discounts by staking weight, lowest tier first
+    #[max_len(4)]
+    pub fee_tiers: Vec<FeeTier>,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+impl StakingConfig {
+    pub const MAX_FEE_TIERS: usize = 4;
+
+    /// Fee discount, in basis points, of the highest tier `weight` reaches
+    pub fn fee_discount_bps(&self, weight: u64) -> u16 {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| weight >= tier.min_weight)
+            .map_or(0, |tier| tier.discount_bps)
+    }
+
+    /// Transfer out of the vault, signed by the config PDA
+    pub fn transfer_signed<'info>(
+        config: &Account<'info, StakingConfig>,
+        from: AccountInfo<'info>,
+        to: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let signer: &[&[&[u8]]] = &[&[seeds::CONFIG, &[config.bump]]];
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: config.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(token_program, cpi_accounts, signer), amount)
+    }
+}
+
+/// Stake behind one oracle, consumer or juror, pooled across its backers
+#[account]
+#[derive(InitSpace)]
+pub struct Backing {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    /// Tokens backing the subject, net of slashes
+    pub amount: u64,
+    /// Shares issued to backers; each is worth `amount / shares` tokens
+    pub shares: u64,
+    pub slashed_total: u64,
+    pub bump: u8,
+}
+
+impl Backing {
+    /// Shares `amount` newly staked tokens buy at the backing's current value
+    pub fn shares_for(&self, amount: u64) -> Result<u64> {
+        if self.shares == 0 {
+            return Ok(amount);
+        }
+        // Shares of a backing slashed to nothing are worthless; new stake cannot buy into it
+        require!(self.amount > 0, ErrorCode::BackingWipedOut);
+        Ok((amount as u128 * self.shares as u128 / self.amount as u128) as u64)
+    }
+
+    /// Tokens `shares` are worth
+    pub fn value_of(&self, shares: u64) -> u64 {
+        if self.shares == 0 {
+            return 0;
+        }
+        (shares as u128 * self.amount as u128 / self.shares as u128) as u64
+    }
+
+    /// Take up to `amount` from the backing, returning the amount taken
+    pub fn slash(&mut self, amount: u64) -> u64 {
+        let slashed = amount.min(self.amount);
+        self.amount -= slashed;
+        self.slashed_total += slashed;
+        slashed
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    pub staker: Pubkey,
+    pub backing: Pubkey,
+    pub shares: u64,
+    /// Value of the shares not under cooldown when last synced, as counted in the staker's weight
+    pub weight: u64,
+    /// Shares under cooldown, withdrawable at `unlock_at`
+    pub unstaking_shares: u64,
+    pub unlock_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl StakePosition {
+    /// Recount the position's contribution to `weight` at the backing's current value
+    pub fn sync(&mut self, backing: &Backing, weight: &mut StakeWeight) {
+        let value = backing.value_of(self.shares - self.unstaking_shares);
+        weight.weight = weight.weight.saturating_sub(self.weight) + value;
+        self.weight = value;
+    }
+}
+
+/// A staker's weight across positions: their governance voting power and fee tier
+#[account]
+#[derive(InitSpace)]
+pub struct StakeWeight {
+    pub owner: Pubkey,
+    pub weight: u64,
+    /// End of the latest vote the weight was cast in; unstaking waits until then
+    pub locked_until: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DisputeSlash {
+    pub dispute: Pubkey,
+    pub backing: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+/// Protocol tokens paid out to a beneficiary on a cliff and linear schedule
+#[account]
+#[derive(InitSpace)]
+pub struct Vesting {
+    pub beneficiary: Pubkey,
+    /// Paid in the tokens; unvested tokens return to it on revocation
+    pub funder: Pubkey,
+    pub vesting_id: u64,
+    pub kind: VestingKind,
+    /// Tokens the schedule pays in all, cut to what had vested on revocation
+    pub total: u64,
+    pub claimed: u64,
+    /// Vesting accrues linearly from `start_at` to `end_at`
+    pub start_at: i64,
+    /// Nothing is claimable before the cliff
+    pub cliff_at: i64,
+    pub end_at: i64,
+    pub revoked_at: Option<i64>,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl Vesting {
+    /// Tokens vested by `now`; everything once ended or revoked
+    pub fn vested_at(&self, now: i64) -> u64 {
+        if self.revoked_at.is_some() || now >= self.end_at {
+            return self.total;
+        }
+        if now < self.cliff_at {
+            return 0;
+        }
+        let elapsed = (now - self.start_at) as u128;
+        let duration = (self.end_at - self.start_at) as u128;
+        (self.total as u128 * elapsed / duration) as u64
+    }
+
+    /// Tokens vested by `now` and not yet claimed
+    pub fn claimable(&self, now: i64) -> u64 {
+        self.vested_at(now) - self.claimed
+    }
+}
+
+/// Protocol tokens allocated by a Merkle tree of (wallet, amount) leaves, claimed with proofs
+#[account]
+#[derive(InitSpace)]
+pub struct Airdrop {
+    pub airdrop_id: u64,
+    pub merkle_root: [u8; 32],
+    /// Allocations in the tree; proofs are as deep as the smallest power of two that holds them
+    pub leaf_count: u32,
+    pub claimed_count: u32,
+    pub claimed_amount: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl Airdrop {
+    /// Leaf committing to allocation `index`; prefixed apart from inner nodes
+    pub fn leaf(index: u32, wallet: &Pubkey, amount: u64) -> [u8; 32] {
+        hashv(&[&[0u8], &index.to_le_bytes(), wallet.as_ref(), &amount.to_le_bytes()]).to_bytes()
+    }
+
+    pub fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&[1u8], left, right]).to_bytes()
+    }
+
+    /// Siblings in a proof; leaves past `leaf_count` are zero
+    pub fn depth(&self) -> usize {
+        self.leaf_count.next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// Whether `proof` leads from `leaf` at `index` to the root
+    pub fn verify(&self, leaf: &[u8; 32], index: u32, proof: &[[u8; 32]]) -> bool {
+        if proof.len() != self.depth() {
+            return false;
+        }
+        let mut node = *leaf;
+        for (level, sibling) in proof.iter().enumerate() {
+            node = match (index >> level) & 1 {
+                0 => Self::node(&node, sibling),
+                _ => Self::node(sibling, &node),
+            };
+        }
+        node == self.merkle_root
+    }
+}
+
+/// Claimed flags of one chunk of an airdrop's allocations
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropBitmap {
+    pub airdrop: Pubkey,
+    pub chunk: u32,
+    pub bits: [u8; 1024],
+    pub bump: u8,
+}
+
+impl AirdropBitmap {
+    /// Allocations per chunk
+    pub const BITS: u32 = 8 * 1024;
+
+    pub fn chunk_of(index: u32) -> u32 {
+        index / Self::BITS
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        let bit = index % Self::BITS;
+        self.bits[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        let bit = index % Self::BITS;
+        self.bits[(bit / 8) as usize] |= 1 << (bit % 8);
+    }
+}
+
+// Enums
+
+/// What a backing stands behind
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum StakeRole {
+    Oracle,
+    Consumer,
+    Juror,
+}
+
+impl StakeRole {
+    /// Seed byte of the role's backings
+    pub fn seed(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// What a vesting pays for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum VestingKind {
+    /// Long-term incentive for running a KYC oracle
+    OracleIncentive,
+    /// Allocation to a protocol contributor
+    ContributorAllocation,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct FeeTier {
+    /// Least staking weight that qualifies
+    pub min_weight: u64,
+    /// Discount off the marketplace fee, in basis points
+    pub discount_bps: u16,
+}
+
+// Events
+
+#[event]
+pub struct StakedEvent {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct StakeWithdrawnEvent {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct BackingSlashedEvent {
+    pub role: StakeRole,
+    pub subject: Pubkey,
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub evidence_tx_id: String,
+}
+
+#[event]
+pub struct FeeTiersUpdatedEvent {
+    pub fee_tiers: Vec<FeeTier>,
+}
+
+#[event]
+pub struct VestingCreatedEvent {
+    pub beneficiary: Pubkey,
+    pub vesting_id: u64,
+    pub kind: VestingKind,
+    pub total: u64,
+    pub start_at: i64,
+    pub cliff_at: i64,
+    pub end_at: i64,
+}
+
+#[event]
+pub struct VestedClaimedEvent {
+    pub beneficiary: Pubkey,
+    pub vesting_id: u64,
+    pub amount: u64,
+    /// Claimed over the vesting's life, this claim included
+    pub claimed: u64,
+}
+
+#[event]
+pub struct VestingRevokedEvent {
+    pub beneficiary: Pubkey,
+    pub vesting_id: u64,
+    /// Tokens vested at revocation, which stay claimable
+    pub vested: u64,
+    /// Unvested tokens returned to the funder
+    pub returned: u64,
+}
+
+#[event]
+pub struct AirdropCreatedEvent {
+    pub airdrop_id: u64,
+    pub merkle_root: [u8; 32],
+    pub leaf_count: u32,
+}
+
+#[event]
+pub struct AirdropClaimedEvent {
+    pub airdrop_id: u64,
+    pub index: u32,
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+// Error codes
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unstake cooldown must not be negative and slash shares at most 10000 basis points")]
+    InvalidStakingConfig,
+    #[msg("Too many fee tiers")]
+    TooManyFeeTiers,
+    #[msg("Fee tiers must rise in weight and discount at most 10000 basis points")]
+    InvalidFeeTiers,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Backing was slashed to nothing")]
+    BackingWipedOut,
+    #[msg("Stake is locked until the votes it was cast in close")]
+    StakeLocked,
+    #[msg("Unstake already requested")]
+    UnstakeAlreadyRequested,
+    #[msg("No unstake requested")]
+    UnstakeNotRequested,
+    #[msg("Unstake cooldown has not elapsed")]
+    CooldownNotElapsed,
+    #[msg("Evidence transaction ID too long")]
+    EvidenceTxIdTooLong,
+    #[msg("Dispute is not resolved")]
+    DisputeNotResolved,
+    #[msg("Backing is not slashable for this dispute")]
+    NotSlashable,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Vesting cliff must fall between its start and end, and it must end after it starts")]
+    InvalidVestingSchedule,
+    #[msg("Nothing vested to claim")]
+    NothingVested,
+    #[msg("Vesting already revoked")]
+    VestingRevoked,
+    #[msg("Airdrop must have at least one allocation")]
+    InvalidAirdrop,
+    #[msg("Merkle proof does not match the airdrop root")]
+    InvalidMerkleProof,
+    #[msg("Airdrop allocation already claimed")]
+    AirdropAlreadyClaimed,
+}
@@ -0,0 +1,147 @@
+//! Every account, filled to the limits the program enforces, must serialize within the space `InitSpace` derives for it.
+
+use anchor_lang::prelude::*;
+use datasov_staking::{
+    Airdrop, AirdropBitmap, Backing, DisputeSlash, FeeTier, StakePosition, StakeRole, StakeWeight, StakingConfig,
+    Vesting, VestingKind,
+};
+use proptest::prelude::*;
+
+/// Serialize with the discriminator, check the encoding fits and round-trips
+fn assert_fits<T: AccountSerialize + AccountDeserialize + Space>(account: &T) {
+    let len = 8 + T::INIT_SPACE;
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= len, "{} bytes serialized, {} allocated", data.len(), len);
+
+    let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+    let mut reencoded = Vec::new();
+    decoded.try_serialize(&mut reencoded).unwrap();
+    assert_eq!(data, reencoded);
+}
+
+fn stake_role() -> impl Strategy<Value = StakeRole> {
+    prop_oneof![
+        Just(StakeRole::Oracle),
+        Just(StakeRole::Consumer),
+        Just(StakeRole::Juror),
+    ]
+}
+
+fn fee_tier() -> impl Strategy<Value = FeeTier> {
+    (any::<u64>(), any::<u16>()).prop_map(|(min_weight, discount_bps)| FeeTier {
+        min_weight,
+        discount_bps,
+    })
+}
+
+proptest! {
+    #[test]
+    fn staking_config_fits(fee_tiers in proptest::collection::vec(fee_tier(), StakingConfig::MAX_FEE_TIERS)) {
+        let config = StakingConfig {
+            authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            vote_authority: Pubkey::new_unique(),
+            unstake_cooldown: i64::MAX,
+            consumer_slash_bps: u16::MAX,
+            juror_slash_bps: u16::MAX,
+            fee_tiers,
+            total_staked: u64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&config);
+    }
+
+    #[test]
+    fn backing_fits(role in stake_role()) {
+        let backing = Backing {
+            role,
+            subject: Pubkey::new_unique(),
+            amount: u64::MAX,
+            shares: u64::MAX,
+            slashed_total: u64::MAX,
+            bump: u8::MAX,
+        };
+        assert_fits(&backing);
+    }
+}
+
+#[test]
+fn stake_position_fits() {
+    let position = StakePosition {
+        staker: Pubkey::new_unique(),
+        backing: Pubkey::new_unique(),
+        shares: u64::MAX,
+        weight: u64::MAX,
+        unstaking_shares: u64::MAX,
+        unlock_at: Some(i64::MAX),
+        bump: u8::MAX,
+    };
+    assert_fits(&position);
+}
+
+#[test]
+fn stake_weight_fits() {
+    let weight = StakeWeight {
+        owner: Pubkey::new_unique(),
+        weight: u64::MAX,
+        locked_until: i64::MAX,
+        bump: u8::MAX,
+    };
+    assert_fits(&weight);
+}
+
+#[test]
+fn dispute_slash_fits() {
+    let record = DisputeSlash {
+        dispute: Pubkey::new_unique(),
+        backing: Pubkey::new_unique(),
+        amount: u64::MAX,
+        bump: u8::MAX,
+    };
+    assert_fits(&record);
+}
+
+#[test]
+fn vesting_fits() {
+    let vesting = Vesting {
+        beneficiary: Pubkey::new_unique(),
+        funder: Pubkey::new_unique(),
+        vesting_id: u64::MAX,
+        kind: VestingKind::ContributorAllocation,
+        total: u64::MAX,
+        claimed: u64::MAX,
+        start_at: i64::MAX,
+        cliff_at: i64::MAX,
+        end_at: i64::MAX,
+        revoked_at: Some(i64::MAX),
+        bump: u8::MAX,
+        vault_bump: u8::MAX,
+    };
+    assert_fits(&vesting);
+}
+
+#[test]
+fn airdrop_fits() {
+    let airdrop = Airdrop {
+        airdrop_id: u64::MAX,
+        merkle_root: [u8::MAX; 32],
+        leaf_count: u32::MAX,
+        claimed_count: u32::MAX,
+        claimed_amount: u64::MAX,
+        bump: u8::MAX,
+        vault_bump: u8::MAX,
+    };
+    assert_fits(&airdrop);
+}
+
+#[test]
+fn airdrop_bitmap_fits() {
+    let bitmap = AirdropBitmap {
+        airdrop: Pubkey::new_unique(),
+        chunk: u32::MAX,
+        bits: [u8::MAX; 1024],
+        bump: u8::MAX,
+    };
+    assert_fits(&bitmap);
+}
@@ -0,0 +1,388 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+declare_id!("22ayit18pgtWvxLH1yzrJiR7FhAdu99cGu8Dj6Zr9vLT");
+
+/// PDA seed prefixes
+pub mod seeds {
+    /// `Timelock` config. Its address is the upgrade and admin authority handed to the timelock;
+    /// it signs queued operations as they execute.
+    pub const TIMELOCK: &[u8] = b"timelock";
+    /// `Operation`, per operation ID
+    pub const OPERATION: &[u8] = b"operation";
+}
+
+#[program]
+pub mod datasov_timelock {
+    use super::*;
+
+    /// Create the timelock. Programs come under it once their upgrade authority (and marketplace
+    /// and registry authorities) are set to the `Timelock` PDA.
+    pub fn initialize_timelock(
+        ctx: Context<InitializeTimelock>,
+        admin: Pubkey,
+        delay: i64,
+    ) -> Result<()> {
+        require!(
+            delay >= Timelock::MIN_DELAY && delay <= Timelock::MAX_DELAY,
+            ErrorCode::InvalidDelay
+        );
+
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.admin = admin;
+        timelock.delay = delay;
+        timelock.operation_count = 0;
+        timelock.bump = ctx.bumps.timelock;
+
+        msg!("Timelock initialized with a {} second delay", delay);
+        Ok(())
+    }
+
+    /// Queue an instruction to run, signed by the timelock, once the delay has passed
+    pub fn queue_operation(
+        ctx: Context<QueueOperation>,
+        program_id: Pubkey,
+        accounts: Vec<OperationAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        require!(accounts.len() <= Operation::MAX_ACCOUNTS, ErrorCode::TooManyAccounts);
+        require!(data.len() <= Operation::MAX_DATA_LEN, ErrorCode::DataTooLong);
+
+        let timelock = &mut ctx.accounts.timelock;
+        let now = Clock::get()?.unix_timestamp;
+
+        let operation = &mut ctx.accounts.operation;
+        operation.id = timelock.operation_count;
+        operation.program_id = program_id;
+        operation.accounts = accounts;
+        operation.data = data;
+        operation.queued_at = now;
+        operation.eta = now + timelock.delay;
+        operation.status = OperationStatus::Queued;
+        operation.bump = ctx.bumps.operation;
+        timelock.operation_count += 1;
+
+        emit!(OperationQueuedEvent {
+            operation_id: operation.id,
+            program_id: program_id,
+            accounts: operation.accounts.clone(),
+            data: operation.data.clone(),
+            eta: operation.eta,
+        });
+
+        msg!("Operation {} queued, executable at {}", operation.id, operation.eta);
+        Ok(())
+    }
+
+    /// Cancel a queued operation (admin only)
+    pub fn cancel_operation(
+        ctx: Context<CancelOperation>,
+    ) -> Result<()> {
+        let operation = &mut ctx.accounts.operation;
+
+        require!(operation.status == OperationStatus::Queued, ErrorCode::OperationNotQueued);
+
+        operation.status = OperationStatus::Cancelled;
+
+        emit!(OperationCancelledEvent {
+            operation_id: operation.id,
+        });
+
+        msg!("Operation {} cancelled", operation.id);
+        Ok(())
+    }
+
+    /// Run a queued operation whose delay has passed (callable by anyone). The remaining accounts
+    /// are the operation's accounts, in order, followed by its program.
+    pub fn execute_operation<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteOperation<'info>>,
+    ) -> Result<()> {
+        let operation = &mut ctx.accounts.operation;
+
+        require!(operation.status == OperationStatus::Queued, ErrorCode::OperationNotQueued);
+        require!(Clock::get()?.unix_timestamp >= operation.eta, ErrorCode::OperationNotReady);
+        require!(
+            ctx.remaining_accounts.len() == operation.accounts.len() + 1,
+            ErrorCode::OperationAccountMismatch
+        );
+        for (account_info, account) in ctx.remaining_accounts.iter().zip(operation.accounts.iter()) {
+            require_keys_eq!(account_info.key(), account.pubkey, ErrorCode::OperationAccountMismatch);
+        }
+        require_keys_eq!(
+            ctx.remaining_accounts[operation.accounts.len()].key(),
+            operation.program_id,
+            ErrorCode::OperationAccountMismatch
+        );
+
+        // Marked executed before the call so the operation cannot re-enter itself
+        operation.status = OperationStatus::Executed;
+        let instruction = Instruction {
+            program_id: operation.program_id,
+            accounts: operation
+                .accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: operation.data.clone(),
+        };
+        let operation_id = operation.id;
+        operation.exit(&crate::ID)?;
+
+        let bump = ctx.accounts.timelock.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::TIMELOCK, &[bump]]];
+        invoke_signed(&instruction, ctx.remaining_accounts, signer_seeds)?;
+
+        emit!(OperationExecutedEvent {
+            operation_id: operation_id,
+        });
+
+        msg!("Operation {} executed", operation_id);
+        Ok(())
+    }
+
+    /// Change the delay. Signed by the timelock itself, so only a queued operation can call it.
+    pub fn set_delay(
+        ctx: Context<UpdateTimelock>,
+        delay: i64,
+    ) -> Result<()> {
+        require!(
+            delay >= Timelock::MIN_DELAY && delay <= Timelock::MAX_DELAY,
+            ErrorCode::InvalidDelay
+        );
+
+        let timelock = &mut ctx.accounts.timelock;
+        let previous_delay = timelock.delay;
+        timelock.delay = delay;
+
+        emit!(TimelockDelayChangedEvent {
+            previous_delay: previous_delay,
+            delay: delay,
+        });
+
+        msg!("Timelock delay changed to {} seconds", delay);
+        Ok(())
+    }
+
+    /// Change who queues and cancels operations. Signed by the timelock itself, so only a queued
+    /// operation can call it.
+    pub fn set_admin(
+        ctx: Context<UpdateTimelock>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        let timelock = &mut ctx.accounts.timelock;
+        let previous_admin = timelock.admin;
+        timelock.admin = new_admin;
+
+        emit!(TimelockAdminChangedEvent {
+            previous_admin: previous_admin,
+            new_admin: new_admin,
+        });
+
+        msg!("Timelock admin changed to {}", new_admin);
+        Ok(())
+    }
+}
+
+// Account validation structs
+
+#[derive(Accounts)]
+pub struct InitializeTimelock<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Timelock::INIT_SPACE,
+        seeds = [seeds::TIMELOCK],
+        bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueOperation<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::TIMELOCK],
+        bump = timelock.bump,
+        has_one = admin
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Operation::INIT_SPACE,
+        seeds = [seeds::OPERATION, &timelock.operation_count.to_le_bytes()],
+        bump
+    )]
+    pub operation: Account<'info, Operation>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOperation<'info> {
+    #[account(
+        seeds = [seeds::TIMELOCK],
+        bump = timelock.bump,
+        has_one = admin
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        mut,
+        seeds = [seeds::OPERATION, &operation.id.to_le_bytes()],
+        bump = operation.bump
+    )]
+    pub operation: Account<'info, Operation>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteOperation<'info> {
+    #[account(
+        seeds = [seeds::TIMELOCK],
+        bump = timelock.bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        mut,
+        seeds = [seeds::OPERATION, &operation.id.to_le_bytes()],
+        bump = operation.bump
+    )]
+    pub operation: Account<'info, Operation>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTimelock<'info> {
+    #[account(
+        mut,
+        seeds = [seeds::TIMELOCK],
+        bump = timelock.bump,
+        constraint = timelock.to_account_info().is_signer @ ErrorCode::NotTimelock
+    )]
+    pub timelock: Account<'info, Timelock>,
+}
+
+// Account structures
+
+#[account]
+#[derive(InitSpace)]
+pub struct Timelock {
+    /// Queues and cancels operations
+    pub admin: Pubkey,
+    /// Seconds between queueing an operation and when it can execute
+    pub delay: i64,
+    pub operation_count: u64,
+    pub bump: u8,
+}
+
+impl Timelock {
+    pub const MIN_DELAY: i64 = 24 * 60 * 60;
+    pub const MAX_DELAY: i64 = 30 * 24 * 60 * 60;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Operation {
+    pub id: u64,
+    pub program_id: Pubkey,
+    #[max_len(16)]
+    pub accounts: Vec<OperationAccount>,
+    #[max_len(512)]
+    pub data: Vec<u8>,
+    pub queued_at: i64,
+    /// Earliest time the operation can execute
+    pub eta: i64,
+    pub status: OperationStatus,
+    pub bump: u8,
+}
+
+impl Operation {
+    pub const MAX_ACCOUNTS: usize = 16;
+    pub const MAX_DATA_LEN: usize = 512;
+}
+
+/// An account of a queued instruction; the `Timelock` PDA is its signer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct OperationAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// Enums
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum OperationStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+// Events
+
+#[event]
+pub struct OperationQueuedEvent {
+    pub operation_id: u64,
+    pub program_id: Pubkey,
+    pub accounts: Vec<OperationAccount>,
+    pub data: Vec<u8>,
+    pub eta: i64,
+}
+
+#[event]
+pub struct OperationCancelledEvent {
+    pub operation_id: u64,
+}
+
+#[event]
+pub struct OperationExecutedEvent {
+    pub operation_id: u64,
+}
+
+#[event]
+pub struct TimelockDelayChangedEvent {
+    pub previous_delay: i64,
+    pub delay: i64,
+}
+
+#[event]
+pub struct TimelockAdminChangedEvent {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+// Error codes
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Delay must be between one and thirty days")]
+    InvalidDelay,
+    #[msg("Operation has too many accounts")]
+    TooManyAccounts,
+    #[msg("Operation instruction data too long")]
+    DataTooLong,
+    #[msg("Operation is not queued")]
+    OperationNotQueued,
+    #[msg("Operation delay has not passed")]
+    OperationNotReady,
+    #[msg("Accounts do not match the queued operation")]
+    OperationAccountMismatch,
+    #[msg("Only the timelock itself can make this change")]
+    NotTimelock,
+}
@@ -0,0 +1,71 @@
+//! Every account, filled to the limits the program enforces, must serialize within the space `InitSpace` derives for it.
+
+use anchor_lang::prelude::*;
+use datasov_timelock::{Operation, OperationAccount, OperationStatus, Timelock};
+use proptest::prelude::*;
+
+/// Serialize with the discriminator, check the encoding fits and round-trips
+fn assert_fits<T: AccountSerialize + AccountDeserialize + Space>(account: &T) {
+    let len = 8 + T::INIT_SPACE;
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert!(data.len() <= len, "{} bytes serialized, {} allocated", data.len(), len);
+
+    let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+    let mut reencoded = Vec::new();
+    decoded.try_serialize(&mut reencoded).unwrap();
+    assert_eq!(data, reencoded);
+}
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn operation_account() -> impl Strategy<Value = OperationAccount> {
+    (pubkey(), any::<bool>(), any::<bool>()).prop_map(|(pubkey, is_signer, is_writable)| OperationAccount {
+        pubkey,
+        is_signer,
+        is_writable,
+    })
+}
+
+fn operation_status() -> impl Strategy<Value = OperationStatus> {
+    prop_oneof![
+        Just(OperationStatus::Queued),
+        Just(OperationStatus::Executed),
+        Just(OperationStatus::Cancelled),
+    ]
+}
+
+#[test]
+fn timelock_fits() {
+    let timelock = Timelock {
+        admin: Pubkey::new_unique(),
+        delay: i64::MAX,
+        operation_count: u64::MAX,
+        bump: u8::MAX,
+    };
+    assert_fits(&timelock);
+}
+
+proptest! {
+    #[test]
+    fn operation_fits(
+        program_id in pubkey(),
+        accounts in proptest::collection::vec(operation_account(), Operation::MAX_ACCOUNTS),
+        data in proptest::collection::vec(any::<u8>(), Operation::MAX_DATA_LEN),
+        status in operation_status(),
+    ) {
+        let operation = Operation {
+            id: u64::MAX,
+            program_id,
+            accounts,
+            data,
+            queued_at: i64::MAX,
+            eta: i64::MAX,
+            status,
+            bump: u8::MAX,
+        };
+        assert_fits(&operation);
+    }
+}
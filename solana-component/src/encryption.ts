@@ -0,0 +1,353 @@
+import {
+    Connection,
+    PublicKey,
+    TransactionInstruction,
+} from "@solana/web3.js";
+import { webcrypto } from "crypto";
+import nacl from "tweetnacl";
+import { TextEncoder } from "util";
+import { getIdentityPDA } from "./siws";
+
+/**
+ * Encryption shared by sellers and buyers, byte-compatible with the Rust
+ * client's `datasov_client::encryption`.
+ *
+ * Documents are sealed with a per-document content key (AES-256-GCM,
+ * `nonce || ciphertext`). Content keys are handed to a counterparty by sealing
+ * them to the X25519 key published on its identity or consumer profile.
+ *
+ * Envelope format: `version (1) || ephemeral public key (32) || nonce (12) || ciphertext`,
+ * under the key `HKDF-SHA256(X25519(ephemeral, recipient), salt = ephemeral || recipient)`.
+ */
+
+export interface EncryptionKeypair {
+    publicKey: Uint8Array;
+    secretKey: Uint8Array;
+}
+
+const subtle = webcrypto.subtle;
+const NONCE_LENGTH = 12;
+const ENVELOPE_VERSION = 1;
+const ENVELOPE_INFO = new TextEncoder().encode("datasov-envelope-v1");
+const DERIVATION_MESSAGE = new TextEncoder().encode("DataSov encryption key v1");
+
+// Anchor discriminators: sha256("global:<instruction name>")[..8]
+const SET_IDENTITY_ENCRYPTION_KEY = Buffer.from([94, 20, 106, 13, 30, 15, 85, 126]);
+const SET_CONSUMER_ENCRYPTION_KEY = Buffer.from([80, 189, 151, 175, 252, 37, 105, 65]);
+
+/**
+ * Generate a fresh per-document content key
+ */
+export function generateContentKey(): Uint8Array {
+    return nacl.randomBytes(32);
+}
+
+/**
+ * Generate a random X25519 keypair
+ */
+export function generateEncryptionKeypair(): EncryptionKeypair {
+    return keypairFromSecret(nacl.randomBytes(32));
+}
+
+/**
+ * Derive the X25519 keypair for a wallet from its signature over a fixed
+ * message, so the key can be recovered from the wallet alone
+ */
+export async function deriveEncryptionKeypair(
+    signMessage: (message: Uint8Array) => Promise<Uint8Array>
+): Promise<EncryptionKeypair> {
+    const signature = await signMessage(DERIVATION_MESSAGE);
+    const secret = new Uint8Array(
+        await subtle.digest("SHA-256", signature)
+    );
+    return keypairFromSecret(secret);
+}
+
+export function keypairFromSecret(secretKey: Uint8Array): EncryptionKeypair {
+    return {
+        publicKey: nacl.scalarMult.base(secretKey),
+        secretKey,
+    };
+}
+
+/**
+ * Encrypt a payload under a content key with a fresh random nonce
+ */
+export async function seal(
+    contentKey: Uint8Array,
+    plaintext: Uint8Array
+): Promise<Uint8Array> {
+    const nonce = nacl.randomBytes(NONCE_LENGTH);
+    const key = await subtle.importKey("raw", contentKey, "AES-GCM", false, [
+        "encrypt",
+    ]);
+    const ciphertext = new Uint8Array(
+        await subtle.encrypt({ name: "AES-GCM", iv: nonce }, key, plaintext)
+    );
+    return concat(nonce, ciphertext);
+}
+
+/**
+ * Decrypt a payload produced by seal; throws if it was truncated or modified
+ */
+export async function open(
+    contentKey: Uint8Array,
+    sealed: Uint8Array
+): Promise<Uint8Array> {
+    if (sealed.length < NONCE_LENGTH) {
+        throw new Error("Decryption failed: payload too short");
+    }
+    const key = await subtle.importKey("raw", contentKey, "AES-GCM", false, [
+        "decrypt",
+    ]);
+    return new Uint8Array(
+        await subtle.decrypt(
+            { name: "AES-GCM", iv: sealed.subarray(0, NONCE_LENGTH) },
+            key,
+            sealed.subarray(NONCE_LENGTH)
+        )
+    );
+}
+
+/**
+ * Encrypt a payload so only the holder of the recipient's X25519 secret key can read it
+ */
+export async function sealTo(
+    recipient: Uint8Array,
+    plaintext: Uint8Array
+): Promise<Uint8Array> {
+    const ephemeral = generateEncryptionKeypair();
+    const shared = nacl.scalarMult(ephemeral.secretKey, recipient);
+    if (shared.every((byte) => byte === 0)) {
+        throw new Error("Encryption failed: invalid recipient key");
+    }
+    const key = await envelopeKey(shared, ephemeral.publicKey, recipient);
+    return concat(
+        Uint8Array.of(ENVELOPE_VERSION),
+        ephemeral.publicKey,
+        await seal(key, plaintext)
+    );
+}
+
+/**
+ * Decrypt an envelope produced by sealTo
+ */
+export async function openEnvelope(
+    keypair: EncryptionKeypair,
+    envelope: Uint8Array
+): Promise<Uint8Array> {
+    if (envelope.length < 33 || envelope[0] !== ENVELOPE_VERSION) {
+        throw new Error("Decryption failed: unsupported envelope");
+    }
+    const ephemeralPublicKey = envelope.subarray(1, 33);
+    const shared = nacl.scalarMult(keypair.secretKey, ephemeralPublicKey);
+    const key = await envelopeKey(shared, ephemeralPublicKey, keypair.publicKey);
+    return open(key, envelope.subarray(33));
+}
+
+/**
+ * Hand a document's content key to a recipient
+ */
+export function wrapContentKey(
+    recipient: Uint8Array,
+    contentKey: Uint8Array
+): Promise<Uint8Array> {
+    return sealTo(recipient, contentKey);
+}
+
+export async function unwrapContentKey(
+    keypair: EncryptionKeypair,
+    envelope: Uint8Array
+): Promise<Uint8Array> {
+    const contentKey = await openEnvelope(keypair, envelope);
+    if (contentKey.length !== 32) {
+        throw new Error("Decryption failed: not a content key");
+    }
+    return contentKey;
+}
+
+/**
+ * Instruction publishing (or, with null, clearing) an identity's encryption key
+ */
+export function createSetIdentityEncryptionKeyInstruction(
+    identityProgramId: PublicKey,
+    owner: PublicKey,
+    identityId: string,
+    encryptionKey: Uint8Array | null
+): TransactionInstruction {
+    return new TransactionInstruction({
+        programId: identityProgramId,
+        keys: [
+            {
+                pubkey: getIdentityPDA(identityProgramId, identityId),
+                isSigner: false,
+                isWritable: true,
+            },
+            { pubkey: owner, isSigner: true, isWritable: false },
+        ],
+        data: Buffer.concat([SET_IDENTITY_ENCRYPTION_KEY, encodeOptionalKey(encryptionKey)]),
+    });
+}
+
+/**
+ * Instruction publishing (or, with null, clearing) a consumer's encryption key
+ */
+export function createSetConsumerEncryptionKeyInstruction(
+    identityProgramId: PublicKey,
+    consumer: PublicKey,
+    encryptionKey: Uint8Array | null
+): TransactionInstruction {
+    return new TransactionInstruction({
+        programId: identityProgramId,
+        keys: [
+            {
+                pubkey: getConsumerProfilePDA(identityProgramId, consumer),
+                isSigner: false,
+                isWritable: true,
+            },
+            { pubkey: consumer, isSigner: true, isWritable: false },
+        ],
+        data: Buffer.concat([SET_CONSUMER_ENCRYPTION_KEY, encodeOptionalKey(encryptionKey)]),
+    });
+}
+
+/**
+ * Derive the consumer profile PDA for a consumer wallet
+ */
+export function getConsumerProfilePDA(
+    identityProgramId: PublicKey,
+    consumer: PublicKey
+): PublicKey {
+    const [profilePDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("consumer"), consumer.toBuffer()],
+        identityProgramId
+    );
+    return profilePDA;
+}
+
+/**
+ * Fetch the encryption key published on an identity, or null if none is set
+ */
+export async function fetchIdentityEncryptionKey(
+    connection: Connection,
+    identityProgramId: PublicKey,
+    identityId: string
+): Promise<Uint8Array | null> {
+    const accountInfo = await connection.getAccountInfo(
+        getIdentityPDA(identityProgramId, identityId)
+    );
+    if (!accountInfo || !accountInfo.owner.equals(identityProgramId)) {
+        return null;
+    }
+    // IdentityAccount: identity_id, owner, arweave_tx_id, status, verification_level,
+    // verified_at, created_at, updated_at, guardian, emancipation_at, sns_domain,
+    // sns_domain_account, encryption_key
+    const reader = new AccountReader(accountInfo.data);
+    reader.string();
+    reader.skip(32);
+    reader.string();
+    reader.skip(2);
+    reader.option(8);
+    reader.skip(16);
+    reader.option(32);
+    reader.option(8);
+    reader.string();
+    reader.option(32);
+    return reader.option(32);
+}
+
+/**
+ * Fetch the encryption key published on a consumer profile, or null if none is set
+ */
+export async function fetchConsumerEncryptionKey(
+    connection: Connection,
+    identityProgramId: PublicKey,
+    consumer: PublicKey
+): Promise<Uint8Array | null> {
+    const accountInfo = await connection.getAccountInfo(
+        getConsumerProfilePDA(identityProgramId, consumer)
+    );
+    if (!accountInfo || !accountInfo.owner.equals(identityProgramId)) {
+        return null;
+    }
+    // ConsumerProfile: consumer, organization_name_hash, contact_tx_id, kyb_attestation_tx_id,
+    // completed_purchases, disputes_lost, revocations_received, kyb_verified_by,
+    // kyb_verified_at, overdue_exports, terms_hash, terms_version, encryption_key
+    const reader = new AccountReader(accountInfo.data);
+    reader.skip(64);
+    reader.string();
+    reader.string();
+    reader.skip(16);
+    reader.option(32);
+    reader.option(8);
+    reader.skip(40);
+    return reader.option(32);
+}
+
+async function envelopeKey(
+    shared: Uint8Array,
+    ephemeralPublicKey: Uint8Array,
+    recipient: Uint8Array
+): Promise<Uint8Array> {
+    const ikm = await subtle.importKey("raw", shared, "HKDF", false, [
+        "deriveBits",
+    ]);
+    const bits = await subtle.deriveBits(
+        {
+            name: "HKDF",
+            hash: "SHA-256",
+            salt: concat(ephemeralPublicKey, recipient),
+            info: ENVELOPE_INFO,
+        },
+        ikm,
+        256
+    );
+    return new Uint8Array(bits);
+}
+
+function encodeOptionalKey(key: Uint8Array | null): Buffer {
+    if (key === null) {
+        return Buffer.from([0]);
+    }
+    if (key.length !== 32) {
+        throw new Error("Encryption key must be 32 bytes");
+    }
+    return Buffer.concat([Buffer.from([1]), Buffer.from(key)]);
+}
+
+function concat(...parts: Uint8Array[]): Uint8Array {
+    const out = new Uint8Array(parts.reduce((length, part) => length + part.length, 0));
+    let offset = 0;
+    for (const part of parts) {
+        out.set(part, offset);
+        offset += part.length;
+    }
+    return out;
+}
+
+/** Sequential Borsh reader over an Anchor account, starting after the discriminator */
+class AccountReader {
+    private offset = 8;
+
+    constructor(private data: Buffer) {}
+
+    skip(length: number): void {
+        this.offset += length;
+    }
+
+    string(): void {
+        const length = this.data.readUInt32LE(this.offset);
+        this.offset += 4 + length;
+    }
+
+    option(length: number): Uint8Array | null {
+        const present = this.data[this.offset] === 1;
+        this.offset += 1;
+        if (!present) {
+            return null;
+        }
+        const value = new Uint8Array(this.data.subarray(this.offset, this.offset + length));
+        this.offset += length;
+        return value;
+    }
+}
@@ -355,18 +355,7 @@ export class DataSovClient {
             const listing = await this.program.account.dataListing.fetch(
                 listingPDA
             );
-            return {
-                id: listing.id.toNumber(),
-                owner: listing.owner,
-                price: listing.price,
-                dataType: this.parseDataType(listing.dataType),
-                description: listing.description,
-                isActive: listing.isActive,
-                createdAt: listing.createdAt.toNumber(),
-                soldAt: listing.soldAt?.toNumber(),
-                cancelledAt: listing.cancelledAt?.toNumber(),
-                buyer: listing.buyer,
-            };
+            return this.parseListing(listing);
         } catch (error) {
             return null;
         }
@@ -378,18 +367,7 @@ export class DataSovClient {
     async getAllListings(): Promise<DataListing[]> {
         try {
             const listings = await this.program.account.dataListing.all();
-            return listings.map((listing) => ({
-                id: listing.account.id.toNumber(),
-                owner: listing.account.owner,
-                price: listing.account.price,
-                dataType: this.parseDataType(listing.account.dataType),
-                description: listing.account.description,
-                isActive: listing.account.isActive,
-                createdAt: listing.account.createdAt.toNumber(),
-                soldAt: listing.account.soldAt?.toNumber(),
-                cancelledAt: listing.account.cancelledAt?.toNumber(),
-                buyer: listing.account.buyer,
-            }));
+            return listings.map((listing) => this.parseListing(listing.account));
         } catch (error) {
             return [];
         }
@@ -419,15 +397,42 @@ export class DataSovClient {
         return allListings.filter((listing) => listing.owner.equals(owner));
     }
 
-    private parseDataType(dataType: any): DataType {
-        if (dataType.locationHistory) return DataType.LocationHistory;
-        if (dataType.appUsage) return DataType.AppUsage;
-        if (dataType.purchaseHistory) return DataType.PurchaseHistory;
-        if (dataType.healthData) return DataType.HealthData;
-        if (dataType.socialMediaActivity) return DataType.SocialMediaActivity;
-        if (dataType.searchHistory) return DataType.SearchHistory;
-        if (dataType.custom) return DataType.Custom;
-        return DataType.Custom;
+    /**
+     * Decode a zero-copy listing account: text is stored as a length and a fixed-size buffer,
+     * each optional field behind a `has` flag
+     */
+    private parseListing(listing: any): DataListing {
+        return {
+            id: listing.id.toNumber(),
+            owner: listing.owner,
+            price: listing.price,
+            dataType: this.parseDataType(listing.dataType),
+            description: Buffer.from(
+                listing.description.slice(0, listing.descriptionLen)
+            ).toString("utf8"),
+            isActive: listing.isActive !== 0,
+            createdAt: listing.createdAt.toNumber(),
+            soldAt: listing.hasSoldAt ? listing.soldAt.toNumber() : undefined,
+            cancelledAt: listing.hasCancelledAt
+                ? listing.cancelledAt.toNumber()
+                : undefined,
+            buyer: listing.hasBuyer ? listing.buyer : undefined,
+        };
+    }
+
+    /**
+     * Map a listing's data type variant index; types this SDK does not name are `Custom`
+     */
+    private parseDataType(index: number): DataType {
+        const dataTypes = [
+            DataType.LocationHistory,
+            DataType.AppUsage,
+            DataType.PurchaseHistory,
+            DataType.HealthData,
+            DataType.SocialMediaActivity,
+            DataType.SearchHistory,
+        ];
+        return dataTypes[index] ?? DataType.Custom;
     }
 
     /**
@@ -452,3 +457,5 @@ export class DataSovClient {
 // Export types and enums
 export { DataType };
 export type { DataListing, DataSovConfig };
+export * from "./siws";
+export * from "./encryption";
@@ -0,0 +1,249 @@
+import { Connection, PublicKey } from "@solana/web3.js";
+import { createHash } from "crypto";
+import nacl from "tweetnacl";
+
+/**
+ * Sign-in-with-Solana helpers bound to a DataSov identity.
+ *
+ * A backend issues a message naming the identity, the wallet signs it, and the
+ * backend verifies both the signature and that the wallet owns the identity on chain.
+ */
+
+export interface SignInMessage {
+    domain: string;
+    address: PublicKey;
+    identityId: string;
+    statement?: string;
+    uri: string;
+    version: string;
+    chainId: string;
+    nonce: string;
+    issuedAt: Date;
+    expirationTime?: Date;
+}
+
+export interface SignInOptions {
+    domain: string;
+    address: PublicKey;
+    identityId: string;
+    uri: string;
+    statement?: string;
+    chainId?: string;
+    ttlSeconds?: number;
+}
+
+export interface SignInVerification {
+    isValid: boolean;
+    errors: string[];
+    message?: SignInMessage;
+    identityAccount?: PublicKey;
+}
+
+export interface VerifySignInOptions {
+    connection: Connection;
+    identityProgramId: PublicKey;
+    expectedDomain: string;
+    expectedNonce: string;
+    now?: Date;
+}
+
+// IdentityAccount layout: discriminator, identity_id, owner, arweave_tx_id, status
+const IDENTITY_STATUS_VERIFIED = 1;
+const RESOURCE_PREFIX = "datasov:identity:";
+
+/**
+ * Build a sign-in message for the given wallet and DataSov identity
+ */
+export function createSignInMessage(options: SignInOptions): SignInMessage {
+    const issuedAt = new Date();
+    return {
+        domain: options.domain,
+        address: options.address,
+        identityId: options.identityId,
+        statement: options.statement,
+        uri: options.uri,
+        version: "1",
+        chainId: options.chainId ?? "mainnet",
+        nonce: Buffer.from(nacl.randomBytes(16)).toString("hex"),
+        issuedAt,
+        expirationTime: new Date(
+            issuedAt.getTime() + (options.ttlSeconds ?? 300) * 1000
+        ),
+    };
+}
+
+/**
+ * Render a sign-in message as the text the wallet signs
+ */
+export function formatSignInMessage(message: SignInMessage): string {
+    const lines = [
+        `${message.domain} wants you to sign in with your Solana account:`,
+        message.address.toBase58(),
+    ];
+    if (message.statement) {
+        lines.push("", message.statement);
+    }
+    lines.push(
+        "",
+        `URI: ${message.uri}`,
+        `Version: ${message.version}`,
+        `Chain ID: ${message.chainId}`,
+        `Nonce: ${message.nonce}`,
+        `Issued At: ${message.issuedAt.toISOString()}`
+    );
+    if (message.expirationTime) {
+        lines.push(`Expiration Time: ${message.expirationTime.toISOString()}`);
+    }
+    lines.push("Resources:", `- ${RESOURCE_PREFIX}${message.identityId}`);
+    return lines.join("\n");
+}
+
+/**
+ * Parse text produced by formatSignInMessage, returning null if it is malformed
+ */
+export function parseSignInMessage(text: string): SignInMessage | null {
+    const lines = text.split("\n");
+    const header = lines[0]?.match(
+        /^(.+) wants you to sign in with your Solana account:$/
+    );
+    if (!header || lines.length < 2) {
+        return null;
+    }
+
+    let address: PublicKey;
+    try {
+        address = new PublicKey(lines[1]);
+    } catch {
+        return null;
+    }
+
+    // An optional statement sits between blank lines ahead of the fields
+    let statement: string | undefined;
+    let start = 2;
+    if (lines[3] !== undefined && !lines[3].startsWith("URI: ")) {
+        statement = lines[3];
+        start = 4;
+    }
+
+    const fields = new Map<string, string>();
+    let identityId: string | undefined;
+    for (const line of lines.slice(start)) {
+        const field = line.match(/^([A-Za-z ]+): (.+)$/);
+        if (line.startsWith(`- ${RESOURCE_PREFIX}`)) {
+            identityId = line.slice(RESOURCE_PREFIX.length + 2);
+        } else if (field) {
+            fields.set(field[1], field[2]);
+        }
+    }
+
+    const required = ["URI", "Version", "Chain ID", "Nonce", "Issued At"];
+    if (!identityId || required.some((key) => !fields.has(key))) {
+        return null;
+    }
+
+    const expiration = fields.get("Expiration Time");
+    return {
+        domain: header[1],
+        address,
+        identityId,
+        statement,
+        uri: fields.get("URI")!,
+        version: fields.get("Version")!,
+        chainId: fields.get("Chain ID")!,
+        nonce: fields.get("Nonce")!,
+        issuedAt: new Date(fields.get("Issued At")!),
+        expirationTime: expiration ? new Date(expiration) : undefined,
+    };
+}
+
+/**
+ * Derive the identity account PDA for an identity ID, seeded by the SHA-256 of the ID
+ */
+export function getIdentityPDA(
+    identityProgramId: PublicKey,
+    identityId: string
+): PublicKey {
+    const [identityPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("identity"), createHash("sha256").update(identityId).digest()],
+        identityProgramId
+    );
+    return identityPDA;
+}
+
+/**
+ * Verify a signed sign-in message: signature, domain, nonce, validity window,
+ * and that the signing wallet owns the named identity and that it is verified
+ */
+export async function verifySignInMessage(
+    text: string,
+    signature: Uint8Array,
+    options: VerifySignInOptions
+): Promise<SignInVerification> {
+    const errors: string[] = [];
+    const message = parseSignInMessage(text);
+    if (!message) {
+        return { isValid: false, errors: ["Malformed sign-in message"] };
+    }
+
+    if (
+        !nacl.sign.detached.verify(
+            Buffer.from(text, "utf8"),
+            signature,
+            message.address.toBytes()
+        )
+    ) {
+        errors.push("Invalid signature");
+    }
+    if (message.domain !== options.expectedDomain) {
+        errors.push("Domain mismatch");
+    }
+    if (message.nonce !== options.expectedNonce) {
+        errors.push("Nonce mismatch");
+    }
+
+    const now = options.now ?? new Date();
+    if (message.issuedAt.getTime() > now.getTime()) {
+        errors.push("Message issued in the future");
+    }
+    if (message.expirationTime && message.expirationTime.getTime() <= now.getTime()) {
+        errors.push("Message expired");
+    }
+
+    const identityAccount = getIdentityPDA(
+        options.identityProgramId,
+        message.identityId
+    );
+    const accountInfo = await options.connection.getAccountInfo(identityAccount);
+    if (!accountInfo || !accountInfo.owner.equals(options.identityProgramId)) {
+        errors.push("Identity not found");
+    } else {
+        const { owner, status } = decodeIdentityOwnerAndStatus(accountInfo.data);
+        if (!owner.equals(message.address)) {
+            errors.push("Wallet does not own identity");
+        }
+        if (status !== IDENTITY_STATUS_VERIFIED) {
+            errors.push("Identity not verified");
+        }
+    }
+
+    return {
+        isValid: errors.length === 0,
+        errors,
+        message,
+        identityAccount,
+    };
+}
+
+function decodeIdentityOwnerAndStatus(data: Buffer): {
+    owner: PublicKey;
+    status: number;
+} {
+    let offset = 8;
+    const identityIdLength = data.readUInt32LE(offset);
+    offset += 4 + identityIdLength;
+    const owner = new PublicKey(data.subarray(offset, offset + 32));
+    offset += 32;
+    const arweaveTxIdLength = data.readUInt32LE(offset);
+    offset += 4 + arweaveTxIdLength;
+    return { owner, status: data[offset] };
+}
@@ -247,19 +247,121 @@ export interface DataSovSolana {
                         name: "price";
                         type: "u64";
                     },
+                    {
+                        name: "isActive";
+                        type: "u8";
+                    },
+                    {
+                        name: "reserved";
+                        type: {
+                            array: ["u8", 16];
+                        };
+                    },
                     {
                         name: "dataType";
+                        type: "u8";
+                    },
+                    {
+                        name: "customDataTypeLen";
+                        type: "u8";
+                    },
+                    {
+                        name: "customDataType";
                         type: {
-                            defined: "DataType";
+                            array: ["u8", 32];
                         };
                     },
+                    {
+                        name: "descriptionLen";
+                        type: "u8";
+                    },
                     {
                         name: "description";
-                        type: "string";
+                        type: {
+                            array: ["u8", 200];
+                        };
                     },
                     {
-                        name: "isActive";
-                        type: "bool";
+                        name: "identityIdLen";
+                        type: "u8";
+                    },
+                    {
+                        name: "identityId";
+                        type: {
+                            array: ["u8", 64];
+                        };
+                    },
+                    {
+                        name: "isPrivate";
+                        type: "u8";
+                    },
+                    {
+                        name: "requiresKyb";
+                        type: "u8";
+                    },
+                    {
+                        name: "storagePaused";
+                        type: "u8";
+                    },
+                    {
+                        name: "rightsTransferable";
+                        type: "u8";
+                    },
+                    {
+                        name: "hasSoldAt";
+                        type: "u8";
+                    },
+                    {
+                        name: "hasCancelledAt";
+                        type: "u8";
+                    },
+                    {
+                        name: "hasBuyer";
+                        type: "u8";
+                    },
+                    {
+                        name: "hasExpiresAt";
+                        type: "u8";
+                    },
+                    {
+                        name: "hasUnion";
+                        type: "u8";
+                    },
+                    {
+                        name: "hasFreshnessOracle";
+                        type: "u8";
+                    },
+                    {
+                        name: "hasLicenseTemplate";
+                        type: "u8";
+                    },
+                    {
+                        name: "bump";
+                        type: "u8";
+                    },
+                    {
+                        name: "dataPointer";
+                        type: {
+                            defined: "StorageSlot";
+                        };
+                    },
+                    {
+                        name: "preview";
+                        type: {
+                            defined: "StorageSlot";
+                        };
+                    },
+                    {
+                        name: "previewHash";
+                        type: {
+                            array: ["u8", 32];
+                        };
+                    },
+                    {
+                        name: "padding";
+                        type: {
+                            array: ["u8", 1];
+                        };
                     },
                     {
                         name: "createdAt";
@@ -267,25 +369,71 @@ export interface DataSovSolana {
                     },
                     {
                         name: "soldAt";
+                        type: "i64";
+                    },
+                    {
+                        name: "cancelledAt";
+                        type: "i64";
+                    },
+                    {
+                        name: "expiresAt";
+                        type: "i64";
+                    },
+                    {
+                        name: "lastRefreshedAt";
+                        type: "i64";
+                    },
+                    {
+                        name: "buyer";
+                        type: "publicKey";
+                    },
+                    {
+                        name: "priceCommitment";
                         type: {
-                            option: "i64";
+                            array: ["u8", 32];
                         };
                     },
                     {
-                        name: "cancelledAt";
+                        name: "descriptionHash";
                         type: {
-                            option: "i64";
+                            array: ["u8", 32];
                         };
                     },
                     {
-                        name: "buyer";
+                        name: "union";
+                        type: "publicKey";
+                    },
+                    {
+                        name: "payoutWallet";
+                        type: "publicKey";
+                    },
+                    {
+                        name: "dataHash";
                         type: {
-                            option: "publicKey";
+                            array: ["u8", 32];
                         };
                     },
                     {
-                        name: "bump";
-                        type: "u8";
+                        name: "freshnessOracle";
+                        type: "publicKey";
+                    },
+                    {
+                        name: "rentPayer";
+                        type: "publicKey";
+                    },
+                    {
+                        name: "licenseTemplate";
+                        type: "u32";
+                    },
+                    {
+                        name: "allowedPurposes";
+                        type: "u16";
+                    },
+                    {
+                        name: "paddingEnd";
+                        type: {
+                            array: ["u8", 2];
+                        };
                     }
                 ];
             };
@@ -326,6 +474,32 @@ export interface DataSovSolana {
                     }
                 ];
             };
+        },
+        {
+            name: "StorageSlot";
+            type: {
+                kind: "struct";
+                fields: [
+                    {
+                        name: "isSome";
+                        type: "u8";
+                    },
+                    {
+                        name: "backend";
+                        type: "u8";
+                    },
+                    {
+                        name: "referenceLen";
+                        type: "u8";
+                    },
+                    {
+                        name: "reference";
+                        type: {
+                            array: ["u8", 128];
+                        };
+                    }
+                ];
+            };
         }
     ];
     errors: [
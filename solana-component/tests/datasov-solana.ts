@@ -130,8 +130,12 @@ describe("datasov-solana", () => {
             dataOwner.publicKey.toString()
         );
         expect(listing.price.toString()).to.equal(price.toString());
-        expect(listing.isActive).to.be.true;
-        expect(listing.description).to.equal(description);
+        expect(listing.isActive).to.equal(1);
+        expect(
+            Buffer.from(
+                listing.description.slice(0, listing.descriptionLen)
+            ).toString()
+        ).to.equal(description);
 
         // Check marketplace was updated
         const marketplace = await program.account.marketplace.fetch(
@@ -229,7 +233,8 @@ describe("datasov-solana", () => {
         const updatedListing = await program.account.dataListing.fetch(
             listingPDA
         );
-        expect(updatedListing.isActive).to.be.false;
+        expect(updatedListing.isActive).to.equal(0);
+        expect(updatedListing.hasBuyer).to.equal(1);
         expect(updatedListing.buyer?.toString()).to.equal(
             buyer.publicKey.toString()
         );
@@ -279,8 +284,8 @@ describe("datasov-solana", () => {
 
         // Verify listing was cancelled
         const listing = await program.account.dataListing.fetch(listingPDA);
-        expect(listing.isActive).to.be.false;
-        expect(listing.cancelledAt).to.not.be.null;
+        expect(listing.isActive).to.equal(0);
+        expect(listing.hasCancelledAt).to.equal(1);
     });
 
     it("Withdraws marketplace fees", async () => {